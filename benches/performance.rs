@@ -542,8 +542,12 @@ fn benchmark_lockin() -> Lockin {
     Lockin {
         workers: 1,
         stride_samples: 100,
+        harmonics: vec![1, 2, 3, 4, 5, 6],
         lpf_kind: LockinLpfKind::BoxcarLegacy,
         lpf_half_window_cycles: 1.0,
+        lpf_cutoff: None,
+        lpf_order: pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+        lpf_zero_phase: true,
         lpf_debug_output: false,
         lpf_debug_label: None,
         lpf_debug_overwrite: false,
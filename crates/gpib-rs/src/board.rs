@@ -278,6 +278,52 @@ pub fn scan_board(request: &str, timeout_secs: u64) -> Result<Vec<i32>> {
     b.scan_pads()
 }
 
+/// List VISA resources matching `expr`, for example `?*::INSTR`.
+/// An expression that matches nothing yields an empty list.
+#[cfg(target_os = "windows")]
+pub fn find_resources(expr: &str) -> Result<Vec<String>> {
+    let query = CString::new(expr)
+        .map_err(|_| crate::error::sys_err("viFindRsrc", "NUL in resource expression"))?;
+    let mut rm: ViSession = 0;
+    let mut resources = Vec::new();
+    unsafe {
+        if viOpenDefaultRM(&mut rm) < VI_SUCCESS {
+            return Err(err("viOpenDefaultRM"));
+        }
+
+        let mut find_list: ViFindList = 0;
+        let mut ret_cnt: ViUInt32 = 0;
+        let mut desc_buf = [0 as c_char; 256];
+        let status = viFindRsrc(
+            rm,
+            query.as_ptr(),
+            &mut find_list,
+            &mut ret_cnt,
+            desc_buf.as_mut_ptr(),
+        );
+        if status >= VI_SUCCESS {
+            resources.push(
+                CStr::from_ptr(desc_buf.as_ptr())
+                    .to_string_lossy()
+                    .into_owned(),
+            );
+            for _ in 1..ret_cnt {
+                if viFindNext(find_list, desc_buf.as_mut_ptr()) < VI_SUCCESS {
+                    break;
+                }
+                resources.push(
+                    CStr::from_ptr(desc_buf.as_ptr())
+                        .to_string_lossy()
+                        .into_owned(),
+                );
+            }
+            viClose(find_list);
+        }
+        viClose(rm);
+    }
+    Ok(resources)
+}
+
 // Helper to parse "GPIB0::17::INSTR" -> 17
 #[cfg(target_os = "windows")]
 fn parse_visa_rsrc_pad(buf: &[c_char]) -> Option<i32> {
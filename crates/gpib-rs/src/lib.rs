@@ -15,6 +15,8 @@ mod instrument;
 mod tmo;
 mod util;
 
+#[cfg(target_os = "windows")]
+pub use crate::board::find_resources;
 pub use crate::board::{Board, scan_board, scan_gpib0};
 pub use crate::error::{GpibError, Result, ibcntl_now, iberr_now, ibsta_now};
 pub use crate::instrument::{Instrument, OpenOptions};
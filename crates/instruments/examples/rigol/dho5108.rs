@@ -1,4 +1,5 @@
 use anyhow::Result;
+use instruments::oscilloscope::Oscilloscope;
 use instruments::rigol::DHO5108;
 
 fn main() -> Result<()> {
@@ -17,5 +17,6 @@ pub const SPEC: InstrumentSpec = InstrumentSpec {
     transports: TRANSPORTS,
     protocols: PROTOCOLS,
     capabilities: CAPABILITIES,
+    idn: None,
     description: "In-memory dummy instrument",
 };
@@ -1,4 +1,5 @@
 use crate::Result;
+use crate::keithley::measure::{KeithleyMeasureSetup, measure_setup_commands, parse_reading};
 use crate::transport::BoxedScpiTransport;
 
 pub struct Keithley2010 {
@@ -17,6 +18,18 @@ impl Keithley2010 {
     pub fn identify(&mut self) -> Result<String> {
         self.transport.query_line("*IDN?")
     }
+
+    pub fn configure_measurement(&mut self, setup: &KeithleyMeasureSetup) -> Result<()> {
+        for command in measure_setup_commands(setup) {
+            self.transport.write_line(&command)?;
+        }
+        Ok(())
+    }
+
+    /// Triggers one measurement with the configured function and returns it.
+    pub fn read(&mut self) -> Result<f64> {
+        parse_reading(&self.transport.query_line(":READ?")?)
+    }
 }
 
 #[cfg(test)]
@@ -2,7 +2,7 @@
 mod driver;
 
 use crate::registry::{
-    InstrumentCapability, InstrumentRole, InstrumentSpec, ProtocolKind, TransportKind,
+    IdnPattern, InstrumentCapability, InstrumentRole, InstrumentSpec, ProtocolKind, TransportKind,
 };
 
 pub use driver::Keithley2010;
@@ -24,5 +24,9 @@ pub const SPEC: InstrumentSpec = InstrumentSpec {
     transports: TRANSPORTS,
     protocols: PROTOCOLS,
     capabilities: CAPABILITIES,
+    idn: Some(IdnPattern {
+        manufacturers: &["KEITHLEY"],
+        models: &["2010"],
+    }),
     description: "Keithley 2010 multimeter",
 };
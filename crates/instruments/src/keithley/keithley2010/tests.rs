@@ -1,5 +1,6 @@
 use super::*;
 use crate::Result;
+use crate::keithley::{KeithleyFunction, KeithleyMeasureSetup, KeithleyThermocouple};
 use crate::transport::ScpiTransport;
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
@@ -44,3 +45,58 @@ fn identify_queries_idn() {
     );
     assert_eq!(*writes.lock().unwrap(), vec!["*IDN?".to_string()]);
 }
+
+#[test]
+fn thermocouple_temperature_is_configured_without_a_range() {
+    let transport = MockTransport::default();
+    transport
+        .responses
+        .lock()
+        .unwrap()
+        .push_back("+2.15300000E+01".to_string());
+    let writes = Arc::clone(&transport.writes);
+    let mut device = Keithley2010::new(Box::new(transport));
+
+    device
+        .configure_measurement(&KeithleyMeasureSetup {
+            function: KeithleyFunction::Temperature,
+            range: Some(10.0),
+            thermocouple: Some(KeithleyThermocouple::K),
+        })
+        .unwrap();
+    assert_eq!(device.read().unwrap(), 21.53);
+
+    assert_eq!(
+        *writes.lock().unwrap(),
+        vec![
+            ":FORMat:ELEMents READing".to_string(),
+            ":SENSe:FUNCtion 'TEMPerature'".to_string(),
+            ":UNIT:TEMPerature C".to_string(),
+            ":SENSe:TEMPerature:TC:TYPE K".to_string(),
+            ":READ?".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn dc_volts_range_and_reading_parse() {
+    let transport = MockTransport::default();
+    transport
+        .responses
+        .lock()
+        .unwrap()
+        .extend(["-1.23400000E-03".to_string(), "overflow".to_string()]);
+    let writes = Arc::clone(&transport.writes);
+    let mut device = Keithley2010::new(Box::new(transport));
+
+    device
+        .configure_measurement(&KeithleyMeasureSetup {
+            function: KeithleyFunction::DcVolts,
+            range: None,
+            thermocouple: None,
+        })
+        .unwrap();
+    assert_eq!(device.read().unwrap(), -1.234e-3);
+    assert!(device.read().is_err());
+    assert_eq!(writes.lock().unwrap()[2], ":SENSe:VOLTage:DC:RANGe:AUTO ON");
+}
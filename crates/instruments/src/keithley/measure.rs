@@ -0,0 +1,120 @@
+//! SCPI measurement setup shared by the Keithley 2000 and 2010.
+
+use crate::Result;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeithleyFunction {
+    DcVolts,
+    AcVolts,
+    DcCurrent,
+    AcCurrent,
+    Resistance,
+    FourWireResistance,
+    Temperature,
+    Frequency,
+}
+
+impl KeithleyFunction {
+    fn sense(self) -> &'static str {
+        match self {
+            Self::DcVolts => "VOLTage:DC",
+            Self::AcVolts => "VOLTage:AC",
+            Self::DcCurrent => "CURRent:DC",
+            Self::AcCurrent => "CURRent:AC",
+            Self::Resistance => "RESistance",
+            Self::FourWireResistance => "FRESistance",
+            Self::Temperature => "TEMPerature",
+            Self::Frequency => "FREQuency",
+        }
+    }
+
+    /// Unit of the value returned by `:READ?`; temperature is read in Celsius.
+    pub fn unit(self) -> &'static str {
+        match self {
+            Self::DcVolts | Self::AcVolts => "V",
+            Self::DcCurrent | Self::AcCurrent => "A",
+            Self::Resistance | Self::FourWireResistance => "ohm",
+            Self::Temperature => "degC",
+            Self::Frequency => "Hz",
+        }
+    }
+
+    pub fn has_range(self) -> bool {
+        !matches!(self, Self::Temperature | Self::Frequency)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeithleyThermocouple {
+    J,
+    K,
+    N,
+    T,
+    E,
+    R,
+    S,
+    B,
+}
+
+impl KeithleyThermocouple {
+    fn token(self) -> &'static str {
+        match self {
+            Self::J => "J",
+            Self::K => "K",
+            Self::N => "N",
+            Self::T => "T",
+            Self::E => "E",
+            Self::R => "R",
+            Self::S => "S",
+            Self::B => "B",
+        }
+    }
+}
+
+/// Measurement function and range; `range: None` selects autorange.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeithleyMeasureSetup {
+    pub function: KeithleyFunction,
+    pub range: Option<f64>,
+    pub thermocouple: Option<KeithleyThermocouple>,
+}
+
+pub(crate) fn measure_setup_commands(setup: &KeithleyMeasureSetup) -> Vec<String> {
+    let sense = setup.function.sense();
+    let mut commands = vec![
+        ":FORMat:ELEMents READing".to_string(),
+        format!(":SENSe:FUNCtion '{sense}'"),
+    ];
+    if setup.function.has_range() {
+        commands.push(match setup.range {
+            Some(range) => format!(":SENSe:{sense}:RANGe {range:E}"),
+            None => format!(":SENSe:{sense}:RANGe:AUTO ON"),
+        });
+    }
+    if setup.function == KeithleyFunction::Temperature {
+        commands.push(":UNIT:TEMPerature C".to_string());
+        if let Some(thermocouple) = setup.thermocouple {
+            commands.push(format!(
+                ":SENSe:TEMPerature:TC:TYPE {}",
+                thermocouple.token()
+            ));
+        }
+    }
+    commands
+}
+
+pub(crate) fn parse_reading(response: &str) -> Result<f64> {
+    let value = response.split(',').next().unwrap_or_default().trim();
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid multimeter reading: {:?}", response.trim()),
+            )
+            .into()
+        })
+}
@@ -1,4 +1,6 @@
 pub mod keithley2000;
 pub mod keithley2010;
+pub mod measure;
 pub use keithley2000::Keithley2000;
 pub use keithley2010::Keithley2010;
+pub use measure::{KeithleyFunction, KeithleyMeasureSetup, KeithleyThermocouple};
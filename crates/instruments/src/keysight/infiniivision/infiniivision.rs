@@ -0,0 +1,385 @@
+use crate::oscilloscope::transport::{
+    ScopeTransport, ensure_response, ensure_stopped, expected_raw_word_bytes, invalid_setting,
+    parse_finite_f64, parse_memory_depth, validate_opc_response,
+};
+use crate::oscilloscope::{
+    AcquisitionSetup, BandwidthLimit, ChannelSetup, Coupling, HorizontalSettings, Oscilloscope,
+    RawWaveformWritten, TriggerSlope, TriggerSource, TriggerStatus, WaveformPreamble,
+};
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// `:OPERegister:CONDition?` bit set while the scope is acquiring.
+const OPERATION_RUN: u32 = 1 << 3;
+/// `:OPERegister:CONDition?` bit set while the scope is armed and waiting.
+const OPERATION_WAIT_TRIG: u32 = 1 << 5;
+
+/// Keysight InfiniiVision X-Series oscilloscope.
+pub struct InfiniiVision {
+    transport: ScopeTransport,
+}
+
+#[allow(dead_code)]
+impl InfiniiVision {
+    pub fn open(ip: &str, port: u16, timeout: Option<Duration>) -> io::Result<Self> {
+        Self::open_with_timeouts(ip, port, timeout, timeout)
+    }
+
+    pub fn open_with_timeouts(
+        ip: &str,
+        port: u16,
+        connect_timeout: Option<Duration>,
+        io_timeout: Option<Duration>,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            transport: ScopeTransport::open_tcp(ip, port, connect_timeout, io_timeout)?,
+        })
+    }
+
+    pub fn open_usbtmc(resource: &str, timeout: Option<Duration>) -> io::Result<Self> {
+        Ok(Self {
+            transport: ScopeTransport::open_usbtmc(resource, timeout)?,
+        })
+    }
+
+    /// Opens a Linux `usbtmc` character device such as `/dev/usbtmc0`.
+    pub fn open_usbtmc_device(path: &str) -> io::Result<Self> {
+        Ok(Self {
+            transport: ScopeTransport::open_usbtmc_device(path)?,
+        })
+    }
+
+    pub fn write_line(&mut self, cmd: &str) -> io::Result<()> {
+        self.transport.write_line(cmd)
+    }
+
+    pub fn query(&mut self, cmd: &str) -> io::Result<String> {
+        self.transport.query(cmd)
+    }
+
+    fn query_f64(&mut self, cmd: &str, name: &str) -> io::Result<f64> {
+        self.transport.query_f64(cmd, name)
+    }
+
+    fn setup_raw_word_fetch(&mut self, ch: u8, memory_depth: usize) -> io::Result<()> {
+        self.transport
+            .write_lines(&raw_word_setup_commands(ch, memory_depth))?;
+        validate_opc_response(&self.transport.read_line()?)?;
+        ensure_stopped(self.query_trigger_status()?)?;
+        ensure_response(
+            "waveform source",
+            &self.query(":WAVeform:SOURce?")?,
+            &format!("CHAN{ch}"),
+        )?;
+        ensure_response("waveform format", &self.query(":WAVeform:FORMat?")?, "WORD")?;
+        ensure_response(
+            "waveform byte order",
+            &self.query(":WAVeform:BYTeorder?")?,
+            "LSBF",
+        )?;
+        ensure_response("waveform sign", &self.query(":WAVeform:UNSigned?")?, "1")?;
+        let points = parse_memory_depth(&self.query(":WAVeform:POINts?")?)?;
+        if points != memory_depth {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("waveform points are {points}, expected {memory_depth}"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Oscilloscope for InfiniiVision {
+    fn identify(&mut self) -> io::Result<String> {
+        self.query("*IDN?")
+    }
+
+    fn set_single(&mut self) -> io::Result<()> {
+        self.write_line(":SINGle")
+    }
+
+    fn stop(&mut self) -> io::Result<()> {
+        self.write_line(":STOP")
+    }
+
+    fn query_trigger_status(&mut self) -> io::Result<TriggerStatus> {
+        parse_operation_condition(&self.query(":OPERegister:CONDition?")?)
+    }
+
+    fn query_horizontal_settings(&mut self) -> io::Result<HorizontalSettings> {
+        let offset = self.query_f64(":TIMebase:POSition?", "horizontal offset")?;
+        let scale = self.query_f64(":TIMebase:SCALe?", "horizontal scale")?;
+        Ok(HorizontalSettings { offset, scale })
+    }
+
+    fn query_memory_depth(&mut self) -> io::Result<usize> {
+        parse_memory_depth(&self.query(":ACQuire:POINts?")?)
+    }
+
+    fn query_waveform_preamble(&mut self, ch: u8) -> io::Result<WaveformPreamble> {
+        let raw = self.query(":WAVeform:PREamble?")?;
+        let vertical_offset =
+            self.query_f64(&format!(":CHANnel{ch}:OFFSet?"), "channel vertical offset")?;
+        let vertical_scale =
+            self.query_f64(&format!(":CHANnel{ch}:SCALe?"), "channel vertical scale")?;
+        parse_preamble(raw, vertical_offset, vertical_scale)
+    }
+
+    fn fetch_raw_word_into(
+        &mut self,
+        ch: u8,
+        memory_depth: usize,
+        writer: &mut dyn Write,
+    ) -> io::Result<RawWaveformWritten> {
+        self.setup_raw_word_fetch(ch, memory_depth)?;
+        let preamble = self.query_waveform_preamble(ch)?;
+
+        let expected_length = expected_raw_word_bytes(memory_depth)?;
+        let byte_count =
+            self.transport
+                .query_binary_into(":WAVeform:DATA?", writer, Some(expected_length))?;
+        ensure_stopped(self.query_trigger_status()?)?;
+
+        Ok(RawWaveformWritten {
+            preamble,
+            byte_count,
+        })
+    }
+
+    fn capture_display_png(&mut self) -> io::Result<Vec<u8>> {
+        self.transport
+            .query_binary(":DISPlay:DATA? PNG, COLor", None)
+    }
+
+    fn apply_acquisition_setup(&mut self, setup: &AcquisitionSetup) -> io::Result<()> {
+        self.transport
+            .write_lines(&acquisition_setup_commands(setup)?)?;
+        validate_opc_response(&self.transport.read_line()?)
+    }
+
+    fn query_acquisition_setup(&mut self, channels: &[u8]) -> io::Result<AcquisitionSetup> {
+        let horizontal = self.query_horizontal_settings()?;
+        let memory_depth = self.query_memory_depth()?;
+        let trigger_source = parse_trigger_source(&self.query(":TRIGger:EDGE:SOURce?")?)?;
+        let trigger_level = self.query_f64(":TRIGger:EDGE:LEVel?", "trigger level")?;
+        let trigger_slope = parse_trigger_slope(&self.query(":TRIGger:EDGE:SLOPe?")?)?;
+        let channels = channels
+            .iter()
+            .map(|&ch| {
+                Ok(ChannelSetup {
+                    channel: ch,
+                    scale: Some(
+                        self.query_f64(&format!(":CHANnel{ch}:SCALe?"), "channel vertical scale")?,
+                    ),
+                    offset: Some(
+                        self.query_f64(
+                            &format!(":CHANnel{ch}:OFFSet?"),
+                            "channel vertical offset",
+                        )?,
+                    ),
+                    coupling: Some(parse_coupling(
+                        &self.query(&format!(":CHANnel{ch}:COUPling?"))?,
+                    )?),
+                    bandwidth_limit: Some(parse_bandwidth_limit(
+                        &self.query(&format!(":CHANnel{ch}:BWLimit?"))?,
+                    )?),
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(AcquisitionSetup {
+            timebase_scale: Some(horizontal.scale),
+            timebase_offset: Some(horizontal.offset),
+            memory_depth: Some(memory_depth),
+            trigger_source: Some(trigger_source),
+            trigger_level: Some(trigger_level),
+            trigger_slope: Some(trigger_slope),
+            channels,
+        })
+    }
+}
+
+fn raw_word_setup_commands(ch: u8, memory_depth: usize) -> Vec<String> {
+    vec![
+        ":STOP".to_string(),
+        format!(":WAVeform:SOURce CHANnel{ch}"),
+        ":WAVeform:FORMat WORD".to_string(),
+        ":WAVeform:BYTeorder LSBFirst".to_string(),
+        ":WAVeform:UNSigned ON".to_string(),
+        ":WAVeform:POINts:MODE RAW".to_string(),
+        format!(":WAVeform:POINts {memory_depth}"),
+        "*OPC?".to_string(),
+    ]
+}
+
+fn acquisition_setup_commands(setup: &AcquisitionSetup) -> io::Result<Vec<String>> {
+    // The record length follows the timebase on InfiniiVision scopes.
+    if setup.memory_depth.is_some() {
+        return Err(unsupported_setting("setting the memory depth"));
+    }
+    let mut commands = Vec::new();
+    if let Some(scale) = setup.timebase_scale {
+        commands.push(format!(":TIMebase:SCALe {scale:E}"));
+    }
+    if let Some(offset) = setup.timebase_offset {
+        commands.push(format!(":TIMebase:POSition {offset:E}"));
+    }
+    if setup.trigger_source.is_some()
+        || setup.trigger_level.is_some()
+        || setup.trigger_slope.is_some()
+    {
+        commands.push(":TRIGger:MODE EDGE".to_string());
+    }
+    if let Some(source) = setup.trigger_source {
+        let token = match source {
+            TriggerSource::Channel(ch) => format!("CHANnel{ch}"),
+            TriggerSource::External => "EXTernal".to_string(),
+            TriggerSource::AcLine => "LINE".to_string(),
+        };
+        commands.push(format!(":TRIGger:EDGE:SOURce {token}"));
+    }
+    if let Some(level) = setup.trigger_level {
+        commands.push(format!(":TRIGger:EDGE:LEVel {level:E}"));
+    }
+    if let Some(slope) = setup.trigger_slope {
+        let token = match slope {
+            TriggerSlope::Positive => "POSitive",
+            TriggerSlope::Negative => "NEGative",
+            TriggerSlope::Either => "EITHer",
+        };
+        commands.push(format!(":TRIGger:EDGE:SLOPe {token}"));
+    }
+    for channel in &setup.channels {
+        let ch = channel.channel;
+        if let Some(coupling) = channel.coupling {
+            let token = match coupling {
+                Coupling::Dc => "DC",
+                Coupling::Ac => "AC",
+                Coupling::Gnd => return Err(unsupported_setting("GND coupling")),
+            };
+            commands.push(format!(":CHANnel{ch}:COUPling {token}"));
+        }
+        if let Some(limit) = channel.bandwidth_limit {
+            let token = match limit {
+                BandwidthLimit::Off => "OFF",
+                BandwidthLimit::Mhz25 => "ON",
+                BandwidthLimit::Mhz20 | BandwidthLimit::Mhz100 | BandwidthLimit::Mhz250 => {
+                    return Err(unsupported_setting("bandwidth limits other than 25 MHz"));
+                }
+            };
+            commands.push(format!(":CHANnel{ch}:BWLimit {token}"));
+        }
+        if let Some(scale) = channel.scale {
+            commands.push(format!(":CHANnel{ch}:SCALe {scale:E}"));
+        }
+        if let Some(offset) = channel.offset {
+            commands.push(format!(":CHANnel{ch}:OFFSet {offset:E}"));
+        }
+    }
+    commands.push("*OPC?".to_string());
+    Ok(commands)
+}
+
+fn unsupported_setting(setting: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("InfiniiVision does not support {setting}"),
+    )
+}
+
+fn parse_operation_condition(value: &str) -> io::Result<TriggerStatus> {
+    let condition = value
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| invalid_setting("operation status condition", value))?;
+    Ok(if condition & OPERATION_RUN == 0 {
+        TriggerStatus::Stop
+    } else if condition & OPERATION_WAIT_TRIG != 0 {
+        TriggerStatus::Wait
+    } else {
+        TriggerStatus::Run
+    })
+}
+
+/// Converts `:WAVeform:PREamble?` to the [`WaveformPreamble`] convention.
+///
+/// Keysight reports `volts = (code - yreference) * yincrement + yorigin` with
+/// `yorigin` in volts, so the origin is re-expressed in codes.
+fn parse_preamble(
+    raw: String,
+    vertical_offset: f64,
+    vertical_scale: f64,
+) -> io::Result<WaveformPreamble> {
+    let fields: Vec<&str> = raw.split(',').collect();
+    if fields.len() != 10 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("expected 10 waveform preamble fields, got {}", fields.len()),
+        ));
+    }
+    let x_increment = parse_finite_f64(fields[4], "xincrement")?;
+    let x_origin = parse_finite_f64(fields[5], "xorigin")?;
+    let x_reference = parse_finite_f64(fields[6], "xreference")?;
+    let y_increment = parse_finite_f64(fields[7], "yincrement")?;
+    let y_origin_volts = parse_finite_f64(fields[8], "yorigin")?;
+    let y_reference = parse_finite_f64(fields[9], "yreference")?;
+    if y_increment <= 0.0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid waveform yincrement: {y_increment}"),
+        ));
+    }
+    Ok(WaveformPreamble {
+        x_increment,
+        x_origin,
+        x_reference,
+        y_increment,
+        y_origin: -y_origin_volts / y_increment,
+        y_reference,
+        vertical_offset,
+        vertical_scale,
+        raw,
+    })
+}
+
+fn parse_trigger_source(value: &str) -> io::Result<TriggerSource> {
+    let token = value.trim().to_ascii_uppercase();
+    match token.as_str() {
+        "EXT" | "EXTERNAL" => return Ok(TriggerSource::External),
+        "LINE" => return Ok(TriggerSource::AcLine),
+        _ => {}
+    }
+    token
+        .strip_prefix("CHAN")
+        .and_then(|ch| ch.strip_prefix("NEL").unwrap_or(ch).parse::<u8>().ok())
+        .map(TriggerSource::Channel)
+        .ok_or_else(|| invalid_setting("trigger source", value))
+}
+
+fn parse_trigger_slope(value: &str) -> io::Result<TriggerSlope> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "POS" | "POSITIVE" => Ok(TriggerSlope::Positive),
+        "NEG" | "NEGATIVE" => Ok(TriggerSlope::Negative),
+        "EITH" | "EITHER" => Ok(TriggerSlope::Either),
+        _ => Err(invalid_setting("trigger slope", value)),
+    }
+}
+
+fn parse_coupling(value: &str) -> io::Result<Coupling> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "DC" => Ok(Coupling::Dc),
+        "AC" => Ok(Coupling::Ac),
+        _ => Err(invalid_setting("channel coupling", value)),
+    }
+}
+
+fn parse_bandwidth_limit(value: &str) -> io::Result<BandwidthLimit> {
+    match value.trim() {
+        "0" => Ok(BandwidthLimit::Off),
+        "1" => Ok(BandwidthLimit::Mhz25),
+        _ => Err(invalid_setting("channel bandwidth limit", value)),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
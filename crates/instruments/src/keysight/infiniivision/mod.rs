@@ -0,0 +1,34 @@
+#[path = "infiniivision.rs"]
+mod driver;
+
+use crate::registry::{
+    IdnPattern, InstrumentCapability, InstrumentRole, InstrumentSpec, ProtocolKind, TransportKind,
+};
+
+pub use driver::InfiniiVision;
+
+pub const MODEL: &str = "InfiniiVision";
+pub const TRANSPORTS: &[TransportKind] = &[
+    TransportKind::Tcpip,
+    TransportKind::Usbtmc,
+    TransportKind::UsbtmcDevice,
+];
+pub const PROTOCOLS: &[ProtocolKind] = &[ProtocolKind::Scpi];
+pub const CAPABILITIES: &[InstrumentCapability] = &[
+    InstrumentCapability::ScpiIdentify,
+    InstrumentCapability::Screenshot,
+    InstrumentCapability::WaveformFetch,
+    InstrumentCapability::AcquisitionSetup,
+];
+pub const SPEC: InstrumentSpec = InstrumentSpec {
+    model: MODEL,
+    role: InstrumentRole::Oscilloscope,
+    transports: TRANSPORTS,
+    protocols: PROTOCOLS,
+    capabilities: CAPABILITIES,
+    idn: Some(IdnPattern {
+        manufacturers: &["KEYSIGHT", "AGILENT"],
+        models: &["DSOX*", "MSOX*", "EDUX*"],
+    }),
+    description: "Keysight InfiniiVision X-Series oscilloscope",
+};
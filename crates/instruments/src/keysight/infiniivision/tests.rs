@@ -0,0 +1,185 @@
+use super::*;
+use std::io::{BufRead, BufReader};
+use std::net::{TcpListener, TcpStream};
+
+fn expect_command(reader: &mut BufReader<TcpStream>, expected: &str) {
+    let mut command = String::new();
+    reader.read_line(&mut command).unwrap();
+    assert_eq!(command.trim_end(), expected);
+}
+
+fn reply_line(reader: &mut BufReader<TcpStream>, response: &str) {
+    reader
+        .get_mut()
+        .write_all(format!("{response}\n").as_bytes())
+        .unwrap();
+    reader.get_mut().flush().unwrap();
+}
+
+fn connect(port: u16) -> InfiniiVision {
+    InfiniiVision::open_with_timeouts(
+        "127.0.0.1",
+        port,
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    )
+    .unwrap()
+}
+
+#[test]
+fn operation_condition_maps_run_and_wait_bits() {
+    assert_eq!(parse_operation_condition("0").unwrap(), TriggerStatus::Stop);
+    assert_eq!(
+        parse_operation_condition("40").unwrap(),
+        TriggerStatus::Wait
+    );
+    assert_eq!(parse_operation_condition("8").unwrap(), TriggerStatus::Run);
+    assert_eq!(
+        parse_operation_condition("32").unwrap(),
+        TriggerStatus::Stop
+    );
+    assert!(parse_operation_condition("RUN").is_err());
+}
+
+#[test]
+fn preamble_origin_is_converted_to_codes() {
+    let preamble = parse_preamble(
+        "+1,+0,+2,+1,+5.0E-10,-1.0E-06,+0,+1.0E-03,+2.0E-01,+32768".to_string(),
+        0.2,
+        0.05,
+    )
+    .unwrap();
+
+    assert_eq!(preamble.x_increment, 5.0e-10);
+    assert_eq!(preamble.x_origin, -1.0e-6);
+    assert_eq!(preamble.y_reference, 32768.0);
+    // code 32968 is 200 codes above the reference: 0.2 V + 200 mV.
+    let volts = (32968.0 - preamble.y_origin - preamble.y_reference) * preamble.y_increment;
+    assert!((volts - 0.4).abs() < 1e-12);
+    assert!(parse_preamble("1,0,2".to_string(), 0.0, 1.0).is_err());
+}
+
+#[test]
+fn acquisition_setup_rejects_settings_the_scope_lacks() {
+    let setup = AcquisitionSetup {
+        timebase_scale: Some(2.0e-3),
+        trigger_source: Some(TriggerSource::External),
+        trigger_slope: Some(TriggerSlope::Either),
+        channels: vec![ChannelSetup {
+            channel: 2,
+            coupling: Some(Coupling::Ac),
+            bandwidth_limit: Some(BandwidthLimit::Mhz25),
+            ..ChannelSetup::default()
+        }],
+        ..AcquisitionSetup::default()
+    };
+    assert_eq!(
+        acquisition_setup_commands(&setup).unwrap(),
+        [
+            ":TIMebase:SCALe 2E-3",
+            ":TRIGger:MODE EDGE",
+            ":TRIGger:EDGE:SOURce EXTernal",
+            ":TRIGger:EDGE:SLOPe EITHer",
+            ":CHANnel2:COUPling AC",
+            ":CHANnel2:BWLimit ON",
+            "*OPC?",
+        ]
+    );
+
+    let depth = AcquisitionSetup {
+        memory_depth: Some(1_000_000),
+        ..AcquisitionSetup::default()
+    };
+    let error = acquisition_setup_commands(&depth).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+    let limit = AcquisitionSetup {
+        channels: vec![ChannelSetup {
+            channel: 1,
+            bandwidth_limit: Some(BandwidthLimit::Mhz20),
+            ..ChannelSetup::default()
+        }],
+        ..AcquisitionSetup::default()
+    };
+    assert!(acquisition_setup_commands(&limit).is_err());
+}
+
+#[test]
+fn raw_fetch_verifies_word_setup_and_streams_the_block() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+
+        for expected in raw_word_setup_commands(3, 2) {
+            expect_command(&mut reader, &expected);
+        }
+        reply_line(&mut reader, "1");
+        for (command, response) in [
+            (":OPERegister:CONDition?", "0"),
+            (":WAVeform:SOURce?", "CHAN3"),
+            (":WAVeform:FORMat?", "WORD"),
+            (":WAVeform:BYTeorder?", "LSBF"),
+            (":WAVeform:UNSigned?", "1"),
+            (":WAVeform:POINts?", "+2"),
+            (
+                ":WAVeform:PREamble?",
+                "+1,+0,+2,+1,+5.0E-01,+0.0E+00,+0,+1.0E+00,+0.0E+00,+0",
+            ),
+            (":CHANnel3:OFFSet?", "+0.0E+00"),
+            (":CHANnel3:SCALe?", "+1.0E+00"),
+        ] {
+            expect_command(&mut reader, command);
+            reply_line(&mut reader, response);
+        }
+        expect_command(&mut reader, ":WAVeform:DATA?");
+        reader
+            .get_mut()
+            .write_all(b"#14\x01\x00\x02\x00\n")
+            .unwrap();
+        reader.get_mut().flush().unwrap();
+        expect_command(&mut reader, ":OPERegister:CONDition?");
+        reply_line(&mut reader, "0");
+    });
+
+    let mut scope = connect(port);
+    let raw = scope.fetch_raw_word(3, 2).unwrap();
+
+    assert_eq!(raw.data, [1, 0, 2, 0]);
+    assert_eq!(raw.preamble.x_increment, 0.5);
+    server.join().unwrap();
+}
+
+#[test]
+fn acquisition_setup_read_back_uses_infiniivision_queries() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        for (command, response) in [
+            (":TIMebase:POSition?", "+1.0E-03"),
+            (":TIMebase:SCALe?", "+2.0E-03"),
+            (":ACQuire:POINts?", "+62500"),
+            (":TRIGger:EDGE:SOURce?", "CHAN1"),
+            (":TRIGger:EDGE:LEVel?", "+1.5E+00"),
+            (":TRIGger:EDGE:SLOPe?", "NEG"),
+            (":CHANnel1:SCALe?", "+5.0E-01"),
+            (":CHANnel1:OFFSet?", "+0.0E+00"),
+            (":CHANnel1:COUPling?", "DC"),
+            (":CHANnel1:BWLimit?", "0"),
+        ] {
+            expect_command(&mut reader, command);
+            reply_line(&mut reader, response);
+        }
+    });
+
+    let setup = connect(port).query_acquisition_setup(&[1]).unwrap();
+
+    assert_eq!(setup.timebase_offset, Some(1.0e-3));
+    assert_eq!(setup.memory_depth, Some(62_500));
+    assert_eq!(setup.trigger_source, Some(TriggerSource::Channel(1)));
+    assert_eq!(setup.trigger_slope, Some(TriggerSlope::Negative));
+    assert_eq!(setup.channels[0].bandwidth_limit, Some(BandwidthLimit::Off));
+    server.join().unwrap();
+}
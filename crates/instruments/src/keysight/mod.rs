@@ -0,0 +1,2 @@
+pub mod infiniivision;
+pub use infiniivision::InfiniiVision;
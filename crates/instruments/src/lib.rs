@@ -2,9 +2,12 @@ pub mod dummy;
 pub mod error;
 pub mod instruments;
 pub mod keithley;
+pub mod keysight;
 pub mod nf;
+pub mod oscilloscope;
 pub mod registry;
 pub mod rigol;
+pub mod sim;
 pub mod transport;
 
 pub use crate::error::{InstrumentError, Result};
@@ -1,2 +1,2 @@
 pub mod wf1946b;
-pub use wf1946b::WF1946B;
+pub use wf1946b::{WF1946B, WfOutputSetup, WfTriggerSource, WfWaveform};
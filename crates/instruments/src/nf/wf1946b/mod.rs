@@ -2,21 +2,23 @@
 mod driver;
 
 use crate::registry::{
-    InstrumentCapability, InstrumentRole, InstrumentSpec, ProtocolKind, TransportKind,
+    IdnPattern, InstrumentCapability, InstrumentRole, InstrumentSpec, ProtocolKind, TransportKind,
 };
 
-pub use driver::WF1946B;
+pub use driver::{WF1946B, WfOutputSetup, WfTriggerSource, WfWaveform};
 
 pub const MODEL: &str = "WF1946B";
 pub const TRANSPORTS: &[TransportKind] = &[
     TransportKind::Gpib,
     TransportKind::PrologixTcp,
     TransportKind::PrologixSerial,
+    TransportKind::Sim,
 ];
 pub const PROTOCOLS: &[ProtocolKind] = &[ProtocolKind::Scpi];
 pub const CAPABILITIES: &[InstrumentCapability] = &[
     InstrumentCapability::ScpiIdentify,
     InstrumentCapability::ScpiTrigger,
+    InstrumentCapability::OutputSetup,
 ];
 pub const SPEC: InstrumentSpec = InstrumentSpec {
     model: MODEL,
@@ -24,5 +26,9 @@ pub const SPEC: InstrumentSpec = InstrumentSpec {
     transports: TRANSPORTS,
     protocols: PROTOCOLS,
     capabilities: CAPABILITIES,
+    idn: Some(IdnPattern {
+        manufacturers: &["NF"],
+        models: &["WF1946B"],
+    }),
     description: "NF WF1946B function generator",
 };
@@ -45,3 +45,107 @@ fn wf1946b_uses_only_scpi_transport() {
         vec!["*IDN?".to_string(), "*TRG".to_string()]
     );
 }
+
+#[test]
+fn output_setup_sends_only_configured_settings_with_output_last() {
+    let transport = MockTransport::default();
+    transport
+        .responses
+        .lock()
+        .unwrap()
+        .push_back("0,\"No error\"".to_string());
+    let writes = Arc::clone(&transport.writes);
+    let mut device = WF1946B::new(Box::new(transport));
+
+    device
+        .apply_output_setup(&WfOutputSetup {
+            waveform: Some(WfWaveform::Square),
+            frequency_hz: Some(1.0e3),
+            amplitude_vpp: Some(2.5),
+            burst_cycles: Some(3),
+            trigger_source: Some(WfTriggerSource::Bus),
+            output: Some(true),
+            ..WfOutputSetup::default()
+        })
+        .unwrap();
+
+    assert_eq!(
+        *writes.lock().unwrap(),
+        vec![
+            ":SOURce1:FUNCtion:SHAPe SQUare".to_string(),
+            ":SOURce1:FREQuency 1E3".to_string(),
+            ":SOURce1:VOLTage:AMPLitude 2.5E0VPP".to_string(),
+            ":SOURce1:BURSt:STATe ON".to_string(),
+            ":SOURce1:BURSt:MODE TRIGger".to_string(),
+            ":SOURce1:BURSt:TRIGger:NCYCles 3".to_string(),
+            ":TRIGger1:BURSt:SOURce BUS".to_string(),
+            ":OUTPut1:STATe ON".to_string(),
+            ":SYSTem:ERRor?".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn output_setup_fails_on_queued_generator_errors() {
+    let transport = MockTransport::default();
+    transport.responses.lock().unwrap().extend([
+        "-222,\"Data out of range\"".to_string(),
+        "0,\"No error\"".to_string(),
+    ]);
+    let mut device = WF1946B::new(Box::new(transport));
+
+    let error = device
+        .apply_output_setup(&WfOutputSetup {
+            amplitude_vpp: Some(50.0),
+            ..WfOutputSetup::default()
+        })
+        .unwrap_err();
+
+    assert!(error.to_string().contains("Data out of range"), "{error}");
+}
+
+#[test]
+fn output_setup_read_back_parses_generator_responses() {
+    let transport = MockTransport::default();
+    transport
+        .responses
+        .lock()
+        .unwrap()
+        .extend(["SQU", "1.0E+03", "2.5", "-2.5E-1", "1", "3", "EXT", "ON"].map(str::to_string));
+    let mut device = WF1946B::new(Box::new(transport));
+
+    assert_eq!(
+        device.query_output_setup().unwrap(),
+        WfOutputSetup {
+            waveform: Some(WfWaveform::Square),
+            frequency_hz: Some(1.0e3),
+            amplitude_vpp: Some(2.5),
+            offset_v: Some(-0.25),
+            burst_cycles: Some(3),
+            trigger_source: Some(WfTriggerSource::External),
+            output: Some(true),
+        }
+    );
+}
+
+#[test]
+fn typed_setters_format_values_for_the_generator() {
+    let transport = MockTransport::default();
+    let writes = Arc::clone(&transport.writes);
+    let mut device = WF1946B::new(Box::new(transport));
+
+    device.set_offset(-0.25).unwrap();
+    device
+        .set_trigger_source(WfTriggerSource::External)
+        .unwrap();
+    device.set_output(false).unwrap();
+
+    assert_eq!(
+        *writes.lock().unwrap(),
+        vec![
+            ":SOURce1:VOLTage:OFFSet -2.5E-1".to_string(),
+            ":TRIGger1:BURSt:SOURce EXTernal".to_string(),
+            ":OUTPut1:STATe OFF".to_string(),
+        ]
+    );
+}
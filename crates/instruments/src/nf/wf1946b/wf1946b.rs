@@ -1,10 +1,45 @@
 use crate::Result;
 use crate::transport::BoxedScpiTransport;
+use std::io;
+
+/// Upper bound on error-queue reads, so a generator that never reports
+/// `0,"No error"` cannot stall the setup.
+const MAX_QUEUED_ERRORS: usize = 32;
 
 pub struct WF1946B {
     transport: BoxedScpiTransport,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WfWaveform {
+    Sine,
+    Square,
+    Pulse,
+    Ramp,
+    Noise,
+    Dc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WfTriggerSource {
+    /// `*TRG` over the bus; what `trigger` sends.
+    Bus,
+    External,
+    Internal,
+}
+
+/// Output settings; `None` fields are left as they are on the instrument.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WfOutputSetup {
+    pub waveform: Option<WfWaveform>,
+    pub frequency_hz: Option<f64>,
+    pub amplitude_vpp: Option<f64>,
+    pub offset_v: Option<f64>,
+    pub burst_cycles: Option<u32>,
+    pub trigger_source: Option<WfTriggerSource>,
+    pub output: Option<bool>,
+}
+
 impl WF1946B {
     pub fn new(transport: BoxedScpiTransport) -> Self {
         Self { transport }
@@ -18,6 +53,224 @@ impl WF1946B {
         self.transport.write_line("*TRG")?;
         Ok(())
     }
+
+    pub fn set_waveform(&mut self, waveform: WfWaveform) -> Result<()> {
+        self.transport.write_line(&format!(
+            ":SOURce1:FUNCtion:SHAPe {}",
+            waveform_token(waveform)
+        ))
+    }
+
+    pub fn set_frequency(&mut self, frequency_hz: f64) -> Result<()> {
+        self.transport
+            .write_line(&format!(":SOURce1:FREQuency {frequency_hz:E}"))
+    }
+
+    pub fn set_amplitude(&mut self, amplitude_vpp: f64) -> Result<()> {
+        self.transport
+            .write_line(&format!(":SOURce1:VOLTage:AMPLitude {amplitude_vpp:E}VPP"))
+    }
+
+    pub fn set_offset(&mut self, offset_v: f64) -> Result<()> {
+        self.transport
+            .write_line(&format!(":SOURce1:VOLTage:OFFSet {offset_v:E}"))
+    }
+
+    /// Switches to triggered burst mode emitting `cycles` periods per trigger.
+    pub fn set_burst_count(&mut self, cycles: u32) -> Result<()> {
+        self.transport.write_line(":SOURce1:BURSt:STATe ON")?;
+        self.transport.write_line(":SOURce1:BURSt:MODE TRIGger")?;
+        self.transport
+            .write_line(&format!(":SOURce1:BURSt:TRIGger:NCYCles {cycles}"))
+    }
+
+    pub fn set_trigger_source(&mut self, source: WfTriggerSource) -> Result<()> {
+        self.transport.write_line(&format!(
+            ":TRIGger1:BURSt:SOURce {}",
+            trigger_source_token(source)
+        ))
+    }
+
+    pub fn set_output(&mut self, enabled: bool) -> Result<()> {
+        self.transport.write_line(&format!(
+            ":OUTPut1:STATe {}",
+            if enabled { "ON" } else { "OFF" }
+        ))
+    }
+
+    /// Applies every configured setting; the output state goes last so the
+    /// channel is only enabled once the waveform is in place.
+    pub fn apply_output_setup(&mut self, setup: &WfOutputSetup) -> Result<()> {
+        if let Some(waveform) = setup.waveform {
+            self.set_waveform(waveform)?;
+        }
+        if let Some(frequency_hz) = setup.frequency_hz {
+            self.set_frequency(frequency_hz)?;
+        }
+        if let Some(amplitude_vpp) = setup.amplitude_vpp {
+            self.set_amplitude(amplitude_vpp)?;
+        }
+        if let Some(offset_v) = setup.offset_v {
+            self.set_offset(offset_v)?;
+        }
+        if let Some(cycles) = setup.burst_cycles {
+            self.set_burst_count(cycles)?;
+        }
+        if let Some(source) = setup.trigger_source {
+            self.set_trigger_source(source)?;
+        }
+        if let Some(enabled) = setup.output {
+            self.set_output(enabled)?;
+        }
+        self.check_errors()
+    }
+
+    /// Reads back the settings `apply_output_setup` writes. `burst_cycles` is
+    /// `None` while burst mode is off.
+    pub fn query_output_setup(&mut self) -> Result<WfOutputSetup> {
+        let waveform = parse_waveform(&self.transport.query_line(":SOURce1:FUNCtion:SHAPe?")?)?;
+        let frequency_hz = parse_number(
+            &self.transport.query_line(":SOURce1:FREQuency?")?,
+            "frequency",
+        )?;
+        let amplitude_vpp = parse_number(
+            &self.transport.query_line(":SOURce1:VOLTage:AMPLitude?")?,
+            "amplitude",
+        )?;
+        let offset_v = parse_number(
+            &self.transport.query_line(":SOURce1:VOLTage:OFFSet?")?,
+            "offset",
+        )?;
+        let burst_cycles = if parse_state(
+            &self.transport.query_line(":SOURce1:BURSt:STATe?")?,
+            "burst state",
+        )? {
+            let cycles = parse_number(
+                &self
+                    .transport
+                    .query_line(":SOURce1:BURSt:TRIGger:NCYCles?")?,
+                "burst cycle count",
+            )?;
+            Some(cycles.round() as u32)
+        } else {
+            None
+        };
+        let trigger_source =
+            parse_trigger_source(&self.transport.query_line(":TRIGger1:BURSt:SOURce?")?)?;
+        let output = parse_state(
+            &self.transport.query_line(":OUTPut1:STATe?")?,
+            "output state",
+        )?;
+        Ok(WfOutputSetup {
+            waveform: Some(waveform),
+            frequency_hz: Some(frequency_hz),
+            amplitude_vpp: Some(amplitude_vpp),
+            offset_v: Some(offset_v),
+            burst_cycles,
+            trigger_source: Some(trigger_source),
+            output: Some(output),
+        })
+    }
+
+    /// Drains the error queue and fails if the generator rejected a command,
+    /// for example an amplitude outside its range.
+    pub fn check_errors(&mut self) -> Result<()> {
+        let mut errors = Vec::new();
+        for _ in 0..MAX_QUEUED_ERRORS {
+            let response = self.transport.query_line(":SYSTem:ERRor?")?;
+            let code = response
+                .split(',')
+                .next()
+                .and_then(|code| code.trim().parse::<i32>().ok())
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid generator error response: {:?}", response.trim()),
+                    )
+                })?;
+            if code == 0 {
+                break;
+            }
+            errors.push(response.trim().to_string());
+        }
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("generator rejected settings: {}", errors.join("; ")),
+        )
+        .into())
+    }
+}
+
+fn waveform_token(waveform: WfWaveform) -> &'static str {
+    match waveform {
+        WfWaveform::Sine => "SINusoid",
+        WfWaveform::Square => "SQUare",
+        WfWaveform::Pulse => "PULSe",
+        WfWaveform::Ramp => "RAMP",
+        WfWaveform::Noise => "NOISe",
+        WfWaveform::Dc => "DC",
+    }
+}
+
+fn trigger_source_token(source: WfTriggerSource) -> &'static str {
+    match source {
+        WfTriggerSource::Bus => "BUS",
+        WfTriggerSource::External => "EXTernal",
+        WfTriggerSource::Internal => "INTernal",
+    }
+}
+
+fn parse_waveform(value: &str) -> Result<WfWaveform> {
+    let value = value.trim().to_ascii_uppercase();
+    let waveform = match value.as_str() {
+        "SIN" | "SINUSOID" => WfWaveform::Sine,
+        "SQU" | "SQUARE" => WfWaveform::Square,
+        "PULS" | "PULSE" => WfWaveform::Pulse,
+        "RAMP" => WfWaveform::Ramp,
+        "NOIS" | "NOISE" => WfWaveform::Noise,
+        "DC" => WfWaveform::Dc,
+        _ => return Err(invalid_response("waveform", &value)),
+    };
+    Ok(waveform)
+}
+
+fn parse_trigger_source(value: &str) -> Result<WfTriggerSource> {
+    let value = value.trim().to_ascii_uppercase();
+    let source = match value.as_str() {
+        "BUS" => WfTriggerSource::Bus,
+        "EXT" | "EXTERNAL" => WfTriggerSource::External,
+        "INT" | "INTERNAL" => WfTriggerSource::Internal,
+        _ => return Err(invalid_response("burst trigger source", &value)),
+    };
+    Ok(source)
+}
+
+fn parse_state(value: &str, name: &str) -> Result<bool> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "1" | "ON" => Ok(true),
+        "0" | "OFF" => Ok(false),
+        _ => Err(invalid_response(name, value)),
+    }
+}
+
+fn parse_number(value: &str, name: &str) -> Result<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| invalid_response(name, value))
+}
+
+fn invalid_response(name: &str, value: &str) -> crate::InstrumentError {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid generator {name}: {:?}", value.trim()),
+    )
+    .into()
 }
 
 #[cfg(test)]
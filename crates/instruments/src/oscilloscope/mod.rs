@@ -0,0 +1,163 @@
+//! Driver-independent oscilloscope interface used by the fetch pipeline.
+
+pub(crate) mod transport;
+
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy)]
+pub struct HorizontalSettings {
+    pub offset: f64,
+    pub scale: f64,
+}
+
+/// Waveform scaling for one channel.
+///
+/// Every driver reports the Rigol convention so stored RAW words replay the
+/// same way regardless of the scope that wrote them:
+/// `volts = (code - y_origin - y_reference) * y_increment` and
+/// `seconds = x_origin + (index - x_reference) * x_increment`.
+/// `raw` keeps the instrument's own preamble response unchanged.
+#[derive(Debug, Clone)]
+pub struct WaveformPreamble {
+    pub raw: String,
+    pub x_increment: f64,
+    pub x_origin: f64,
+    pub x_reference: f64,
+    pub y_increment: f64,
+    pub y_origin: f64,
+    pub y_reference: f64,
+    pub vertical_offset: f64,
+    pub vertical_scale: f64,
+}
+
+/// Unsigned 16-bit little-endian samples of one channel.
+#[derive(Debug, Clone)]
+pub struct RawWaveform {
+    pub preamble: WaveformPreamble,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RawWaveformWritten {
+    pub preamble: WaveformPreamble,
+    pub byte_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerStatus {
+    Triggered,
+    Wait,
+    Run,
+    Auto,
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerSource {
+    Channel(u8),
+    External,
+    AcLine,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerSlope {
+    Positive,
+    Negative,
+    Either,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Coupling {
+    Dc,
+    Ac,
+    Gnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandwidthLimit {
+    Off,
+    Mhz20,
+    Mhz25,
+    Mhz100,
+    Mhz250,
+}
+
+/// Acquisition settings; `None` fields are left as they are on the instrument.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AcquisitionSetup {
+    pub timebase_scale: Option<f64>,
+    pub timebase_offset: Option<f64>,
+    pub memory_depth: Option<usize>,
+    pub trigger_source: Option<TriggerSource>,
+    pub trigger_level: Option<f64>,
+    pub trigger_slope: Option<TriggerSlope>,
+    pub channels: Vec<ChannelSetup>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelSetup {
+    pub channel: u8,
+    pub scale: Option<f64>,
+    pub offset: Option<f64>,
+    pub coupling: Option<Coupling>,
+    pub bandwidth_limit: Option<BandwidthLimit>,
+}
+
+/// Operations the acquisition pipeline needs from an oscilloscope.
+pub trait Oscilloscope {
+    fn identify(&mut self) -> io::Result<String>;
+
+    /// Arms a single acquisition.
+    fn set_single(&mut self) -> io::Result<()>;
+
+    fn stop(&mut self) -> io::Result<()>;
+
+    fn query_trigger_status(&mut self) -> io::Result<TriggerStatus>;
+
+    fn query_horizontal_settings(&mut self) -> io::Result<HorizontalSettings>;
+
+    fn query_memory_depth(&mut self) -> io::Result<usize>;
+
+    /// Reads the scaling of the waveform source selected for `ch`.
+    fn query_waveform_preamble(&mut self, ch: u8) -> io::Result<WaveformPreamble>;
+
+    /// Streams the full record of `ch` as 16-bit little-endian words.
+    fn fetch_raw_word_into(
+        &mut self,
+        ch: u8,
+        memory_depth: usize,
+        writer: &mut dyn Write,
+    ) -> io::Result<RawWaveformWritten>;
+
+    fn fetch_raw_word(&mut self, ch: u8, memory_depth: usize) -> io::Result<RawWaveform> {
+        let mut data = Vec::new();
+        let written = self.fetch_raw_word_into(ch, memory_depth, &mut data)?;
+        Ok(RawWaveform {
+            preamble: written.preamble,
+            data,
+        })
+    }
+
+    /// Fetches `ch` and converts it to volts.
+    fn fetch(&mut self, ch: u8, memory_depth: usize) -> io::Result<Vec<f64>> {
+        let raw = self.fetch_raw_word(ch, memory_depth)?;
+        let preamble = &raw.preamble;
+        Ok(raw
+            .data
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|&[low, high]| {
+                let code = u16::from_le_bytes([low, high]) as f64;
+                (code - preamble.y_origin - preamble.y_reference) * preamble.y_increment
+            })
+            .collect())
+    }
+
+    fn capture_display_png(&mut self) -> io::Result<Vec<u8>>;
+
+    fn apply_acquisition_setup(&mut self, setup: &AcquisitionSetup) -> io::Result<()>;
+
+    /// Reads back every setting of [`AcquisitionSetup`] for `channels`.
+    fn query_acquisition_setup(&mut self, channels: &[u8]) -> io::Result<AcquisitionSetup>;
+}
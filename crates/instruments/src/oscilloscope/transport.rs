@@ -0,0 +1,376 @@
+//! Line and IEEE 488.2 binary block I/O shared by the LAN/USB oscilloscopes.
+
+use crate::transport::UsbtmcDevice;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+pub(crate) enum ScopeTransport {
+    Tcp(BufReader<TcpStream>),
+    UsbtmcDevice(BufReader<UsbtmcDevice>),
+    #[cfg(all(target_os = "windows", feature = "gpib"))]
+    Visa(gpib_rs::Instrument),
+}
+
+impl ScopeTransport {
+    pub(crate) fn open_tcp(
+        ip: &str,
+        port: u16,
+        connect_timeout: Option<Duration>,
+        io_timeout: Option<Duration>,
+    ) -> io::Result<Self> {
+        let stream = if let Some(connect_timeout) = connect_timeout {
+            let addresses = (ip, port).to_socket_addrs()?.collect::<Vec<_>>();
+            let mut last_error = None;
+            let mut connected = None;
+            for address in addresses {
+                match TcpStream::connect_timeout(&address, connect_timeout) {
+                    Ok(stream) => {
+                        connected = Some(stream);
+                        break;
+                    }
+                    Err(error) => last_error = Some(error),
+                }
+            }
+            connected.ok_or_else(|| {
+                last_error.unwrap_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "no socket address resolved")
+                })
+            })?
+        } else {
+            TcpStream::connect((ip, port))?
+        };
+        stream.set_read_timeout(io_timeout)?;
+        stream.set_write_timeout(io_timeout)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::Tcp(BufReader::new(stream)))
+    }
+
+    pub(crate) fn open_usbtmc(resource: &str, timeout: Option<Duration>) -> io::Result<Self> {
+        #[cfg(all(target_os = "windows", feature = "gpib"))]
+        {
+            let instrument = gpib_rs::Instrument::open_resource(resource, timeout)
+                .map_err(|error| io::Error::other(error.to_string()))?;
+            Ok(Self::Visa(instrument))
+        }
+
+        #[cfg(not(all(target_os = "windows", feature = "gpib")))]
+        {
+            let _ = (resource, timeout);
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "USB-TMC requires a Windows build with the gpib feature",
+            ))
+        }
+    }
+
+    /// Opens a Linux `usbtmc` character device such as `/dev/usbtmc0`.
+    pub(crate) fn open_usbtmc_device(path: &str) -> io::Result<Self> {
+        Ok(Self::UsbtmcDevice(BufReader::new(UsbtmcDevice::open(
+            path,
+        )?)))
+    }
+
+    fn write_raw(&mut self, data: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Tcp(reader) => {
+                reader.get_mut().write_all(data)?;
+                reader.get_mut().flush()
+            }
+            Self::UsbtmcDevice(reader) => {
+                reader.get_mut().write_all(data)?;
+                reader.get_mut().flush()
+            }
+            #[cfg(all(target_os = "windows", feature = "gpib"))]
+            Self::Visa(instrument) => instrument
+                .write_raw(data)
+                .map_err(|error| io::Error::other(error.to_string())),
+        }
+    }
+
+    pub(crate) fn write_line(&mut self, cmd: &str) -> io::Result<()> {
+        let s = format!("{cmd}\n");
+        self.write_raw(s.as_bytes())
+    }
+
+    pub(crate) fn write_lines(&mut self, commands: &[String]) -> io::Result<()> {
+        let capacity = commands.iter().map(|command| command.len() + 1).sum();
+        let mut request = String::with_capacity(capacity);
+        for command in commands {
+            request.push_str(command);
+            request.push('\n');
+        }
+        self.write_raw(request.as_bytes())
+    }
+
+    pub(crate) fn read_line(&mut self) -> io::Result<String> {
+        match self {
+            Self::Tcp(reader) => read_stream_line(reader),
+            Self::UsbtmcDevice(reader) => read_stream_line(reader),
+            #[cfg(all(target_os = "windows", feature = "gpib"))]
+            Self::Visa(instrument) => instrument
+                .read_string()
+                .map_err(|error| io::Error::other(error.to_string())),
+        }
+    }
+
+    pub(crate) fn query(&mut self, cmd: &str) -> io::Result<String> {
+        self.write_line(cmd)?;
+        self.read_line()
+    }
+
+    pub(crate) fn query_f64(&mut self, cmd: &str, name: &str) -> io::Result<f64> {
+        parse_finite_f64(&self.query(cmd)?, name)
+    }
+
+    pub(crate) fn query_binary(
+        &mut self,
+        cmd: &str,
+        expected_length: Option<usize>,
+    ) -> io::Result<Vec<u8>> {
+        #[cfg(all(target_os = "windows", feature = "gpib"))]
+        if let Self::Visa(instrument) = self {
+            let mut data = Vec::new();
+            instrument
+                .query_ieee_block(cmd, &mut data)
+                .map_err(|error| io::Error::other(error.to_string()))?;
+            if let Some(expected_length) = expected_length {
+                validate_binary_block_length(data.len(), expected_length)?;
+            }
+            return Ok(data);
+        }
+
+        self.write_line(cmd)?;
+        let mut data = Vec::with_capacity(expected_length.unwrap_or_default());
+        self.copy_stream_block(&mut data, expected_length)?;
+        Ok(data)
+    }
+
+    pub(crate) fn query_binary_into(
+        &mut self,
+        cmd: &str,
+        writer: &mut dyn Write,
+        expected_length: Option<usize>,
+    ) -> io::Result<usize> {
+        #[cfg(all(target_os = "windows", feature = "gpib"))]
+        if let Self::Visa(instrument) = self {
+            return instrument
+                .query_ieee_block_into(cmd, &mut &mut *writer, expected_length)
+                .map_err(|error| io::Error::other(error.to_string()));
+        }
+
+        self.write_line(cmd)?;
+        self.copy_stream_block(writer, expected_length)
+    }
+
+    fn copy_stream_block(
+        &mut self,
+        writer: &mut dyn Write,
+        expected_length: Option<usize>,
+    ) -> io::Result<usize> {
+        match self {
+            Self::Tcp(reader) => copy_binary_block(reader, writer, expected_length),
+            Self::UsbtmcDevice(reader) => copy_binary_block(reader, writer, expected_length),
+            #[cfg(all(target_os = "windows", feature = "gpib"))]
+            Self::Visa(_) => unreachable!("VISA transport is handled separately"),
+        }
+    }
+}
+
+fn read_stream_line<S: Read>(reader: &mut BufReader<S>) -> io::Result<String> {
+    let mut s = String::new();
+    for _ in 0..4 {
+        s.clear();
+        let read = reader.read_line(&mut s)?;
+        let trimmed = s.trim();
+        if read == 0 || !trimmed.is_empty() {
+            return Ok(trimmed.to_string());
+        }
+    }
+    Ok(String::new())
+}
+
+fn copy_binary_block<S: Read>(
+    reader: &mut BufReader<S>,
+    writer: &mut dyn Write,
+    expected_length: Option<usize>,
+) -> io::Result<usize> {
+    let length = read_binary_block_length(reader)?;
+    if let Some(expected_length) = expected_length {
+        validate_binary_block_length(length, expected_length)?;
+    }
+    let copied = {
+        let mut limited = reader.by_ref().take(length as u64);
+        io::copy(&mut limited, writer)?
+    };
+    if copied != length as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("binary block ended after {copied} bytes, expected {length}"),
+        ));
+    }
+    consume_buffered_terminator(reader);
+    Ok(length)
+}
+
+pub(crate) fn validate_opc_response(response: &str) -> io::Result<()> {
+    if matches!(response.trim().parse::<u8>(), Ok(1)) {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid *OPC? response: {response:?}"),
+    ))
+}
+
+pub(crate) fn ensure_response(name: &str, actual: &str, expected: &str) -> io::Result<()> {
+    if actual.trim().eq_ignore_ascii_case(expected) {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unexpected {name}: {actual:?}, expected {expected:?}"),
+    ))
+}
+
+pub(crate) fn ensure_stopped(status: super::TriggerStatus) -> io::Result<()> {
+    if status == super::TriggerStatus::Stop {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("oscilloscope acquisition changed during RAW transfer: {status:?}"),
+        ))
+    }
+}
+
+pub(crate) fn invalid_setting(name: &str, value: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid {name}: {:?}", value.trim()),
+    )
+}
+
+pub(crate) fn read_binary_block_length<R: BufRead>(reader: &mut R) -> io::Result<usize> {
+    // SCPI binary block structure:
+    // 1  1         n             length          1
+    // # <n> <length_header> <binary_data> [<terminator>]
+    let start = read_next_non_terminator_byte(reader)?;
+    if start != b'#' {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Expected '#' at the start of binary block",
+        ));
+    }
+
+    let mut one = [0u8; 1];
+    reader.read_exact(&mut one)?;
+    if !one[0].is_ascii_digit() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "binary block length digit is not ASCII",
+        ));
+    }
+    let n = (one[0] - b'0') as usize;
+
+    let mut len_buf = vec![0u8; n];
+    reader.read_exact(&mut len_buf)?;
+    let len_str =
+        String::from_utf8(len_buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    len_str
+        .parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn read_next_non_terminator_byte<R: BufRead>(reader: &mut R) -> io::Result<u8> {
+    let mut one = [0u8; 1];
+    loop {
+        reader.read_exact(&mut one)?;
+        if !matches!(one[0], b'\r' | b'\n') {
+            return Ok(one[0]);
+        }
+    }
+}
+
+fn consume_buffered_terminator<S: Read>(reader: &mut BufReader<S>) -> bool {
+    let buffered = reader.buffer();
+    let consume = match buffered.first().copied() {
+        Some(b'\n') => 1,
+        Some(b'\r') if buffered.get(1) == Some(&b'\n') => 2,
+        Some(b'\r') => 1,
+        _ => 0,
+    };
+
+    if consume > 0 {
+        reader.consume(consume);
+        true
+    } else {
+        false
+    }
+}
+
+pub(crate) fn parse_memory_depth(raw: &str) -> io::Result<usize> {
+    let value = raw.trim();
+    let points = value.parse::<f64>().map_err(|error| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid memory depth '{value}': {error}"),
+        )
+    })?;
+    if !points.is_finite() || points <= 0.0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid memory depth '{value}'"),
+        ));
+    }
+    let rounded = points.round();
+    if (points - rounded).abs() > f64::EPSILON || rounded > usize::MAX as f64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("memory depth is not a positive integer: '{value}'"),
+        ));
+    }
+    let memory_depth = rounded as usize;
+    expected_raw_word_bytes(memory_depth).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("memory depth is too large for WORD data: '{value}'"),
+        )
+    })?;
+    Ok(memory_depth)
+}
+
+pub(crate) fn expected_raw_word_bytes(memory_depth: usize) -> io::Result<usize> {
+    memory_depth.checked_mul(2).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "raw WORD byte count overflows usize",
+        )
+    })
+}
+
+pub(crate) fn validate_binary_block_length(actual: usize, expected: usize) -> io::Result<()> {
+    if actual != expected {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("binary block length is {actual} bytes, expected {expected} bytes"),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_finite_f64(raw: &str, name: &str) -> io::Result<f64> {
+    let value = raw.trim().parse::<f64>().map_err(|error| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid waveform {name}: {error}"),
+        )
+    })?;
+    if !value.is_finite() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid waveform {name}: value is not finite"),
+        ));
+    }
+    Ok(value)
+}
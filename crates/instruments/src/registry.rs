@@ -20,9 +20,12 @@ pub enum TransportKind {
     Dummy,
     Gpib,
     Tcpip,
+    Vxi11,
     Usbtmc,
+    UsbtmcDevice,
     PrologixTcp,
     PrologixSerial,
+    Sim,
 }
 
 impl TransportKind {
@@ -31,9 +34,12 @@ impl TransportKind {
             Self::Dummy => "dummy",
             Self::Gpib => "gpib",
             Self::Tcpip => "tcpip",
+            Self::Vxi11 => "vxi11",
             Self::Usbtmc => "usbtmc",
+            Self::UsbtmcDevice => "usbtmc_device",
             Self::PrologixTcp => "prologix_tcp",
             Self::PrologixSerial => "prologix_serial",
+            Self::Sim => "sim",
         }
     }
 
@@ -42,15 +48,19 @@ impl TransportKind {
             Self::Dummy => None,
             Self::Gpib => Some("hw-gpib"),
             Self::Tcpip => Some("hw-core"),
+            Self::Vxi11 => Some("hw-core"),
             Self::Usbtmc => Some("hw-gpib"),
+            Self::UsbtmcDevice => Some("hw-core"),
             Self::PrologixTcp => Some("hw-prologix-tcp"),
             Self::PrologixSerial => Some("hw-prologix-serial"),
+            Self::Sim => Some("hw-core"),
         }
     }
 
     pub fn feature_note(self) -> Option<&'static str> {
         match self {
             Self::Usbtmc => Some("Windows + NI-VISA"),
+            Self::UsbtmcDevice => Some("Linux usbtmc driver"),
             _ => None,
         }
     }
@@ -60,9 +70,12 @@ impl TransportKind {
             Self::Dummy => "dummy://default",
             Self::Gpib => "gpib://0/<addr>",
             Self::Tcpip => "tcp://<host>:<port>",
+            Self::Vxi11 => "vxi11://<host>/inst0",
             Self::Usbtmc => "visa:USB0::...::INSTR",
+            Self::UsbtmcDevice => "usbtmc:///dev/usbtmc0",
             Self::PrologixTcp => "prologix-tcp://<host>:1234?addr=<addr>",
             Self::PrologixSerial => "prologix-serial:///dev/cu.usbserial-XXXX?addr=<addr>",
+            Self::Sim => "sim://<bench>",
         }
     }
 
@@ -73,7 +86,13 @@ impl TransportKind {
 
         match self {
             Self::PrologixTcp | Self::PrologixSerial => PROLOGIX,
-            Self::Dummy | Self::Gpib | Self::Tcpip | Self::Usbtmc => NONE,
+            Self::Dummy
+            | Self::Gpib
+            | Self::Tcpip
+            | Self::Vxi11
+            | Self::Usbtmc
+            | Self::UsbtmcDevice
+            | Self::Sim => NONE,
         }
     }
 }
@@ -102,6 +121,8 @@ pub enum InstrumentCapability {
     ScpiMeasureRead,
     Screenshot,
     WaveformFetch,
+    AcquisitionSetup,
+    OutputSetup,
     VendorIdentify,
 }
 
@@ -113,6 +134,8 @@ impl InstrumentCapability {
             Self::ScpiMeasureRead => "scpi_measure_read",
             Self::Screenshot => "screenshot",
             Self::WaveformFetch => "waveform_fetch",
+            Self::AcquisitionSetup => "acquisition_setup",
+            Self::OutputSetup => "output_setup",
             Self::VendorIdentify => "vendor_identify",
         }
     }
@@ -131,6 +154,48 @@ impl TransportDiagnosticCapability {
     }
 }
 
+/// `*IDN?` manufacturer and model fields reported by one instrument family.
+///
+/// Fields are compared upper-cased with everything but letters and digits
+/// removed, and a leading `MODEL` dropped, so `KEITHLEY INSTRUMENTS INC.,MODEL
+/// 2010` matches manufacturer `KEITHLEY` and model `2010`. Manufacturers match
+/// by prefix; a model ending in `*` matches by prefix, any other exactly.
+#[derive(Debug, Clone, Copy)]
+pub struct IdnPattern {
+    pub manufacturers: &'static [&'static str],
+    pub models: &'static [&'static str],
+}
+
+impl IdnPattern {
+    pub fn matches(self, idn: &str) -> bool {
+        let mut fields = idn.split(',');
+        let (Some(manufacturer), Some(model)) = (fields.next(), fields.next()) else {
+            return false;
+        };
+        let manufacturer = normalize_idn_field(manufacturer);
+        let model = normalize_idn_field(model);
+        let model = model.strip_prefix("MODEL").unwrap_or(&model);
+        self.manufacturers
+            .iter()
+            .any(|expected| manufacturer.starts_with(expected))
+            && self
+                .models
+                .iter()
+                .any(|expected| match expected.strip_suffix('*') {
+                    Some(prefix) => model.starts_with(prefix),
+                    None => model == *expected,
+                })
+    }
+}
+
+fn normalize_idn_field(field: &str) -> String {
+    field
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|character| character.to_ascii_uppercase())
+        .collect()
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct InstrumentSpec {
     pub model: &'static str,
@@ -138,11 +203,14 @@ pub struct InstrumentSpec {
     pub transports: &'static [TransportKind],
     pub protocols: &'static [ProtocolKind],
     pub capabilities: &'static [InstrumentCapability],
+    /// `None` for instruments without a SCPI `*IDN?` response.
+    pub idn: Option<IdnPattern>,
     pub description: &'static str,
 }
 
 pub const KNOWN_INSTRUMENTS: &[InstrumentSpec] = &[
     crate::rigol::dho5108::SPEC,
+    crate::keysight::infiniivision::SPEC,
     crate::nf::wf1946b::SPEC,
     crate::keithley::keithley2010::SPEC,
     crate::keithley::keithley2000::SPEC,
@@ -153,6 +221,13 @@ pub fn find_instrument(model: &str) -> Option<&'static InstrumentSpec> {
     KNOWN_INSTRUMENTS.iter().find(|spec| spec.model == model)
 }
 
+/// Finds the registered model whose [`IdnPattern`] matches an `*IDN?` response.
+pub fn find_instrument_by_idn(idn: &str) -> Option<&'static InstrumentSpec> {
+    KNOWN_INSTRUMENTS
+        .iter()
+        .find(|spec| spec.idn.is_some_and(|pattern| pattern.matches(idn)))
+}
+
 pub fn supports_transport(model: &str, transport: TransportKind) -> bool {
     find_instrument(model)
         .map(|spec| spec.transports.contains(&transport))
@@ -17,6 +17,10 @@ fn known_instruments_are_registered_from_device_specs() {
         find_instrument(crate::rigol::dho5108::MODEL).map(|spec| spec.model),
         Some(crate::rigol::dho5108::MODEL)
     );
+    assert_eq!(
+        find_instrument(crate::keysight::infiniivision::MODEL).map(|spec| spec.role),
+        Some(InstrumentRole::Oscilloscope)
+    );
     assert_eq!(
         find_instrument(crate::nf::wf1946b::MODEL).map(|spec| spec.model),
         Some(crate::nf::wf1946b::MODEL)
@@ -78,6 +82,14 @@ fn transport_metadata_provides_features_and_connection_templates() {
         TransportKind::Usbtmc.feature_note(),
         Some("Windows + NI-VISA")
     );
+    assert_eq!(
+        TransportKind::UsbtmcDevice.required_feature(),
+        Some("hw-core")
+    );
+    assert_eq!(
+        TransportKind::UsbtmcDevice.connection_template(),
+        "usbtmc:///dev/usbtmc0"
+    );
     assert_eq!(
         TransportKind::PrologixTcp.required_feature(),
         Some("hw-prologix-tcp")
@@ -92,3 +104,40 @@ fn transport_metadata_provides_features_and_connection_templates() {
     );
     assert!(TransportKind::Tcpip.diagnostic_capabilities().is_empty());
 }
+
+#[test]
+fn idn_responses_resolve_to_registered_models() {
+    for (idn, model) in [
+        (
+            "RIGOL TECHNOLOGIES,DHO5108,DHO5A000000,00.01.02",
+            Some("DHO5108"),
+        ),
+        ("RIGOL,DHO5108A,serial,firmware", None),
+        (
+            "KEYSIGHT TECHNOLOGIES,DSO-X 3024T,MY00000000,07.50",
+            Some("InfiniiVision"),
+        ),
+        (
+            "AGILENT TECHNOLOGIES,MSO-X 4154A,MY1,02.40",
+            Some("InfiniiVision"),
+        ),
+        ("NF Corporation,WF1946B,0,1.00", Some("WF1946B")),
+        (
+            "KEITHLEY INSTRUMENTS INC.,MODEL 2010,0000000,A13 /A02",
+            Some("Keithley2010"),
+        ),
+        (
+            "KEITHLEY INSTRUMENTS INC.,MODEL 2000,0000000,A20 /A02",
+            Some("Keithley2000"),
+        ),
+        ("KEITHLEY INSTRUMENTS INC.,MODEL 2400,1,C32", None),
+        ("FLUKE,45,0,1", None),
+        ("", None),
+    ] {
+        assert_eq!(
+            find_instrument_by_idn(idn).map(|spec| spec.model),
+            model,
+            "{idn}"
+        );
+    }
+}
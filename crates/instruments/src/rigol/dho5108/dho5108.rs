@@ -1,55 +1,16 @@
-use std::io::{self, BufRead, BufReader, Read, Write};
-use std::net::{TcpStream, ToSocketAddrs};
+use crate::oscilloscope::transport::{
+    ScopeTransport, ensure_response, ensure_stopped, expected_raw_word_bytes, invalid_setting,
+    parse_memory_depth, validate_opc_response,
+};
+use crate::oscilloscope::{
+    AcquisitionSetup, BandwidthLimit, ChannelSetup, Coupling, HorizontalSettings, Oscilloscope,
+    RawWaveformWritten, TriggerSlope, TriggerSource, TriggerStatus, WaveformPreamble,
+};
+use std::io::{self, Write};
 use std::time::Duration;
 
-enum DhoTransport {
-    Tcp(BufReader<TcpStream>),
-    #[cfg(all(target_os = "windows", feature = "gpib"))]
-    Visa(gpib_rs::Instrument),
-}
-
 pub struct DHO5108 {
-    transport: DhoTransport,
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct DhoHorizontalSettings {
-    pub offset: f64,
-    pub scale: f64,
-}
-
-#[derive(Debug, Clone)]
-pub struct DhoWaveformPreamble {
-    pub raw: String,
-    pub x_increment: f64,
-    pub x_origin: f64,
-    pub x_reference: f64,
-    pub y_increment: f64,
-    pub y_origin: f64,
-    pub y_reference: f64,
-    pub vertical_offset: f64,
-    pub vertical_scale: f64,
-}
-
-#[derive(Debug, Clone)]
-pub struct DhoRawWaveform {
-    pub preamble: DhoWaveformPreamble,
-    pub data: Vec<u8>,
-}
-
-#[derive(Debug, Clone)]
-pub struct DhoRawWaveformWritten {
-    pub preamble: DhoWaveformPreamble,
-    pub byte_count: usize,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum DhoTriggerStatus {
-    Triggered,
-    Wait,
-    Run,
-    Auto,
-    Stop,
+    transport: ScopeTransport,
 }
 
 #[allow(dead_code)]
@@ -64,230 +25,50 @@ impl DHO5108 {
         connect_timeout: Option<Duration>,
         io_timeout: Option<Duration>,
     ) -> io::Result<Self> {
-        let stream = if let Some(connect_timeout) = connect_timeout {
-            let addresses = (ip, port).to_socket_addrs()?.collect::<Vec<_>>();
-            let mut last_error = None;
-            let mut connected = None;
-            for address in addresses {
-                match TcpStream::connect_timeout(&address, connect_timeout) {
-                    Ok(stream) => {
-                        connected = Some(stream);
-                        break;
-                    }
-                    Err(error) => last_error = Some(error),
-                }
-            }
-            connected.ok_or_else(|| {
-                last_error.unwrap_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidInput, "no socket address resolved")
-                })
-            })?
-        } else {
-            TcpStream::connect((ip, port))?
-        };
-        stream.set_read_timeout(io_timeout)?;
-        stream.set_write_timeout(io_timeout)?;
-        stream.set_nodelay(true)?;
-        let reader = BufReader::new(stream);
-        Ok(DHO5108 {
-            transport: DhoTransport::Tcp(reader),
+        Ok(Self {
+            transport: ScopeTransport::open_tcp(ip, port, connect_timeout, io_timeout)?,
         })
     }
 
     pub fn open_usbtmc(resource: &str, timeout: Option<Duration>) -> io::Result<Self> {
-        #[cfg(all(target_os = "windows", feature = "gpib"))]
-        {
-            let instrument = gpib_rs::Instrument::open_resource(resource, timeout)
-                .map_err(|error| io::Error::other(error.to_string()))?;
-            Ok(Self {
-                transport: DhoTransport::Visa(instrument),
-            })
-        }
-
-        #[cfg(not(all(target_os = "windows", feature = "gpib")))]
-        {
-            let _ = (resource, timeout);
-            Err(io::Error::new(
-                io::ErrorKind::Unsupported,
-                "USB-TMC requires a Windows build with the gpib feature",
-            ))
-        }
+        Ok(Self {
+            transport: ScopeTransport::open_usbtmc(resource, timeout)?,
+        })
     }
 
-    fn close(self) {}
-
-    fn write_raw(&mut self, data: &[u8]) -> io::Result<()> {
-        match &mut self.transport {
-            DhoTransport::Tcp(reader) => {
-                reader.get_mut().write_all(data)?;
-                reader.get_mut().flush()
-            }
-            #[cfg(all(target_os = "windows", feature = "gpib"))]
-            DhoTransport::Visa(instrument) => instrument
-                .write_raw(data)
-                .map_err(|error| io::Error::other(error.to_string())),
-        }
+    /// Opens a Linux `usbtmc` character device such as `/dev/usbtmc0`.
+    pub fn open_usbtmc_device(path: &str) -> io::Result<Self> {
+        Ok(Self {
+            transport: ScopeTransport::open_usbtmc_device(path)?,
+        })
     }
 
-    fn tcp_reader_mut(&mut self) -> &mut BufReader<TcpStream> {
-        #[cfg(all(target_os = "windows", feature = "gpib"))]
-        match &mut self.transport {
-            DhoTransport::Tcp(reader) => reader,
-            DhoTransport::Visa(_) => unreachable!("VISA transport is handled separately"),
-        }
-
-        #[cfg(not(all(target_os = "windows", feature = "gpib")))]
-        {
-            let DhoTransport::Tcp(reader) = &mut self.transport;
-            reader
-        }
-    }
+    fn close(self) {}
 
     pub fn write_line(&mut self, cmd: &str) -> io::Result<()> {
-        let s = format!("{cmd}\n");
-        self.write_raw(s.as_bytes())
-    }
-
-    fn write_lines(&mut self, commands: &[String]) -> io::Result<()> {
-        let capacity = commands.iter().map(|command| command.len() + 1).sum();
-        let mut request = String::with_capacity(capacity);
-        for command in commands {
-            request.push_str(command);
-            request.push('\n');
-        }
-        self.write_raw(request.as_bytes())
+        self.transport.write_line(cmd)
     }
 
     pub fn read_line(&mut self) -> io::Result<String> {
-        match &mut self.transport {
-            DhoTransport::Tcp(reader) => {
-                let mut s = String::new();
-                for _ in 0..4 {
-                    s.clear();
-                    let read = reader.read_line(&mut s)?;
-                    let trimmed = s.trim();
-                    if read == 0 || !trimmed.is_empty() {
-                        return Ok(trimmed.to_string());
-                    }
-                }
-                Ok(String::new())
-            }
-            #[cfg(all(target_os = "windows", feature = "gpib"))]
-            DhoTransport::Visa(instrument) => instrument
-                .read_string()
-                .map_err(|error| io::Error::other(error.to_string())),
-        }
+        self.transport.read_line()
     }
 
     pub fn query(&mut self, cmd: &str) -> io::Result<String> {
-        self.write_line(cmd)?;
-        self.read_line()
+        self.transport.query(cmd)
     }
 
     pub fn query_binary(&mut self, cmd: &str) -> io::Result<Vec<u8>> {
-        self.query_binary_with_expected_length(cmd, None)
+        self.transport.query_binary(cmd, None)
     }
 
-    fn query_binary_with_expected_length(
-        &mut self,
-        cmd: &str,
-        expected_length: Option<usize>,
-    ) -> io::Result<Vec<u8>> {
-        #[cfg(all(target_os = "windows", feature = "gpib"))]
-        if let DhoTransport::Visa(instrument) = &mut self.transport {
-            let mut data = Vec::new();
-            instrument
-                .query_ieee_block(cmd, &mut data)
-                .map_err(|error| io::Error::other(error.to_string()))?;
-            if let Some(expected_length) = expected_length {
-                validate_binary_block_length(data.len(), expected_length)?;
-            }
-            return Ok(data);
-        }
-
-        self.write_line(cmd)?;
-
-        let reader = self.tcp_reader_mut();
-        let length = read_binary_block_length(reader)?;
-        if let Some(expected_length) = expected_length {
-            validate_binary_block_length(length, expected_length)?;
-        }
-
-        let mut data = vec![0u8; length];
-        reader.read_exact(&mut data)?;
-        consume_buffered_terminator(reader);
-
-        Ok(data)
-    }
-
-    pub fn query_binary_into<W: Write>(&mut self, cmd: &str, writer: &mut W) -> io::Result<usize> {
-        self.query_binary_into_with_expected_length(cmd, writer, None)
-    }
-
-    fn query_binary_into_with_expected_length<W: Write>(
-        &mut self,
-        cmd: &str,
-        writer: &mut W,
-        expected_length: Option<usize>,
-    ) -> io::Result<usize> {
-        #[cfg(all(target_os = "windows", feature = "gpib"))]
-        if let DhoTransport::Visa(instrument) = &mut self.transport {
-            return instrument
-                .query_ieee_block_into(cmd, writer, expected_length)
-                .map_err(|error| io::Error::other(error.to_string()));
-        }
-
-        self.write_line(cmd)?;
-
-        let reader = self.tcp_reader_mut();
-        let length = read_binary_block_length(reader)?;
-        if let Some(expected_length) = expected_length {
-            validate_binary_block_length(length, expected_length)?;
-        }
-        let copied = {
-            let mut limited = reader.by_ref().take(length as u64);
-            io::copy(&mut limited, writer)?
-        };
-        if copied != length as u64 {
-            return Err(io::Error::new(
-                io::ErrorKind::UnexpectedEof,
-                format!("binary block ended after {copied} bytes, expected {length}"),
-            ));
-        }
-        consume_buffered_terminator(reader);
-        Ok(length)
-    }
-
-    pub fn identify(&mut self) -> io::Result<String> {
-        self.query("*IDN?")
-    }
-
-    pub fn query_horizontal_settings(&mut self) -> io::Result<DhoHorizontalSettings> {
-        let offset = self.query_f64(":TIMebase:MAIN:OFFSet?", "horizontal offset")?;
-        let scale = self.query_f64(":TIMebase:MAIN:SCALe?", "horizontal scale")?;
-        Ok(DhoHorizontalSettings { offset, scale })
-    }
-
-    pub fn query_memory_depth(&mut self) -> io::Result<usize> {
-        let raw = self.query(":ACQuire:MDEPth?")?;
-        parse_memory_depth(&raw)
-    }
-
-    pub fn stop(&mut self) -> io::Result<()> {
-        self.write_line(":STOP")
-    }
-
-    pub fn query_trigger_status(&mut self) -> io::Result<DhoTriggerStatus> {
-        parse_trigger_status(&self.query(":TRIGger:STATus?")?)
-    }
-
-    pub fn capture_display_png(&mut self) -> io::Result<Vec<u8>> {
-        self.query_binary(":DISPlay:DATA? PNG")
+    pub fn query_binary_into(&mut self, cmd: &str, writer: &mut dyn Write) -> io::Result<usize> {
+        self.transport.query_binary_into(cmd, writer, None)
     }
 
     fn setup_raw_word_fetch(&mut self, ch: u8, memory_depth: usize) -> io::Result<()> {
         // Send sequential setup commands in one write and synchronize once at the end.
-        self.write_lines(&raw_word_setup_commands(ch, memory_depth))?;
+        self.transport
+            .write_lines(&raw_word_setup_commands(ch, memory_depth))?;
         validate_opc_response(&self.read_line()?)?;
         self.verify_raw_word_setup(ch, memory_depth)?;
         Ok(())
@@ -312,7 +93,40 @@ impl DHO5108 {
         Ok(())
     }
 
-    fn query_waveform_preamble(&mut self, ch: u8) -> io::Result<DhoWaveformPreamble> {
+    fn query_f64(&mut self, cmd: &str, name: &str) -> io::Result<f64> {
+        self.transport.query_f64(cmd, name)
+    }
+}
+
+impl Oscilloscope for DHO5108 {
+    fn identify(&mut self) -> io::Result<String> {
+        self.query("*IDN?")
+    }
+
+    fn set_single(&mut self) -> io::Result<()> {
+        self.write_line("TRIG:SWE SING")
+    }
+
+    fn stop(&mut self) -> io::Result<()> {
+        self.write_line(":STOP")
+    }
+
+    fn query_trigger_status(&mut self) -> io::Result<TriggerStatus> {
+        parse_trigger_status(&self.query(":TRIGger:STATus?")?)
+    }
+
+    fn query_horizontal_settings(&mut self) -> io::Result<HorizontalSettings> {
+        let offset = self.query_f64(":TIMebase:MAIN:OFFSet?", "horizontal offset")?;
+        let scale = self.query_f64(":TIMebase:MAIN:SCALe?", "horizontal scale")?;
+        Ok(HorizontalSettings { offset, scale })
+    }
+
+    fn query_memory_depth(&mut self) -> io::Result<usize> {
+        let raw = self.query(":ACQuire:MDEPth?")?;
+        parse_memory_depth(&raw)
+    }
+
+    fn query_waveform_preamble(&mut self, ch: u8) -> io::Result<WaveformPreamble> {
         // PREamble preserves the full instrument context in metadata, but Rigol
         // rounds some scaling fields there. Query voltage scaling separately so
         // CSV/raw replay matches the older high-precision conversion path.
@@ -335,7 +149,7 @@ impl DHO5108 {
         let vertical_scale =
             self.query_f64(&format!(":CHANnel{ch}:SCALe?"), "channel vertical scale")?;
 
-        Ok(DhoWaveformPreamble {
+        Ok(WaveformPreamble {
             raw: preamble,
             x_increment,
             x_origin,
@@ -348,63 +162,161 @@ impl DHO5108 {
         })
     }
 
-    fn query_f64(&mut self, cmd: &str, name: &str) -> io::Result<f64> {
-        parse_finite_f64(&self.query(cmd)?, name)
-    }
-
-    pub fn fetch_raw_word(&mut self, ch: u8, memory_depth: usize) -> io::Result<DhoRawWaveform> {
-        self.setup_raw_word_fetch(ch, memory_depth)?;
-        let preamble = self.query_waveform_preamble(ch)?;
-
-        let expected_length = expected_raw_word_bytes(memory_depth)?;
-        let data = self.query_binary_with_expected_length("WAV:DATA?", Some(expected_length))?;
-        ensure_stopped(self.query_trigger_status()?)?;
-
-        Ok(DhoRawWaveform { preamble, data })
-    }
-
-    pub fn fetch_raw_word_into<W: Write>(
+    fn fetch_raw_word_into(
         &mut self,
         ch: u8,
         memory_depth: usize,
-        writer: &mut W,
-    ) -> io::Result<DhoRawWaveformWritten> {
+        writer: &mut dyn Write,
+    ) -> io::Result<RawWaveformWritten> {
         self.setup_raw_word_fetch(ch, memory_depth)?;
         let preamble = self.query_waveform_preamble(ch)?;
 
         let expected_length = expected_raw_word_bytes(memory_depth)?;
-        let byte_count = self.query_binary_into_with_expected_length(
-            "WAV:DATA?",
-            writer,
-            Some(expected_length),
-        )?;
+        let byte_count =
+            self.transport
+                .query_binary_into("WAV:DATA?", writer, Some(expected_length))?;
         ensure_stopped(self.query_trigger_status()?)?;
 
-        Ok(DhoRawWaveformWritten {
+        Ok(RawWaveformWritten {
             preamble,
             byte_count,
         })
     }
 
-    pub fn fetch(&mut self, ch: u8, memory_depth: usize) -> io::Result<Vec<f64>> {
-        let raw = self.fetch_raw_word(ch, memory_depth)?;
-        let y_inc = raw.preamble.y_increment;
-        let y_ori = raw.preamble.y_origin;
-        let y_ref = raw.preamble.y_reference;
+    fn capture_display_png(&mut self) -> io::Result<Vec<u8>> {
+        self.query_binary(":DISPlay:DATA? PNG")
+    }
 
-        let mut result = Vec::with_capacity(raw.data.len() / 2);
-        for &[low, high] in raw.data.as_chunks::<2>().0 {
-            let v = u16::from_le_bytes([low, high]) as f64;
-            let y = (v - y_ori - y_ref) * y_inc;
-            result.push(y);
-        }
+    fn apply_acquisition_setup(&mut self, setup: &AcquisitionSetup) -> io::Result<()> {
+        self.transport
+            .write_lines(&acquisition_setup_commands(setup)?)?;
+        validate_opc_response(&self.read_line()?)
+    }
+
+    fn query_acquisition_setup(&mut self, channels: &[u8]) -> io::Result<AcquisitionSetup> {
+        let horizontal = self.query_horizontal_settings()?;
+        let memory_depth = self.query_memory_depth()?;
+        let trigger_source = parse_trigger_source(&self.query(":TRIGger:EDGE:SOURce?")?)?;
+        let trigger_level = self.query_f64(":TRIGger:EDGE:LEVel?", "trigger level")?;
+        let trigger_slope = parse_trigger_slope(&self.query(":TRIGger:EDGE:SLOPe?")?)?;
+        let channels = channels
+            .iter()
+            .map(|&ch| {
+                Ok(ChannelSetup {
+                    channel: ch,
+                    scale: Some(
+                        self.query_f64(&format!(":CHANnel{ch}:SCALe?"), "channel vertical scale")?,
+                    ),
+                    offset: Some(
+                        self.query_f64(
+                            &format!(":CHANnel{ch}:OFFSet?"),
+                            "channel vertical offset",
+                        )?,
+                    ),
+                    coupling: Some(parse_coupling(
+                        &self.query(&format!(":CHANnel{ch}:COUPling?"))?,
+                    )?),
+                    bandwidth_limit: Some(parse_bandwidth_limit(
+                        &self.query(&format!(":CHANnel{ch}:BWLimit?"))?,
+                    )?),
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(AcquisitionSetup {
+            timebase_scale: Some(horizontal.scale),
+            timebase_offset: Some(horizontal.offset),
+            memory_depth: Some(memory_depth),
+            trigger_source: Some(trigger_source),
+            trigger_level: Some(trigger_level),
+            trigger_slope: Some(trigger_slope),
+            channels,
+        })
+    }
+}
 
-        Ok(result)
+fn acquisition_setup_commands(setup: &AcquisitionSetup) -> io::Result<Vec<String>> {
+    let mut commands = Vec::new();
+    // Memory depth limits the timebase range, and the vertical scale limits
+    // the offset range, so each is sent before the setting it constrains.
+    if let Some(depth) = setup.memory_depth {
+        commands.push(format!(":ACQuire:MDEPth {}", memory_depth_token(depth)));
+    }
+    if let Some(scale) = setup.timebase_scale {
+        commands.push(format!(":TIMebase:MAIN:SCALe {scale:E}"));
+    }
+    if let Some(offset) = setup.timebase_offset {
+        commands.push(format!(":TIMebase:MAIN:OFFSet {offset:E}"));
+    }
+    if setup.trigger_source.is_some()
+        || setup.trigger_level.is_some()
+        || setup.trigger_slope.is_some()
+    {
+        commands.push(":TRIGger:MODE EDGE".to_string());
+    }
+    if let Some(source) = setup.trigger_source {
+        let token = match source {
+            TriggerSource::Channel(ch) => format!("CHAN{ch}"),
+            TriggerSource::External => "EXT".to_string(),
+            TriggerSource::AcLine => "ACLine".to_string(),
+        };
+        commands.push(format!(":TRIGger:EDGE:SOURce {token}"));
+    }
+    if let Some(level) = setup.trigger_level {
+        commands.push(format!(":TRIGger:EDGE:LEVel {level:E}"));
+    }
+    if let Some(slope) = setup.trigger_slope {
+        let token = match slope {
+            TriggerSlope::Positive => "POSitive",
+            TriggerSlope::Negative => "NEGative",
+            TriggerSlope::Either => "RFALl",
+        };
+        commands.push(format!(":TRIGger:EDGE:SLOPe {token}"));
+    }
+    for channel in &setup.channels {
+        let ch = channel.channel;
+        if let Some(coupling) = channel.coupling {
+            let token = match coupling {
+                Coupling::Dc => "DC",
+                Coupling::Ac => "AC",
+                Coupling::Gnd => "GND",
+            };
+            commands.push(format!(":CHANnel{ch}:COUPling {token}"));
+        }
+        if let Some(limit) = channel.bandwidth_limit {
+            let token = match limit {
+                BandwidthLimit::Off => "OFF",
+                BandwidthLimit::Mhz20 => "20M",
+                BandwidthLimit::Mhz100 => "100M",
+                BandwidthLimit::Mhz250 => "250M",
+                BandwidthLimit::Mhz25 => {
+                    return Err(unsupported_setting("a 25 MHz bandwidth limit"));
+                }
+            };
+            commands.push(format!(":CHANnel{ch}:BWLimit {token}"));
+        }
+        if let Some(scale) = channel.scale {
+            commands.push(format!(":CHANnel{ch}:SCALe {scale:E}"));
+        }
+        if let Some(offset) = channel.offset {
+            commands.push(format!(":CHANnel{ch}:OFFSet {offset:E}"));
+        }
     }
+    commands.push("*OPC?".to_string());
+    Ok(commands)
+}
 
-    pub fn set_single(&mut self) -> io::Result<()> {
-        self.write_line("TRIG:SWE SING")?;
-        Ok(())
+fn unsupported_setting(setting: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("DHO5108 does not support {setting}"),
+    )
+}
+
+fn memory_depth_token(depth: usize) -> String {
+    match depth {
+        d if d >= 1_000_000 && d % 1_000_000 == 0 => format!("{}M", d / 1_000_000),
+        d if d >= 1_000 && d % 1_000 == 0 => format!("{}k", d / 1_000),
+        d => d.to_string(),
     }
 }
 
@@ -421,33 +333,13 @@ fn raw_word_setup_commands(ch: u8, memory_depth: usize) -> Vec<String> {
     ]
 }
 
-fn validate_opc_response(response: &str) -> io::Result<()> {
-    if matches!(response.trim().parse::<u8>(), Ok(1)) {
-        return Ok(());
-    }
-    Err(io::Error::new(
-        io::ErrorKind::InvalidData,
-        format!("invalid *OPC? response: {response:?}"),
-    ))
-}
-
-fn ensure_response(name: &str, actual: &str, expected: &str) -> io::Result<()> {
-    if actual.trim().eq_ignore_ascii_case(expected) {
-        return Ok(());
-    }
-    Err(io::Error::new(
-        io::ErrorKind::InvalidData,
-        format!("unexpected {name}: {actual:?}, expected {expected:?}"),
-    ))
-}
-
-fn parse_trigger_status(value: &str) -> io::Result<DhoTriggerStatus> {
+fn parse_trigger_status(value: &str) -> io::Result<TriggerStatus> {
     match value.trim().to_ascii_uppercase().as_str() {
-        "TD" => Ok(DhoTriggerStatus::Triggered),
-        "WAIT" => Ok(DhoTriggerStatus::Wait),
-        "RUN" => Ok(DhoTriggerStatus::Run),
-        "AUTO" => Ok(DhoTriggerStatus::Auto),
-        "STOP" => Ok(DhoTriggerStatus::Stop),
+        "TD" => Ok(TriggerStatus::Triggered),
+        "WAIT" => Ok(TriggerStatus::Wait),
+        "RUN" => Ok(TriggerStatus::Run),
+        "AUTO" => Ok(TriggerStatus::Auto),
+        "STOP" => Ok(TriggerStatus::Stop),
         other => Err(io::Error::new(
             io::ErrorKind::InvalidData,
             format!("invalid trigger status: {other:?}"),
@@ -455,139 +347,46 @@ fn parse_trigger_status(value: &str) -> io::Result<DhoTriggerStatus> {
     }
 }
 
-fn ensure_stopped(status: DhoTriggerStatus) -> io::Result<()> {
-    if status == DhoTriggerStatus::Stop {
-        Ok(())
-    } else {
-        Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("oscilloscope acquisition changed during RAW transfer: {status:?}"),
-        ))
-    }
-}
-
-fn read_binary_block_length<R: BufRead>(reader: &mut R) -> io::Result<usize> {
-    // SCPI binary block structure:
-    // 1  1         n             length          1
-    // # <n> <length_header> <binary_data> [<terminator>]
-    let start = read_next_non_terminator_byte(reader)?;
-    if start != b'#' {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            "Expected '#' at the start of binary block",
-        ));
-    }
-
-    let mut one = [0u8; 1];
-    reader.read_exact(&mut one)?;
-    if !one[0].is_ascii_digit() {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            "binary block length digit is not ASCII",
-        ));
-    }
-    let n = (one[0] - b'0') as usize;
-
-    let mut len_buf = vec![0u8; n];
-    reader.read_exact(&mut len_buf)?;
-    let len_str =
-        String::from_utf8(len_buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-    len_str
-        .parse()
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+fn parse_trigger_source(value: &str) -> io::Result<TriggerSource> {
+    let token = value.trim().to_ascii_uppercase();
+    match token.as_str() {
+        "EXT" => return Ok(TriggerSource::External),
+        "ACL" | "ACLINE" => return Ok(TriggerSource::AcLine),
+        _ => {}
+    }
+    token
+        .strip_prefix("CHAN")
+        .and_then(|ch| ch.strip_prefix("NEL").unwrap_or(ch).parse::<u8>().ok())
+        .map(TriggerSource::Channel)
+        .ok_or_else(|| invalid_setting("trigger source", value))
 }
 
-fn read_next_non_terminator_byte<R: BufRead>(reader: &mut R) -> io::Result<u8> {
-    let mut one = [0u8; 1];
-    loop {
-        reader.read_exact(&mut one)?;
-        if !matches!(one[0], b'\r' | b'\n') {
-            return Ok(one[0]);
-        }
-    }
-}
-
-fn consume_buffered_terminator(reader: &mut BufReader<TcpStream>) -> bool {
-    let buffered = reader.buffer();
-    let consume = match buffered.first().copied() {
-        Some(b'\n') => 1,
-        Some(b'\r') if buffered.get(1) == Some(&b'\n') => 2,
-        Some(b'\r') => 1,
-        _ => 0,
-    };
-
-    if consume > 0 {
-        reader.consume(consume);
-        true
-    } else {
-        false
-    }
-}
-
-fn parse_memory_depth(raw: &str) -> io::Result<usize> {
-    let value = raw.trim();
-    let points = value.parse::<f64>().map_err(|error| {
-        io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("invalid memory depth '{value}': {error}"),
-        )
-    })?;
-    if !points.is_finite() || points <= 0.0 {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("invalid memory depth '{value}'"),
-        ));
-    }
-    let rounded = points.round();
-    if (points - rounded).abs() > f64::EPSILON || rounded > usize::MAX as f64 {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("memory depth is not a positive integer: '{value}'"),
-        ));
+fn parse_trigger_slope(value: &str) -> io::Result<TriggerSlope> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "POS" | "POSITIVE" => Ok(TriggerSlope::Positive),
+        "NEG" | "NEGATIVE" => Ok(TriggerSlope::Negative),
+        "RFAL" | "RFALL" => Ok(TriggerSlope::Either),
+        _ => Err(invalid_setting("trigger slope", value)),
     }
-    let memory_depth = rounded as usize;
-    expected_raw_word_bytes(memory_depth).map_err(|_| {
-        io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("memory depth is too large for WORD data: '{value}'"),
-        )
-    })?;
-    Ok(memory_depth)
-}
-
-fn expected_raw_word_bytes(memory_depth: usize) -> io::Result<usize> {
-    memory_depth.checked_mul(2).ok_or_else(|| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "raw WORD byte count overflows usize",
-        )
-    })
 }
 
-fn validate_binary_block_length(actual: usize, expected: usize) -> io::Result<()> {
-    if actual != expected {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("binary block length is {actual} bytes, expected {expected} bytes"),
-        ));
+fn parse_coupling(value: &str) -> io::Result<Coupling> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "DC" => Ok(Coupling::Dc),
+        "AC" => Ok(Coupling::Ac),
+        "GND" => Ok(Coupling::Gnd),
+        _ => Err(invalid_setting("channel coupling", value)),
     }
-    Ok(())
 }
 
-fn parse_finite_f64(raw: &str, name: &str) -> io::Result<f64> {
-    let value = raw.trim().parse::<f64>().map_err(|error| {
-        io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("invalid waveform {name}: {error}"),
-        )
-    })?;
-    if !value.is_finite() {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("invalid waveform {name}: value is not finite"),
-        ));
+fn parse_bandwidth_limit(value: &str) -> io::Result<BandwidthLimit> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "OFF" => Ok(BandwidthLimit::Off),
+        "20M" => Ok(BandwidthLimit::Mhz20),
+        "100M" => Ok(BandwidthLimit::Mhz100),
+        "250M" => Ok(BandwidthLimit::Mhz250),
+        _ => Err(invalid_setting("channel bandwidth limit", value)),
     }
-    Ok(value)
 }
 
 #[cfg(test)]
@@ -2,21 +2,61 @@
 mod driver;
 
 use crate::registry::{
-    InstrumentCapability, InstrumentRole, InstrumentSpec, ProtocolKind, TransportKind,
+    IdnPattern, InstrumentCapability, InstrumentRole, InstrumentSpec, ProtocolKind, TransportKind,
 };
 
-pub use driver::{
-    DHO5108, DhoHorizontalSettings, DhoRawWaveform, DhoRawWaveformWritten, DhoTriggerStatus,
-    DhoWaveformPreamble,
-};
+pub use driver::DHO5108;
 
+#[deprecated(
+    since = "0.3.0",
+    note = "use instruments::oscilloscope::AcquisitionSetup"
+)]
+pub type DhoAcquisitionSetup = crate::oscilloscope::AcquisitionSetup;
+#[deprecated(
+    since = "0.3.0",
+    note = "use instruments::oscilloscope::BandwidthLimit"
+)]
+pub type DhoBandwidthLimit = crate::oscilloscope::BandwidthLimit;
+#[deprecated(since = "0.3.0", note = "use instruments::oscilloscope::ChannelSetup")]
+pub type DhoChannelSetup = crate::oscilloscope::ChannelSetup;
+#[deprecated(since = "0.3.0", note = "use instruments::oscilloscope::Coupling")]
+pub type DhoCoupling = crate::oscilloscope::Coupling;
+#[deprecated(
+    since = "0.3.0",
+    note = "use instruments::oscilloscope::HorizontalSettings"
+)]
+pub type DhoHorizontalSettings = crate::oscilloscope::HorizontalSettings;
+#[deprecated(since = "0.3.0", note = "use instruments::oscilloscope::RawWaveform")]
+pub type DhoRawWaveform = crate::oscilloscope::RawWaveform;
+#[deprecated(
+    since = "0.3.0",
+    note = "use instruments::oscilloscope::RawWaveformWritten"
+)]
+pub type DhoRawWaveformWritten = crate::oscilloscope::RawWaveformWritten;
+#[deprecated(since = "0.3.0", note = "use instruments::oscilloscope::TriggerSlope")]
+pub type DhoTriggerSlope = crate::oscilloscope::TriggerSlope;
+#[deprecated(since = "0.3.0", note = "use instruments::oscilloscope::TriggerSource")]
+pub type DhoTriggerSource = crate::oscilloscope::TriggerSource;
+#[deprecated(since = "0.3.0", note = "use instruments::oscilloscope::TriggerStatus")]
+pub type DhoTriggerStatus = crate::oscilloscope::TriggerStatus;
+#[deprecated(
+    since = "0.3.0",
+    note = "use instruments::oscilloscope::WaveformPreamble"
+)]
+pub type DhoWaveformPreamble = crate::oscilloscope::WaveformPreamble;
 pub const MODEL: &str = "DHO5108";
-pub const TRANSPORTS: &[TransportKind] = &[TransportKind::Tcpip, TransportKind::Usbtmc];
+pub const TRANSPORTS: &[TransportKind] = &[
+    TransportKind::Tcpip,
+    TransportKind::Usbtmc,
+    TransportKind::UsbtmcDevice,
+    TransportKind::Sim,
+];
 pub const PROTOCOLS: &[ProtocolKind] = &[ProtocolKind::Scpi];
 pub const CAPABILITIES: &[InstrumentCapability] = &[
     InstrumentCapability::ScpiIdentify,
     InstrumentCapability::Screenshot,
     InstrumentCapability::WaveformFetch,
+    InstrumentCapability::AcquisitionSetup,
 ];
 pub const SPEC: InstrumentSpec = InstrumentSpec {
     model: MODEL,
@@ -24,5 +64,9 @@ pub const SPEC: InstrumentSpec = InstrumentSpec {
     transports: TRANSPORTS,
     protocols: PROTOCOLS,
     capabilities: CAPABILITIES,
+    idn: Some(IdnPattern {
+        manufacturers: &["RIGOL"],
+        models: &["DHO5108"],
+    }),
     description: "Rigol DHO5108 oscilloscope",
 };
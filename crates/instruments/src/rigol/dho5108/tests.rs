@@ -1,12 +1,16 @@
 use super::*;
-use std::io::{BufReader, Cursor, Read};
-use std::net::TcpListener;
+use crate::oscilloscope::transport::{
+    parse_finite_f64, parse_memory_depth, read_binary_block_length, validate_binary_block_length,
+};
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::net::{TcpListener, TcpStream};
 
 fn tcp_stream(dho: &DHO5108) -> &TcpStream {
     match &dho.transport {
-        DhoTransport::Tcp(reader) => reader.get_ref(),
+        ScopeTransport::Tcp(reader) => reader.get_ref(),
+        ScopeTransport::UsbtmcDevice(_) => panic!("test instrument unexpectedly uses USBTMC"),
         #[cfg(all(target_os = "windows", feature = "gpib"))]
-        DhoTransport::Visa(_) => panic!("test instrument unexpectedly uses VISA"),
+        ScopeTransport::Visa(_) => panic!("test instrument unexpectedly uses VISA"),
     }
 }
 
@@ -90,22 +94,118 @@ fn opc_response_must_report_completion() {
 fn trigger_status_parser_accepts_documented_states() {
     assert_eq!(
         parse_trigger_status("TD").unwrap(),
-        DhoTriggerStatus::Triggered
+        TriggerStatus::Triggered
     );
+    assert_eq!(parse_trigger_status("WAIT").unwrap(), TriggerStatus::Wait);
+    assert_eq!(parse_trigger_status("RUN").unwrap(), TriggerStatus::Run);
+    assert_eq!(parse_trigger_status("AUTO").unwrap(), TriggerStatus::Auto);
+    assert_eq!(parse_trigger_status("STOP").unwrap(), TriggerStatus::Stop);
+    assert!(parse_trigger_status("UNKNOWN").is_err());
+}
+
+#[test]
+fn acquisition_setup_sends_only_configured_settings_in_dependency_order() {
+    let setup = AcquisitionSetup {
+        timebase_scale: Some(2.0e-3),
+        memory_depth: Some(10_000_000),
+        trigger_source: Some(TriggerSource::External),
+        channels: vec![ChannelSetup {
+            channel: 2,
+            scale: Some(0.5),
+            offset: Some(-0.25),
+            bandwidth_limit: Some(BandwidthLimit::Mhz20),
+            ..ChannelSetup::default()
+        }],
+        ..AcquisitionSetup::default()
+    };
+
     assert_eq!(
-        parse_trigger_status("WAIT").unwrap(),
-        DhoTriggerStatus::Wait
+        acquisition_setup_commands(&setup).unwrap(),
+        [
+            ":ACQuire:MDEPth 10M",
+            ":TIMebase:MAIN:SCALe 2E-3",
+            ":TRIGger:MODE EDGE",
+            ":TRIGger:EDGE:SOURce EXT",
+            ":CHANnel2:BWLimit 20M",
+            ":CHANnel2:SCALe 5E-1",
+            ":CHANnel2:OFFSet -2.5E-1",
+            "*OPC?",
+        ]
     );
-    assert_eq!(parse_trigger_status("RUN").unwrap(), DhoTriggerStatus::Run);
     assert_eq!(
-        parse_trigger_status("AUTO").unwrap(),
-        DhoTriggerStatus::Auto
+        acquisition_setup_commands(&AcquisitionSetup::default()).unwrap(),
+        ["*OPC?"]
     );
+    assert_eq!(memory_depth_token(25_000), "25k");
+    assert_eq!(memory_depth_token(1_234), "1234");
+}
+
+#[test]
+fn setting_parsers_accept_short_and_long_forms() {
     assert_eq!(
-        parse_trigger_status("STOP").unwrap(),
-        DhoTriggerStatus::Stop
+        parse_trigger_source("CHAN3").unwrap(),
+        TriggerSource::Channel(3)
     );
-    assert!(parse_trigger_status("UNKNOWN").is_err());
+    assert_eq!(
+        parse_trigger_source("channel1\n").unwrap(),
+        TriggerSource::Channel(1)
+    );
+    assert_eq!(parse_trigger_source("ACL").unwrap(), TriggerSource::AcLine);
+    assert!(parse_trigger_source("CHANX").is_err());
+    assert_eq!(parse_trigger_slope("RFAL").unwrap(), TriggerSlope::Either);
+    assert_eq!(parse_coupling("gnd").unwrap(), Coupling::Gnd);
+    assert_eq!(parse_bandwidth_limit("OFF").unwrap(), BandwidthLimit::Off);
+    assert!(parse_bandwidth_limit("10M").is_err());
+}
+
+#[test]
+fn acquisition_setup_read_back_queries_each_channel() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        for (command, response) in [
+            (":TIMebase:MAIN:OFFSet?", "1.0E-3"),
+            (":TIMebase:MAIN:SCALe?", "2.0E-3"),
+            (":ACQuire:MDEPth?", "1.000E+7"),
+            (":TRIGger:EDGE:SOURce?", "EXT"),
+            (":TRIGger:EDGE:LEVel?", "1.5"),
+            (":TRIGger:EDGE:SLOPe?", "POS"),
+            (":CHANnel1:SCALe?", "0.5"),
+            (":CHANnel1:OFFSet?", "0"),
+            (":CHANnel1:COUPling?", "DC"),
+            (":CHANnel1:BWLimit?", "20M"),
+        ] {
+            expect_command(&mut reader, command);
+            reply_line(&mut reader, response);
+        }
+    });
+    let mut dho = DHO5108::open_with_timeouts(
+        "127.0.0.1",
+        port,
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(1)),
+    )
+    .unwrap();
+
+    let setup = dho.query_acquisition_setup(&[1]).unwrap();
+
+    assert_eq!(setup.timebase_scale, Some(2.0e-3));
+    assert_eq!(setup.memory_depth, Some(10_000_000));
+    assert_eq!(setup.trigger_source, Some(TriggerSource::External));
+    assert_eq!(setup.trigger_slope, Some(TriggerSlope::Positive));
+    assert_eq!(
+        setup.channels,
+        [ChannelSetup {
+            channel: 1,
+            scale: Some(0.5),
+            offset: Some(0.0),
+            coupling: Some(Coupling::Dc),
+            bandwidth_limit: Some(BandwidthLimit::Mhz20),
+        }]
+    );
+    server.join().unwrap();
 }
 
 #[test]
@@ -213,7 +313,8 @@ fn binary_query_rejects_declared_payload_length_mismatch() {
         let mut output = Vec::new();
 
         let error = dho
-            .query_binary_into_with_expected_length("BIN?", &mut output, Some(4))
+            .transport
+            .query_binary_into("BIN?", &mut output, Some(4))
             .unwrap_err();
 
         assert_eq!(error.kind(), io::ErrorKind::InvalidData);
@@ -223,6 +324,24 @@ fn binary_query_rejects_declared_payload_length_mismatch() {
     }
 }
 
+#[test]
+fn usbtmc_device_reads_a_definite_length_block_from_a_fake_device_file() {
+    let command = ":WAV:DATA?";
+    let path = std::env::temp_dir().join(format!("pmoke-dho5108-usbtmc-{}", std::process::id()));
+    let mut contents = vec![b' '; command.len() + 1];
+    contents.extend_from_slice(b"#9000000004\x01\x02\x03\x04\n");
+    std::fs::write(&path, contents).unwrap();
+
+    let mut dho = DHO5108::open_usbtmc_device(path.to_str().unwrap()).unwrap();
+    let payload = dho.transport.query_binary(command, Some(4)).unwrap();
+    drop(dho);
+
+    let written = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(payload, [1, 2, 3, 4]);
+    assert!(written.starts_with(b":WAV:DATA?\n"));
+}
+
 #[test]
 fn binary_query_rejects_truncated_payload() {
     let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
@@ -1,5 +1,8 @@
 pub mod dho5108;
+pub use dho5108::DHO5108;
+#[allow(deprecated)]
 pub use dho5108::{
-    DHO5108, DhoHorizontalSettings, DhoRawWaveform, DhoRawWaveformWritten, DhoTriggerStatus,
+    DhoAcquisitionSetup, DhoBandwidthLimit, DhoChannelSetup, DhoCoupling, DhoHorizontalSettings,
+    DhoRawWaveform, DhoRawWaveformWritten, DhoTriggerSlope, DhoTriggerSource, DhoTriggerStatus,
     DhoWaveformPreamble,
 };
@@ -0,0 +1,186 @@
+use super::{SIM_IDN_SERIAL, SimBench, header_matches};
+use crate::Result;
+use crate::transport::ScpiTransport;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Arc;
+
+const MAX_AMPLITUDE_VPP: f64 = 20.0;
+const MAX_FREQUENCY_HZ: f64 = 15.0e6;
+const ILLEGAL_PARAMETER: &str = "-224,\"Illegal parameter value\"";
+const OUT_OF_RANGE: &str = "-222,\"Data out of range\"";
+
+/// Output settings and error queue; the WF1946B only accepts `*TRG` from the
+/// bus as a burst trigger.
+pub(super) struct GeneratorState {
+    waveform: String,
+    frequency_hz: f64,
+    amplitude_vpp: f64,
+    offset_v: f64,
+    burst: bool,
+    burst_cycles: u32,
+    trigger_source: String,
+    output: bool,
+    errors: VecDeque<&'static str>,
+}
+
+impl Default for GeneratorState {
+    fn default() -> Self {
+        Self {
+            waveform: "SIN".to_string(),
+            frequency_hz: 1.0e3,
+            amplitude_vpp: 0.1,
+            offset_v: 0.0,
+            burst: false,
+            burst_cycles: 1,
+            trigger_source: "BUS".to_string(),
+            output: false,
+            errors: VecDeque::new(),
+        }
+    }
+}
+
+impl GeneratorState {
+    /// Applies a setting; rejected values queue an error as the instrument does.
+    fn set(&mut self, header: &str, argument: &str) {
+        let value = argument.trim().to_ascii_uppercase();
+        let is = |pattern| header_matches(pattern, header);
+        let result = if is(":SOURce1:FUNCtion:SHAPe") {
+            token(&value, &["SIN", "SQU", "PULS", "RAMP", "NOIS", "DC"])
+                .map(|shape| self.waveform = shape.to_string())
+        } else if is(":SOURce1:FREQuency") {
+            in_range(&value, 0.0, MAX_FREQUENCY_HZ).map(|value| self.frequency_hz = value)
+        } else if is(":SOURce1:VOLTage:AMPLitude") {
+            let value = value.strip_suffix("VPP").unwrap_or(&value);
+            in_range(value, 0.0, MAX_AMPLITUDE_VPP).map(|value| self.amplitude_vpp = value)
+        } else if is(":SOURce1:VOLTage:OFFSet") {
+            in_range(&value, -MAX_AMPLITUDE_VPP / 2.0, MAX_AMPLITUDE_VPP / 2.0)
+                .map(|value| self.offset_v = value)
+        } else if is(":SOURce1:BURSt:STATe") {
+            state(&value).map(|state| self.burst = state)
+        } else if is(":SOURce1:BURSt:TRIGger:NCYCles") {
+            value
+                .parse::<u32>()
+                .ok()
+                .filter(|cycles| *cycles > 0)
+                .ok_or(OUT_OF_RANGE)
+                .map(|cycles| self.burst_cycles = cycles)
+        } else if is(":TRIGger1:BURSt:SOURce") {
+            token(&value, &["BUS", "EXT", "INT"])
+                .map(|source| self.trigger_source = source.to_string())
+        } else if is(":OUTPut1:STATe") {
+            state(&value).map(|state| self.output = state)
+        } else {
+            Ok(())
+        };
+        if let Err(error) = result {
+            self.errors.push_back(error);
+        }
+    }
+
+    fn query(&mut self, header: &str) -> Option<String> {
+        let is = |pattern| header_matches(pattern, header);
+        let on_off = |state: bool| if state { "ON" } else { "OFF" }.to_string();
+        let reply = if is("*IDN") {
+            format!("NF Corporation,WF1946B,{SIM_IDN_SERIAL},sim")
+        } else if is("*OPC") {
+            "1".to_string()
+        } else if is(":SYSTem:ERRor") {
+            self.errors
+                .pop_front()
+                .unwrap_or("0,\"No error\"")
+                .to_string()
+        } else if is(":SOURce1:FUNCtion:SHAPe") {
+            self.waveform.clone()
+        } else if is(":SOURce1:FREQuency") {
+            format!("{:E}", self.frequency_hz)
+        } else if is(":SOURce1:VOLTage:AMPLitude") {
+            format!("{:E}", self.amplitude_vpp)
+        } else if is(":SOURce1:VOLTage:OFFSet") {
+            format!("{:E}", self.offset_v)
+        } else if is(":SOURce1:BURSt:STATe") {
+            on_off(self.burst)
+        } else if is(":SOURce1:BURSt:TRIGger:NCYCles") {
+            self.burst_cycles.to_string()
+        } else if is(":TRIGger1:BURSt:SOURce") {
+            self.trigger_source.clone()
+        } else if is(":OUTPut1:STATe") {
+            on_off(self.output)
+        } else {
+            return None;
+        };
+        Some(reply)
+    }
+}
+
+/// Matches the long or short form of a keyword and returns the short form.
+fn token<'a>(value: &str, shorts: &[&'a str]) -> std::result::Result<&'a str, &'static str> {
+    shorts
+        .iter()
+        .find(|short| value.starts_with(*short))
+        .copied()
+        .ok_or(ILLEGAL_PARAMETER)
+}
+
+fn state(value: &str) -> std::result::Result<bool, &'static str> {
+    match value {
+        "1" | "ON" => Ok(true),
+        "0" | "OFF" => Ok(false),
+        _ => Err(ILLEGAL_PARAMETER),
+    }
+}
+
+fn in_range(value: &str, min: f64, max: f64) -> std::result::Result<f64, &'static str> {
+    let value = value.parse::<f64>().map_err(|_| ILLEGAL_PARAMETER)?;
+    if value.is_finite() && value >= min && value <= max {
+        Ok(value)
+    } else {
+        Err(OUT_OF_RANGE)
+    }
+}
+
+/// SCPI transport standing in for a WF1946B on a simulated bench.
+pub struct SimulatedWf1946b {
+    bench: Arc<SimBench>,
+}
+
+impl SimulatedWf1946b {
+    pub fn new(bench: Arc<SimBench>) -> Self {
+        Self { bench }
+    }
+}
+
+impl ScpiTransport for SimulatedWf1946b {
+    fn write_line(&mut self, command: &str) -> Result<()> {
+        let (header, argument) = command
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((command.trim(), ""));
+        if header.eq_ignore_ascii_case("*TRG") {
+            let from_bus = self.bench.lock().generator.trigger_source == "BUS";
+            if from_bus {
+                self.bench.fire_trigger();
+            }
+        } else {
+            self.bench.lock().generator.set(header, argument);
+        }
+        Ok(())
+    }
+
+    fn query_line(&mut self, command: &str) -> Result<String> {
+        let command = command.trim();
+        let reply = command
+            .strip_suffix('?')
+            .and_then(|header| self.bench.lock().generator.query(header));
+        reply.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "simulated WF1946B does not answer {}",
+                    command.to_ascii_uppercase()
+                ),
+            )
+            .into()
+        })
+    }
+}
@@ -0,0 +1,120 @@
+//! In-process simulated bench for hardware-free runs.
+//!
+//! A bench pairs a DHO5108 emulation, served over loopback TCP so the real
+//! driver and its binary WORD transfer are exercised, with a WF1946B
+//! transport whose `*TRG` completes the armed single acquisition. Instruments
+//! opened with the same bench name share one trigger, and the bench outlives
+//! individual connections so a shot can be armed, fired and fetched through
+//! separate handlers.
+
+mod generator;
+mod scope;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+pub use generator::SimulatedWf1946b;
+pub use scope::open_dho5108;
+
+pub const SIM_IDN_SERIAL: &str = "SIM0000000";
+
+/// Supplies the voltages the simulated scope records for each shot.
+pub trait WaveformSource: Send {
+    /// Voltages of `channel` at the sample times of `time` for `shot`.
+    fn channel(&mut self, shot: u64, channel: u8, time: &[f64]) -> Vec<f64>;
+}
+
+pub struct SimBench {
+    name: String,
+    state: Mutex<BenchState>,
+}
+
+struct BenchState {
+    scope: scope::ScopeState,
+    generator: generator::GeneratorState,
+    shot: u64,
+    source: Option<Box<dyn WaveformSource>>,
+}
+
+impl SimBench {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            state: Mutex::new(BenchState {
+                scope: scope::ScopeState::default(),
+                generator: generator::GeneratorState::default(),
+                shot: 0,
+                source: None,
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Replaces the waveform model; channels read as 0 V until one is set.
+    pub fn set_waveform_source(&self, source: Box<dyn WaveformSource>) {
+        self.lock().source = Some(source);
+    }
+
+    /// Number of acquisitions completed by a generator trigger.
+    pub fn shot(&self) -> u64 {
+        self.lock().shot
+    }
+
+    /// Delivers a trigger edge to the scope; only an armed single acquisition
+    /// captures it.
+    pub fn fire_trigger(&self) -> bool {
+        let mut state = self.lock();
+        if state.scope.complete_single() {
+            state.shot += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BenchState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Returns the process-wide bench called `name`, creating it on first use.
+pub fn bench(name: &str) -> Arc<SimBench> {
+    static BENCHES: OnceLock<Mutex<HashMap<String, Arc<SimBench>>>> = OnceLock::new();
+    let mut benches = BENCHES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    benches
+        .entry(name.to_string())
+        .or_insert_with(|| Arc::new(SimBench::new(name)))
+        .clone()
+}
+
+/// Matches a command header against a SCPI pattern such as
+/// `:WAVeform:SOURce`, accepting the long or the short (upper-case) form of
+/// each node. A numeric suffix of 1 in the pattern may be omitted.
+fn header_matches(pattern: &str, header: &str) -> bool {
+    let pattern_nodes = pattern.trim_start_matches(':').split(':');
+    let header_nodes = header.trim_start_matches(':').split(':');
+    pattern_nodes.clone().count() == header_nodes.clone().count()
+        && pattern_nodes.zip(header_nodes).all(|(pattern, node)| {
+            let long = pattern.to_ascii_uppercase();
+            let short = pattern
+                .chars()
+                .filter(|character| !character.is_ascii_lowercase())
+                .collect::<String>();
+            let node = node.to_ascii_uppercase();
+            [long, short]
+                .into_iter()
+                .any(|form| form == node || form.strip_suffix('1').is_some_and(|form| form == node))
+        })
+}
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
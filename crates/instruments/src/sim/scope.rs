@@ -0,0 +1,428 @@
+use super::{BenchState, SIM_IDN_SERIAL, SimBench, header_matches};
+use crate::rigol::DHO5108;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const CHANNELS: usize = 8;
+const HORIZONTAL_DIVISIONS: f64 = 10.0;
+/// Codes per vertical division of the 16-bit WORD record (8 divisions).
+const CODES_PER_DIVISION: f64 = 8192.0;
+const Y_REFERENCE: f64 = 32768.0;
+
+/// 1x1 PNG returned for `:DISPlay:DATA?`.
+const DISPLAY_PNG: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+    0x89, 0x00, 0x00, 0x00, 0x0a, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0x00, 0x01, 0x00, 0x00,
+    0x05, 0x00, 0x01, 0x0d, 0x0a, 0x2d, 0xb4, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae,
+    0x42, 0x60, 0x82,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Run,
+    Wait,
+    Stop,
+}
+
+#[derive(Debug, Clone)]
+struct ChannelState {
+    scale: f64,
+    offset: f64,
+    coupling: String,
+    bandwidth_limit: String,
+}
+
+impl Default for ChannelState {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            offset: 0.0,
+            coupling: "DC".to_string(),
+            bandwidth_limit: "OFF".to_string(),
+        }
+    }
+}
+
+/// Front-panel state of the emulated DHO5108. It powers up stopped with a
+/// record of shot 0 so a fetch works before the first trigger.
+pub(super) struct ScopeState {
+    status: Status,
+    memory_depth: usize,
+    timebase_scale: f64,
+    timebase_offset: f64,
+    trigger_source: String,
+    trigger_level: f64,
+    trigger_slope: String,
+    channels: [ChannelState; CHANNELS],
+    waveform_source: u8,
+    waveform_mode: String,
+    waveform_format: String,
+    waveform_points: usize,
+}
+
+impl Default for ScopeState {
+    fn default() -> Self {
+        Self {
+            status: Status::Stop,
+            memory_depth: 100_000,
+            timebase_scale: 5.0e-3,
+            timebase_offset: 20.0e-3,
+            trigger_source: "EXT".to_string(),
+            trigger_level: 1.0,
+            trigger_slope: "POS".to_string(),
+            channels: Default::default(),
+            waveform_source: 1,
+            waveform_mode: "NORM".to_string(),
+            waveform_format: "BYTE".to_string(),
+            waveform_points: 1_000,
+        }
+    }
+}
+
+impl ScopeState {
+    /// Finishes an armed single acquisition; returns whether one was armed.
+    pub(super) fn complete_single(&mut self) -> bool {
+        if self.status == Status::Wait {
+            self.status = Status::Stop;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn x_increment(&self) -> f64 {
+        HORIZONTAL_DIVISIONS * self.timebase_scale / self.memory_depth as f64
+    }
+
+    fn x_origin(&self) -> f64 {
+        self.timebase_offset - HORIZONTAL_DIVISIONS / 2.0 * self.timebase_scale
+    }
+
+    fn points(&self) -> usize {
+        self.waveform_points.clamp(1, self.memory_depth)
+    }
+
+    fn channel(&self, ch: u8) -> Option<&ChannelState> {
+        self.channels.get(usize::from(ch).checked_sub(1)?)
+    }
+
+    fn channel_mut(&mut self, ch: u8) -> Option<&mut ChannelState> {
+        self.channels.get_mut(usize::from(ch).checked_sub(1)?)
+    }
+
+    /// `(y_increment, y_origin, y_reference)` of `ch` in the Rigol convention.
+    fn y_scaling(&self, ch: u8) -> (f64, f64, f64) {
+        let channel = self.channel(ch).cloned().unwrap_or_default();
+        let y_increment = channel.scale / CODES_PER_DIVISION;
+        (y_increment, channel.offset / y_increment, Y_REFERENCE)
+    }
+
+    fn preamble(&self) -> String {
+        let (y_increment, y_origin, y_reference) = self.y_scaling(self.waveform_source);
+        let format = match self.waveform_format.as_str() {
+            "WORD" => 1,
+            "ASC" => 2,
+            _ => 0,
+        };
+        let mode = match self.waveform_mode.as_str() {
+            "MAX" => 1,
+            "RAW" => 2,
+            _ => 0,
+        };
+        format!(
+            "{format},{mode},{},1,{:E},{:E},0,{y_increment:E},{y_origin:E},{y_reference}",
+            self.points(),
+            self.x_increment(),
+            self.x_origin(),
+        )
+    }
+}
+
+/// Starts a DHO5108 emulation on a loopback port and connects the real
+/// driver to it. The server thread ends when the driver is dropped.
+pub fn open_dho5108(bench: Arc<SimBench>, io_timeout: Option<Duration>) -> io::Result<DHO5108> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let port = listener.local_addr()?.port();
+    thread::Builder::new()
+        .name(format!("sim-dho5108-{}", bench.name()))
+        .spawn(move || {
+            if let Ok((stream, _)) = listener.accept() {
+                let _ = serve(stream, &bench);
+            }
+        })?;
+    DHO5108::open_with_timeouts("127.0.0.1", port, io_timeout, io_timeout)
+}
+
+enum Reply {
+    Line(String),
+    Block(Vec<u8>),
+}
+
+fn serve(stream: TcpStream, bench: &SimBench) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let reply = handle(&mut bench.lock(), line.trim());
+        match reply {
+            Some(Reply::Line(text)) => writer.write_all(format!("{text}\n").as_bytes())?,
+            Some(Reply::Block(data)) => {
+                writer.write_all(format!("#9{:09}", data.len()).as_bytes())?;
+                writer.write_all(&data)?;
+                writer.write_all(b"\n")?;
+            }
+            None => continue,
+        }
+        writer.flush()?;
+    }
+}
+
+fn handle(state: &mut BenchState, command: &str) -> Option<Reply> {
+    if command.is_empty() {
+        return None;
+    }
+    let (header, argument) = command
+        .split_once(char::is_whitespace)
+        .map_or((command, ""), |(header, argument)| {
+            (header, argument.trim())
+        });
+    match header.strip_suffix('?') {
+        Some(header) => Some(query(state, header, argument)),
+        None => {
+            set(&mut state.scope, header, argument);
+            None
+        }
+    }
+}
+
+fn query(state: &mut BenchState, header: &str, argument: &str) -> Reply {
+    let scope = &state.scope;
+    if let Some((ch, setting)) = channel_header(header) {
+        let Some(channel) = scope.channel(ch) else {
+            return Reply::Line(format!("ERROR: no channel {ch}"));
+        };
+        return Reply::Line(match setting {
+            "SCALE" => format!("{:E}", channel.scale),
+            "OFFSET" => format!("{:E}", channel.offset),
+            "COUPLING" => channel.coupling.clone(),
+            "BWLIMIT" => channel.bandwidth_limit.clone(),
+            _ => format!("ERROR: unsupported query {header}?"),
+        });
+    }
+    let is = |pattern| header_matches(pattern, header);
+    let (y_increment, y_origin, y_reference) = scope.y_scaling(scope.waveform_source);
+    let text = if is("*IDN") {
+        format!("RIGOL TECHNOLOGIES,DHO5108,{SIM_IDN_SERIAL},00.01.00.SIM")
+    } else if is("*OPC") {
+        "1".to_string()
+    } else if is(":TRIGger:STATus") {
+        match scope.status {
+            Status::Run => "RUN",
+            Status::Wait => "WAIT",
+            Status::Stop => "STOP",
+        }
+        .to_string()
+    } else if is(":TIMebase:MAIN:SCALe") || is(":TIMebase:SCALe") {
+        format!("{:E}", scope.timebase_scale)
+    } else if is(":TIMebase:MAIN:OFFSet") || is(":TIMebase:OFFSet") {
+        format!("{:E}", scope.timebase_offset)
+    } else if is(":ACQuire:MDEPth") {
+        scope.memory_depth.to_string()
+    } else if is(":TRIGger:MODE") {
+        "EDGE".to_string()
+    } else if is(":TRIGger:EDGE:SOURce") {
+        scope.trigger_source.clone()
+    } else if is(":TRIGger:EDGE:LEVel") {
+        format!("{:E}", scope.trigger_level)
+    } else if is(":TRIGger:EDGE:SLOPe") {
+        scope.trigger_slope.clone()
+    } else if is(":WAVeform:SOURce") {
+        format!("CHAN{}", scope.waveform_source)
+    } else if is(":WAVeform:MODE") {
+        scope.waveform_mode.clone()
+    } else if is(":WAVeform:FORMat") {
+        scope.waveform_format.clone()
+    } else if is(":WAVeform:POINts") {
+        scope.points().to_string()
+    } else if is(":WAVeform:PREamble") {
+        scope.preamble()
+    } else if is(":WAVeform:XINCrement") {
+        format!("{:E}", scope.x_increment())
+    } else if is(":WAVeform:XORigin") {
+        format!("{:E}", scope.x_origin())
+    } else if is(":WAVeform:XREFerence") {
+        "0".to_string()
+    } else if is(":WAVeform:YINCrement") {
+        format!("{y_increment:E}")
+    } else if is(":WAVeform:YORigin") {
+        format!("{y_origin:E}")
+    } else if is(":WAVeform:YREFerence") {
+        format!("{y_reference}")
+    } else if is(":WAVeform:DATA") {
+        return Reply::Block(waveform_words(state));
+    } else if is(":DISPlay:DATA") && argument.eq_ignore_ascii_case("PNG") {
+        return Reply::Block(DISPLAY_PNG.to_vec());
+    } else {
+        format!("ERROR: unsupported query {header}?")
+    };
+    Reply::Line(text)
+}
+
+/// Applies a setting; malformed or unknown commands are ignored, as the
+/// instrument would only queue an error for them.
+fn set(scope: &mut ScopeState, header: &str, argument: &str) {
+    let value = argument.to_ascii_uppercase();
+    if let Some((ch, setting)) = channel_header(header) {
+        let Some(channel) = scope.channel_mut(ch) else {
+            return;
+        };
+        match setting {
+            "SCALE" => set_positive(&mut channel.scale, argument),
+            "OFFSET" => set_finite(&mut channel.offset, argument),
+            "COUPLING" if matches!(value.as_str(), "DC" | "AC" | "GND") => channel.coupling = value,
+            "BWLIMIT" if matches!(value.as_str(), "OFF" | "20M" | "100M" | "250M") => {
+                channel.bandwidth_limit = value;
+            }
+            _ => {}
+        }
+        return;
+    }
+    let is = |pattern| header_matches(pattern, header);
+    if (is(":TRIGger:SWEep") && matches!(value.as_str(), "SING" | "SINGLE")) || is(":SINGle") {
+        scope.status = Status::Wait;
+    } else if is(":RUN") {
+        scope.status = Status::Run;
+    } else if is(":STOP") {
+        scope.status = Status::Stop;
+    } else if is(":TIMebase:MAIN:SCALe") || is(":TIMebase:SCALe") {
+        set_positive(&mut scope.timebase_scale, argument);
+    } else if is(":TIMebase:MAIN:OFFSet") || is(":TIMebase:OFFSet") {
+        set_finite(&mut scope.timebase_offset, argument);
+    } else if is(":ACQuire:MDEPth") {
+        if let Some(depth) = parse_memory_depth_token(&value) {
+            scope.memory_depth = depth;
+        }
+    } else if is(":TRIGger:EDGE:SOURce") {
+        scope.trigger_source = match value.as_str() {
+            "EXTERNAL" => "EXT".to_string(),
+            "ACLINE" => "ACL".to_string(),
+            _ => value.replace("CHANNEL", "CHAN"),
+        };
+    } else if is(":TRIGger:EDGE:LEVel") {
+        set_finite(&mut scope.trigger_level, argument);
+    } else if is(":TRIGger:EDGE:SLOPe") {
+        scope.trigger_slope = match value.as_str() {
+            "POSITIVE" => "POS".to_string(),
+            "NEGATIVE" => "NEG".to_string(),
+            "RFALL" => "RFAL".to_string(),
+            _ => value,
+        };
+    } else if is(":WAVeform:SOURce") {
+        if let Some(ch) = channel_number(&value).filter(|ch| scope.channel(*ch).is_some()) {
+            scope.waveform_source = ch;
+        }
+    } else if is(":WAVeform:MODE") {
+        scope.waveform_mode = value
+            .chars()
+            .take(4)
+            .collect::<String>()
+            .replace("MAXI", "MAX");
+    } else if is(":WAVeform:FORMat") {
+        scope.waveform_format = value
+            .chars()
+            .take(4)
+            .collect::<String>()
+            .replace("ASCI", "ASC");
+    } else if is(":WAVeform:POINts")
+        && let Ok(points) = argument.parse::<usize>()
+    {
+        scope.waveform_points = points;
+    }
+}
+
+/// The selected channel of the current shot as unsigned little-endian
+/// words; this emulation always transfers WORD data.
+fn waveform_words(state: &mut BenchState) -> Vec<u8> {
+    let scope = &state.scope;
+    let ch = scope.waveform_source;
+    let (x_increment, x_origin) = (scope.x_increment(), scope.x_origin());
+    let time = (0..scope.points())
+        .map(|index| x_origin + index as f64 * x_increment)
+        .collect::<Vec<_>>();
+    let (y_increment, y_origin, y_reference) = scope.y_scaling(ch);
+    let mut volts = match state.source.as_mut() {
+        Some(source) => source.channel(state.shot, ch, &time),
+        None => Vec::new(),
+    };
+    volts.resize(time.len(), 0.0);
+    volts
+        .iter()
+        .flat_map(|volts| {
+            let code = (volts / y_increment + y_origin + y_reference)
+                .round()
+                .clamp(0.0, f64::from(u16::MAX));
+            (code as u16).to_le_bytes()
+        })
+        .collect()
+}
+
+/// Splits `:CHANnel<n>:<setting>` into the channel and the long setting name.
+fn channel_header(header: &str) -> Option<(u8, &'static str)> {
+    let header = header.trim_start_matches(':');
+    let (node, setting) = header.split_once(':')?;
+    let ch = channel_number(&node.to_ascii_uppercase())?;
+    ["SCALe", "OFFSet", "COUPling", "BWLimit"]
+        .into_iter()
+        .find(|pattern| header_matches(pattern, setting))
+        .map(|pattern| {
+            let setting: &'static str = match pattern {
+                "SCALe" => "SCALE",
+                "OFFSet" => "OFFSET",
+                "COUPling" => "COUPLING",
+                _ => "BWLIMIT",
+            };
+            (ch, setting)
+        })
+}
+
+fn channel_number(node: &str) -> Option<u8> {
+    let digits = node
+        .strip_prefix("CHANNEL")
+        .or_else(|| node.strip_prefix("CHAN"))?;
+    digits.parse().ok()
+}
+
+fn parse_memory_depth_token(token: &str) -> Option<usize> {
+    let (number, multiplier) = match token.as_bytes().last()? {
+        b'K' => (&token[..token.len() - 1], 1_000),
+        b'M' => (&token[..token.len() - 1], 1_000_000),
+        _ => (token, 1),
+    };
+    let depth = number.parse::<usize>().ok()?.checked_mul(multiplier)?;
+    (depth > 0).then_some(depth)
+}
+
+fn set_finite(target: &mut f64, argument: &str) {
+    if let Ok(value) = argument.parse::<f64>()
+        && value.is_finite()
+    {
+        *target = value;
+    }
+}
+
+fn set_positive(target: &mut f64, argument: &str) {
+    if let Ok(value) = argument.parse::<f64>()
+        && value.is_finite()
+        && value > 0.0
+    {
+        *target = value;
+    }
+}
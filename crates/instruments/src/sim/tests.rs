@@ -0,0 +1,111 @@
+use super::*;
+use crate::oscilloscope::{Oscilloscope, TriggerStatus};
+use crate::transport::ScpiTransport;
+use std::time::Duration;
+
+struct Ramp;
+
+impl WaveformSource for Ramp {
+    fn channel(&mut self, shot: u64, channel: u8, time: &[f64]) -> Vec<f64> {
+        time.iter()
+            .map(|_| f64::from(channel) * 0.25 + shot as f64)
+            .collect()
+    }
+}
+
+#[test]
+fn header_matching_accepts_long_short_and_implied_suffix_forms() {
+    assert!(header_matches(":WAVeform:SOURce", "WAV:SOUR"));
+    assert!(header_matches(":WAVeform:SOURce", ":waveform:source"));
+    assert!(header_matches(":TRIGger1:BURSt:SOURce", ":TRIG:BURS:SOUR"));
+    assert!(!header_matches(":WAVeform:SOURce", "WAV:SOURC"));
+    assert!(!header_matches(":STOP", "WAV:STOP"));
+}
+
+#[test]
+fn generator_trigger_completes_the_armed_single_shot() {
+    let bench = bench("tests-generator-trigger");
+    bench.set_waveform_source(Box::new(Ramp));
+    let mut scope = open_dho5108(bench.clone(), Some(Duration::from_secs(5))).unwrap();
+    let mut generator = SimulatedWf1946b::new(bench.clone());
+
+    assert!(scope.identify().unwrap().contains("DHO5108"));
+    assert_eq!(scope.query_trigger_status().unwrap(), TriggerStatus::Stop);
+    scope.set_single().unwrap();
+    assert_eq!(scope.query_trigger_status().unwrap(), TriggerStatus::Wait);
+    generator.write_line("*TRG").unwrap();
+    assert_eq!(scope.query_trigger_status().unwrap(), TriggerStatus::Stop);
+    assert_eq!(bench.shot(), 1);
+
+    let depth = scope.query_memory_depth().unwrap();
+    let volts = scope.fetch(2, depth).unwrap();
+    assert_eq!(volts.len(), depth);
+    assert!(volts.iter().all(|value| (value - 1.5).abs() < 1e-3));
+}
+
+#[test]
+fn bus_trigger_is_ignored_once_the_generator_uses_an_external_source() {
+    let bench = bench("tests-external-source");
+    let mut scope = open_dho5108(bench.clone(), Some(Duration::from_secs(5))).unwrap();
+    let mut generator = SimulatedWf1946b::new(bench.clone());
+
+    generator
+        .write_line(":TRIGger1:BURSt:SOURce EXTernal")
+        .unwrap();
+    scope.set_single().unwrap();
+    generator.write_line("*TRG").unwrap();
+
+    assert_eq!(scope.query_trigger_status().unwrap(), TriggerStatus::Wait);
+    assert_eq!(bench.shot(), 0);
+}
+
+#[test]
+fn acquisition_setup_reads_back_from_the_emulated_scope() {
+    let bench = bench("tests-setup");
+    let mut scope = open_dho5108(bench, Some(Duration::from_secs(5))).unwrap();
+    let setup = crate::oscilloscope::AcquisitionSetup {
+        timebase_scale: Some(2.0e-3),
+        memory_depth: Some(10_000),
+        channels: vec![crate::oscilloscope::ChannelSetup {
+            channel: 3,
+            scale: Some(0.5),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    scope.apply_acquisition_setup(&setup).unwrap();
+    let actual = scope.query_acquisition_setup(&[3]).unwrap();
+
+    assert_eq!(actual.timebase_scale, Some(2.0e-3));
+    assert_eq!(actual.memory_depth, Some(10_000));
+    assert_eq!(actual.channels[0].scale, Some(0.5));
+    assert!(scope.capture_display_png().unwrap().starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn generator_setup_reads_back_and_queues_rejected_values() {
+    let bench = bench("tests-generator-setup");
+    let mut generator = crate::nf::WF1946B::new(Box::new(SimulatedWf1946b::new(bench.clone())));
+    let setup = crate::nf::WfOutputSetup {
+        waveform: Some(crate::nf::WfWaveform::Square),
+        frequency_hz: Some(2.0e3),
+        amplitude_vpp: Some(1.5),
+        offset_v: Some(0.0),
+        burst_cycles: Some(4),
+        trigger_source: Some(crate::nf::WfTriggerSource::Bus),
+        output: Some(true),
+    };
+
+    generator.apply_output_setup(&setup).unwrap();
+    assert_eq!(generator.query_output_setup().unwrap(), setup);
+
+    let error = generator
+        .apply_output_setup(&crate::nf::WfOutputSetup {
+            amplitude_vpp: Some(50.0),
+            ..Default::default()
+        })
+        .unwrap_err();
+    assert!(error.to_string().contains("Data out of range"), "{error}");
+    assert_eq!(generator.query_output_setup().unwrap(), setup);
+}
@@ -2,6 +2,14 @@ use crate::Result;
 use std::fmt;
 use std::time::Duration;
 
+mod tcp;
+mod usbtmc;
+mod vxi11;
+
+pub use tcp::TcpTransport;
+pub use usbtmc::{USBTMC_DRIVER_TIMEOUT_MS, UsbtmcDevice, UsbtmcTransport};
+pub use vxi11::{DEFAULT_VXI11_DEVICE, Vxi11Transport};
+
 const PROLOGIX_HOST_TIMEOUT_MARGIN_MS: u64 = 250;
 
 pub trait ScpiTransport {
@@ -19,12 +27,34 @@ pub trait ScpiTransport {
     fn controller_version(&mut self) -> Result<Option<String>> {
         Ok(None)
     }
+
+    /// Re-addresses a controller that reaches several GPIB instruments.
+    fn set_gpib_address(&mut self, _address: u8) -> Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "this transport cannot change the GPIB address",
+        )
+        .into())
+    }
 }
 
 pub type BoxedScpiTransport = Box<dyn ScpiTransport + Send>;
 
 #[derive(Debug, Clone)]
 pub enum ScpiConnection {
+    Tcp {
+        host: String,
+        port: u16,
+        timeout_ms: u64,
+    },
+    Vxi11 {
+        host: String,
+        device: String,
+        timeout_ms: u64,
+    },
+    UsbtmcDevice {
+        path: String,
+    },
     Gpib {
         board: i32,
         address: i32,
@@ -48,6 +78,9 @@ pub enum ScpiConnection {
 impl fmt::Display for ScpiConnection {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::Tcp { host, port, .. } => write!(f, "TCP/IP {host}:{port}"),
+            Self::Vxi11 { host, device, .. } => write!(f, "VXI-11 {host}, device {device}"),
+            Self::UsbtmcDevice { path } => write!(f, "USBTMC device {path}"),
             Self::Gpib { board, address, .. } => write!(f, "GPIB board {board}, address {address}"),
             Self::PrologixTcp {
                 host,
@@ -64,6 +97,25 @@ impl fmt::Display for ScpiConnection {
 
 pub fn open_scpi_transport(connection: &ScpiConnection) -> Result<BoxedScpiTransport> {
     match connection {
+        ScpiConnection::Tcp {
+            host,
+            port,
+            timeout_ms,
+        } => Ok(Box::new(TcpTransport::connect(
+            host,
+            *port,
+            Duration::from_millis(*timeout_ms),
+        )?)),
+        ScpiConnection::Vxi11 {
+            host,
+            device,
+            timeout_ms,
+        } => Ok(Box::new(Vxi11Transport::connect(
+            host,
+            device,
+            Duration::from_millis(*timeout_ms),
+        )?)),
+        ScpiConnection::UsbtmcDevice { path } => Ok(Box::new(UsbtmcTransport::open(path)?)),
         ScpiConnection::Gpib {
             board,
             address,
@@ -173,6 +225,52 @@ fn open_prologix_serial_transport(
     .into())
 }
 
+/// Lists the primary addresses that answer on GPIB board `board`.
+#[cfg(feature = "gpib")]
+pub fn scan_gpib_board(board: i32, timeout_secs: u64) -> Result<Vec<i32>> {
+    Ok(gpib_rs::scan_board(&format!("gpib{board}"), timeout_secs)?)
+}
+
+#[cfg(not(feature = "gpib"))]
+pub fn scan_gpib_board(_board: i32, _timeout_secs: u64) -> Result<Vec<i32>> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "this instruments build does not include GPIB support",
+    )
+    .into())
+}
+
+/// Lists the NI-VISA `INSTR` resources known to the resource manager.
+#[cfg(all(target_os = "windows", feature = "gpib"))]
+pub fn find_visa_resources() -> Result<Vec<String>> {
+    Ok(gpib_rs::find_resources("?*::INSTR")?)
+}
+
+#[cfg(not(all(target_os = "windows", feature = "gpib")))]
+pub fn find_visa_resources() -> Result<Vec<String>> {
+    Err(visa_unsupported())
+}
+
+#[cfg(all(target_os = "windows", feature = "gpib"))]
+pub fn open_visa_transport(resource: &str, timeout: Duration) -> Result<BoxedScpiTransport> {
+    let instrument = gpib_rs::Instrument::open_resource(resource, Some(timeout))?;
+    Ok(Box::new(GpibTransport::from_instrument(instrument)))
+}
+
+#[cfg(not(all(target_os = "windows", feature = "gpib")))]
+pub fn open_visa_transport(_resource: &str, _timeout: Duration) -> Result<BoxedScpiTransport> {
+    Err(visa_unsupported())
+}
+
+#[cfg(not(all(target_os = "windows", feature = "gpib")))]
+fn visa_unsupported() -> crate::InstrumentError {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "VISA resources require NI-VISA on Windows and GPIB support",
+    )
+    .into()
+}
+
 #[cfg(feature = "gpib")]
 pub struct GpibTransport {
     instrument: gpib_rs::Instrument,
@@ -257,6 +355,10 @@ where
     fn controller_version(&mut self) -> Result<Option<String>> {
         Ok(Some(prologix_rs::Prologix::controller_version(self)?))
     }
+
+    fn set_gpib_address(&mut self, address: u8) -> Result<()> {
+        Ok(prologix_rs::Prologix::set_address(self, address)?)
+    }
 }
 
 pub fn prologix_host_io_timeout(read_timeout_ms: u16) -> Duration {
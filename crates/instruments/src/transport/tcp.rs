@@ -0,0 +1,90 @@
+use crate::Result;
+use crate::transport::ScpiTransport;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Blank lines tolerated before a query response, e.g. a stray `\r\n`
+/// left over from a previous reply.
+const MAX_BLANK_RESPONSE_LINES: usize = 4;
+
+/// Line-oriented SCPI over a raw socket, usually port 5025.
+pub struct TcpTransport {
+    reader: BufReader<TcpStream>,
+    endpoint: String,
+}
+
+impl TcpTransport {
+    pub fn connect(host: &str, port: u16, timeout: Duration) -> Result<Self> {
+        let stream = connect_stream(host, port, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            reader: BufReader::new(stream),
+            endpoint: format!("{host}:{port}"),
+        })
+    }
+}
+
+/// Connects to the first address `host` resolves to that accepts within
+/// `timeout`.
+pub(crate) fn connect_stream(host: &str, port: u16, timeout: Duration) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for address in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(error) => last_error = Some(error),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no socket address resolved for {host}:{port}"),
+        )
+    }))
+}
+
+/// Reads one response line without its terminator, skipping blank lines.
+pub(crate) fn read_response_line<R: BufRead>(reader: &mut R, endpoint: &str) -> io::Result<String> {
+    let mut response = String::new();
+    for _ in 0..MAX_BLANK_RESPONSE_LINES {
+        response.clear();
+        if reader.read_line(&mut response)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("connection closed before {endpoint} returned a response"),
+            ));
+        }
+        let line = response.trim_end_matches(['\r', '\n']);
+        if !line.is_empty() {
+            return Ok(line.to_string());
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("received only blank response lines from {endpoint}"),
+    ))
+}
+
+impl ScpiTransport for TcpTransport {
+    fn write_line(&mut self, command: &str) -> Result<()> {
+        let stream = self.reader.get_mut();
+        writeln!(stream, "{command}")?;
+        stream.flush()?;
+        Ok(())
+    }
+
+    fn query_line(&mut self, command: &str) -> Result<String> {
+        self.write_line(command)?;
+        Ok(read_response_line(&mut self.reader, &self.endpoint)?)
+    }
+
+    fn set_timeout_secs(&mut self, secs: u64) -> Result<()> {
+        let timeout = Some(Duration::from_secs(secs.max(1)));
+        let stream = self.reader.get_ref();
+        stream.set_read_timeout(timeout)?;
+        stream.set_write_timeout(timeout)?;
+        Ok(())
+    }
+}
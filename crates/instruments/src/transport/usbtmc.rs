@@ -0,0 +1,132 @@
+//! USBTMC through the Linux `usbtmc` kernel driver's character device.
+//!
+//! Each `write` sends one complete device-dependent message and each `read`
+//! returns data from the pending response, so the device is buffered per
+//! response and never read ahead of a query. Transfer timeouts are the
+//! driver's own (5 s per transfer by default).
+
+use crate::Result;
+use crate::transport::ScpiTransport;
+use crate::transport::tcp::read_response_line;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, Read, Write};
+use std::path::Path;
+
+/// Default per-transfer timeout of the Linux `usbtmc` driver.
+pub const USBTMC_DRIVER_TIMEOUT_MS: u64 = 5_000;
+
+/// Opened `/dev/usbtmc*` character device.
+pub struct UsbtmcDevice {
+    file: File,
+    path: String,
+}
+
+impl UsbtmcDevice {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|error| {
+                io::Error::new(
+                    error.kind(),
+                    format!("failed to open USBTMC device {}: {error}", path.display()),
+                )
+            })?;
+        Ok(Self {
+            file,
+            path: path.display().to_string(),
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Read for UsbtmcDevice {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+}
+
+impl Write for UsbtmcDevice {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Line-oriented SCPI over a [`UsbtmcDevice`].
+pub struct UsbtmcTransport {
+    reader: BufReader<UsbtmcDevice>,
+}
+
+impl UsbtmcTransport {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(UsbtmcDevice::open(path)?),
+        })
+    }
+}
+
+impl ScpiTransport for UsbtmcTransport {
+    fn write_line(&mut self, command: &str) -> Result<()> {
+        // One write per message: the driver frames each call as a USBTMC
+        // transfer with EOM set.
+        let device = self.reader.get_mut();
+        device.write_all(format!("{command}\n").as_bytes())?;
+        device.flush()?;
+        Ok(())
+    }
+
+    fn query_line(&mut self, command: &str) -> Result<String> {
+        self.write_line(command)?;
+        let endpoint = self.reader.get_ref().path().to_string();
+        Ok(read_response_line(&mut self.reader, &endpoint)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A fake device file: the command written at offset 0 overwrites the
+    /// placeholder, and the following read returns the canned response.
+    fn fake_device(name: &str, command: &str, response: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("pmoke-usbtmc-{name}-{}", std::process::id()));
+        let mut contents = vec![b' '; command.len() + 1];
+        contents.extend_from_slice(response);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn usbtmc_query_writes_one_message_and_reads_the_response_line() {
+        let path = fake_device("query", "*IDN?", b"FAKE,USBTMC,0,1\n");
+
+        let mut transport = UsbtmcTransport::open(&path).unwrap();
+        let response = transport.query_line("*IDN?").unwrap();
+        drop(transport);
+
+        let written = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(response, "FAKE,USBTMC,0,1");
+        assert!(written.starts_with(b"*IDN?\n"));
+    }
+
+    #[test]
+    fn missing_usbtmc_device_reports_its_path() {
+        let error = UsbtmcDevice::open("/dev/usbtmc-does-not-exist")
+            .err()
+            .unwrap();
+
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().contains("/dev/usbtmc-does-not-exist"));
+    }
+}
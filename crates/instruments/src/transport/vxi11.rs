@@ -0,0 +1,524 @@
+//! VXI-11 core channel: ONC-RPC over TCP, located through the portmapper.
+
+use crate::Result;
+use crate::transport::ScpiTransport;
+use crate::transport::tcp::connect_stream;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+pub const DEFAULT_VXI11_DEVICE: &str = "inst0";
+
+const PORTMAPPER_PORT: u16 = 111;
+const PORTMAPPER_PROGRAM: u32 = 100_000;
+const PORTMAPPER_VERSION: u32 = 2;
+const PORTMAPPER_GETPORT: u32 = 3;
+const IPPROTO_TCP: u32 = 6;
+
+const CORE_PROGRAM: u32 = 0x0006_07AF;
+const CORE_VERSION: u32 = 1;
+const CREATE_LINK: u32 = 10;
+const DEVICE_WRITE: u32 = 11;
+const DEVICE_READ: u32 = 12;
+const DEVICE_CLEAR: u32 = 15;
+const DESTROY_LINK: u32 = 23;
+
+const FLAG_END: u32 = 0x08;
+const REASON_CHR: u32 = 0x02;
+const REASON_END: u32 = 0x04;
+const DEVICE_ERROR_IO_TIMEOUT: u32 = 15;
+const LAST_FRAGMENT: u32 = 0x8000_0000;
+const READ_REQUEST_SIZE: u32 = 0x0010_0000;
+/// Largest RPC record accepted, well above a full `device_read` reply, so a
+/// garbled or non-VXI-11 peer cannot make the client allocate gigabytes.
+const MAX_RECORD_SIZE: usize = 4 * READ_REQUEST_SIZE as usize;
+/// Extra host socket time so the instrument reports its own I/O timeout
+/// before the connection gives up.
+const HOST_TIMEOUT_MARGIN: Duration = Duration::from_secs(1);
+
+/// SCPI over a VXI-11 link to `device`, usually `inst0`.
+pub struct Vxi11Transport {
+    rpc: RpcClient,
+    link: u32,
+    max_write_size: usize,
+    io_timeout_ms: u32,
+}
+
+impl Vxi11Transport {
+    pub fn connect(host: &str, device: &str, timeout: Duration) -> Result<Self> {
+        Self::connect_via_portmapper(host, PORTMAPPER_PORT, device, timeout)
+    }
+
+    fn connect_via_portmapper(
+        host: &str,
+        portmapper_port: u16,
+        device: &str,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let mut portmapper = RpcClient::connect(host, portmapper_port, timeout)?;
+        let mut args = XdrWriter::default();
+        args.u32(CORE_PROGRAM)
+            .u32(CORE_VERSION)
+            .u32(IPPROTO_TCP)
+            .u32(0);
+        let reply = portmapper.call(
+            PORTMAPPER_PROGRAM,
+            PORTMAPPER_VERSION,
+            PORTMAPPER_GETPORT,
+            &args,
+        )?;
+        let port = XdrReader::new(&reply).u32()?;
+        let port = u16::try_from(port)
+            .ok()
+            .filter(|port| *port != 0)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{host} does not register a VXI-11 core channel"),
+                )
+            })?;
+        Self::connect_core(host, port, device, timeout)
+    }
+
+    fn connect_core(host: &str, port: u16, device: &str, timeout: Duration) -> Result<Self> {
+        let mut rpc = RpcClient::connect(host, port, timeout)?;
+        let io_timeout_ms = timeout_ms(timeout);
+        let mut args = XdrWriter::default();
+        args.u32(std::process::id())
+            .u32(0)
+            .u32(io_timeout_ms)
+            .opaque(device.as_bytes());
+        let reply = rpc.call(CORE_PROGRAM, CORE_VERSION, CREATE_LINK, &args)?;
+        let mut reply = XdrReader::new(&reply);
+        check_device_error(reply.u32()?, "create_link")?;
+        let link = reply.u32()?;
+        let _abort_port = reply.u32()?;
+        let max_write_size = reply.u32()?.max(1) as usize;
+        Ok(Self {
+            rpc,
+            link,
+            max_write_size,
+            io_timeout_ms,
+        })
+    }
+
+    fn read_response(&mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        loop {
+            let mut args = XdrWriter::default();
+            args.u32(self.link)
+                .u32(READ_REQUEST_SIZE)
+                .u32(self.io_timeout_ms)
+                .u32(0)
+                .u32(0)
+                .u32(0);
+            let reply = self
+                .rpc
+                .call(CORE_PROGRAM, CORE_VERSION, DEVICE_READ, &args)?;
+            let mut reply = XdrReader::new(&reply);
+            check_device_error(reply.u32()?, "device_read")?;
+            let reason = reply.u32()?;
+            data.extend_from_slice(reply.opaque()?);
+            if reason & (REASON_END | REASON_CHR) != 0 {
+                return Ok(data);
+            }
+        }
+    }
+}
+
+impl ScpiTransport for Vxi11Transport {
+    fn write_line(&mut self, command: &str) -> Result<()> {
+        let message = format!("{command}\n");
+        let chunks = message.as_bytes().chunks(self.max_write_size);
+        let count = chunks.len();
+        for (index, chunk) in chunks.enumerate() {
+            let flags = if index + 1 == count { FLAG_END } else { 0 };
+            let mut args = XdrWriter::default();
+            args.u32(self.link)
+                .u32(self.io_timeout_ms)
+                .u32(0)
+                .u32(flags)
+                .opaque(chunk);
+            let reply = self
+                .rpc
+                .call(CORE_PROGRAM, CORE_VERSION, DEVICE_WRITE, &args)?;
+            check_device_error(XdrReader::new(&reply).u32()?, "device_write")?;
+        }
+        Ok(())
+    }
+
+    fn query_line(&mut self, command: &str) -> Result<String> {
+        self.write_line(command)?;
+        let response = self.read_response()?;
+        Ok(String::from_utf8_lossy(&response)
+            .trim_end_matches(['\r', '\n'])
+            .to_string())
+    }
+
+    fn set_timeout_secs(&mut self, secs: u64) -> Result<()> {
+        let timeout = Duration::from_secs(secs.max(1));
+        self.io_timeout_ms = timeout_ms(timeout);
+        self.rpc.set_timeout(timeout)?;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        let mut args = XdrWriter::default();
+        args.u32(self.link).u32(0).u32(0).u32(self.io_timeout_ms);
+        let reply = self
+            .rpc
+            .call(CORE_PROGRAM, CORE_VERSION, DEVICE_CLEAR, &args)?;
+        check_device_error(XdrReader::new(&reply).u32()?, "device_clear")?;
+        Ok(())
+    }
+}
+
+impl Drop for Vxi11Transport {
+    fn drop(&mut self) {
+        let mut args = XdrWriter::default();
+        args.u32(self.link);
+        let _ = self
+            .rpc
+            .call(CORE_PROGRAM, CORE_VERSION, DESTROY_LINK, &args);
+    }
+}
+
+fn timeout_ms(timeout: Duration) -> u32 {
+    u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX)
+}
+
+fn check_device_error(code: u32, operation: &str) -> io::Result<()> {
+    let (kind, message) = match code {
+        0 => return Ok(()),
+        DEVICE_ERROR_IO_TIMEOUT => (io::ErrorKind::TimedOut, "I/O timeout"),
+        1 => (io::ErrorKind::InvalidInput, "syntax error"),
+        3 => (io::ErrorKind::NotFound, "device not accessible"),
+        4 => (io::ErrorKind::InvalidInput, "invalid link identifier"),
+        5 => (io::ErrorKind::InvalidInput, "parameter error"),
+        8 => (io::ErrorKind::Unsupported, "operation not supported"),
+        9 => (io::ErrorKind::OutOfMemory, "out of resources"),
+        11 => (io::ErrorKind::ResourceBusy, "device locked by another link"),
+        17 => (io::ErrorKind::Other, "I/O error"),
+        21 => (io::ErrorKind::InvalidInput, "invalid address"),
+        23 => (io::ErrorKind::Interrupted, "abort"),
+        _ => (io::ErrorKind::Other, "device error"),
+    };
+    Err(io::Error::new(
+        kind,
+        format!("VXI-11 {operation} failed: {message} (error {code})"),
+    ))
+}
+
+/// ONC-RPC client over a record-marked TCP stream.
+struct RpcClient {
+    stream: TcpStream,
+    xid: u32,
+}
+
+impl RpcClient {
+    fn connect(host: &str, port: u16, timeout: Duration) -> io::Result<Self> {
+        let stream = connect_stream(host, port, timeout)?;
+        stream.set_nodelay(true)?;
+        let mut client = Self { stream, xid: 0 };
+        client.set_timeout(timeout)?;
+        Ok(client)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> io::Result<()> {
+        let timeout = Some(timeout + HOST_TIMEOUT_MARGIN);
+        self.stream.set_read_timeout(timeout)?;
+        self.stream.set_write_timeout(timeout)
+    }
+
+    fn call(
+        &mut self,
+        program: u32,
+        version: u32,
+        procedure: u32,
+        args: &XdrWriter,
+    ) -> io::Result<Vec<u8>> {
+        self.xid = self.xid.wrapping_add(1);
+        let mut message = XdrWriter::default();
+        message
+            .u32(self.xid)
+            .u32(0)
+            .u32(2)
+            .u32(program)
+            .u32(version)
+            .u32(procedure)
+            .u32(0)
+            .u32(0)
+            .u32(0)
+            .u32(0);
+        message.0.extend_from_slice(&args.0);
+        let header = LAST_FRAGMENT | message.0.len() as u32;
+        self.stream.write_all(&header.to_be_bytes())?;
+        self.stream.write_all(&message.0)?;
+        self.stream.flush()?;
+
+        loop {
+            let reply = self.read_record()?;
+            let mut reader = XdrReader::new(&reply);
+            if reader.u32()? != self.xid {
+                continue;
+            }
+            if reader.u32()? != 1 || reader.u32()? != 0 {
+                return Err(invalid_data("RPC call was rejected"));
+            }
+            let _verifier_flavor = reader.u32()?;
+            reader.opaque()?;
+            let accept_status = reader.u32()?;
+            if accept_status != 0 {
+                return Err(invalid_data(&format!(
+                    "RPC call failed with accept status {accept_status}"
+                )));
+            }
+            return Ok(reader.rest().to_vec());
+        }
+    }
+
+    fn read_record(&mut self) -> io::Result<Vec<u8>> {
+        let mut record = Vec::new();
+        loop {
+            let mut header = [0; 4];
+            self.stream.read_exact(&mut header)?;
+            let header = u32::from_be_bytes(header);
+            let start = record.len();
+            let end = start + (header & !LAST_FRAGMENT) as usize;
+            if end > MAX_RECORD_SIZE {
+                return Err(invalid_data(&format!(
+                    "RPC record exceeds {MAX_RECORD_SIZE} bytes; the peer is not a VXI-11 instrument"
+                )));
+            }
+            record.resize(end, 0);
+            self.stream.read_exact(&mut record[start..])?;
+            if header & LAST_FRAGMENT != 0 {
+                return Ok(record);
+            }
+        }
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[derive(Default)]
+struct XdrWriter(Vec<u8>);
+
+impl XdrWriter {
+    fn u32(&mut self, value: u32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    fn opaque(&mut self, bytes: &[u8]) -> &mut Self {
+        self.u32(bytes.len() as u32);
+        self.0.extend_from_slice(bytes);
+        self.0.resize(self.0.len().next_multiple_of(4), 0);
+        self
+    }
+}
+
+struct XdrReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> XdrReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(invalid_data("truncated RPC reply"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn opaque(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        let data = self.take(len)?;
+        self.take(len.next_multiple_of(4) - len)?;
+        Ok(data)
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves one portmapper GETPORT and then a core channel that answers
+    /// `*IDN?` in two `device_read` replies.
+    fn serve_fake_instrument() -> (u16, thread::JoinHandle<Vec<u32>>) {
+        let portmapper = TcpListener::bind("127.0.0.1:0").unwrap();
+        let core = TcpListener::bind("127.0.0.1:0").unwrap();
+        let portmapper_port = portmapper.local_addr().unwrap().port();
+        let core_port = core.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = portmapper.accept().unwrap();
+            let (xid, procedure, _) = read_call(&mut stream);
+            assert_eq!(procedure, PORTMAPPER_GETPORT);
+            reply(
+                &mut stream,
+                xid,
+                XdrWriter::default().u32(u32::from(core_port)),
+            );
+
+            let (mut stream, _) = core.accept().unwrap();
+            let mut procedures = Vec::new();
+            loop {
+                let mut header = [0; 4];
+                if stream.peek(&mut header).unwrap_or(0) == 0 {
+                    return procedures;
+                }
+                let (xid, procedure, args) = read_call(&mut stream);
+                procedures.push(procedure);
+                let mut result = XdrWriter::default();
+                match procedure {
+                    CREATE_LINK => {
+                        let mut args = XdrReader::new(&args);
+                        args.take(12).unwrap();
+                        assert_eq!(args.opaque().unwrap(), b"inst0");
+                        result.u32(0).u32(7).u32(0).u32(4);
+                    }
+                    DEVICE_WRITE => {
+                        let mut args = XdrReader::new(&args);
+                        assert_eq!(args.u32().unwrap(), 7);
+                        args.take(8).unwrap();
+                        let flags = args.u32().unwrap();
+                        let data = args.opaque().unwrap();
+                        assert!(data.len() <= 4);
+                        assert_eq!(flags & FLAG_END != 0, data.ends_with(b"\n"));
+                        result.u32(0).u32(data.len() as u32);
+                    }
+                    DEVICE_READ
+                        if procedures.iter().filter(|p| **p == DEVICE_READ).count() == 1 =>
+                    {
+                        result.u32(0).u32(0).opaque(b"FAKE,VXI");
+                    }
+                    DEVICE_READ => {
+                        result.u32(0).u32(REASON_END).opaque(b"11,0,1\n");
+                    }
+                    DEVICE_CLEAR => {
+                        result.u32(DEVICE_ERROR_IO_TIMEOUT);
+                    }
+                    _ => {
+                        result.u32(0);
+                    }
+                }
+                reply(&mut stream, xid, &mut result);
+            }
+        });
+        (portmapper_port, server)
+    }
+
+    fn read_call(stream: &mut TcpStream) -> (u32, u32, Vec<u8>) {
+        let mut header = [0; 4];
+        stream.read_exact(&mut header).unwrap();
+        let mut body = vec![0; (u32::from_be_bytes(header) & !LAST_FRAGMENT) as usize];
+        stream.read_exact(&mut body).unwrap();
+        let mut reader = XdrReader::new(&body);
+        let xid = reader.u32().unwrap();
+        reader.take(16).unwrap();
+        let procedure = reader.u32().unwrap();
+        reader.take(16).unwrap();
+        (xid, procedure, reader.rest().to_vec())
+    }
+
+    fn reply(stream: &mut TcpStream, xid: u32, result: &mut XdrWriter) {
+        let mut message = XdrWriter::default();
+        message.u32(xid).u32(1).u32(0).u32(0).u32(0).u32(0);
+        message.0.extend_from_slice(&result.0);
+        stream
+            .write_all(&(LAST_FRAGMENT | message.0.len() as u32).to_be_bytes())
+            .unwrap();
+        stream.write_all(&message.0).unwrap();
+    }
+
+    #[test]
+    fn vxi11_query_chunks_writes_and_joins_reads_until_end() {
+        let (portmapper_port, server) = serve_fake_instrument();
+        let mut transport = Vxi11Transport::connect_via_portmapper(
+            "127.0.0.1",
+            portmapper_port,
+            DEFAULT_VXI11_DEVICE,
+            Duration::from_secs(2),
+        )
+        .unwrap();
+
+        let response = transport.query_line("*IDN?").unwrap();
+        let error = transport.clear().unwrap_err();
+        drop(transport);
+
+        assert_eq!(response, "FAKE,VXI11,0,1");
+        assert!(error.is_timeout(), "{error}");
+        assert_eq!(
+            server.join().unwrap(),
+            vec![
+                CREATE_LINK,
+                DEVICE_WRITE,
+                DEVICE_WRITE,
+                DEVICE_READ,
+                DEVICE_READ,
+                DEVICE_CLEAR,
+                DESTROY_LINK
+            ]
+        );
+    }
+
+    #[test]
+    fn oversized_rpc_records_are_rejected_before_allocation() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (_, procedure, _) = read_call(&mut stream);
+            assert_eq!(procedure, PORTMAPPER_GETPORT);
+            stream.write_all(&0x7FFF_FFF0_u32.to_be_bytes()).unwrap();
+        });
+
+        let error = match Vxi11Transport::connect_via_portmapper(
+            "127.0.0.1",
+            port,
+            DEFAULT_VXI11_DEVICE,
+            Duration::from_secs(2),
+        ) {
+            Ok(_) => panic!("an oversized record must be rejected"),
+            Err(error) => error,
+        };
+        server.join().unwrap();
+
+        #[allow(irrefutable_let_patterns)]
+        let crate::InstrumentError::Io(error) = error else {
+            panic!("expected an I/O error, got {error}");
+        };
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("exceeds"), "{error}");
+    }
+
+    #[test]
+    fn xdr_opaque_round_trips_with_padding() {
+        let mut writer = XdrWriter::default();
+        writer.opaque(b"abcde").u32(9);
+        assert_eq!(writer.0.len(), 4 + 8 + 4);
+
+        let mut reader = XdrReader::new(&writer.0);
+        assert_eq!(reader.opaque().unwrap(), b"abcde");
+        assert_eq!(reader.u32().unwrap(), 9);
+        assert!(reader.u32().is_err());
+    }
+}
@@ -0,0 +1,545 @@
+use crate::{AnalysisError, Result};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{LN_2, PI, TAU};
+
+pub const MAX_RC_CASCADE_ORDER: usize = 8;
+pub const MAX_FILTER_TAPS: usize = 1 << 24;
+
+const GAUSSIAN_SUPPORT_SIGMAS: f64 = 4.0;
+const RC_SETTLING_TOLERANCE: f64 = 1.0e-9;
+const ENBW_SEARCH_ITERATIONS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LowPassKind {
+    RcCascade,
+    WindowedSinc,
+    Gaussian,
+}
+
+impl LowPassKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RcCascade => "rc_cascade",
+            Self::WindowedSinc => "windowed_sinc",
+            Self::Gaussian => "gaussian",
+        }
+    }
+}
+
+/// Low-pass filter applied to the mixed-down signal.
+///
+/// `cutoff_hz` is the per-stage -3 dB corner for `rc_cascade`, the -6 dB
+/// point of the sinc for `windowed_sinc`, and the -3 dB point of `gaussian`. `half_window_s` sets the one-sided
+/// support of `windowed_sinc` and is ignored otherwise; `order` is the
+/// number of RC stages and is ignored by the FIR kinds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LowPassSettings {
+    pub kind: LowPassKind,
+    pub cutoff_hz: f64,
+    pub order: usize,
+    pub half_window_s: f64,
+    pub zero_phase: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LowPassFilter {
+    settings: LowPassSettings,
+    sample_interval_s: f64,
+    taps: Vec<f64>,
+    alpha: f64,
+    edge_samples: usize,
+}
+
+impl LowPassFilter {
+    pub fn new(settings: LowPassSettings, sample_interval_s: f64) -> Result<Self> {
+        if !sample_interval_s.is_finite() || sample_interval_s <= 0.0 {
+            return Err(AnalysisError::new(
+                "invalid_parameter",
+                "sample_interval_s must be finite and positive",
+            ));
+        }
+        let nyquist_hz = 0.5 / sample_interval_s;
+        if !settings.cutoff_hz.is_finite()
+            || settings.cutoff_hz <= 0.0
+            || settings.cutoff_hz >= nyquist_hz
+        {
+            return Err(AnalysisError::new(
+                "invalid_cutoff",
+                format!(
+                    "cutoff_hz must be positive and below the Nyquist frequency {nyquist_hz} Hz (got {})",
+                    settings.cutoff_hz
+                ),
+            ));
+        }
+        match settings.kind {
+            LowPassKind::RcCascade => {
+                if !(1..=MAX_RC_CASCADE_ORDER).contains(&settings.order) {
+                    return Err(AnalysisError::new(
+                        "invalid_parameter",
+                        format!(
+                            "rc_cascade order must be between 1 and {MAX_RC_CASCADE_ORDER} (got {})",
+                            settings.order
+                        ),
+                    ));
+                }
+                let alpha = rc_alpha(settings.cutoff_hz, sample_interval_s);
+                let settling = rc_settling_samples(settings.order, alpha)?;
+                Ok(Self {
+                    settings,
+                    sample_interval_s,
+                    taps: Vec::new(),
+                    alpha,
+                    edge_samples: settling,
+                })
+            }
+            LowPassKind::WindowedSinc => {
+                if !settings.half_window_s.is_finite() || settings.half_window_s < sample_interval_s
+                {
+                    return Err(AnalysisError::new(
+                        "window_too_short",
+                        "windowed_sinc half-window must be finite and at least one sample interval",
+                    ));
+                }
+                let half_taps = half_tap_count(settings.half_window_s / sample_interval_s)?;
+                let taps = windowed_sinc_taps(half_taps, settings.cutoff_hz * sample_interval_s);
+                Ok(Self::fir(settings, sample_interval_s, taps, half_taps))
+            }
+            LowPassKind::Gaussian => {
+                let sigma_samples = gaussian_sigma_s(settings.cutoff_hz) / sample_interval_s;
+                let half_taps = half_tap_count(GAUSSIAN_SUPPORT_SIGMAS * sigma_samples)?.max(1);
+                let taps = gaussian_taps(half_taps, sigma_samples);
+                Ok(Self::fir(settings, sample_interval_s, taps, half_taps))
+            }
+        }
+    }
+
+    fn fir(
+        settings: LowPassSettings,
+        sample_interval_s: f64,
+        taps: Vec<f64>,
+        half_taps: usize,
+    ) -> Self {
+        // A causal FIR looks back over its whole support instead of half of it.
+        let edge_samples = if settings.zero_phase {
+            half_taps
+        } else {
+            2 * half_taps
+        };
+        Self {
+            settings,
+            sample_interval_s,
+            taps,
+            alpha: f64::NAN,
+            edge_samples,
+        }
+    }
+
+    pub fn settings(&self) -> LowPassSettings {
+        self.settings
+    }
+
+    /// Samples the filter needs on each side of an output before it is
+    /// free of edge transients.
+    pub fn edge_samples(&self) -> usize {
+        self.edge_samples
+    }
+
+    pub fn taps(&self) -> &[f64] {
+        &self.taps
+    }
+
+    pub fn enbw_hz(&self) -> f64 {
+        let sample_rate_hz = 1.0 / self.sample_interval_s;
+        match self.settings.kind {
+            LowPassKind::RcCascade => {
+                sample_rate_hz * rc_cascade_energy(self.effective_rc_order(), self.alpha)
+            }
+            LowPassKind::WindowedSinc | LowPassKind::Gaussian => {
+                let sum = self.taps.iter().sum::<f64>();
+                let sum_of_squares = self.taps.iter().map(|tap| tap * tap).sum::<f64>();
+                sample_rate_hz * sum_of_squares / (sum * sum)
+            }
+        }
+    }
+
+    pub fn response_abs(&self, frequency_hz: f64) -> f64 {
+        let omega = TAU * frequency_hz * self.sample_interval_s;
+        match self.settings.kind {
+            LowPassKind::RcCascade => {
+                let beta = 1.0 - self.alpha;
+                let denominator = (1.0 - beta * omega.cos()).hypot(beta * omega.sin());
+                (self.alpha / denominator).powi(self.effective_rc_order() as i32)
+            }
+            LowPassKind::WindowedSinc | LowPassKind::Gaussian => {
+                let (re, im) =
+                    self.taps
+                        .iter()
+                        .enumerate()
+                        .fold((0.0, 0.0), |(re, im), (index, tap)| {
+                            let (sin, cos) = (omega * index as f64).sin_cos();
+                            (re + tap * cos, im - tap * sin)
+                        });
+                re.hypot(im)
+            }
+        }
+    }
+
+    /// Filters `mixed` in place; the RC stages run forward and, for a
+    /// zero-phase filter, backward again.
+    pub(crate) fn apply_recursive(&self, mixed: &mut [(f64, f64)]) {
+        debug_assert_eq!(self.settings.kind, LowPassKind::RcCascade);
+        for _ in 0..self.settings.order {
+            rc_stage(mixed.iter_mut(), self.alpha);
+        }
+        if self.settings.zero_phase {
+            for _ in 0..self.settings.order {
+                rc_stage(mixed.iter_mut().rev(), self.alpha);
+            }
+        }
+    }
+
+    /// Convolves the taps around `center`; a causal filter ends at `center`.
+    pub(crate) fn apply_fir_at(&self, mixed: &[(f64, f64)], center: usize) -> (f64, f64) {
+        let last = self.taps.len() - 1;
+        let first = if self.settings.zero_phase {
+            center - last / 2
+        } else {
+            center - last
+        };
+        self.taps
+            .iter()
+            .rev()
+            .zip(&mixed[first..=first + last])
+            .fold((0.0, 0.0), |(re, im), (tap, value)| {
+                (re + tap * value.0, im + tap * value.1)
+            })
+    }
+
+    fn effective_rc_order(&self) -> usize {
+        if self.settings.zero_phase {
+            2 * self.settings.order
+        } else {
+            self.settings.order
+        }
+    }
+}
+
+/// Returns the cutoff that gives `settings` the requested ENBW; the
+/// `cutoff_hz` already in `settings` is ignored.
+pub fn lowpass_cutoff_for_enbw(
+    settings: LowPassSettings,
+    sample_interval_s: f64,
+    enbw_hz: f64,
+) -> Result<f64> {
+    if !enbw_hz.is_finite() || enbw_hz <= 0.0 {
+        return Err(AnalysisError::new(
+            "invalid_parameter",
+            format!("enbw_hz must be finite and positive (got {enbw_hz})"),
+        ));
+    }
+    if settings.kind == LowPassKind::Gaussian {
+        // Two-sided ENBW of a Gaussian is 1 / (2 sqrt(pi) sigma).
+        return Ok(enbw_hz * (LN_2 / PI).sqrt());
+    }
+    let nyquist_hz = 0.5 / sample_interval_s;
+    if settings.kind == LowPassKind::RcCascade {
+        // Validates the order; the settling length plays no part in the ENBW.
+        LowPassFilter::new(
+            LowPassSettings {
+                cutoff_hz: 0.5 * nyquist_hz,
+                ..settings
+            },
+            sample_interval_s,
+        )?;
+    }
+    let enbw_at = |cutoff_hz: f64| match settings.kind {
+        LowPassKind::RcCascade => {
+            let order = if settings.zero_phase {
+                2 * settings.order
+            } else {
+                settings.order
+            };
+            Ok(
+                rc_cascade_energy(order, rc_alpha(cutoff_hz, sample_interval_s))
+                    / sample_interval_s,
+            )
+        }
+        _ => LowPassFilter::new(
+            LowPassSettings {
+                cutoff_hz,
+                ..settings
+            },
+            sample_interval_s,
+        )
+        .map(|filter| filter.enbw_hz()),
+    };
+    let mut low = nyquist_hz * 1.0e-12;
+    let mut high = nyquist_hz * (1.0 - 1.0e-9);
+    if enbw_at(high)? < enbw_hz || enbw_at(low)? > enbw_hz {
+        return Err(AnalysisError::new(
+            "unreachable_enbw",
+            format!(
+                "{} cannot reach an ENBW of {enbw_hz} Hz at this sample rate",
+                settings.kind.as_str()
+            ),
+        ));
+    }
+    for _ in 0..ENBW_SEARCH_ITERATIONS {
+        let middle = (low * high).sqrt();
+        if enbw_at(middle)? < enbw_hz {
+            low = middle;
+        } else {
+            high = middle;
+        }
+        if high / low - 1.0 < 1.0e-12 {
+            break;
+        }
+    }
+    Ok((low * high).sqrt())
+}
+
+fn half_tap_count(half_window_samples: f64) -> Result<usize> {
+    let half_taps = half_window_samples.floor();
+    if !half_taps.is_finite() || half_taps * 2.0 + 1.0 > MAX_FILTER_TAPS as f64 {
+        return Err(AnalysisError::new(
+            "filter_too_long",
+            format!("filter support exceeds {MAX_FILTER_TAPS} taps; raise the cutoff"),
+        ));
+    }
+    Ok(half_taps as usize)
+}
+
+fn windowed_sinc_taps(half_taps: usize, normalized_cutoff: f64) -> Vec<f64> {
+    let length = 2 * half_taps + 1;
+    let span = (length - 1) as f64;
+    let mut taps = (0..length)
+        .map(|index| {
+            let offset = index as f64 - half_taps as f64;
+            let argument = 2.0 * normalized_cutoff * offset;
+            let sinc = if offset == 0.0 {
+                1.0
+            } else {
+                (PI * argument).sin() / (PI * argument)
+            };
+            let phase = TAU * index as f64 / span;
+            let blackman = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+            sinc * blackman
+        })
+        .collect::<Vec<_>>();
+    normalize(&mut taps);
+    taps
+}
+
+fn gaussian_sigma_s(cutoff_hz: f64) -> f64 {
+    // |H(f)| = exp(-2 pi^2 sigma^2 f^2) falls to 1/sqrt(2) at the cutoff.
+    LN_2.sqrt() / (TAU * cutoff_hz)
+}
+
+fn gaussian_taps(half_taps: usize, sigma_samples: f64) -> Vec<f64> {
+    let mut taps = (0..=2 * half_taps)
+        .map(|index| {
+            let offset = index as f64 - half_taps as f64;
+            (-0.5 * (offset / sigma_samples).powi(2)).exp()
+        })
+        .collect::<Vec<_>>();
+    normalize(&mut taps);
+    taps
+}
+
+fn normalize(taps: &mut [f64]) {
+    let sum = taps.iter().sum::<f64>();
+    taps.iter_mut().for_each(|tap| *tap /= sum);
+}
+
+fn rc_alpha(cutoff_hz: f64, sample_interval_s: f64) -> f64 {
+    -(-TAU * cutoff_hz * sample_interval_s).exp_m1()
+}
+
+fn rc_stage<'a>(values: impl Iterator<Item = &'a mut (f64, f64)>, alpha: f64) {
+    let mut state = (0.0, 0.0);
+    for value in values {
+        state.0 += alpha * (value.0 - state.0);
+        state.1 += alpha * (value.1 - state.1);
+        *value = state;
+    }
+}
+
+/// Sum of the squared impulse response of `order` identical one-pole stages,
+/// `(a / (2 - a))^n P_{n-1}((1 + b^2) / (a (2 - a)))` with `b = 1 - a`.
+fn rc_cascade_energy(order: usize, alpha: f64) -> f64 {
+    let beta = 1.0 - alpha;
+    let z = (1.0 + beta * beta) / (alpha * (2.0 - alpha));
+    (alpha / (2.0 - alpha)).powi(order as i32) * legendre(order - 1, z)
+}
+
+fn legendre(degree: usize, z: f64) -> f64 {
+    let (mut previous, mut current) = (1.0, z);
+    if degree == 0 {
+        return previous;
+    }
+    for k in 1..degree {
+        let k = k as f64;
+        (previous, current) = (
+            current,
+            ((2.0 * k + 1.0) * z * current - k * previous) / (k + 1.0),
+        );
+    }
+    current
+}
+
+/// Smallest sample count after which the cascade's step response is within
+/// `RC_SETTLING_TOLERANCE` of its final value.
+fn rc_settling_samples(order: usize, alpha: f64) -> Result<usize> {
+    let residual = |samples: usize| {
+        // Probability of fewer than `order` successes in `samples + order`
+        // Bernoulli(alpha) trials, i.e. the unsettled part of the step.
+        let trials = (samples + order) as f64;
+        let (log_alpha, log_beta) = (alpha.ln(), (-alpha).ln_1p());
+        let mut log_binomial = 0.0;
+        (0..order)
+            .map(|successes| {
+                if successes > 0 {
+                    let successes = successes as f64;
+                    log_binomial += ((trials - successes + 1.0) / successes).ln();
+                }
+                let successes = successes as f64;
+                (log_binomial + successes * log_alpha + (trials - successes) * log_beta).exp()
+            })
+            .sum::<f64>()
+    };
+    let mut high = 1usize;
+    while residual(high) > RC_SETTLING_TOLERANCE {
+        if high > MAX_FILTER_TAPS {
+            return Err(AnalysisError::new(
+                "filter_too_long",
+                format!("rc_cascade settling exceeds {MAX_FILTER_TAPS} samples; raise the cutoff"),
+            ));
+        }
+        high *= 2;
+    }
+    let mut low = high / 2;
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if residual(middle) > RC_SETTLING_TOLERANCE {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Ok(high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 1.0e-5;
+
+    fn settings(kind: LowPassKind, zero_phase: bool) -> LowPassSettings {
+        LowPassSettings {
+            kind,
+            cutoff_hz: 200.0,
+            order: 4,
+            half_window_s: 2.0e-2,
+            zero_phase,
+        }
+    }
+
+    #[test]
+    fn filters_have_unity_dc_gain_and_half_power_at_cutoff() {
+        for (kind, at_cutoff) in [
+            (LowPassKind::WindowedSinc, 0.5),
+            (LowPassKind::Gaussian, 0.5_f64.sqrt()),
+        ] {
+            let filter = LowPassFilter::new(settings(kind, true), DT).unwrap();
+            assert!((filter.taps().iter().sum::<f64>() - 1.0).abs() < 1.0e-12);
+            assert!((filter.response_abs(0.0) - 1.0).abs() < 1.0e-12);
+            assert!(
+                (filter.response_abs(200.0) - at_cutoff).abs() < 1.0e-3,
+                "{kind:?}: {}",
+                filter.response_abs(200.0)
+            );
+        }
+        let mut single = settings(LowPassKind::RcCascade, false);
+        single.order = 1;
+        let filter = LowPassFilter::new(single, DT).unwrap();
+        assert!((filter.response_abs(0.0) - 1.0).abs() < 1.0e-12);
+        assert!((filter.response_abs(200.0) - 0.5_f64.sqrt()).abs() < 1.0e-3);
+    }
+
+    #[test]
+    fn rc_cascade_enbw_matches_impulse_response_energy() {
+        for zero_phase in [false, true] {
+            let filter =
+                LowPassFilter::new(settings(LowPassKind::RcCascade, zero_phase), DT).unwrap();
+            let mut impulse = vec![(0.0, 0.0); 40_000];
+            impulse[20_000].0 = 1.0;
+            filter.apply_recursive(&mut impulse);
+            let energy = impulse.iter().map(|value| value.0 * value.0).sum::<f64>();
+            assert!((filter.enbw_hz() * DT / energy - 1.0).abs() < 1.0e-9);
+        }
+    }
+
+    #[test]
+    fn rc_cascade_settles_within_edge_samples() {
+        let filter = LowPassFilter::new(settings(LowPassKind::RcCascade, false), DT).unwrap();
+        let mut step = vec![(1.0, 0.0); filter.edge_samples() + 1];
+        filter.apply_recursive(&mut step);
+        assert!(1.0 - step[filter.edge_samples()].0 <= RC_SETTLING_TOLERANCE);
+        assert!(1.0 - step[filter.edge_samples() - 1].0 > RC_SETTLING_TOLERANCE);
+    }
+
+    #[test]
+    fn cutoff_search_recovers_requested_enbw() {
+        for kind in [
+            LowPassKind::RcCascade,
+            LowPassKind::WindowedSinc,
+            LowPassKind::Gaussian,
+        ] {
+            let design = settings(kind, true);
+            let cutoff_hz = lowpass_cutoff_for_enbw(design, DT, 150.0).unwrap();
+            let filter = LowPassFilter::new(
+                LowPassSettings {
+                    cutoff_hz,
+                    ..design
+                },
+                DT,
+            )
+            .unwrap();
+            assert!(
+                (filter.enbw_hz() / 150.0 - 1.0).abs() < 1.0e-3,
+                "{kind:?}: {}",
+                filter.enbw_hz()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_designs() {
+        let mut above_nyquist = settings(LowPassKind::Gaussian, true);
+        above_nyquist.cutoff_hz = 60_000.0;
+        assert_eq!(
+            LowPassFilter::new(above_nyquist, DT).unwrap_err().code(),
+            "invalid_cutoff"
+        );
+        let mut no_stages = settings(LowPassKind::RcCascade, true);
+        no_stages.order = 0;
+        assert_eq!(
+            LowPassFilter::new(no_stages, DT).unwrap_err().code(),
+            "invalid_parameter"
+        );
+        let mut short_window = settings(LowPassKind::WindowedSinc, true);
+        short_window.half_window_s = 0.5 * DT;
+        assert_eq!(
+            LowPassFilter::new(short_window, DT).unwrap_err().code(),
+            "window_too_short"
+        );
+        let mut tiny_cutoff = settings(LowPassKind::Gaussian, true);
+        tiny_cutoff.cutoff_hz = 1.0e-6;
+        assert_eq!(
+            LowPassFilter::new(tiny_cutoff, DT).unwrap_err().code(),
+            "filter_too_long"
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use crate::error::{AnalysisError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// A secondary lobe at least this fraction of the main peak marks a bipolar pulse.
+pub const BIPOLAR_LOBE_RATIO: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldSweep {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldPolarity {
+    Positive,
+    Negative,
+}
+
+/// Contiguous run of samples over which the field moves in one direction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldBranch {
+    pub sweep: FieldSweep,
+    /// Set only for bipolar pulses, where each sweep direction occurs once per lobe.
+    pub polarity: Option<FieldPolarity>,
+    pub samples: Range<usize>,
+}
+
+impl FieldBranch {
+    /// File-name suffix such as `up` or `down_neg`.
+    pub fn name(&self) -> &'static str {
+        match (self.sweep, self.polarity) {
+            (FieldSweep::Up, None) => "up",
+            (FieldSweep::Down, None) => "down",
+            (FieldSweep::Up, Some(FieldPolarity::Positive)) => "up_pos",
+            (FieldSweep::Down, Some(FieldPolarity::Positive)) => "down_pos",
+            (FieldSweep::Up, Some(FieldPolarity::Negative)) => "up_neg",
+            (FieldSweep::Down, Some(FieldPolarity::Negative)) => "down_neg",
+        }
+    }
+}
+
+/// Per-bin statistics on a field grid; only bins that received samples are kept.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldBins {
+    pub centers: Vec<f64>,
+    pub counts: Vec<usize>,
+    /// One series per input column, aligned with `centers`.
+    pub means: Vec<Vec<f64>>,
+    /// Population standard deviation within each bin.
+    pub stds: Vec<Vec<f64>>,
+}
+
+/// Splits a pulsed-field trace at its extrema into rising and falling branches.
+///
+/// A unipolar pulse yields two branches split at the peak. A bipolar pulse yields
+/// four, split at the first peak, the zero crossing between the lobes, and the
+/// second peak.
+pub fn split_field_branches(field: &[f64]) -> Result<Vec<FieldBranch>> {
+    if field.len() < 2 {
+        return Err(AnalysisError::new(
+            "short_field_trace",
+            "branch separation requires at least two field samples",
+        ));
+    }
+    if field.iter().any(|value| !value.is_finite()) {
+        return Err(AnalysisError::new(
+            "non_finite_field",
+            "field trace must be finite",
+        ));
+    }
+    let (i_max, max) = extremum(field, |candidate, best| candidate > best);
+    let (i_min, min) = extremum(field, |candidate, best| candidate < best);
+    let positive = max.max(0.0);
+    let negative = (-min).max(0.0);
+    if positive == 0.0 && negative == 0.0 {
+        return Err(AnalysisError::new(
+            "flat_field_trace",
+            "field trace never leaves zero",
+        ));
+    }
+
+    let bipolar = positive.min(negative) >= BIPOLAR_LOBE_RATIO * positive.max(negative);
+    let ranges = if bipolar {
+        let (first, second) = (i_max.min(i_min), i_max.max(i_min));
+        let first_sign = field[first].signum();
+        let crossing = (first + 1..=second)
+            .find(|&index| field[index] * first_sign <= 0.0)
+            .unwrap_or(second);
+        vec![
+            0..first + 1,
+            first + 1..crossing,
+            crossing..second + 1,
+            second + 1..field.len(),
+        ]
+    } else {
+        let peak = if positive >= negative { i_max } else { i_min };
+        vec![0..peak + 1, peak + 1..field.len()]
+    };
+    if let Some(empty) = ranges.iter().position(Range::is_empty) {
+        return Err(AnalysisError::new(
+            "empty_field_branch",
+            format!(
+                "field branch {} of {} is empty; the trace must extend past each field extremum",
+                empty + 1,
+                ranges.len()
+            ),
+        ));
+    }
+
+    let first_positive = if bipolar {
+        i_max < i_min
+    } else {
+        positive >= negative
+    };
+    let (rise, fall) = if first_positive {
+        (FieldSweep::Up, FieldSweep::Down)
+    } else {
+        (FieldSweep::Down, FieldSweep::Up)
+    };
+    let lobes = if !bipolar {
+        [None, None]
+    } else if first_positive {
+        [Some(FieldPolarity::Positive), Some(FieldPolarity::Negative)]
+    } else {
+        [Some(FieldPolarity::Negative), Some(FieldPolarity::Positive)]
+    };
+    Ok(ranges
+        .into_iter()
+        .enumerate()
+        .map(|(index, samples)| FieldBranch {
+            // Away from zero, back to zero; the second lobe reverses both.
+            sweep: if (index % 2 == 0) == (index < 2) {
+                rise
+            } else {
+                fall
+            },
+            polarity: lobes[index / 2],
+            samples,
+        })
+        .collect())
+}
+
+/// Averages `values` onto a grid of bins centred on integer multiples of `width`.
+pub fn bin_by_field(field: &[f64], values: &[&[f64]], width: f64) -> Result<FieldBins> {
+    if !(width.is_finite() && width > 0.0) {
+        return Err(AnalysisError::new(
+            "invalid_bin_width",
+            format!("field bin width must be finite and positive (got {width})"),
+        ));
+    }
+    if values.iter().any(|series| series.len() != field.len()) {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            "binned series must have the same length as the field trace",
+        ));
+    }
+    if field
+        .iter()
+        .chain(values.iter().flat_map(|series| series.iter()))
+        .any(|value| !value.is_finite())
+    {
+        return Err(AnalysisError::new(
+            "non_finite_field",
+            "field trace and binned series must be finite",
+        ));
+    }
+
+    let mut members = BTreeMap::<i64, Vec<usize>>::new();
+    for (index, value) in field.iter().enumerate() {
+        members
+            .entry((value / width).round() as i64)
+            .or_default()
+            .push(index);
+    }
+    let mut bins = FieldBins {
+        centers: Vec::with_capacity(members.len()),
+        counts: Vec::with_capacity(members.len()),
+        means: vec![Vec::with_capacity(members.len()); values.len()],
+        stds: vec![Vec::with_capacity(members.len()); values.len()],
+    };
+    for (bin, indices) in members {
+        let count = indices.len() as f64;
+        bins.centers.push(bin as f64 * width);
+        bins.counts.push(indices.len());
+        for (series, (means, stds)) in values
+            .iter()
+            .zip(bins.means.iter_mut().zip(bins.stds.iter_mut()))
+        {
+            let mean = indices.iter().map(|&index| series[index]).sum::<f64>() / count;
+            let variance = indices
+                .iter()
+                .map(|&index| (series[index] - mean).powi(2))
+                .sum::<f64>()
+                / count;
+            means.push(mean);
+            stds.push(variance.sqrt());
+        }
+    }
+    Ok(bins)
+}
+
+fn extremum(values: &[f64], better: impl Fn(f64, f64) -> bool) -> (usize, f64) {
+    values
+        .iter()
+        .copied()
+        .enumerate()
+        .fold((0, values[0]), |best, (index, value)| {
+            if better(value, best.1) {
+                (index, value)
+            } else {
+                best
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(branches: &[FieldBranch]) -> Vec<&'static str> {
+        branches.iter().map(FieldBranch::name).collect()
+    }
+
+    #[test]
+    fn unipolar_pulses_split_at_the_peak() {
+        let field = [0.0, 1.0, 3.0, 4.0, 2.0, 0.5];
+        let branches = split_field_branches(&field).unwrap();
+        assert_eq!(names(&branches), ["up", "down"]);
+        assert_eq!(branches[0].samples, 0..4);
+        assert_eq!(branches[1].samples, 4..6);
+
+        let negative = field.map(|value| -value);
+        let branches = split_field_branches(&negative).unwrap();
+        assert_eq!(names(&branches), ["down", "up"]);
+
+        // A small undershoot does not make the pulse bipolar.
+        let undershoot = [0.0, 2.0, 4.0, 1.0, -0.5, 0.0];
+        assert_eq!(
+            names(&split_field_branches(&undershoot).unwrap()),
+            ["up", "down"]
+        );
+    }
+
+    #[test]
+    fn bipolar_pulses_split_into_four_branches() {
+        let field = [0.0, 2.0, 4.0, 1.0, -1.0, -3.0, -2.0, 0.0];
+        let branches = split_field_branches(&field).unwrap();
+        assert_eq!(
+            names(&branches),
+            ["up_pos", "down_pos", "down_neg", "up_neg"]
+        );
+        assert_eq!(
+            branches
+                .iter()
+                .map(|branch| branch.samples.clone())
+                .collect::<Vec<_>>(),
+            [0..3, 3..4, 4..6, 6..8]
+        );
+
+        let reversed = field.map(|value| -value);
+        assert_eq!(
+            names(&split_field_branches(&reversed).unwrap()),
+            ["down_neg", "up_neg", "up_pos", "down_pos"]
+        );
+    }
+
+    #[test]
+    fn branch_separation_rejects_degenerate_traces() {
+        let code = |field: &[f64]| split_field_branches(field).unwrap_err().code();
+        assert_eq!(code(&[1.0]), "short_field_trace");
+        assert_eq!(code(&[0.0, f64::NAN]), "non_finite_field");
+        assert_eq!(code(&[0.0, 0.0, 0.0]), "flat_field_trace");
+        assert_eq!(code(&[0.0, 1.0, 2.0]), "empty_field_branch");
+    }
+
+    #[test]
+    fn bins_average_samples_around_grid_points() {
+        let field = [-0.4, 0.1, 0.2, 0.9, 1.1, 1.4];
+        let kerr = [5.0, 1.0, 3.0, 2.0, 4.0, 6.0];
+        let bins = bin_by_field(&field, &[&kerr], 1.0).unwrap();
+        assert_eq!(bins.centers, [0.0, 1.0]);
+        assert_eq!(bins.counts, [3, 3]);
+        assert_eq!(bins.means, [vec![3.0, 4.0]]);
+        let std = (8.0_f64 / 3.0).sqrt();
+        assert!(
+            bins.stds[0]
+                .iter()
+                .all(|value| (value - std).abs() < 1.0e-12)
+        );
+
+        assert_eq!(
+            bin_by_field(&field, &[&kerr], 0.0).unwrap_err().code(),
+            "invalid_bin_width"
+        );
+        assert_eq!(
+            bin_by_field(&field, &[&kerr[..2]], 1.0).unwrap_err().code(),
+            "length_mismatch"
+        );
+    }
+}
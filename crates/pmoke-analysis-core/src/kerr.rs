@@ -44,6 +44,61 @@ pub fn calculate_harmonics_kerr(
         ));
     }
 
+    let modulation_depth = median_modulation_depth(a2, a4, a6)?;
+
+    let values_rad = a2
+        .iter()
+        .zip(a3)
+        .zip(a4)
+        .map(|((&second, &third), &fourth)| {
+            let denominator = (second + fourth) * modulation_depth / 6.0;
+            0.5 * (third / denominator).atan() * factor
+        })
+        .collect::<Vec<_>>();
+    if values_rad.iter().any(|value| !value.is_finite()) {
+        return Err(AnalysisError::new(
+            "non_finite_kerr",
+            "harmonics Kerr calculation produced a non-finite result",
+        ));
+    }
+
+    Ok(HarmonicsKerrOutput {
+        values_rad,
+        representative_modulation_depth: modulation_depth,
+    })
+}
+
+/// Median Bessel argument 2φm estimated per point from the in-phase
+/// harmonics 2, 4, and 6; the same value [`calculate_harmonics_kerr`] reports
+/// as `representative_modulation_depth`.
+pub fn estimate_modulation_depth(a2: &[f64], a4: &[f64], a6: &[f64]) -> Result<f64> {
+    if a2.is_empty() {
+        return Err(AnalysisError::new(
+            "empty_harmonics",
+            "harmonic arrays must not be empty",
+        ));
+    }
+    if a4.len() != a2.len() || a6.len() != a2.len() {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            "harmonic arrays must have equal lengths",
+        ));
+    }
+    if a2
+        .iter()
+        .chain(a4)
+        .chain(a6)
+        .any(|value| !value.is_finite())
+    {
+        return Err(AnalysisError::new(
+            "non_finite_harmonics",
+            "harmonic arrays must be finite",
+        ));
+    }
+    median_modulation_depth(a2, a4, a6)
+}
+
+fn median_modulation_depth(a2: &[f64], a4: &[f64], a6: &[f64]) -> Result<f64> {
     let mut modulation_depths = a2
         .iter()
         .zip(a4)
@@ -63,37 +118,162 @@ pub fn calculate_harmonics_kerr(
     }
     modulation_depths.sort_by(f64::total_cmp);
     let midpoint = modulation_depths.len() / 2;
-    let modulation_depth = if modulation_depths.len().is_multiple_of(2) {
+    Ok(if modulation_depths.len().is_multiple_of(2) {
         0.5 * (modulation_depths[midpoint - 1] + modulation_depths[midpoint])
     } else {
         modulation_depths[midpoint]
-    };
+    })
+}
 
-    let values_rad = a2
+pub fn calculate_standard_kerr(
+    a1: &[f64],
+    a2: &[f64],
+    modulation_depth_rad: f64,
+    factor: f64,
+) -> Result<Vec<f64>> {
+    if a1.is_empty() {
+        return Err(AnalysisError::new(
+            "empty_harmonics",
+            "harmonic arrays must not be empty",
+        ));
+    }
+    if a1.len() != a2.len() {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            "harmonic arrays must have equal lengths",
+        ));
+    }
+    if !factor.is_finite() || a1.iter().chain(a2).any(|value| !value.is_finite()) {
+        return Err(AnalysisError::new(
+            "non_finite_harmonics",
+            "harmonic arrays and factor must be finite",
+        ));
+    }
+    let argument = 2.0 * modulation_depth_rad;
+    let first = bessel_j(1, argument);
+    if !modulation_depth_rad.is_finite() || modulation_depth_rad <= 0.0 || first <= 0.0 {
+        return Err(AnalysisError::new(
+            "invalid_modulation_depth",
+            format!(
+                "modulation depth must be positive and below the first zero of J1(2φm) (got {modulation_depth_rad})"
+            ),
+        ));
+    }
+    let ratio = bessel_j(2, argument) / first;
+
+    // A zero second harmonic reaches the ±π/4 fold boundary; 0/0 stays NaN
+    // as in the SciPy implementation.
+    Ok(a1
         .iter()
-        .zip(a3)
-        .zip(a4)
-        .map(|((&second, &third), &fourth)| {
-            let denominator = (second + fourth) * modulation_depth / 6.0;
-            0.5 * (third / denominator).atan() * factor
+        .zip(a2)
+        .map(|(&first, &second)| 0.5 * (ratio * first / second).atan() * factor)
+        .collect())
+}
+
+/// First-order uncertainty of [`calculate_harmonics_kerr`] for per-point
+/// in-phase noise standard deviations `sigma = [σ2, σ3, σ4]`, with the
+/// modulation depth held at its representative value. NaN noise yields NaN
+/// errors.
+pub fn calculate_harmonics_kerr_error(
+    a2: &[f64],
+    a3: &[f64],
+    a4: &[f64],
+    sigma: [&[f64]; 3],
+    modulation_depth: f64,
+    factor: f64,
+) -> Result<Vec<f64>> {
+    if a2.len() != a3.len() || a2.len() != a4.len() {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            "harmonic arrays must have equal lengths",
+        ));
+    }
+    validate_noise(&sigma, a2.len())?;
+    let [second, third, fourth] = sigma;
+    let scale = modulation_depth / 6.0;
+    Ok((0..a2.len())
+        .map(|index| {
+            // K = f/2·atan(a3/b) with b = (a2 + a4)·m/6.
+            let b = (a2[index] + a4[index]) * scale;
+            let denominator_sigma = scale * second[index].hypot(fourth[index]);
+            let gradient = (b * third[index]).hypot(a3[index] * denominator_sigma);
+            0.5 * factor.abs() * gradient / (a3[index] * a3[index] + b * b)
         })
-        .collect::<Vec<_>>();
-    if values_rad.iter().any(|value| !value.is_finite()) {
+        .collect())
+}
+
+/// First-order uncertainty of [`calculate_standard_kerr`] for per-point
+/// in-phase noise standard deviations `sigma = [σ1, σ2]`. NaN noise yields NaN
+/// errors.
+pub fn calculate_standard_kerr_error(
+    a1: &[f64],
+    a2: &[f64],
+    sigma: [&[f64]; 2],
+    modulation_depth_rad: f64,
+    factor: f64,
+) -> Result<Vec<f64>> {
+    if a1.len() != a2.len() {
         return Err(AnalysisError::new(
-            "non_finite_kerr",
-            "harmonics Kerr calculation produced a non-finite result",
+            "length_mismatch",
+            "harmonic arrays must have equal lengths",
         ));
     }
+    validate_noise(&sigma, a1.len())?;
+    let argument = 2.0 * modulation_depth_rad;
+    let ratio = bessel_j(2, argument) / bessel_j(1, argument);
+    let [first, second] = sigma;
+    Ok(a1
+        .iter()
+        .zip(a2)
+        .zip(first.iter().zip(second))
+        .map(|((&a1, &a2), (&first, &second))| {
+            // K = f/2·atan(r·a1/a2).
+            let gradient = (a2 * first).hypot(a1 * second);
+            0.5 * factor.abs() * ratio.abs() * gradient / (a2 * a2 + ratio * ratio * a1 * a1)
+        })
+        .collect())
+}
 
-    Ok(HarmonicsKerrOutput {
-        values_rad,
-        representative_modulation_depth: modulation_depth,
-    })
+fn validate_noise(sigma: &[&[f64]], len: usize) -> Result<()> {
+    if sigma.iter().any(|sigma| sigma.len() != len) {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            "noise arrays must match the harmonic arrays",
+        ));
+    }
+    if sigma
+        .iter()
+        .copied()
+        .flatten()
+        .any(|value| value.is_infinite() || *value < 0.0)
+    {
+        return Err(AnalysisError::new(
+            "invalid_noise",
+            "noise standard deviations must be non-negative or NaN",
+        ));
+    }
+    Ok(())
+}
+
+fn bessel_j(order: u32, x: f64) -> f64 {
+    let half = 0.5 * x;
+    let mut term = (1..=order).fold(1.0, |value, k| value * half / f64::from(k));
+    let mut sum = term;
+    let square = half * half;
+    for k in 1..=60 {
+        term *= -square / (f64::from(k) * f64::from(k + order));
+        sum += term;
+        if term.abs() <= f64::EPSILON * sum.abs() {
+            break;
+        }
+    }
+    sum
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::f64::consts::FRAC_PI_4;
 
     #[test]
     fn recovers_folded_kerr_angle() {
@@ -118,6 +298,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn modulation_depth_estimate_needs_only_even_harmonics() {
+        let a2 = [0.315_745_306_087_972_3; 4];
+        let a4 = [0.025_139_158_519_404_087; 4];
+        let a6 = [0.000_745_551_998_014_054_3; 4];
+        let depth = estimate_modulation_depth(&a2, &a4, &a6).unwrap();
+        assert!((depth - 1.84).abs() < 2.0e-3, "got {depth}");
+        assert_eq!(
+            estimate_modulation_depth(&a2, &a4[..3], &a6)
+                .unwrap_err()
+                .code(),
+            "length_mismatch"
+        );
+    }
+
     #[test]
     fn rejects_non_finite_and_misaligned_inputs() {
         assert_eq!(
@@ -133,4 +328,149 @@ mod tests {
             "non_finite_harmonics"
         );
     }
+
+    #[test]
+    fn bessel_series_matches_reference_values() {
+        assert!((bessel_j(0, 1.0) - 0.765_197_686_557_966_6).abs() < 1.0e-15);
+        assert!((bessel_j(1, 1.84) - 0.581_864_936_842_083_4).abs() < 1.0e-14);
+        assert!((bessel_j(2, 1.84) - 0.315_745_306_087_972_3).abs() < 1.0e-14);
+        assert!(bessel_j(1, 3.831_705_970_207_512).abs() < 1.0e-13);
+    }
+
+    #[test]
+    fn standard_kerr_recovers_folded_angle() {
+        let theta = 0.01_f64;
+        let depth = 0.92;
+        let a1 = vec![(2.0 * theta).sin() * bessel_j(1, 2.0 * depth); 8];
+        let a2 = vec![(2.0 * theta).cos() * bessel_j(2, 2.0 * depth); 8];
+        let values = calculate_standard_kerr(&a1, &a2, depth, 2.0).unwrap();
+        assert!(
+            values
+                .iter()
+                .all(|value| (value - 2.0 * theta).abs() < 1.0e-12)
+        );
+
+        let negated_a1 = a1.iter().map(|value| -value).collect::<Vec<_>>();
+        let negated_a2 = a2.iter().map(|value| -value).collect::<Vec<_>>();
+        let values = calculate_standard_kerr(&negated_a1, &negated_a2, depth, 1.0).unwrap();
+        assert!(values.iter().all(|value| (value - theta).abs() < 1.0e-12));
+    }
+
+    #[test]
+    fn standard_kerr_reaches_fold_boundary_for_zero_second_harmonic() {
+        let values = calculate_standard_kerr(&[1.0, -1.0], &[0.0, 0.0], 0.92, 1.0).unwrap();
+        assert_eq!(values, [FRAC_PI_4, -FRAC_PI_4]);
+    }
+
+    #[test]
+    fn standard_kerr_rejects_invalid_depth_and_inputs() {
+        for depth in [0.0, -0.3, 1.92, f64::NAN] {
+            assert_eq!(
+                calculate_standard_kerr(&[1.0], &[1.0], depth, 1.0)
+                    .unwrap_err()
+                    .code(),
+                "invalid_modulation_depth"
+            );
+        }
+        assert_eq!(
+            calculate_standard_kerr(&[1.0], &[1.0, 2.0], 0.92, 1.0)
+                .unwrap_err()
+                .code(),
+            "length_mismatch"
+        );
+        assert_eq!(
+            calculate_standard_kerr(&[f64::NAN], &[1.0], 0.92, 1.0)
+                .unwrap_err()
+                .code(),
+            "non_finite_harmonics"
+        );
+    }
+
+    #[test]
+    fn standard_kerr_zero_over_zero_is_nan() {
+        let values = calculate_standard_kerr(&[0.0, 1.0], &[0.0, 1.0], 0.92, 1.0).unwrap();
+        assert!(values[0].is_nan());
+        assert!(values[1].is_finite());
+    }
+
+    fn numeric_error(kerr: impl Fn(&[f64]) -> f64, values: &[f64], sigma: &[f64]) -> f64 {
+        let step = 1.0e-7;
+        sigma
+            .iter()
+            .enumerate()
+            .map(|(index, sigma)| {
+                let mut shifted = values.to_vec();
+                shifted[index] += step;
+                (kerr(&shifted) - kerr(values)) / step * sigma
+            })
+            .map(|term| term * term)
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    #[test]
+    fn kerr_errors_match_numeric_propagation() {
+        let depth = 0.92;
+        let values = [0.004, 0.3];
+        let sigma = [1.0e-4, 3.0e-4];
+        let error = calculate_standard_kerr_error(
+            &[values[0]],
+            &[values[1]],
+            [&sigma[..1], &sigma[1..]],
+            depth,
+            -2.0,
+        )
+        .unwrap()[0];
+        let expected = numeric_error(
+            |v| calculate_standard_kerr(&[v[0]], &[v[1]], depth, -2.0).unwrap()[0],
+            &values,
+            &sigma,
+        );
+        assert!(
+            (error - expected).abs() < 1.0e-6 * expected,
+            "{error} != {expected}"
+        );
+
+        let modulation_depth = 1.84;
+        let values = [0.31, 0.006, 0.025];
+        let sigma = [2.0e-4, 1.0e-4, 5.0e-5];
+        let error = calculate_harmonics_kerr_error(
+            &[values[0]],
+            &[values[1]],
+            &[values[2]],
+            [&sigma[..1], &sigma[1..2], &sigma[2..]],
+            modulation_depth,
+            1.0,
+        )
+        .unwrap()[0];
+        let expected = numeric_error(
+            |v| {
+                let b = (v[0] + v[2]) * modulation_depth / 6.0;
+                0.5 * (v[1] / b).atan()
+            },
+            &values,
+            &sigma,
+        );
+        assert!(
+            (error - expected).abs() < 1.0e-6 * expected,
+            "{error} != {expected}"
+        );
+
+        let unavailable =
+            calculate_standard_kerr_error(&[0.1], &[0.3], [&[f64::NAN], &[1.0e-4]], depth, 1.0)
+                .unwrap();
+        assert!(unavailable[0].is_nan());
+        assert_eq!(
+            calculate_standard_kerr_error(&[0.1], &[0.3], [&[-1.0], &[1.0e-4]], depth, 1.0)
+                .unwrap_err()
+                .code(),
+            "invalid_noise"
+        );
+        assert_eq!(
+            calculate_standard_kerr_error(&[0.1], &[0.3], [&[], &[1.0e-4]], depth, 1.0)
+                .unwrap_err()
+                .code(),
+            "length_mismatch"
+        );
+    }
 }
@@ -1,17 +1,38 @@
 mod error;
+mod filter;
+mod hysteresis;
 mod kerr;
 mod lockin;
 mod phase;
+mod reference;
 mod synthetic;
 
 pub use error::{AnalysisError, Result};
-pub use kerr::{HarmonicsKerrOutput, calculate_harmonics_kerr};
+pub use filter::{
+    LowPassFilter, LowPassKind, LowPassSettings, MAX_FILTER_TAPS, MAX_RC_CASCADE_ORDER,
+    lowpass_cutoff_for_enbw,
+};
+pub use hysteresis::{
+    BIPOLAR_LOBE_RATIO, FieldBins, FieldBranch, FieldPolarity, FieldSweep, bin_by_field,
+    split_field_branches,
+};
+pub use kerr::{
+    HarmonicsKerrOutput, calculate_harmonics_kerr, calculate_harmonics_kerr_error,
+    calculate_standard_kerr, calculate_standard_kerr_error, estimate_modulation_depth,
+};
 pub use lockin::{
-    BoxcarLegacyOutput, BoxcarLegacyPairOutput, BoxcarLegacySettings, FiniteSignal, LockinMetadata,
-    analyze_boxcar_legacy, analyze_boxcar_legacy_pair, analyze_boxcar_legacy_pair_finite,
-    boxcar_response_abs,
+    BoxcarLegacyOutput, BoxcarLegacyPairOutput, BoxcarLegacySettings, BoxcarLegacyStream,
+    FiniteSignal, LockinMetadata, LockinNoise, LowPassLockinSettings, analyze_boxcar_legacy,
+    analyze_boxcar_legacy_pair, analyze_boxcar_legacy_pair_finite, analyze_lowpass_pair_finite,
+    boxcar_response_abs, estimate_lockin_noise,
+};
+pub use phase::{
+    HarmonicPhaseResidual, OMEGA_T0_CONFIDENCE_Z, OmegaT0Estimate, PhaseDriftKnot,
+    estimate_omega_t0, fit_phase_drift, interpolate_phase_drift, rotate_phase, rotate_phase_series,
+};
+pub use reference::{
+    ReferenceSineFit, ReferenceSpectrumPeak, estimate_reference_spectrum_peak, fit_reference_sine,
 };
-pub use phase::rotate_phase;
 pub use synthetic::{SyntheticSignalSettings, generate_synthetic_signal};
 
 pub const DEFAULT_MAX_DEMO_SAMPLES: usize = 100_000;
@@ -1,4 +1,4 @@
-use crate::{AnalysisError, Result};
+use crate::{AnalysisError, LowPassFilter, LowPassKind, LowPassSettings, Result};
 use serde::{Deserialize, Serialize};
 use std::f64::consts::TAU;
 
@@ -24,10 +24,23 @@ pub struct LockinMetadata {
     pub half_window_s: f64,
     pub support_s: f64,
     pub estimated_enbw_hz: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cutoff_hz: Option<f64>,
     pub first_input_index: usize,
     pub last_input_index: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LowPassLockinSettings {
+    pub start_time_s: f64,
+    pub sample_interval_s: f64,
+    pub reference_frequency_hz: f64,
+    pub reference_phase_rad: f64,
+    pub stride_samples: usize,
+    pub harmonic: usize,
+    pub filter: LowPassSettings,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BoxcarLegacyOutput {
     pub time_s: Vec<f64>,
@@ -93,30 +106,107 @@ pub fn analyze_boxcar_legacy_pair_finite(
     settings: BoxcarLegacySettings,
 ) -> Result<BoxcarLegacyPairOutput> {
     let signal = signal.as_slice();
-    let geometry = Geometry::new(signal, settings)?;
-    let output_samples = geometry.i_end - geometry.i_start + 1;
-    let mut x = Vec::with_capacity(output_samples);
-    let mut y = Vec::with_capacity(output_samples);
-    let edge_dt =
-        geometry.half_window_s - (geometry.half_window_samples as f64) * settings.sample_interval_s;
-    let scale = 1.0 / (2.0 * geometry.half_window_s);
-
-    let window_len = 2 * geometry.half_window_samples + 3;
-    let first_center = geometry.i_start * settings.stride_samples;
-    let last_center = geometry.i_end * settings.stride_samples;
-    let raw_start = first_center - geometry.half_window_samples - 1;
-    let raw_end = last_center + geometry.half_window_samples + 2;
-    let mut window = RollingMixedWindow::new(window_len);
-    let mut oscillator = MixedOscillator::new(settings, raw_start);
-    let mut next_emit = first_center + geometry.half_window_samples + 1;
-
-    for (offset, &sample) in signal[raw_start..raw_end].iter().enumerate() {
-        let input_index = raw_start + offset;
-        window.push(oscillator.mix(input_index, sample));
-        if input_index != next_emit {
-            continue;
+    let mut stream = BoxcarLegacyStream::new(settings, signal.len())?;
+    stream.push_finite(signal);
+    stream.finish()
+}
+
+/// Stateful form of [`analyze_boxcar_legacy_pair_finite`] for signals that
+/// arrive in chunks. Only the rolling window of mixed samples is retained, and
+/// the outputs are bit-identical to the batch function for any chunking.
+pub struct BoxcarLegacyStream {
+    settings: BoxcarLegacySettings,
+    geometry: Geometry,
+    signal_len: usize,
+    received: usize,
+    raw_start: usize,
+    raw_end: usize,
+    next_emit: usize,
+    edge_dt: f64,
+    scale: f64,
+    window: RollingMixedWindow,
+    oscillator: MixedOscillator,
+    x: Vec<f64>,
+    y: Vec<f64>,
+}
+
+impl BoxcarLegacyStream {
+    /// `signal_len` is the total number of samples that will be pushed; the
+    /// output grid depends on it exactly as in the batch function.
+    pub fn new(settings: BoxcarLegacySettings, signal_len: usize) -> Result<Self> {
+        let geometry = Geometry::new(signal_len, settings)?;
+        let output_samples = geometry.i_end - geometry.i_start + 1;
+        let first_center = geometry.i_start * settings.stride_samples;
+        let last_center = geometry.i_end * settings.stride_samples;
+        let raw_start = first_center - geometry.half_window_samples - 1;
+        Ok(Self {
+            settings,
+            geometry,
+            signal_len,
+            received: 0,
+            raw_start,
+            raw_end: last_center + geometry.half_window_samples + 2,
+            next_emit: first_center + geometry.half_window_samples + 1,
+            edge_dt: geometry.half_window_s
+                - (geometry.half_window_samples as f64) * settings.sample_interval_s,
+            scale: 1.0 / (2.0 * geometry.half_window_s),
+            window: RollingMixedWindow::new(2 * geometry.half_window_samples + 3),
+            oscillator: MixedOscillator::new(settings, raw_start),
+            x: Vec::with_capacity(output_samples),
+            y: Vec::with_capacity(output_samples),
+        })
+    }
+
+    /// Index of the first output sample in the full signal.
+    pub fn first_input_index(&self) -> usize {
+        self.geometry.i_start * self.settings.stride_samples
+    }
+
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    pub fn push(&mut self, chunk: &[f64]) -> Result<()> {
+        if let Some(index) = chunk.iter().position(|value| !value.is_finite()) {
+            return Err(AnalysisError::new(
+                "non_finite_signal",
+                format!(
+                    "signal contains NaN or infinity at sample {}",
+                    self.received + index
+                ),
+            ));
+        }
+        if chunk.len() > self.signal_len - self.received {
+            return Err(AnalysisError::new(
+                "signal_too_long",
+                format!(
+                    "stream received more than the declared {} samples",
+                    self.signal_len
+                ),
+            ));
         }
+        self.push_finite(chunk);
+        Ok(())
+    }
 
+    fn push_finite(&mut self, chunk: &[f64]) {
+        let chunk_start = self.received;
+        self.received += chunk.len();
+        let first = self.raw_start.max(chunk_start);
+        let last = self.raw_end.min(self.received);
+        for input_index in first..last {
+            let sample = chunk[input_index - chunk_start];
+            self.window.push(self.oscillator.mix(input_index, sample));
+            if input_index == self.next_emit {
+                self.emit();
+            }
+        }
+    }
+
+    fn emit(&mut self) {
+        let window = &self.window;
+        let window_len = 2 * self.geometry.half_window_samples + 3;
+        let sample_interval_s = self.settings.sample_interval_s;
         debug_assert_eq!(window.len(), window_len);
         let outer_negative = window.get(0);
         let inner_negative = window.get(1);
@@ -127,60 +217,147 @@ pub fn analyze_boxcar_legacy_pair_finite(
             - outer_positive.0
             - 0.5 * inner_negative.0
             - 0.5 * inner_positive.0)
-            * settings.sample_interval_s;
+            * sample_interval_s;
         let integral_im = (window.sum_im()
             - outer_negative.1
             - outer_positive.1
             - 0.5 * inner_negative.1
             - 0.5 * inner_positive.1)
-            * settings.sample_interval_s;
+            * sample_interval_s;
         let edge_negative_re = edge_integral(
             inner_negative.0,
             outer_negative.0,
-            edge_dt,
-            settings.sample_interval_s,
+            self.edge_dt,
+            sample_interval_s,
         );
         let edge_positive_re = edge_integral(
             inner_positive.0,
             outer_positive.0,
-            edge_dt,
-            settings.sample_interval_s,
+            self.edge_dt,
+            sample_interval_s,
         );
         let edge_negative_im = edge_integral(
             inner_negative.1,
             outer_negative.1,
-            edge_dt,
-            settings.sample_interval_s,
+            self.edge_dt,
+            sample_interval_s,
         );
         let edge_positive_im = edge_integral(
             inner_positive.1,
             outer_positive.1,
-            edge_dt,
+            self.edge_dt,
+            sample_interval_s,
+        );
+        self.x
+            .push(-(integral_im + edge_negative_im + edge_positive_im) * self.scale);
+        self.y
+            .push((integral_re + edge_negative_re + edge_positive_re) * self.scale);
+        self.next_emit = self.next_emit.saturating_add(self.settings.stride_samples);
+    }
+
+    pub fn finish(self) -> Result<BoxcarLegacyPairOutput> {
+        if self.received != self.signal_len {
+            return Err(AnalysisError::new(
+                "signal_too_short",
+                format!(
+                    "stream ended after {} of {} samples",
+                    self.received, self.signal_len
+                ),
+            ));
+        }
+        let geometry = self.geometry;
+        let settings = self.settings;
+        let output_samples = geometry.i_end - geometry.i_start + 1;
+        debug_assert_eq!(self.x.len(), output_samples);
+        let weights = legacy_boxcar_weights(
+            geometry.half_window_samples,
+            geometry.half_window_s,
             settings.sample_interval_s,
         );
-        x.push(-(integral_im + edge_negative_im + edge_positive_im) * scale);
-        y.push((integral_re + edge_negative_re + edge_positive_re) * scale);
-        next_emit = next_emit.saturating_add(settings.stride_samples);
+        let sample_rate_hz = 1.0 / settings.sample_interval_s;
+        Ok(BoxcarLegacyPairOutput {
+            metadata: LockinMetadata {
+                input_samples: self.signal_len,
+                output_samples,
+                sample_rate_hz,
+                output_rate_hz: sample_rate_hz / settings.stride_samples as f64,
+                half_window_s: geometry.half_window_s,
+                support_s: 2.0 * geometry.half_window_s,
+                estimated_enbw_hz: enbw_hz(&weights, sample_rate_hz),
+                cutoff_hz: None,
+                first_input_index: geometry.i_start * settings.stride_samples,
+                last_input_index: geometry.i_end * settings.stride_samples,
+            },
+            x: self.x,
+            y: self.y,
+        })
     }
-    debug_assert_eq!(x.len(), output_samples);
+}
+
+/// Demodulates one harmonic and low-passes the mixed signal with `filter`
+/// instead of the legacy boxcar integral; outputs share the boxcar grid
+/// with the filter's edge samples in place of the half-window.
+pub fn analyze_lowpass_pair_finite(
+    signal: FiniteSignal<'_>,
+    settings: LowPassLockinSettings,
+) -> Result<BoxcarLegacyPairOutput> {
+    let signal = signal.as_slice();
+    let oscillator_settings = BoxcarLegacySettings {
+        start_time_s: settings.start_time_s,
+        sample_interval_s: settings.sample_interval_s,
+        reference_frequency_hz: settings.reference_frequency_hz,
+        reference_phase_rad: settings.reference_phase_rad,
+        half_window_cycles: 1.0,
+        stride_samples: settings.stride_samples,
+        harmonic: settings.harmonic,
+    };
+    validate_oscillator(signal.len(), oscillator_settings)?;
+    let filter = LowPassFilter::new(settings.filter, settings.sample_interval_s)?;
+    let half_window_samples = filter.edge_samples().max(1);
+    let geometry = Geometry::from_half_window(
+        signal.len(),
+        settings.stride_samples,
+        half_window_samples as f64 * settings.sample_interval_s,
+        half_window_samples,
+    )?;
+
+    let first_center = geometry.i_start * settings.stride_samples;
+    let last_center = geometry.i_end * settings.stride_samples;
+    let raw_start = first_center - half_window_samples - 1;
+    let raw_end = last_center + half_window_samples + 2;
+    let mut oscillator = MixedOscillator::new(oscillator_settings, raw_start);
+    let mut mixed = signal[raw_start..raw_end]
+        .iter()
+        .enumerate()
+        .map(|(offset, &sample)| oscillator.mix(raw_start + offset, sample))
+        .collect::<Vec<_>>();
+    let centers = (first_center..=last_center)
+        .step_by(settings.stride_samples)
+        .map(|center| center - raw_start);
+    let filtered = match settings.filter.kind {
+        LowPassKind::RcCascade => {
+            filter.apply_recursive(&mut mixed);
+            centers.map(|center| mixed[center]).collect::<Vec<_>>()
+        }
+        LowPassKind::WindowedSinc | LowPassKind::Gaussian => centers
+            .map(|center| filter.apply_fir_at(&mixed, center))
+            .collect(),
+    };
+    let (x, y) = filtered.into_iter().map(|(re, im)| (-im, re)).unzip();
 
-    let weights = legacy_boxcar_weights(
-        geometry.half_window_samples,
-        geometry.half_window_s,
-        settings.sample_interval_s,
-    );
     let sample_rate_hz = 1.0 / settings.sample_interval_s;
     Ok(BoxcarLegacyPairOutput {
         metadata: LockinMetadata {
             input_samples: signal.len(),
-            output_samples,
+            output_samples: geometry.i_end - geometry.i_start + 1,
             sample_rate_hz,
             output_rate_hz: sample_rate_hz / settings.stride_samples as f64,
             half_window_s: geometry.half_window_s,
             support_s: 2.0 * geometry.half_window_s,
-            estimated_enbw_hz: enbw_hz(&weights, sample_rate_hz),
-            first_input_index: geometry.i_start * settings.stride_samples,
-            last_input_index: geometry.i_end * settings.stride_samples,
+            estimated_enbw_hz: filter.enbw_hz(),
+            cutoff_hz: Some(settings.filter.cutoff_hz),
+            first_input_index: first_center,
+            last_input_index: last_center,
         },
         x,
         y,
@@ -198,6 +375,115 @@ pub fn boxcar_response_abs(half_window_s: f64, frequency_hz: f64) -> Result<f64>
     })
 }
 
+/// Sample covariance of one lock-in x/y pair over a signal-free window.
+///
+/// Variances are NaN when fewer than two finite samples fall in the window.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LockinNoise {
+    pub x_var: f64,
+    pub y_var: f64,
+    pub xy_cov: f64,
+    pub samples: usize,
+    /// Equivalent noise bandwidth of the low-pass filter; about `enbw_hz · T`
+    /// samples within a span `T` are independent. NaN when unknown.
+    pub enbw_hz: f64,
+}
+
+impl LockinNoise {
+    /// Placeholder for a pair whose noise could not be estimated.
+    pub fn unavailable() -> Self {
+        Self {
+            x_var: f64::NAN,
+            y_var: f64::NAN,
+            xy_cov: f64::NAN,
+            samples: 0,
+            enbw_hz: f64::NAN,
+        }
+    }
+
+    pub fn x_std(&self) -> f64 {
+        self.x_var.sqrt()
+    }
+
+    pub fn y_std(&self) -> f64 {
+        self.y_var.sqrt()
+    }
+
+    /// Covariance of the pair after [`crate::rotate_phase`] by `delta`.
+    pub fn rotated(&self, delta: f64) -> Self {
+        let (sin, cos) = delta.sin_cos();
+        Self {
+            x_var: cos * cos * self.x_var + 2.0 * cos * sin * self.xy_cov + sin * sin * self.y_var,
+            y_var: sin * sin * self.x_var - 2.0 * cos * sin * self.xy_cov + cos * cos * self.y_var,
+            xy_cov: cos * sin * (self.y_var - self.x_var) + (cos * cos - sin * sin) * self.xy_cov,
+            samples: self.samples,
+            enbw_hz: self.enbw_hz,
+        }
+    }
+}
+
+/// Estimates the noise of a lock-in x/y pair from the finite samples with
+/// `start <= t <= end`; `enbw_hz` is the bandwidth of the filter that produced
+/// them.
+pub fn estimate_lockin_noise(
+    t: &[f64],
+    x: &[f64],
+    y: &[f64],
+    start: f64,
+    end: f64,
+    enbw_hz: f64,
+) -> Result<LockinNoise> {
+    if t.len() != x.len() || t.len() != y.len() {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            format!(
+                "noise estimation requires equal-length t, x, and y arrays (got {}, {}, and {})",
+                t.len(),
+                x.len(),
+                y.len()
+            ),
+        ));
+    }
+    if !(start.is_finite() && end.is_finite() && start < end) {
+        return Err(AnalysisError::new(
+            "invalid_window",
+            format!("noise window must be finite with start < end (got [{start}, {end}])"),
+        ));
+    }
+    let samples = t
+        .iter()
+        .zip(x.iter().zip(y))
+        .filter(|(time, (x, y))| **time >= start && **time <= end && x.is_finite() && y.is_finite())
+        .map(|(_, (&x, &y))| (x, y))
+        .collect::<Vec<_>>();
+    if samples.len() < 2 {
+        return Ok(LockinNoise {
+            samples: samples.len(),
+            enbw_hz,
+            ..LockinNoise::unavailable()
+        });
+    }
+    let count = samples.len() as f64;
+    let (x_sum, y_sum) = samples
+        .iter()
+        .fold((0.0, 0.0), |(x_sum, y_sum), (x, y)| (x_sum + x, y_sum + y));
+    let (x_mean, y_mean) = (x_sum / count, y_sum / count);
+    let (x_var, y_var, xy_cov) = samples
+        .iter()
+        .fold((0.0, 0.0, 0.0), |(xx, yy, xy), (x, y)| {
+            let (dx, dy) = (x - x_mean, y - y_mean);
+            (xx + dx * dx, yy + dy * dy, xy + dx * dy)
+        });
+    let dof = count - 1.0;
+    Ok(LockinNoise {
+        x_var: x_var / dof,
+        y_var: y_var / dof,
+        xy_cov: xy_cov / dof,
+        samples: samples.len(),
+        enbw_hz,
+    })
+}
+
 #[derive(Debug, Clone, Copy)]
 struct Geometry {
     half_window_s: f64,
@@ -207,24 +493,9 @@ struct Geometry {
 }
 
 impl Geometry {
-    fn new(signal: &[f64], settings: BoxcarLegacySettings) -> Result<Self> {
-        if signal.len() < 2 {
-            return Err(AnalysisError::new(
-                "signal_too_short",
-                "signal must contain at least two samples",
-            ));
-        }
-        require_finite("start_time_s", settings.start_time_s)?;
-        require_positive_finite("sample_interval_s", settings.sample_interval_s)?;
-        require_positive_finite("reference_frequency_hz", settings.reference_frequency_hz)?;
-        require_finite("reference_phase_rad", settings.reference_phase_rad)?;
+    fn new(signal_len: usize, settings: BoxcarLegacySettings) -> Result<Self> {
+        validate_oscillator(signal_len, settings)?;
         require_positive_finite("half_window_cycles", settings.half_window_cycles)?;
-        if settings.stride_samples == 0 {
-            return Err(AnalysisError::new(
-                "invalid_stride",
-                "stride_samples must be positive",
-            ));
-        }
         let half_window_s = settings.half_window_cycles / settings.reference_frequency_hz;
         if !half_window_s.is_finite() || half_window_s < settings.sample_interval_s {
             return Err(AnalysisError::new(
@@ -234,8 +505,22 @@ impl Geometry {
         }
         let half_window_samples =
             ((half_window_s / settings.sample_interval_s).floor() as usize).max(1);
-        let integration_points = ((signal.len() - 1) / settings.stride_samples) + 1;
-        let i_start = 2 + (half_window_samples + 1) / settings.stride_samples;
+        Self::from_half_window(
+            signal_len,
+            settings.stride_samples,
+            half_window_s,
+            half_window_samples,
+        )
+    }
+
+    fn from_half_window(
+        signal_len: usize,
+        stride_samples: usize,
+        half_window_s: f64,
+        half_window_samples: usize,
+    ) -> Result<Self> {
+        let integration_points = ((signal_len - 1) / stride_samples) + 1;
+        let i_start = 2 + (half_window_samples + 1) / stride_samples;
         let i_end = integration_points.saturating_sub(i_start);
         if i_end < i_start {
             return Err(AnalysisError::new(
@@ -243,12 +528,12 @@ impl Geometry {
                 "signal does not contain a complete lock-in window",
             ));
         }
-        let first_center = i_start * settings.stride_samples;
-        let last_center = i_end * settings.stride_samples;
+        let first_center = i_start * stride_samples;
+        let last_center = i_end * stride_samples;
         if first_center <= half_window_samples
             || last_center
                 .checked_add(half_window_samples + 1)
-                .is_none_or(|index| index >= signal.len())
+                .is_none_or(|index| index >= signal_len)
         {
             return Err(AnalysisError::new(
                 "window_out_of_range",
@@ -264,6 +549,26 @@ impl Geometry {
     }
 }
 
+fn validate_oscillator(signal_len: usize, settings: BoxcarLegacySettings) -> Result<()> {
+    if signal_len < 2 {
+        return Err(AnalysisError::new(
+            "signal_too_short",
+            "signal must contain at least two samples",
+        ));
+    }
+    require_finite("start_time_s", settings.start_time_s)?;
+    require_positive_finite("sample_interval_s", settings.sample_interval_s)?;
+    require_positive_finite("reference_frequency_hz", settings.reference_frequency_hz)?;
+    require_finite("reference_phase_rad", settings.reference_phase_rad)?;
+    if settings.stride_samples == 0 {
+        return Err(AnalysisError::new(
+            "invalid_stride",
+            "stride_samples must be positive",
+        ));
+    }
+    Ok(())
+}
+
 struct MixedOscillator {
     phase_zero: f64,
     step_phase: f64,
@@ -470,6 +775,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn stream_matches_batch_bit_for_bit_for_any_chunking() {
+        let settings = BoxcarLegacySettings {
+            half_window_cycles: 1.37,
+            harmonic: 3,
+            ..settings()
+        };
+        let signal = (0..12_345)
+            .map(|index| (index as f64 * 0.0137).sin() + 0.3 * (index as f64 * 0.21).cos())
+            .collect::<Vec<_>>();
+        let batch = analyze_boxcar_legacy_pair(&signal, settings).unwrap();
+        for chunk_len in [1, 7, 100, 4_096, 5_000, signal.len()] {
+            let mut stream = BoxcarLegacyStream::new(settings, signal.len()).unwrap();
+            for chunk in signal.chunks(chunk_len) {
+                stream.push(chunk).unwrap();
+            }
+            let streamed = stream.finish().unwrap();
+            assert_eq!(
+                streamed.x.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+                batch.x.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+                "chunk_len={chunk_len}"
+            );
+            assert_eq!(
+                streamed.y.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+                batch.y.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+                "chunk_len={chunk_len}"
+            );
+            assert_eq!(streamed.metadata, batch.metadata);
+        }
+    }
+
+    #[test]
+    fn stream_rejects_incomplete_overlong_and_non_finite_input() {
+        let settings = settings();
+        let mut stream = BoxcarLegacyStream::new(settings, 10_000).unwrap();
+        stream.push(&[0.0; 4_000]).unwrap();
+        assert_eq!(stream.finish().unwrap_err().code(), "signal_too_short");
+
+        let mut stream = BoxcarLegacyStream::new(settings, 10_000).unwrap();
+        stream.push(&[0.0; 9_000]).unwrap();
+        assert_eq!(
+            stream.push(&[0.0; 1_001]).unwrap_err().code(),
+            "signal_too_long"
+        );
+
+        let mut stream = BoxcarLegacyStream::new(settings, 10_000).unwrap();
+        stream.push(&[0.0; 100]).unwrap();
+        let error = stream.push(&[0.0, f64::NAN]).unwrap_err();
+        assert_eq!(error.code(), "non_finite_signal");
+        assert!(error.message().contains("sample 101"));
+    }
+
     #[test]
     fn recovers_known_amplitude_and_phase() {
         let settings = settings();
@@ -535,10 +892,108 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lowpass_kinds_recover_known_amplitude_and_phase() {
+        let base = settings();
+        let amplitude = 0.8;
+        let signal_phase = 0.3;
+        let signal = (0..40_000)
+            .map(|index| {
+                let time = base.start_time_s + index as f64 * base.sample_interval_s;
+                amplitude * (TAU * base.reference_frequency_hz * time + signal_phase).sin()
+            })
+            .collect::<Vec<_>>();
+        let expected_phase = signal_phase + base.reference_phase_rad;
+        for kind in [
+            LowPassKind::RcCascade,
+            LowPassKind::WindowedSinc,
+            LowPassKind::Gaussian,
+        ] {
+            for zero_phase in [true, false] {
+                let filter = LowPassSettings {
+                    kind,
+                    cutoff_hz: 100.0,
+                    order: 4,
+                    half_window_s: 0.02,
+                    zero_phase,
+                };
+                let output = analyze_lowpass_pair_finite(
+                    FiniteSignal::new(&signal).unwrap(),
+                    LowPassLockinSettings {
+                        start_time_s: base.start_time_s,
+                        sample_interval_s: base.sample_interval_s,
+                        reference_frequency_hz: base.reference_frequency_hz,
+                        reference_phase_rad: base.reference_phase_rad,
+                        stride_samples: base.stride_samples,
+                        harmonic: 1,
+                        filter,
+                    },
+                )
+                .unwrap();
+                let filter_design = LowPassFilter::new(filter, base.sample_interval_s).unwrap();
+                assert_eq!(output.metadata.cutoff_hz, Some(100.0));
+                assert_eq!(output.metadata.estimated_enbw_hz, filter_design.enbw_hz());
+                assert!(output.metadata.first_input_index > filter_design.edge_samples());
+                let error = output
+                    .x
+                    .iter()
+                    .zip(&output.y)
+                    .map(|(x, y)| {
+                        (x - 0.5 * amplitude * expected_phase.cos())
+                            .hypot(y - 0.5 * amplitude * expected_phase.sin())
+                    })
+                    .fold(0.0, f64::max);
+                assert!(error < 1.0e-5, "{kind:?} zero_phase={zero_phase}: {error}");
+            }
+        }
+    }
+
     #[test]
     fn response_has_unity_dc_and_first_null() {
         let half_window = 0.001;
         assert_eq!(boxcar_response_abs(half_window, 0.0).unwrap(), 1.0);
         assert!(boxcar_response_abs(half_window, 500.0).unwrap() < 1.0e-15);
     }
+
+    #[test]
+    fn noise_covariance_follows_phase_rotation() {
+        let t = (0..400)
+            .map(|index| index as f64 * 0.01 - 2.0)
+            .collect::<Vec<_>>();
+        let x = t
+            .iter()
+            .map(|time| 0.3 * (time * 37.0).sin() + if *time > 0.0 { 5.0 } else { 0.0 })
+            .collect::<Vec<_>>();
+        let y = t
+            .iter()
+            .map(|time| 0.1 * (time * 37.0).sin() + 0.2 * (time * 53.0).cos())
+            .collect::<Vec<_>>();
+        let noise = estimate_lockin_noise(&t, &x, &y, -2.0, -0.5, 20.0).unwrap();
+        assert_eq!(noise.samples, 151);
+        assert!(noise.x_std() > 0.1 && noise.x_std() < 0.3);
+        assert!(noise.xy_cov > 0.0);
+
+        let delta = 0.7;
+        let (rx, ry) = crate::rotate_phase(&x, &y, delta).unwrap();
+        let expected = estimate_lockin_noise(&t, &rx, &ry, -2.0, -0.5, 20.0).unwrap();
+        let rotated = noise.rotated(delta);
+        assert_eq!(rotated.enbw_hz, 20.0);
+        for (value, expected) in [
+            (rotated.x_var, expected.x_var),
+            (rotated.y_var, expected.y_var),
+            (rotated.xy_cov, expected.xy_cov),
+        ] {
+            assert!((value - expected).abs() < 1.0e-12, "{value} != {expected}");
+        }
+
+        let empty = estimate_lockin_noise(&t, &x, &y, 10.0, 11.0, 20.0).unwrap();
+        assert_eq!(empty.samples, 0);
+        assert!(empty.x_var.is_nan());
+        assert_eq!(
+            estimate_lockin_noise(&t, &x, &y, 1.0, 0.0, 20.0)
+                .unwrap_err()
+                .code(),
+            "invalid_window"
+        );
+    }
 }
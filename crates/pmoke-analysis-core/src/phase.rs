@@ -1,4 +1,301 @@
 use crate::error::{AnalysisError, Result};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{PI, TAU};
+
+/// Two-sided 95 % normal quantile used for the omega·t0 confidence interval.
+pub const OMEGA_T0_CONFIDENCE_Z: f64 = 1.959_963_984_540_054;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OmegaT0Estimate {
+    pub omega_t0: f64,
+    pub omega_t0_std: f64,
+    pub confidence_interval: [f64; 2],
+    pub residual_rms: f64,
+    pub samples: usize,
+    pub harmonics: Vec<HarmonicPhaseResidual>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HarmonicPhaseResidual {
+    pub harmonic: usize,
+    pub fitted: bool,
+    pub omega_t0: f64,
+    pub mean_residual_rad: f64,
+    pub residual_rms_rad: f64,
+}
+
+impl OmegaT0Estimate {
+    /// Largest mean residual among the harmonics that entered the fit.
+    pub fn max_harmonic_residual_rad(&self) -> f64 {
+        self.harmonics
+            .iter()
+            .filter(|harmonic| harmonic.fitted)
+            .map(|harmonic| harmonic.mean_residual_rad.abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Fits `m_omega_t0 = -omega_t0 * n` through the origin on the even harmonics.
+pub fn estimate_omega_t0(harmonics: &[usize], m_omega_t0: &[&[f64]]) -> Result<OmegaT0Estimate> {
+    if harmonics.len() != m_omega_t0.len() {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            format!(
+                "omega_t0 estimation requires one phase series per harmonic (got {} harmonics and {} series)",
+                harmonics.len(),
+                m_omega_t0.len()
+            ),
+        ));
+    }
+    if m_omega_t0.iter().any(|values| values.is_empty()) {
+        return Err(AnalysisError::new(
+            "empty_phase_column",
+            "omega_t0 estimation requires non-empty phase series",
+        ));
+    }
+    if m_omega_t0
+        .iter()
+        .flat_map(|values| values.iter())
+        .any(|value| !value.is_finite())
+    {
+        return Err(AnalysisError::new(
+            "non_finite_phase",
+            "omega_t0 estimation requires finite phase series",
+        ));
+    }
+
+    let mut samples = 0usize;
+    let mut sum_nn = 0.0;
+    let mut sum_ny = 0.0;
+    for (&harmonic, values) in harmonics.iter().zip(m_omega_t0) {
+        if harmonic == 0 || !harmonic.is_multiple_of(2) {
+            continue;
+        }
+        let n = harmonic as f64;
+        samples += values.len();
+        sum_nn += n * n * values.len() as f64;
+        sum_ny += n * values.iter().sum::<f64>();
+    }
+    if samples == 0 {
+        return Err(AnalysisError::new(
+            "missing_even_harmonic",
+            "omega_t0 is fitted on even harmonics; at least one is required",
+        ));
+    }
+    let slope = sum_ny / sum_nn;
+
+    let mut sum_squared_residual = 0.0;
+    let harmonic_residuals = harmonics
+        .iter()
+        .zip(m_omega_t0)
+        .map(|(&harmonic, values)| {
+            let n = harmonic as f64;
+            let count = values.len() as f64;
+            let (sum, sum_squared) = values.iter().fold((0.0, 0.0), |(sum, sum_squared), y| {
+                let residual = y - slope * n;
+                (sum + residual, sum_squared + residual * residual)
+            });
+            let fitted = harmonic != 0 && harmonic.is_multiple_of(2);
+            if fitted {
+                sum_squared_residual += sum_squared;
+            }
+            HarmonicPhaseResidual {
+                harmonic,
+                fitted,
+                omega_t0: if harmonic == 0 {
+                    f64::NAN
+                } else {
+                    -values.iter().sum::<f64>() / (count * n)
+                },
+                mean_residual_rad: sum / count,
+                residual_rms_rad: (sum_squared / count).sqrt(),
+            }
+        })
+        .collect();
+
+    let degrees_of_freedom = samples.saturating_sub(1).max(1) as f64;
+    let omega_t0 = -slope;
+    let omega_t0_std = (sum_squared_residual / degrees_of_freedom / sum_nn).sqrt();
+    let half_width = OMEGA_T0_CONFIDENCE_Z * omega_t0_std;
+    Ok(OmegaT0Estimate {
+        omega_t0,
+        omega_t0_std,
+        confidence_interval: [omega_t0 - half_width, omega_t0 + half_width],
+        residual_rms: (sum_squared_residual / samples as f64).sqrt(),
+        samples,
+        harmonics: harmonic_residuals,
+    })
+}
+
+/// omega·t0 fitted in one sliding window of the lock-in trace.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PhaseDriftKnot {
+    /// Mean sample time of the window in seconds.
+    pub time: f64,
+    pub omega_t0: f64,
+    pub samples: usize,
+}
+
+/// Fits omega·t0 in sliding windows of `window` seconds whose centres are
+/// `step` seconds apart.
+///
+/// Each window sums the x/y pairs of every even harmonic, so the fit follows
+/// the out-of-phase component rather than per-sample angles. `angle_offsets`
+/// are added to each summed angle to give `-n·omega_t0`, as in
+/// [`estimate_omega_t0`]. The windowed angles are unwrapped against the
+/// global `omega_t0`, so drifts up to ±π/n stay continuous.
+pub fn fit_phase_drift(
+    t: &[f64],
+    harmonics: &[usize],
+    pairs: &[[&[f64]; 2]],
+    angle_offsets: &[f64],
+    omega_t0: f64,
+    window: f64,
+    step: f64,
+) -> Result<Vec<PhaseDriftKnot>> {
+    if harmonics.len() != pairs.len() || harmonics.len() != angle_offsets.len() {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            format!(
+                "phase drift fitting requires one x/y pair and angle offset per harmonic (got {} harmonics, {} pairs, {} offsets)",
+                harmonics.len(),
+                pairs.len(),
+                angle_offsets.len()
+            ),
+        ));
+    }
+    if t.is_empty() {
+        return Err(AnalysisError::new(
+            "empty_phase_column",
+            "phase drift fitting requires a non-empty time axis",
+        ));
+    }
+    if pairs.iter().flatten().any(|column| column.len() != t.len()) {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            "phase drift fitting requires x/y columns as long as the time axis",
+        ));
+    }
+    if !window.is_finite() || window <= 0.0 || !step.is_finite() || step <= 0.0 {
+        return Err(AnalysisError::new(
+            "invalid_window",
+            format!(
+                "phase drift window and step must be finite and positive (got {window} and {step})"
+            ),
+        ));
+    }
+    if !omega_t0.is_finite()
+        || t.iter()
+            .chain(angle_offsets)
+            .any(|value| !value.is_finite())
+        || t.windows(2).any(|pair| pair[1] < pair[0])
+    {
+        return Err(AnalysisError::new(
+            "non_finite_phase",
+            "phase drift fitting requires finite offsets and a finite, non-decreasing time axis",
+        ));
+    }
+
+    let even = harmonics
+        .iter()
+        .zip(pairs)
+        .zip(angle_offsets)
+        .filter(|((harmonic, _), _)| **harmonic != 0 && harmonic.is_multiple_of(2))
+        .map(|((&harmonic, &[x, y]), &offset)| {
+            (harmonic as f64, prefix_sum(x), prefix_sum(y), offset)
+        })
+        .collect::<Vec<_>>();
+    if even.is_empty() {
+        return Err(AnalysisError::new(
+            "missing_even_harmonic",
+            "phase drift is fitted on even harmonics; at least one is required",
+        ));
+    }
+    let sum_nn = even.iter().map(|(n, ..)| n * n).sum::<f64>();
+    let time_sums = prefix_sum(t);
+
+    let first = t[0];
+    let last = t[t.len() - 1];
+    let half = window / 2.0;
+    let mut centres = Vec::new();
+    if last - first <= window {
+        centres.push((first + last) / 2.0);
+    } else {
+        let final_centre = last - half;
+        let mut centre = first + half;
+        while centre < final_centre {
+            centres.push(centre);
+            centre = first + half + step * centres.len() as f64;
+        }
+        centres.push(final_centre);
+    }
+
+    let knots = centres
+        .into_iter()
+        .filter_map(|centre| {
+            let start = t.partition_point(|&time| time < centre - half);
+            let end = t.partition_point(|&time| time <= centre + half);
+            if start == end {
+                return None;
+            }
+            let sum_n_residual = even
+                .iter()
+                .map(|(n, x, y, offset)| {
+                    let angle = (y[end] - y[start]).atan2(x[end] - x[start]) + offset;
+                    n * wrap_phase(angle + n * omega_t0)
+                })
+                .sum::<f64>();
+            Some(PhaseDriftKnot {
+                time: (time_sums[end] - time_sums[start]) / (end - start) as f64,
+                omega_t0: omega_t0 - sum_n_residual / sum_nn,
+                samples: end - start,
+            })
+        })
+        .collect::<Vec<_>>();
+    if knots.is_empty() {
+        return Err(AnalysisError::new(
+            "empty_phase_column",
+            "phase drift windows contain no samples",
+        ));
+    }
+    Ok(knots)
+}
+
+/// omega·t0 at each time, linearly interpolated between knots and held
+/// constant outside them.
+pub fn interpolate_phase_drift(knots: &[PhaseDriftKnot], t: &[f64]) -> Vec<f64> {
+    t.iter()
+        .map(|&time| {
+            let after = knots.partition_point(|knot| knot.time <= time);
+            match (
+                after.checked_sub(1).map(|index| knots[index]),
+                knots.get(after).copied(),
+            ) {
+                (Some(before), Some(after)) => {
+                    let fraction = (time - before.time) / (after.time - before.time);
+                    before.omega_t0 + fraction * (after.omega_t0 - before.omega_t0)
+                }
+                (Some(knot), None) | (None, Some(knot)) => knot.omega_t0,
+                (None, None) => f64::NAN,
+            }
+        })
+        .collect()
+}
+
+fn prefix_sum(values: &[f64]) -> Vec<f64> {
+    let mut sums = Vec::with_capacity(values.len() + 1);
+    sums.push(0.0);
+    let mut total = 0.0;
+    for value in values {
+        total += value;
+        sums.push(total);
+    }
+    sums
+}
+
+fn wrap_phase(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
 
 pub fn rotate_phase(lix: &[f64], liy: &[f64], delta: f64) -> Result<(Vec<f64>, Vec<f64>)> {
     if lix.len() != liy.len() {
@@ -32,6 +329,49 @@ pub fn rotate_phase(lix: &[f64], liy: &[f64], delta: f64) -> Result<(Vec<f64>, V
         .unzip())
 }
 
+/// Same as [`rotate_phase`], with a separate rotation angle for every point.
+pub fn rotate_phase_series(
+    lix: &[f64],
+    liy: &[f64],
+    deltas: &[f64],
+) -> Result<(Vec<f64>, Vec<f64>)> {
+    if lix.len() != liy.len() || lix.len() != deltas.len() {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            format!(
+                "phase rotation requires equal-length x, y, and delta arrays (got {}, {}, and {})",
+                lix.len(),
+                liy.len(),
+                deltas.len()
+            ),
+        ));
+    }
+    if lix
+        .iter()
+        .chain(liy)
+        .chain(deltas)
+        .any(|value| !value.is_finite())
+    {
+        return Err(AnalysisError::new(
+            "non_finite_phase",
+            "phase rotation x, y, and delta must be finite",
+        ));
+    }
+
+    Ok(lix
+        .iter()
+        .zip(liy)
+        .zip(deltas)
+        .map(|((&x, &y), &delta)| {
+            let (sin_delta, cos_delta) = delta.sin_cos();
+            (
+                x * cos_delta + y * sin_delta,
+                -x * sin_delta + y * cos_delta,
+            )
+        })
+        .unzip())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,4 +414,147 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn omega_t0_fit_matches_even_harmonic_least_squares() {
+        let harmonics = [1, 2, 3, 4, 6];
+        let omega_t0 = 0.125;
+        let series = harmonics
+            .iter()
+            .map(|&harmonic| {
+                let ideal = -omega_t0 * harmonic as f64;
+                if harmonic % 2 == 1 {
+                    vec![ideal + 1.0; 4]
+                } else {
+                    vec![ideal - 0.01, ideal + 0.01, ideal - 0.02, ideal + 0.02]
+                }
+            })
+            .collect::<Vec<_>>();
+        let refs = series.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+        let estimate = estimate_omega_t0(&harmonics, &refs).unwrap();
+
+        assert_eq!(estimate.samples, 12);
+        assert!((estimate.omega_t0 - omega_t0).abs() < 1.0e-12);
+        let expected_std = (0.001_f64 * 3.0 / 11.0 / (4.0 * (4.0 + 16.0 + 36.0))).sqrt();
+        assert!((estimate.omega_t0_std - expected_std).abs() < 1.0e-12);
+        assert!(estimate.confidence_interval[0] < omega_t0);
+        assert!(estimate.confidence_interval[1] > omega_t0);
+        assert!(estimate.max_harmonic_residual_rad() < 1.0e-12);
+        assert!(!estimate.harmonics[0].fitted);
+        assert!((estimate.harmonics[0].mean_residual_rad - 1.0).abs() < 1.0e-12);
+        assert!((estimate.harmonics[1].residual_rms_rad - 0.000_25_f64.sqrt()).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn omega_t0_reports_disagreeing_harmonics() {
+        let harmonics = [2, 4];
+        let series = [vec![-0.2; 3], vec![-0.2; 3]];
+        let refs = series.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+        let estimate = estimate_omega_t0(&harmonics, &refs).unwrap();
+
+        assert!((estimate.omega_t0 - 0.06).abs() < 1.0e-12);
+        assert!((estimate.harmonics[0].omega_t0 - 0.1).abs() < 1.0e-12);
+        assert!((estimate.harmonics[1].omega_t0 - 0.05).abs() < 1.0e-12);
+        assert!((estimate.max_harmonic_residual_rad() - 0.08).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn phase_drift_follows_a_linear_ramp_in_omega_t0() {
+        let harmonics = [1, 2, 4];
+        let t = (0..1000)
+            .map(|index| index as f64 * 1.0e-3)
+            .collect::<Vec<_>>();
+        let omega_t0 = |time: f64| 0.2 + 0.3 * time;
+        let columns = harmonics
+            .iter()
+            .map(|&harmonic| {
+                let n = harmonic as f64;
+                let angle = |time: f64| PI / 2.0 - n * omega_t0(time);
+                (
+                    t.iter().map(|&time| angle(time).cos()).collect::<Vec<_>>(),
+                    t.iter().map(|&time| angle(time).sin()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let pairs = columns
+            .iter()
+            .map(|(x, y)| [x.as_slice(), y.as_slice()])
+            .collect::<Vec<_>>();
+
+        let knots = fit_phase_drift(
+            &t,
+            &harmonics,
+            &pairs,
+            &[0.0, -PI / 2.0, -PI / 2.0],
+            0.3,
+            0.1,
+            0.05,
+        )
+        .unwrap();
+
+        assert_eq!(knots.len(), 19);
+        assert!((knots[0].time - 0.05).abs() < 1.0e-12);
+        assert!((knots[18].time - 0.949).abs() < 1.0e-12);
+        for knot in &knots {
+            assert!(
+                (knot.omega_t0 - omega_t0(knot.time)).abs() < 1.0e-6,
+                "{knot:?}"
+            );
+            assert!(knot.samples >= 100);
+        }
+        let interpolated = interpolate_phase_drift(&knots, &t);
+        assert!((interpolated[0] - knots[0].omega_t0).abs() < 1.0e-12);
+        assert!((interpolated[500] - omega_t0(0.5)).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn phase_drift_uses_one_window_for_short_traces() {
+        let x = [1.0, 1.0];
+        let y = [0.0, 0.0];
+        let knots =
+            fit_phase_drift(&[0.0, 1.0], &[2], &[[&x, &y]], &[-0.1], 0.05, 5.0, 1.0).unwrap();
+        assert_eq!(knots.len(), 1);
+        assert_eq!(knots[0].samples, 2);
+        assert!((knots[0].omega_t0 - 0.05).abs() < 1.0e-12);
+        assert!((knots[0].time - 0.5).abs() < 1.0e-12);
+
+        let error =
+            fit_phase_drift(&[0.0, 1.0], &[2], &[[&x, &y]], &[0.0], 0.0, 0.0, 1.0).unwrap_err();
+        assert_eq!(error.code(), "invalid_window");
+    }
+
+    #[test]
+    fn per_point_rotation_matches_constant_rotation() {
+        let x = [3.0, -1.0];
+        let y = [4.0, 2.0];
+        let (constant_x, constant_y) = rotate_phase(&x, &y, 0.73).unwrap();
+        let (series_x, series_y) = rotate_phase_series(&x, &y, &[0.73, 0.73]).unwrap();
+        assert_eq!(constant_x, series_x);
+        assert_eq!(constant_y, series_y);
+
+        let error = rotate_phase_series(&x, &y, &[0.73]).unwrap_err();
+        assert_eq!(error.code(), "length_mismatch");
+    }
+
+    #[test]
+    fn omega_t0_rejects_invalid_series() {
+        for (harmonics, series, code) in [
+            (vec![2, 4], vec![vec![0.0]], "length_mismatch"),
+            (vec![2], vec![Vec::new()], "empty_phase_column"),
+            (vec![2], vec![vec![f64::NAN]], "non_finite_phase"),
+            (
+                vec![1, 3],
+                vec![vec![0.0], vec![0.0]],
+                "missing_even_harmonic",
+            ),
+        ] {
+            let refs = series.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            assert_eq!(
+                estimate_omega_t0(&harmonics, &refs).unwrap_err().code(),
+                code
+            );
+        }
+    }
 }
@@ -0,0 +1,584 @@
+use crate::{AnalysisError, Result};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+const FFT_PAD_FACTOR: usize = 3;
+const MAX_FIT_ITERATIONS: usize = 200;
+const FIT_PARAMETERS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReferenceSpectrumPeak {
+    pub frequency_hz: f64,
+    pub amplitude: f64,
+    pub phase_rad: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReferenceSineFit {
+    pub frequency_hz: f64,
+    pub amplitude: f64,
+    pub phase_rad: f64,
+    pub offset: f64,
+    pub frequency_std_hz: f64,
+    pub amplitude_std: f64,
+    pub phase_std_rad: f64,
+    pub offset_std: f64,
+    pub residual_rms: f64,
+    pub residual_max_abs: f64,
+    pub r_squared: f64,
+    pub samples: usize,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+pub fn estimate_reference_spectrum_peak(
+    sample_interval_s: f64,
+    signal: &[f64],
+) -> Result<ReferenceSpectrumPeak> {
+    if signal.len() < 2 {
+        return Err(AnalysisError::new(
+            "signal_too_short",
+            "reference FFT requires at least two samples",
+        ));
+    }
+    if !sample_interval_s.is_finite() || sample_interval_s <= 0.0 {
+        return Err(AnalysisError::new(
+            "invalid_sample_interval",
+            format!("reference FFT dt must be positive and finite (got {sample_interval_s})"),
+        ));
+    }
+    if signal.iter().any(|value| !value.is_finite()) {
+        return Err(AnalysisError::new(
+            "non_finite_signal",
+            "reference FFT requires finite samples",
+        ));
+    }
+
+    let (min, max) = signal
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        });
+    let scale = min.abs().max(max.abs());
+    if max - min <= f64::EPSILON * scale.max(f64::MIN_POSITIVE) {
+        return Err(AnalysisError::new(
+            "constant_signal",
+            "reference signal has no non-DC component",
+        ));
+    }
+
+    let mean = signal.iter().sum::<f64>() / signal.len() as f64;
+    let centered_scale = signal
+        .iter()
+        .map(|value| (value - mean).abs())
+        .fold(0.0, f64::max);
+    if centered_scale == 0.0 {
+        return Err(AnalysisError::new(
+            "constant_signal",
+            "reference signal has no non-DC component",
+        ));
+    }
+
+    let window = hann_window(signal.len());
+    let window_sum = window.iter().sum::<f64>();
+    let padded_len = (signal.len() * FFT_PAD_FACTOR).next_power_of_two();
+    let mut spectrum = vec![Complex::default(); padded_len];
+    for ((bin, &value), &weight) in spectrum.iter_mut().zip(signal).zip(&window) {
+        bin.re = (value - mean) * weight;
+    }
+    fft_in_place(&mut spectrum);
+    let spectrum = &spectrum[..=padded_len / 2];
+
+    let (peak_index, peak) = spectrum
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(index, value)| (index, value.norm()))
+        .max_by(|left, right| left.1.total_cmp(&right.1))
+        .ok_or_else(|| {
+            AnalysisError::new(
+                "unresolvable_reference",
+                "reference FFT has no non-DC frequency bins",
+            )
+        })?;
+    let peak_amplitude = 2.0 * peak / window_sum;
+    if !peak_amplitude.is_finite() || peak_amplitude <= f64::EPSILON * centered_scale {
+        return Err(AnalysisError::new(
+            "unresolvable_reference",
+            "reference FFT has no resolvable non-DC carrier",
+        ));
+    }
+
+    let frequency_hz = peak_index as f64 / (padded_len as f64 * sample_interval_s);
+    if !frequency_hz.is_finite() || frequency_hz <= 0.0 {
+        return Err(AnalysisError::new(
+            "unresolvable_reference",
+            format!("reference FFT estimated an invalid carrier frequency: {frequency_hz}"),
+        ));
+    }
+    let value = interpolate_peak(spectrum, peak_index);
+
+    Ok(ReferenceSpectrumPeak {
+        frequency_hz,
+        amplitude: 2.0 * value.norm() / window_sum,
+        phase_rad: -(value.im.atan2(value.re) + FRAC_PI_2),
+    })
+}
+
+pub fn fit_reference_sine(
+    time_s: &[f64],
+    signal: &[f64],
+    seed_frequency_hz: f64,
+) -> Result<ReferenceSineFit> {
+    if time_s.len() != signal.len() {
+        return Err(AnalysisError::new(
+            "length_mismatch",
+            format!(
+                "time length ({}) and reference length ({}) differ",
+                time_s.len(),
+                signal.len()
+            ),
+        ));
+    }
+    if signal.len() <= FIT_PARAMETERS {
+        return Err(AnalysisError::new(
+            "signal_too_short",
+            format!(
+                "reference fit requires more than {FIT_PARAMETERS} samples (got {})",
+                signal.len()
+            ),
+        ));
+    }
+    if time_s.iter().chain(signal).any(|value| !value.is_finite()) {
+        return Err(AnalysisError::new(
+            "non_finite_signal",
+            "reference fit requires finite times and samples",
+        ));
+    }
+    if !seed_frequency_hz.is_finite() || seed_frequency_hz <= 0.0 {
+        return Err(AnalysisError::new(
+            "invalid_parameter",
+            format!(
+                "reference fit seed frequency must be positive and finite (got {seed_frequency_hz})"
+            ),
+        ));
+    }
+
+    let samples = signal.len();
+    let time_origin = time_s.iter().sum::<f64>() / samples as f64;
+    let tau = time_s
+        .iter()
+        .map(|time| time - time_origin)
+        .collect::<Vec<_>>();
+
+    let mut parameters = linear_seed(&tau, signal, seed_frequency_hz)?;
+    let mut ssr = sum_squared_residuals(&tau, signal, &parameters);
+    let mut damping = 1.0e-3;
+    let mut iterations = 0;
+    let mut converged = false;
+    while iterations < MAX_FIT_ITERATIONS {
+        iterations += 1;
+        let (normal, gradient) = normal_equations(&tau, signal, &parameters);
+        let mut accepted = false;
+        while damping < 1.0e12 {
+            let mut damped = normal;
+            for (index, row) in damped.iter_mut().enumerate() {
+                row[index] += damping * normal[index][index].max(f64::MIN_POSITIVE);
+            }
+            let Some(step) = solve(damped, gradient) else {
+                damping *= 10.0;
+                continue;
+            };
+            let trial = [
+                parameters[0] + step[0],
+                parameters[1] + step[1],
+                parameters[2] + step[2],
+                parameters[3] + step[3],
+            ];
+            let trial_ssr = sum_squared_residuals(&tau, signal, &trial);
+            if trial_ssr.is_finite() && trial_ssr <= ssr {
+                let small_step = step
+                    .iter()
+                    .zip(&trial)
+                    .all(|(delta, value)| delta.abs() <= 1.0e-12 * (value.abs() + 1.0e-12));
+                let small_change = ssr - trial_ssr <= 1.0e-15 * ssr.max(f64::MIN_POSITIVE);
+                parameters = trial;
+                ssr = trial_ssr;
+                damping = (damping * 0.1).max(1.0e-12);
+                accepted = true;
+                converged = small_step || small_change;
+                break;
+            }
+            damping *= 10.0;
+        }
+        if !accepted {
+            converged = true;
+        }
+        if converged {
+            break;
+        }
+    }
+
+    let [mut amplitude, frequency_hz, mut centered_phase, offset] = parameters;
+    if amplitude < 0.0 {
+        amplitude = -amplitude;
+        centered_phase += PI;
+    }
+    let (normal, _) = normal_equations(
+        &tau,
+        signal,
+        &[amplitude, frequency_hz, centered_phase, offset],
+    );
+    let covariance = invert(normal).ok_or_else(|| {
+        AnalysisError::new(
+            "singular_fit",
+            "reference fit normal matrix is singular; the fit window does not constrain the sine",
+        )
+    })?;
+    let variance = ssr / (samples - FIT_PARAMETERS) as f64;
+    let lever = TAU * time_origin;
+    let phase_variance =
+        covariance[2][2] + lever * lever * covariance[1][1] + 2.0 * lever * covariance[1][2];
+
+    let mean = signal.iter().sum::<f64>() / samples as f64;
+    let total = signal
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>();
+    let residual_max_abs = tau
+        .iter()
+        .zip(signal)
+        .map(|(&tau, &value)| (value - model(tau, &parameters)).abs())
+        .fold(0.0, f64::max);
+    let fit = ReferenceSineFit {
+        frequency_hz,
+        amplitude,
+        phase_rad: wrap_phase(centered_phase + lever * frequency_hz),
+        offset,
+        frequency_std_hz: (variance * covariance[1][1]).max(0.0).sqrt(),
+        amplitude_std: (variance * covariance[0][0]).max(0.0).sqrt(),
+        phase_std_rad: (variance * phase_variance).max(0.0).sqrt(),
+        offset_std: (variance * covariance[3][3]).max(0.0).sqrt(),
+        residual_rms: (ssr / samples as f64).sqrt(),
+        residual_max_abs,
+        r_squared: if total > 0.0 { 1.0 - ssr / total } else { 0.0 },
+        samples,
+        iterations,
+        converged,
+    };
+    if !fit.frequency_hz.is_finite()
+        || fit.frequency_hz <= 0.0
+        || !fit.amplitude.is_finite()
+        || !fit.phase_rad.is_finite()
+    {
+        return Err(AnalysisError::new(
+            "non_finite_fit",
+            "reference fit produced a non-finite or non-positive frequency",
+        ));
+    }
+    Ok(fit)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+fn hann_window(length: usize) -> Vec<f64> {
+    let denominator = (length - 1) as f64;
+    (0..length)
+        .map(|index| 0.5 - 0.5 * (TAU * index as f64 / denominator).cos())
+        .collect()
+}
+
+fn fft_in_place(values: &mut [Complex]) {
+    let length = values.len();
+    debug_assert!(length.is_power_of_two());
+    let bits = length.trailing_zeros();
+    for index in 0..length {
+        let reversed = index.reverse_bits() >> (usize::BITS - bits);
+        if index < reversed {
+            values.swap(index, reversed);
+        }
+    }
+    let mut width = 2;
+    while width <= length {
+        let angle = -TAU / width as f64;
+        for start in (0..length).step_by(width) {
+            for offset in 0..width / 2 {
+                let (sin, cos) = (angle * offset as f64).sin_cos();
+                let even = values[start + offset];
+                let odd = values[start + offset + width / 2];
+                let twiddled = Complex {
+                    re: odd.re * cos - odd.im * sin,
+                    im: odd.re * sin + odd.im * cos,
+                };
+                values[start + offset] = Complex {
+                    re: even.re + twiddled.re,
+                    im: even.im + twiddled.im,
+                };
+                values[start + offset + width / 2] = Complex {
+                    re: even.re - twiddled.re,
+                    im: even.im - twiddled.im,
+                };
+            }
+        }
+        width *= 2;
+    }
+}
+
+fn interpolate_peak(spectrum: &[Complex], index: usize) -> Complex {
+    let center = spectrum[index];
+    if index + 1 >= spectrum.len() {
+        return center;
+    }
+    let (before, after) = (spectrum[index - 1], spectrum[index + 1]);
+    let (y_before, y_center, y_after) = (before.norm(), center.norm(), after.norm());
+    let denominator = y_before - 2.0 * y_center + y_after;
+    if y_center < y_before || y_center < y_after || denominator.abs() < 1.0e-12 {
+        return center;
+    }
+    let mut offset = 0.5 * (y_before - y_after) / denominator;
+    if offset.abs() > 1.0 {
+        offset = 0.0;
+    }
+    let quadratic = |before: f64, center: f64, after: f64| {
+        center
+            + 0.5 * (after - before) * offset
+            + (0.5 * (after + before) - center) * offset * offset
+    };
+    Complex {
+        re: quadratic(before.re, center.re, after.re),
+        im: quadratic(before.im, center.im, after.im),
+    }
+}
+
+fn model(tau: f64, parameters: &[f64; FIT_PARAMETERS]) -> f64 {
+    let [amplitude, frequency_hz, phase, offset] = *parameters;
+    amplitude * (TAU * frequency_hz * tau - phase).sin() + offset
+}
+
+fn sum_squared_residuals(tau: &[f64], signal: &[f64], parameters: &[f64; FIT_PARAMETERS]) -> f64 {
+    tau.iter()
+        .zip(signal)
+        .map(|(&tau, &value)| (value - model(tau, parameters)).powi(2))
+        .sum()
+}
+
+fn linear_seed(tau: &[f64], signal: &[f64], frequency_hz: f64) -> Result<[f64; FIT_PARAMETERS]> {
+    let mut normal = [[0.0; 3]; 3];
+    let mut rhs = [0.0; 3];
+    for (&tau, &value) in tau.iter().zip(signal) {
+        let (sin, cos) = (TAU * frequency_hz * tau).sin_cos();
+        let basis = [sin, cos, 1.0];
+        for row in 0..3 {
+            rhs[row] += basis[row] * value;
+            for column in 0..3 {
+                normal[row][column] += basis[row] * basis[column];
+            }
+        }
+    }
+    let [sin_weight, cos_weight, offset] = solve(normal, rhs).ok_or_else(|| {
+        AnalysisError::new(
+            "singular_fit",
+            "reference fit window does not resolve the seed frequency",
+        )
+    })?;
+    Ok([
+        sin_weight.hypot(cos_weight),
+        frequency_hz,
+        (-cos_weight).atan2(sin_weight),
+        offset,
+    ])
+}
+
+type Matrix = [[f64; FIT_PARAMETERS]; FIT_PARAMETERS];
+
+fn normal_equations(
+    tau: &[f64],
+    signal: &[f64],
+    parameters: &[f64; FIT_PARAMETERS],
+) -> (Matrix, [f64; FIT_PARAMETERS]) {
+    let [amplitude, frequency_hz, phase, _] = *parameters;
+    let mut normal = [[0.0; FIT_PARAMETERS]; FIT_PARAMETERS];
+    let mut gradient = [0.0; FIT_PARAMETERS];
+    for (&tau, &value) in tau.iter().zip(signal) {
+        let (sin, cos) = (TAU * frequency_hz * tau - phase).sin_cos();
+        let residual = value - model(tau, parameters);
+        let jacobian = [sin, amplitude * cos * TAU * tau, -amplitude * cos, 1.0];
+        for row in 0..FIT_PARAMETERS {
+            gradient[row] += jacobian[row] * residual;
+            for column in 0..FIT_PARAMETERS {
+                normal[row][column] += jacobian[row] * jacobian[column];
+            }
+        }
+    }
+    (normal, gradient)
+}
+
+fn solve<const N: usize>(mut matrix: [[f64; N]; N], mut rhs: [f64; N]) -> Option<[f64; N]> {
+    for column in 0..N {
+        let pivot = (column..N).max_by(|&left, &right| {
+            matrix[left][column]
+                .abs()
+                .total_cmp(&matrix[right][column].abs())
+        })?;
+        if matrix[pivot][column] == 0.0 || !matrix[pivot][column].is_finite() {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+        for row in column + 1..N {
+            let factor = matrix[row][column] / matrix[column][column];
+            let pivot_row = matrix[column];
+            for (value, pivot_value) in matrix[row].iter_mut().zip(pivot_row).skip(column) {
+                *value -= factor * pivot_value;
+            }
+            rhs[row] -= factor * rhs[column];
+        }
+    }
+    let mut solution = [0.0; N];
+    for row in (0..N).rev() {
+        let tail = (row + 1..N)
+            .map(|column| matrix[row][column] * solution[column])
+            .sum::<f64>();
+        solution[row] = (rhs[row] - tail) / matrix[row][row];
+    }
+    solution
+        .iter()
+        .all(|value| value.is_finite())
+        .then_some(solution)
+}
+
+fn invert(matrix: Matrix) -> Option<Matrix> {
+    let mut inverse = [[0.0; FIT_PARAMETERS]; FIT_PARAMETERS];
+    for column in 0..FIT_PARAMETERS {
+        let mut unit = [0.0; FIT_PARAMETERS];
+        unit[column] = 1.0;
+        let solution = solve(matrix, unit)?;
+        for row in 0..FIT_PARAMETERS {
+            inverse[row][column] = solution[row];
+        }
+    }
+    Some(inverse)
+}
+
+fn wrap_phase(phase: f64) -> f64 {
+    let wrapped = (phase + PI).rem_euclid(TAU) - PI;
+    if wrapped <= -PI {
+        wrapped + TAU
+    } else {
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(
+        samples: usize,
+        sample_rate_hz: f64,
+        frequency_hz: f64,
+        amplitude: f64,
+        phase_rad: f64,
+        offset: f64,
+    ) -> (Vec<f64>, Vec<f64>) {
+        let time_s = (0..samples)
+            .map(|index| index as f64 / sample_rate_hz)
+            .collect::<Vec<_>>();
+        let signal = time_s
+            .iter()
+            .map(|&time| amplitude * (TAU * frequency_hz * time - phase_rad).sin() + offset)
+            .collect();
+        (time_s, signal)
+    }
+
+    #[test]
+    fn spectrum_peak_recovers_a_bin_centered_sine() {
+        let sample_rate_hz = 100.0e6;
+        let frequency_hz = 50.0 * sample_rate_hz / 4_096.0;
+        let (_, signal) = sine(4_096, sample_rate_hz, frequency_hz, 1.75, 0.0, 0.3);
+
+        let peak = estimate_reference_spectrum_peak(1.0 / sample_rate_hz, &signal).unwrap();
+
+        assert!((peak.frequency_hz - frequency_hz).abs() < 1.0e-6);
+        assert!((peak.amplitude - 1.75).abs() < 1.0e-7);
+        assert!(peak.phase_rad.abs() < 2.0e-6);
+    }
+
+    #[test]
+    fn fit_recovers_off_bin_frequency_phase_and_offset() {
+        let (time_s, signal) = sine(20_000, 1.0e6, 12_345.6, 0.8, 2.9, -0.05);
+        let seed = estimate_reference_spectrum_peak(1.0e-6, &signal).unwrap();
+
+        let fit = fit_reference_sine(&time_s, &signal, seed.frequency_hz).unwrap();
+
+        assert!(fit.converged);
+        assert!((fit.frequency_hz - 12_345.6).abs() < 1.0e-6);
+        assert!((fit.amplitude - 0.8).abs() < 1.0e-9);
+        assert!((fit.phase_rad - 2.9).abs() < 1.0e-8);
+        assert!((fit.offset + 0.05).abs() < 1.0e-9);
+        assert!(fit.residual_rms < 1.0e-9);
+        assert!(fit.r_squared > 1.0 - 1.0e-12);
+    }
+
+    #[test]
+    fn fit_uncertainties_track_noise_level() {
+        let (time_s, mut signal) = sine(8_192, 1.0e6, 31_250.0, 1.0, -1.0, 0.0);
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        for value in &mut signal {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *value += 0.01 * ((state >> 11) as f64 / (1_u64 << 53) as f64 - 0.5);
+        }
+
+        let seed = estimate_reference_spectrum_peak(1.0e-6, &signal).unwrap();
+
+        let fit = fit_reference_sine(&time_s, &signal, seed.frequency_hz).unwrap();
+
+        assert!((fit.frequency_hz - 31_250.0).abs() < 5.0 * fit.frequency_std_hz + 1.0e-3);
+        assert!((fit.amplitude - 1.0).abs() < 5.0 * fit.amplitude_std);
+        assert!((fit.phase_rad + 1.0).abs() < 5.0 * fit.phase_std_rad);
+        assert!(fit.amplitude_std > 0.0 && fit.amplitude_std < 1.0e-3);
+        assert!((fit.residual_rms - 0.01 / 12.0_f64.sqrt()).abs() < 2.0e-4);
+    }
+
+    #[test]
+    fn rejects_constant_non_finite_and_short_inputs() {
+        assert_eq!(
+            estimate_reference_spectrum_peak(1.0e-8, &[0.3; 64])
+                .unwrap_err()
+                .code(),
+            "constant_signal"
+        );
+        let mut signal = vec![0.0; 64];
+        signal[3] = f64::NAN;
+        assert_eq!(
+            estimate_reference_spectrum_peak(1.0e-8, &signal)
+                .unwrap_err()
+                .code(),
+            "non_finite_signal"
+        );
+        assert_eq!(
+            fit_reference_sine(&[0.0, 1.0], &[0.0, 1.0], 1.0)
+                .unwrap_err()
+                .code(),
+            "signal_too_short"
+        );
+        assert_eq!(
+            fit_reference_sine(&[0.0; 8], &[0.0; 7], 1.0)
+                .unwrap_err()
+                .code(),
+            "length_mismatch"
+        );
+    }
+}
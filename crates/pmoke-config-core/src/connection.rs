@@ -3,6 +3,8 @@ use std::fmt;
 pub const DEFAULT_PROLOGIX_PORT: u16 = 1234;
 pub const DEFAULT_PROLOGIX_BAUD_RATE: u32 = 115_200;
 pub const DEFAULT_PROLOGIX_READ_TIMEOUT_MS: u16 = 3000;
+pub const DEFAULT_SIM_BENCH: &str = "default";
+pub const DEFAULT_VXI11_DEVICE: &str = "inst0";
 
 #[derive(Debug, Clone, Copy)]
 pub struct ConnectionDefaults {
@@ -23,9 +25,18 @@ pub enum ConnectionUri {
         host: String,
         port: u16,
     },
+    /// VXI-11 link; the core channel port comes from the host's portmapper.
+    Vxi11 {
+        host: String,
+        device: String,
+    },
     Visa {
         resource: String,
     },
+    /// Linux `usbtmc` kernel driver character device, e.g. `/dev/usbtmc0`.
+    UsbtmcDevice {
+        path: String,
+    },
     Gpib {
         board: u8,
         address: u8,
@@ -42,6 +53,11 @@ pub enum ConnectionUri {
         baud_rate: u32,
         read_timeout_ms: u16,
     },
+    /// In-process simulated instrument; instruments naming the same bench
+    /// share one trigger.
+    Sim {
+        bench: String,
+    },
 }
 
 impl ConnectionUri {
@@ -50,6 +66,10 @@ impl ConnectionUri {
             let (host, port) = parse_host_port(endpoint, None, "TCP")?;
             return Ok(Self::Tcp { host, port });
         }
+        if let Some(endpoint) = value.strip_prefix("vxi11://") {
+            let (host, device) = parse_vxi11_endpoint(endpoint)?;
+            return Ok(Self::Vxi11 { host, device });
+        }
         if let Some(resource) = value.strip_prefix("visa:") {
             let resource = resource.trim();
             if resource.is_empty() {
@@ -59,6 +79,18 @@ impl ConnectionUri {
                 resource: resource.to_string(),
             });
         }
+        if let Some(path) = value.strip_prefix("usbtmc://") {
+            let path = path.trim();
+            if !path.starts_with('/') || path.len() == 1 {
+                return Err(
+                    "USBTMC connection must be usbtmc:// followed by an absolute device path, e.g. usbtmc:///dev/usbtmc0"
+                        .to_string(),
+                );
+            }
+            return Ok(Self::UsbtmcDevice {
+                path: path.to_string(),
+            });
+        }
         if let Some(endpoint) = value.strip_prefix("gpib://") {
             let (board, address) = endpoint
                 .split_once('/')
@@ -109,6 +141,26 @@ impl ConnectionUri {
             });
         }
 
+        if let Some(bench) = value.strip_prefix("sim://") {
+            let bench = bench.trim();
+            if bench
+                .chars()
+                .any(|character| !(character.is_ascii_alphanumeric() || "-_.".contains(character)))
+            {
+                return Err(format!(
+                    "simulated bench name must use letters, digits, '-', '_' or '.': {bench}"
+                ));
+            }
+            return Ok(Self::Sim {
+                bench: if bench.is_empty() {
+                    DEFAULT_SIM_BENCH
+                } else {
+                    bench
+                }
+                .to_string(),
+            });
+        }
+
         Err(format!("unsupported connection string: {value}"))
     }
 }
@@ -117,7 +169,11 @@ impl fmt::Display for ConnectionUri {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Tcp { host, port } => write!(formatter, "tcp://{}:{port}", display_host(host)),
+            Self::Vxi11 { host, device } => {
+                write!(formatter, "vxi11://{}/{device}", display_host(host))
+            }
             Self::Visa { resource } => write!(formatter, "visa:{resource}"),
+            Self::UsbtmcDevice { path } => write!(formatter, "usbtmc://{path}"),
             Self::Gpib { board, address } => write!(formatter, "gpib://{board}/{address}"),
             Self::PrologixTcp {
                 host,
@@ -138,6 +194,7 @@ impl fmt::Display for ConnectionUri {
                 formatter,
                 "prologix-serial://{path}?addr={address}&baud_rate={baud_rate}&read_timeout_ms={read_timeout_ms}"
             ),
+            Self::Sim { bench } => write!(formatter, "sim://{bench}"),
         }
     }
 }
@@ -189,6 +246,43 @@ fn parse_host_port(
     Ok((host.to_string(), port))
 }
 
+fn parse_vxi11_endpoint(endpoint: &str) -> Result<(String, String), String> {
+    let (host, device) = if let Some(rest) = endpoint.strip_prefix('[') {
+        let (host, tail) = rest
+            .split_once(']')
+            .ok_or_else(|| "VXI-11 IPv6 host must be [address]".to_string())?;
+        match tail.strip_prefix('/') {
+            Some(device) => (host, device),
+            None if tail.is_empty() => (host, ""),
+            None => return Err("VXI-11 connection must be vxi11://host/device".to_string()),
+        }
+    } else {
+        let (host, device) = endpoint.split_once('/').unwrap_or((endpoint, ""));
+        if host.contains(':') {
+            return Err(
+                "VXI-11 host must not include a port; the portmapper assigns it".to_string(),
+            );
+        }
+        (host, device)
+    };
+    let host = host.trim();
+    if host.is_empty() {
+        return Err("VXI-11 host must not be empty".to_string());
+    }
+    let device = match device.trim() {
+        "" => DEFAULT_VXI11_DEVICE,
+        device
+            if device
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || ",_".contains(character)) =>
+        {
+            device
+        }
+        device => return Err(format!("invalid VXI-11 device name: {device}")),
+    };
+    Ok((host.to_string(), device.to_string()))
+}
+
 fn parse_port(value: &str, label: &str) -> Result<u16, String> {
     let port = value
         .parse::<u16>()
@@ -310,6 +404,14 @@ mod tests {
                 "prologix-serial:///dev/ttyUSB0?address=11",
                 "prologix-serial:///dev/ttyUSB0?addr=11&baud_rate=115200&read_timeout_ms=3000",
             ),
+            ("sim://", "sim://default"),
+            ("sim://bench-2", "sim://bench-2"),
+            ("vxi11://192.0.2.30", "vxi11://192.0.2.30/inst0"),
+            (
+                "vxi11://[2001:db8::1]/gpib0,5",
+                "vxi11://[2001:db8::1]/gpib0,5",
+            ),
+            ("usbtmc:///dev/usbtmc0", "usbtmc:///dev/usbtmc0"),
         ];
         for (input, expected) in cases {
             assert_eq!(
@@ -319,5 +421,14 @@ mod tests {
                 expected
             );
         }
+        for invalid in [
+            "sim://a/b",
+            "vxi11://host:111/inst0",
+            "vxi11:///inst0",
+            "usbtmc://dev/usbtmc0",
+            "usbtmc:///",
+        ] {
+            assert!(ConnectionUri::parse(invalid, ConnectionDefaults::default()).is_err());
+        }
     }
 }
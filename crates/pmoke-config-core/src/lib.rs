@@ -4,7 +4,11 @@ pub mod connection;
 mod model;
 
 use connection::{ConnectionDefaults, ConnectionUri};
-use model::{ConfigV5, SensorScale, Window};
+pub use model::{CoilCalibration, CoilCompensation, KerrFieldBackground, KerrModulationDepth};
+use model::{
+    ConfigV5, Filter, GeneratorSetup, MultimeterFunction, PhaseDrift, ScopeSetup, SensorScale,
+    Window,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::ops::Range;
@@ -14,6 +18,17 @@ pub const CONFIG_SCHEMA_VERSION: u32 = 5;
 pub const MAX_CONFIG_BYTES: usize = 1_048_576;
 pub const CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const CORE_COMMIT: &str = env!("PMOKE_SOURCE_COMMIT");
+pub const DEFAULT_LOCKIN_HARMONICS: [usize; 6] = [1, 2, 3, 4, 5, 6];
+pub const MAX_LOCKIN_HARMONIC: usize = 64;
+pub const DEFAULT_LOCKIN_RC_ORDER: usize = 4;
+pub const MAX_LOCKIN_RC_ORDER: usize = 8;
+pub const MAX_PULSE_BASELINE_ORDER: usize = 3;
+pub const MAX_KERR_FIELD_BACKGROUND_ORDER: usize = 7;
+pub const DEFAULT_KERR_MODULATION_DEPTH_RAD: f64 = 0.92;
+pub const DEFAULT_TRIGGER_TIMEOUT_S: f64 = 10.0;
+pub const DEFAULT_TRIGGER_POLL_INTERVAL_S: f64 = 0.1;
+/// Half the first zero of J1; the standard Kerr ratio J2(2φm)/J1(2φm) diverges there.
+pub const MAX_KERR_MODULATION_DEPTH_RAD: f64 = 1.915_852_985_1;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -36,6 +51,7 @@ pub enum DiagnosticCode {
     UnsupportedTransport,
     PlatformNotChecked,
     DuplicateChannel,
+    DuplicateLabel,
     ChannelOutOfRange,
     EmptyValue,
     InvalidScale,
@@ -283,7 +299,7 @@ fn validate_v5(config: &mut ConfigV5, report: &mut ValidationReport) {
             ),
         );
     }
-    if config.scope.model != "DHO5108" {
+    if !matches!(config.scope.model.as_str(), "DHO5108" | "InfiniiVision") {
         error(
             report,
             DiagnosticCode::UnsupportedModel,
@@ -303,11 +319,32 @@ fn validate_v5(config: &mut ConfigV5, report: &mut ValidationReport) {
             }
             config.scope.connection = connection.to_string();
         }
+        Ok(connection @ ConnectionUri::UsbtmcDevice { .. }) => {
+            warning(
+                report,
+                DiagnosticCode::PlatformNotChecked,
+                "scope.connection",
+                "the Linux usbtmc driver and device permissions are not checked in the browser",
+            );
+            config.scope.connection = connection.to_string();
+        }
+        Ok(connection @ ConnectionUri::Sim { .. }) if config.scope.model == "DHO5108" => {
+            config.scope.connection = connection.to_string();
+        }
+        Ok(ConnectionUri::Sim { .. }) => error(
+            report,
+            DiagnosticCode::UnsupportedTransport,
+            "scope.connection",
+            "sim:// only emulates the DHO5108",
+        ),
         Ok(_) => error(
             report,
             DiagnosticCode::UnsupportedTransport,
             "scope.connection",
-            "DHO5108 requires direct TCP/IP or VISA",
+            format!(
+                "{} requires direct TCP/IP, VISA, or USBTMC",
+                config.scope.model
+            ),
         ),
         Err(message) => error(
             report,
@@ -316,6 +353,31 @@ fn validate_v5(config: &mut ConfigV5, report: &mut ValidationReport) {
             message,
         ),
     }
+    let (timeout, interval) = (
+        config.scope.trigger_timeout_s,
+        config.scope.trigger_poll_interval_s,
+    );
+    if !(timeout.is_finite() && timeout > 0.0) {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "scope.trigger_timeout_s",
+            format!("scope.trigger_timeout_s must be finite and positive (got {timeout})"),
+        );
+    }
+    if !(interval.is_finite() && interval > 0.0 && interval <= timeout) {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "scope.trigger_poll_interval_s",
+            format!(
+                "scope.trigger_poll_interval_s must be positive and no longer than scope.trigger_timeout_s (got {interval})"
+            ),
+        );
+    }
+    if let Some(setup) = &config.scope.setup {
+        validate_scope_setup(setup, report);
+    }
 
     if let Some(generator) = &mut config.generator {
         if generator.model != "WF1946B" {
@@ -340,11 +402,14 @@ fn validate_v5(config: &mut ConfigV5, report: &mut ValidationReport) {
                 );
                 generator.connection = connection.to_string();
             }
+            Ok(connection @ ConnectionUri::Sim { .. }) => {
+                generator.connection = connection.to_string();
+            }
             Ok(_) => error(
                 report,
                 DiagnosticCode::UnsupportedTransport,
                 "generator.connection",
-                "WF1946B requires GPIB or Prologix",
+                "WF1946B requires GPIB, Prologix, or sim://",
             ),
             Err(message) => error(
                 report,
@@ -353,7 +418,11 @@ fn validate_v5(config: &mut ConfigV5, report: &mut ValidationReport) {
                 message,
             ),
         }
+        if let Some(setup) = &generator.setup {
+            validate_generator_setup(setup, report);
+        }
     }
+    validate_multimeters(config, report);
 
     validate_channels(config, report);
     validate_windows(config, report);
@@ -388,13 +457,15 @@ fn validate_v5(config: &mut ConfigV5, report: &mut ValidationReport) {
         );
     }
 
-    if config.phase.offsets.len() != 6 {
+    validate_harmonics(config, report);
+    if config.phase.offsets.len() != config.lockin.harmonics.len() {
         error(
             report,
             DiagnosticCode::InvalidCount,
             "phase.offsets",
             format!(
-                "phase.offsets must have length 6 (got {})",
+                "phase.offsets must have one entry per lock-in harmonic ({} expected, got {})",
+                config.lockin.harmonics.len(),
                 config.phase.offsets.len()
             ),
         );
@@ -416,6 +487,23 @@ fn validate_v5(config: &mut ConfigV5, report: &mut ValidationReport) {
             ),
         }
     }
+    if let PhaseDrift::PiecewiseLinear { window, step } = config.phase.drift {
+        for (path, value) in [
+            ("phase.drift.window", Some(window)),
+            ("phase.drift.step", step),
+        ] {
+            if let Some(value) = value
+                && (!value.is_finite() || value <= 0.0)
+            {
+                error(
+                    report,
+                    DiagnosticCode::InvalidRange,
+                    path,
+                    format!("{path} must be finite and positive (got {value})"),
+                );
+            }
+        }
+    }
     if !config.kerr.factor.is_finite() {
         error(
             report,
@@ -424,6 +512,74 @@ fn validate_v5(config: &mut ConfigV5, report: &mut ValidationReport) {
             "kerr.factor must be finite",
         );
     }
+    if let KerrModulationDepth::Fixed(depth) = config.kerr.modulation_depth
+        && !(depth.is_finite() && depth > 0.0 && depth < MAX_KERR_MODULATION_DEPTH_RAD)
+    {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "kerr.modulation_depth",
+            format!(
+                "kerr.modulation_depth must be in (0, {MAX_KERR_MODULATION_DEPTH_RAD}) rad (got {depth})"
+            ),
+        );
+    }
+    if let Some(width) = config.kerr.field_bin_width
+        && !(width.is_finite() && width > 0.0)
+    {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "kerr.field_bin_width",
+            format!("kerr.field_bin_width must be finite and positive (got {width})"),
+        );
+    }
+    if config
+        .kerr
+        .background_run
+        .as_deref()
+        .is_some_and(|run| run.trim().is_empty())
+    {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "kerr.background_run",
+            "kerr.background_run must name a run directory",
+        );
+    }
+    if let Some(background) = &config.kerr.field_background {
+        let order = background.order();
+        if order % 2 == 0 || order > MAX_KERR_FIELD_BACKGROUND_ORDER {
+            error(
+                report,
+                DiagnosticCode::InvalidRange,
+                "kerr.field_background.order",
+                format!(
+                    "kerr.field_background.order must be odd and at most {MAX_KERR_FIELD_BACKGROUND_ORDER} (got {order})"
+                ),
+            );
+        }
+        if background.ranges().is_empty() {
+            error(
+                report,
+                DiagnosticCode::InvalidRange,
+                "kerr.field_background.ranges",
+                "kerr.field_background.ranges must list at least one field range",
+            );
+        }
+        for (index, [low, high]) in background.ranges().iter().enumerate() {
+            if !(low.is_finite() && high.is_finite() && low < high) {
+                error(
+                    report,
+                    DiagnosticCode::InvalidRange,
+                    format!("kerr.field_background.ranges[{index}]"),
+                    format!(
+                        "kerr.field_background.ranges[{index}] must be finite with low < high (got [{low}, {high}])"
+                    ),
+                );
+            }
+        }
+    }
     if !config
         .sensors
         .iter()
@@ -449,6 +605,55 @@ fn validate_v5(config: &mut ConfigV5, report: &mut ValidationReport) {
     }
 }
 
+fn validate_harmonics(config: &ConfigV5, report: &mut ValidationReport) {
+    let harmonics = &config.lockin.harmonics;
+    if harmonics.is_empty() {
+        error(
+            report,
+            DiagnosticCode::EmptyValue,
+            "lockin.harmonics",
+            "lockin.harmonics must list at least one harmonic",
+        );
+        return;
+    }
+    for (index, &harmonic) in harmonics.iter().enumerate() {
+        if !(1..=MAX_LOCKIN_HARMONIC).contains(&harmonic) {
+            error(
+                report,
+                DiagnosticCode::InvalidRange,
+                format!("lockin.harmonics[{index}]"),
+                format!("harmonic must be in 1..={MAX_LOCKIN_HARMONIC} (got {harmonic})"),
+            );
+        }
+    }
+    if harmonics.windows(2).any(|pair| pair[0] >= pair[1]) {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "lockin.harmonics",
+            "lockin.harmonics must be strictly increasing without duplicates",
+        );
+    }
+    let required = config.kerr.required_harmonics();
+    let missing = required
+        .iter()
+        .filter(|harmonic| !harmonics.contains(harmonic))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "lockin.harmonics",
+            format!(
+                "{} requires harmonics {:?}; missing {:?}",
+                config.kerr.requirement_source(),
+                required,
+                missing
+            ),
+        );
+    }
+}
+
 fn validate_channels(config: &ConfigV5, report: &mut ValidationReport) {
     let mut assignments = BTreeMap::<u8, String>::new();
     let mut assign = |channel: u8, path: String, report: &mut ValidationReport| {
@@ -518,6 +723,47 @@ fn validate_channels(config: &ConfigV5, report: &mut ValidationReport) {
                     );
                 }
             }
+            SensorScale::Coil(coil) => {
+                if !coil.area_turns.is_finite() || coil.area_turns <= 0.0 {
+                    error(
+                        report,
+                        DiagnosticCode::InvalidScale,
+                        format!("{base}.scale.area_turns"),
+                        "coil area_turns must be finite and positive",
+                    );
+                }
+                if !coil.gain.is_finite() || coil.gain <= 0.0 {
+                    error(
+                        report,
+                        DiagnosticCode::InvalidScale,
+                        format!("{base}.scale.gain"),
+                        "coil gain must be finite and positive",
+                    );
+                }
+                if !matches!(coil.polarity, -1 | 1) {
+                    error(
+                        report,
+                        DiagnosticCode::InvalidScale,
+                        format!("{base}.scale.polarity"),
+                        "coil polarity must be -1 or 1",
+                    );
+                }
+                if let Some(compensation) = coil.compensation {
+                    assign(
+                        compensation.channel,
+                        format!("{base}.scale.compensation.channel"),
+                        report,
+                    );
+                    if !compensation.balance.is_finite() {
+                        error(
+                            report,
+                            DiagnosticCode::InvalidScale,
+                            format!("{base}.scale.compensation.balance"),
+                            "coil compensation balance must be finite",
+                        );
+                    }
+                }
+            }
             SensorScale::Factor(_) => {}
         }
     }
@@ -559,15 +805,73 @@ fn validate_windows(config: &ConfigV5, report: &mut ValidationReport) {
             "pulse background windows must not overlap",
         );
     }
+    if let Some(order) = config.pulse.baseline.order()
+        && !(1..=MAX_PULSE_BASELINE_ORDER).contains(&order)
+    {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "pulse.baseline.order",
+            format!(
+                "pulse.baseline.order must be between 1 and {MAX_PULSE_BASELINE_ORDER} (got {order})"
+            ),
+        );
+    }
 }
 
 fn validate_filter(config: &ConfigV5, report: &mut ValidationReport) {
     let filter = &config.lockin.filter;
-    positive_f64(
-        report,
-        "lockin.filter.half_window_cycles",
-        filter.half_window_cycles(),
-    );
+    if let Some(half_window_cycles) = filter.half_window_cycles() {
+        positive_f64(
+            report,
+            "lockin.filter.half_window_cycles",
+            half_window_cycles,
+        );
+    }
+    if let Filter::RcCascade { order, .. } = filter
+        && !(1..=MAX_LOCKIN_RC_ORDER).contains(order)
+    {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "lockin.filter.order",
+            format!(
+                "lockin.filter.order must be between 1 and {MAX_LOCKIN_RC_ORDER} (got {order})"
+            ),
+        );
+    }
+    let Some(cutoff) = filter.cutoff() else {
+        return;
+    };
+    let selected = [
+        ("lockin.filter.cutoff_hz", cutoff.cutoff_hz),
+        ("lockin.filter.cutoff_ref_ratio", cutoff.cutoff_ref_ratio),
+        ("lockin.filter.enbw_hz", cutoff.enbw_hz),
+    ]
+    .into_iter()
+    .filter_map(|(path, value)| value.map(|value| (path, value)))
+    .collect::<Vec<_>>();
+    for (path, value) in &selected {
+        positive_f64(report, path, *value);
+    }
+    match selected.as_slice() {
+        [] => warning(
+            report,
+            DiagnosticCode::ImplicitFallback,
+            "lockin.filter",
+            format!(
+                "{} has no cutoff; the cutoff is chosen to match the ENBW of a one-cycle boxcar (half the reference frequency)",
+                filter.kind()
+            ),
+        ),
+        [_] => {}
+        [_, second, ..] => error(
+            report,
+            DiagnosticCode::MutuallyExclusive,
+            second.0,
+            "set only one of lockin.filter.cutoff_hz, cutoff_ref_ratio, or enbw_hz",
+        ),
+    }
 }
 
 fn check_window(report: &mut ValidationReport, path: &str, window: Window) {
@@ -594,6 +898,200 @@ fn check_window(report: &mut ValidationReport, path: &str, window: Window) {
     }
 }
 
+fn validate_multimeters(config: &mut ConfigV5, report: &mut ValidationReport) {
+    let mut labels = Vec::new();
+    for (index, multimeter) in config.multimeters.iter_mut().enumerate() {
+        let prefix = format!("multimeters[{index}]");
+        let label = multimeter.label.trim().to_string();
+        if label.is_empty() {
+            error(
+                report,
+                DiagnosticCode::EmptyValue,
+                format!("{prefix}.label"),
+                format!("{prefix}.label must not be empty"),
+            );
+        } else if labels.contains(&label) {
+            error(
+                report,
+                DiagnosticCode::DuplicateLabel,
+                format!("{prefix}.label"),
+                format!("multimeter label {label:?} appears more than once"),
+            );
+        } else {
+            labels.push(label);
+        }
+        if !matches!(multimeter.model.as_str(), "Keithley2000" | "Keithley2010") {
+            error(
+                report,
+                DiagnosticCode::UnsupportedModel,
+                format!("{prefix}.model"),
+                format!("unsupported multimeter model: {}", multimeter.model),
+            );
+        }
+        let path = format!("{prefix}.connection");
+        match ConnectionUri::parse(&multimeter.connection, ConnectionDefaults::default()) {
+            Ok(
+                connection @ (ConnectionUri::Gpib { .. }
+                | ConnectionUri::PrologixTcp { .. }
+                | ConnectionUri::PrologixSerial { .. }),
+            ) => {
+                warning(
+                    report,
+                    DiagnosticCode::PlatformNotChecked,
+                    &path,
+                    "transport feature, driver, and hardware reachability are not checked in the browser",
+                );
+                multimeter.connection = connection.to_string();
+            }
+            Ok(_) => error(
+                report,
+                DiagnosticCode::UnsupportedTransport,
+                &path,
+                "Keithley multimeters require GPIB or Prologix",
+            ),
+            Err(message) => error(report, DiagnosticCode::InvalidConnection, &path, message),
+        }
+        let ranged = !matches!(
+            multimeter.function,
+            MultimeterFunction::Temperature | MultimeterFunction::Frequency
+        );
+        if let Some(range) = multimeter.range {
+            if !ranged {
+                error(
+                    report,
+                    DiagnosticCode::InvalidRange,
+                    format!("{prefix}.range"),
+                    format!("{prefix}.range is not supported for this function"),
+                );
+            } else if !(range.is_finite() && range > 0.0) {
+                error(
+                    report,
+                    DiagnosticCode::InvalidRange,
+                    format!("{prefix}.range"),
+                    format!("{prefix}.range must be finite and positive (got {range})"),
+                );
+            }
+        }
+        if multimeter.thermocouple.is_some()
+            && multimeter.function != MultimeterFunction::Temperature
+        {
+            error(
+                report,
+                DiagnosticCode::MutuallyExclusive,
+                format!("{prefix}.thermocouple"),
+                format!("{prefix}.thermocouple requires function = \"temperature\""),
+            );
+        }
+    }
+}
+
+fn validate_generator_setup(setup: &GeneratorSetup, report: &mut ValidationReport) {
+    for (path, value) in [
+        ("generator.setup.frequency_hz", setup.frequency_hz),
+        ("generator.setup.amplitude_vpp", setup.amplitude_vpp),
+    ] {
+        if let Some(value) = value.filter(|value| !(value.is_finite() && *value > 0.0)) {
+            error(
+                report,
+                DiagnosticCode::InvalidRange,
+                path,
+                format!("{path} must be finite and positive (got {value})"),
+            );
+        }
+    }
+    if let Some(offset) = setup.offset_v.filter(|offset| !offset.is_finite()) {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "generator.setup.offset_v",
+            format!("generator.setup.offset_v must be finite (got {offset})"),
+        );
+    }
+    if setup.burst_cycles == Some(0) {
+        error(
+            report,
+            DiagnosticCode::InvalidCount,
+            "generator.setup.burst_cycles",
+            "generator.setup.burst_cycles must be at least 1",
+        );
+    }
+}
+
+fn validate_scope_setup(setup: &ScopeSetup, report: &mut ValidationReport) {
+    if let Some(scale) = setup.timebase_scale
+        && !(scale.is_finite() && scale > 0.0)
+    {
+        error(
+            report,
+            DiagnosticCode::InvalidRange,
+            "scope.setup.timebase_scale",
+            format!("scope.setup.timebase_scale must be finite and positive (got {scale})"),
+        );
+    }
+    if let Some(depth) = setup.memory_depth {
+        positive_usize(report, "scope.setup.memory_depth", depth);
+    }
+    for (path, value) in [
+        ("scope.setup.timebase_offset", setup.timebase_offset),
+        ("scope.setup.trigger_level", setup.trigger_level),
+    ] {
+        if let Some(value) = value.filter(|value| !value.is_finite()) {
+            error(
+                report,
+                DiagnosticCode::InvalidRange,
+                path,
+                format!("{path} must be finite (got {value})"),
+            );
+        }
+    }
+    let mut seen = Vec::new();
+    for (index, channel) in setup.channels.iter().enumerate() {
+        let path = format!("scope.setup.channels[{index}].channel");
+        if !(1..=8).contains(&channel.channel) {
+            error(
+                report,
+                DiagnosticCode::ChannelOutOfRange,
+                &path,
+                format!("{path} must be between 1 and 8 (got {})", channel.channel),
+            );
+        } else if seen.contains(&channel.channel) {
+            error(
+                report,
+                DiagnosticCode::DuplicateChannel,
+                &path,
+                format!(
+                    "channel {} appears more than once in scope.setup.channels",
+                    channel.channel
+                ),
+            );
+        } else {
+            seen.push(channel.channel);
+        }
+        if let Some(scale) = channel.scale
+            && !(scale.is_finite() && scale > 0.0)
+        {
+            error(
+                report,
+                DiagnosticCode::InvalidScale,
+                format!("scope.setup.channels[{index}].scale"),
+                format!(
+                    "scope.setup.channels[{index}].scale must be finite and positive (got {scale})"
+                ),
+            );
+        }
+        if let Some(offset) = channel.offset
+            && !offset.is_finite()
+        {
+            error(
+                report,
+                DiagnosticCode::InvalidRange,
+                format!("scope.setup.channels[{index}].offset"),
+                format!("scope.setup.channels[{index}].offset must be finite (got {offset})"),
+            );
+        }
+    }
+}
+
 fn positive_usize(report: &mut ValidationReport, path: &str, value: usize) {
     if value == 0 {
         error(
@@ -791,6 +1289,89 @@ factor = -1.0
         }));
     }
 
+    #[test]
+    fn scope_setup_is_normalized_and_range_checked() {
+        let setup = "[scope.setup]\ntimebase_scale = 0.002\ntrigger_source = \"ext\"\n\
+                     [[scope.setup.channels]]\nchannel = 3\nscale = 0.5\nbandwidth_limit = \"20mhz\"\n[data]";
+        let report = validate_config_toml(&VALID.replacen("[data]", setup, 1));
+        assert!(report.valid, "{:#?}", report.diagnostics);
+        assert!(
+            report
+                .normalized_toml
+                .unwrap()
+                .contains("bandwidth_limit = \"20mhz\"")
+        );
+
+        let report = validate_config_toml(
+            &VALID.replacen(
+                "[data]",
+                &setup
+                    .replace("channel = 3", "channel = 9")
+                    .replace("scale = 0.5", "scale = 0.0"),
+                1,
+            ),
+        );
+        assert!(report.diagnostics.iter().any(|item| {
+            item.code == DiagnosticCode::ChannelOutOfRange
+                && item.path.as_deref() == Some("scope.setup.channels[0].channel")
+        }));
+        assert!(report.diagnostics.iter().any(|item| {
+            item.code == DiagnosticCode::InvalidScale
+                && item.path.as_deref() == Some("scope.setup.channels[0].scale")
+        }));
+    }
+
+    #[test]
+    fn generator_setup_is_normalized_and_range_checked() {
+        let setup = "[generator]\nmodel = \"WF1946B\"\nconnection = \"gpib://0/2\"\n\
+                     [generator.setup]\nwaveform = \"square\"\nfrequency_hz = 1000.0\nburst_cycles = 1\n[data]";
+        let report = validate_config_toml(&VALID.replacen("[data]", setup, 1));
+        assert!(report.valid, "{:#?}", report.diagnostics);
+        assert!(
+            report
+                .normalized_toml
+                .unwrap()
+                .contains("waveform = \"square\"")
+        );
+
+        let report = validate_config_toml(
+            &VALID.replacen(
+                "[data]",
+                &setup
+                    .replace("frequency_hz = 1000.0", "frequency_hz = -1.0")
+                    .replace("burst_cycles = 1", "burst_cycles = 0"),
+                1,
+            ),
+        );
+        assert!(report.diagnostics.iter().any(|item| {
+            item.code == DiagnosticCode::InvalidRange
+                && item.path.as_deref() == Some("generator.setup.frequency_hz")
+        }));
+        assert!(report.diagnostics.iter().any(|item| {
+            item.code == DiagnosticCode::InvalidCount
+                && item.path.as_deref() == Some("generator.setup.burst_cycles")
+        }));
+    }
+
+    #[test]
+    fn multimeters_are_normalized_and_checked() {
+        let multimeters = "[[multimeters]]\nlabel = \"sample\"\nmodel = \"Keithley2010\"\n\
+                           connection = \"gpib://0/16\"\nfunction = \"temperature\"\nthermocouple = \"k\"\n[data]";
+        let report = validate_config_toml(&VALID.replacen("[data]", multimeters, 1));
+        assert!(report.valid, "{:#?}", report.diagnostics);
+        assert!(report.normalized_toml.unwrap().contains("[[multimeters]]"));
+
+        let report = validate_config_toml(&VALID.replacen(
+            "[data]",
+            &multimeters.replace("\"temperature\"", "\"dc_volts\""),
+            1,
+        ));
+        assert!(report.diagnostics.iter().any(|item| {
+            item.code == DiagnosticCode::MutuallyExclusive
+                && item.path.as_deref() == Some("multimeters[0].thermocouple")
+        }));
+    }
+
     #[test]
     fn syntax_diagnostic_has_a_source_span() {
         let report = validate_config_toml("version = 5\n[scope\n");
@@ -856,6 +1437,93 @@ factor = -1.0
         }
     }
 
+    #[test]
+    fn harmonics_default_to_one_through_six_and_drive_offset_count() {
+        let report = validate_config_toml(VALID);
+        let normalized: toml::Value = toml::from_str(&report.normalized_toml.unwrap()).unwrap();
+        assert_eq!(
+            normalized["lockin"]["harmonics"],
+            toml::Value::try_from([1, 2, 3, 4, 5, 6]).unwrap()
+        );
+
+        let reduced = VALID
+            .replace(
+                "stride_samples = 100\nfilter",
+                "stride_samples = 100\nharmonics = [1, 2, 3, 4, 6, 8]\nfilter",
+            )
+            .replace("offsets = [0, 0, 0, 0, 0, 0]", "offsets = [0, 0, 0, 0, 0]");
+        let report = validate_config_toml(&reduced);
+        assert!(report.diagnostics.iter().any(|item| {
+            item.code == DiagnosticCode::InvalidCount
+                && item.path.as_deref() == Some("phase.offsets")
+        }));
+        let report = validate_config_toml(
+            &reduced.replace("offsets = [0, 0, 0, 0, 0]", "offsets = [0, 0, 0, 0, 0, 0]"),
+        );
+        assert!(report.valid, "{:#?}", report.diagnostics);
+    }
+
+    #[test]
+    fn rejects_unordered_out_of_range_or_insufficient_harmonics() {
+        for (harmonics, path) in [
+            ("[]", "lockin.harmonics"),
+            ("[2, 1, 3, 4, 6]", "lockin.harmonics"),
+            ("[2, 2, 3, 4, 6]", "lockin.harmonics"),
+            ("[0, 2, 3, 4, 6]", "lockin.harmonics[0]"),
+            ("[2, 3, 4, 6, 65]", "lockin.harmonics[4]"),
+            ("[1, 2, 3, 4, 5]", "lockin.harmonics"),
+        ] {
+            let report = validate_config_toml(&VALID.replace(
+                "stride_samples = 100\nfilter",
+                &format!("stride_samples = 100\nharmonics = {harmonics}\nfilter"),
+            ));
+            assert!(!report.valid, "unexpectedly accepted {harmonics}");
+            assert!(
+                report
+                    .diagnostics
+                    .iter()
+                    .any(|item| item.path.as_deref() == Some(path)),
+                "{harmonics}: {:#?}",
+                report.diagnostics
+            );
+        }
+    }
+
+    #[test]
+    fn kerr_modulation_depth_is_fixed_radians_or_harmonics_estimate() {
+        let report = validate_config_toml(VALID);
+        let normalized: toml::Value = toml::from_str(&report.normalized_toml.unwrap()).unwrap();
+        assert_eq!(
+            normalized["kerr"]["modulation_depth"].as_float(),
+            Some(DEFAULT_KERR_MODULATION_DEPTH_RAD)
+        );
+
+        let standard = VALID.replace("method = \"harmonics\"", "method = \"standard\"");
+        let report = validate_config_toml(&format!("{standard}modulation_depth = 1.05\n"));
+        assert!(report.valid, "{:#?}", report.diagnostics);
+        for depth in ["0", "-0.5", "2.0"] {
+            let report = validate_config_toml(&format!("{standard}modulation_depth = {depth}\n"));
+            assert!(report.diagnostics.iter().any(|item| {
+                item.code == DiagnosticCode::InvalidRange
+                    && item.path.as_deref() == Some("kerr.modulation_depth")
+            }));
+        }
+        let report = validate_config_toml(&format!("{standard}modulation_depth = \"bessel\"\n"));
+        assert!(!report.valid);
+
+        let estimated = format!("{standard}modulation_depth = \"harmonics\"\n");
+        assert!(validate_config_toml(&estimated).valid);
+        let report = validate_config_toml(&estimated.replace(
+            "stride_samples = 100\nfilter",
+            "stride_samples = 100\nharmonics = [1, 2, 3, 4, 5]\nfilter",
+        ));
+        assert!(report.diagnostics.iter().any(|item| {
+            item.path.as_deref() == Some("lockin.harmonics")
+                && item.message.contains("modulation_depth = \"harmonics\"")
+                && item.message.contains("missing [6]")
+        }));
+    }
+
     #[test]
     fn json_report_is_deterministic_and_round_trips() {
         let first = validate_config_toml_json(VALID);
@@ -7,6 +7,8 @@ pub(crate) struct ConfigV5 {
     pub scope: Scope,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub generator: Option<Generator>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub multimeters: Vec<Multimeter>,
     pub data: Data,
     #[serde(default)]
     pub sensors: Vec<Sensor>,
@@ -24,6 +26,98 @@ pub(crate) struct ConfigV5 {
 pub(crate) struct Scope {
     pub model: String,
     pub connection: String,
+    #[serde(default = "default_trigger_timeout_s")]
+    pub trigger_timeout_s: f64,
+    #[serde(default = "default_trigger_poll_interval_s")]
+    pub trigger_poll_interval_s: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup: Option<ScopeSetup>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ScopeSetup {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timebase_scale: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timebase_offset: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_depth: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_source: Option<ScopeTriggerSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_level: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_slope: Option<ScopeTriggerSlope>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<ScopeChannelSetup>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ScopeChannelSetup {
+    pub channel: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coupling: Option<ScopeCoupling>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bandwidth_limit: Option<ScopeBandwidthLimit>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ScopeTriggerSource {
+    Ch1,
+    Ch2,
+    Ch3,
+    Ch4,
+    Ch5,
+    Ch6,
+    Ch7,
+    Ch8,
+    Ext,
+    AcLine,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ScopeTriggerSlope {
+    Rising,
+    Falling,
+    Either,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ScopeCoupling {
+    Dc,
+    Ac,
+    Gnd,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub(crate) enum ScopeBandwidthLimit {
+    #[serde(rename = "off")]
+    Off,
+    #[serde(rename = "20mhz")]
+    Mhz20,
+    #[serde(rename = "25mhz")]
+    Mhz25,
+    #[serde(rename = "100mhz")]
+    Mhz100,
+    #[serde(rename = "250mhz")]
+    Mhz250,
+}
+
+fn default_trigger_timeout_s() -> f64 {
+    crate::DEFAULT_TRIGGER_TIMEOUT_S
+}
+
+fn default_trigger_poll_interval_s() -> f64 {
+    crate::DEFAULT_TRIGGER_POLL_INTERVAL_S
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -31,6 +125,85 @@ pub(crate) struct Scope {
 pub(crate) struct Generator {
     pub model: String,
     pub connection: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup: Option<GeneratorSetup>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct GeneratorSetup {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waveform: Option<GeneratorWaveform>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_hz: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amplitude_vpp: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_v: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst_cycles: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_source: Option<GeneratorTriggerSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GeneratorWaveform {
+    Sine,
+    Square,
+    Pulse,
+    Ramp,
+    Noise,
+    Dc,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GeneratorTriggerSource {
+    Bus,
+    External,
+    Internal,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Multimeter {
+    pub label: String,
+    pub model: String,
+    pub connection: String,
+    pub function: MultimeterFunction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thermocouple: Option<Thermocouple>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MultimeterFunction {
+    DcVolts,
+    AcVolts,
+    DcCurrent,
+    AcCurrent,
+    Resistance,
+    FourWireResistance,
+    Temperature,
+    Frequency,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Thermocouple {
+    J,
+    K,
+    N,
+    T,
+    E,
+    R,
+    S,
+    B,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -72,6 +245,46 @@ pub(crate) struct Sensor {
 pub(crate) enum SensorScale {
     Factor(FactorScale),
     MaxAbs(MaxAbsScale),
+    Coil(CoilCalibration),
+}
+
+/// Pickup-coil calibration that converts recorded volt-seconds into tesla.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CoilCalibration {
+    /// Effective area-turns product NA_eff in m².
+    pub area_turns: f64,
+    /// Recorded volts per coil volt, for example 0.1 behind a 10:1 divider.
+    #[serde(default = "default_coil_gain")]
+    pub gain: f64,
+    #[serde(default = "default_coil_polarity")]
+    pub polarity: i8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compensation: Option<CoilCompensation>,
+}
+
+/// Second pickup coil whose recorded voltage, times `balance`, is subtracted
+/// from the main coil before integration.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CoilCompensation {
+    pub channel: u8,
+    pub balance: f64,
+}
+
+impl CoilCalibration {
+    /// Tesla per recorded volt-second: `polarity / (gain · area_turns)`.
+    pub fn tesla_per_volt_second(&self) -> f64 {
+        f64::from(self.polarity) / (self.gain * self.area_turns)
+    }
+}
+
+fn default_coil_gain() -> f64 {
+    1.0
+}
+
+fn default_coil_polarity() -> i8 {
+    1
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -99,6 +312,41 @@ pub(crate) struct Window {
 pub(crate) struct Pulse {
     pub background_before: Window,
     pub background_after: Window,
+    #[serde(default, skip_serializing_if = "PulseBaseline::is_constant")]
+    pub baseline: PulseBaseline,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum PulseBaseline {
+    #[default]
+    Constant,
+    Linear,
+    Polynomial {
+        order: usize,
+    },
+    EndpointConstrained {
+        #[serde(default = "default_endpoint_baseline_order")]
+        order: usize,
+    },
+}
+
+impl PulseBaseline {
+    /// Explicit polynomial order, for the kinds that accept one.
+    pub fn order(&self) -> Option<usize> {
+        match *self {
+            Self::Constant | Self::Linear => None,
+            Self::Polynomial { order } | Self::EndpointConstrained { order } => Some(order),
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        *self == Self::Constant
+    }
+}
+
+fn default_endpoint_baseline_order() -> usize {
+    1
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -116,6 +364,8 @@ pub(crate) struct Lockin {
     pub signal_channels: Vec<u8>,
     pub workers: usize,
     pub stride_samples: usize,
+    #[serde(default = "default_harmonics")]
+    pub harmonics: Vec<usize>,
     pub filter: Filter,
     #[serde(default, skip_serializing_if = "is_false")]
     pub debug_output: bool,
@@ -134,19 +384,97 @@ pub(crate) struct Lockin {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
 pub(crate) enum Filter {
-    BoxcarLegacy { half_window_cycles: f64 },
+    BoxcarLegacy {
+        half_window_cycles: f64,
+    },
+    RcCascade {
+        #[serde(default = "default_rc_order")]
+        order: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cutoff_hz: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cutoff_ref_ratio: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enbw_hz: Option<f64>,
+        #[serde(default = "default_true")]
+        zero_phase: bool,
+    },
+    WindowedSinc {
+        half_window_cycles: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cutoff_hz: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cutoff_ref_ratio: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enbw_hz: Option<f64>,
+        #[serde(default = "default_true")]
+        zero_phase: bool,
+    },
+    Gaussian {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cutoff_hz: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cutoff_ref_ratio: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enbw_hz: Option<f64>,
+        #[serde(default = "default_true")]
+        zero_phase: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FilterCutoff {
+    pub cutoff_hz: Option<f64>,
+    pub cutoff_ref_ratio: Option<f64>,
+    pub enbw_hz: Option<f64>,
 }
 
 impl Filter {
     pub fn kind(&self) -> &'static str {
         match self {
             Self::BoxcarLegacy { .. } => "boxcar_legacy",
+            Self::RcCascade { .. } => "rc_cascade",
+            Self::WindowedSinc { .. } => "windowed_sinc",
+            Self::Gaussian { .. } => "gaussian",
+        }
+    }
+
+    pub fn half_window_cycles(&self) -> Option<f64> {
+        match self {
+            Self::BoxcarLegacy { half_window_cycles }
+            | Self::WindowedSinc {
+                half_window_cycles, ..
+            } => Some(*half_window_cycles),
+            Self::RcCascade { .. } | Self::Gaussian { .. } => None,
         }
     }
 
-    pub fn half_window_cycles(&self) -> f64 {
-        let Self::BoxcarLegacy { half_window_cycles } = self;
-        *half_window_cycles
+    pub fn cutoff(&self) -> Option<FilterCutoff> {
+        match self {
+            Self::BoxcarLegacy { .. } => None,
+            Self::RcCascade {
+                cutoff_hz,
+                cutoff_ref_ratio,
+                enbw_hz,
+                ..
+            }
+            | Self::WindowedSinc {
+                cutoff_hz,
+                cutoff_ref_ratio,
+                enbw_hz,
+                ..
+            }
+            | Self::Gaussian {
+                cutoff_hz,
+                cutoff_ref_ratio,
+                enbw_hz,
+                ..
+            } => Some(FilterCutoff {
+                cutoff_hz: *cutoff_hz,
+                cutoff_ref_ratio: *cutoff_ref_ratio,
+                enbw_hz: *enbw_hz,
+            }),
+        }
     }
 }
 
@@ -154,6 +482,20 @@ impl Filter {
 #[serde(deny_unknown_fields)]
 pub(crate) struct Phase {
     pub offsets: Vec<NumberOrExpression>,
+    #[serde(default)]
+    pub drift: PhaseDrift,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum PhaseDrift {
+    #[default]
+    Constant,
+    PiecewiseLinear {
+        window: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        step: Option<f64>,
+    },
 }
 
 impl Serialize for Phase {
@@ -162,7 +504,8 @@ impl Serialize for Phase {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("Phase", 1)?;
+        let drift = self.drift != PhaseDrift::Constant;
+        let mut state = serializer.serialize_struct("Phase", 1 + usize::from(drift))?;
         state.serialize_field(
             "offsets",
             &self
@@ -171,6 +514,11 @@ impl Serialize for Phase {
                 .map(|value| value.evaluate().unwrap_or(f64::NAN))
                 .collect::<Vec<_>>(),
         )?;
+        if drift {
+            state.serialize_field("drift", &self.drift)?;
+        } else {
+            state.skip_field("drift")?;
+        }
         state.end()
     }
 }
@@ -243,6 +591,125 @@ pub(crate) struct Kerr {
     pub sensor: u8,
     pub method: KerrMethod,
     pub factor: f64,
+    #[serde(default)]
+    pub modulation_depth: KerrModulationDepth,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_bin_width: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_run: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_background: Option<KerrFieldBackground>,
+}
+
+impl Kerr {
+    pub fn required_harmonics(&self) -> Vec<usize> {
+        let mut required = self.method.required_harmonics().to_vec();
+        if self.estimates_modulation_depth() {
+            required.extend_from_slice(KerrMethod::Harmonics.required_harmonics());
+            required.sort_unstable();
+            required.dedup();
+        }
+        required
+    }
+
+    pub fn requirement_source(&self) -> String {
+        if self.estimates_modulation_depth() {
+            format!(
+                "kerr.method = \"{}\" with kerr.modulation_depth = \"harmonics\"",
+                self.method.name()
+            )
+        } else {
+            format!("kerr.method = \"{}\"", self.method.name())
+        }
+    }
+
+    fn estimates_modulation_depth(&self) -> bool {
+        matches!(self.method, KerrMethod::Standard)
+            && self.modulation_depth == KerrModulationDepth::Harmonics
+    }
+}
+
+/// EOM retardation amplitude φm used by the standard Kerr method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KerrModulationDepth {
+    /// Fixed φm in radians.
+    Fixed(f64),
+    /// φm estimated from harmonics 2, 4 and 6 of the same shot.
+    Harmonics,
+}
+
+impl Default for KerrModulationDepth {
+    fn default() -> Self {
+        Self::Fixed(crate::DEFAULT_KERR_MODULATION_DEPTH_RAD)
+    }
+}
+
+impl Serialize for KerrModulationDepth {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Fixed(value) => serializer.serialize_f64(*value),
+            Self::Harmonics => serializer.serialize_str("harmonics"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for KerrModulationDepth {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum NumberOrMode {
+            Number(f64),
+            Mode(String),
+        }
+
+        match NumberOrMode::deserialize(deserializer)? {
+            NumberOrMode::Number(value) => Ok(Self::Fixed(value)),
+            NumberOrMode::Mode(mode) if mode == "harmonics" => Ok(Self::Harmonics),
+            NumberOrMode::Mode(mode) => Err(serde::de::Error::custom(format!(
+                "modulation_depth must be a number in radians or \"harmonics\" (got \"{mode}\")"
+            ))),
+        }
+    }
+}
+
+/// Background linear (or odd) in the field, such as substrate Faraday
+/// rotation, fitted over high-field ranges and removed from the Kerr angle.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum KerrFieldBackground {
+    /// Slope in the field plus one offset per range.
+    Linear { ranges: Vec<[f64; 2]> },
+    /// Odd powers of the field up to `order` plus one offset per range.
+    OddPolynomial { order: usize, ranges: Vec<[f64; 2]> },
+}
+
+impl KerrFieldBackground {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Linear { .. } => "linear",
+            Self::OddPolynomial { .. } => "odd_polynomial",
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        match self {
+            Self::Linear { .. } => 1,
+            Self::OddPolynomial { order, .. } => *order,
+        }
+    }
+
+    /// Field ranges, as `[low, high]` in the Kerr sensor's output unit.
+    pub fn ranges(&self) -> &[[f64; 2]] {
+        match self {
+            Self::Linear { ranges } | Self::OddPolynomial { ranges, .. } => ranges,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -252,6 +719,22 @@ pub(crate) enum KerrMethod {
     Harmonics,
 }
 
+impl KerrMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::Harmonics => "harmonics",
+        }
+    }
+
+    pub fn required_harmonics(self) -> &'static [usize] {
+        match self {
+            Self::Standard => &[1, 2],
+            Self::Harmonics => &[2, 3, 4, 6],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub(crate) struct Plot {
@@ -316,3 +799,15 @@ pub(crate) enum PlotDecimation {
 fn is_false(value: &bool) -> bool {
     !value
 }
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_rc_order() -> usize {
+    crate::DEFAULT_LOCKIN_RC_ORDER
+}
+
+fn default_harmonics() -> Vec<usize> {
+    crate::DEFAULT_LOCKIN_HARMONICS.to_vec()
+}
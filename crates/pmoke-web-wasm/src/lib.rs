@@ -4,6 +4,7 @@ use wasm_bindgen::prelude::*;
 const MIN_SAMPLES: usize = 64;
 const MAX_SAMPLES: usize = 4_096;
 const LOCKIN_HEADER_VALUES: usize = 8;
+const REFERENCE_FIT_VALUES: usize = 17;
 
 /// Compute raw channel values at continuous time t in [0, 1].
 pub fn sample_channels(t: f64, phase: f64) -> (f64, f64, f64) {
@@ -134,6 +135,58 @@ pub fn calculate_harmonics_kerr_packed(
     Ok(packed.into_boxed_slice())
 }
 
+#[wasm_bindgen]
+pub fn calculate_standard_kerr_packed(
+    a1: &[f64],
+    a2: &[f64],
+    modulation_depth_rad: f64,
+    factor: f64,
+) -> Result<Box<[f64]>, JsError> {
+    let values_rad =
+        pmoke_analysis_core::calculate_standard_kerr(a1, a2, modulation_depth_rad, factor)
+            .map_err(analysis_error)?;
+    let mut packed = Vec::with_capacity(values_rad.len() + 1);
+    packed.push(modulation_depth_rad);
+    packed.extend(values_rad);
+    Ok(packed.into_boxed_slice())
+}
+
+#[wasm_bindgen]
+pub fn fit_reference_sine_packed(
+    signal: &[f64],
+    start_time_s: f64,
+    sample_rate_hz: f64,
+) -> Result<Box<[f64]>, JsError> {
+    let sample_interval_s = 1.0 / sample_rate_hz;
+    let seed = pmoke_analysis_core::estimate_reference_spectrum_peak(sample_interval_s, signal)
+        .map_err(analysis_error)?;
+    let time_s = (0..signal.len())
+        .map(|index| start_time_s + index as f64 * sample_interval_s)
+        .collect::<Vec<_>>();
+    let fit = pmoke_analysis_core::fit_reference_sine(&time_s, signal, seed.frequency_hz)
+        .map_err(analysis_error)?;
+    let packed: [f64; REFERENCE_FIT_VALUES] = [
+        seed.frequency_hz,
+        seed.amplitude,
+        seed.phase_rad,
+        fit.frequency_hz,
+        fit.amplitude,
+        fit.phase_rad,
+        fit.offset,
+        fit.frequency_std_hz,
+        fit.amplitude_std,
+        fit.phase_std_rad,
+        fit.offset_std,
+        fit.residual_rms,
+        fit.residual_max_abs,
+        fit.r_squared,
+        fit.samples as f64,
+        fit.iterations as f64,
+        f64::from(u8::from(fit.converged)),
+    ];
+    Ok(Box::new(packed))
+}
+
 #[wasm_bindgen]
 pub fn boxcar_response_interleaved(
     half_window_s: f64,
@@ -163,12 +216,13 @@ pub fn boxcar_response_interleaved(
 #[wasm_bindgen]
 pub fn analysis_limits_json() -> String {
     format!(
-        r#"{{"max_demo_samples":{},"max_upload_samples":{},"max_upload_bytes":{},"max_total_harmonic_points":{},"lockin_header_values":{}}}"#,
+        r#"{{"max_demo_samples":{},"max_upload_samples":{},"max_upload_bytes":{},"max_total_harmonic_points":{},"lockin_header_values":{},"reference_fit_values":{}}}"#,
         pmoke_analysis_core::DEFAULT_MAX_DEMO_SAMPLES,
         pmoke_analysis_core::MAX_UPLOAD_SAMPLES,
         pmoke_analysis_core::MAX_UPLOAD_BYTES,
         pmoke_analysis_core::MAX_TOTAL_HARMONIC_POINTS,
         LOCKIN_HEADER_VALUES,
+        REFERENCE_FIT_VALUES,
     )
 }
 
@@ -347,5 +401,38 @@ factor = -1.0
             Some(pmoke_analysis_core::MAX_UPLOAD_SAMPLES as u64)
         );
         assert_eq!(limits["lockin_header_values"].as_u64(), Some(8));
+        assert_eq!(limits["reference_fit_values"].as_u64(), Some(17));
+    }
+
+    #[test]
+    fn standard_kerr_packs_modulation_depth_before_values() {
+        let a1 = [0.02, 0.04];
+        let a2 = [0.3, 0.3];
+        let packed = calculate_standard_kerr_packed(&a1, &a2, 0.92, -1.0).unwrap();
+        let expected = pmoke_analysis_core::calculate_standard_kerr(&a1, &a2, 0.92, -1.0).unwrap();
+        assert_eq!(packed[0], 0.92);
+        assert_eq!(&packed[1..], expected.as_slice());
+    }
+
+    #[test]
+    fn reference_fit_packs_fft_seed_and_refined_sine() {
+        let sample_rate_hz = 1.0e6;
+        let signal = (0..8_192)
+            .map(|index| {
+                let time = 2.0e-3 + index as f64 / sample_rate_hz;
+                0.1 + 0.6 * (TAU * 23_456.7 * time - 1.2).sin()
+            })
+            .collect::<Vec<_>>();
+
+        let packed = fit_reference_sine_packed(&signal, 2.0e-3, sample_rate_hz).unwrap();
+
+        assert_eq!(packed.len(), REFERENCE_FIT_VALUES);
+        assert!((packed[0] - 23_456.7).abs() < 50.0);
+        assert!((packed[3] - 23_456.7).abs() < 1.0e-6);
+        assert!((packed[4] - 0.6).abs() < 1.0e-9);
+        assert!((packed[5] - 1.2).abs() < 1.0e-8);
+        assert!((packed[6] - 0.1).abs() < 1.0e-9);
+        assert_eq!(packed[14], 8_192.0);
+        assert_eq!(packed[16], 1.0);
     }
 }
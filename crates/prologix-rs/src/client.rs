@@ -46,6 +46,14 @@ where
         Ok(())
     }
 
+    /// Points the controller at another instrument with `++addr`.
+    pub fn set_address(&mut self, address: u8) -> Result<()> {
+        let config = ControllerConfig::with_read_timeout_ms(address, self.read_timeout_ms())?;
+        self.controller_command(&format!("++addr {address}"))?;
+        self.config = config;
+        Ok(())
+    }
+
     pub fn controller_command(&mut self, command: &str) -> Result<()> {
         write_line(&mut self.io, command)
     }
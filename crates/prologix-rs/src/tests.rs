@@ -77,6 +77,18 @@ fn query_uses_explicit_eoi_read_and_trims_text_response() {
     );
 }
 
+#[test]
+fn set_address_readdresses_the_controller_and_rejects_invalid_addresses() {
+    let mut controller = Prologix::new(MockIo::default(), 11).unwrap();
+
+    controller.set_address(17).unwrap();
+    let error = controller.set_address(31).unwrap_err();
+
+    assert!(matches!(error, Error::InvalidAddress(31)));
+    assert_eq!(controller.address(), 17);
+    assert_eq!(controller.into_inner().written_text(), "++addr 17\n");
+}
+
 #[test]
 fn controller_version_queries_the_adapter_without_gpib_read() {
     let io = MockIo::with_read(b"Prologix GPIB-ETHERNET Controller version 6.101\r\n");
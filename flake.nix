@@ -28,22 +28,6 @@
                 # SciPy checks enabled.
                 disabledTests = old.disabledTests ++ [ "test_support_moments_sample" ];
               });
-              uncertainties = pythonPackages.uncertainties.overridePythonAttrs (old: {
-                nativeCheckInputs = map (
-                  dependency: if pkgs.lib.getName dependency == "scipy" then scipy else dependency
-                ) old.nativeCheckInputs;
-              });
-              lmfit = pythonPackages.lmfit.overridePythonAttrs (old: {
-                dependencies = map (
-                  dependency:
-                  if pkgs.lib.getName dependency == "scipy" then
-                    scipy
-                  else if pkgs.lib.getName dependency == "uncertainties" then
-                    uncertainties
-                  else
-                    dependency
-                ) old.dependencies;
-              });
               gsplot = pythonPackages.buildPythonPackage rec {
                 pname = "gsplot";
                 version = "0.2.0";
@@ -78,7 +62,6 @@
             in
             [
               gsplot
-              lmfit
               pythonPackages.matplotlib
               pythonPackages.numpy
               scipy
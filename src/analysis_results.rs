@@ -131,7 +131,7 @@ fn validate_context_columns(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::constants::LI_HEADER;
+    use crate::lockin::save::lockin_result_headers;
     use crate::test_support::test_config;
 
     #[test]
@@ -169,7 +169,7 @@ mod tests {
     #[test]
     fn builds_headers_in_time_rate_integral_result_order() {
         let cfg = test_config(vec![1, 2], vec![3]);
-        let headers = build_analysis_headers(&cfg, LI_HEADER).unwrap();
+        let headers = build_analysis_headers(&cfg, lockin_result_headers(&[1, 2])).unwrap();
 
         let mut expected = vec![
             "time (s)".to_string(),
@@ -178,7 +178,7 @@ mod tests {
             "ch1 integral (T)".to_string(),
             "ch2 integral (T)".to_string(),
         ];
-        expected.extend(LI_HEADER.iter().map(|header| header.to_string()));
+        expected.extend(lockin_result_headers(&[1, 2]));
         assert_eq!(headers, expected);
     }
 
@@ -90,6 +90,12 @@ pub enum Command {
     /// Capture an oscilloscope screenshot directly to the PC
     #[cfg(feature = "hw-core")]
     Screenshot,
+    /// Push or check the oscilloscope acquisition setup from the config
+    #[cfg(feature = "hw-core")]
+    Scope {
+        #[command(subcommand)]
+        command: ScopeCommand,
+    },
     /// Perform auto measurement (set single mode, trigger, fetch)
     #[cfg(feature = "hw-core")]
     Automeasure,
@@ -105,12 +111,37 @@ pub enum Command {
     Kerr,
     /// Run all analysis steps: reference, sensor, lock-in, phase, Kerr
     Analyze,
+    /// Average repeated shots from several analyzed run directories
+    Stack {
+        /// Analyzed run directories to average
+        #[arg(required = true, num_args = 2.., value_name = "RUN_DIR")]
+        runs: Vec<PathBuf>,
+
+        /// Analysis results to average
+        #[arg(long, value_enum, default_value_t = StackStage::Kerr)]
+        stage: StackStage,
+
+        /// How shots are aligned before averaging
+        #[arg(long, value_enum, default_value_t = StackAlign::Trigger)]
+        align: StackAlign,
+
+        /// New run directory for the stacked results
+        #[arg(short, long, value_name = "DIR")]
+        output: PathBuf,
+    },
     /// Automated analysis after manually triggering the pulse (fetch, lock-in, phase, Kerr)
     #[cfg(feature = "hw-core")]
     Process,
     /// Run the full automatic measurement and analysis
     #[cfg(feature = "hw-core")]
     Auto,
+    /// Run a series of measurement shots listed in a campaign file
+    #[cfg(feature = "hw-core")]
+    Campaign {
+        /// Campaign file listing the shots and their config overrides
+        #[arg(value_name = "FILE")]
+        campaign: PathBuf,
+    },
     /// Generate shell completion script
     Completions {
         /// Shell to generate for: bash, zsh, fish, powershell, elvish
@@ -202,6 +233,24 @@ pub enum BenchProtocol {
     Line,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+pub enum StackStage {
+    /// Lock-in X/Y tables, lockin/ch*_xy.csv
+    Li,
+    /// Phase-rotated lock-in tables, lockin/ch*_rotated.csv
+    Phase,
+    /// Kerr angle table, kerr/kerr.csv
+    Kerr,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+pub enum StackAlign {
+    /// Keep the recorded time axes, which start from the same trigger
+    Trigger,
+    /// Shift each shot so the peaks of the first sensor integral coincide
+    Pulse,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum InstrumentsCommand {
     /// List supported instrument models
@@ -231,6 +280,43 @@ pub enum InstrumentsCommand {
         /// SCPI query command, for example *IDN?
         command: String,
     },
+    /// Find instruments that answer *IDN? and print their connection URIs
+    Discover(DiscoverArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct DiscoverArgs {
+    /// GPIB board to scan; repeat for multiple boards
+    #[arg(long = "gpib-board", default_value = "0", value_name = "N")]
+    pub gpib_boards: Vec<u8>,
+
+    /// Skip GPIB board scans
+    #[arg(long)]
+    pub no_gpib: bool,
+
+    /// Skip the VISA resource list
+    #[arg(long)]
+    pub no_visa: bool,
+
+    /// Prologix controller to sweep with ++addr, for example prologix-tcp://host:1234
+    #[arg(long, value_name = "URI")]
+    pub prologix: Vec<String>,
+
+    /// TCP/IP host or IPv4 subnet such as 192.0.2.0/24 to probe
+    #[arg(long, value_name = "HOST|CIDR")]
+    pub tcp: Vec<String>,
+
+    /// Port probed on every --tcp host; repeat for multiple ports
+    #[arg(long = "port", default_values_t = [5025, 5555], value_name = "PORT")]
+    pub ports: Vec<u16>,
+
+    /// Connect and response timeout for each probe
+    #[arg(long, default_value_t = 500, value_name = "MS")]
+    pub timeout_ms: u64,
+
+    /// Emit machine-readable JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Args, Debug)]
@@ -287,6 +373,15 @@ pub enum ConfigCommand {
     },
 }
 
+#[cfg(feature = "hw-core")]
+#[derive(Subcommand, Debug)]
+pub enum ScopeCommand {
+    /// Send scope.setup to the oscilloscope and read it back
+    Apply,
+    /// Read the oscilloscope setup back and report differences from scope.setup
+    Verify,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum RawCommand {
     /// Verify RAW metadata, file sizes, and available checksums
@@ -468,6 +563,46 @@ mod config_command_tests {
         ));
     }
 
+    #[test]
+    fn parses_instruments_discover_defaults_and_repeated_targets() {
+        let defaults = Cli::try_parse_from(["pmoke", "instruments", "discover"]).unwrap();
+        let Some(Command::Instruments {
+            command: InstrumentsCommand::Discover(args),
+        }) = defaults.command
+        else {
+            panic!("expected instruments discover");
+        };
+        assert_eq!(args.gpib_boards, vec![0]);
+        assert_eq!(args.ports, vec![5025, 5555]);
+        assert_eq!(args.timeout_ms, 500);
+        assert!(args.prologix.is_empty() && args.tcp.is_empty());
+
+        let cli = Cli::try_parse_from([
+            "pmoke",
+            "instruments",
+            "discover",
+            "--no-gpib",
+            "--prologix",
+            "prologix-tcp://192.0.2.20",
+            "--tcp",
+            "192.0.2.0/24",
+            "--tcp",
+            "scope.local",
+            "--port",
+            "55255",
+        ])
+        .unwrap();
+        let Some(Command::Instruments {
+            command: InstrumentsCommand::Discover(args),
+        }) = cli.command
+        else {
+            panic!("expected instruments discover");
+        };
+        assert!(args.no_gpib && !args.no_visa);
+        assert_eq!(args.tcp, vec!["192.0.2.0/24", "scope.local"]);
+        assert_eq!(args.ports, vec![55255]);
+    }
+
     #[test]
     fn parses_transport_benchmark_defaults() {
         let cli = Cli::try_parse_from([
@@ -647,6 +782,38 @@ mod config_command_tests {
         assert_eq!(cli.run_dir, Some(PathBuf::from("shot_000124")));
     }
 
+    #[test]
+    fn parses_stack_with_defaults_and_requires_two_runs() {
+        let cli = Cli::try_parse_from([
+            "pmoke", "stack", "shot-001", "shot-002", "--output", "stacked",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Stack {
+                runs,
+                stage: StackStage::Kerr,
+                align: StackAlign::Trigger,
+                output,
+            }) if runs.len() == 2 && output == std::path::Path::new("stacked")
+        ));
+
+        let cli = Cli::try_parse_from([
+            "pmoke", "stack", "a", "b", "c", "--stage", "phase", "--align", "pulse", "-o", "out",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Stack {
+                stage: StackStage::Phase,
+                align: StackAlign::Pulse,
+                ..
+            })
+        ));
+
+        assert!(Cli::try_parse_from(["pmoke", "stack", "a", "--output", "out"]).is_err());
+    }
+
     #[test]
     fn parses_analysis_npy_export() {
         let cli =
@@ -1,10 +1,10 @@
 use crate::{
     config::Config,
     kerr::run_kerr_analysis,
-    lockin::run_li,
+    lockin::{LockinInput, read_lockin_input, run_li_input},
     phase::run_phase_analysis,
     ui,
-    utils::waveform::{WaveformData, read_all_fetched_waveforms},
+    utils::waveform::WaveformData,
 };
 use anyhow::{Context, Result, bail};
 
@@ -18,23 +18,24 @@ pub fn analyze(cfg: &Config) -> Result<()> {
     let result = (|| {
         let pb = ui::spinner("reading fetched waveform data");
         let t0 = std::time::Instant::now();
-        let data = read_all_fetched_waveforms(cfg)?;
+        let fetched = read_lockin_input(cfg)?;
+        let data = fetched.as_input();
         let elapsed_read = t0.elapsed();
 
         ui::finish_read(
             pb,
             format!(
                 "fetched data: {} channels, {} samples ({})",
-                data.channels.len(),
-                data.channels.first().map_or(0, Vec::len),
+                data.channel_count(),
+                data.loaded().t.len(),
                 ui::fmt_duration(elapsed_read)
             ),
         );
 
-        if data.channels.is_empty() {
+        if data.channel_count() == 0 {
             bail!("Fetched data is empty, cannot extract channels.");
         }
-        run_analyze_inner(cfg, &data)
+        run_analyze_inner(cfg, data)
     })();
     record_analysis_result(cfg, &result)?;
     result
@@ -49,7 +50,7 @@ pub fn run_analyze(cfg: &Config, data: &WaveformData) -> Result<()> {
 
 pub fn run_analyze_locked(cfg: &Config, data: &WaveformData) -> Result<()> {
     crate::commands::run_dir::write_run_state(cfg, "analyzing", "analysis", None)?;
-    let result = run_analyze_inner(cfg, data);
+    let result = run_analyze_inner(cfg, LockinInput::Memory(data));
     record_analysis_result(cfg, &result)?;
     result
 }
@@ -64,7 +65,7 @@ fn record_analysis_result(cfg: &Config, result: &Result<()>) -> Result<()> {
     Ok(())
 }
 
-fn run_analyze_inner(cfg: &Config, data: &WaveformData) -> Result<()> {
+fn run_analyze_inner(cfg: &Config, data: LockinInput<'_>) -> Result<()> {
     let mut cfg_staging = cfg.clone();
     cfg_staging.staging_active = true;
 
@@ -77,31 +78,42 @@ fn run_analyze_inner(cfg: &Config, data: &WaveformData) -> Result<()> {
         .context("failed to create analysis staging directory")?;
     crate::commands::run_dir::write_analysis_config_snapshots(&cfg_staging)?;
 
-    validate_waveform_data(data)?;
-    let (t_stride, sensor_rate_stride, sensor_integral_stride, li_results, reference, provenance) =
-        run_li(&cfg_staging, &data.t, &data.channels)?;
+    validate_waveform_data(data.loaded())?;
+    let (
+        t_stride,
+        sensor_rate_stride,
+        sensor_integral_stride,
+        li_results,
+        noise,
+        reference,
+        provenance,
+        sensor_provenance,
+    ) = run_li_input(&cfg_staging, data)?;
 
     // run phase analysis here
     let ch = cfg_staging.phase_signal_ch();
 
+    let mut kerr_provenance = None;
     if !ch.is_empty() {
-        let li_rotated_results = run_phase_analysis(
+        let (li_rotated_results, rotated_noise) = run_phase_analysis(
             &cfg_staging,
             &t_stride,
             &sensor_rate_stride,
             &sensor_integral_stride,
             &li_results,
+            &noise,
         )?;
         drop(li_results);
 
         // run Kerr analysis here
-        run_kerr_analysis(
+        kerr_provenance = Some(run_kerr_analysis(
             &cfg_staging,
             &t_stride,
             &sensor_rate_stride,
             &sensor_integral_stride,
             &li_rotated_results,
-        )?;
+            &rotated_noise,
+        )?);
     } else {
         ui::skipped("phase analysis: no channels specified");
     }
@@ -112,6 +124,8 @@ fn run_analyze_inner(cfg: &Config, data: &WaveformData) -> Result<()> {
         &cfg.resolver(),
         &reference,
         &provenance,
+        &sensor_provenance,
+        kerr_provenance.as_ref(),
         cfg_staging.roles.reference_ch,
     )?;
 
@@ -188,12 +202,12 @@ pub(crate) fn validate_waveform_data(data: &WaveformData) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::{analyze, run_analyze, validate_waveform_data};
-    use crate::config::{KerrType, LockinLpfKind, Window};
-    use crate::utils::csv::read_csv;
+    use crate::config::{Config, KerrType, LockinLpfKind, Window};
+    use crate::utils::csv::{read_csv, read_csv_with_headers};
     use crate::utils::waveform::WaveformData;
     use std::f64::consts::PI;
     use std::fs;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -223,6 +237,94 @@ mod tests {
         }
     }
 
+    fn synthetic_harmonics_config(directory: &Path) -> Config {
+        let mut cfg = crate::test_support::test_config(vec![1], vec![3]);
+        cfg.source_path = directory.join("config.toml");
+        cfg.set_artifact_root(directory.to_path_buf());
+        cfg.roles.reference_ch = 2;
+        cfg.reference.fft_window = Window {
+            start: 0.0,
+            end: 0.097_3,
+        };
+        cfg.reference.stride_samples = 1_000;
+        cfg.reference.window_samples = 100;
+        cfg.pulse.bg_window_before = Window {
+            start: 0.0,
+            end: 0.01,
+        };
+        cfg.pulse.bg_window_after = Window {
+            start: 0.18,
+            end: 0.199,
+        };
+        cfg.lockin.lpf_kind = LockinLpfKind::BoxcarLegacy;
+        cfg.lockin.stride_samples = 20;
+        cfg.lockin.lpf_half_window_cycles = 1.0;
+        cfg.phase.m_omega_t0_offset = vec![0.0; 6];
+        cfg.kerr.kerr_type = KerrType::Harmonics;
+        cfg
+    }
+
+    /// Sensor pulse, drifting reference, and a signal carrying a Kerr angle `theta`.
+    fn synthetic_harmonics_waveform(theta: f64) -> WaveformData {
+        let sample_count = 20_000;
+        let dt = 1.0e-5;
+        let frequency = 1_000.0;
+        let bessel = [
+            0.581_864_936_842_083_3,
+            0.315_745_306_087_972_3,
+            0.104_537_902_479_595_42,
+            0.025_139_158_519_404_087,
+            0.004_762_786_735_204_94,
+            0.000_745_551_998_014_054_3,
+        ];
+        let time = (0..sample_count)
+            .map(|index| index as f64 * dt)
+            .collect::<Vec<_>>();
+        let sensor = time
+            .iter()
+            .map(|value| {
+                if (0.03..0.15).contains(value) {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>();
+        let reference = time
+            .iter()
+            .map(|value| {
+                let amplitude_drift = 1.0 + 0.01 * (2.0 * PI * 3.0 * value).sin();
+                0.02 + 0.01 * value + amplitude_drift * (2.0 * PI * frequency * value).sin()
+            })
+            .collect::<Vec<_>>();
+        let signal = time
+            .iter()
+            .map(|value| {
+                let harmonics = bessel
+                    .iter()
+                    .enumerate()
+                    .map(|(index, coefficient)| {
+                        let harmonic = index + 1;
+                        let amplitude = if harmonic % 2 == 0 {
+                            (2.0 * theta).cos() * coefficient
+                        } else {
+                            (2.0 * theta).sin() * coefficient
+                        };
+                        let phase = if harmonic % 2 == 0 { PI / 2.0 } else { PI };
+                        2.0 * amplitude
+                            * (harmonic as f64 * 2.0 * PI * frequency * value + phase).sin()
+                    })
+                    .sum::<f64>();
+                let deterministic_noise = 1.0e-5 * (2.0 * PI * 12_345.0 * value + 0.4).sin();
+                0.01 + 0.002 * value + harmonics + deterministic_noise
+            })
+            .collect::<Vec<_>>();
+        WaveformData {
+            t: time.into(),
+            channels: vec![sensor, reference, signal],
+        }
+    }
+
     #[test]
     fn waveform_preflight_rejects_non_finite_and_missing_samples() {
         let non_finite = WaveformData {
@@ -255,6 +357,7 @@ mod tests {
         cfg.roles.reference_ch = 2;
         cfg.set_artifact_root(directory.0.clone());
         cfg.instruments = Some(crate::config::Instruments {
+            multimeters: Vec::new(),
             function_generator: None,
             oscilloscope: crate::config::Oscilloscope {
                 connection: crate::config::Connection::Tcpip {
@@ -262,6 +365,9 @@ mod tests {
                     port: 55255,
                 },
                 model: "DHO5108".to_string(),
+                trigger_timeout_s: pmoke_config_core::DEFAULT_TRIGGER_TIMEOUT_S,
+                trigger_poll_interval_s: pmoke_config_core::DEFAULT_TRIGGER_POLL_INTERVAL_S,
+                setup: None,
             },
         });
         std::fs::write(directory.0.join("raw.csv"), b"ch1,ch2,ch3\n0,0,0\n").unwrap();
@@ -424,9 +530,24 @@ mod tests {
 
         run_analyze(&cfg, &data).unwrap();
 
-        let columns = read_csv(cfg.paths().kerr_csv()).unwrap();
-        let kerr = columns.last().unwrap();
+        let (headers, columns) = read_csv_with_headers(cfg.paths().kerr_csv()).unwrap();
+        let column = |header: &str| {
+            &columns[headers
+                .iter()
+                .position(|candidate| candidate == header)
+                .unwrap()]
+        };
+        let kerr = column("Ch3 Kerr angle (rad)");
         assert!(!kerr.is_empty());
+        let kerr_err = column("Ch3 kerr_err (rad)");
+        assert!(
+            kerr_err
+                .iter()
+                .all(|error| error.is_finite() && *error >= 0.0 && *error < 1.0e-3),
+            "{kerr_err:?}"
+        );
+        assert!(cfg.paths().lockin_noise_csv(3).is_file());
+        assert!(cfg.paths().lockin_rotated_noise_csv(3).is_file());
         let expected = 0.5 * (2.0 * theta).tan().atan();
         let maximum_error = kerr
             .iter()
@@ -439,6 +560,58 @@ mod tests {
         assert!(cfg.paths().analysis_manifest().is_file());
     }
 
+    #[test]
+    fn kerr_background_run_is_subtracted_and_recorded() {
+        let background_directory = TemporaryDirectory::new();
+        let background_cfg = synthetic_harmonics_config(&background_directory.0);
+        let background_theta = 0.004_f64;
+        run_analyze(
+            &background_cfg,
+            &synthetic_harmonics_waveform(background_theta),
+        )
+        .unwrap();
+
+        let directory = TemporaryDirectory::new();
+        let mut cfg = synthetic_harmonics_config(&directory.0);
+        cfg.kerr.background_run = Some(background_directory.0.display().to_string());
+        let theta = 0.01_f64;
+        let data = synthetic_harmonics_waveform(theta);
+        run_analyze(&cfg, &data).unwrap();
+
+        let columns = read_csv(cfg.paths().kerr_csv()).unwrap();
+        let expected =
+            0.5 * (2.0 * theta).tan().atan() - 0.5 * (2.0 * background_theta).tan().atan();
+        // time, ch1 rate, ch1 integral, Ch3 Kerr angle, Ch3 kerr_err
+        let maximum_error = columns[3]
+            .iter()
+            .map(|value| (value - expected).abs())
+            .fold(0.0_f64, f64::max);
+        assert!(
+            maximum_error < 2.0e-4,
+            "expected {expected}, maximum error was {maximum_error}"
+        );
+        let manifest: toml::Value =
+            toml::from_str(&fs::read_to_string(cfg.paths().analysis_manifest()).unwrap()).unwrap();
+        let background = &manifest["kerr"]["background"];
+        assert_eq!(background["file"].as_str(), Some("kerr/kerr.csv"));
+        assert_eq!(
+            background["sha256"].as_str().unwrap(),
+            crate::utils::checksum::file_sha256(&background_cfg.paths().kerr_csv()).unwrap()
+        );
+
+        cfg.lockin.stride_samples = 25;
+        let error = run_analyze(&cfg, &data).unwrap_err();
+        assert!(format!("{error:#}").contains("time axis"), "{error:#}");
+
+        cfg.lockin.stride_samples = 20;
+        cfg.kerr.background_run = Some(directory.0.display().to_string());
+        let error = run_analyze(&cfg, &data).unwrap_err();
+        assert!(
+            format!("{error:#}").contains("not the run being analyzed"),
+            "{error:#}"
+        );
+    }
+
     #[test]
     fn run_analyze_supports_repeated_runs_without_force() {
         let directory = TemporaryDirectory::new();
@@ -875,6 +1048,7 @@ csv = "kerr/kerr.csv"
             f_ref: 1000.0,
             a_ref: 1.0,
             omega_tref: 0.0,
+            fit: None,
         };
         let time = (0..2000).map(|i| i as f64 * 1e-5).collect::<Vec<_>>();
         let signal = vec![0.0; 2000];
@@ -886,18 +1060,27 @@ csv = "kerr/kerr.csv"
             &cfg.lockin,
         )
         .unwrap();
-        let provenance = crate::lockin::provenance::LockinProvenance::from_processor(&processor);
+        let provenance = crate::lockin::provenance::LockinProvenance::from_processor(
+            &processor,
+            &cfg.lockin.harmonics,
+        );
         // Ensure there is at least one lockin csv file in staging so it gets described
         let staging_lockin_csv = li_cfg.paths().lockin_xy_csv(3);
         std::fs::create_dir_all(staging_lockin_csv.parent().unwrap()).unwrap();
         std::fs::write(&staging_lockin_csv, b"time,ch3 rate,ch3 integral\n0,0,0\n").unwrap();
 
+        let sensor = crate::lockin::sensor::SensorProvenance {
+            baseline: crate::config::PulseBaseline::Constant,
+            channels: Vec::new(),
+        };
         crate::lockin::provenance::write_analysis_metadata(
             &li_cfg,
             &li_cfg.paths(),
             &cfg.resolver(),
             &reference,
             &provenance,
+            &sensor,
+            None,
             2,
         )
         .unwrap();
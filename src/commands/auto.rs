@@ -2,15 +2,14 @@ use crate::commands::analyze::run_analyze_locked;
 use crate::commands::fetch::{
     begin_fetch_after_preflight_locked, preflight_fetch_locked, run_fetch_after_preflight_locked,
 };
+use crate::commands::multimeter::Multimeters;
 use crate::communications::function_generator::FGHandler;
 use crate::communications::oscilloscope::OscilloscopeHandler;
 use crate::{
-    commands::autoshot::autoshot,
+    commands::autoshot::{apply_generator_setup, shoot},
     config::{Config, ValidationTarget},
 };
 use anyhow::{Context, Result};
-use std::thread;
-use std::time::Duration;
 
 pub fn auto(cfg: &Config) -> Result<()> {
     if cfg.force {
@@ -25,16 +24,18 @@ pub fn auto(cfg: &Config) -> Result<()> {
     crate::config::validate_for_target(cfg, ValidationTarget::Auto)?;
     preflight_fetch_locked(cfg)?;
     validate_instrument_connections(cfg)?;
+    let mut multimeters = Multimeters::connect(cfg)?;
     begin_fetch_after_preflight_locked(cfg)?;
 
-    if let Err(error) = autoshot(cfg) {
-        crate::commands::run_dir::write_run_state(cfg, "failed", "auto", Some(&error))?;
-        return Err(error);
-    }
-
-    thread::sleep(Duration::from_secs(3));
+    let readings = match multimeters.read_around_shot(|| shoot(cfg)) {
+        Ok(readings) => readings,
+        Err(error) => {
+            crate::commands::run_dir::write_run_state(cfg, "failed", "auto", Some(&error))?;
+            return Err(error);
+        }
+    };
 
-    let data = run_fetch_after_preflight_locked(cfg)?;
+    let data = run_fetch_after_preflight_locked(cfg, &readings)?;
     run_analyze_locked(cfg, &data)?;
 
     Ok(())
@@ -52,5 +53,6 @@ fn validate_instrument_connections(cfg: &Config) -> Result<()> {
     generator
         .identify()
         .context("failed to identify generator during auto preflight")?;
+    apply_generator_setup(&mut generator, cfg)?;
     Ok(())
 }
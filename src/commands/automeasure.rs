@@ -1,14 +1,12 @@
+use crate::commands::autoshot::{apply_generator_setup, shoot};
 use crate::commands::fetch::{
     begin_fetch_after_preflight_locked, preflight_fetch_locked, run_fetch_after_preflight_locked,
 };
-use crate::commands::single::single;
-use crate::commands::trigger::trigger;
+use crate::commands::multimeter::Multimeters;
 use crate::communications::function_generator::FGHandler;
 use crate::communications::oscilloscope::OscilloscopeHandler;
 use crate::config::{Config, ValidationTarget};
 use anyhow::{Context, Result};
-use std::thread;
-use std::time::Duration;
 
 pub fn automeasure(cfg: &Config) -> Result<()> {
     if cfg.force {
@@ -23,26 +21,18 @@ pub fn automeasure(cfg: &Config) -> Result<()> {
     crate::config::validate_for_target(cfg, ValidationTarget::Automeasure)?;
     preflight_fetch_locked(cfg)?;
     validate_instrument_connections(cfg)?;
+    let mut multimeters = Multimeters::connect(cfg)?;
     begin_fetch_after_preflight_locked(cfg)?;
 
-    if let Err(error) = run_measurement_trigger(cfg) {
-        crate::commands::run_dir::write_run_state(cfg, "failed", "automeasure", Some(&error))?;
-        return Err(error);
-    }
-
-    run_fetch_after_preflight_locked(cfg)?;
-    Ok(())
-}
-
-fn run_measurement_trigger(cfg: &Config) -> Result<()> {
-    single(cfg)?;
-
-    thread::sleep(Duration::from_secs(1));
-
-    trigger(cfg)?;
-
-    thread::sleep(Duration::from_secs(1));
+    let readings = match multimeters.read_around_shot(|| shoot(cfg)) {
+        Ok(readings) => readings,
+        Err(error) => {
+            crate::commands::run_dir::write_run_state(cfg, "failed", "automeasure", Some(&error))?;
+            return Err(error);
+        }
+    };
 
+    run_fetch_after_preflight_locked(cfg, &readings)?;
     Ok(())
 }
 
@@ -57,5 +47,6 @@ fn validate_instrument_connections(cfg: &Config) -> Result<()> {
     generator
         .identify()
         .context("failed to identify generator during automeasure preflight")?;
+    apply_generator_setup(&mut generator, cfg)?;
     Ok(())
 }
@@ -1,16 +1,219 @@
-use crate::commands::single::single;
+use crate::commands::scope::{SetupDifference, compare_settings};
 use crate::commands::trigger::trigger;
-use crate::config::Config;
-use anyhow::Result;
+use crate::communications::function_generator::FGHandler;
+use crate::communications::oscilloscope::OscilloscopeHandler;
+use crate::config::{Config, FunctionGeneratorSetup};
+use crate::ui;
+use anyhow::{Context, Result, bail};
+use instruments::oscilloscope::TriggerStatus;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 pub fn autoshot(cfg: &Config) -> Result<()> {
-    single(cfg)?;
+    if configured_generator_setup(cfg).is_some() {
+        let mut generator = FGHandler::initialize(cfg)
+            .context("failed to initialize function generator handler")?;
+        apply_generator_setup(&mut generator, cfg)?;
+    }
+    shoot(cfg)
+}
 
-    thread::sleep(Duration::from_secs(1));
+/// Arms the scope, fires the generator and waits for the capture. Callers
+/// apply `[generator.setup]` beforehand, `auto` during its preflight.
+pub(crate) fn shoot(cfg: &Config) -> Result<()> {
+    let timing = TriggerTiming::from_config(cfg)?;
+    let mut scope = OscilloscopeHandler::initialize(cfg)
+        .context("failed to initialize oscilloscope handler")?;
+    scope
+        .set_single()
+        .context("failed to set oscilloscope to single mode")?;
+    wait_for_trigger_status(|| scope.query_trigger_status(), TriggerWait::Armed, timing)?;
+    ui::success("oscilloscope armed in single mode");
 
     trigger(cfg)?;
 
+    wait_for_trigger_status(
+        || scope.query_trigger_status(),
+        TriggerWait::Stopped,
+        timing,
+    )?;
+    ui::success("oscilloscope captured the shot");
+    Ok(())
+}
+
+pub(crate) fn apply_generator_setup(generator: &mut FGHandler, cfg: &Config) -> Result<()> {
+    let Some(setup) = configured_generator_setup(cfg) else {
+        return Ok(());
+    };
+    generator
+        .apply_setup(setup)
+        .context("failed to apply function generator setup")?;
+    ui::success("function generator setup applied");
     Ok(())
 }
+
+/// Lists every output setting pinned in `expected` whose read-back value differs.
+pub(crate) fn generator_setup_differences(
+    expected: &FunctionGeneratorSetup,
+    actual: &FunctionGeneratorSetup,
+) -> Vec<SetupDifference> {
+    let expected = serde_json::to_value(expected).expect("setup serializes to JSON");
+    let actual = serde_json::to_value(actual).expect("setup serializes to JSON");
+    let mut differences = Vec::new();
+    compare_settings("", &expected, &actual, &[], &mut differences);
+    differences
+}
+
+pub(crate) fn configured_generator_setup(cfg: &Config) -> Option<&FunctionGeneratorSetup> {
+    cfg.instruments
+        .as_ref()?
+        .function_generator
+        .as_ref()?
+        .setup
+        .as_ref()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TriggerTiming {
+    timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl TriggerTiming {
+    fn from_config(cfg: &Config) -> Result<Self> {
+        let scope = &cfg
+            .instruments
+            .as_ref()
+            .context("oscilloscope configuration is missing")?
+            .oscilloscope;
+        Ok(Self {
+            timeout: Duration::try_from_secs_f64(scope.trigger_timeout_s)
+                .context("invalid scope.trigger_timeout_s")?,
+            poll_interval: Duration::try_from_secs_f64(scope.trigger_poll_interval_s)
+                .context("invalid scope.trigger_poll_interval_s")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TriggerWait {
+    /// SINGLE accepted and waiting for a trigger; the generator may fire.
+    Armed,
+    /// Single acquisition finished; the record is ready to fetch.
+    Stopped,
+}
+
+impl TriggerWait {
+    fn target(self) -> TriggerStatus {
+        match self {
+            Self::Armed => TriggerStatus::Wait,
+            Self::Stopped => TriggerStatus::Stop,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Armed => "arm (WAIT) after SINGLE",
+            Self::Stopped => "reach STOP after the generator trigger",
+        }
+    }
+}
+
+fn wait_for_trigger_status(
+    mut query: impl FnMut() -> Result<TriggerStatus>,
+    wait: TriggerWait,
+    timing: TriggerTiming,
+) -> Result<()> {
+    let start = Instant::now();
+    loop {
+        let status = query().context("failed to query oscilloscope trigger status")?;
+        if status == wait.target() {
+            return Ok(());
+        }
+        if wait == TriggerWait::Armed
+            && matches!(status, TriggerStatus::Triggered | TriggerStatus::Stop)
+        {
+            bail!(
+                "oscilloscope reported {status:?} before the generator was triggered; \
+                 check the trigger source and level"
+            );
+        }
+        if start.elapsed() >= timing.timeout {
+            bail!(
+                "oscilloscope did not {} within {:.3} s (last status {status:?})",
+                wait.description(),
+                timing.timeout.as_secs_f64()
+            );
+        }
+        thread::sleep(timing.poll_interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAST: TriggerTiming = TriggerTiming {
+        timeout: Duration::from_millis(50),
+        poll_interval: Duration::from_millis(1),
+    };
+
+    fn replay(statuses: &[TriggerStatus]) -> impl FnMut() -> Result<TriggerStatus> + use<'_> {
+        let mut index = 0;
+        move || {
+            let status = statuses[index.min(statuses.len() - 1)];
+            index += 1;
+            Ok(status)
+        }
+    }
+
+    #[test]
+    fn polling_returns_once_the_target_status_is_reported() {
+        use TriggerStatus::*;
+        wait_for_trigger_status(replay(&[Run, Run, Wait]), TriggerWait::Armed, FAST).unwrap();
+        wait_for_trigger_status(replay(&[Wait, Triggered, Stop]), TriggerWait::Stopped, FAST)
+            .unwrap();
+    }
+
+    #[test]
+    fn arming_fails_if_the_scope_triggers_before_the_generator() {
+        let error = wait_for_trigger_status(
+            replay(&[TriggerStatus::Run, TriggerStatus::Triggered]),
+            TriggerWait::Armed,
+            FAST,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("before the generator"));
+    }
+
+    #[test]
+    fn polling_times_out_with_the_last_status() {
+        let error =
+            wait_for_trigger_status(replay(&[TriggerStatus::Wait]), TriggerWait::Stopped, FAST)
+                .unwrap_err()
+                .to_string();
+        assert!(error.contains("did not reach STOP"), "{error}");
+        assert!(error.contains("last status Wait"), "{error}");
+    }
+
+    #[test]
+    fn generator_read_back_is_compared_only_on_configured_settings() {
+        let expected = FunctionGeneratorSetup {
+            amplitude_vpp: Some(2.0),
+            burst_cycles: Some(1),
+            ..FunctionGeneratorSetup::default()
+        };
+        let actual = FunctionGeneratorSetup {
+            frequency_hz: Some(1.0e3),
+            amplitude_vpp: Some(2.0 * (1.0 + 1.0e-5)),
+            burst_cycles: None,
+            ..FunctionGeneratorSetup::default()
+        };
+
+        let differences = generator_setup_differences(&expected, &actual);
+
+        assert_eq!(differences.len(), 1, "{differences:?}");
+        assert_eq!(differences[0].field, "burst_cycles");
+        assert_eq!(differences[0].actual, "(not reported)");
+    }
+}
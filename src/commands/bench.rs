@@ -1,11 +1,12 @@
 use crate::cli::{BenchCommand, BenchProtocol};
 use crate::commands::instruments::{
-    QueryConnection, TextQuerySession, configured_query_timeout_ms, display_query_connection,
+    TextQuerySession, configured_query_timeout_ms, display_query_connection,
     open_text_query_session, parse_query_connection, validate_line_request,
     validate_scpi_query_command,
 };
 use crate::ui;
 use anyhow::{Context, Result, bail};
+use instruments::transport::ScpiConnection;
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::env;
@@ -220,7 +221,6 @@ fn run_scpi_query(options: ScpiQueryOptions<'_>) -> Result<()> {
     let mut session = None;
     let result = benchmark_request(
         &connection,
-        options.timeout_ms,
         options.command,
         options.warmup,
         options.iterations,
@@ -238,7 +238,7 @@ fn run_scpi_query(options: ScpiQueryOptions<'_>) -> Result<()> {
         }
     }
 
-    let report = compact_scpi_report(&connection, options.timeout_ms, options.warmup, &result);
+    let report = compact_scpi_report(&connection, options.warmup, &result);
     let encoded_toml = toml::to_string_pretty(&report)
         .context("failed to encode SCPI query benchmark report as TOML")?;
     let saved_path = save_scpi_query_report(
@@ -282,8 +282,7 @@ fn validate_scpi_query_options(options: &ScpiQueryOptions<'_>) -> Result<()> {
 }
 
 fn compact_scpi_report(
-    connection: &QueryConnection,
-    timeout_ms: u64,
+    connection: &ScpiConnection,
     warmup: usize,
     result: &RequestBenchmark,
 ) -> ScpiQueryBenchmarkReport {
@@ -323,7 +322,7 @@ fn compact_scpi_report(
         connection: display_query_connection(connection),
         protocol: "scpi",
         command: result.request.clone(),
-        timeout_ms: configured_query_timeout_ms(connection, timeout_ms),
+        timeout_ms: configured_query_timeout_ms(connection),
         iterations: result.attempts,
         warmup,
         warmup_failure_count: result.warmup_failure_count,
@@ -349,7 +348,7 @@ fn run_transport(options: TransportOptions<'_>) -> Result<()> {
     }
 
     let connection = parse_query_connection(options.connection, options.timeout_ms)?;
-    let mut session = Some(open_text_query_session(&connection, options.timeout_ms)?);
+    let mut session = Some(open_text_query_session(&connection)?);
     let total = options
         .requests
         .len()
@@ -362,7 +361,6 @@ fn run_transport(options: TransportOptions<'_>) -> Result<()> {
     for request in options.requests {
         let result = benchmark_request(
             &connection,
-            options.timeout_ms,
             request,
             options.warmup,
             options.iterations,
@@ -385,7 +383,7 @@ fn run_transport(options: TransportOptions<'_>) -> Result<()> {
         generated_at: jiff::Timestamp::now().to_string(),
         connection: display_query_connection(&connection),
         protocol: protocol_name(options.protocol),
-        timeout_ms: configured_query_timeout_ms(&connection, options.timeout_ms),
+        timeout_ms: configured_query_timeout_ms(&connection),
         warmup: options.warmup,
         iterations: options.iterations,
         results,
@@ -452,8 +450,7 @@ fn validate_request(protocol: BenchProtocol, request: &str) -> Result<()> {
 }
 
 fn benchmark_request(
-    connection: &QueryConnection,
-    timeout_ms: u64,
+    connection: &ScpiConnection,
     request: &str,
     warmup: usize,
     iterations: usize,
@@ -462,8 +459,8 @@ fn benchmark_request(
 ) -> RequestBenchmark {
     let mut warmup_failure_count = 0;
     for _ in 0..warmup {
-        let outcome = prepare_session(session, connection, timeout_ms)
-            .and_then(|()| query_session(session, request));
+        let outcome =
+            prepare_session(session, connection).and_then(|()| query_session(session, request));
         if outcome.is_err() {
             warmup_failure_count += 1;
             session.take();
@@ -477,7 +474,7 @@ fn benchmark_request(
     let mut successful_latencies = Vec::with_capacity(iterations);
     let mut last_response = None;
     for iteration in 1..=iterations {
-        let (outcome, elapsed) = measured_query(session, connection, timeout_ms, request);
+        let (outcome, elapsed) = measured_query(session, connection, request);
         let elapsed_ms = duration_ms(elapsed);
         match outcome {
             Ok(response) => {
@@ -524,13 +521,12 @@ fn benchmark_request(
 
 fn measured_query(
     session: &mut Option<TextQuerySession>,
-    connection: &QueryConnection,
-    timeout_ms: u64,
+    connection: &ScpiConnection,
     request: &str,
 ) -> (Result<String>, Duration) {
     if session.is_none() {
         let started = Instant::now();
-        if let Err(error) = reconnect_session(session, connection, timeout_ms) {
+        if let Err(error) = reconnect_session(session, connection) {
             return (
                 Err(error.context("failed to reconnect before a measured request")),
                 started.elapsed(),
@@ -545,11 +541,10 @@ fn measured_query(
 
 fn prepare_session(
     session: &mut Option<TextQuerySession>,
-    connection: &QueryConnection,
-    timeout_ms: u64,
+    connection: &ScpiConnection,
 ) -> Result<()> {
     if session.is_none() {
-        reconnect_session(session, connection, timeout_ms)
+        reconnect_session(session, connection)
             .context("failed to reconnect before a warmup request")?;
     }
     Ok(())
@@ -564,11 +559,10 @@ fn query_session(session: &mut Option<TextQuerySession>, request: &str) -> Resul
 
 fn reconnect_session(
     session: &mut Option<TextQuerySession>,
-    connection: &QueryConnection,
-    timeout_ms: u64,
+    connection: &ScpiConnection,
 ) -> Result<()> {
     session.take();
-    *session = Some(open_text_query_session(connection, timeout_ms)?);
+    *session = Some(open_text_query_session(connection)?);
     Ok(())
 }
 
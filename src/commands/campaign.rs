@@ -0,0 +1,431 @@
+use crate::commands::run_dir::{RunProgress, read_run_progress, write_atomic_file};
+use crate::config::{self, Config, ConfigLoad};
+use crate::ui;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const CAMPAIGN_INDEX_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CampaignFile {
+    config: Option<PathBuf>,
+    #[serde(default)]
+    min_interval_s: f64,
+    #[serde(default)]
+    on_error: ErrorPolicy,
+    index: Option<PathBuf>,
+    shots: Vec<CampaignShot>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CampaignShot {
+    run_dir: PathBuf,
+    label: Option<String>,
+    #[serde(default)]
+    overrides: toml::Table,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ErrorPolicy {
+    #[default]
+    Stop,
+    Continue,
+}
+
+#[derive(Debug, Serialize)]
+struct CampaignIndex {
+    schema_version: u32,
+    pmoke_version: &'static str,
+    campaign: String,
+    config: String,
+    updated_at: String,
+    shots: Vec<ShotRecord>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShotRecord {
+    shot: usize,
+    run_dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    outcome: ShotOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    finished_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ShotOutcome {
+    Pending,
+    Complete,
+    AlreadyComplete,
+    Analyzed,
+    Failed,
+}
+
+/// What a shot still needs, judged from the `run.toml` left by earlier attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShotAction {
+    Acquire,
+    Analyze,
+    Skip,
+}
+
+fn next_action(progress: Option<&RunProgress>) -> ShotAction {
+    match progress {
+        Some(progress) if progress.status == "complete" => ShotAction::Skip,
+        Some(progress) if progress.acquired => ShotAction::Analyze,
+        _ => ShotAction::Acquire,
+    }
+}
+
+pub fn run(campaign_path: &Path, default_config: &Path) -> Result<()> {
+    let campaign = read_campaign(campaign_path)?;
+    let base_dir = campaign_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let config_path = campaign
+        .config
+        .as_ref()
+        .map_or_else(|| default_config.to_path_buf(), |path| base_dir.join(path));
+    let base_text = fs::read_to_string(&config_path)
+        .with_context(|| format!("failed to read {}", config_path.display()))?;
+    let index_path = campaign.index.as_ref().map_or_else(
+        || default_index_path(campaign_path),
+        |path| base_dir.join(path),
+    );
+
+    let mut index = CampaignIndex {
+        schema_version: CAMPAIGN_INDEX_SCHEMA_VERSION,
+        pmoke_version: env!("CARGO_PKG_VERSION"),
+        campaign: campaign_path.display().to_string(),
+        config: config_path.display().to_string(),
+        updated_at: jiff::Timestamp::now().to_string(),
+        shots: campaign
+            .shots
+            .iter()
+            .enumerate()
+            .map(|(i, shot)| ShotRecord {
+                shot: i + 1,
+                run_dir: base_dir.join(&shot.run_dir).display().to_string(),
+                label: shot.label.clone(),
+                outcome: ShotOutcome::Pending,
+                run_status: None,
+                started_at: None,
+                finished_at: None,
+                error: None,
+            })
+            .collect(),
+    };
+    write_index(&index_path, &mut index)?;
+
+    let total = campaign.shots.len();
+    let mut last_shot: Option<jiff::Timestamp> = None;
+    let mut failures = 0;
+    for (i, shot) in campaign.shots.iter().enumerate() {
+        let run_dir = base_dir.join(&shot.run_dir);
+        let name = shot_name(i, total, shot);
+        let progress = read_run_progress(&run_dir)?;
+        let action = next_action(progress.as_ref());
+        if let Some(started) = progress
+            .as_ref()
+            .filter(|progress| progress.acquired)
+            .and_then(|progress| progress.started_at.parse().ok())
+        {
+            last_shot = last_shot.max(Some(started));
+        }
+
+        let record = &mut index.shots[i];
+        if action == ShotAction::Skip {
+            ui::skipped(format!("{name}: already complete"));
+            record.outcome = ShotOutcome::AlreadyComplete;
+            record.run_status = progress.map(|progress| progress.status);
+            write_index(&index_path, &mut index)?;
+            continue;
+        }
+
+        if action == ShotAction::Acquire {
+            wait_for_interval(last_shot, campaign.min_interval_s);
+            last_shot = Some(jiff::Timestamp::now());
+        }
+        ui::section(match action {
+            ShotAction::Acquire => format!("{name}: acquiring"),
+            _ => format!("{name}: resuming analysis"),
+        });
+        record.started_at = Some(jiff::Timestamp::now().to_string());
+        let result =
+            shot_config(&config_path, &base_text, shot, &run_dir).and_then(|cfg| match action {
+                ShotAction::Acquire => crate::commands::auto::auto(&cfg),
+                _ => crate::commands::analyze::analyze(&cfg),
+            });
+        let record = &mut index.shots[i];
+        record.finished_at = Some(jiff::Timestamp::now().to_string());
+        record.run_status = read_run_progress(&run_dir)
+            .ok()
+            .flatten()
+            .map(|progress| progress.status);
+        match result {
+            Ok(()) => {
+                record.outcome = match action {
+                    ShotAction::Acquire => ShotOutcome::Complete,
+                    _ => ShotOutcome::Analyzed,
+                };
+                write_index(&index_path, &mut index)?;
+            }
+            Err(error) => {
+                record.outcome = ShotOutcome::Failed;
+                record.error = Some(format!("{error:#}"));
+                write_index(&index_path, &mut index)?;
+                if campaign.on_error == ErrorPolicy::Stop {
+                    return Err(error)
+                        .with_context(|| format!("campaign stopped at {name}; rerun to resume"));
+                }
+                ui::error(format!("{name} failed: {error:#}"));
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        bail!(
+            "{failures} of {total} campaign shots failed; see {}",
+            index_path.display()
+        );
+    }
+    ui::success(format!(
+        "campaign finished: {total} shots; index saved to {}",
+        index_path.display()
+    ));
+    Ok(())
+}
+
+fn read_campaign(path: &Path) -> Result<CampaignFile> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read campaign {}", path.display()))?;
+    let campaign: CampaignFile = toml::from_str(&text)
+        .with_context(|| format!("failed to parse campaign {}", path.display()))?;
+    validate_campaign(&campaign)?;
+    Ok(campaign)
+}
+
+fn validate_campaign(campaign: &CampaignFile) -> Result<()> {
+    if campaign.shots.is_empty() {
+        bail!("campaign must list at least one [[shots]] entry");
+    }
+    if !campaign.min_interval_s.is_finite() || campaign.min_interval_s < 0.0 {
+        bail!("min_interval_s must be a finite, non-negative number of seconds");
+    }
+    let mut run_dirs = HashSet::new();
+    for (i, shot) in campaign.shots.iter().enumerate() {
+        if shot.run_dir.as_os_str().is_empty() {
+            bail!("shots[{i}].run_dir must not be empty");
+        }
+        if !run_dirs.insert(&shot.run_dir) {
+            bail!(
+                "shots[{i}].run_dir {} is used by more than one shot",
+                shot.run_dir.display()
+            );
+        }
+        if shot.overrides.contains_key("version") {
+            bail!("shots[{i}].overrides must not change the config version");
+        }
+    }
+    Ok(())
+}
+
+fn default_index_path(campaign_path: &Path) -> PathBuf {
+    let stem = campaign_path
+        .file_stem()
+        .map_or_else(|| "campaign".into(), |stem| stem.to_string_lossy());
+    campaign_path.with_file_name(format!("{stem}.index.toml"))
+}
+
+fn shot_name(i: usize, total: usize, shot: &CampaignShot) -> String {
+    match &shot.label {
+        Some(label) => format!("shot {}/{total} ({label})", i + 1),
+        None => format!("shot {}/{total}", i + 1),
+    }
+}
+
+/// Loads the base config with the shot's overrides merged in and points its
+/// artifacts at the shot's run directory.
+fn shot_config(
+    config_path: &Path,
+    base_text: &str,
+    shot: &CampaignShot,
+    run_dir: &Path,
+) -> Result<Config> {
+    let text = merged_config_text(base_text, &shot.overrides)?;
+    let (mut cfg, warnings) = match config::load_from_str(&text) {
+        ConfigLoad::Ready { config, warnings } => (config, warnings),
+        ConfigLoad::Diagnostics(diagnostics) => {
+            let messages = diagnostics
+                .diagnostics
+                .iter()
+                .map(|diagnostic| match &diagnostic.path {
+                    Some(path) => format!("{path}: {}", diagnostic.message),
+                    None => diagnostic.message.clone(),
+                })
+                .collect::<Vec<_>>();
+            bail!(
+                "configuration with shot overrides is not runnable: {}",
+                messages.join("; ")
+            );
+        }
+    };
+    crate::commands::show::print_warnings(&warnings);
+    cfg.source_path = config_path.to_path_buf();
+    cfg.source_text = Some(text);
+    cfg.set_artifact_root(run_dir.to_path_buf());
+    Ok(cfg)
+}
+
+fn merged_config_text(base_text: &str, overrides: &toml::Table) -> Result<String> {
+    if overrides.is_empty() {
+        return Ok(base_text.to_string());
+    }
+    let mut base: toml::Table = toml::from_str(base_text).context("failed to parse base config")?;
+    merge_tables(&mut base, overrides);
+    toml::to_string_pretty(&base).context("failed to render config with shot overrides")
+}
+
+/// Tables merge key by key; any other override value replaces the base value.
+fn merge_tables(base: &mut toml::Table, overrides: &toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overrides)) => {
+                merge_tables(base, overrides);
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn wait_for_interval(last_shot: Option<jiff::Timestamp>, min_interval_s: f64) {
+    let Some(last_shot) = last_shot else {
+        return;
+    };
+    let elapsed = jiff::Timestamp::now()
+        .duration_since(last_shot)
+        .as_secs_f64();
+    let remaining = min_interval_s - elapsed;
+    if remaining > 0.0 {
+        let pb = ui::spinner(format!("waiting {remaining:.1} s before the next shot"));
+        std::thread::sleep(Duration::from_secs_f64(remaining));
+        ui::finish_success(pb, "minimum shot interval elapsed");
+    }
+}
+
+fn write_index(path: &Path, index: &mut CampaignIndex) -> Result<()> {
+    index.updated_at = jiff::Timestamp::now().to_string();
+    let encoded = toml::to_string_pretty(index).context("failed to encode campaign index")?;
+    write_atomic_file(path, encoded.as_bytes())
+        .with_context(|| format!("failed to write campaign index {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(status: &str, acquired: bool) -> RunProgress {
+        RunProgress {
+            status: status.to_string(),
+            started_at: "2026-01-01T00:00:00Z".to_string(),
+            acquired,
+        }
+    }
+
+    #[test]
+    fn resume_action_follows_the_run_state() {
+        assert_eq!(next_action(None), ShotAction::Acquire);
+        assert_eq!(
+            next_action(Some(&progress("failed", false))),
+            ShotAction::Acquire
+        );
+        assert_eq!(
+            next_action(Some(&progress("acquiring", false))),
+            ShotAction::Acquire
+        );
+        assert_eq!(
+            next_action(Some(&progress("analyzing", true))),
+            ShotAction::Analyze
+        );
+        assert_eq!(
+            next_action(Some(&progress("failed", true))),
+            ShotAction::Analyze
+        );
+        assert_eq!(
+            next_action(Some(&progress("complete", true))),
+            ShotAction::Skip
+        );
+    }
+
+    #[test]
+    fn overrides_merge_into_nested_tables() {
+        let base = "version = 5\n\n[phase]\noffsets = [0.0]\nmode = \"fixed\"\n";
+        let overrides: toml::Table =
+            toml::from_str("phase.offsets = [0.5, 1.0]\nlabel = \"300K\"\n").unwrap();
+
+        let merged: toml::Table =
+            toml::from_str(&merged_config_text(base, &overrides).unwrap()).unwrap();
+
+        assert_eq!(merged["version"].as_integer(), Some(5));
+        assert_eq!(merged["phase"]["mode"].as_str(), Some("fixed"));
+        assert_eq!(
+            merged["phase"]["offsets"],
+            toml::Value::Array(vec![0.5.into(), 1.0.into()])
+        );
+        assert_eq!(merged["label"].as_str(), Some("300K"));
+        assert_eq!(merged_config_text(base, &toml::Table::new()).unwrap(), base);
+    }
+
+    #[test]
+    fn campaign_file_is_validated() {
+        let campaign: CampaignFile = toml::from_str(
+            "min_interval_s = 30\non_error = \"continue\"\n\n\
+             [[shots]]\nrun_dir = \"runs/a\"\nlabel = \"10K\"\n\
+             [shots.overrides.phase]\noffsets = [0.1]\n\n\
+             [[shots]]\nrun_dir = \"runs/b\"\n",
+        )
+        .unwrap();
+        validate_campaign(&campaign).unwrap();
+        assert_eq!(campaign.on_error, ErrorPolicy::Continue);
+        assert_eq!(campaign.shots[0].label.as_deref(), Some("10K"));
+        assert!(campaign.shots[1].overrides.is_empty());
+
+        let duplicate: CampaignFile =
+            toml::from_str("[[shots]]\nrun_dir = \"runs/a\"\n\n[[shots]]\nrun_dir = \"runs/a\"\n")
+                .unwrap();
+        let error = validate_campaign(&duplicate).unwrap_err();
+        assert!(error.to_string().contains("more than one shot"));
+
+        let negative: CampaignFile =
+            toml::from_str("min_interval_s = -1\n[[shots]]\nrun_dir = \"a\"\n").unwrap();
+        assert!(validate_campaign(&negative).is_err());
+        assert!(toml::from_str::<CampaignFile>("shots = []\nretries = 2\n").is_err());
+    }
+
+    #[test]
+    fn index_path_defaults_next_to_the_campaign_file() {
+        assert_eq!(
+            default_index_path(Path::new("sessions/cooldown.toml")),
+            PathBuf::from("sessions/cooldown.index.toml")
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use crate::cli::ConfigCommand;
 use crate::commands::show;
 use crate::config::{
-    CONFIG_FIELD_DOCS, ConfigFieldDoc, ConfigLoad, MigrationPlan, load_from_path, load_from_str,
-    plan_latest_executable_migration, plan_migration,
+    CONFIG_FIELD_DOCS, Config, ConfigFieldDoc, ConfigLoad, MigrationPlan, load_from_path,
+    load_from_str, plan_latest_executable_migration, plan_migration,
 };
 use crate::ui;
 use anyhow::{Context, Result, bail, ensure};
@@ -22,7 +22,7 @@ pub fn run(config_path: &str, command: &ConfigCommand) -> Result<ConfigCommandOu
             run_init(Path::new(config_path), output.as_deref(), *force)
         }
         ConfigCommand::Validate => run_validate(Path::new(config_path)),
-        ConfigCommand::Explain { path } => run_explain(path.as_deref()),
+        ConfigCommand::Explain { path } => run_explain(Path::new(config_path), path.as_deref()),
         ConfigCommand::Migrate {
             output,
             in_place,
@@ -143,8 +143,9 @@ fn run_validate(source: &Path) -> Result<ConfigCommandOutcome> {
     }
 }
 
-fn run_explain(path: Option<&str>) -> Result<ConfigCommandOutcome> {
-    match path.map(str::trim).filter(|value| !value.is_empty()) {
+fn run_explain(source: &Path, path: Option<&str>) -> Result<ConfigCommandOutcome> {
+    let path = path.map(str::trim).filter(|value| !value.is_empty());
+    match path {
         None => print_field_docs("Config Fields", CONFIG_FIELD_DOCS),
         Some(path) => {
             let matches = explain_matches(path);
@@ -160,9 +161,49 @@ fn run_explain(path: Option<&str>) -> Result<ConfigCommandOutcome> {
             print_field_docs(&title, &matches);
         }
     }
+    if path.is_none_or(|path| path.starts_with("sensors"))
+        && source.is_file()
+        && let ConfigLoad::Ready { config, .. } = load_from_path(source)
+    {
+        ui::settings_table(
+            format!("Sensor Calibrations: {}", source.display()),
+            sensor_calibration_rows(&config),
+        );
+    }
     Ok(ConfigCommandOutcome { exit_code: 0 })
 }
 
+/// Resolved scale of each sensor in `config`, for `config explain`.
+fn sensor_calibration_rows(config: &Config) -> Vec<(String, String)> {
+    config
+        .roles
+        .sensor_ch
+        .iter()
+        .filter_map(|&ch| config.channels.iter().find(|channel| channel.index == ch))
+        .map(|channel| {
+            let scale = match (channel.factor, channel.scale_to_abs_max, channel.coil) {
+                (_, _, Some(coil)) => crate::lockin::sensor::coil_summary(&coil),
+                (Some(factor), _, _) => format!("factor={factor:.6e}"),
+                (None, Some(target), None) => {
+                    format!("scaled at analysis time so that max |integral| = {target:.6e}")
+                }
+                (None, None, None) => "-".to_string(),
+            };
+            (
+                format!(
+                    "ch{} {}",
+                    channel.index,
+                    channel.label.as_deref().unwrap_or("-")
+                ),
+                format!(
+                    "unit={}, {scale}",
+                    channel.unit_out.as_deref().unwrap_or("-")
+                ),
+            )
+        })
+        .collect()
+}
+
 fn explain_matches(path: &str) -> Vec<ConfigFieldDoc> {
     let exact = CONFIG_FIELD_DOCS
         .iter()
@@ -842,9 +883,51 @@ mod tests {
 
     #[test]
     fn explain_accepts_sections_and_rejects_unknown_paths() {
-        assert_eq!(run_explain(Some("lockin.filter")).unwrap().exit_code, 0);
-        assert_eq!(run_explain(Some("sensors.channel")).unwrap().exit_code, 0);
-        assert!(run_explain(Some("does.not.exist")).is_err());
+        let missing = Path::new("missing-config.toml");
+        assert_eq!(
+            run_explain(missing, Some("lockin.filter"))
+                .unwrap()
+                .exit_code,
+            0
+        );
+        assert_eq!(
+            run_explain(missing, Some("sensors.channel"))
+                .unwrap()
+                .exit_code,
+            0
+        );
+        assert!(run_explain(missing, Some("does.not.exist")).is_err());
+    }
+
+    #[test]
+    fn explain_resolves_coil_sensor_calibrations() {
+        let text = CONFIG_TEMPLATE_V5.replacen(
+            "scale = { factor = 1.0 }",
+            "scale = { area_turns = 2.0e-4, gain = 0.1, compensation = { channel = 5, balance = 0.98 } }",
+            1,
+        );
+        let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+            panic!("coil sensor config must load");
+        };
+
+        let rows = sensor_calibration_rows(&config);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].0, "ch4 sensor");
+        assert!(
+            rows[1].1.contains("factor=5.000000e4 T/(V*s)"),
+            "{}",
+            rows[1].1
+        );
+        assert!(
+            rows[1].1.contains("compensation=ch5 x 0.98"),
+            "{}",
+            rows[1].1
+        );
+        assert!(
+            rows[0].1.contains("max |integral| = -5.500000e1"),
+            "{}",
+            rows[0].1
+        );
     }
 
     #[test]
@@ -318,7 +318,7 @@ fn staging_path(output: &Path) -> PathBuf {
 
 fn check_python(cfg: &Config, checks: &mut Vec<DoctorCheck>) {
     Python::attach(|py| {
-        for module in ["numpy", "scipy", "lmfit", "gsplot"] {
+        for module in ["numpy", "scipy", "gsplot"] {
             checks.push(match PyModule::import(py, module) {
                 Ok(_) => DoctorCheck {
                     name: format!("python.{module}"),
@@ -499,9 +499,12 @@ fn transport_kind(connection: &Connection) -> TransportKind {
     match connection {
         Connection::Gpib { .. } => TransportKind::Gpib,
         Connection::Tcpip { .. } => TransportKind::Tcpip,
+        Connection::Vxi11 { .. } => TransportKind::Vxi11,
         Connection::Usbtmc { .. } => TransportKind::Usbtmc,
+        Connection::UsbtmcDevice { .. } => TransportKind::UsbtmcDevice,
         Connection::PrologixTcp { .. } => TransportKind::PrologixTcp,
         Connection::PrologixSerial { .. } => TransportKind::PrologixSerial,
+        Connection::Sim { .. } => TransportKind::Sim,
     }
 }
 
@@ -509,18 +512,24 @@ fn transport_feature_available(transport: TransportKind) -> bool {
     match transport {
         TransportKind::Dummy => true,
         TransportKind::Gpib => cfg!(feature = "hw-gpib"),
-        TransportKind::Tcpip => cfg!(feature = "hw-core"),
+        TransportKind::Tcpip | TransportKind::Vxi11 => cfg!(feature = "hw-core"),
         TransportKind::Usbtmc => cfg!(all(target_os = "windows", feature = "hw-gpib")),
+        TransportKind::UsbtmcDevice => cfg!(all(target_os = "linux", feature = "hw-core")),
         TransportKind::PrologixTcp => cfg!(feature = "hw-prologix-tcp"),
         TransportKind::PrologixSerial => cfg!(feature = "hw-prologix-serial"),
+        TransportKind::Sim => cfg!(feature = "hw-core"),
     }
 }
 
 fn timeout_detail(name: &str, connection: &Connection) -> String {
     match connection {
         Connection::Tcpip { .. } if name == "scope" => "connect=5s, read/write=30s".to_string(),
-        Connection::Tcpip { .. } => "connection-specific timeout".to_string(),
+        Connection::Tcpip { .. } | Connection::Vxi11 { .. } => {
+            "connection-specific timeout".to_string()
+        }
         Connection::Usbtmc { .. } => "read/write=30s".to_string(),
+        Connection::UsbtmcDevice { .. } => "usbtmc driver transfer timeout".to_string(),
+        Connection::Sim { bench } => format!("in-process simulated bench {bench}"),
         Connection::Gpib { .. } => "read/write=10s".to_string(),
         Connection::PrologixTcp {
             read_timeout_ms, ..
@@ -543,7 +552,7 @@ fn probe_scope(
 ) -> Option<u64> {
     use crate::communications::oscilloscope::OscilloscopeHandler;
     use crate::utils::channels::build_channel_list;
-    use instruments::rigol::DhoTriggerStatus;
+    use instruments::oscilloscope::TriggerStatus;
 
     let mut predicted_bytes = None;
     match OscilloscopeHandler::initialize(cfg) {
@@ -573,7 +582,7 @@ fn probe_scope(
             match scope.query_trigger_status() {
                 Ok(status) => checks.push(DoctorCheck {
                     name: "scope.state".to_string(),
-                    status: if status == DhoTriggerStatus::Stop {
+                    status: if status == TriggerStatus::Stop {
                         CheckStatus::Pass
                     } else {
                         CheckStatus::Warn
@@ -638,8 +647,7 @@ fn probe_generator(
     spec: &InstrumentSpec,
     checks: &mut Vec<DoctorCheck>,
 ) {
-    use crate::communications::function_generator::scpi_connection;
-    use instruments::transport::open_scpi_transport;
+    use crate::communications::function_generator::open_generator_transport;
 
     let has_scpi_identity = spec
         .capabilities
@@ -656,14 +664,7 @@ fn probe_generator(
         return;
     }
 
-    let scpi_connection = match scpi_connection(connection) {
-        Ok(connection) => connection,
-        Err(error) => {
-            checks.push(failed("generator.open", error));
-            return;
-        }
-    };
-    let mut transport = match open_scpi_transport(&scpi_connection) {
+    let mut transport = match open_generator_transport(connection) {
         Ok(transport) => transport,
         Err(error) => {
             checks.push(failed("generator.open", error));
@@ -1,9 +1,15 @@
 use crate::cli::FetchFormat;
+use crate::commands::autoshot::{configured_generator_setup, generator_setup_differences};
+use crate::commands::multimeter::MultimeterReading;
 use crate::commands::screenshot::{
     capture_screenshot, prepare_screenshot, prepare_screenshot_path, report_saved_screenshot,
 };
+use crate::communications::function_generator::FGHandler;
 use crate::communications::oscilloscope::OscilloscopeHandler;
-use crate::config::{Config, Connection, FetchOutput, render_normalized_config};
+use crate::config::{
+    Config, Connection, FetchOutput, FunctionGeneratorSetup, OscilloscopeSetup,
+    render_normalized_config,
+};
 #[cfg(test)]
 use crate::constants::RAW_METADATA_FNAME;
 use crate::constants::{RAW_METADATA_VERSION, T_HEADER};
@@ -18,7 +24,7 @@ use crate::utils::raw_data::{
 use crate::utils::time_axis::WaveformTime;
 use crate::utils::waveform::{WaveformData, read_raw_waveform_channels_from_dir};
 use anyhow::{Context, Result, anyhow, bail};
-use instruments::rigol::{DhoHorizontalSettings, DhoRawWaveform, DhoTriggerStatus};
+use instruments::oscilloscope::{HorizontalSettings, RawWaveform, TriggerStatus, WaveformPreamble};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::ffi::OsString;
@@ -75,6 +81,9 @@ struct RawFetchMetadata {
     resolved_config_file: &'static str,
     resolved_config_sha256: String,
     oscilloscope: RawOscilloscopeMetadata,
+    /// Generator output read back before the transfer when `[generator.setup]` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generator: Option<RawGeneratorMetadata>,
     channels: Vec<RawChannelMetadata>,
 }
 
@@ -95,6 +104,16 @@ struct RawOscilloscopeMetadata {
     channels: Vec<u8>,
     horizontal_offset: f64,
     horizontal_scale: f64,
+    /// Acquisition settings read back from the scope before the transfer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    setup: Option<OscilloscopeSetup>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct RawGeneratorMetadata {
+    model: String,
+    connection: Connection,
+    setup: FunctionGeneratorSetup,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -608,8 +627,11 @@ pub(crate) fn begin_fetch_after_preflight_locked(cfg: &Config) -> Result<()> {
     crate::commands::run_dir::write_run_state(cfg, "acquiring", "fetch", None)
 }
 
-pub(crate) fn run_fetch_after_preflight_locked(cfg: &Config) -> Result<WaveformData> {
-    let result = run_fetch_for_process_inner(cfg);
+pub(crate) fn run_fetch_after_preflight_locked(
+    cfg: &Config,
+    multimeters: &[MultimeterReading],
+) -> Result<WaveformData> {
+    let result = run_fetch_for_process_inner(cfg, multimeters);
     match &result {
         Ok(_) => crate::commands::run_dir::write_run_state(cfg, "acquired", "fetch", None)?,
         Err(error) => {
@@ -619,7 +641,10 @@ pub(crate) fn run_fetch_after_preflight_locked(cfg: &Config) -> Result<WaveformD
     result
 }
 
-fn run_fetch_for_process_inner(cfg: &Config) -> Result<WaveformData> {
+fn run_fetch_for_process_inner(
+    cfg: &Config,
+    multimeters: &[MultimeterReading],
+) -> Result<WaveformData> {
     check_acquisition_exists(cfg)?;
 
     let mut cfg_staging = cfg.clone();
@@ -644,6 +669,9 @@ fn run_fetch_for_process_inner(cfg: &Config) -> Result<WaveformData> {
         FetchOutput::Raw => fetch_raw_collect(&cfg_staging, &raw_out)?,
         FetchOutput::CsvAndRaw => fetch_csv_and_raw_collect(&cfg_staging, &csv_out, &raw_out)?,
     };
+    if !multimeters.is_empty() {
+        record_multimeter_readings(&paths.acquisition_manifest(), multimeters)?;
+    }
 
     let canonical_acquisition = cfg.paths().acquisition_dir();
     crate::commands::run_dir::publish_staged_directory(
@@ -655,6 +683,21 @@ fn run_fetch_for_process_inner(cfg: &Config) -> Result<WaveformData> {
     Ok(data)
 }
 
+/// Adds the readings as `[[multimeters]]` to the staged acquisition manifest.
+fn record_multimeter_readings(manifest: &Path, readings: &[MultimeterReading]) -> Result<()> {
+    let contents = fs::read_to_string(manifest)
+        .with_context(|| format!("failed to read {}", manifest.display()))?;
+    let mut table: toml::Table = toml::from_str(&contents)
+        .with_context(|| format!("failed to parse {}", manifest.display()))?;
+    table.insert(
+        "multimeters".to_string(),
+        toml::Value::try_from(readings).context("failed to encode multimeter readings")?,
+    );
+    let encoded =
+        toml::to_string_pretty(&table).context("failed to encode acquisition manifest")?;
+    write_synced_file(manifest, encoded.as_bytes())
+}
+
 fn write_csv_acquisition_manifest(cfg: &Config, csv: &Path, data: &WaveformData) -> Result<()> {
     let acquisition = cfg.paths().acquisition_dir();
     let relative = csv.strip_prefix(&acquisition).with_context(|| {
@@ -991,8 +1034,21 @@ fn fetch_raw_into_dir(
     let horizontal = handler
         .query_horizontal_settings()
         .context("failed to query oscilloscope horizontal settings")?;
+    // The read-back is diagnostic only, so an unparsable setup must not abort the fetch.
+    let setup = match handler.query_setup(&channels) {
+        Ok(setup) => {
+            warn_on_setup_drift(cfg, &setup);
+            Some(setup)
+        }
+        Err(error) => {
+            ui::warn(format!("oscilloscope setup read-back skipped: {error:#}"));
+            None
+        }
+    };
     let mut metadata =
         build_raw_metadata(cfg, &channels, depth, horizontal, idn_raw, config_snapshots)?;
+    metadata.oscilloscope.setup = setup;
+    metadata.generator = read_back_generator_setup(cfg);
     let mut time_axis = None;
 
     let pb = ui::progress(
@@ -1047,7 +1103,7 @@ fn build_raw_metadata(
     cfg: &Config,
     channels: &[u8],
     memory_depth: usize,
-    horizontal: DhoHorizontalSettings,
+    horizontal: HorizontalSettings,
     idn_raw: String,
     config_snapshots: ConfigSnapshotHashes,
 ) -> Result<RawFetchMetadata> {
@@ -1082,26 +1138,80 @@ fn build_raw_metadata(
             memory_depth,
             waveform_mode: "RAW",
             waveform_format: "WORD",
-            // The DHO/MHO5000 programming guide exposes WORD format but no
-            // waveform byte-order command. The DHO driver decodes its fixed
-            // WORD payload as least-significant byte first.
             byte_order: "little-endian",
-            byte_order_source: "DHO5000 WORD protocol",
+            byte_order_source: byte_order_source(&osc_cfg.model),
             acquisition_state: "STOP",
             sample_count: memory_depth,
             channels: channels.to_vec(),
             horizontal_offset: horizontal.offset,
             horizontal_scale: horizontal.scale,
+            setup: None,
         },
+        generator: None,
         channels: Vec::new(),
     })
 }
 
+fn byte_order_source(model: &str) -> &'static str {
+    match model {
+        // InfiniiVision scopes are switched to LSB-first words before the read.
+        "InfiniiVision" => ":WAVeform:BYTeorder LSBFirst",
+        // The DHO/MHO5000 programming guide exposes WORD format but no
+        // waveform byte-order command. The DHO driver decodes its fixed
+        // WORD payload as least-significant byte first.
+        _ => "DHO5000 WORD protocol",
+    }
+}
+
+fn warn_on_setup_drift(cfg: &Config, actual: &OscilloscopeSetup) {
+    let Some(expected) = cfg
+        .instruments
+        .as_ref()
+        .and_then(|instruments| instruments.oscilloscope.setup.as_ref())
+    else {
+        return;
+    };
+    for difference in crate::commands::scope::setup_differences(expected, actual) {
+        ui::warn(format!(
+            "oscilloscope {} is {}, scope.setup expects {}",
+            difference.field, difference.actual, difference.expected
+        ));
+    }
+}
+
+/// Records what the generator actually runs with; like the scope read-back it
+/// is diagnostic, so an unreachable generator only skips the record.
+fn read_back_generator_setup(cfg: &Config) -> Option<RawGeneratorMetadata> {
+    let expected = configured_generator_setup(cfg)?;
+    let generator = cfg.instruments.as_ref()?.function_generator.as_ref()?;
+    let read_back = FGHandler::initialize(cfg).and_then(|mut handler| handler.query_setup());
+    let setup = match read_back {
+        Ok(setup) => setup,
+        Err(error) => {
+            ui::warn(format!(
+                "function generator setup read-back skipped: {error:#}"
+            ));
+            return None;
+        }
+    };
+    for difference in generator_setup_differences(expected, &setup) {
+        ui::warn(format!(
+            "function generator {} is {}, generator.setup expects {}",
+            difference.field, difference.actual, difference.expected
+        ));
+    }
+    Some(RawGeneratorMetadata {
+        model: generator.model.clone(),
+        connection: generator.connection.clone(),
+        setup,
+    })
+}
+
 fn ensure_scope_stopped(handler: &mut OscilloscopeHandler, context: &str) -> Result<()> {
     let status = handler
         .query_trigger_status()
         .with_context(|| format!("failed to query oscilloscope trigger status {context}"))?;
-    if status != DhoTriggerStatus::Stop {
+    if status != TriggerStatus::Stop {
         bail!("oscilloscope must be STOP {context}, got {status:?}");
     }
     Ok(())
@@ -1188,7 +1298,7 @@ fn write_raw_channel(
     dir: &Path,
     ch: u8,
     expected_depth: usize,
-    raw: DhoRawWaveform,
+    raw: RawWaveform,
 ) -> Result<RawChannelMetadata> {
     validate_raw_word_byte_count(ch, raw.data.len(), expected_depth)?;
     let sample_count = expected_depth;
@@ -1320,7 +1430,7 @@ fn validate_raw_word_byte_count(ch: u8, byte_count: usize, expected_depth: usize
     Ok(())
 }
 
-fn convert_raw_word_to_voltages(raw: &DhoRawWaveform) -> Vec<f64> {
+fn convert_raw_word_to_voltages(raw: &RawWaveform) -> Vec<f64> {
     let scale = RawVoltageScale {
         y_increment: raw.preamble.y_increment,
         y_origin: raw.preamble.y_origin,
@@ -1361,7 +1471,7 @@ fn update_metadata_time_axis(
 
 fn update_time_axis(
     time_axis: &mut Option<RawTimeAxis>,
-    preamble: &instruments::rigol::dho5108::DhoWaveformPreamble,
+    preamble: &WaveformPreamble,
     sample_count: usize,
     ch: u8,
 ) -> Result<()> {
@@ -1,5 +1,5 @@
 use super::*;
-use instruments::rigol::dho5108::DhoWaveformPreamble;
+use crate::config::{GeneratorTriggerSource, ScopeChannelSetup, ScopeCoupling, ScopeTriggerSource};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -41,8 +41,8 @@ fn write_raw_channel_preserves_saturated_constant_word_bytes() {
     fs::create_dir(&dir).unwrap();
 
     let raw_bytes = vec![0xff, 0xff, 0xff, 0xff];
-    let raw = DhoRawWaveform {
-        preamble: DhoWaveformPreamble {
+    let raw = RawWaveform {
+        preamble: WaveformPreamble {
             raw: "0,0,0,0,5e-10,-0.03,0,0.001,0,32768".to_string(),
             x_increment: 5.0e-10,
             x_origin: -0.03,
@@ -202,7 +202,31 @@ fn raw_metadata_serializes_horizontal_settings() {
             channels: vec![1, 2, 3, 4],
             horizontal_offset: -0.03,
             horizontal_scale: 0.005,
+            setup: Some(OscilloscopeSetup {
+                timebase_scale: Some(0.005),
+                trigger_source: Some(ScopeTriggerSource::Ext),
+                channels: vec![ScopeChannelSetup {
+                    channel: 1,
+                    scale: Some(0.202),
+                    coupling: Some(ScopeCoupling::Dc),
+                    ..ScopeChannelSetup::default()
+                }],
+                ..OscilloscopeSetup::default()
+            }),
         },
+        generator: Some(RawGeneratorMetadata {
+            model: "WF1946B".to_string(),
+            connection: Connection::Sim {
+                bench: "default".to_string(),
+            },
+            setup: FunctionGeneratorSetup {
+                frequency_hz: Some(1.0e3),
+                burst_cycles: Some(1),
+                trigger_source: Some(GeneratorTriggerSource::Bus),
+                output: Some(true),
+                ..FunctionGeneratorSetup::default()
+            },
+        }),
         channels: vec![RawChannelMetadata {
             index: Some(1),
             file: "ch1.u16le".to_string(),
@@ -261,6 +285,15 @@ fn raw_metadata_serializes_horizontal_settings() {
         ch1_meta.vertical_scale
     );
     assert_eq!(ch1["preamble_raw"].as_str().unwrap(), ch1_meta.preamble_raw);
+
+    let setup = &decoded["oscilloscope"]["setup"];
+    assert_eq!(setup["trigger_source"].as_str(), Some("ext"));
+    assert_eq!(setup["channels"][0]["scale"].as_float(), Some(0.202));
+    assert_eq!(setup["channels"][0]["coupling"].as_str(), Some("dc"));
+    let generator = &decoded["generator"];
+    assert_eq!(generator["model"].as_str(), Some("WF1946B"));
+    assert_eq!(generator["setup"]["frequency_hz"].as_float(), Some(1.0e3));
+    assert_eq!(generator["setup"]["trigger_source"].as_str(), Some("bus"));
 }
 
 #[test]
@@ -528,7 +561,9 @@ fn single_channel_raw_metadata(file: &str, sample_count: usize) -> RawFetchMetad
             channels: vec![1],
             horizontal_offset: 0.0,
             horizontal_scale: 1.0,
+            setup: None,
         },
+        generator: None,
         channels,
     }
 }
@@ -930,12 +965,16 @@ fn process_auto_and_automeasure_preflight_before_state_or_hardware() {
     config.source_path = dir.join("config.toml");
     config.source_text = Some("version = 5\n".to_string());
     config.instruments = Some(crate::config::Instruments {
+        multimeters: Vec::new(),
         oscilloscope: crate::config::Oscilloscope {
             connection: crate::config::Connection::Tcpip {
                 ip: "127.0.0.1".to_string(),
                 port: 1,
             },
             model: "DHO5108".to_string(),
+            trigger_timeout_s: pmoke_config_core::DEFAULT_TRIGGER_TIMEOUT_S,
+            trigger_poll_interval_s: pmoke_config_core::DEFAULT_TRIGGER_POLL_INTERVAL_S,
+            setup: None,
         },
         function_generator: Some(crate::config::FunctionGenerator {
             connection: crate::config::Connection::Gpib {
@@ -943,6 +982,7 @@ fn process_auto_and_automeasure_preflight_before_state_or_hardware() {
                 address: 1,
             },
             model: "WF1946B".to_string(),
+            setup: None,
         }),
     });
     crate::commands::run_dir::ensure_run_directory(&config.paths().run_dir).unwrap();
@@ -1027,3 +1067,33 @@ fn test_process_and_auto_reject_force() {
             .contains("--force is not supported for process/auto")
     );
 }
+
+#[test]
+fn multimeter_readings_are_added_to_the_acquisition_manifest() {
+    let dir = unique_test_dir();
+    fs::create_dir(&dir).unwrap();
+    let manifest = dir.join("manifest.toml");
+    fs::write(&manifest, "schema_version = 1\nstatus = \"complete\"\n").unwrap();
+
+    record_multimeter_readings(
+        &manifest,
+        &[MultimeterReading {
+            label: "sample_temperature".to_string(),
+            model: "Keithley2010".to_string(),
+            function: crate::config::MultimeterFunction::Temperature,
+            unit: "degC",
+            before: Some(21.5),
+            after: Some(21.75),
+        }],
+    )
+    .unwrap();
+
+    let decoded: toml::Value = toml::from_str(&fs::read_to_string(&manifest).unwrap()).unwrap();
+    assert_eq!(decoded["status"].as_str(), Some("complete"));
+    let reading = &decoded["multimeters"][0];
+    assert_eq!(reading["label"].as_str(), Some("sample_temperature"));
+    assert_eq!(reading["function"].as_str(), Some("temperature"));
+    assert_eq!(reading["before"].as_float(), Some(21.5));
+    assert_eq!(reading["after"].as_float(), Some(21.75));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,3 +1,5 @@
+mod discover;
+
 use crate::cli::{InstrumentsCommand, JsonOutput};
 use crate::connection::{ConnectionDefaults, ConnectionUri};
 use crate::ui;
@@ -6,9 +8,6 @@ use instruments::registry::{InstrumentSpec, KNOWN_INSTRUMENTS};
 use instruments::transport::{BoxedScpiTransport, ScpiConnection, open_scpi_transport};
 use serde::Serialize;
 use std::collections::BTreeSet;
-use std::io::{BufRead, BufReader, Write};
-use std::net::{TcpStream, ToSocketAddrs};
-use std::time::Duration;
 
 #[derive(Debug, Serialize, PartialEq, Eq)]
 struct InstrumentListItem {
@@ -49,32 +48,17 @@ struct QueryOutput {
     response: String,
 }
 
-#[derive(Debug, Clone)]
-pub(crate) enum QueryConnection {
-    Tcpip { host: String, port: u16 },
-    Scpi(ScpiConnection),
-}
-
 pub(crate) struct TextQuerySession {
     connection: String,
-    inner: TextQuerySessionInner,
-}
-
-enum TextQuerySessionInner {
-    Tcp(BufReader<TcpStream>),
-    Scpi(BoxedScpiTransport),
+    transport: BoxedScpiTransport,
 }
 
 impl TextQuerySession {
     pub(crate) fn query_line(&mut self, request: &str) -> Result<String> {
-        let response = match &mut self.inner {
-            TextQuerySessionInner::Tcp(reader) => {
-                query_tcp_reader(reader, request, &self.connection)
-            }
-            TextQuerySessionInner::Scpi(transport) => transport
-                .query_line(request)
-                .with_context(|| format!("failed to query {}", self.connection)),
-        }?;
+        let response = self
+            .transport
+            .query_line(request)
+            .with_context(|| format!("failed to query {}", self.connection))?;
         let response = trim_scpi_line_ending(&response);
         if response.is_empty() {
             bail!("received an empty text response from {}", self.connection);
@@ -93,6 +77,7 @@ pub fn run(command: &InstrumentsCommand) -> Result<()> {
             json,
             command,
         } => query(connection, command, *timeout_ms, *json),
+        InstrumentsCommand::Discover(args) => discover::run(args),
     }
 }
 
@@ -186,7 +171,7 @@ fn explain(model: &str, json: bool) -> Result<()> {
 fn query(connection: &str, command: &str, timeout_ms: u64, json: bool) -> Result<()> {
     validate_scpi_query_command(command)?;
     let connection = parse_query_connection(connection, timeout_ms)?;
-    let mut session = open_text_query_session(&connection, timeout_ms)?;
+    let mut session = open_text_query_session(&connection)?;
     let response = session.query_line(command)?;
     let output = QueryOutput {
         connection: display_query_connection(&connection),
@@ -356,100 +341,33 @@ fn display_list(values: &[&str]) -> String {
     }
 }
 
-pub(crate) fn open_text_query_session(
-    connection: &QueryConnection,
-    timeout_ms: u64,
-) -> Result<TextQuerySession> {
-    let display = display_query_connection(connection);
-    match connection {
-        QueryConnection::Tcpip { host, port } => Ok(TextQuerySession {
-            connection: display,
-            inner: TextQuerySessionInner::Tcp(open_tcp_text(host, *port, timeout_ms)?),
-        }),
-        QueryConnection::Scpi(connection) => Ok(TextQuerySession {
-            connection: display,
-            inner: TextQuerySessionInner::Scpi(
-                open_scpi_transport(connection)
-                    .with_context(|| format!("failed to open {connection}"))?,
-            ),
-        }),
-    }
+pub(crate) fn open_text_query_session(connection: &ScpiConnection) -> Result<TextQuerySession> {
+    Ok(TextQuerySession {
+        connection: display_query_connection(connection),
+        transport: open_scpi_transport(connection)
+            .with_context(|| format!("failed to open {connection}"))?,
+    })
 }
 
 fn trim_scpi_line_ending(response: &str) -> &str {
     response.trim_end_matches(['\r', '\n'])
 }
 
-fn open_tcp_text(host: &str, port: u16, timeout_ms: u64) -> Result<BufReader<TcpStream>> {
-    let timeout = Duration::from_millis(timeout_ms);
-    let addresses = (host, port)
-        .to_socket_addrs()
-        .with_context(|| format!("failed to resolve {host}:{port}"))?
-        .collect::<Vec<_>>();
-    if addresses.is_empty() {
-        bail!("no socket address resolved for {host}:{port}");
-    }
-
-    let mut last_error = None;
-    let mut stream = None;
-    for address in addresses {
-        match TcpStream::connect_timeout(&address, timeout) {
-            Ok(candidate) => {
-                stream = Some(candidate);
-                break;
-            }
-            Err(error) => last_error = Some(error),
-        }
-    }
-    let stream = stream.ok_or_else(|| {
-        last_error
-            .map(anyhow::Error::from)
-            .unwrap_or_else(|| anyhow!("no socket address resolved for {host}:{port}"))
-    })?;
-    stream.set_read_timeout(Some(timeout))?;
-    stream.set_write_timeout(Some(timeout))?;
-    stream.set_nodelay(true)?;
-
-    Ok(BufReader::new(stream))
-}
-
-fn query_tcp_reader(
-    reader: &mut BufReader<TcpStream>,
-    request: &str,
-    connection: &str,
-) -> Result<String> {
-    writeln!(reader.get_mut(), "{request}")?;
-    reader.get_mut().flush()?;
-
-    let mut response = String::new();
-    for _ in 0..4 {
-        response.clear();
-        let read = reader.read_line(&mut response)?;
-        let line = trim_scpi_line_ending(&response);
-        if read == 0 {
-            bail!("connection closed before {connection} returned a text response");
-        }
-        if !line.is_empty() {
-            return Ok(line.to_string());
-        }
-    }
-    bail!("received only blank text response lines from {connection}")
-}
-
 #[cfg(test)]
 fn query_tcp_text(host: &str, port: u16, request: &str, timeout_ms: u64) -> Result<String> {
-    let connection = QueryConnection::Tcpip {
+    let connection = ScpiConnection::Tcp {
         host: host.to_string(),
         port,
+        timeout_ms,
     };
-    let mut session = open_text_query_session(&connection, timeout_ms)?;
+    let mut session = open_text_query_session(&connection)?;
     session.query_line(request)
 }
 
 pub(crate) fn parse_query_connection(
     value: &str,
     default_timeout_ms: u64,
-) -> Result<QueryConnection> {
+) -> Result<ScpiConnection> {
     if default_timeout_ms == 0 {
         bail!("timeout_ms must be positive");
     }
@@ -461,69 +379,88 @@ pub(crate) fn parse_query_connection(
     )
     .map_err(anyhow::Error::msg)?;
     Ok(match parsed {
-        ConnectionUri::Tcp { host, port } => QueryConnection::Tcpip { host, port },
+        ConnectionUri::Tcp { host, port } => ScpiConnection::Tcp {
+            host,
+            port,
+            timeout_ms: default_timeout_ms,
+        },
+        ConnectionUri::Vxi11 { host, device } => ScpiConnection::Vxi11 {
+            host,
+            device,
+            timeout_ms: default_timeout_ms,
+        },
         ConnectionUri::Visa { .. } => bail!(
-            "visa connections are not supported by generic instruments query yet; use a TCP/IP, GPIB, or Prologix SCPI connection"
+            "visa connections are not supported by generic instruments query yet; use a TCP/IP, VXI-11, USBTMC, GPIB, or Prologix SCPI connection"
         ),
-        ConnectionUri::Gpib { board, address } => QueryConnection::Scpi(ScpiConnection::Gpib {
+        ConnectionUri::UsbtmcDevice { path } => ScpiConnection::UsbtmcDevice { path },
+        ConnectionUri::Sim { .. } => bail!(
+            "sim:// benches only serve configured instruments; use a TCP/IP, VXI-11, USBTMC, GPIB, or Prologix SCPI connection"
+        ),
+        ConnectionUri::Gpib { board, address } => ScpiConnection::Gpib {
             board: i32::from(board),
             address: i32::from(address),
             timeout_secs: timeout_ms_to_secs(default_timeout_ms),
             use_crlf: false,
-        }),
+        },
         ConnectionUri::PrologixTcp {
             host,
             port,
             address,
             read_timeout_ms,
-        } => QueryConnection::Scpi(ScpiConnection::PrologixTcp {
+        } => ScpiConnection::PrologixTcp {
             host,
             port,
             address,
             read_timeout_ms,
-        }),
+        },
         ConnectionUri::PrologixSerial {
             path,
             address,
             baud_rate,
             read_timeout_ms,
-        } => QueryConnection::Scpi(ScpiConnection::PrologixSerial {
+        } => ScpiConnection::PrologixSerial {
             path,
             address,
             baud_rate,
             read_timeout_ms,
-        }),
+        },
     })
 }
 
-pub(crate) fn display_query_connection(connection: &QueryConnection) -> String {
+pub(crate) fn display_query_connection(connection: &ScpiConnection) -> String {
     match connection {
-        QueryConnection::Tcpip { host, port } => ConnectionUri::Tcp {
+        ScpiConnection::Tcp { host, port, .. } => ConnectionUri::Tcp {
             host: host.clone(),
             port: *port,
         }
         .to_string(),
-        QueryConnection::Scpi(ScpiConnection::Gpib { board, address, .. }) => {
-            format!("gpib://{board}/{address}")
+        ScpiConnection::Vxi11 { host, device, .. } => ConnectionUri::Vxi11 {
+            host: host.clone(),
+            device: device.clone(),
         }
-        QueryConnection::Scpi(ScpiConnection::PrologixTcp {
+        .to_string(),
+        ScpiConnection::UsbtmcDevice { path } => {
+            ConnectionUri::UsbtmcDevice { path: path.clone() }.to_string()
+        }
+        ScpiConnection::Gpib { board, address, .. } => format!("gpib://{board}/{address}"),
+        ScpiConnection::PrologixTcp {
             host,
             port,
             address,
             read_timeout_ms,
-        }) => ConnectionUri::PrologixTcp {
+        } => ConnectionUri::PrologixTcp {
             host: host.clone(),
             port: *port,
             address: *address,
             read_timeout_ms: *read_timeout_ms,
         }
         .to_string(),
-        QueryConnection::Scpi(ScpiConnection::PrologixSerial {
+        ScpiConnection::PrologixSerial {
             path,
             address,
             baud_rate,
             read_timeout_ms,
-        }) => ConnectionUri::PrologixSerial {
+        } => ConnectionUri::PrologixSerial {
             path: path.clone(),
             address: *address,
             baud_rate: *baud_rate,
@@ -533,21 +470,19 @@ pub(crate) fn display_query_connection(connection: &QueryConnection) -> String {
     }
 }
 
-pub(crate) fn configured_query_timeout_ms(
-    connection: &QueryConnection,
-    tcp_timeout_ms: u64,
-) -> u64 {
+pub(crate) fn configured_query_timeout_ms(connection: &ScpiConnection) -> u64 {
     match connection {
-        QueryConnection::Tcpip { .. } => tcp_timeout_ms,
-        QueryConnection::Scpi(ScpiConnection::Gpib { timeout_secs, .. }) => {
-            timeout_secs.saturating_mul(1_000)
+        ScpiConnection::Tcp { timeout_ms, .. } | ScpiConnection::Vxi11 { timeout_ms, .. } => {
+            *timeout_ms
         }
-        QueryConnection::Scpi(ScpiConnection::PrologixTcp {
+        ScpiConnection::UsbtmcDevice { .. } => instruments::transport::USBTMC_DRIVER_TIMEOUT_MS,
+        ScpiConnection::Gpib { timeout_secs, .. } => timeout_secs.saturating_mul(1_000),
+        ScpiConnection::PrologixTcp {
             read_timeout_ms, ..
-        })
-        | QueryConnection::Scpi(ScpiConnection::PrologixSerial {
+        }
+        | ScpiConnection::PrologixSerial {
             read_timeout_ms, ..
-        }) => u64::from(*read_timeout_ms),
+        } => u64::from(*read_timeout_ms),
     }
 }
 
@@ -558,6 +493,7 @@ fn timeout_ms_to_secs(timeout_ms: u64) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::{BufRead, BufReader, Write};
     use std::net::TcpListener;
     use std::thread;
 
@@ -598,7 +534,7 @@ mod tests {
 
         assert!(item.required_features.contains(&"hw-gpib"));
         assert!(!item.required_features.contains(&"hw-gpib on Windows"));
-        assert_eq!(item.notes, vec!["Windows + NI-VISA"]);
+        assert_eq!(item.notes, vec!["Linux usbtmc driver", "Windows + NI-VISA"]);
         assert!(details.connection_templates.iter().any(|example| {
             example.transport == "usbtmc"
                 && example.required_feature == Some("hw-gpib")
@@ -626,27 +562,44 @@ mod tests {
         let tcp = parse_query_connection("tcp://192.168.10.100:55255", 2500).unwrap();
         assert!(matches!(
             tcp,
-            QueryConnection::Tcpip { host, port }
+            ScpiConnection::Tcp { host, port, timeout_ms: 2500 }
                 if host == "192.168.10.100" && port == 55255
         ));
 
         let tcp_ipv6 = parse_query_connection("tcp://[2001:db8::1]:55255", 2500).unwrap();
         assert!(matches!(
             tcp_ipv6,
-            QueryConnection::Tcpip { host, port }
+            ScpiConnection::Tcp { host, port, .. }
                 if host == "2001:db8::1" && port == 55255
         ));
 
+        let vxi11 = parse_query_connection("vxi11://192.168.10.101", 2500).unwrap();
+        assert_eq!(
+            display_query_connection(&vxi11),
+            "vxi11://192.168.10.101/inst0"
+        );
+        assert!(matches!(
+            vxi11,
+            ScpiConnection::Vxi11 { device, timeout_ms: 2500, .. } if device == "inst0"
+        ));
+
+        let usbtmc = parse_query_connection("usbtmc:///dev/usbtmc0", 2500).unwrap();
+        assert_eq!(display_query_connection(&usbtmc), "usbtmc:///dev/usbtmc0");
+        assert_eq!(
+            configured_query_timeout_ms(&usbtmc),
+            instruments::transport::USBTMC_DRIVER_TIMEOUT_MS
+        );
+
         let prologix =
             parse_query_connection("prologix-tcp://10.249.11.17:1234?addr=17", 2500).unwrap();
         assert!(matches!(
             prologix,
-            QueryConnection::Scpi(ScpiConnection::PrologixTcp {
+            ScpiConnection::PrologixTcp {
                 ref host,
                 port: 1234,
                 address: 17,
                 read_timeout_ms: 2500,
-            }) if host == "10.249.11.17"
+            } if host == "10.249.11.17"
         ));
         assert_eq!(
             display_query_connection(&prologix),
@@ -702,12 +655,12 @@ mod tests {
         let gpib = parse_query_connection("gpib://0/17", 2500).unwrap();
         assert!(matches!(
             gpib,
-            QueryConnection::Scpi(ScpiConnection::Gpib {
+            ScpiConnection::Gpib {
                 board: 0,
                 address: 17,
                 timeout_secs: 3,
                 use_crlf: false,
-            })
+            }
         ));
 
         let serial = parse_query_connection(
@@ -717,27 +670,27 @@ mod tests {
         .unwrap();
         assert!(matches!(
             serial,
-            QueryConnection::Scpi(ScpiConnection::PrologixSerial {
+            ScpiConnection::PrologixSerial {
                 path,
                 address: 11,
                 baud_rate: 57600,
                 read_timeout_ms: 1500,
-            }) if path == "/dev/cu.usbserial-XXXX"
+            } if path == "/dev/cu.usbserial-XXXX"
         ));
     }
 
     #[test]
     fn configured_query_timeout_uses_parsed_transport_value() {
         let tcp = parse_query_connection("tcp://host:5025", 1250).unwrap();
-        assert_eq!(configured_query_timeout_ms(&tcp, 1250), 1250);
+        assert_eq!(configured_query_timeout_ms(&tcp), 1250);
 
         let gpib = parse_query_connection("gpib://0/17", 1250).unwrap();
-        assert_eq!(configured_query_timeout_ms(&gpib, 1250), 2000);
+        assert_eq!(configured_query_timeout_ms(&gpib), 2000);
 
         let prologix =
             parse_query_connection("prologix-tcp://host:1234?addr=17&read_timeout_ms=750", 1250)
                 .unwrap();
-        assert_eq!(configured_query_timeout_ms(&prologix, 1250), 750);
+        assert_eq!(configured_query_timeout_ms(&prologix), 750);
     }
 
     #[test]
@@ -775,7 +728,7 @@ mod tests {
         let error = query_tcp_text("127.0.0.1", port, "*IDN?", 1000).unwrap_err();
 
         server.join().unwrap();
-        assert!(error.to_string().contains("closed before"));
+        assert!(format!("{error:#}").contains("closed before"), "{error:#}");
     }
 
     #[test]
@@ -0,0 +1,715 @@
+//! `pmoke instruments discover`: probes GPIB boards, VISA resources, Prologix
+//! controllers and TCP/IP hosts with `*IDN?` and matches the responses against
+//! the instrument registry.
+
+use super::{display_query_connection, timeout_ms_to_secs, trim_scpi_line_ending};
+use crate::cli::DiscoverArgs;
+use crate::connection::{ConnectionDefaults, ConnectionUri, DEFAULT_PROLOGIX_READ_TIMEOUT_MS};
+use crate::ui;
+use anyhow::{Context, Result, bail};
+use instruments::InstrumentError;
+use instruments::registry::find_instrument_by_idn;
+use instruments::transport::{
+    BoxedScpiTransport, ScpiConnection, find_visa_resources, open_scpi_transport,
+    open_visa_transport, scan_gpib_board,
+};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::io;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+const IDN_QUERY: &str = "*IDN?";
+const MAX_GPIB_ADDRESS: u8 = 30;
+const MAX_PROLOGIX_READ_TIMEOUT_MS: u64 = 3000;
+/// Concurrent connection attempts while probing TCP/IP hosts.
+const TCP_PROBE_THREADS: usize = 64;
+/// Shortest IPv4 prefix accepted by `--tcp`, i.e. at most 4094 hosts.
+const MIN_TCP_PREFIX_LEN: u32 = 20;
+
+#[derive(Debug, Default, Serialize)]
+struct DiscoverOutput {
+    instruments: Vec<DiscoveredInstrument>,
+    sources: Vec<SourceReport>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct DiscoveredInstrument {
+    connection: String,
+    model: Option<&'static str>,
+    role: Option<&'static str>,
+    idn: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct SourceReport {
+    source: String,
+    status: SourceStatus,
+    detail: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum SourceStatus {
+    Scanned,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct TcpTarget {
+    label: String,
+    hosts: Vec<String>,
+}
+
+#[derive(Debug)]
+struct PrologixController {
+    label: String,
+    connection: ScpiConnection,
+}
+
+pub(super) fn run(args: &DiscoverArgs) -> Result<()> {
+    if args.timeout_ms == 0 {
+        bail!("timeout_ms must be positive");
+    }
+    let controllers = args
+        .prologix
+        .iter()
+        .map(|value| parse_prologix_controller(value, args.timeout_ms))
+        .collect::<Result<Vec<_>>>()?;
+    let tcp_targets = args
+        .tcp
+        .iter()
+        .map(|value| parse_tcp_target(value))
+        .collect::<Result<Vec<_>>>()?;
+    if !tcp_targets.is_empty() && args.ports.is_empty() {
+        bail!("--tcp needs at least one --port");
+    }
+
+    let timeout = Duration::from_millis(args.timeout_ms);
+    let mut output = DiscoverOutput::default();
+    let pb = (!args.json).then(|| ui::spinner("Scanning for instruments"));
+    let set_message = |message: String| {
+        if let Some(pb) = &pb {
+            pb.set_message(message);
+        }
+    };
+    if !args.no_gpib {
+        for board in &args.gpib_boards {
+            set_message(format!("Scanning gpib{board}"));
+            discover_gpib_board(*board, args.timeout_ms, &mut output);
+        }
+    }
+    if !args.no_visa {
+        set_message("Listing VISA resources".to_string());
+        discover_visa(timeout, !args.no_gpib, &mut output);
+    }
+    for controller in &controllers {
+        set_message(format!("Sweeping {}", controller.label));
+        discover_prologix(controller, &mut output);
+    }
+    for target in &tcp_targets {
+        set_message(format!("Probing {}", target.label));
+        discover_tcp(target, &args.ports, args.timeout_ms, &mut output)?;
+    }
+    if let Some(pb) = pb {
+        ui::finish_read(
+            pb,
+            format!(
+                "{} instrument(s) answered on {} source(s)",
+                output.instruments.len(),
+                output.sources.len()
+            ),
+        );
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print_output(&output);
+    }
+    Ok(())
+}
+
+fn print_output(output: &DiscoverOutput) {
+    ui::section("Discovered Instruments");
+    if output.instruments.is_empty() {
+        ui::info("no instrument answered *IDN?");
+    } else {
+        println!(
+            "{}",
+            ui::table(
+                &["Connection", "Model", "Response"],
+                output
+                    .instruments
+                    .iter()
+                    .map(|instrument| {
+                        vec![
+                            instrument.connection.clone(),
+                            instrument.model.unwrap_or("unknown").to_string(),
+                            match (&instrument.idn, &instrument.error) {
+                                (Some(idn), _) => idn.clone(),
+                                (None, Some(error)) => format!("*IDN? failed: {error}"),
+                                (None, None) => "-".to_string(),
+                            },
+                        ]
+                    })
+                    .collect(),
+            )
+        );
+        let known = output
+            .instruments
+            .iter()
+            .filter_map(|instrument| Some((instrument.model?, &instrument.connection)))
+            .collect::<Vec<_>>();
+        if !known.is_empty() {
+            ui::section("Connection URIs");
+            for (model, connection) in known {
+                println!("connection = \"{connection}\"  # {model}");
+            }
+        }
+    }
+
+    ui::section("Sources");
+    println!(
+        "{}",
+        ui::table(
+            &["Source", "Status", "Detail"],
+            output
+                .sources
+                .iter()
+                .map(|source| {
+                    vec![
+                        source.source.clone(),
+                        source.status.as_str().to_string(),
+                        source.detail.clone(),
+                    ]
+                })
+                .collect(),
+        )
+    );
+}
+
+impl SourceStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Scanned => "scanned",
+            Self::Skipped => "skipped",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl SourceReport {
+    fn scanned(source: impl Into<String>, detail: String) -> Self {
+        Self {
+            source: source.into(),
+            status: SourceStatus::Scanned,
+            detail,
+        }
+    }
+
+    /// Unsupported transports are skipped rather than failed, so a build
+    /// without GPIB or a non-Windows host does not look broken.
+    fn from_error(source: impl Into<String>, error: &InstrumentError) -> Self {
+        let unsupported = matches!(
+            error,
+            InstrumentError::Io(error) if error.kind() == io::ErrorKind::Unsupported
+        );
+        Self {
+            source: source.into(),
+            status: if unsupported {
+                SourceStatus::Skipped
+            } else {
+                SourceStatus::Failed
+            },
+            detail: error.to_string(),
+        }
+    }
+}
+
+fn discover_gpib_board(board: u8, timeout_ms: u64, output: &mut DiscoverOutput) {
+    let source = format!("gpib{board}");
+    let timeout_secs = timeout_ms_to_secs(timeout_ms);
+    let addresses = match scan_gpib_board(i32::from(board), timeout_secs) {
+        Ok(addresses) => addresses,
+        Err(error) => {
+            output
+                .sources
+                .push(SourceReport::from_error(source, &error));
+            return;
+        }
+    };
+    for address in &addresses {
+        let connection = ScpiConnection::Gpib {
+            board: i32::from(board),
+            address: *address,
+            timeout_secs,
+            use_crlf: false,
+        };
+        output.instruments.push(probe(
+            display_query_connection(&connection),
+            open_scpi_transport(&connection),
+        ));
+    }
+    output.sources.push(SourceReport::scanned(
+        source,
+        format!("{} listener(s) on the bus", addresses.len()),
+    ));
+}
+
+/// Serial ports are never probed: writing `*IDN?` to an unknown serial
+/// device is not harmless. GPIB resources are left to the board scan.
+fn discover_visa(timeout: Duration, gpib_scanned: bool, output: &mut DiscoverOutput) {
+    let resources = match find_visa_resources() {
+        Ok(resources) => resources,
+        Err(error) => {
+            output
+                .sources
+                .push(SourceReport::from_error("visa", &error));
+            return;
+        }
+    };
+    let probed = resources
+        .iter()
+        .filter(|resource| {
+            let covered_by_gpib_scan = gpib_scanned && resource.starts_with("GPIB");
+            !(resource.starts_with("ASRL") || covered_by_gpib_scan)
+        })
+        .collect::<Vec<_>>();
+    for resource in &probed {
+        output.instruments.push(probe(
+            ConnectionUri::Visa {
+                resource: resource.to_string(),
+            }
+            .to_string(),
+            open_visa_transport(resource, timeout),
+        ));
+    }
+    output.sources.push(SourceReport::scanned(
+        "visa",
+        format!(
+            "{} of {} INSTR resource(s) probed",
+            probed.len(),
+            resources.len()
+        ),
+    ));
+}
+
+/// Sweeps every GPIB address over one controller connection. Empty
+/// addresses time out inside the controller, so a sweep takes up to 31
+/// read timeouts.
+fn discover_prologix(controller: &PrologixController, output: &mut DiscoverOutput) {
+    let mut transport = match open_scpi_transport(&controller.connection) {
+        Ok(transport) => transport,
+        Err(error) => {
+            output
+                .sources
+                .push(SourceReport::from_error(controller.label.clone(), &error));
+            return;
+        }
+    };
+    let mut found = 0;
+    for address in 0..=MAX_GPIB_ADDRESS {
+        let response = transport
+            .set_gpib_address(address)
+            .and_then(|()| transport.query_line(IDN_QUERY));
+        match response {
+            Ok(idn) if trim_scpi_line_ending(&idn).trim().is_empty() => {}
+            Ok(idn) => {
+                found += 1;
+                output.instruments.push(identified(
+                    prologix_instrument_uri(&controller.connection, address),
+                    &idn,
+                ));
+            }
+            Err(error) if error.is_timeout() => {}
+            Err(error) => {
+                output.sources.push(SourceReport {
+                    source: controller.label.clone(),
+                    status: SourceStatus::Failed,
+                    detail: format!("sweep stopped at address {address}: {error}"),
+                });
+                return;
+            }
+        }
+    }
+    output.sources.push(SourceReport::scanned(
+        controller.label.clone(),
+        format!(
+            "{found} of {} GPIB addresses answered",
+            MAX_GPIB_ADDRESS + 1
+        ),
+    ));
+}
+
+/// Hosts that refuse or ignore the connection are not reported; an open
+/// port that does not answer `*IDN?` is.
+fn discover_tcp(
+    target: &TcpTarget,
+    ports: &[u16],
+    timeout_ms: u64,
+    output: &mut DiscoverOutput,
+) -> Result<()> {
+    let candidates = target
+        .hosts
+        .iter()
+        .flat_map(|host| {
+            ports.iter().map(|port| ScpiConnection::Tcp {
+                host: host.clone(),
+                port: *port,
+                timeout_ms,
+            })
+        })
+        .collect::<Vec<_>>();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(TCP_PROBE_THREADS.min(candidates.len()).max(1))
+        .build()
+        .context("Failed to build TCP probe thread pool")?;
+    let found = pool.install(|| {
+        candidates
+            .par_iter()
+            .filter_map(|connection| {
+                let transport = open_scpi_transport(connection).ok()?;
+                Some(probe(display_query_connection(connection), Ok(transport)))
+            })
+            .collect::<Vec<_>>()
+    });
+    output.sources.push(SourceReport::scanned(
+        target.label.clone(),
+        format!(
+            "{} open port(s) among {} host(s) x {} port(s)",
+            found.len(),
+            target.hosts.len(),
+            ports.len()
+        ),
+    ));
+    output.instruments.extend(found);
+    Ok(())
+}
+
+fn probe(
+    connection: String,
+    transport: instruments::Result<BoxedScpiTransport>,
+) -> DiscoveredInstrument {
+    match transport.and_then(|mut transport| transport.query_line(IDN_QUERY)) {
+        Ok(idn) => identified(connection, &idn),
+        Err(error) => DiscoveredInstrument {
+            connection,
+            model: None,
+            role: None,
+            idn: None,
+            error: Some(error.to_string()),
+        },
+    }
+}
+
+fn identified(connection: String, idn: &str) -> DiscoveredInstrument {
+    let idn = trim_scpi_line_ending(idn).trim();
+    let spec = find_instrument_by_idn(idn);
+    DiscoveredInstrument {
+        connection,
+        model: spec.map(|spec| spec.model),
+        role: spec.map(|spec| spec.role.as_str()),
+        idn: Some(idn.to_string()),
+        error: None,
+    }
+}
+
+/// Found instruments get the default read timeout, not the short sweep one,
+/// so the URI can be pasted into a config as is.
+fn prologix_instrument_uri(controller: &ScpiConnection, address: u8) -> String {
+    match controller {
+        ScpiConnection::PrologixTcp { host, port, .. } => ConnectionUri::PrologixTcp {
+            host: host.clone(),
+            port: *port,
+            address,
+            read_timeout_ms: DEFAULT_PROLOGIX_READ_TIMEOUT_MS,
+        }
+        .to_string(),
+        ScpiConnection::PrologixSerial {
+            path, baud_rate, ..
+        } => ConnectionUri::PrologixSerial {
+            path: path.clone(),
+            address,
+            baud_rate: *baud_rate,
+            read_timeout_ms: DEFAULT_PROLOGIX_READ_TIMEOUT_MS,
+        }
+        .to_string(),
+        other => display_query_connection(other),
+    }
+}
+
+/// Parses a Prologix controller URI without `addr`; the sweep supplies every
+/// address itself.
+fn parse_prologix_controller(value: &str, timeout_ms: u64) -> Result<PrologixController> {
+    let query = value.split_once('?').map(|(_, query)| query);
+    if query.is_some_and(|query| {
+        query.split('&').any(|param| {
+            matches!(
+                param.split_once('=').map_or(param, |(key, _)| key),
+                "addr" | "address"
+            )
+        })
+    }) {
+        bail!("--prologix sweeps every GPIB address; remove addr from {value}");
+    }
+    let separator = if query.is_some() { '&' } else { '?' };
+    let parsed = ConnectionUri::parse(
+        &format!("{value}{separator}addr=0"),
+        ConnectionDefaults {
+            prologix_read_timeout_ms: timeout_ms.min(MAX_PROLOGIX_READ_TIMEOUT_MS),
+        },
+    )
+    .map_err(anyhow::Error::msg)?;
+    let connection = match parsed {
+        ConnectionUri::PrologixTcp {
+            host,
+            port,
+            read_timeout_ms,
+            ..
+        } => ScpiConnection::PrologixTcp {
+            host,
+            port,
+            address: 0,
+            read_timeout_ms,
+        },
+        ConnectionUri::PrologixSerial {
+            path,
+            baud_rate,
+            read_timeout_ms,
+            ..
+        } => ScpiConnection::PrologixSerial {
+            path,
+            address: 0,
+            baud_rate,
+            read_timeout_ms,
+        },
+        _ => bail!("--prologix expects a prologix-tcp:// or prologix-serial:// URI, got {value}"),
+    };
+    Ok(PrologixController {
+        label: value.to_string(),
+        connection,
+    })
+}
+
+/// Parses `--tcp` as one host name or address, or as an IPv4 subnet whose
+/// network and broadcast addresses are left out.
+fn parse_tcp_target(value: &str) -> Result<TcpTarget> {
+    let value = value.trim();
+    let Some((address, prefix_len)) = value.split_once('/') else {
+        let host = value
+            .strip_prefix('[')
+            .and_then(|host| host.strip_suffix(']'))
+            .unwrap_or(value);
+        if host.is_empty() {
+            bail!("--tcp host must not be empty");
+        }
+        return Ok(TcpTarget {
+            label: value.to_string(),
+            hosts: vec![host.to_string()],
+        });
+    };
+    let address = address.parse::<Ipv4Addr>().with_context(|| {
+        format!("--tcp subnet must be an IPv4 CIDR such as 192.0.2.0/24: {value}")
+    })?;
+    let prefix_len = prefix_len
+        .parse::<u32>()
+        .ok()
+        .filter(|prefix_len| *prefix_len <= 32)
+        .with_context(|| format!("invalid --tcp prefix length: {value}"))?;
+    if prefix_len < MIN_TCP_PREFIX_LEN {
+        bail!("--tcp scans at most a /{MIN_TCP_PREFIX_LEN} subnet at a time: {value}");
+    }
+    let mask = u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0);
+    let network = u32::from(address) & mask;
+    let broadcast = network | !mask;
+    let hosts = if prefix_len >= 31 {
+        network..=broadcast
+    } else {
+        network + 1..=broadcast - 1
+    };
+    Ok(TcpTarget {
+        label: format!("{}/{prefix_len}", Ipv4Addr::from(network)),
+        hosts: hosts.map(|host| Ipv4Addr::from(host).to_string()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn tcp_targets_expand_subnets_without_network_and_broadcast() {
+        let subnet = parse_tcp_target("192.0.2.9/30").unwrap();
+        assert_eq!(subnet.label, "192.0.2.8/30");
+        assert_eq!(subnet.hosts, vec!["192.0.2.9", "192.0.2.10"]);
+
+        assert_eq!(
+            parse_tcp_target("192.0.2.9/32").unwrap().hosts,
+            vec!["192.0.2.9"]
+        );
+        assert_eq!(
+            parse_tcp_target("[2001:db8::1]").unwrap().hosts,
+            vec!["2001:db8::1"]
+        );
+        assert_eq!(
+            parse_tcp_target("scope.local").unwrap().hosts,
+            vec!["scope.local"]
+        );
+        for invalid in ["10.0.0.0/8", "192.0.2.0/33", "scope.local/24", ""] {
+            assert!(parse_tcp_target(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn prologix_controllers_are_given_without_an_address() {
+        let controller = parse_prologix_controller("prologix-tcp://bridge.local", 500).unwrap();
+        assert!(matches!(
+            controller.connection,
+            ScpiConnection::PrologixTcp {
+                ref host,
+                port: 1234,
+                address: 0,
+                read_timeout_ms: 500,
+            } if host == "bridge.local"
+        ));
+        assert_eq!(
+            prologix_instrument_uri(&controller.connection, 17),
+            "prologix-tcp://bridge.local:1234?addr=17&read_timeout_ms=3000"
+        );
+
+        let serial =
+            parse_prologix_controller("prologix-serial:///dev/ttyUSB0?baud_rate=9600", 5000)
+                .unwrap();
+        assert!(matches!(
+            serial.connection,
+            ScpiConnection::PrologixSerial {
+                baud_rate: 9600,
+                read_timeout_ms: 3000,
+                ..
+            }
+        ));
+
+        for invalid in [
+            "prologix-tcp://bridge.local?addr=17",
+            "prologix-serial:///dev/ttyUSB0?baud_rate=9600&address=3",
+            "tcp://bridge.local:1234",
+        ] {
+            assert!(
+                parse_prologix_controller(invalid, 500).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn tcp_discovery_matches_idn_against_the_registry() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut command = String::new();
+            reader.read_line(&mut command).unwrap();
+            assert_eq!(command, "*IDN?\n");
+            reader
+                .get_mut()
+                .write_all(b"RIGOL TECHNOLOGIES,DHO5108,serial,firmware\n")
+                .unwrap();
+        });
+
+        let target = parse_tcp_target("127.0.0.1").unwrap();
+        let mut output = DiscoverOutput::default();
+        discover_tcp(&target, &[port], 1000, &mut output).unwrap();
+        server.join().unwrap();
+
+        assert_eq!(
+            output.instruments,
+            vec![DiscoveredInstrument {
+                connection: format!("tcp://127.0.0.1:{port}"),
+                model: Some("DHO5108"),
+                role: Some("oscilloscope"),
+                idn: Some("RIGOL TECHNOLOGIES,DHO5108,serial,firmware".to_string()),
+                error: None,
+            }]
+        );
+        assert_eq!(output.sources[0].status, SourceStatus::Scanned);
+    }
+
+    #[cfg(feature = "hw-prologix-tcp")]
+    #[test]
+    fn prologix_sweep_reports_each_answering_address() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut address = 0;
+            let mut line = String::new();
+            loop {
+                line.clear();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+                if let Some(value) = line.trim().strip_prefix("++addr ") {
+                    address = value.parse::<u8>().unwrap();
+                } else if line.trim() == "++read eoi" {
+                    // An empty line stands in for the controller's read
+                    // timeout on an address without an instrument.
+                    let response: &[u8] = match address {
+                        17 => b"KEITHLEY INSTRUMENTS INC.,MODEL 2010,1,A13\n",
+                        22 => b"ACME,WIDGET,1,1\n",
+                        _ => b"\n",
+                    };
+                    reader.get_mut().write_all(response).unwrap();
+                }
+            }
+        });
+
+        let controller =
+            parse_prologix_controller(&format!("prologix-tcp://127.0.0.1:{port}"), 200).unwrap();
+        let mut output = DiscoverOutput::default();
+        discover_prologix(&controller, &mut output);
+        server.join().unwrap();
+
+        let found = output
+            .instruments
+            .iter()
+            .map(|instrument| (instrument.connection.as_str(), instrument.model))
+            .collect::<Vec<_>>();
+        let uri = |address| {
+            format!("prologix-tcp://127.0.0.1:{port}?addr={address}&read_timeout_ms=3000")
+        };
+        assert_eq!(
+            found,
+            vec![
+                (uri(17).as_str(), Some("Keithley2010")),
+                (uri(22).as_str(), None),
+            ]
+        );
+        assert_eq!(output.sources[0].detail, "2 of 31 GPIB addresses answered");
+    }
+
+    #[test]
+    fn unsupported_sources_are_skipped_not_failed() {
+        let unsupported = InstrumentError::from(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "VISA resources require NI-VISA on Windows and GPIB support",
+        ));
+        let refused = InstrumentError::from(io::Error::from(io::ErrorKind::ConnectionRefused));
+
+        assert_eq!(
+            SourceReport::from_error("visa", &unsupported).status,
+            SourceStatus::Skipped
+        );
+        assert_eq!(
+            SourceReport::from_error("gpib0", &refused).status,
+            SourceStatus::Failed
+        );
+    }
+}
@@ -24,7 +24,10 @@ fn kerr_inner(cfg: &Config) -> Result<()> {
         crate::commands::run_dir::AnalysisStage::Kerr,
     )?;
     crate::commands::run_dir::write_analysis_config_snapshots(&staging_cfg)?;
-    run(&staging_cfg)?;
+    let provenance = run(&staging_cfg)?;
     crate::lockin::provenance::refresh_analysis_manifest_outputs(&staging_cfg, "kerr")?;
+    if let Some(provenance) = provenance {
+        crate::lockin::provenance::write_kerr_provenance(&staging_cfg, &provenance)?;
+    }
     crate::commands::run_dir::publish_analysis_staging(cfg, &staging_cfg)
 }
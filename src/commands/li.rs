@@ -1,6 +1,5 @@
 use crate::config::Config;
-use crate::lockin::run_li;
-use crate::utils::waveform::read_all_fetched_waveforms;
+use crate::lockin::{read_lockin_input, run_li_input};
 use anyhow::{Result, bail};
 
 pub fn li(cfg: &Config) -> Result<()> {
@@ -18,23 +17,26 @@ pub fn li(cfg: &Config) -> Result<()> {
 }
 
 fn li_inner(cfg: &Config) -> Result<()> {
-    let data = read_all_fetched_waveforms(cfg)?;
-    if data.channels.is_empty() {
+    let fetched = read_lockin_input(cfg)?;
+    let data = fetched.as_input();
+    if data.channel_count() == 0 {
         bail!("fetched data is empty, cannot run lock-in analysis");
     }
-    crate::commands::analyze::validate_waveform_data(&data)?;
+    crate::commands::analyze::validate_waveform_data(data.loaded())?;
     let staging_cfg = crate::commands::run_dir::prepare_analysis_staging(
         cfg,
         crate::commands::run_dir::AnalysisStage::Li,
     )?;
     crate::commands::run_dir::write_analysis_config_snapshots(&staging_cfg)?;
-    let (_, _, _, _, reference, provenance) = run_li(&staging_cfg, &data.t, &data.channels)?;
+    let (_, _, _, _, _, reference, provenance, sensor) = run_li_input(&staging_cfg, data)?;
     crate::lockin::provenance::write_analysis_metadata(
         &staging_cfg,
         &staging_cfg.paths(),
         &cfg.resolver(),
         &reference,
         &provenance,
+        &sensor,
+        None,
         staging_cfg.roles.reference_ch,
     )?;
     crate::commands::run_dir::publish_analysis_staging(cfg, &staging_cfg)
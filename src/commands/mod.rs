@@ -6,6 +6,8 @@ pub mod automeasure;
 #[cfg(feature = "hw-core")]
 pub mod autoshot;
 pub mod bench;
+#[cfg(feature = "hw-core")]
+pub mod campaign;
 pub mod completions;
 pub mod config;
 pub mod doctor;
@@ -16,6 +18,8 @@ pub mod instruments;
 pub mod kerr;
 pub mod li;
 pub mod monitor;
+#[cfg(feature = "hw-core")]
+pub mod multimeter;
 pub mod phase;
 #[cfg(feature = "hw-core")]
 pub mod process;
@@ -23,10 +27,13 @@ pub mod raw;
 pub mod reference;
 pub mod run_dir;
 #[cfg(feature = "hw-core")]
+pub mod scope;
+#[cfg(feature = "hw-core")]
 pub mod screenshot;
 pub mod sensor;
 pub mod show;
 #[cfg(feature = "hw-core")]
 pub mod single;
+pub mod stack;
 #[cfg(feature = "hw-core")]
 pub mod trigger;
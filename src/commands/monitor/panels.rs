@@ -23,8 +23,11 @@ pub(super) fn render_config(frame: &mut Frame<'_>, app: &MonitorApp, area: Rect)
         vec![
             "Lock-in".to_string(),
             format!(
-                "{:?}, workers={}, stride={}",
-                cfg.lockin.lpf_kind, cfg.lockin.workers, cfg.lockin.stride_samples
+                "{:?}, harmonics={:?}, workers={}, stride={}",
+                cfg.lockin.lpf_kind,
+                cfg.lockin.harmonics,
+                cfg.lockin.workers,
+                cfg.lockin.stride_samples
             ),
         ],
         vec![
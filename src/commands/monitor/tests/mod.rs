@@ -1,7 +1,8 @@
 use super::*;
 use crate::config::{
-    Channel, ConfigDiagnostic, DiagnosticKind, Fetch, Kerr, KerrType, Lockin, LockinLpfKind, Phase,
-    Plot, Pulse, Reference, Roles, Screenshot, Window,
+    Channel, ConfigDiagnostic, DiagnosticKind, Fetch, Kerr, KerrModulationDepth, KerrType, Lockin,
+    LockinLpfKind, Phase, PhaseDrift, Plot, Pulse, PulseBaseline, Reference, Roles, Screenshot,
+    Window,
 };
 
 fn test_app() -> MonitorApp {
@@ -48,6 +49,7 @@ fn ready_test_app(channel_count: u8) -> MonitorApp {
                         index,
                         factor: None,
                         scale_to_abs_max: None,
+                        coil: None,
                         label: Some(format!("channel {index}")),
                         unit_out: None,
                     })
@@ -55,6 +57,7 @@ fn ready_test_app(channel_count: u8) -> MonitorApp {
                 pulse: Pulse {
                     bg_window_before: window,
                     bg_window_after: window,
+                    baseline: PulseBaseline::Constant,
                 },
                 reference: Reference {
                     fft_window: window,
@@ -64,8 +67,12 @@ fn ready_test_app(channel_count: u8) -> MonitorApp {
                 lockin: Lockin {
                     workers: 1,
                     stride_samples: 1,
+                    harmonics: vec![1, 2, 3, 4, 5, 6],
                     lpf_kind: LockinLpfKind::BoxcarLegacy,
                     lpf_half_window_cycles: 1.0,
+                    lpf_cutoff: None,
+                    lpf_order: pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+                    lpf_zero_phase: true,
                     lpf_debug_output: false,
                     lpf_debug_label: None,
                     lpf_debug_overwrite: false,
@@ -75,11 +82,16 @@ fn ready_test_app(channel_count: u8) -> MonitorApp {
                 },
                 phase: Phase {
                     m_omega_t0_offset: Vec::new(),
+                    drift: PhaseDrift::Constant,
                 },
                 kerr: Kerr {
                     use_sensor_ch: 1,
                     kerr_type: KerrType::Standard,
                     factor: 1.0,
+                    modulation_depth: KerrModulationDepth::default(),
+                    field_bin_width: None,
+                    background_run: None,
+                    field_background: None,
                 },
             },
             warnings: Vec::new(),
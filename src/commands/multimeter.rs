@@ -0,0 +1,117 @@
+use crate::communications::multimeter::{DmmHandler, reading_unit};
+use crate::config::{Config, Multimeter, MultimeterFunction};
+use crate::ui;
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+/// Readings of one `[[multimeters]]` entry around a shot, recorded under
+/// `[[multimeters]]` in the acquisition and analysis manifests.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct MultimeterReading {
+    pub label: String,
+    pub model: String,
+    pub function: MultimeterFunction,
+    pub unit: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<f64>,
+}
+
+/// Connected and configured multimeters of one run.
+pub(crate) struct Multimeters<'a> {
+    meters: Vec<(&'a Multimeter, DmmHandler)>,
+}
+
+impl<'a> Multimeters<'a> {
+    /// Connects to, identifies and configures every configured multimeter;
+    /// part of the instrument preflight.
+    pub(crate) fn connect(cfg: &'a Config) -> Result<Self> {
+        let configured = cfg
+            .instruments
+            .as_ref()
+            .map(|instruments| instruments.multimeters.as_slice())
+            .unwrap_or_default();
+        let mut meters = Vec::with_capacity(configured.len());
+        for multimeter in configured {
+            let label = &multimeter.label;
+            let mut handler = DmmHandler::initialize(multimeter)
+                .with_context(|| format!("failed to connect to multimeter {label:?}"))?;
+            handler
+                .identify()
+                .with_context(|| format!("failed to identify multimeter {label:?}"))?;
+            handler
+                .configure(multimeter)
+                .with_context(|| format!("failed to configure multimeter {label:?}"))?;
+            meters.push((multimeter, handler));
+        }
+        Ok(Self { meters })
+    }
+
+    /// Reads every multimeter immediately before and after `shot`.
+    pub(crate) fn read_around_shot(
+        &mut self,
+        shot: impl FnOnce() -> Result<()>,
+    ) -> Result<Vec<MultimeterReading>> {
+        let before = self.read_all()?;
+        shot()?;
+        let after = self.read_all()?;
+        let readings = self.readings(before.into_iter().map(Some), after.into_iter().map(Some));
+        report(&readings);
+        Ok(readings)
+    }
+
+    /// Reads every multimeter once for a shot that was triggered by hand
+    /// before the command started, so there is no `before` reading.
+    pub(crate) fn read_after_shot(&mut self) -> Result<Vec<MultimeterReading>> {
+        let after = self.read_all()?;
+        let readings = self.readings(
+            std::iter::repeat_n(None, after.len()),
+            after.into_iter().map(Some),
+        );
+        report(&readings);
+        Ok(readings)
+    }
+
+    fn read_all(&mut self) -> Result<Vec<f64>> {
+        self.meters
+            .iter_mut()
+            .map(|(multimeter, handler)| {
+                handler
+                    .read()
+                    .with_context(|| format!("failed to read multimeter {:?}", multimeter.label))
+            })
+            .collect()
+    }
+
+    fn readings(
+        &self,
+        before: impl Iterator<Item = Option<f64>>,
+        after: impl Iterator<Item = Option<f64>>,
+    ) -> Vec<MultimeterReading> {
+        self.meters
+            .iter()
+            .zip(before.zip(after))
+            .map(|((multimeter, _), (before, after))| MultimeterReading {
+                label: multimeter.label.clone(),
+                model: multimeter.model.clone(),
+                function: multimeter.function,
+                unit: reading_unit(multimeter.function),
+                before,
+                after,
+            })
+            .collect()
+    }
+}
+
+fn report(readings: &[MultimeterReading]) {
+    for reading in readings {
+        let values = [("before", reading.before), ("after", reading.after)]
+            .into_iter()
+            .filter_map(|(when, value)| {
+                value.map(|value| format!("{when} {value:.6} {}", reading.unit))
+            })
+            .collect::<Vec<_>>();
+        ui::success(format!("{}: {}", reading.label, values.join(", ")));
+    }
+}
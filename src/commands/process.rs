@@ -2,6 +2,7 @@ use crate::commands::analyze::run_analyze_locked;
 use crate::commands::fetch::{
     begin_fetch_after_preflight_locked, preflight_fetch_locked, run_fetch_after_preflight_locked,
 };
+use crate::commands::multimeter::Multimeters;
 use crate::communications::oscilloscope::OscilloscopeHandler;
 use crate::config::{Config, ValidationTarget};
 use anyhow::{Context, Result};
@@ -20,8 +21,16 @@ pub fn process(cfg: &Config) -> Result<()> {
     crate::config::validate_for_target(cfg, ValidationTarget::Process)?;
     preflight_fetch_locked(cfg)?;
     validate_scope_connection(cfg)?;
+    let mut multimeters = Multimeters::connect(cfg)?;
     begin_fetch_after_preflight_locked(cfg)?;
-    let data = run_fetch_after_preflight_locked(cfg)?;
+    let readings = match multimeters.read_after_shot() {
+        Ok(readings) => readings,
+        Err(error) => {
+            crate::commands::run_dir::write_run_state(cfg, "failed", "process", Some(&error))?;
+            return Err(error);
+        }
+    };
+    let data = run_fetch_after_preflight_locked(cfg, &readings)?;
     run_analyze_locked(cfg, &data)?;
 
     Ok(())
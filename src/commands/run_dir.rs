@@ -346,6 +346,35 @@ struct AnalysisAttempt {
     error_summary: Option<String>,
 }
 
+/// Summary of a run directory's `run.toml`, used to resume interrupted work.
+#[cfg(feature = "hw-core")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RunProgress {
+    pub status: String,
+    pub started_at: String,
+    pub acquired: bool,
+}
+
+#[cfg(feature = "hw-core")]
+pub(crate) fn read_run_progress(run_dir: &Path) -> Result<Option<RunProgress>> {
+    let manifest = crate::config::ArtifactPaths::new(run_dir).run_manifest();
+    let contents = match fs::read_to_string(&manifest) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(error)
+                .with_context(|| format!("failed to read run manifest: {}", manifest.display()));
+        }
+    };
+    let state = toml::from_str::<RunState>(&contents)
+        .with_context(|| format!("failed to parse run manifest: {}", manifest.display()))?;
+    Ok(Some(RunProgress {
+        acquired: state.acquired_at.is_some(),
+        status: state.status,
+        started_at: state.started_at,
+    }))
+}
+
 pub fn write_run_state(
     cfg: &Config,
     status: &str,
@@ -554,7 +583,7 @@ fn read_published_analysis(cfg: &Config) -> Result<Option<(Option<u64>, Option<S
     Ok(Some((generation, through)))
 }
 
-fn write_atomic_file(path: &Path, contents: &[u8]) -> Result<()> {
+pub(crate) fn write_atomic_file(path: &Path, contents: &[u8]) -> Result<()> {
     let mut name = path.file_name().unwrap_or_default().to_os_string();
     name.push(format!(".{}.tmp", std::process::id()));
     let temporary = path.with_file_name(name);
@@ -725,6 +754,10 @@ pub(crate) fn prepare_analysis_staging(cfg: &Config, stage: AnalysisStage) -> Re
             &resolver.lockin_xy_npy(channel),
             &staging.lockin_xy_npy(channel),
         )?;
+        copy_optional_file(
+            &cfg.paths().lockin_noise_csv(channel),
+            &staging.lockin_noise_csv(channel),
+        )?;
         if stage == AnalysisStage::Kerr {
             copy_required_file(
                 &resolver.lockin_rotated_csv(channel),
@@ -735,6 +768,14 @@ pub(crate) fn prepare_analysis_staging(cfg: &Config, stage: AnalysisStage) -> Re
                 &resolver.lockin_rotated_npy(channel),
                 &staging.lockin_rotated_npy(channel),
             )?;
+            copy_optional_file(
+                &cfg.paths().phase_drift_csv(channel),
+                &staging.phase_drift_csv(channel),
+            )?;
+            copy_optional_file(
+                &cfg.paths().lockin_rotated_noise_csv(channel),
+                &staging.lockin_rotated_noise_csv(channel),
+            )?;
         }
     }
 
@@ -1088,6 +1129,25 @@ mod tests {
         fs::remove_dir_all(directory).unwrap();
     }
 
+    #[cfg(feature = "hw-core")]
+    #[test]
+    fn run_progress_reports_status_and_acquisition() {
+        let directory = temporary_directory();
+        let mut cfg = crate::test_support::test_config(vec![1], vec![2]);
+        cfg.set_artifact_root(directory.clone());
+
+        assert_eq!(read_run_progress(&directory).unwrap(), None);
+        write_run_state(&cfg, "acquiring", "fetch", None).unwrap();
+        let progress = read_run_progress(&directory).unwrap().unwrap();
+        assert_eq!(progress.status, "acquiring");
+        assert!(!progress.acquired);
+        write_run_state(&cfg, "acquired", "fetch", None).unwrap();
+        let progress = read_run_progress(&directory).unwrap().unwrap();
+        assert_eq!(progress.status, "acquired");
+        assert!(progress.acquired);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
     #[test]
     fn analysis_prepare_does_not_fabricate_acquisition_config_snapshots() {
         let directory = temporary_directory();
@@ -1404,6 +1464,7 @@ sha256 = "2f0cde9b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088fa52e"
         cfg.pulse.bg_window_after = window_bg;
         cfg.plot.enabled = false;
         cfg.instruments = Some(crate::config::Instruments {
+            multimeters: Vec::new(),
             function_generator: None,
             oscilloscope: crate::config::Oscilloscope {
                 connection: crate::config::Connection::Tcpip {
@@ -1411,6 +1472,9 @@ sha256 = "2f0cde9b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088fa52e"
                     port: 80,
                 },
                 model: "dummy".to_string(),
+                trigger_timeout_s: pmoke_config_core::DEFAULT_TRIGGER_TIMEOUT_S,
+                trigger_poll_interval_s: pmoke_config_core::DEFAULT_TRIGGER_POLL_INTERVAL_S,
+                setup: None,
             },
         });
         write_analysis_config_snapshots(&cfg).unwrap();
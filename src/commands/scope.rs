@@ -0,0 +1,243 @@
+use crate::cli::ScopeCommand;
+use crate::communications::oscilloscope::OscilloscopeHandler;
+use crate::config::{Config, OscilloscopeSetup};
+use crate::ui;
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+/// Read-back values within this fraction of the configured value match; the
+/// scope rounds scales and offsets to its own steps and echoes few digits.
+const SETUP_RELATIVE_TOLERANCE: f64 = 1.0e-3;
+/// Floor for settings configured as zero, such as offsets and trigger levels,
+/// which read back as formatting residue like `-1.2E-12`.
+const SETUP_ABSOLUTE_TOLERANCE: f64 = 1.0e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SetupDifference {
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+pub fn run(cfg: &Config, command: &ScopeCommand) -> Result<()> {
+    let setup = configured_setup(cfg)?;
+    let mut handler = OscilloscopeHandler::initialize(cfg)
+        .context("failed to initialize oscilloscope handler")?;
+    if matches!(command, ScopeCommand::Apply) {
+        handler
+            .apply_setup(setup)
+            .context("failed to apply oscilloscope setup")?;
+        ui::success("oscilloscope setup applied");
+    }
+    let actual = handler
+        .query_setup(&setup_channels(setup))
+        .context("failed to read back oscilloscope setup")?;
+    let differences = setup_differences(setup, &actual);
+    if differences.is_empty() {
+        ui::success("oscilloscope matches scope.setup");
+        return Ok(());
+    }
+    ui::section("Oscilloscope Setup Differences");
+    println!(
+        "{}",
+        ui::table(
+            &["Setting", "Config", "Oscilloscope"],
+            differences
+                .iter()
+                .map(|difference| {
+                    vec![
+                        difference.field.clone(),
+                        difference.expected.clone(),
+                        difference.actual.clone(),
+                    ]
+                })
+                .collect(),
+        )
+    );
+    bail!(
+        "{} oscilloscope setting(s) differ from scope.setup",
+        differences.len()
+    )
+}
+
+fn configured_setup(cfg: &Config) -> Result<&OscilloscopeSetup> {
+    cfg.instruments
+        .as_ref()
+        .and_then(|instruments| instruments.oscilloscope.setup.as_ref())
+        .context("scope.setup is not configured; add a [scope.setup] table to the config")
+}
+
+pub(crate) fn setup_channels(setup: &OscilloscopeSetup) -> Vec<u8> {
+    setup
+        .channels
+        .iter()
+        .map(|channel| channel.channel)
+        .collect()
+}
+
+/// Lists every setting pinned in `expected` whose read-back value differs.
+pub(crate) fn setup_differences(
+    expected: &OscilloscopeSetup,
+    actual: &OscilloscopeSetup,
+) -> Vec<SetupDifference> {
+    let expected = serde_json::to_value(expected).expect("setup serializes to JSON");
+    let actual = serde_json::to_value(actual).expect("setup serializes to JSON");
+    let mut differences = Vec::new();
+    compare_settings("", &expected, &actual, &["channels"], &mut differences);
+    let no_channels = Vec::new();
+    let actual_channels = actual["channels"].as_array().unwrap_or(&no_channels);
+    for channel in expected["channels"].as_array().unwrap_or(&no_channels) {
+        let number = &channel["channel"];
+        let read_back = actual_channels
+            .iter()
+            .find(|candidate| candidate["channel"] == *number)
+            .unwrap_or(&Value::Null);
+        compare_settings(
+            &format!("channels.{number}."),
+            channel,
+            read_back,
+            &["channel"],
+            &mut differences,
+        );
+    }
+    differences
+}
+
+pub(crate) fn compare_settings(
+    prefix: &str,
+    expected: &Value,
+    actual: &Value,
+    skip: &[&str],
+    differences: &mut Vec<SetupDifference>,
+) {
+    let Some(fields) = expected.as_object() else {
+        return;
+    };
+    for (name, value) in fields {
+        if skip.contains(&name.as_str()) {
+            continue;
+        }
+        let read_back = &actual[name.as_str()];
+        if !setting_matches(value, read_back) {
+            differences.push(SetupDifference {
+                field: format!("{prefix}{name}"),
+                expected: display_setting(value),
+                actual: display_setting(read_back),
+            });
+        }
+    }
+}
+
+fn setting_matches(expected: &Value, actual: &Value) -> bool {
+    match (expected.as_f64(), actual.as_f64()) {
+        (Some(expected), Some(actual)) => {
+            (expected - actual).abs()
+                <= SETUP_ABSOLUTE_TOLERANCE
+                    .max(SETUP_RELATIVE_TOLERANCE * expected.abs().max(actual.abs()))
+        }
+        _ => expected == actual,
+    }
+}
+
+fn display_setting(value: &Value) -> String {
+    match value {
+        Value::Null => "(not reported)".to_string(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        ScopeBandwidthLimit, ScopeChannelSetup, ScopeCoupling, ScopeTriggerSource,
+    };
+
+    fn configured() -> OscilloscopeSetup {
+        OscilloscopeSetup {
+            timebase_scale: Some(2.0e-3),
+            trigger_source: Some(ScopeTriggerSource::Ext),
+            channels: vec![ScopeChannelSetup {
+                channel: 2,
+                scale: Some(0.5),
+                offset: Some(0.0),
+                coupling: Some(ScopeCoupling::Dc),
+                ..ScopeChannelSetup::default()
+            }],
+            ..OscilloscopeSetup::default()
+        }
+    }
+
+    fn read_back() -> OscilloscopeSetup {
+        OscilloscopeSetup {
+            timebase_scale: Some(2.0e-3 * (1.0 + 1.0e-5)),
+            timebase_offset: Some(1.0e-3),
+            memory_depth: Some(10_000_000),
+            trigger_source: Some(ScopeTriggerSource::Ext),
+            trigger_level: Some(1.5),
+            trigger_slope: None,
+            channels: vec![ScopeChannelSetup {
+                channel: 2,
+                scale: Some(0.5),
+                offset: Some(0.0),
+                coupling: Some(ScopeCoupling::Dc),
+                bandwidth_limit: Some(ScopeBandwidthLimit::Off),
+            }],
+        }
+    }
+
+    #[test]
+    fn only_configured_settings_are_compared() {
+        assert!(setup_differences(&configured(), &read_back()).is_empty());
+        assert_eq!(setup_channels(&configured()), [2]);
+    }
+
+    #[test]
+    fn zero_settings_tolerate_formatting_residue() {
+        let mut actual = read_back();
+        actual.channels[0].offset = Some(-1.2e-12);
+        assert!(setup_differences(&configured(), &actual).is_empty());
+
+        actual.channels[0].offset = Some(0.01);
+        assert_eq!(setup_differences(&configured(), &actual).len(), 1);
+    }
+
+    #[test]
+    fn differences_name_the_setting_and_both_values() {
+        let mut actual = read_back();
+        actual.trigger_source = Some(ScopeTriggerSource::Ch1);
+        actual.channels[0].scale = Some(1.0);
+        actual.channels[0].coupling = Some(ScopeCoupling::Ac);
+
+        let differences = setup_differences(&configured(), &actual);
+        assert_eq!(differences.len(), 3);
+        for expected in [
+            SetupDifference {
+                field: "trigger_source".to_string(),
+                expected: "ext".to_string(),
+                actual: "ch1".to_string(),
+            },
+            SetupDifference {
+                field: "channels.2.scale".to_string(),
+                expected: "0.5".to_string(),
+                actual: "1.0".to_string(),
+            },
+            SetupDifference {
+                field: "channels.2.coupling".to_string(),
+                expected: "dc".to_string(),
+                actual: "ac".to_string(),
+            },
+        ] {
+            assert!(differences.contains(&expected), "{differences:?}");
+        }
+
+        actual.channels.clear();
+        let missing = setup_differences(&configured(), &actual);
+        assert!(
+            missing
+                .iter()
+                .any(|difference| difference.actual == "(not reported)")
+        );
+    }
+}
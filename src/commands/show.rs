@@ -113,13 +113,14 @@ fn channel_row(config: &Config, channel: &Channel) -> (String, String) {
     } else {
         roles.join(", ")
     };
-    let scale = match (channel.factor, channel.scale_to_abs_max) {
-        (Some(factor), None) => format!("factor={factor}"),
-        (None, Some(target)) => format!(
+    let scale = match (channel.factor, channel.scale_to_abs_max, channel.coil) {
+        (Some(factor), None, None) => format!("factor={factor}"),
+        (None, Some(target), None) => format!(
             "max_abs={}, polarity={}",
             target.abs(),
             if target.is_sign_negative() { -1 } else { 1 }
         ),
+        (None, None, Some(coil)) => crate::lockin::sensor::coil_summary(&coil),
         _ => "scale=-".to_string(),
     };
 
@@ -0,0 +1,655 @@
+use crate::cli::{StackAlign, StackStage};
+use crate::config::ArtifactPaths;
+use crate::constants::KERR_ERR_HEADER;
+use crate::ui;
+use crate::utils::csv::{read_csv_with_headers, write_csv};
+use crate::utils::time_axis::same_time_axis;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STACK_MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct StackManifest {
+    schema_version: u32,
+    pmoke_version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_commit: Option<&'static str>,
+    created_at: String,
+    stage: &'static str,
+    align: &'static str,
+    config_sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config_resolved: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config_resolved_sha256: Option<String>,
+    shot_count: usize,
+    rows: usize,
+    sources: Vec<StackSource>,
+    outputs: Vec<StackFile>,
+}
+
+#[derive(Serialize)]
+struct StackSource {
+    run_dir: String,
+    analysis_manifest_sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation: Option<u64>,
+    offset_rows: i64,
+    files: Vec<StackFile>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+struct StackFile {
+    file: String,
+    sha256: String,
+}
+
+/// One analyzed run whose published results for the stacked stage have been
+/// checked against its analysis manifest.
+struct SourceRun {
+    run_dir: PathBuf,
+    paths: ArtifactPaths,
+    manifest_sha256: String,
+    generation: Option<u64>,
+    config_sha256: String,
+    files: Vec<StackFile>,
+}
+
+struct ResultTable {
+    headers: Vec<String>,
+    columns: Vec<Vec<f64>>,
+}
+
+pub fn run(
+    runs: &[PathBuf],
+    stage: StackStage,
+    align: StackAlign,
+    output: &Path,
+    force: bool,
+) -> Result<()> {
+    if runs.len() < 2 {
+        bail!("pmoke stack needs at least two run directories");
+    }
+    if output.exists() && !force {
+        bail!("output directory already exists: {}", output.display());
+    }
+    let t0 = std::time::Instant::now();
+    let pb = ui::spinner(format!("verifying {} analyzed runs", runs.len()));
+    let sources = runs
+        .iter()
+        .map(|run| SourceRun::load(run, stage))
+        .collect::<Result<Vec<_>>>()?;
+    reject_output_inside_sources(output, &sources)?;
+    check_sources_match(&sources, stage)?;
+
+    let files = sources[0]
+        .files
+        .iter()
+        .map(|file| file.file.clone())
+        .collect::<Vec<_>>();
+    let tables = files
+        .iter()
+        .map(|file| {
+            sources
+                .iter()
+                .map(|source| read_table(&source.paths.analysis_dir().join(file)))
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ui::finish_read(
+        pb,
+        format!(
+            "{} results from {} runs ({})",
+            stage_name(stage),
+            sources.len(),
+            ui::fmt_duration(t0.elapsed())
+        ),
+    );
+
+    for (file, file_tables) in files.iter().zip(&tables) {
+        check_tables_match(file, &sources, file_tables)?;
+    }
+    let offsets = match align {
+        StackAlign::Trigger => vec![0; sources.len()],
+        StackAlign::Pulse => pulse_offsets(&tables[0])?,
+    };
+    let stacked = tables
+        .iter()
+        .map(|file_tables| stack_tables(file_tables, &offsets))
+        .collect::<Result<Vec<_>>>()?;
+
+    let staging = staging_path(output);
+    if staging.exists() {
+        fs::remove_dir_all(&staging).with_context(|| {
+            format!(
+                "failed to remove incomplete stack directory: {}",
+                staging.display()
+            )
+        })?;
+    }
+    let staging_paths = ArtifactPaths::new(&staging);
+    let mut outputs = Vec::with_capacity(files.len());
+    for (file, table) in files.iter().zip(&stacked) {
+        let path = staging_paths.stack_dir().join(file);
+        let headers = table.headers.iter().map(String::as_str).collect::<Vec<_>>();
+        write_csv(&path, &headers, &table.columns)?;
+        outputs.push(StackFile {
+            file: file.clone(),
+            sha256: crate::utils::checksum::file_sha256(&path)?,
+        });
+    }
+    let source_config = sources[0].paths.analysis_resolved_config();
+    let config_resolved_sha256 = if source_config.is_file() {
+        fs::copy(&source_config, staging_paths.stack_resolved_config()).with_context(|| {
+            format!(
+                "failed to copy resolved config snapshot: {}",
+                source_config.display()
+            )
+        })?;
+        Some(crate::utils::checksum::file_sha256(
+            &staging_paths.stack_resolved_config(),
+        )?)
+    } else {
+        None
+    };
+
+    let rows = stacked.first().map_or(0, |table| table.columns[0].len());
+    let manifest = StackManifest {
+        schema_version: STACK_MANIFEST_SCHEMA_VERSION,
+        pmoke_version: env!("CARGO_PKG_VERSION"),
+        git_commit: option_env!("PMOKE_GIT_COMMIT"),
+        created_at: jiff::Timestamp::now().to_string(),
+        stage: stage_name(stage),
+        align: match align {
+            StackAlign::Trigger => "trigger",
+            StackAlign::Pulse => "pulse",
+        },
+        config_sha256: sources[0].config_sha256.clone(),
+        config_resolved: config_resolved_sha256
+            .is_some()
+            .then_some("config.resolved.toml"),
+        config_resolved_sha256,
+        shot_count: sources.len(),
+        rows,
+        sources: sources
+            .iter()
+            .zip(&offsets)
+            .map(|(source, &offset)| StackSource {
+                run_dir: source.run_dir.display().to_string(),
+                analysis_manifest_sha256: source.manifest_sha256.clone(),
+                generation: source.generation,
+                offset_rows: offset,
+                files: source.files.clone(),
+            })
+            .collect(),
+        outputs,
+    };
+    let encoded = toml::to_string_pretty(&manifest).context("failed to encode stack manifest")?;
+    fs::write(staging_paths.stack_manifest(), encoded).context("failed to write stack manifest")?;
+    crate::commands::run_dir::publish_staged_directory(&staging, output, force)?;
+
+    ui::saved(format!(
+        "{} shots stacked into {} ({} rows)",
+        sources.len(),
+        ArtifactPaths::new(output).stack_dir().display(),
+        rows
+    ));
+    Ok(())
+}
+
+impl SourceRun {
+    fn load(run_dir: &Path, stage: StackStage) -> Result<Self> {
+        let published = crate::lockin::provenance::load_published_stage_outputs(
+            run_dir,
+            stage_name(stage),
+            |file| stage_output_matches(stage, file),
+        )?;
+        Ok(Self {
+            run_dir: published.run_dir,
+            paths: published.paths,
+            manifest_sha256: published.manifest_sha256,
+            generation: published.generation,
+            config_sha256: published.config_sha256,
+            files: published
+                .files
+                .into_iter()
+                .map(|(file, sha256)| StackFile { file, sha256 })
+                .collect(),
+        })
+    }
+}
+
+fn stage_name(stage: StackStage) -> &'static str {
+    match stage {
+        StackStage::Li => "li",
+        StackStage::Phase => "phase",
+        StackStage::Kerr => "kerr",
+    }
+}
+
+fn stage_output_matches(stage: StackStage, file: &str) -> bool {
+    match stage {
+        StackStage::Li => file.starts_with("lockin/") && file.ends_with("_xy.csv"),
+        StackStage::Phase => file.starts_with("lockin/") && file.ends_with("_rotated.csv"),
+        StackStage::Kerr => file == "kerr/kerr.csv",
+    }
+}
+
+fn reject_output_inside_sources(output: &Path, sources: &[SourceRun]) -> Result<()> {
+    let Ok(output) = fs::canonicalize(output) else {
+        return Ok(());
+    };
+    if let Some(source) = sources
+        .iter()
+        .find(|source| output.starts_with(&source.run_dir) || source.run_dir.starts_with(&output))
+    {
+        bail!(
+            "stack output {} overlaps source run {}",
+            output.display(),
+            source.run_dir.display()
+        );
+    }
+    Ok(())
+}
+
+fn check_sources_match(sources: &[SourceRun], stage: StackStage) -> Result<()> {
+    let first = &sources[0];
+    for source in &sources[1..] {
+        if source.run_dir == first.run_dir {
+            bail!("run {} is listed more than once", source.run_dir.display());
+        }
+        if source.config_sha256 != first.config_sha256 {
+            bail!(
+                "{} and {} were analyzed with different {} configs; stacking needs matching stage_config_fingerprint values",
+                first.run_dir.display(),
+                source.run_dir.display(),
+                stage_name(stage)
+            );
+        }
+        let names = |run: &SourceRun| {
+            run.files
+                .iter()
+                .map(|file| file.file.clone())
+                .collect::<Vec<_>>()
+        };
+        if names(source) != names(first) {
+            bail!(
+                "{} and {} publish different result tables: {:?} vs {:?}",
+                first.run_dir.display(),
+                source.run_dir.display(),
+                names(first),
+                names(source)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn read_table(path: &Path) -> Result<ResultTable> {
+    let (headers, columns) = read_csv_with_headers(path)?;
+    if columns.len() != headers.len() || columns.first().is_none_or(|time| time.len() < 2) {
+        bail!(
+            "analysis table needs a header and at least two rows: {}",
+            path.display()
+        );
+    }
+    Ok(ResultTable { headers, columns })
+}
+
+fn check_tables_match(file: &str, sources: &[SourceRun], tables: &[ResultTable]) -> Result<()> {
+    let first = &tables[0];
+    let time = &first.columns[0];
+    let dt = time[1] - time[0];
+    if !dt.is_finite() || dt <= 0.0 {
+        bail!(
+            "{file} in {} has a non-increasing time axis",
+            sources[0].run_dir.display()
+        );
+    }
+    for (source, table) in sources.iter().zip(tables).skip(1) {
+        if table.headers != first.headers {
+            bail!(
+                "{file} columns in {} differ from {}",
+                source.run_dir.display(),
+                sources[0].run_dir.display()
+            );
+        }
+        if !same_time_axis(time, &table.columns[0], dt) {
+            bail!(
+                "{file} time axis in {} does not match {}; stacked shots need the same oscilloscope timebase",
+                source.run_dir.display(),
+                sources[0].run_dir.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Number of sensor rate columns; each is followed later by its integral.
+fn sensor_column_count(headers: &[String]) -> usize {
+    headers
+        .iter()
+        .skip(1)
+        .take_while(|header| header.contains(" rate ("))
+        .count()
+}
+
+/// Row offset of each shot relative to the first, taken from the peak of the
+/// first sensor integral.
+fn pulse_offsets(tables: &[ResultTable]) -> Result<Vec<i64>> {
+    let sensors = sensor_column_count(&tables[0].headers);
+    if sensors == 0 {
+        bail!("--align pulse needs sensor columns in the stacked tables");
+    }
+    let peaks = tables
+        .iter()
+        .map(|table| {
+            table.columns[1 + sensors]
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| value.is_finite())
+                .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+                .map(|(index, _)| index as i64)
+                .ok_or_else(|| anyhow::anyhow!("sensor integral has no finite samples"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(peaks.iter().map(|peak| peak - peaks[0]).collect())
+}
+
+/// Averages shots row by row after shifting each by its offset. Result
+/// columns gain a standard-error column each; propagated `kerr_err` columns
+/// are combined as `sqrt(Σ err²) / N` instead. The time axis is the first
+/// shot's.
+fn stack_tables(tables: &[ResultTable], offsets: &[i64]) -> Result<ResultTable> {
+    let first = &tables[0];
+    let len = first.columns[0].len() as i64;
+    let start = offsets
+        .iter()
+        .map(|offset| -offset)
+        .max()
+        .unwrap_or(0)
+        .max(0);
+    let end = offsets
+        .iter()
+        .map(|offset| len - offset)
+        .min()
+        .unwrap_or(len)
+        .min(len);
+    if end - start < 1 {
+        bail!("aligned shots do not overlap; check the sensor pulses or use --align trigger");
+    }
+    let rows = start as usize..end as usize;
+    let context_columns = 1 + 2 * sensor_column_count(&first.headers);
+    let shots = tables.len() as f64;
+
+    let mut headers = first.headers.clone();
+    let mut columns = vec![first.columns[0][rows.clone()].to_vec()];
+    let mut errors = Vec::new();
+    for column in 1..first.columns.len() {
+        if first.headers[column].ends_with(KERR_ERR_HEADER) {
+            columns.push(
+                rows.clone()
+                    .map(|row| {
+                        tables
+                            .iter()
+                            .zip(offsets)
+                            .map(|(table, &offset)| {
+                                table.columns[column][(row as i64 + offset) as usize].powi(2)
+                            })
+                            .sum::<f64>()
+                            .sqrt()
+                            / shots
+                    })
+                    .collect(),
+            );
+            continue;
+        }
+        let mut mean = Vec::with_capacity(rows.len());
+        let mut stderr = Vec::with_capacity(rows.len());
+        for row in rows.clone() {
+            let samples = tables
+                .iter()
+                .zip(offsets)
+                .map(|(table, &offset)| table.columns[column][(row as i64 + offset) as usize]);
+            let average = samples.clone().sum::<f64>() / shots;
+            let variance =
+                samples.map(|value| (value - average).powi(2)).sum::<f64>() / (shots - 1.0);
+            mean.push(average);
+            stderr.push((variance / shots).sqrt());
+        }
+        columns.push(mean);
+        if column >= context_columns {
+            headers.push(stderr_header(&first.headers[column]));
+            errors.push(stderr);
+        }
+    }
+    columns.extend(errors);
+    Ok(ResultTable { headers, columns })
+}
+
+/// `Ch3 Kerr angle (rad)` becomes `Ch3 Kerr angle stderr (rad)`.
+fn stderr_header(header: &str) -> String {
+    match header.rfind(" (") {
+        Some(split) if header.ends_with(')') => {
+            format!("{} stderr{}", &header[..split], &header[split..])
+        }
+        _ => format!("{header} stderr"),
+    }
+}
+
+fn staging_path(output: &Path) -> PathBuf {
+    let mut name = output.file_name().unwrap_or_default().to_os_string();
+    name.push(".incomplete");
+    output.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const HEADERS: [&str; 4] = [
+        "time (s)",
+        "ch1 rate (T/s)",
+        "ch1 integral (T)",
+        "Ch3 Kerr angle (rad)",
+    ];
+
+    #[test]
+    fn stacks_kerr_results_with_standard_error_and_source_checksums() {
+        let root = unique_test_dir("stack_trigger");
+        let first = write_run(
+            &root.join("a"),
+            "fingerprint",
+            &[0.0, 1.0, 0.0],
+            &[1.0, 2.0, 3.0],
+        );
+        let second = write_run(
+            &root.join("b"),
+            "fingerprint",
+            &[0.0, 1.0, 0.0],
+            &[3.0, 4.0, 7.0],
+        );
+        let output = root.join("stacked");
+
+        run(
+            &[first.clone(), second],
+            StackStage::Kerr,
+            StackAlign::Trigger,
+            &output,
+            false,
+        )
+        .unwrap();
+
+        let paths = ArtifactPaths::new(&output);
+        let (headers, columns) =
+            read_csv_with_headers(paths.stack_dir().join("kerr/kerr.csv")).unwrap();
+        assert_eq!(headers[..4], HEADERS.map(str::to_string));
+        assert_eq!(headers[4], "Ch3 Kerr angle stderr (rad)");
+        assert_eq!(columns[3], vec![2.0, 3.0, 5.0]);
+        assert_eq!(columns[4], vec![1.0, 1.0, 2.0]);
+
+        let manifest: toml::Value =
+            toml::from_str(&fs::read_to_string(paths.stack_manifest()).unwrap()).unwrap();
+        assert_eq!(manifest["shot_count"].as_integer(), Some(2));
+        assert_eq!(manifest["config_sha256"].as_str(), Some("fingerprint"));
+        let source = &manifest["sources"][0];
+        assert_eq!(
+            source["run_dir"].as_str(),
+            Some(fs::canonicalize(&first).unwrap().to_str().unwrap())
+        );
+        assert_eq!(
+            source["files"][0]["sha256"].as_str().unwrap(),
+            crate::utils::checksum::file_sha256(&first.join("analysis/kerr/kerr.csv")).unwrap()
+        );
+        assert!(!staging_path(&output).exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn pulse_alignment_shifts_shots_onto_the_first_sensor_peak() {
+        let root = unique_test_dir("stack_pulse");
+        let first = write_run(
+            &root.join("a"),
+            "fingerprint",
+            &[0.0, 1.0, 0.5, 0.0],
+            &[10.0, 20.0, 30.0, 40.0],
+        );
+        let second = write_run(
+            &root.join("b"),
+            "fingerprint",
+            &[0.0, 0.0, 1.0, 0.5],
+            &[0.0, 12.0, 22.0, 32.0],
+        );
+        let output = root.join("stacked");
+
+        run(
+            &[first, second],
+            StackStage::Kerr,
+            StackAlign::Pulse,
+            &output,
+            false,
+        )
+        .unwrap();
+
+        let paths = ArtifactPaths::new(&output);
+        let (_, columns) = read_csv_with_headers(paths.stack_dir().join("kerr/kerr.csv")).unwrap();
+        assert_eq!(columns[0], vec![0.0, 1.0, 2.0]);
+        assert_eq!(columns[2], vec![0.0, 1.0, 0.5]);
+        assert_eq!(columns[3], vec![11.0, 21.0, 31.0]);
+        let manifest: toml::Value =
+            toml::from_str(&fs::read_to_string(paths.stack_manifest()).unwrap()).unwrap();
+        assert_eq!(manifest["sources"][1]["offset_rows"].as_integer(), Some(1));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rejects_mismatched_fingerprints_checksums_and_time_axes() {
+        let root = unique_test_dir("stack_reject");
+        let first = write_run(&root.join("a"), "one", &[0.0, 1.0], &[1.0, 2.0]);
+        let other = write_run(&root.join("b"), "two", &[0.0, 1.0], &[1.0, 2.0]);
+        let output = root.join("stacked");
+        let stack = |runs: &[PathBuf]| {
+            run(runs, StackStage::Kerr, StackAlign::Trigger, &output, false)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(stack(&[first.clone(), other]).contains("different kerr configs"));
+
+        let tampered = write_run(&root.join("c"), "one", &[0.0, 1.0], &[1.0, 2.0]);
+        fs::write(
+            tampered.join("analysis/kerr/kerr.csv"),
+            format!("{}\n0,0,0,9\n1,1,1,9\n", HEADERS.join(",")),
+        )
+        .unwrap();
+        assert!(stack(&[first.clone(), tampered]).contains("checksum mismatch"));
+
+        let shifted = root.join("d");
+        write_table(&shifted, "one", &[0.5, 1.5], &[0.0, 1.0], &[1.0, 2.0]);
+        assert!(stack(&[first.clone(), shifted]).contains("time axis"));
+
+        assert!(stack(&[first.clone(), first]).contains("listed more than once"));
+        assert!(!output.exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn propagated_errors_combine_in_quadrature_without_stderr() {
+        let table = |kerr: [f64; 2], err: [f64; 2]| ResultTable {
+            headers: vec![
+                "time (s)".to_string(),
+                "Ch3 Kerr angle (rad)".to_string(),
+                "Ch3 kerr_err (rad)".to_string(),
+            ],
+            columns: vec![vec![0.0, 1.0], kerr.to_vec(), err.to_vec()],
+        };
+        let stacked = stack_tables(
+            &[
+                table([1.0, 2.0], [3.0, 0.1]),
+                table([3.0, 2.0], [4.0, f64::NAN]),
+            ],
+            &[0, 0],
+        )
+        .unwrap();
+        assert_eq!(
+            stacked.headers,
+            [
+                "time (s)",
+                "Ch3 Kerr angle (rad)",
+                "Ch3 kerr_err (rad)",
+                "Ch3 Kerr angle stderr (rad)",
+            ]
+        );
+        assert_eq!(stacked.columns[2][0], 2.5);
+        assert!(stacked.columns[2][1].is_nan());
+        assert_eq!(stacked.columns[3], vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn stderr_header_keeps_the_unit_last() {
+        assert_eq!(
+            stderr_header("Ch3 Kerr angle (rad)"),
+            "Ch3 Kerr angle stderr (rad)"
+        );
+        assert_eq!(stderr_header("H1 X"), "H1 X stderr");
+    }
+
+    fn write_run(run_dir: &Path, fingerprint: &str, integral: &[f64], kerr: &[f64]) -> PathBuf {
+        let time = (0..kerr.len()).map(|row| row as f64).collect::<Vec<_>>();
+        write_table(run_dir, fingerprint, &time, integral, kerr);
+        run_dir.to_path_buf()
+    }
+
+    fn write_table(
+        run_dir: &Path,
+        fingerprint: &str,
+        time: &[f64],
+        integral: &[f64],
+        kerr: &[f64],
+    ) {
+        let paths = ArtifactPaths::new(run_dir);
+        let csv = paths.kerr_csv();
+        let rate = vec![0.0; time.len()];
+        write_csv(&csv, &HEADERS, &[time, &rate, integral, kerr]).unwrap();
+        let sha256 = crate::utils::checksum::file_sha256(&csv).unwrap();
+        fs::write(
+            paths.analysis_manifest(),
+            format!(
+                "generation = 1\n\n[stages.kerr]\nconfig_sha256 = \"{fingerprint}\"\n\n[[outputs]]\nfile = \"kerr/kerr.csv\"\nsha256 = \"{sha256}\"\n"
+            ),
+        )
+        .unwrap();
+    }
+
+    fn unique_test_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("pmoke_{name}_{}_{nanos}", std::process::id()))
+    }
+}
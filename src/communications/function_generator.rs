@@ -1,8 +1,14 @@
 use crate::communications::validator::validate_fg;
-use crate::config::{Config, Connection};
+use crate::config::{
+    Config, Connection, FunctionGeneratorSetup, GeneratorTriggerSource, GeneratorWaveform,
+};
 use anyhow::{Context, Result, anyhow};
-use instruments::nf::WF1946B;
-use instruments::transport::{ScpiConnection, open_scpi_transport};
+use instruments::nf::{WF1946B, WfOutputSetup, WfTriggerSource, WfWaveform};
+use instruments::sim::SimulatedWf1946b;
+use instruments::transport::{BoxedScpiTransport, ScpiConnection, open_scpi_transport};
+
+/// I/O timeout for LAN SCPI links, matching the 10 s used for GPIB.
+const SCPI_LAN_TIMEOUT_MS: u64 = 10_000;
 
 pub enum FG {
     WF1946B(WF1946B),
@@ -35,16 +41,38 @@ impl FGHandler {
 
 fn open_function_generator(model: &str, connection: &Connection) -> Result<FG> {
     match model {
-        "WF1946B" => {
-            let transport = open_scpi_transport(&scpi_connection(connection)?)?;
-            Ok(FG::WF1946B(WF1946B::new(transport)))
-        }
+        "WF1946B" => Ok(FG::WF1946B(WF1946B::new(open_generator_transport(
+            connection,
+        )?))),
         other => Err(anyhow!("unknown function generator model: {other}")),
     }
 }
 
+/// Opens the SCPI transport of a generator, including `sim://` benches.
+pub(crate) fn open_generator_transport(connection: &Connection) -> Result<BoxedScpiTransport> {
+    match connection {
+        Connection::Sim { bench } => Ok(Box::new(SimulatedWf1946b::new(instruments::sim::bench(
+            bench,
+        )))),
+        connection => Ok(open_scpi_transport(&scpi_connection(connection)?)?),
+    }
+}
+
 pub(crate) fn scpi_connection(connection: &Connection) -> Result<ScpiConnection> {
     match connection {
+        Connection::Tcpip { ip, port } => Ok(ScpiConnection::Tcp {
+            host: ip.clone(),
+            port: *port,
+            timeout_ms: SCPI_LAN_TIMEOUT_MS,
+        }),
+        Connection::Vxi11 { host, device } => Ok(ScpiConnection::Vxi11 {
+            host: host.clone(),
+            device: device.clone(),
+            timeout_ms: SCPI_LAN_TIMEOUT_MS,
+        }),
+        Connection::UsbtmcDevice { path } => {
+            Ok(ScpiConnection::UsbtmcDevice { path: path.clone() })
+        }
         Connection::Gpib { board, address } => Ok(ScpiConnection::Gpib {
             board: *board as i32,
             address: *address as i32,
@@ -73,8 +101,8 @@ pub(crate) fn scpi_connection(connection: &Connection) -> Result<ScpiConnection>
             baud_rate: *baud_rate,
             read_timeout_ms: *read_timeout_ms,
         }),
-        Connection::Tcpip { .. } | Connection::Usbtmc { .. } => Err(anyhow!(
-            "function generator requires a SCPI GPIB or Prologix connection"
+        Connection::Usbtmc { .. } | Connection::Sim { .. } => Err(anyhow!(
+            "SCPI instrument requires a TCP/IP, VXI-11, USBTMC, GPIB, or Prologix connection"
         )),
     }
 }
@@ -97,4 +125,62 @@ impl FGHandler {
             )),
         }
     }
+
+    pub fn apply_setup(&mut self, setup: &FunctionGeneratorSetup) -> Result<()> {
+        match &mut self.inner {
+            FG::WF1946B(dev) => Ok(dev.apply_output_setup(&wf_setup(setup))?),
+        }
+    }
+
+    pub fn query_setup(&mut self) -> Result<FunctionGeneratorSetup> {
+        match &mut self.inner {
+            FG::WF1946B(dev) => Ok(setup_from_wf(dev.query_output_setup()?)),
+        }
+    }
+}
+
+fn wf_setup(setup: &FunctionGeneratorSetup) -> WfOutputSetup {
+    WfOutputSetup {
+        waveform: setup.waveform.map(|waveform| match waveform {
+            GeneratorWaveform::Sine => WfWaveform::Sine,
+            GeneratorWaveform::Square => WfWaveform::Square,
+            GeneratorWaveform::Pulse => WfWaveform::Pulse,
+            GeneratorWaveform::Ramp => WfWaveform::Ramp,
+            GeneratorWaveform::Noise => WfWaveform::Noise,
+            GeneratorWaveform::Dc => WfWaveform::Dc,
+        }),
+        frequency_hz: setup.frequency_hz,
+        amplitude_vpp: setup.amplitude_vpp,
+        offset_v: setup.offset_v,
+        burst_cycles: setup.burst_cycles,
+        trigger_source: setup.trigger_source.map(|source| match source {
+            GeneratorTriggerSource::Bus => WfTriggerSource::Bus,
+            GeneratorTriggerSource::External => WfTriggerSource::External,
+            GeneratorTriggerSource::Internal => WfTriggerSource::Internal,
+        }),
+        output: setup.output,
+    }
+}
+
+fn setup_from_wf(setup: WfOutputSetup) -> FunctionGeneratorSetup {
+    FunctionGeneratorSetup {
+        waveform: setup.waveform.map(|waveform| match waveform {
+            WfWaveform::Sine => GeneratorWaveform::Sine,
+            WfWaveform::Square => GeneratorWaveform::Square,
+            WfWaveform::Pulse => GeneratorWaveform::Pulse,
+            WfWaveform::Ramp => GeneratorWaveform::Ramp,
+            WfWaveform::Noise => GeneratorWaveform::Noise,
+            WfWaveform::Dc => GeneratorWaveform::Dc,
+        }),
+        frequency_hz: setup.frequency_hz,
+        amplitude_vpp: setup.amplitude_vpp,
+        offset_v: setup.offset_v,
+        burst_cycles: setup.burst_cycles,
+        trigger_source: setup.trigger_source.map(|source| match source {
+            WfTriggerSource::Bus => GeneratorTriggerSource::Bus,
+            WfTriggerSource::External => GeneratorTriggerSource::External,
+            WfTriggerSource::Internal => GeneratorTriggerSource::Internal,
+        }),
+        output: setup.output,
+    }
 }
@@ -1,3 +1,5 @@
 pub mod function_generator;
+pub mod multimeter;
 pub mod oscilloscope;
+pub mod sim;
 pub mod validator;
@@ -0,0 +1,89 @@
+use crate::communications::function_generator::scpi_connection;
+use crate::communications::validator::validate_connection;
+use crate::config::{Multimeter, MultimeterFunction, Thermocouple};
+use anyhow::{Result, anyhow};
+use instruments::keithley::{
+    Keithley2000, Keithley2010, KeithleyFunction, KeithleyMeasureSetup, KeithleyThermocouple,
+};
+use instruments::transport::open_scpi_transport;
+
+pub enum Dmm {
+    Keithley2000(Keithley2000),
+    Keithley2010(Keithley2010),
+}
+
+pub struct DmmHandler {
+    inner: Dmm,
+}
+
+impl DmmHandler {
+    pub fn initialize(multimeter: &Multimeter) -> Result<Self> {
+        let connection = validate_connection(&multimeter.connection)?;
+        let transport = open_scpi_transport(&scpi_connection(&connection)?)?;
+        let inner = match multimeter.model.as_str() {
+            "Keithley2000" => Dmm::Keithley2000(Keithley2000::new(transport)),
+            "Keithley2010" => Dmm::Keithley2010(Keithley2010::new(transport)),
+            other => return Err(anyhow!("unknown multimeter model: {other}")),
+        };
+        Ok(Self { inner })
+    }
+
+    pub fn identify(&mut self) -> Result<String> {
+        match &mut self.inner {
+            Dmm::Keithley2000(dev) => Ok(dev.identify()?),
+            Dmm::Keithley2010(dev) => Ok(dev.identify()?),
+        }
+    }
+
+    pub fn configure(&mut self, multimeter: &Multimeter) -> Result<()> {
+        let setup = measure_setup(multimeter);
+        match &mut self.inner {
+            Dmm::Keithley2000(dev) => Ok(dev.configure_measurement(&setup)?),
+            Dmm::Keithley2010(dev) => Ok(dev.configure_measurement(&setup)?),
+        }
+    }
+
+    pub fn read(&mut self) -> Result<f64> {
+        match &mut self.inner {
+            Dmm::Keithley2000(dev) => Ok(dev.read()?),
+            Dmm::Keithley2010(dev) => Ok(dev.read()?),
+        }
+    }
+}
+
+/// Unit of the readings returned for `function`.
+pub fn reading_unit(function: MultimeterFunction) -> &'static str {
+    keithley_function(function).unit()
+}
+
+fn measure_setup(multimeter: &Multimeter) -> KeithleyMeasureSetup {
+    KeithleyMeasureSetup {
+        function: keithley_function(multimeter.function),
+        range: multimeter.range,
+        thermocouple: multimeter
+            .thermocouple
+            .map(|thermocouple| match thermocouple {
+                Thermocouple::J => KeithleyThermocouple::J,
+                Thermocouple::K => KeithleyThermocouple::K,
+                Thermocouple::N => KeithleyThermocouple::N,
+                Thermocouple::T => KeithleyThermocouple::T,
+                Thermocouple::E => KeithleyThermocouple::E,
+                Thermocouple::R => KeithleyThermocouple::R,
+                Thermocouple::S => KeithleyThermocouple::S,
+                Thermocouple::B => KeithleyThermocouple::B,
+            }),
+    }
+}
+
+fn keithley_function(function: MultimeterFunction) -> KeithleyFunction {
+    match function {
+        MultimeterFunction::DcVolts => KeithleyFunction::DcVolts,
+        MultimeterFunction::AcVolts => KeithleyFunction::AcVolts,
+        MultimeterFunction::DcCurrent => KeithleyFunction::DcCurrent,
+        MultimeterFunction::AcCurrent => KeithleyFunction::AcCurrent,
+        MultimeterFunction::Resistance => KeithleyFunction::Resistance,
+        MultimeterFunction::FourWireResistance => KeithleyFunction::FourWireResistance,
+        MultimeterFunction::Temperature => KeithleyFunction::Temperature,
+        MultimeterFunction::Frequency => KeithleyFunction::Frequency,
+    }
+}
@@ -1,21 +1,24 @@
+use crate::communications::sim;
 use crate::communications::validator::validate_oscilloscope;
-use crate::config::{Config, Connection};
+use crate::config::{
+    Config, Connection, OscilloscopeSetup, ScopeBandwidthLimit, ScopeChannelSetup, ScopeCoupling,
+    ScopeTriggerSlope, ScopeTriggerSource,
+};
 use anyhow::{Context, Result, anyhow};
-use instruments::rigol::{
-    DHO5108, DhoHorizontalSettings, DhoRawWaveform, DhoRawWaveformWritten, DhoTriggerStatus,
+use instruments::keysight::InfiniiVision;
+use instruments::oscilloscope::{
+    AcquisitionSetup, BandwidthLimit, ChannelSetup, Coupling, HorizontalSettings, Oscilloscope,
+    RawWaveform, RawWaveformWritten, TriggerSlope, TriggerSource, TriggerStatus,
 };
+use instruments::rigol::DHO5108;
 use std::io::Write;
 use std::time::Duration;
 
 const SCOPE_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 const SCOPE_IO_TIMEOUT: Duration = Duration::from_secs(30);
 
-pub enum Oscilloscope {
-    DHO5108(DHO5108),
-}
-
 pub struct OscilloscopeHandler {
-    inner: Oscilloscope,
+    inner: Box<dyn Oscilloscope>,
 }
 
 impl OscilloscopeHandler {
@@ -31,24 +34,45 @@ impl OscilloscopeHandler {
         let model = osc_cfg.model.as_str();
         let connection = &osc_cfg.connection;
 
-        let osc = match (model, connection) {
-            ("DHO5108", Connection::Tcpip { ip, port }) => {
-                let dho = DHO5108::open_with_timeouts(
+        let osc: Box<dyn Oscilloscope> = match (model, connection) {
+            ("DHO5108", Connection::Tcpip { ip, port }) => Box::new(DHO5108::open_with_timeouts(
+                ip,
+                *port,
+                Some(SCOPE_CONNECT_TIMEOUT),
+                Some(SCOPE_IO_TIMEOUT),
+            )?),
+            ("DHO5108", Connection::Usbtmc { resource }) => {
+                Box::new(DHO5108::open_usbtmc(resource, Some(SCOPE_IO_TIMEOUT))?)
+            }
+            ("DHO5108", Connection::UsbtmcDevice { path }) => {
+                Box::new(DHO5108::open_usbtmc_device(path)?)
+            }
+            ("DHO5108", Connection::Sim { bench }) => {
+                Box::new(sim::open_scope(bench, cfg, SCOPE_IO_TIMEOUT)?)
+            }
+            ("InfiniiVision", Connection::Tcpip { ip, port }) => {
+                Box::new(InfiniiVision::open_with_timeouts(
                     ip,
                     *port,
                     Some(SCOPE_CONNECT_TIMEOUT),
                     Some(SCOPE_IO_TIMEOUT),
-                )?;
-                Oscilloscope::DHO5108(dho)
+                )?)
             }
-            ("DHO5108", Connection::Usbtmc { resource }) => {
-                let dho = DHO5108::open_usbtmc(resource, Some(SCOPE_IO_TIMEOUT))?;
-                Oscilloscope::DHO5108(dho)
+            ("InfiniiVision", Connection::Usbtmc { resource }) => Box::new(
+                InfiniiVision::open_usbtmc(resource, Some(SCOPE_IO_TIMEOUT))?,
+            ),
+            ("InfiniiVision", Connection::UsbtmcDevice { path }) => {
+                Box::new(InfiniiVision::open_usbtmc_device(path)?)
             }
-            ("DHO5108", Connection::Gpib { .. })
-            | ("DHO5108", Connection::PrologixTcp { .. })
-            | ("DHO5108", Connection::PrologixSerial { .. }) => {
-                return Err(anyhow!("DHO5108 requires a TCP/IP or USB-TMC connection"));
+            (
+                model @ ("DHO5108" | "InfiniiVision"),
+                Connection::Gpib { .. }
+                | Connection::Vxi11 { .. }
+                | Connection::PrologixTcp { .. }
+                | Connection::PrologixSerial { .. }
+                | Connection::Sim { .. },
+            ) => {
+                return Err(anyhow!("{model} requires a TCP/IP or USB-TMC connection"));
             }
             (other, _) => return Err(anyhow!("Unknown oscilloscope model: {other}")),
         };
@@ -60,38 +84,22 @@ impl OscilloscopeHandler {
 impl OscilloscopeHandler {
     #[allow(dead_code)]
     pub fn identify(&mut self) -> Result<String> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.identify()?),
-        }
+        Ok(self.inner.identify()?)
     }
 
     #[allow(dead_code)]
     pub fn set_single(&mut self) -> Result<()> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.set_single()?),
-            #[allow(unreachable_patterns)]
-            _ => Err(anyhow!("set_single is not supported on this oscilloscope")),
-        }
+        Ok(self.inner.set_single()?)
     }
 
     #[allow(dead_code)]
     pub fn fetch(&mut self, ch: u8, depth: usize) -> Result<Vec<f64>> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.fetch(ch, depth)?),
-            #[allow(unreachable_patterns)]
-            _ => Err(anyhow!("fetch is not supported on this oscilloscope")),
-        }
+        Ok(self.inner.fetch(ch, depth)?)
     }
 
     #[allow(dead_code)]
-    pub fn fetch_raw_word(&mut self, ch: u8, depth: usize) -> Result<DhoRawWaveform> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.fetch_raw_word(ch, depth)?),
-            #[allow(unreachable_patterns)]
-            _ => Err(anyhow!(
-                "raw WORD fetch is not supported on this oscilloscope"
-            )),
-        }
+    pub fn fetch_raw_word(&mut self, ch: u8, depth: usize) -> Result<RawWaveform> {
+        Ok(self.inner.fetch_raw_word(ch, depth)?)
     }
 
     #[allow(dead_code)]
@@ -100,43 +108,125 @@ impl OscilloscopeHandler {
         ch: u8,
         depth: usize,
         writer: &mut W,
-    ) -> Result<DhoRawWaveformWritten> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.fetch_raw_word_into(ch, depth, writer)?),
-            #[allow(unreachable_patterns)]
-            _ => Err(anyhow!(
-                "raw WORD fetch is not supported on this oscilloscope"
-            )),
-        }
+    ) -> Result<RawWaveformWritten> {
+        Ok(self.inner.fetch_raw_word_into(ch, depth, writer)?)
     }
 
-    pub fn query_horizontal_settings(&mut self) -> Result<DhoHorizontalSettings> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.query_horizontal_settings()?),
-        }
+    pub fn query_horizontal_settings(&mut self) -> Result<HorizontalSettings> {
+        Ok(self.inner.query_horizontal_settings()?)
     }
 
     pub fn query_memory_depth(&mut self) -> Result<usize> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.query_memory_depth()?),
-        }
+        Ok(self.inner.query_memory_depth()?)
     }
 
     pub fn stop(&mut self) -> Result<()> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.stop()?),
-        }
+        Ok(self.inner.stop()?)
     }
 
-    pub fn query_trigger_status(&mut self) -> Result<DhoTriggerStatus> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.query_trigger_status()?),
-        }
+    pub fn query_trigger_status(&mut self) -> Result<TriggerStatus> {
+        Ok(self.inner.query_trigger_status()?)
     }
 
     pub fn capture_display_png(&mut self) -> Result<Vec<u8>> {
-        match &mut self.inner {
-            Oscilloscope::DHO5108(dev) => Ok(dev.capture_display_png()?),
-        }
+        Ok(self.inner.capture_display_png()?)
     }
+
+    pub fn apply_setup(&mut self, setup: &OscilloscopeSetup) -> Result<()> {
+        Ok(self.inner.apply_acquisition_setup(&scope_setup(setup))?)
+    }
+
+    /// Reads back the full acquisition setup, including every listed channel.
+    pub fn query_setup(&mut self, channels: &[u8]) -> Result<OscilloscopeSetup> {
+        setup_from_scope(self.inner.query_acquisition_setup(channels)?)
+    }
+}
+
+fn scope_setup(setup: &OscilloscopeSetup) -> AcquisitionSetup {
+    AcquisitionSetup {
+        timebase_scale: setup.timebase_scale,
+        timebase_offset: setup.timebase_offset,
+        memory_depth: setup.memory_depth,
+        trigger_source: setup.trigger_source.map(|source| match source {
+            ScopeTriggerSource::Ext => TriggerSource::External,
+            ScopeTriggerSource::AcLine => TriggerSource::AcLine,
+            channel => TriggerSource::Channel(
+                channel
+                    .channel()
+                    .expect("non-channel trigger sources are matched above"),
+            ),
+        }),
+        trigger_level: setup.trigger_level,
+        trigger_slope: setup.trigger_slope.map(|slope| match slope {
+            ScopeTriggerSlope::Rising => TriggerSlope::Positive,
+            ScopeTriggerSlope::Falling => TriggerSlope::Negative,
+            ScopeTriggerSlope::Either => TriggerSlope::Either,
+        }),
+        channels: setup
+            .channels
+            .iter()
+            .map(|channel| ChannelSetup {
+                channel: channel.channel,
+                scale: channel.scale,
+                offset: channel.offset,
+                coupling: channel.coupling.map(|coupling| match coupling {
+                    ScopeCoupling::Dc => Coupling::Dc,
+                    ScopeCoupling::Ac => Coupling::Ac,
+                    ScopeCoupling::Gnd => Coupling::Gnd,
+                }),
+                bandwidth_limit: channel.bandwidth_limit.map(|limit| match limit {
+                    ScopeBandwidthLimit::Off => BandwidthLimit::Off,
+                    ScopeBandwidthLimit::Mhz20 => BandwidthLimit::Mhz20,
+                    ScopeBandwidthLimit::Mhz25 => BandwidthLimit::Mhz25,
+                    ScopeBandwidthLimit::Mhz100 => BandwidthLimit::Mhz100,
+                    ScopeBandwidthLimit::Mhz250 => BandwidthLimit::Mhz250,
+                }),
+            })
+            .collect(),
+    }
+}
+
+fn setup_from_scope(setup: AcquisitionSetup) -> Result<OscilloscopeSetup> {
+    let trigger_source = setup
+        .trigger_source
+        .map(|source| match source {
+            TriggerSource::External => Ok(ScopeTriggerSource::Ext),
+            TriggerSource::AcLine => Ok(ScopeTriggerSource::AcLine),
+            TriggerSource::Channel(ch) => ScopeTriggerSource::from_channel(ch)
+                .ok_or_else(|| anyhow!("oscilloscope reported trigger source channel {ch}")),
+        })
+        .transpose()?;
+    Ok(OscilloscopeSetup {
+        timebase_scale: setup.timebase_scale,
+        timebase_offset: setup.timebase_offset,
+        memory_depth: setup.memory_depth,
+        trigger_source,
+        trigger_level: setup.trigger_level,
+        trigger_slope: setup.trigger_slope.map(|slope| match slope {
+            TriggerSlope::Positive => ScopeTriggerSlope::Rising,
+            TriggerSlope::Negative => ScopeTriggerSlope::Falling,
+            TriggerSlope::Either => ScopeTriggerSlope::Either,
+        }),
+        channels: setup
+            .channels
+            .into_iter()
+            .map(|channel| ScopeChannelSetup {
+                channel: channel.channel,
+                scale: channel.scale,
+                offset: channel.offset,
+                coupling: channel.coupling.map(|coupling| match coupling {
+                    Coupling::Dc => ScopeCoupling::Dc,
+                    Coupling::Ac => ScopeCoupling::Ac,
+                    Coupling::Gnd => ScopeCoupling::Gnd,
+                }),
+                bandwidth_limit: channel.bandwidth_limit.map(|limit| match limit {
+                    BandwidthLimit::Off => ScopeBandwidthLimit::Off,
+                    BandwidthLimit::Mhz20 => ScopeBandwidthLimit::Mhz20,
+                    BandwidthLimit::Mhz25 => ScopeBandwidthLimit::Mhz25,
+                    BandwidthLimit::Mhz100 => ScopeBandwidthLimit::Mhz100,
+                    BandwidthLimit::Mhz250 => ScopeBandwidthLimit::Mhz250,
+                }),
+            })
+            .collect(),
+    })
 }
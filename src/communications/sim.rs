@@ -0,0 +1,213 @@
+//! Synthetic shots for `sim://` benches.
+//!
+//! The simulated DHO5108 records a magnetic field pulse starting at the
+//! trigger: sensor channels carry the pickup-coil voltage `dB/dt`, the
+//! reference channel the modulator reference, and signal channels the
+//! detector output of `generate_synthetic_signal` with a Kerr angle that
+//! follows the field. The detector harmonics are phased like a real
+//! modulator's, so the default `[phase] offsets` recover the injected angle.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use instruments::rigol::DHO5108;
+use instruments::sim::{WaveformSource, open_dho5108};
+use pmoke_analysis_core::{SyntheticSignalSettings, generate_synthetic_signal};
+use std::f64::consts::{PI, TAU};
+use std::time::Duration;
+
+const REFERENCE_FREQUENCY_HZ: f64 = 50_000.0;
+const PULSE_WIDTH_S: f64 = 10.0e-3;
+const SENSOR_PEAK_V: f64 = 1.0;
+const PEAK_KERR_ANGLE_RAD: f64 = 1.0e-3;
+const SIGNAL_AMPLITUDE_V: f64 = 1.0;
+const SIGNAL_NOISE_RMS_V: f64 = 1.0e-3;
+/// Samples per `generate_synthetic_signal` call; the Kerr angle is held
+/// constant within each chunk.
+const CHUNK_SAMPLES: usize = 256;
+const MIN_CHUNK_SAMPLES: usize = 64;
+
+/// Opens the DHO5108 emulation of `bench` with waveforms for the roles in `cfg`.
+pub(crate) fn open_scope(bench: &str, cfg: &Config, timeout: Duration) -> Result<DHO5108> {
+    let bench = instruments::sim::bench(bench);
+    bench.set_waveform_source(Box::new(SyntheticShot::from_config(cfg)));
+    open_dho5108(bench, Some(timeout)).context("failed to start the simulated DHO5108")
+}
+
+struct SyntheticShot {
+    reference_ch: u8,
+    signal_ch: Vec<u8>,
+    sensor_ch: Vec<u8>,
+}
+
+impl SyntheticShot {
+    fn from_config(cfg: &Config) -> Self {
+        Self {
+            reference_ch: cfg.roles.reference_ch,
+            signal_ch: cfg.roles.signal_ch.clone(),
+            sensor_ch: cfg.roles.sensor_ch.clone(),
+        }
+    }
+}
+
+impl WaveformSource for SyntheticShot {
+    fn channel(&mut self, shot: u64, channel: u8, time: &[f64]) -> Vec<f64> {
+        let (Some(&start), Some(&next)) = (time.first(), time.get(1)) else {
+            return vec![0.0; time.len()];
+        };
+        let carrier = Carrier::new(next - start);
+        if channel == self.reference_ch {
+            time.iter()
+                .map(|&t| (TAU * carrier.frequency_hz * (t - start)).sin())
+                .collect()
+        } else if self.sensor_ch.contains(&channel) {
+            time.iter()
+                .map(|&t| SENSOR_PEAK_V * field_rate(t))
+                .collect()
+        } else if self.signal_ch.contains(&channel) {
+            detector_signal(shot, channel, time, carrier)
+        } else {
+            vec![0.0; time.len()]
+        }
+    }
+}
+
+/// Normalized field `B(t)`: a half-sine pulse starting at the trigger.
+fn field(t: f64) -> f64 {
+    if (0.0..=PULSE_WIDTH_S).contains(&t) {
+        (PI * t / PULSE_WIDTH_S).sin()
+    } else {
+        0.0
+    }
+}
+
+/// `dB/dt` of [`field`] scaled to a unit peak, as seen by a pickup coil.
+fn field_rate(t: f64) -> f64 {
+    if (0.0..=PULSE_WIDTH_S).contains(&t) {
+        (PI * t / PULSE_WIDTH_S).cos()
+    } else {
+        0.0
+    }
+}
+
+/// Modulator reference, phased to the first sample of the record.
+#[derive(Debug, Clone, Copy)]
+struct Carrier {
+    frequency_hz: f64,
+    sample_rate_hz: f64,
+    samples_per_period: usize,
+}
+
+impl Carrier {
+    /// Rounds the frequency to a whole, even number of samples per period,
+    /// and at least 16 so the sixth harmonic stays below Nyquist.
+    fn new(dt: f64) -> Self {
+        let sample_rate_hz = 1.0 / dt;
+        let samples_per_period = ((sample_rate_hz / REFERENCE_FREQUENCY_HZ).round() as usize)
+            .max(16)
+            .next_multiple_of(2);
+        Self {
+            frequency_hz: sample_rate_hz / samples_per_period as f64,
+            sample_rate_hz,
+            samples_per_period,
+        }
+    }
+}
+
+/// Chunks span whole reference periods, so every chunk starts at the same
+/// carrier phase and the harmonics stay continuous across chunk borders.
+fn detector_signal(shot: u64, channel: u8, time: &[f64], carrier: Carrier) -> Vec<f64> {
+    if time.len() < MIN_CHUNK_SAMPLES {
+        return vec![0.0; time.len()];
+    }
+    let chunk_samples =
+        CHUNK_SAMPLES.div_ceil(carrier.samples_per_period) * carrier.samples_per_period;
+    let mut signal = Vec::with_capacity(time.len());
+    let mut begin = 0;
+    while begin < time.len() {
+        let mut end = (begin + chunk_samples).min(time.len());
+        if time.len() - end < MIN_CHUNK_SAMPLES {
+            end = time.len();
+        }
+        let settings = SyntheticSignalSettings {
+            samples: end - begin,
+            sample_rate_hz: carrier.sample_rate_hz,
+            reference_frequency_hz: carrier.frequency_hz,
+            amplitude: SIGNAL_AMPLITUDE_V,
+            phase_rad: 0.0,
+            noise_rms: SIGNAL_NOISE_RMS_V,
+            kerr_angle_rad: PEAK_KERR_ANGLE_RAD * field(time[(begin + end) / 2]),
+            seed: (shot << 32) ^ (u64::from(channel) << 24) ^ begin as u64,
+        };
+        match modulator_chunk(settings, carrier.samples_per_period) {
+            Ok(chunk) => signal.extend(chunk),
+            Err(_) => signal.resize(end, 0.0),
+        }
+        begin = end;
+    }
+    signal
+}
+
+/// `generate_synthetic_signal` puts every harmonic at `phase_rad`, whereas a
+/// modulator puts the even harmonics at π/2 and the odd ones at π, which is
+/// what the phase stage rotates onto the in-phase axis. Half a reference
+/// period later the odd harmonics have flipped sign and the even ones have
+/// not, which splits a noise-free signal into its even and odd parts; the
+/// noise is what the seeded generator adds on top of the noise-free signal.
+fn modulator_chunk(
+    settings: SyntheticSignalSettings,
+    samples_per_period: usize,
+) -> pmoke_analysis_core::Result<Vec<f64>> {
+    let half_period = samples_per_period / 2;
+    let noise_free = |phase_rad, samples| {
+        generate_synthetic_signal(SyntheticSignalSettings {
+            samples,
+            phase_rad,
+            noise_rms: 0.0,
+            ..settings
+        })
+    };
+    let even = noise_free(PI / 2.0, settings.samples + half_period)?;
+    let odd = noise_free(PI, settings.samples + half_period)?;
+    let clean = noise_free(settings.phase_rad, settings.samples)?;
+    let noisy = generate_synthetic_signal(settings)?;
+    Ok((0..settings.samples)
+        .map(|index| {
+            let shifted = index + half_period;
+            0.5 * (even[index] + even[shifted])
+                + 0.5 * (odd[index] - odd[shifted])
+                + (noisy[index] - clean[index])
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synthetic_shot_assigns_waveforms_by_channel_role() {
+        let mut shot = SyntheticShot {
+            reference_ch: 2,
+            signal_ch: vec![3],
+            sensor_ch: vec![1],
+        };
+        let dt = 5.0e-7;
+        let time = (0..60_000)
+            .map(|index| -5.0e-3 + index as f64 * dt)
+            .collect::<Vec<_>>();
+
+        let sensor = shot.channel(1, 1, &time);
+        let reference = shot.channel(1, 2, &time);
+        let signal = shot.channel(1, 3, &time);
+
+        let integral = sensor.iter().sum::<f64>() * dt;
+        assert!(integral.abs() < 1e-4, "{integral}");
+        let peak = sensor.iter().copied().fold(0.0, f64::max);
+        assert!((peak - SENSOR_PEAK_V).abs() < 1e-6);
+        assert_eq!(reference.len(), time.len());
+        assert_eq!(signal.len(), time.len());
+        assert!(signal.iter().all(|value| value.is_finite()));
+        assert_ne!(signal, shot.channel(2, 3, &time));
+        assert!(shot.channel(1, 4, &time).iter().all(|value| *value == 0.0));
+    }
+}
@@ -16,6 +16,15 @@ pub fn validate_connection(conn: &Connection) -> anyhow::Result<Connection> {
             ip: ip.clone(),
             port: *port,
         }),
+        Connection::Vxi11 { host, device } => {
+            if host.trim().is_empty() || device.trim().is_empty() {
+                bail!("VXI-11 host and device must not be empty.");
+            }
+            Ok(Connection::Vxi11 {
+                host: host.clone(),
+                device: device.clone(),
+            })
+        }
         Connection::Usbtmc { resource } => {
             if resource.trim().is_empty() {
                 bail!("USB-TMC VISA resource must not be empty.");
@@ -24,6 +33,12 @@ pub fn validate_connection(conn: &Connection) -> anyhow::Result<Connection> {
                 resource: resource.clone(),
             })
         }
+        Connection::UsbtmcDevice { path } => {
+            if !path.starts_with('/') {
+                bail!("USBTMC device path must be absolute: {path}");
+            }
+            Ok(Connection::UsbtmcDevice { path: path.clone() })
+        }
         Connection::PrologixTcp {
             host,
             port,
@@ -66,6 +81,14 @@ pub fn validate_connection(conn: &Connection) -> anyhow::Result<Connection> {
                 read_timeout_ms: *read_timeout_ms,
             })
         }
+        Connection::Sim { bench } => {
+            if bench.trim().is_empty() {
+                bail!("Simulated bench name must not be empty.");
+            }
+            Ok(Connection::Sim {
+                bench: bench.clone(),
+            })
+        }
     }
 }
 
@@ -80,10 +103,11 @@ pub fn validate_oscilloscope(cfg: &Config) -> Result<()> {
     let endpoint = validate_connection(&osc_cfg.connection)?;
 
     match osc_cfg.model.as_str() {
-        "DHO5108" => match endpoint {
+        "DHO5108" if matches!(endpoint, Connection::Sim { .. }) => {}
+        model @ ("DHO5108" | "InfiniiVision") => match endpoint {
             Connection::Tcpip { .. } | Connection::Usbtmc { .. } => {}
             _ => {
-                bail!("DHO5108 must be connected over TCP/IP or USB-TMC.");
+                bail!("{model} must be connected over TCP/IP or USB-TMC.");
             }
         },
         other => {
@@ -110,9 +134,10 @@ pub fn validate_fg(cfg: &Config) -> Result<()> {
         "WF1946B" => match endpoint {
             Connection::Gpib { .. }
             | Connection::PrologixTcp { .. }
-            | Connection::PrologixSerial { .. } => {}
+            | Connection::PrologixSerial { .. }
+            | Connection::Sim { .. } => {}
             _ => {
-                bail!("WF1946B must be connected over GPIB or Prologix.");
+                bail!("WF1946B must be connected over GPIB, Prologix, or sim://.");
             }
         },
         other => {
@@ -51,6 +51,21 @@ fn usbtmc_unsupported_diagnostic(path: impl Into<String>) -> ConfigDiagnostic {
     )
 }
 
+fn usbtmc_device_supported() -> bool {
+    cfg!(all(target_os = "linux", feature = "hw-core"))
+}
+
+fn usbtmc_device_unsupported_diagnostic(path: impl Into<String>) -> ConfigDiagnostic {
+    ConfigDiagnostic::new(
+        DiagnosticKind::Validation,
+        Some(path.into()),
+        "usbtmc:// connections require the Linux usbtmc driver and the hw-core feature",
+        Some(
+            "use tcp://host:port, or run pmoke on Linux built with --features hw-core".to_string(),
+        ),
+    )
+}
+
 fn eval_f64_expr(s: &str) -> Result<f64> {
     if contains_print_call(s) {
         bail!("invalid expression '{s}': print() is not allowed in config values");
@@ -184,6 +199,37 @@ impl Config {
         &self.roles.signal_ch
     }
 
+    /// Pre-pulse window for lock-in noise estimates and S/N diagnostics.
+    pub fn lockin_noise_window(&self) -> Window {
+        self.lockin
+            .snr_background_window
+            .unwrap_or(self.pulse.bg_window_before)
+    }
+
+    /// Pickup-coil calibration configured for a sensor channel, if any.
+    pub fn sensor_coil(&self, sensor_ch: u8) -> Option<CoilCalibration> {
+        self.channels
+            .iter()
+            .find(|channel| channel.index == sensor_ch)
+            .and_then(|channel| channel.coil)
+    }
+
+    /// Compensation coil configured for a sensor channel, if any.
+    pub fn sensor_compensation(&self, sensor_ch: u8) -> Option<CoilCompensation> {
+        self.sensor_coil(sensor_ch)
+            .and_then(|coil| coil.compensation)
+    }
+
+    /// Compensation coil channels, in sensor channel order.
+    pub fn sensor_compensation_ch(&self) -> Vec<u8> {
+        self.roles
+            .sensor_ch
+            .iter()
+            .filter_map(|&ch| self.sensor_compensation(ch))
+            .map(|compensation| compensation.channel)
+            .collect()
+    }
+
     pub fn resolver(&self) -> ArtifactResolver {
         ArtifactResolver::new(self.paths().run_dir)
     }
@@ -223,6 +269,20 @@ impl Config {
             .join(path)
     }
 
+    /// Resolves a path written in the config against the config file's
+    /// directory, independent of `--run-dir`.
+    pub fn config_relative_path(&self, path: impl AsRef<Path>) -> PathBuf {
+        let path = path.as_ref();
+        if path.is_absolute() {
+            return path.to_path_buf();
+        }
+        self.source_path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."))
+            .join(path)
+    }
+
     pub fn set_artifact_root(&mut self, root: PathBuf) {
         if self.version < 4 {
             let plot_path = PathBuf::from(&self.plot.output_dir);
@@ -318,18 +378,246 @@ impl PlotDecimation {
 pub struct Instruments {
     pub function_generator: Option<FunctionGenerator>,
     pub oscilloscope: Oscilloscope,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub multimeters: Vec<Multimeter>,
+}
+
+/// Multimeter read immediately before and after each shot; the readings
+/// are stored in the acquisition and analysis manifests.
+#[derive(Debug, Clone, Serialize)]
+pub struct Multimeter {
+    pub label: String,
+    pub connection: Connection,
+    pub model: String,
+    pub function: MultimeterFunction,
+    /// Fixed measurement range in the function's unit; `None` autoranges.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thermocouple: Option<Thermocouple>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MultimeterFunction {
+    DcVolts,
+    AcVolts,
+    DcCurrent,
+    AcCurrent,
+    Resistance,
+    FourWireResistance,
+    Temperature,
+    Frequency,
+}
+
+impl MultimeterFunction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DcVolts => "dc_volts",
+            Self::AcVolts => "ac_volts",
+            Self::DcCurrent => "dc_current",
+            Self::AcCurrent => "ac_current",
+            Self::Resistance => "resistance",
+            Self::FourWireResistance => "four_wire_resistance",
+            Self::Temperature => "temperature",
+            Self::Frequency => "frequency",
+        }
+    }
+
+    pub fn has_range(self) -> bool {
+        !matches!(self, Self::Temperature | Self::Frequency)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Thermocouple {
+    J,
+    K,
+    N,
+    T,
+    E,
+    R,
+    S,
+    B,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct FunctionGenerator {
     pub connection: Connection,
     pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup: Option<FunctionGeneratorSetup>,
+}
+
+/// Output settings applied before each shot by `autoshot`, `auto` and
+/// `automeasure`. Unset fields are left as they are on the instrument.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FunctionGeneratorSetup {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waveform: Option<GeneratorWaveform>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_hz: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amplitude_vpp: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_v: Option<f64>,
+    /// Periods per trigger; setting it switches the output to triggered burst.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst_cycles: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_source: Option<GeneratorTriggerSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratorWaveform {
+    Sine,
+    Square,
+    Pulse,
+    Ramp,
+    Noise,
+    Dc,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratorTriggerSource {
+    Bus,
+    External,
+    Internal,
+}
+
+impl GeneratorTriggerSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Bus => "bus",
+            Self::External => "external",
+            Self::Internal => "internal",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Oscilloscope {
     pub connection: Connection,
     pub model: String,
+    /// Longest wait for the scope to arm or to stop after a generator trigger.
+    #[serde(skip_serializing_if = "is_default_trigger_timeout")]
+    pub trigger_timeout_s: f64,
+    #[serde(skip_serializing_if = "is_default_trigger_poll_interval")]
+    pub trigger_poll_interval_s: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup: Option<OscilloscopeSetup>,
+}
+
+/// Acquisition settings pushed by `scope apply` and checked by `scope verify`.
+/// Unset fields are left as they are on the instrument.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OscilloscopeSetup {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timebase_scale: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timebase_offset: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_depth: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_source: Option<ScopeTriggerSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_level: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_slope: Option<ScopeTriggerSlope>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<ScopeChannelSetup>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScopeChannelSetup {
+    pub channel: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coupling: Option<ScopeCoupling>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bandwidth_limit: Option<ScopeBandwidthLimit>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScopeTriggerSource {
+    Ch1,
+    Ch2,
+    Ch3,
+    Ch4,
+    Ch5,
+    Ch6,
+    Ch7,
+    Ch8,
+    Ext,
+    AcLine,
+}
+
+impl ScopeTriggerSource {
+    const CHANNELS: [Self; 8] = [
+        Self::Ch1,
+        Self::Ch2,
+        Self::Ch3,
+        Self::Ch4,
+        Self::Ch5,
+        Self::Ch6,
+        Self::Ch7,
+        Self::Ch8,
+    ];
+
+    pub fn channel(self) -> Option<u8> {
+        Self::CHANNELS
+            .iter()
+            .position(|source| *source == self)
+            .map(|index| index as u8 + 1)
+    }
+
+    pub fn from_channel(channel: u8) -> Option<Self> {
+        Self::CHANNELS
+            .get(usize::from(channel).checked_sub(1)?)
+            .copied()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScopeTriggerSlope {
+    Rising,
+    Falling,
+    Either,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScopeCoupling {
+    Dc,
+    Ac,
+    Gnd,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScopeBandwidthLimit {
+    #[serde(rename = "off")]
+    Off,
+    #[serde(rename = "20mhz")]
+    Mhz20,
+    #[serde(rename = "25mhz")]
+    Mhz25,
+    #[serde(rename = "100mhz")]
+    Mhz100,
+    #[serde(rename = "250mhz")]
+    Mhz250,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -343,9 +631,18 @@ pub enum Connection {
         ip: String,
         port: u16,
     },
+    Vxi11 {
+        host: String,
+        device: String,
+    },
     Usbtmc {
         resource: String,
     },
+    /// Linux `usbtmc` kernel driver character device, e.g. `/dev/usbtmc0`.
+    #[serde(rename = "usbtmc_device")]
+    UsbtmcDevice {
+        path: String,
+    },
     #[serde(rename = "prologix_tcp")]
     PrologixTcp {
         host: String,
@@ -360,6 +657,10 @@ pub enum Connection {
         baud_rate: u32,
         read_timeout_ms: u16,
     },
+    /// Simulated instrument served in-process; see `communications::sim`.
+    Sim {
+        bench: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -380,6 +681,9 @@ pub struct Channel {
     pub index: u8,
     pub factor: Option<f64>,
     pub scale_to_abs_max: Option<f64>,
+    /// Pickup-coil calibration; set instead of `factor` for coil sensors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coil: Option<CoilCalibration>,
     pub label: Option<String>,
     pub unit_out: Option<String>,
 }
@@ -388,6 +692,54 @@ pub struct Channel {
 pub struct Pulse {
     pub bg_window_before: Window,
     pub bg_window_after: Window,
+    #[serde(skip_serializing_if = "PulseBaseline::is_constant")]
+    pub baseline: PulseBaseline,
+}
+
+/// Background model subtracted from sensor channels before integration.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum PulseBaseline {
+    /// Mean of both background windows.
+    #[default]
+    Constant,
+    /// Least-squares line through both background windows.
+    Linear,
+    /// Least-squares polynomial through both background windows.
+    Polynomial { order: usize },
+    /// Least-squares polynomial whose integral returns to zero at the start
+    /// of the after-pulse window.
+    EndpointConstrained {
+        #[serde(default = "default_endpoint_baseline_order")]
+        order: usize,
+    },
+}
+
+impl PulseBaseline {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Constant => "constant",
+            Self::Linear => "linear",
+            Self::Polynomial { .. } => "polynomial",
+            Self::EndpointConstrained { .. } => "endpoint_constrained",
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        match *self {
+            Self::Constant => 0,
+            Self::Linear => 1,
+            Self::Polynomial { order } | Self::EndpointConstrained { order } => order,
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        *self == Self::Constant
+    }
+}
+
+fn default_endpoint_baseline_order() -> usize {
+    1
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -407,8 +759,16 @@ pub struct Window {
 pub struct Lockin {
     pub workers: usize,
     pub stride_samples: usize,
+    #[serde(skip_serializing_if = "is_default_harmonics")]
+    pub harmonics: Vec<usize>,
     pub lpf_kind: LockinLpfKind,
     pub lpf_half_window_cycles: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lpf_cutoff: Option<LockinCutoff>,
+    #[serde(skip_serializing_if = "is_default_rc_order")]
+    pub lpf_order: usize,
+    #[serde(skip_serializing_if = "is_true")]
+    pub lpf_zero_phase: bool,
     pub lpf_debug_output: bool,
     pub lpf_debug_label: Option<String>,
     pub lpf_debug_overwrite: bool,
@@ -417,15 +777,123 @@ pub struct Lockin {
     pub save_npy: bool,
 }
 
+fn is_default_harmonics(harmonics: &[usize]) -> bool {
+    harmonics == pmoke_config_core::DEFAULT_LOCKIN_HARMONICS
+}
+
+fn is_default_trigger_timeout(timeout: &f64) -> bool {
+    *timeout == pmoke_config_core::DEFAULT_TRIGGER_TIMEOUT_S
+}
+
+fn is_default_trigger_poll_interval(interval: &f64) -> bool {
+    *interval == pmoke_config_core::DEFAULT_TRIGGER_POLL_INTERVAL_S
+}
+
+fn is_default_rc_order(order: &usize) -> bool {
+    *order == pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum LockinLpfKind {
     BoxcarLegacy,
+    RcCascade,
+    WindowedSinc,
+    Gaussian,
+}
+
+impl LockinLpfKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BoxcarLegacy => "boxcar_legacy",
+            Self::RcCascade => "rc_cascade",
+            Self::WindowedSinc => "windowed_sinc",
+            Self::Gaussian => "gaussian",
+        }
+    }
+
+    pub fn uses_half_window(&self) -> bool {
+        matches!(self, Self::BoxcarLegacy | Self::WindowedSinc)
+    }
+}
+
+/// How the cutoff of a non-boxcar lock-in LPF is chosen.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LockinCutoff {
+    Hz(f64),
+    ReferenceRatio(f64),
+    EnbwHz(f64),
+}
+
+impl LockinCutoff {
+    fn from_fields(
+        cutoff_hz: Option<f64>,
+        cutoff_ref_ratio: Option<f64>,
+        enbw_hz: Option<f64>,
+    ) -> Option<Self> {
+        cutoff_hz
+            .map(Self::Hz)
+            .or(cutoff_ref_ratio.map(Self::ReferenceRatio))
+            .or(enbw_hz.map(Self::EnbwHz))
+    }
+
+    pub(crate) fn fields(cutoff: Option<Self>) -> (Option<f64>, Option<f64>, Option<f64>) {
+        match cutoff {
+            Some(Self::Hz(value)) => (Some(value), None, None),
+            Some(Self::ReferenceRatio(value)) => (None, Some(value), None),
+            Some(Self::EnbwHz(value)) => (None, None, Some(value)),
+            None => (None, None, None),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Phase {
     pub m_omega_t0_offset: Vec<f64>,
+    #[serde(skip_serializing_if = "PhaseDrift::is_constant")]
+    pub drift: PhaseDrift,
+}
+
+/// Time dependence of omega·t0 applied by phase rotation.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum PhaseDrift {
+    /// One omega·t0 for the whole trace.
+    #[default]
+    Constant,
+    /// omega·t0 fitted in sliding windows of `window` seconds, `step` seconds
+    /// apart, and linearly interpolated between the window centres.
+    PiecewiseLinear {
+        window: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        step: Option<f64>,
+    },
+}
+
+impl PhaseDrift {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Constant => "constant",
+            Self::PiecewiseLinear { .. } => "piecewise_linear",
+        }
+    }
+
+    /// Window length and centre spacing; `step` defaults to half a window.
+    pub fn window_and_step(&self) -> Option<(f64, f64)> {
+        match *self {
+            Self::Constant => None,
+            Self::PiecewiseLinear { window, step } => Some((window, step.unwrap_or(window / 2.0))),
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        *self == Self::Constant
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -435,11 +903,54 @@ pub enum KerrType {
     Harmonics,
 }
 
+impl KerrType {
+    pub fn required_harmonics(&self) -> &'static [usize] {
+        match self {
+            Self::Standard => &[1, 2],
+            Self::Harmonics => &[2, 3, 4, 6],
+        }
+    }
+}
+
+pub use pmoke_config_core::{
+    CoilCalibration, CoilCompensation, KerrFieldBackground, KerrModulationDepth,
+};
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Kerr {
     pub use_sensor_ch: u8,
     pub kerr_type: KerrType,
     pub factor: f64,
+    #[serde(skip_serializing_if = "is_default_modulation_depth")]
+    pub modulation_depth: KerrModulationDepth,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field_bin_width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_run: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field_background: Option<KerrFieldBackground>,
+}
+
+impl Kerr {
+    pub fn estimates_modulation_depth(&self) -> bool {
+        matches!(self.kerr_type, KerrType::Standard)
+            && self.modulation_depth == KerrModulationDepth::Harmonics
+    }
+
+    pub fn required_harmonics(&self) -> Vec<usize> {
+        let mut required = self.kerr_type.required_harmonics().to_vec();
+        if self.estimates_modulation_depth() {
+            // φm is estimated from the even harmonics alone.
+            required.extend_from_slice(&[2, 4, 6]);
+            required.sort_unstable();
+            required.dedup();
+        }
+        required
+    }
+}
+
+fn is_default_modulation_depth(value: &KerrModulationDepth) -> bool {
+    *value == KerrModulationDepth::default()
 }
 
 #[allow(dead_code)]
@@ -450,6 +961,7 @@ pub enum ValidationTarget {
     Autoshot,
     Fetch,
     Screenshot,
+    Scope,
     Automeasure,
     Reference,
     Sensor,
@@ -588,6 +1100,7 @@ impl From<InstrumentsV1> for Instruments {
         Self {
             function_generator: value.function_generator.map(Into::into),
             oscilloscope: value.oscilloscope.into(),
+            multimeters: Vec::new(),
         }
     }
 }
@@ -597,6 +1110,7 @@ impl From<InstrumentsV2> for Instruments {
         Self {
             function_generator: value.function_generator.map(Into::into),
             oscilloscope: value.oscilloscope.into(),
+            multimeters: Vec::new(),
         }
     }
 }
@@ -683,8 +1197,12 @@ impl TryFrom<LockinV4> for Lockin {
         Ok(Self {
             workers: value.workers,
             stride_samples: value.stride_samples,
+            harmonics: default_lockin_harmonics(),
             lpf_kind,
             lpf_half_window_cycles,
+            lpf_cutoff: None,
+            lpf_order: pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+            lpf_zero_phase: true,
             lpf_debug_output: value.debug_output,
             lpf_debug_label: value.debug_label,
             lpf_debug_overwrite: value.debug_overwrite,
@@ -697,12 +1215,64 @@ impl TryFrom<LockinV4> for Lockin {
 
 impl From<LockinV5> for Lockin {
     fn from(value: LockinV5) -> Self {
-        let LockinFilterV5::BoxcarLegacy { half_window_cycles } = value.filter;
+        let default_order = pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER;
+        let (lpf_kind, lpf_half_window_cycles, lpf_cutoff, lpf_order, lpf_zero_phase) =
+            match value.filter {
+                LockinFilterV5::BoxcarLegacy { half_window_cycles } => (
+                    LockinLpfKind::BoxcarLegacy,
+                    half_window_cycles,
+                    None,
+                    default_order,
+                    true,
+                ),
+                LockinFilterV5::RcCascade {
+                    order,
+                    cutoff_hz,
+                    cutoff_ref_ratio,
+                    enbw_hz,
+                    zero_phase,
+                } => (
+                    LockinLpfKind::RcCascade,
+                    0.0,
+                    LockinCutoff::from_fields(cutoff_hz, cutoff_ref_ratio, enbw_hz),
+                    order,
+                    zero_phase,
+                ),
+                LockinFilterV5::WindowedSinc {
+                    half_window_cycles,
+                    cutoff_hz,
+                    cutoff_ref_ratio,
+                    enbw_hz,
+                    zero_phase,
+                } => (
+                    LockinLpfKind::WindowedSinc,
+                    half_window_cycles,
+                    LockinCutoff::from_fields(cutoff_hz, cutoff_ref_ratio, enbw_hz),
+                    default_order,
+                    zero_phase,
+                ),
+                LockinFilterV5::Gaussian {
+                    cutoff_hz,
+                    cutoff_ref_ratio,
+                    enbw_hz,
+                    zero_phase,
+                } => (
+                    LockinLpfKind::Gaussian,
+                    0.0,
+                    LockinCutoff::from_fields(cutoff_hz, cutoff_ref_ratio, enbw_hz),
+                    default_order,
+                    zero_phase,
+                ),
+            };
         Self {
             workers: value.workers,
             stride_samples: value.stride_samples,
-            lpf_kind: LockinLpfKind::BoxcarLegacy,
-            lpf_half_window_cycles: half_window_cycles,
+            harmonics: value.harmonics,
+            lpf_kind,
+            lpf_half_window_cycles,
+            lpf_cutoff,
+            lpf_order,
+            lpf_zero_phase,
             lpf_debug_output: value.debug_output,
             lpf_debug_label: value.debug_label,
             lpf_debug_overwrite: value.debug_overwrite,
@@ -718,6 +1288,7 @@ impl From<FunctionGeneratorV1> for FunctionGenerator {
         Self {
             connection: value.connection,
             model: value.model,
+            setup: None,
         }
     }
 }
@@ -727,6 +1298,7 @@ impl From<FunctionGeneratorV2> for FunctionGenerator {
         Self {
             connection: value.connection,
             model: value.model,
+            setup: None,
         }
     }
 }
@@ -736,6 +1308,9 @@ impl From<OscilloscopeV1> for Oscilloscope {
         Self {
             connection: value.connection,
             model: value.model,
+            trigger_timeout_s: pmoke_config_core::DEFAULT_TRIGGER_TIMEOUT_S,
+            trigger_poll_interval_s: pmoke_config_core::DEFAULT_TRIGGER_POLL_INTERVAL_S,
+            setup: None,
         }
     }
 }
@@ -745,6 +1320,9 @@ impl From<OscilloscopeV2> for Oscilloscope {
         Self {
             connection: value.connection,
             model: value.model,
+            trigger_timeout_s: pmoke_config_core::DEFAULT_TRIGGER_TIMEOUT_S,
+            trigger_poll_interval_s: pmoke_config_core::DEFAULT_TRIGGER_POLL_INTERVAL_S,
+            setup: None,
         }
     }
 }
@@ -773,6 +1351,7 @@ impl From<ChannelV1> for Channel {
             index: value.index,
             factor: value.factor,
             scale_to_abs_max: value.scale_to_abs_max,
+            coil: None,
             label: value.label,
             unit_out: value.unit_out,
         }
@@ -785,6 +1364,7 @@ impl From<ChannelV2> for Channel {
             index: value.index,
             factor: value.factor,
             scale_to_abs_max: value.scale_to_abs_max,
+            coil: None,
             label: value.label,
             unit_out: value.unit_out,
         }
@@ -796,6 +1376,7 @@ impl From<PulseV1> for Pulse {
         Self {
             bg_window_before: value.bg_window_before,
             bg_window_after: value.bg_window_after,
+            baseline: PulseBaseline::Constant,
         }
     }
 }
@@ -805,6 +1386,7 @@ impl From<PulseV2> for Pulse {
         Self {
             bg_window_before: value.bg_window_before,
             bg_window_after: value.bg_window_after,
+            baseline: PulseBaseline::Constant,
         }
     }
 }
@@ -845,6 +1427,10 @@ impl From<KerrV1> for Kerr {
             use_sensor_ch: value.use_sensor_ch,
             kerr_type: value.kerr_type,
             factor: value.factor,
+            modulation_depth: KerrModulationDepth::default(),
+            field_bin_width: None,
+            background_run: None,
+            field_background: None,
         }
     }
 }
@@ -855,6 +1441,10 @@ impl From<KerrV2> for Kerr {
             use_sensor_ch: value.use_sensor_ch,
             kerr_type: value.kerr_type,
             factor: value.factor,
+            modulation_depth: KerrModulationDepth::default(),
+            field_bin_width: None,
+            background_run: None,
+            field_background: None,
         }
     }
 }
@@ -101,7 +101,7 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         "string",
         Required,
         None,
-        &["DHO5108"],
+        &["DHO5108", "InfiniiVision"],
         None,
         "Oscilloscope driver model.",
         "The model must match an oscilloscope registered by the instruments crate.",
@@ -114,14 +114,211 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         "connection URI",
         Required,
         None,
-        &["tcp://", "visa:", "gpib://"],
+        &["tcp://", "visa:", "usbtmc://", "gpib://", "sim://"],
         None,
         "Oscilloscope transport URI.",
-        "DHO5108 normally uses direct TCP/IP and never uses a Prologix transport.",
+        "DHO5108 normally uses direct TCP/IP and never uses a Prologix transport; `usbtmc:///dev/usbtmc0` opens the Linux usbtmc device and `sim://bench` serves a simulated DHO5108.",
         "オシロスコープの通信URI",
-        "DHO5108で使用するdirect TCP/IP。Prologix transport対象外。",
+        "DHO5108で使用するdirect TCP/IP。Prologix transport対象外。`usbtmc:///dev/usbtmc0`はLinuxのusbtmcデバイス、`sim://bench`は模擬DHO5108。",
         &["transport must be supported by the model and build features"],
     ),
+    field(
+        "scope.trigger_timeout_s",
+        "float",
+        Optional,
+        Some("10.0"),
+        &[],
+        Some("s"),
+        "Trigger wait limit for automated shots.",
+        "autoshot, automeasure, and auto wait at most this long for the scope to arm after SINGLE and again for it to reach STOP after the generator fires. Cover the full capture window.",
+        "自動ショットのトリガー待ち上限",
+        "autoshot・automeasure・autoはSINGLE後のarm待ちと、ジェネレータ発火後のSTOP待ちをそれぞれこの時間まで実行。キャプチャ窓全体を含む値を指定。",
+        &["must be finite and positive"],
+    ),
+    field(
+        "scope.trigger_poll_interval_s",
+        "float",
+        Optional,
+        Some("0.1"),
+        &[],
+        Some("s"),
+        "Trigger-status polling interval.",
+        "Delay between trigger-status queries while waiting for the scope to arm or stop.",
+        "トリガー状態のポーリング間隔",
+        "armまたはSTOP待ち中のトリガー状態問い合わせ間隔。",
+        &["must be positive and no longer than scope.trigger_timeout_s"],
+    ),
+    field(
+        "scope.setup",
+        "table",
+        Optional,
+        None,
+        &[],
+        None,
+        "Pinned oscilloscope acquisition setup.",
+        "pmoke scope apply pushes these settings, pmoke scope verify reads them back and lists differences, and fetch warns when the scope has drifted. Omitted settings are left as they are on the instrument.",
+        "オシロスコープ取得設定の固定値",
+        "pmoke scope applyで送信、pmoke scope verifyで読み戻して差分表示、fetchでずれを警告。未指定項目は機器側設定のまま。",
+        &[],
+    ),
+    field(
+        "scope.setup.timebase_scale",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("s/div"),
+        "Horizontal scale.",
+        "Main timebase scale per division.",
+        "水平スケール",
+        "1目盛あたりのメインタイムベース。",
+        &["must be finite and positive"],
+    ),
+    field(
+        "scope.setup.timebase_offset",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("s"),
+        "Horizontal offset.",
+        "Main timebase offset of the trigger point.",
+        "水平オフセット",
+        "トリガー位置のメインタイムベースオフセット。",
+        &["must be finite"],
+    ),
+    field(
+        "scope.setup.memory_depth",
+        "integer",
+        Optional,
+        None,
+        &[],
+        Some("samples"),
+        "Acquisition memory depth.",
+        "Sent as the scope's memory-depth token, for example 10000000 as 10M; the scope must support the value.",
+        "取得メモリ長",
+        "10000000を10Mのようにメモリ長指定へ変換。機器が対応する値を指定。",
+        &["must be positive"],
+    ),
+    field(
+        "scope.setup.trigger_source",
+        "string",
+        Optional,
+        None,
+        &[
+            "ch1", "ch2", "ch3", "ch4", "ch5", "ch6", "ch7", "ch8", "ext", "ac_line",
+        ],
+        None,
+        "Edge-trigger source.",
+        "Setting any trigger field switches the scope to edge triggering.",
+        "エッジトリガー信号源",
+        "トリガー項目指定時はエッジトリガーへ切替。",
+        &[],
+    ),
+    field(
+        "scope.setup.trigger_level",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("V"),
+        "Edge-trigger level.",
+        "Trigger threshold on the selected source.",
+        "エッジトリガーレベル",
+        "選択信号源のトリガーしきい値。",
+        &["must be finite"],
+    ),
+    field(
+        "scope.setup.trigger_slope",
+        "string",
+        Optional,
+        None,
+        &["rising", "falling", "either"],
+        None,
+        "Edge-trigger slope.",
+        "Edge direction that fires the trigger.",
+        "エッジトリガー極性",
+        "トリガーを発生させるエッジ方向。",
+        &[],
+    ),
+    field(
+        "scope.setup.channels",
+        "array of tables",
+        Optional,
+        Some("[]"),
+        &[],
+        None,
+        "Per-channel vertical setup.",
+        "Each entry pins the vertical settings of one channel; fetch records the read-back state of every fetched channel regardless.",
+        "チャンネル別垂直設定",
+        "各エントリで1チャンネルの垂直設定を固定。fetchは取得全チャンネルの読み戻し値を記録。",
+        &["channels must be unique"],
+    ),
+    field(
+        "scope.setup.channels[].channel",
+        "integer",
+        Required,
+        None,
+        &[],
+        None,
+        "Oscilloscope channel.",
+        "The DHO5108 has channels 1 through 8.",
+        "オシロスコープチャンネル番号",
+        "DHO5108向け1以上8以下の番号。",
+        &["1 <= value <= 8"],
+    ),
+    field(
+        "scope.setup.channels[].scale",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("V/div"),
+        "Vertical scale.",
+        "Volts per division.",
+        "垂直スケール",
+        "1目盛あたりの電圧。",
+        &["must be finite and positive"],
+    ),
+    field(
+        "scope.setup.channels[].offset",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("V"),
+        "Vertical offset.",
+        "Sent after the scale because the allowed offset range depends on it.",
+        "垂直オフセット",
+        "許容範囲がスケールに依存するためスケール後に送信。",
+        &["must be finite"],
+    ),
+    field(
+        "scope.setup.channels[].coupling",
+        "string",
+        Optional,
+        None,
+        &["dc", "ac", "gnd"],
+        None,
+        "Input coupling.",
+        "Channel input coupling.",
+        "入力結合",
+        "チャンネル入力の結合方式。",
+        &[],
+    ),
+    field(
+        "scope.setup.channels[].bandwidth_limit",
+        "string",
+        Optional,
+        None,
+        &["off", "20mhz", "25mhz", "100mhz", "250mhz"],
+        None,
+        "Bandwidth limit.",
+        "Hardware bandwidth limit; the scope rejects limits its model does not offer.",
+        "帯域制限",
+        "ハードウェア帯域制限。機種非対応の値は機器側で拒否。",
+        &[],
+    ),
     field(
         "generator",
         "table",
@@ -153,14 +350,221 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         "connection URI",
         Conditional,
         None,
-        &["gpib://", "prologix-tcp://", "prologix-serial://"],
+        &["gpib://", "prologix-tcp://", "prologix-serial://", "sim://"],
         None,
         "Function-generator transport URI.",
-        "The URI selects native GPIB or a Prologix TCP/serial controller.",
+        "The URI selects native GPIB, a Prologix TCP/serial controller, or a `sim://bench` simulated WF1946B.",
         "ファンクションジェネレータの通信URI",
+        "native GPIB、Prologix TCP/serial、または`sim://bench`の模擬WF1946Bの選択。",
+        &["transport must be supported by the model and build features"],
+    ),
+    field(
+        "generator.setup",
+        "table",
+        Optional,
+        None,
+        &[],
+        None,
+        "Function-generator output setup.",
+        "Applied before each shot by autoshot and during the auto and automeasure preflight; a setting the generator rejects fails the shot. fetch records the read-back output under [generator.setup] in the RAW metadata and warns on drift. Omitted settings are left as they are on the instrument.",
+        "ファンクションジェネレータ出力設定",
+        "autoshotでは各ショット前、autoとautomeasureではpreflightで適用。ジェネレータが拒否した設定はショット失敗。fetchは読み戻した出力をRAW metadataの[generator.setup]に記録し、差異を警告。未指定項目は機器側設定のまま。",
+        &[],
+    ),
+    field(
+        "generator.setup.waveform",
+        "string",
+        Optional,
+        None,
+        &["sine", "square", "pulse", "ramp", "noise", "dc"],
+        None,
+        "Output waveform shape.",
+        "Standard waveform selected on the output channel.",
+        "出力波形",
+        "出力チャンネルで選択する標準波形。",
+        &[],
+    ),
+    field(
+        "generator.setup.frequency_hz",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("Hz"),
+        "Output frequency.",
+        "Waveform frequency; in burst mode this sets the period of each cycle.",
+        "出力周波数",
+        "波形周波数。バーストでは各周期の長さを決定。",
+        &["must be finite and positive"],
+    ),
+    field(
+        "generator.setup.amplitude_vpp",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("Vpp"),
+        "Output amplitude.",
+        "Peak-to-peak amplitude into the generator's configured load.",
+        "出力振幅",
+        "ジェネレータ設定負荷に対するpeak-to-peak振幅。",
+        &["must be finite and positive"],
+    ),
+    field(
+        "generator.setup.offset_v",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("V"),
+        "DC offset.",
+        "DC offset added to the waveform.",
+        "DCオフセット",
+        "波形に加えるDCオフセット。",
+        &["must be finite"],
+    ),
+    field(
+        "generator.setup.burst_cycles",
+        "integer",
+        Optional,
+        None,
+        &[],
+        Some("cycles"),
+        "Cycles per trigger.",
+        "Setting it switches the output to triggered burst mode with this many cycles per trigger.",
+        "トリガーあたりの周期数",
+        "指定時はトリガーバーストモードへ切替え、1トリガーあたりこの周期数を出力。",
+        &["must be at least 1"],
+    ),
+    field(
+        "generator.setup.trigger_source",
+        "string",
+        Optional,
+        None,
+        &["bus", "external", "internal"],
+        None,
+        "Burst trigger source.",
+        "autoshot fires the generator with a bus trigger, so other sources are only for externally fired setups and produce a warning.",
+        "バーストトリガー信号源",
+        "autoshotはバストリガーで発火するため、他の信号源は外部発火時のみ使用し警告を表示。",
+        &[],
+    ),
+    field(
+        "generator.setup.output",
+        "boolean",
+        Optional,
+        None,
+        &[],
+        None,
+        "Output on/off.",
+        "Output state, switched after every other setting has been applied.",
+        "出力オン/オフ",
+        "他の設定をすべて適用した後に切り替える出力状態。",
+        &[],
+    ),
+    field(
+        "multimeters",
+        "array of tables",
+        Optional,
+        Some("[]"),
+        &[],
+        None,
+        "Multimeters read around each shot.",
+        "auto and automeasure read every entry immediately before and after the shot, and process reads once after the manually triggered shot. The readings are stored under [[multimeters]] in the acquisition and analysis manifests.",
+        "ショット前後に読むマルチメータ一覧",
+        "autoとautomeasureはショット直前・直後、processは手動トリガー後に1回読み取り。値はacquisitionとanalysisのmanifestの[[multimeters]]に記録。",
+        &["labels must be unique"],
+    ),
+    field(
+        "multimeters[].label",
+        "string",
+        Required,
+        None,
+        &[],
+        None,
+        "Reading name.",
+        "Name recorded with the reading, for example sample_temperature or lamp_monitor.",
+        "読み取り値の名前",
+        "読み取り値と共に記録する名前。例: sample_temperature、lamp_monitor。",
+        &["must not be empty", "must be unique across multimeters"],
+    ),
+    field(
+        "multimeters[].model",
+        "string",
+        Required,
+        None,
+        &["Keithley2000", "Keithley2010"],
+        None,
+        "Multimeter driver model.",
+        "Registered multimeter model name.",
+        "マルチメータモデル",
+        "登録済みマルチメータのモデル名。",
+        &["must be a registered multimeter model"],
+    ),
+    field(
+        "multimeters[].connection",
+        "connection URI",
+        Required,
+        None,
+        &["gpib://", "prologix-tcp://", "prologix-serial://"],
+        None,
+        "Multimeter transport URI.",
+        "The URI selects native GPIB or a Prologix TCP/serial controller.",
+        "マルチメータの通信URI",
         "native GPIBまたはPrologix TCP/serialの選択。",
         &["transport must be supported by the model and build features"],
     ),
+    field(
+        "multimeters[].function",
+        "string",
+        Required,
+        None,
+        &[
+            "dc_volts",
+            "ac_volts",
+            "dc_current",
+            "ac_current",
+            "resistance",
+            "four_wire_resistance",
+            "temperature",
+            "frequency",
+        ],
+        None,
+        "Measurement function.",
+        "Readings are recorded in V, A, ohm, degC or Hz according to the function.",
+        "測定機能",
+        "読み取り値は機能に応じてV、A、ohm、degC、Hzで記録。",
+        &[],
+    ),
+    field(
+        "multimeters[].range",
+        "float",
+        Optional,
+        Some("autorange"),
+        &[],
+        Some("function unit"),
+        "Fixed measurement range.",
+        "Upper range in the function's unit; omit it to autorange. Not available for temperature or frequency.",
+        "固定測定レンジ",
+        "機能の単位での上限レンジ。省略時はオートレンジ。temperatureとfrequencyでは指定不可。",
+        &[
+            "must be finite and positive",
+            "not allowed for temperature or frequency",
+        ],
+    ),
+    field(
+        "multimeters[].thermocouple",
+        "string",
+        Optional,
+        None,
+        &["j", "k", "n", "t", "e", "r", "s", "b"],
+        None,
+        "Thermocouple type.",
+        "Thermocouple type for temperature readings; omitted keeps the type set on the instrument.",
+        "熱電対の種類",
+        "temperature測定時の熱電対種類。省略時は機器側設定のまま。",
+        &["requires function = \"temperature\""],
+    ),
     field(
         "data",
         "table",
@@ -244,13 +648,17 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         "inline table union",
         Required,
         None,
-        &["{ factor = ... }", "{ max_abs = ..., polarity = ... }"],
+        &[
+            "{ factor = ... }",
+            "{ max_abs = ..., polarity = ... }",
+            "{ area_turns = ..., gain = ..., compensation = { ... } }",
+        ],
         None,
         "Sensor conversion rule.",
-        "Choose direct multiplication or maximum-absolute normalization.",
+        "Choose direct multiplication, maximum-absolute normalization, or a pickup-coil calibration.",
         "センサー変換規則",
-        "直接係数または最大絶対値正規化の択一。",
-        &["factor and max_abs forms are mutually exclusive"],
+        "直接係数、最大絶対値正規化、ピックアップコイル校正の択一。",
+        &["factor, max_abs, and area_turns forms are mutually exclusive"],
     ),
     field(
         "sensors[].scale.factor",
@@ -285,12 +693,77 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         None,
         &["-1", "1"],
         None,
-        "Polarity for maximum-absolute scaling.",
-        "Required with max_abs scaling.",
-        "最大絶対値正規化の極性",
-        "max_abs使用時の必須符号。",
+        "Polarity for maximum-absolute scaling or a coil calibration.",
+        "Required with max_abs scaling; optional for coil calibrations, where it defaults to 1.",
+        "最大絶対値正規化またはコイル校正の極性",
+        "max_abs使用時の必須符号。コイル校正では省略時1。",
         &["must equal -1 or 1"],
     ),
+    field(
+        "sensors[].scale.area_turns",
+        "float",
+        Conditional,
+        None,
+        &[],
+        Some("m^2"),
+        "Effective area-turns product of a pickup coil.",
+        "Selects the coil calibration. The integrated voltage is converted to tesla with polarity / (gain * area_turns); use unit = \"T\".",
+        "ピックアップコイルの実効面積×巻数",
+        "コイル校正を選択。積分電圧をpolarity / (gain * area_turns)でテスラへ換算。unit = \"T\"を推奨。",
+        &["must be finite and positive"],
+    ),
+    field(
+        "sensors[].scale.gain",
+        "float",
+        Optional,
+        Some("1.0"),
+        &[],
+        None,
+        "Divider or attenuator gain in front of the scope.",
+        "Recorded volts per coil volt; a 10:1 divider is 0.1.",
+        "オシロスコープ前段の分圧・減衰ゲイン",
+        "コイル電圧1 Vあたりの記録電圧。10:1分圧なら0.1。",
+        &["must be finite and positive"],
+    ),
+    field(
+        "sensors[].scale.compensation",
+        "inline table",
+        Optional,
+        None,
+        &[],
+        None,
+        "Compensation coil subtracted before integration.",
+        "The compensation channel is fetched with the sensor, multiplied by balance, and subtracted from the main coil voltage before baseline fitting and integration.",
+        "積分前に差し引く補償コイル",
+        "補償チャンネルをセンサーと共に取得し、balance倍して主コイル電圧からベースライン推定・積分前に減算。",
+        &[],
+    ),
+    field(
+        "sensors[].scale.compensation.channel",
+        "integer",
+        Conditional,
+        None,
+        &[],
+        None,
+        "Oscilloscope channel of the compensation coil.",
+        "Required with compensation.",
+        "補償コイルのチャンネル番号",
+        "compensation使用時に必須。",
+        &["1 <= value <= 8", "must be unique across all channel roles"],
+    ),
+    field(
+        "sensors[].scale.compensation.balance",
+        "float",
+        Conditional,
+        None,
+        &[],
+        None,
+        "Balance coefficient of the compensation coil.",
+        "Required with compensation. The sensor voltage becomes main - balance * compensation.",
+        "補償コイルのバランス係数",
+        "compensation使用時に必須。センサー電圧はmain - balance * compensation。",
+        &["must be finite"],
+    ),
     field(
         "sensors[].label",
         "string",
@@ -356,6 +829,45 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         "有限なstartとendのinline table。",
         &["start < end"],
     ),
+    field(
+        "pulse.baseline",
+        "tagged inline table",
+        Optional,
+        Some("{ kind = \"constant\" }"),
+        &[],
+        None,
+        "Sensor background model subtracted before integration.",
+        "The baseline is fitted per sensor channel through both background windows. Its coefficients and the integral left at the start of background_after are recorded in the sensor section of the analysis manifest.",
+        "積分前に差し引くセンサー背景モデル",
+        "センサーチャンネルごとに前後の背景区間を通してfitする。係数とbackground_after開始時点に残る積分値を解析manifestのsensor欄に記録する。",
+        &["fields from other baseline kinds are rejected"],
+    ),
+    field(
+        "pulse.baseline.kind",
+        "string enum",
+        Required,
+        None,
+        &["constant", "linear", "polynomial", "endpoint_constrained"],
+        None,
+        "Sensor background model.",
+        "constant subtracts the mean of both windows, linear and polynomial subtract a least-squares fit that follows offset drift during the pulse, and endpoint_constrained additionally forces the field integral back to zero at the start of background_after.",
+        "センサー背景モデル",
+        "constantは両区間の平均、linearとpolynomialはパルス中のoffset driftに追従する最小二乗fit、endpoint_constrainedはさらにbackground_after開始時点で磁場積分を0に戻す。",
+        &[],
+    ),
+    field(
+        "pulse.baseline.order",
+        "integer",
+        Conditional,
+        None,
+        &[],
+        None,
+        "Polynomial order of the baseline.",
+        "Required by polynomial; optional for endpoint_constrained, where it defaults to 1.",
+        "ベースラインの多項式次数",
+        "polynomialで必須。endpoint_constrainedでは省略時1。",
+        &["must be in 1..=3"],
+    ),
     field(
         "reference",
         "table",
@@ -476,6 +988,24 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         "入力sample rateを割るsample数。出力sample rateを決定。",
         &["must be positive"],
     ),
+    field(
+        "lockin.harmonics",
+        "integer array",
+        Optional,
+        Some("[1, 2, 3, 4, 5, 6]"),
+        &[],
+        None,
+        "Demodulated harmonic orders.",
+        "Lock-in, phase, and Kerr tables carry one x/y or in/out pair per listed harmonic.",
+        "復調する高調波次数",
+        "lock-in・位相・Kerr表は列挙した高調波ごとにx/yまたはin/out列を出力。",
+        &[
+            "strictly increasing values in 1..=64",
+            "standard needs 1 and 2; harmonics needs 2, 3, 4, and 6",
+            "kerr.modulation_depth = \"harmonics\" also needs 2, 4, and 6",
+            "every harmonic times the reference frequency must be below Nyquist",
+        ],
+    ),
     field(
         "lockin.filter",
         "tagged inline table",
@@ -484,9 +1014,9 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         &[],
         None,
         "Lock-in low-pass filter configuration.",
-        "The current runtime accepts the boxcar_legacy kind; fields from other kinds are not part of schema v5.",
+        "Each kind accepts only its own fields; a field that belongs to another kind is rejected.",
         "lock-in low-pass filter 設定",
-        "schema v5ではboxcar_legacyのみを使用。",
+        "kindごとに許可されたフィールドのみ受け付ける。",
         &["fields from other filter kinds are rejected"],
     ),
     field(
@@ -494,27 +1024,101 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         "string enum",
         Required,
         None,
-        &["boxcar_legacy"],
+        &["boxcar_legacy", "rc_cascade", "windowed_sinc", "gaussian"],
         None,
         "Lock-in filter algorithm.",
-        "boxcar_legacy is the only active LPF. Historical FIR and IIR kinds are readable only for migration diagnostics.",
+        "rc_cascade is an n-pole IIR, windowed_sinc a Blackman-windowed FIR, and gaussian a Gaussian FIR. Historical fir_zero_phase, sync_iir_zero_phase, and fir_boxcar_enbw kinds are readable only for migration diagnostics.",
         "lock-in filter アルゴリズム",
-        "現在のLPFはboxcar_legacyのみ。過去のFIR/IIR kindは移行診断のためだけに読み取る。",
+        "rc_cascadeはn段RC(IIR)、windowed_sincはBlackman窓FIR、gaussianはGauss FIR。過去のFIR/IIR kindは移行診断のためだけに読み取る。",
         &[],
     ),
     field(
         "lockin.filter.half_window_cycles",
         "float",
-        Required,
+        Conditional,
         None,
         &[],
         Some("reference cycles"),
         "Half-window scale in reference cycles.",
-        "The boxcar integration window extends symmetrically by this many reference cycles.",
+        "Required by boxcar_legacy and windowed_sinc. The boxcar integration window or the FIR support extends symmetrically by this many reference cycles.",
         "参照周期単位の半窓幅",
-        "boxcar積分窓を参照周期単位で指定する半窓幅。",
+        "boxcar_legacyとwindowed_sincで必須。boxcar積分窓またはFIR支持幅を参照周期単位で指定する。",
         &["must be finite and positive"],
     ),
+    field(
+        "lockin.filter.order",
+        "integer",
+        Optional,
+        Some("4"),
+        &[],
+        None,
+        "Number of cascaded RC stages.",
+        "rc_cascade only. Each stage is a first-order low-pass with the same corner frequency.",
+        "RC段数",
+        "rc_cascadeのみ。各段は同じ遮断周波数の1次low-pass。",
+        &["must be in 1..=8"],
+    ),
+    field(
+        "lockin.filter.cutoff_hz",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("Hz"),
+        "Explicit low-pass cutoff.",
+        "rc_cascade, windowed_sinc, and gaussian. The per-stage -3 dB corner for rc_cascade, the -6 dB point for windowed_sinc, and the -3 dB point for gaussian.",
+        "low-pass遮断周波数",
+        "rc_cascadeでは各段の-3 dB点、windowed_sincでは-6 dB点、gaussianでは-3 dB点。",
+        &[
+            "must be finite and positive",
+            "set at most one of cutoff_hz, cutoff_ref_ratio, and enbw_hz",
+        ],
+    ),
+    field(
+        "lockin.filter.cutoff_ref_ratio",
+        "float",
+        Optional,
+        None,
+        &[],
+        None,
+        "Cutoff as a fraction of the reference frequency.",
+        "The cutoff is this value times the measured reference frequency.",
+        "参照周波数に対する遮断周波数の比",
+        "測定した参照周波数に掛けて遮断周波数とする。",
+        &[
+            "must be finite and positive",
+            "set at most one of cutoff_hz, cutoff_ref_ratio, and enbw_hz",
+        ],
+    ),
+    field(
+        "lockin.filter.enbw_hz",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("Hz"),
+        "Target equivalent noise bandwidth.",
+        "The cutoff is solved so the filter has this two-sided ENBW. Without any cutoff field the target is half the reference frequency, the ENBW of a one-cycle boxcar.",
+        "目標の等価雑音帯域幅",
+        "この両側ENBWになるよう遮断周波数を決める。未指定時は参照周波数の半分(1周期boxcarのENBW)。",
+        &[
+            "must be finite and positive",
+            "set at most one of cutoff_hz, cutoff_ref_ratio, and enbw_hz",
+        ],
+    ),
+    field(
+        "lockin.filter.zero_phase",
+        "boolean",
+        Optional,
+        Some("true"),
+        &["true", "false"],
+        None,
+        "Zero-phase filtering.",
+        "rc_cascade runs forward and backward, doubling the effective order; FIR kinds are centred. false gives a causal filter with its group delay.",
+        "ゼロ位相フィルタ",
+        "rc_cascadeは順方向と逆方向に適用し実効次数が2倍。FIRは中心合わせ。falseで群遅延のある因果フィルタ。",
+        &[],
+    ),
     field(
         "lockin.debug_output",
         "boolean",
@@ -564,10 +1168,10 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         Some("absent"),
         &[],
         Some("s"),
-        "Optional background window for SNR diagnostics.",
-        "An inline table with finite start and end values.",
-        "SNR 診断用背景区間",
-        "有限なstartとendの任意inline table。",
+        "Optional background window for SNR diagnostics and Kerr error bars.",
+        "An inline table with finite start and end values. Defaults to pulse.bg_window_before. The lock-in x/y noise in this window is written to lockin/ch<N>_noise.csv and propagated to the kerr_err (rad) columns.",
+        "SNR 診断とKerr誤差棒用の背景区間",
+        "有限なstartとendの任意inline table。省略時はpulse.bg_window_before。この区間のlock-in x/y雑音をlockin/ch<N>_noise.csvに出力し、kerr_err (rad)列へ伝播する。",
         &["start < end"],
     ),
     field(
@@ -604,9 +1208,9 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         &[],
         None,
         "Phase-rotation settings.",
-        "Contains offsets for the first six harmonic components.",
+        "Contains offsets for the harmonics listed in lockin.harmonics.",
         "位相回転設定",
-        "第1から第6高調波のoffset。",
+        "lockin.harmonicsで指定した高調波のoffset。",
         &[],
     ),
     field(
@@ -616,11 +1220,63 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         None,
         &[],
         Some("rad"),
-        "Six harmonic phase offsets.",
-        "Numeric expressions such as pi/2 are accepted.",
-        "6高調波の位相offset",
-        "pi/2などの数式指定に対応するrad値。",
-        &["must contain exactly 6 finite values"],
+        "Per-harmonic phase offsets.",
+        "One entry per lockin.harmonics value, in the same order; expressions such as pi/2 are accepted.",
+        "高調波ごとの位相offset",
+        "lockin.harmonicsと同じ順序で1要素ずつ。pi/2などの数式指定に対応するrad値。",
+        &["must contain one finite value per lockin.harmonics entry"],
+    ),
+    field(
+        "phase.drift",
+        "tagged inline table",
+        Optional,
+        Some("{ kind = \"constant\" }"),
+        &[],
+        None,
+        "Time dependence of omega_t0 during the pulse.",
+        "piecewise_linear refits omega_t0 in sliding windows of the even-harmonic lock-in vectors and rotates every point by the interpolated phase. The fitted phase is written to lockin/ch<N>_phase_drift.csv.",
+        "パルス中のomega_t0の時間変化",
+        "piecewise_linearは偶数次lock-inベクトルのsliding windowごとにomega_t0を再fitし、補間した位相で各点を回転する。fit位相はlockin/ch<N>_phase_drift.csvに出力する。",
+        &["fields from other drift kinds are rejected"],
+    ),
+    field(
+        "phase.drift.kind",
+        "string enum",
+        Required,
+        None,
+        &["constant", "piecewise_linear"],
+        None,
+        "Phase drift model.",
+        "constant rotates the whole trace by one omega_t0; piecewise_linear follows heating or Faraday-rotation drift at high field.",
+        "位相driftモデル",
+        "constantは全区間を単一のomega_t0で回転、piecewise_linearは高磁場での発熱やFaraday回転によるdriftに追従する。",
+        &[],
+    ),
+    field(
+        "phase.drift.window",
+        "float",
+        Conditional,
+        None,
+        &[],
+        Some("s"),
+        "Length of each drift-fitting window.",
+        "Required by piecewise_linear. Traces shorter than one window are fitted as a single window.",
+        "drift fit窓の長さ",
+        "piecewise_linearで必須。1窓より短いtraceは単一窓としてfitする。",
+        &["must be finite and positive"],
+    ),
+    field(
+        "phase.drift.step",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("s"),
+        "Spacing between drift-fitting windows.",
+        "Defaults to half of window, so adjacent windows overlap by half.",
+        "drift fit窓の間隔",
+        "省略時はwindowの半分で、隣接窓が半分重なる。",
+        &["must be finite and positive"],
     ),
     field(
         "kerr",
@@ -674,6 +1330,106 @@ pub const CONFIG_FIELD_DOCS: &[ConfigFieldDoc] = &[
         "選択したKerr角度計算後に乗算する値。",
         &["must be finite"],
     ),
+    field(
+        "kerr.modulation_depth",
+        "float or \"harmonics\"",
+        Optional,
+        Some("0.92"),
+        &[],
+        Some("rad"),
+        "EOM retardation amplitude φm for the standard method.",
+        "The standard method scales by J2(2φm)/J1(2φm); \"harmonics\" estimates φm per channel from harmonics 2, 4, and 6 of the same shot. The value used is recorded in the analysis manifest.",
+        "standard法で使うEOMのリタデーション振幅φm",
+        "standard法はJ2(2φm)/J1(2φm)で換算。\"harmonics\"は同じショットの2・4・6次高調波からチャンネルごとにφmを推定。使用値は解析マニフェストに記録。",
+        &[
+            "in (0, 1.9158) when numeric",
+            "\"harmonics\" with the standard method also needs harmonics 4 and 6",
+        ],
+    ),
+    field(
+        "kerr.field_bin_width",
+        "float",
+        Optional,
+        None,
+        &[],
+        Some("kerr.sensor output unit"),
+        "Field grid spacing for Kerr hysteresis output.",
+        "When set, the Kerr stage splits the trace at the sensor-integral extrema into rising and falling branches (four for bipolar pulses) and writes per-bin mean, std, and propagated noise error of the mean to kerr/kerr_vs_field_*.csv. The error counts one independent lock-in sample per 1/ENBW of the low-pass filter.",
+        "Kerrヒステリシス出力の磁場グリッド間隔",
+        "指定時、Kerr stageはセンサー積分の極値でトレースを上昇・下降ブランチ(双極パルスは4本)に分割し、ビンごとの平均、標準偏差、雑音から伝播した平均値の誤差をkerr/kerr_vs_field_*.csvに出力。誤差はlock-inフィルタの等価雑音帯域幅(ENBW)の逆数あたり1点を独立サンプルとして数える。",
+        &["must be finite and positive"],
+    ),
+    field(
+        "kerr.background_run",
+        "string",
+        Optional,
+        None,
+        &[],
+        None,
+        "Zero-field run subtracted from the Kerr angle.",
+        "Path to another run directory, relative to this config file, whose published kerr/kerr.csv is subtracted point by point before results are written. Its time axis and Kerr channels must match; its checksums are recorded under [kerr.background] in the analysis manifest.",
+        "Kerr角度から差し引くゼロ磁場ラン",
+        "このconfigファイルからの相対パスで別のランディレクトリを指定。公開済みkerr/kerr.csvを結果出力前に点ごとに差し引く。時間軸とKerrチャンネルの一致が必要。チェックサムは解析マニフェストの[kerr.background]に記録。",
+        &[
+            "the run must have published Kerr results with matching output checksums",
+            "must not be the run being analyzed",
+        ],
+    ),
+    field(
+        "kerr.field_background",
+        "tagged inline table",
+        Optional,
+        None,
+        &[],
+        None,
+        "Odd-in-field background removed from the Kerr angle.",
+        "Fits a per-range offset plus odd powers of the sensor-integral field over the listed high-field ranges, where the magnetization is saturated, and subtracts the odd polynomial from every point. kerr/kerr.csv and kerr/kerr_vs_field_*.csv keep the uncorrected angle as Ch<N> Kerr angle raw (rad); the fit is recorded in the analysis manifest.",
+        "Kerr角度から除去する磁場の奇関数バックグラウンド",
+        "磁化が飽和した高磁場範囲でセンサー積分磁場の奇数次べきと範囲ごとのoffsetをfitし、奇数次多項式を全点から差し引く。補正前の角度はkerr/kerr.csvとkerr/kerr_vs_field_*.csvのCh<N> Kerr angle raw (rad)列に残し、fit結果は解析マニフェストに記録。",
+        &["fields from other background kinds are rejected"],
+    ),
+    field(
+        "kerr.field_background.kind",
+        "string enum",
+        Required,
+        None,
+        &["linear", "odd_polynomial"],
+        None,
+        "Field background model.",
+        "linear removes a term proportional to the field, such as the Faraday rotation of the substrate; odd_polynomial adds higher odd powers up to order.",
+        "磁場バックグラウンドモデル",
+        "linearは基板のFaraday回転など磁場に比例する項を除去、odd_polynomialはorderまでの高次奇数べきを加える。",
+        &[],
+    ),
+    field(
+        "kerr.field_background.order",
+        "integer",
+        Conditional,
+        None,
+        &[],
+        None,
+        "Highest odd power of the field.",
+        "Required by odd_polynomial; 3 fits B and B^3.",
+        "磁場の最高奇数次",
+        "odd_polynomialで必須。3ならBとB^3をfit。",
+        &["must be odd and at most 7"],
+    ),
+    field(
+        "kerr.field_background.ranges",
+        "array of [low, high] float pairs",
+        Required,
+        None,
+        &[],
+        Some("kerr.sensor output unit"),
+        "Field ranges used for the fit.",
+        "Each range should lie where the magnetization is saturated; list both polarities for bipolar pulses so the offsets stay separate.",
+        "fitに使う磁場範囲",
+        "各範囲は磁化が飽和した領域を指定する。双極パルスでは両極性を列挙し、offsetを分けてfitする。",
+        &[
+            "must not be empty",
+            "each range must be finite with low < high and contain field samples",
+        ],
+    ),
     field(
         "plot",
         "table",
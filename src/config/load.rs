@@ -1,6 +1,9 @@
 use super::*;
 use crate::connection::{ConnectionDefaults, ConnectionUri};
 
+/// Oscilloscope drivers the acquisition pipeline can open.
+const SCOPE_MODELS: &[&str] = &["DHO5108", "InfiniiVision"];
+
 pub fn load_from_path(path: impl AsRef<Path>) -> ConfigLoad {
     let path = path.as_ref();
     let text = match fs::read_to_string(path) {
@@ -194,32 +197,24 @@ fn core_diagnostics(report: pmoke_config_core::ValidationReport) -> ConfigLoad {
 
 fn is_removed_lpf_input(path: Option<&str>, message: &str) -> bool {
     const REMOVED_LPF_FIELD_PATHS: &[&str] = &[
-        "lockin.filter.cutoff_hz",
-        "lockin.filter.cutoff_ref_ratio",
         "lockin.filter.stopband_atten_db",
         "lockin.filter.sync_average_cycles",
         "lockin.filter.iir_order",
     ];
     const REMOVED_LPF_KINDS: &[&str] =
         &["fir_boxcar_enbw", "fir_zero_phase", "sync_iir_zero_phase"];
-    const REMOVED_LPF_FIELDS: &[&str] = &[
-        "cutoff_hz",
-        "cutoff_ref_ratio",
-        "stopband_atten_db",
-        "sync_average_cycles",
-        "iir_order",
-    ];
+    const REMOVED_LPF_FIELDS: &[&str] = &["stopband_atten_db", "sync_average_cycles", "iir_order"];
 
     if path.is_some_and(|path| REMOVED_LPF_FIELD_PATHS.contains(&path)) {
         return true;
     }
     let filter_path =
         path.is_some_and(|path| path == "lockin.filter" || path.starts_with("lockin.filter."));
+    // Only the offending name counts; the "expected one of ..." tail lists
+    // current fields such as cutoff_hz.
+    let offending = message.split('`').nth(1).unwrap_or(message);
     filter_path
-        && (REMOVED_LPF_KINDS.iter().any(|kind| message.contains(kind))
-            || REMOVED_LPF_FIELDS
-                .iter()
-                .any(|field| message.contains(field)))
+        && (REMOVED_LPF_KINDS.contains(&offending) || REMOVED_LPF_FIELDS.contains(&offending))
 }
 
 fn deserialize_versioned<T>(s: &str) -> std::result::Result<T, ConfigDiagnostic>
@@ -433,8 +428,12 @@ fn normalize_v1(raw: ConfigV1) -> ConfigLoad {
         lockin: Lockin {
             workers: raw.lockin.workers,
             stride_samples: raw.lockin.stride_samples,
+            harmonics: default_lockin_harmonics(),
             lpf_kind,
             lpf_half_window_cycles,
+            lpf_cutoff: None,
+            lpf_order: pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+            lpf_zero_phase: true,
             lpf_debug_output: raw.lockin.lpf_debug_output,
             lpf_debug_label: raw.lockin.lpf_debug_label,
             lpf_debug_overwrite: raw.lockin.lpf_debug_overwrite,
@@ -444,6 +443,7 @@ fn normalize_v1(raw: ConfigV1) -> ConfigLoad {
         },
         phase: Phase {
             m_omega_t0_offset: raw.phase.m_omega_t0_offset,
+            drift: PhaseDrift::Constant,
         },
         kerr: raw.kerr.into(),
     };
@@ -514,8 +514,12 @@ fn normalize_v2(raw: ConfigV2) -> ConfigLoad {
         lockin: Lockin {
             workers: raw.lockin.workers,
             stride_samples: raw.lockin.stride_samples,
+            harmonics: default_lockin_harmonics(),
             lpf_kind,
             lpf_half_window_cycles: raw.lockin.lpf_half_window_cycles,
+            lpf_cutoff: None,
+            lpf_order: pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+            lpf_zero_phase: true,
             lpf_debug_output: raw.lockin.lpf_debug_output,
             lpf_debug_label: raw.lockin.lpf_debug_label,
             lpf_debug_overwrite: raw.lockin.lpf_debug_overwrite,
@@ -525,6 +529,7 @@ fn normalize_v2(raw: ConfigV2) -> ConfigLoad {
         },
         phase: Phase {
             m_omega_t0_offset: raw.phase.m_omega_t0_offset,
+            drift: PhaseDrift::Constant,
         },
         kerr: raw.kerr.into(),
     };
@@ -588,8 +593,12 @@ fn normalize_v3(raw: ConfigV3) -> ConfigLoad {
         lockin: Lockin {
             workers: raw.lockin.workers,
             stride_samples: raw.lockin.stride_samples,
+            harmonics: default_lockin_harmonics(),
             lpf_kind,
             lpf_half_window_cycles: raw.lockin.lpf_half_window_cycles,
+            lpf_cutoff: None,
+            lpf_order: pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+            lpf_zero_phase: true,
             lpf_debug_output: raw.lockin.lpf_debug_output,
             lpf_debug_label: raw.lockin.lpf_debug_label,
             lpf_debug_overwrite: raw.lockin.lpf_debug_overwrite,
@@ -599,6 +608,7 @@ fn normalize_v3(raw: ConfigV3) -> ConfigLoad {
         },
         phase: Phase {
             m_omega_t0_offset: raw.phase.m_omega_t0_offset,
+            drift: PhaseDrift::Constant,
         },
         kerr: raw.kerr.into(),
     };
@@ -659,12 +669,12 @@ fn normalize_v4(raw: ConfigV4) -> ConfigLoad {
             None,
         ));
     }
-    if raw.scope.model != "DHO5108" {
+    if !SCOPE_MODELS.contains(&raw.scope.model.as_str()) {
         errors.push(ConfigDiagnostic::new(
             DiagnosticKind::Validation,
             Some("scope.model".to_string()),
             format!("unsupported oscilloscope model: {}", raw.scope.model),
-            Some("use model = \"DHO5108\"".to_string()),
+            Some("use model = \"DHO5108\" or \"InfiniiVision\"".to_string()),
         ));
     }
     if let Some(connection) = &scope_connection {
@@ -674,20 +684,40 @@ fn normalize_v4(raw: ConfigV4) -> ConfigLoad {
             Connection::Usbtmc { .. } => {
                 errors.push(usbtmc_unsupported_diagnostic("scope.connection"))
             }
+            Connection::UsbtmcDevice { .. } if usbtmc_device_supported() => {}
+            Connection::UsbtmcDevice { .. } => {
+                errors.push(usbtmc_device_unsupported_diagnostic("scope.connection"))
+            }
             Connection::Gpib { .. } => errors.push(ConfigDiagnostic::new(
                 DiagnosticKind::Validation,
                 Some("scope.connection".to_string()),
-                "DHO5108 does not support a GPIB connection",
+                format!("{} does not support a GPIB connection", raw.scope.model),
+                Some("use tcp://host:port or visa:RESOURCE".to_string()),
+            )),
+            Connection::Vxi11 { .. } => errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some("scope.connection".to_string()),
+                format!("{} does not support a VXI-11 connection", raw.scope.model),
                 Some("use tcp://host:port or visa:RESOURCE".to_string()),
             )),
             Connection::PrologixTcp { .. } | Connection::PrologixSerial { .. } => {
                 errors.push(ConfigDiagnostic::new(
                     DiagnosticKind::Validation,
                     Some("scope.connection".to_string()),
-                    "DHO5108 does not support a Prologix GPIB connection",
+                    format!(
+                        "{} does not support a Prologix GPIB connection",
+                        raw.scope.model
+                    ),
                     Some("use tcp://host:port or visa:RESOURCE".to_string()),
                 ))
             }
+            Connection::Sim { .. } if raw.scope.model == "DHO5108" => {}
+            Connection::Sim { .. } => errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some("scope.connection".to_string()),
+                "sim:// only emulates the DHO5108",
+                Some("use model = \"DHO5108\" with sim://bench".to_string()),
+            )),
         }
     }
     if let Some(generator) = &raw.generator {
@@ -705,13 +735,14 @@ fn normalize_v4(raw: ConfigV4) -> ConfigLoad {
                 Connection::Gpib { .. }
                     | Connection::PrologixTcp { .. }
                     | Connection::PrologixSerial { .. }
+                    | Connection::Sim { .. }
             )
         {
             errors.push(ConfigDiagnostic::new(
                 DiagnosticKind::Validation,
                 Some("generator.connection".to_string()),
-                "WF1946B requires a GPIB or Prologix connection",
-                Some("use gpib://board/address, prologix-tcp://host:1234?addr=11, or prologix-serial:///dev/cu.usbserial?addr=11".to_string()),
+                "WF1946B requires a GPIB, Prologix, or sim:// connection",
+                Some("use gpib://board/address, prologix-tcp://host:1234?addr=11, prologix-serial:///dev/cu.usbserial?addr=11, or sim://bench".to_string()),
             ));
         }
     }
@@ -720,7 +751,7 @@ fn normalize_v4(raw: ConfigV4) -> ConfigLoad {
         &raw.sensors,
         &raw.reference,
         &raw.lockin.signal_channels,
-        &raw.kerr,
+        raw.kerr.factor,
         &raw.pulse,
         &mut errors,
     );
@@ -762,6 +793,7 @@ fn normalize_v4(raw: ConfigV4) -> ConfigLoad {
         index,
         factor: None,
         scale_to_abs_max: None,
+        coil: None,
         label: None,
         unit_out: None,
     }));
@@ -769,6 +801,7 @@ fn normalize_v4(raw: ConfigV4) -> ConfigLoad {
         index: raw.reference.channel,
         factor: None,
         scale_to_abs_max: None,
+        coil: None,
         label: None,
         unit_out: None,
     });
@@ -776,6 +809,7 @@ fn normalize_v4(raw: ConfigV4) -> ConfigLoad {
     let function_generator = raw.generator.map(|generator| FunctionGenerator {
         connection: generator_connection.expect("generator connection parsed above"),
         model: generator.model,
+        setup: None,
     });
     let mut cfg = Config {
         version: 4,
@@ -784,7 +818,11 @@ fn normalize_v4(raw: ConfigV4) -> ConfigLoad {
             oscilloscope: Oscilloscope {
                 connection: scope_connection,
                 model: raw.scope.model,
+                trigger_timeout_s: pmoke_config_core::DEFAULT_TRIGGER_TIMEOUT_S,
+                trigger_poll_interval_s: pmoke_config_core::DEFAULT_TRIGGER_POLL_INTERVAL_S,
+                setup: None,
             },
+            multimeters: Vec::new(),
         }),
         fetch: Fetch {
             output: match raw.data.output {
@@ -814,16 +852,22 @@ fn normalize_v4(raw: ConfigV4) -> ConfigLoad {
         pulse: Pulse {
             bg_window_before: raw.pulse.background_before,
             bg_window_after: raw.pulse.background_after,
+            baseline: raw.pulse.baseline,
         },
         reference: raw.reference.into(),
         lockin,
         phase: Phase {
             m_omega_t0_offset: raw.phase.offsets,
+            drift: raw.phase.drift,
         },
         kerr: Kerr {
             use_sensor_ch: raw.kerr.sensor,
             kerr_type: raw.kerr.method,
             factor: raw.kerr.factor,
+            modulation_depth: KerrModulationDepth::default(),
+            field_bin_width: None,
+            background_run: None,
+            field_background: None,
         },
     };
 
@@ -883,12 +927,12 @@ fn normalize_v5(raw: ConfigV5) -> ConfigLoad {
             None,
         ));
     }
-    if raw.scope.model != "DHO5108" {
+    if !SCOPE_MODELS.contains(&raw.scope.model.as_str()) {
         errors.push(ConfigDiagnostic::new(
             DiagnosticKind::Validation,
             Some("scope.model".to_string()),
             format!("unsupported oscilloscope model: {}", raw.scope.model),
-            Some("use model = \"DHO5108\"".to_string()),
+            Some("use model = \"DHO5108\" or \"InfiniiVision\"".to_string()),
         ));
     }
     if let Some(connection) = &scope_connection {
@@ -898,20 +942,40 @@ fn normalize_v5(raw: ConfigV5) -> ConfigLoad {
             Connection::Usbtmc { .. } => {
                 errors.push(usbtmc_unsupported_diagnostic("scope.connection"))
             }
+            Connection::UsbtmcDevice { .. } if usbtmc_device_supported() => {}
+            Connection::UsbtmcDevice { .. } => {
+                errors.push(usbtmc_device_unsupported_diagnostic("scope.connection"))
+            }
             Connection::Gpib { .. } => errors.push(ConfigDiagnostic::new(
                 DiagnosticKind::Validation,
                 Some("scope.connection".to_string()),
-                "DHO5108 does not support a GPIB connection",
+                format!("{} does not support a GPIB connection", raw.scope.model),
+                Some("use tcp://host:port or visa:RESOURCE".to_string()),
+            )),
+            Connection::Vxi11 { .. } => errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some("scope.connection".to_string()),
+                format!("{} does not support a VXI-11 connection", raw.scope.model),
                 Some("use tcp://host:port or visa:RESOURCE".to_string()),
             )),
             Connection::PrologixTcp { .. } | Connection::PrologixSerial { .. } => {
                 errors.push(ConfigDiagnostic::new(
                     DiagnosticKind::Validation,
                     Some("scope.connection".to_string()),
-                    "DHO5108 does not support a Prologix GPIB connection",
+                    format!(
+                        "{} does not support a Prologix GPIB connection",
+                        raw.scope.model
+                    ),
                     Some("use tcp://host:port or visa:RESOURCE".to_string()),
                 ));
             }
+            Connection::Sim { .. } if raw.scope.model == "DHO5108" => {}
+            Connection::Sim { .. } => errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some("scope.connection".to_string()),
+                "sim:// only emulates the DHO5108",
+                Some("use model = \"DHO5108\" with sim://bench".to_string()),
+            )),
         }
     }
     if let Some(generator) = &raw.generator {
@@ -929,27 +993,65 @@ fn normalize_v5(raw: ConfigV5) -> ConfigLoad {
                 Connection::Gpib { .. }
                     | Connection::PrologixTcp { .. }
                     | Connection::PrologixSerial { .. }
+                    | Connection::Sim { .. }
             )
         {
             errors.push(ConfigDiagnostic::new(
                 DiagnosticKind::Validation,
                 Some("generator.connection".to_string()),
-                "WF1946B requires a GPIB or Prologix connection",
-                Some("use gpib://board/address, prologix-tcp://host:1234?addr=11, or prologix-serial:///dev/cu.usbserial?addr=11".to_string()),
+                "WF1946B requires a GPIB, Prologix, or sim:// connection",
+                Some("use gpib://board/address, prologix-tcp://host:1234?addr=11, prologix-serial:///dev/cu.usbserial?addr=11, or sim://bench".to_string()),
             ));
         }
     }
 
+    let mut multimeters = Vec::with_capacity(raw.multimeters.len());
+    for (index, multimeter) in raw.multimeters.iter().enumerate() {
+        if !matches!(multimeter.model.as_str(), "Keithley2000" | "Keithley2010") {
+            errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some(format!("multimeters[{index}].model")),
+                format!("unsupported multimeter model: {}", multimeter.model),
+                Some("use model = \"Keithley2000\" or \"Keithley2010\"".to_string()),
+            ));
+        }
+        let path = format!("multimeters[{index}].connection");
+        match parse_connection_v4(&multimeter.connection, &path) {
+            Ok(
+                connection @ (Connection::Gpib { .. }
+                | Connection::PrologixTcp { .. }
+                | Connection::PrologixSerial { .. }),
+            ) => multimeters.push(Multimeter {
+                label: multimeter.label.clone(),
+                connection,
+                model: multimeter.model.clone(),
+                function: multimeter.function,
+                range: multimeter.range,
+                thermocouple: multimeter.thermocouple,
+            }),
+            Ok(_) => errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some(path),
+                format!("{} requires a GPIB or Prologix connection", multimeter.model),
+                Some("use gpib://board/address, prologix-tcp://host:1234?addr=16, or prologix-serial:///dev/cu.usbserial?addr=16".to_string()),
+            )),
+            Err(error) => errors.push(error),
+        }
+    }
+
     validate_current_fields(
         &raw.sensors,
         &raw.reference,
         &raw.lockin.signal_channels,
-        &raw.kerr,
+        raw.kerr.factor,
         &raw.pulse,
         &mut errors,
     );
 
-    if scope_connection.is_none() || (raw.generator.is_some() && generator_connection.is_none()) {
+    if scope_connection.is_none()
+        || (raw.generator.is_some() && generator_connection.is_none())
+        || multimeters.len() != raw.multimeters.len()
+    {
         return ConfigLoad::Diagnostics(ConfigDiagnostics {
             version: Some(5),
             warnings: Vec::new(),
@@ -974,6 +1076,7 @@ fn normalize_v5(raw: ConfigV5) -> ConfigLoad {
         index,
         factor: None,
         scale_to_abs_max: None,
+        coil: None,
         label: None,
         unit_out: None,
     }));
@@ -981,6 +1084,7 @@ fn normalize_v5(raw: ConfigV5) -> ConfigLoad {
         index: raw.reference.channel,
         factor: None,
         scale_to_abs_max: None,
+        coil: None,
         label: None,
         unit_out: None,
     });
@@ -988,6 +1092,7 @@ fn normalize_v5(raw: ConfigV5) -> ConfigLoad {
     let function_generator = raw.generator.map(|generator| FunctionGenerator {
         connection: generator_connection.expect("generator connection parsed above"),
         model: generator.model,
+        setup: generator.setup,
     });
     let mut cfg = Config {
         version: 5,
@@ -996,7 +1101,11 @@ fn normalize_v5(raw: ConfigV5) -> ConfigLoad {
             oscilloscope: Oscilloscope {
                 connection: scope_connection,
                 model: raw.scope.model,
+                trigger_timeout_s: raw.scope.trigger_timeout_s,
+                trigger_poll_interval_s: raw.scope.trigger_poll_interval_s,
+                setup: raw.scope.setup,
             },
+            multimeters,
         }),
         fetch: Fetch {
             output: match raw.data.output {
@@ -1026,16 +1135,22 @@ fn normalize_v5(raw: ConfigV5) -> ConfigLoad {
         pulse: Pulse {
             bg_window_before: raw.pulse.background_before,
             bg_window_after: raw.pulse.background_after,
+            baseline: raw.pulse.baseline,
         },
         reference: raw.reference.into(),
         lockin: raw.lockin.into(),
         phase: Phase {
             m_omega_t0_offset: raw.phase.offsets,
+            drift: raw.phase.drift,
         },
         kerr: Kerr {
             use_sensor_ch: raw.kerr.sensor,
             kerr_type: raw.kerr.method,
             factor: raw.kerr.factor,
+            modulation_depth: raw.kerr.modulation_depth,
+            field_bin_width: raw.kerr.field_bin_width,
+            background_run: raw.kerr.background_run,
+            field_background: raw.kerr.field_background,
         },
     };
 
@@ -1062,14 +1177,18 @@ fn normalize_v5(raw: ConfigV5) -> ConfigLoad {
 }
 
 fn channel_from_sensor_v4(sensor: &SensorV4) -> Channel {
-    let (factor, scale_to_abs_max) = match sensor.scale {
-        SensorScaleV4::Factor(ref scale) => (Some(scale.factor), None),
-        SensorScaleV4::MaxAbs(ref scale) => (None, Some(scale.max_abs * f64::from(scale.polarity))),
+    let (factor, scale_to_abs_max, coil) = match sensor.scale {
+        SensorScaleV4::Factor(ref scale) => (Some(scale.factor), None, None),
+        SensorScaleV4::MaxAbs(ref scale) => {
+            (None, Some(scale.max_abs * f64::from(scale.polarity)), None)
+        }
+        SensorScaleV4::Coil(coil) => (None, None, Some(coil)),
     };
     Channel {
         index: sensor.channel,
         factor,
         scale_to_abs_max,
+        coil,
         label: Some(sensor.label.clone()),
         unit_out: Some(sensor.unit.clone()),
     }
@@ -1111,6 +1230,8 @@ fn v4_config_terms(value: &str) -> String {
         ),
         ("lockin.lpf_cutoff_hz", "lockin.filter.cutoff_hz"),
         ("lockin.lpf_iir_order", "lockin.filter.iir_order"),
+        ("lockin.lpf_order", "lockin.filter.order"),
+        ("lockin.lpf_enbw_hz", "lockin.filter.enbw_hz"),
         ("lockin.lpf_debug_label", "lockin.debug_label"),
         ("lockin.lpf_kind", "lockin.filter.kind"),
         ("phase.m_omega_t0_offset", "phase.offsets"),
@@ -1129,7 +1250,7 @@ fn validate_current_fields(
     sensors: &[SensorV4],
     reference: &ReferenceV4,
     signal_channels: &[u8],
-    kerr: &KerrV4,
+    kerr_factor: f64,
     pulse: &PulseV4,
     errors: &mut Vec<ConfigDiagnostic>,
 ) {
@@ -1149,6 +1270,16 @@ fn validate_current_fields(
     };
     for (index, sensor) in sensors.iter().enumerate() {
         assign(sensor.channel, format!("sensors[{index}].channel"));
+        if let SensorScaleV4::Coil(CoilCalibration {
+            compensation: Some(compensation),
+            ..
+        }) = sensor.scale
+        {
+            assign(
+                compensation.channel,
+                format!("sensors[{index}].scale.compensation.channel"),
+            );
+        }
     }
     assign(reference.channel, "reference.channel".to_string());
     for (index, &channel) in signal_channels.iter().enumerate() {
@@ -1230,6 +1361,53 @@ fn validate_current_fields(
                     ));
                 }
             }
+            SensorScaleV4::Coil(ref coil) => {
+                if !coil.area_turns.is_finite() || coil.area_turns <= 0.0 {
+                    errors.push(ConfigDiagnostic::new(
+                        DiagnosticKind::Validation,
+                        Some(format!("sensors[{index}].scale.area_turns")),
+                        "coil area_turns must be finite and positive",
+                        None,
+                    ));
+                }
+                if !coil.gain.is_finite() || coil.gain <= 0.0 {
+                    errors.push(ConfigDiagnostic::new(
+                        DiagnosticKind::Validation,
+                        Some(format!("sensors[{index}].scale.gain")),
+                        "coil gain must be finite and positive",
+                        None,
+                    ));
+                }
+                if !matches!(coil.polarity, -1 | 1) {
+                    errors.push(ConfigDiagnostic::new(
+                        DiagnosticKind::Validation,
+                        Some(format!("sensors[{index}].scale.polarity")),
+                        "coil polarity must be -1 or 1",
+                        None,
+                    ));
+                }
+                if let Some(compensation) = coil.compensation {
+                    if !channel_in_range(compensation.channel) {
+                        errors.push(ConfigDiagnostic::new(
+                            DiagnosticKind::Validation,
+                            Some(format!("sensors[{index}].scale.compensation.channel")),
+                            format!(
+                                "DHO5108 channel must be in 1..=8 (got {})",
+                                compensation.channel
+                            ),
+                            None,
+                        ));
+                    }
+                    if !compensation.balance.is_finite() {
+                        errors.push(ConfigDiagnostic::new(
+                            DiagnosticKind::Validation,
+                            Some(format!("sensors[{index}].scale.compensation.balance")),
+                            "coil compensation balance must be finite",
+                            None,
+                        ));
+                    }
+                }
+            }
             SensorScaleV4::Factor(_) => {}
         }
     }
@@ -1249,7 +1427,7 @@ fn validate_current_fields(
             None,
         ));
     }
-    if !kerr.factor.is_finite() {
+    if !kerr_factor.is_finite() {
         errors.push(ConfigDiagnostic::new(
             DiagnosticKind::Validation,
             Some("kerr.factor".to_string()),
@@ -1278,13 +1456,15 @@ fn parse_connection_v4(
             DiagnosticKind::Validation,
             Some(path.to_string()),
             message,
-            Some("use tcp://host:port, visa:RESOURCE, gpib://board/address, prologix-tcp://host[:port]?addr=address, or prologix-serial:///path?addr=address".to_string()),
+            Some("use tcp://host:port, vxi11://host/inst0, visa:RESOURCE, usbtmc:///dev/usbtmcN, gpib://board/address, prologix-tcp://host[:port]?addr=address, prologix-serial:///path?addr=address, or sim://bench".to_string()),
         )
     };
     let parsed = ConnectionUri::parse(value, ConnectionDefaults::default()).map_err(invalid)?;
     Ok(match parsed {
         ConnectionUri::Tcp { host, port } => Connection::Tcpip { ip: host, port },
+        ConnectionUri::Vxi11 { host, device } => Connection::Vxi11 { host, device },
         ConnectionUri::Visa { resource } => Connection::Usbtmc { resource },
+        ConnectionUri::UsbtmcDevice { path } => Connection::UsbtmcDevice { path },
         ConnectionUri::Gpib { board, address } => Connection::Gpib { board, address },
         ConnectionUri::PrologixTcp {
             host,
@@ -1308,5 +1488,6 @@ fn parse_connection_v4(
             baud_rate,
             read_timeout_ms,
         },
+        ConnectionUri::Sim { bench } => Connection::Sim { bench },
     })
 }
@@ -560,6 +560,7 @@ fn inspect_channel_losses(config: &Config, target_version: u32, issues: &mut Vec
             !config.roles.sensor_ch.contains(&channel.index)
                 && (channel.factor.is_some()
                     || channel.scale_to_abs_max.is_some()
+                    || channel.coil.is_some()
                     || channel.label.is_some()
                     || channel.unit_out.is_some())
         })
@@ -649,6 +650,7 @@ fn canonicalize_for_current_schema(config: &mut Config, target_version: u32) {
         if !config.roles.sensor_ch.contains(&channel.index) {
             channel.factor = None;
             channel.scale_to_abs_max = None;
+            channel.coil = None;
             channel.label = None;
             channel.unit_out = None;
         }
@@ -124,6 +124,24 @@ impl ArtifactPaths {
             .join(format!("ch{channel}_rotated.npy"))
     }
 
+    pub fn lockin_noise_csv(&self, channel: u8) -> PathBuf {
+        self.analysis_dir()
+            .join("lockin")
+            .join(format!("ch{channel}_noise.csv"))
+    }
+
+    pub fn lockin_rotated_noise_csv(&self, channel: u8) -> PathBuf {
+        self.analysis_dir()
+            .join("lockin")
+            .join(format!("ch{channel}_rotated_noise.csv"))
+    }
+
+    pub fn phase_drift_csv(&self, channel: u8) -> PathBuf {
+        self.analysis_dir()
+            .join("lockin")
+            .join(format!("ch{channel}_phase_drift.csv"))
+    }
+
     pub fn kerr_csv(&self) -> PathBuf {
         self.analysis_dir().join("kerr").join("kerr.csv")
     }
@@ -132,6 +150,12 @@ impl ArtifactPaths {
         self.analysis_dir().join("kerr").join("kerr.npy")
     }
 
+    pub fn kerr_vs_field_csv(&self, branch: &str) -> PathBuf {
+        self.analysis_dir()
+            .join("kerr")
+            .join(format!("kerr_vs_field_{branch}.csv"))
+    }
+
     pub fn plot_dir(&self) -> PathBuf {
         self.analysis_dir().join("plots")
     }
@@ -213,6 +237,18 @@ impl ArtifactPaths {
     pub fn debug_dir(&self) -> PathBuf {
         self.analysis_dir().join("debug")
     }
+
+    pub fn stack_dir(&self) -> PathBuf {
+        self.run_dir.join("stack")
+    }
+
+    pub fn stack_manifest(&self) -> PathBuf {
+        self.stack_dir().join("manifest.toml")
+    }
+
+    pub fn stack_resolved_config(&self) -> PathBuf {
+        self.stack_dir().join("config.resolved.toml")
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -80,6 +80,7 @@ fn normalized_config_v4(config: &Config) -> Result<NormalizedConfigV4> {
         pulse: PulseOutputV4 {
             background_before: config.pulse.bg_window_before,
             background_after: config.pulse.bg_window_after,
+            baseline: config.pulse.baseline,
         },
         reference: ReferenceOutputV4 {
             channel: config.roles.reference_ch,
@@ -87,9 +88,10 @@ fn normalized_config_v4(config: &Config) -> Result<NormalizedConfigV4> {
             stride_samples: config.reference.stride_samples,
             window_samples: config.reference.window_samples,
         },
-        lockin: lockin_output_v4(&config.lockin, &config.roles.signal_ch),
+        lockin: lockin_output_v4(&config.lockin, &config.roles.signal_ch)?,
         phase: PhaseOutputV4 {
             offsets: config.phase.m_omega_t0_offset.clone(),
+            drift: config.phase.drift,
         },
         kerr: KerrOutputV4 {
             sensor: config.kerr.use_sensor_ch,
@@ -105,17 +107,33 @@ fn normalized_config_v5(config: &Config) -> Result<NormalizedConfigV5> {
         .instruments
         .as_ref()
         .ok_or_else(|| anyhow!("version 5 normalized config has no oscilloscope"))?;
-    let scope = ScopeOutputV4 {
+    let scope = ScopeOutputV5 {
         model: instruments.oscilloscope.model.clone(),
         connection: connection_uri(&instruments.oscilloscope.connection),
+        trigger_timeout_s: instruments.oscilloscope.trigger_timeout_s,
+        trigger_poll_interval_s: instruments.oscilloscope.trigger_poll_interval_s,
+        setup: instruments.oscilloscope.setup.clone(),
     };
     let generator = instruments
         .function_generator
         .as_ref()
-        .map(|generator| GeneratorOutputV4 {
+        .map(|generator| GeneratorOutputV5 {
             model: generator.model.clone(),
             connection: connection_uri(&generator.connection),
+            setup: generator.setup.clone(),
         });
+    let multimeters = instruments
+        .multimeters
+        .iter()
+        .map(|multimeter| MultimeterOutputV5 {
+            label: multimeter.label.clone(),
+            model: multimeter.model.clone(),
+            connection: connection_uri(&multimeter.connection),
+            function: multimeter.function,
+            range: multimeter.range,
+            thermocouple: multimeter.thermocouple,
+        })
+        .collect();
     let sensors = config
         .roles
         .sensor_ch
@@ -127,6 +145,7 @@ fn normalized_config_v5(config: &Config) -> Result<NormalizedConfigV5> {
         version: 5,
         scope,
         generator,
+        multimeters,
         data: DataOutputConfigV4 {
             output: match config.fetch.output {
                 FetchOutput::Csv => DataOutputV4::Csv,
@@ -140,6 +159,7 @@ fn normalized_config_v5(config: &Config) -> Result<NormalizedConfigV5> {
         pulse: PulseOutputV4 {
             background_before: config.pulse.bg_window_before,
             background_after: config.pulse.bg_window_after,
+            baseline: config.pulse.baseline,
         },
         reference: ReferenceOutputV4 {
             channel: config.roles.reference_ch,
@@ -150,11 +170,16 @@ fn normalized_config_v5(config: &Config) -> Result<NormalizedConfigV5> {
         lockin: lockin_output_v5(&config.lockin, &config.roles.signal_ch),
         phase: PhaseOutputV4 {
             offsets: config.phase.m_omega_t0_offset.clone(),
+            drift: config.phase.drift,
         },
-        kerr: KerrOutputV4 {
+        kerr: KerrOutputV5 {
             sensor: config.kerr.use_sensor_ch,
             method: config.kerr.kerr_type,
             factor: config.kerr.factor,
+            modulation_depth: config.kerr.modulation_depth,
+            field_bin_width: config.kerr.field_bin_width,
+            background_run: config.kerr.background_run.clone(),
+            field_background: config.kerr.field_background.clone(),
         },
         plot: plot_output_v4(&config.plot),
     })
@@ -166,12 +191,13 @@ fn sensor_output_v4(config: &Config, index: u8) -> Result<SensorOutputV4> {
         .iter()
         .find(|channel| channel.index == index)
         .ok_or_else(|| anyhow!("version 4 sensor channel {index} is not defined"))?;
-    let scale = match (channel.factor, channel.scale_to_abs_max) {
-        (Some(factor), None) => SensorScaleOutputV4::Factor { factor },
-        (None, Some(target)) => SensorScaleOutputV4::MaxAbs {
+    let scale = match (channel.factor, channel.scale_to_abs_max, channel.coil) {
+        (Some(factor), None, None) => SensorScaleOutputV4::Factor { factor },
+        (None, Some(target), None) => SensorScaleOutputV4::MaxAbs {
             max_abs: target.abs(),
             polarity: if target.is_sign_negative() { -1 } else { 1 },
         },
+        (None, None, Some(coil)) => SensorScaleOutputV4::Coil(coil),
         _ => bail!("version 4 sensor channel {index} has an invalid scale"),
     };
     Ok(SensorOutputV4 {
@@ -194,9 +220,14 @@ pub(crate) fn connection_uri(connection: &Connection) -> String {
             host: ip.clone(),
             port: *port,
         },
+        Connection::Vxi11 { host, device } => ConnectionUri::Vxi11 {
+            host: host.clone(),
+            device: device.clone(),
+        },
         Connection::Usbtmc { resource } => ConnectionUri::Visa {
             resource: resource.clone(),
         },
+        Connection::UsbtmcDevice { path } => ConnectionUri::UsbtmcDevice { path: path.clone() },
         Connection::Gpib { board, address } => ConnectionUri::Gpib {
             board: *board,
             address: *address,
@@ -223,17 +254,24 @@ pub(crate) fn connection_uri(connection: &Connection) -> String {
             baud_rate: *baud_rate,
             read_timeout_ms: *read_timeout_ms,
         },
+        Connection::Sim { bench } => ConnectionUri::Sim {
+            bench: bench.clone(),
+        },
     };
     uri.to_string()
 }
 
-fn lockin_output_v4(lockin: &Lockin, signal_channels: &[u8]) -> LockinOutputV4 {
+fn lockin_output_v4(lockin: &Lockin, signal_channels: &[u8]) -> Result<LockinOutputV4> {
     let filter = match lockin.lpf_kind {
         LockinLpfKind::BoxcarLegacy => LockinFilterOutputV4::BoxcarLegacy {
             half_window_cycles: lockin.lpf_half_window_cycles,
         },
+        kind => bail!(
+            "lockin.filter.kind = \"{}\" cannot be rendered as a version 4 config",
+            kind.as_str()
+        ),
     };
-    LockinOutputV4 {
+    Ok(LockinOutputV4 {
         signal_channels: signal_channels.to_vec(),
         workers: lockin.workers,
         stride_samples: lockin.stride_samples,
@@ -244,19 +282,41 @@ fn lockin_output_v4(lockin: &Lockin, signal_channels: &[u8]) -> LockinOutputV4 {
         snr_background_window: lockin.snr_background_window,
         snr_signal_window: lockin.snr_signal_window,
         save_npy: lockin.save_npy,
-    }
+    })
 }
 
 fn lockin_output_v5(lockin: &Lockin, signal_channels: &[u8]) -> LockinOutputV5 {
+    let (cutoff_hz, cutoff_ref_ratio, enbw_hz) = LockinCutoff::fields(lockin.lpf_cutoff);
     let filter = match lockin.lpf_kind {
         LockinLpfKind::BoxcarLegacy => LockinFilterOutputV5::BoxcarLegacy {
             half_window_cycles: lockin.lpf_half_window_cycles,
         },
+        LockinLpfKind::RcCascade => LockinFilterOutputV5::RcCascade {
+            order: lockin.lpf_order,
+            cutoff_hz,
+            cutoff_ref_ratio,
+            enbw_hz,
+            zero_phase: lockin.lpf_zero_phase,
+        },
+        LockinLpfKind::WindowedSinc => LockinFilterOutputV5::WindowedSinc {
+            half_window_cycles: lockin.lpf_half_window_cycles,
+            cutoff_hz,
+            cutoff_ref_ratio,
+            enbw_hz,
+            zero_phase: lockin.lpf_zero_phase,
+        },
+        LockinLpfKind::Gaussian => LockinFilterOutputV5::Gaussian {
+            cutoff_hz,
+            cutoff_ref_ratio,
+            enbw_hz,
+            zero_phase: lockin.lpf_zero_phase,
+        },
     };
     LockinOutputV5 {
         signal_channels: signal_channels.to_vec(),
         workers: lockin.workers,
         stride_samples: lockin.stride_samples,
+        harmonics: lockin.harmonics.clone(),
         filter,
         debug_output: lockin.lpf_debug_output,
         debug_label: lockin.lpf_debug_label.clone(),
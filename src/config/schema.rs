@@ -82,9 +82,11 @@ pub(super) struct ConfigV4 {
 #[serde(deny_unknown_fields)]
 pub(super) struct ConfigV5 {
     pub(super) version: u32,
-    pub(super) scope: ScopeV4,
+    pub(super) scope: ScopeV5,
     #[serde(default)]
-    pub(super) generator: Option<GeneratorV4>,
+    pub(super) generator: Option<GeneratorV5>,
+    #[serde(default)]
+    pub(super) multimeters: Vec<MultimeterV5>,
     pub(super) data: DataV4,
     #[serde(default)]
     pub(super) sensors: Vec<SensorV4>,
@@ -92,7 +94,7 @@ pub(super) struct ConfigV5 {
     pub(super) reference: ReferenceV4,
     pub(super) lockin: LockinV5,
     pub(super) phase: PhaseV4,
-    pub(super) kerr: KerrV4,
+    pub(super) kerr: KerrV5,
     #[serde(default)]
     pub(super) plot: PlotV4,
 }
@@ -104,6 +106,27 @@ pub(super) struct ScopeV4 {
     pub(super) connection: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ScopeV5 {
+    pub(super) model: String,
+    pub(super) connection: String,
+    #[serde(default = "default_trigger_timeout_s")]
+    pub(super) trigger_timeout_s: f64,
+    #[serde(default = "default_trigger_poll_interval_s")]
+    pub(super) trigger_poll_interval_s: f64,
+    #[serde(default)]
+    pub(super) setup: Option<OscilloscopeSetup>,
+}
+
+fn default_trigger_timeout_s() -> f64 {
+    pmoke_config_core::DEFAULT_TRIGGER_TIMEOUT_S
+}
+
+fn default_trigger_poll_interval_s() -> f64 {
+    pmoke_config_core::DEFAULT_TRIGGER_POLL_INTERVAL_S
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub(super) struct GeneratorV4 {
@@ -111,6 +134,28 @@ pub(super) struct GeneratorV4 {
     pub(super) connection: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct GeneratorV5 {
+    pub(super) model: String,
+    pub(super) connection: String,
+    #[serde(default)]
+    pub(super) setup: Option<FunctionGeneratorSetup>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct MultimeterV5 {
+    pub(super) label: String,
+    pub(super) model: String,
+    pub(super) connection: String,
+    pub(super) function: MultimeterFunction,
+    #[serde(default)]
+    pub(super) range: Option<f64>,
+    #[serde(default)]
+    pub(super) thermocouple: Option<Thermocouple>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub(super) struct DataV4 {
@@ -142,6 +187,7 @@ pub(super) struct SensorV4 {
 pub(super) enum SensorScaleV4 {
     Factor(SensorFactorScaleV4),
     MaxAbs(SensorMaxAbsScaleV4),
+    Coil(CoilCalibration),
 }
 
 #[derive(Debug, Deserialize)]
@@ -162,6 +208,8 @@ pub(super) struct SensorMaxAbsScaleV4 {
 pub(super) struct PulseV4 {
     pub(super) background_before: Window,
     pub(super) background_after: Window,
+    #[serde(default)]
+    pub(super) baseline: PulseBaseline,
 }
 
 #[derive(Debug, Deserialize)]
@@ -200,6 +248,8 @@ pub(super) struct LockinV5 {
     pub(super) signal_channels: Vec<u8>,
     pub(super) workers: usize,
     pub(super) stride_samples: usize,
+    #[serde(default = "default_lockin_harmonics")]
+    pub(super) harmonics: Vec<usize>,
     pub(super) filter: LockinFilterV5,
     #[serde(default)]
     pub(super) debug_output: bool,
@@ -250,7 +300,42 @@ pub(super) enum LockinFilterV4 {
 #[derive(Debug, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
 pub(super) enum LockinFilterV5 {
-    BoxcarLegacy { half_window_cycles: f64 },
+    BoxcarLegacy {
+        half_window_cycles: f64,
+    },
+    RcCascade {
+        #[serde(default = "default_lockin_rc_order")]
+        order: usize,
+        #[serde(default)]
+        cutoff_hz: Option<f64>,
+        #[serde(default)]
+        cutoff_ref_ratio: Option<f64>,
+        #[serde(default)]
+        enbw_hz: Option<f64>,
+        #[serde(default = "default_true")]
+        zero_phase: bool,
+    },
+    WindowedSinc {
+        half_window_cycles: f64,
+        #[serde(default)]
+        cutoff_hz: Option<f64>,
+        #[serde(default)]
+        cutoff_ref_ratio: Option<f64>,
+        #[serde(default)]
+        enbw_hz: Option<f64>,
+        #[serde(default = "default_true")]
+        zero_phase: bool,
+    },
+    Gaussian {
+        #[serde(default)]
+        cutoff_hz: Option<f64>,
+        #[serde(default)]
+        cutoff_ref_ratio: Option<f64>,
+        #[serde(default)]
+        enbw_hz: Option<f64>,
+        #[serde(default = "default_true")]
+        zero_phase: bool,
+    },
 }
 
 #[derive(Debug, Clone, Copy, Deserialize)]
@@ -278,6 +363,8 @@ impl LegacyLockinLpfKind {
 pub(super) struct PhaseV4 {
     #[serde(deserialize_with = "de_vec_f64_or_expr")]
     pub(super) offsets: Vec<f64>,
+    #[serde(default)]
+    pub(super) drift: PhaseDrift,
 }
 
 #[derive(Debug, Deserialize)]
@@ -288,6 +375,22 @@ pub(super) struct KerrV4 {
     pub(super) factor: f64,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct KerrV5 {
+    pub(super) sensor: u8,
+    pub(super) method: KerrType,
+    pub(super) factor: f64,
+    #[serde(default)]
+    pub(super) modulation_depth: KerrModulationDepth,
+    #[serde(default)]
+    pub(super) field_bin_width: Option<f64>,
+    #[serde(default)]
+    pub(super) background_run: Option<String>,
+    #[serde(default)]
+    pub(super) field_background: Option<KerrFieldBackground>,
+}
+
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub(super) enum PlotModeV4 {
@@ -348,16 +451,18 @@ pub(super) struct NormalizedConfigV4 {
 #[derive(Serialize)]
 pub(super) struct NormalizedConfigV5 {
     pub(super) version: u32,
-    pub(super) scope: ScopeOutputV4,
+    pub(super) scope: ScopeOutputV5,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub(super) generator: Option<GeneratorOutputV4>,
+    pub(super) generator: Option<GeneratorOutputV5>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) multimeters: Vec<MultimeterOutputV5>,
     pub(super) data: DataOutputConfigV4,
     pub(super) sensors: Vec<SensorOutputV4>,
     pub(super) pulse: PulseOutputV4,
     pub(super) reference: ReferenceOutputV4,
     pub(super) lockin: LockinOutputV5,
     pub(super) phase: PhaseOutputV4,
-    pub(super) kerr: KerrOutputV4,
+    pub(super) kerr: KerrOutputV5,
     pub(super) plot: PlotOutputV4,
 }
 
@@ -367,12 +472,42 @@ pub(super) struct ScopeOutputV4 {
     pub(super) connection: String,
 }
 
+#[derive(Serialize)]
+pub(super) struct ScopeOutputV5 {
+    pub(super) model: String,
+    pub(super) connection: String,
+    pub(super) trigger_timeout_s: f64,
+    pub(super) trigger_poll_interval_s: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) setup: Option<OscilloscopeSetup>,
+}
+
 #[derive(Serialize)]
 pub(super) struct GeneratorOutputV4 {
     pub(super) model: String,
     pub(super) connection: String,
 }
 
+#[derive(Serialize)]
+pub(super) struct GeneratorOutputV5 {
+    pub(super) model: String,
+    pub(super) connection: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) setup: Option<FunctionGeneratorSetup>,
+}
+
+#[derive(Serialize)]
+pub(super) struct MultimeterOutputV5 {
+    pub(super) label: String,
+    pub(super) model: String,
+    pub(super) connection: String,
+    pub(super) function: MultimeterFunction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) range: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) thermocouple: Option<Thermocouple>,
+}
+
 #[derive(Serialize)]
 pub(super) struct DataOutputConfigV4 {
     pub(super) output: DataOutputV4,
@@ -393,12 +528,15 @@ pub(super) struct SensorOutputV4 {
 pub(super) enum SensorScaleOutputV4 {
     Factor { factor: f64 },
     MaxAbs { max_abs: f64, polarity: i8 },
+    Coil(CoilCalibration),
 }
 
 #[derive(Serialize)]
 pub(super) struct PulseOutputV4 {
     pub(super) background_before: Window,
     pub(super) background_after: Window,
+    #[serde(skip_serializing_if = "PulseBaseline::is_constant")]
+    pub(super) baseline: PulseBaseline,
 }
 
 #[derive(Serialize)]
@@ -434,6 +572,7 @@ pub(super) struct LockinOutputV5 {
     pub(super) signal_channels: Vec<u8>,
     pub(super) workers: usize,
     pub(super) stride_samples: usize,
+    pub(super) harmonics: Vec<usize>,
     pub(super) filter: LockinFilterOutputV5,
     #[serde(skip_serializing_if = "is_false")]
     pub(super) debug_output: bool,
@@ -458,12 +597,45 @@ pub(super) enum LockinFilterOutputV4 {
 #[derive(Serialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub(super) enum LockinFilterOutputV5 {
-    BoxcarLegacy { half_window_cycles: f64 },
+    BoxcarLegacy {
+        half_window_cycles: f64,
+    },
+    RcCascade {
+        order: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cutoff_hz: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cutoff_ref_ratio: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        enbw_hz: Option<f64>,
+        zero_phase: bool,
+    },
+    WindowedSinc {
+        half_window_cycles: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cutoff_hz: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cutoff_ref_ratio: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        enbw_hz: Option<f64>,
+        zero_phase: bool,
+    },
+    Gaussian {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cutoff_hz: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cutoff_ref_ratio: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        enbw_hz: Option<f64>,
+        zero_phase: bool,
+    },
 }
 
 #[derive(Serialize)]
 pub(super) struct PhaseOutputV4 {
     pub(super) offsets: Vec<f64>,
+    #[serde(skip_serializing_if = "PhaseDrift::is_constant")]
+    pub(super) drift: PhaseDrift,
 }
 
 #[derive(Serialize)]
@@ -473,6 +645,20 @@ pub(super) struct KerrOutputV4 {
     pub(super) factor: f64,
 }
 
+#[derive(Serialize)]
+pub(super) struct KerrOutputV5 {
+    pub(super) sensor: u8,
+    pub(super) method: KerrType,
+    pub(super) factor: f64,
+    pub(super) modulation_depth: KerrModulationDepth,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) field_bin_width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) background_run: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) field_background: Option<KerrFieldBackground>,
+}
+
 #[derive(Serialize)]
 pub(super) struct PlotOutputV4 {
     pub(super) mode: PlotModeV4,
@@ -773,6 +959,18 @@ pub(super) struct KerrV2 {
     pub(super) factor: f64,
 }
 
+pub(super) fn default_lockin_harmonics() -> Vec<usize> {
+    pmoke_config_core::DEFAULT_LOCKIN_HARMONICS.to_vec()
+}
+
+pub(super) fn default_lockin_rc_order() -> usize {
+    pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER
+}
+
+fn default_true() -> bool {
+    true
+}
+
 pub(super) fn default_lockin_stopband_atten_db() -> f64 {
     60.0
 }
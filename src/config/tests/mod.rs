@@ -1,7 +1,7 @@
 use super::{
-    ConfigLoad, Connection, DiagnosticKind, FetchAnalysisInput, FetchOutput, LockinLpfKind,
-    PlotDecimation, ValidationTarget, load_from_path, load_from_str, render_normalized_config,
-    validate_for_target, validate_sensor_metadata,
+    ConfigLoad, Connection, DiagnosticKind, FetchAnalysisInput, FetchOutput, KerrModulationDepth,
+    LockinCutoff, LockinLpfKind, PlotDecimation, ValidationTarget, load_from_path, load_from_str,
+    render_normalized_config, validate_for_target, validate_sensor_metadata,
 };
 use std::fs;
 
@@ -1,5 +1,9 @@
 use super::*;
-use std::path::PathBuf;
+use crate::config::{
+    GeneratorWaveform, KerrFieldBackground, MultimeterFunction, PhaseDrift, PulseBaseline,
+    ScopeBandwidthLimit, ScopeTriggerSource, Thermocouple, usbtmc_device_supported,
+};
+use std::path::{Path, PathBuf};
 
 fn v4_base() -> String {
     r#"
@@ -134,6 +138,23 @@ fn v5_core_and_native_normalized_output_have_identical_values() {
                     1,
                 ),
         ),
+        (
+            "reduced harmonic set",
+            v4_base()
+                .replace("version = 4", "version = 5")
+                .replace(
+                    "stride_samples = 100\nfilter",
+                    "stride_samples = 100\nharmonics = [1, 2]\nfilter",
+                )
+                .replace("offsets = [0, 0, 0, 0, 0, 0]", "offsets = [0, 0]"),
+        ),
+        (
+            "rc_cascade filter",
+            v4_base().replace("version = 4", "version = 5").replace(
+                "filter = { kind = \"boxcar_legacy\", half_window_cycles = 1.0 }",
+                "filter = { kind = \"rc_cascade\", order = 3, enbw_hz = 25.0 }",
+            ),
+        ),
     ];
 
     for (name, text) in fixtures {
@@ -157,6 +178,748 @@ fn assert_core_native_normalized(text: &str, name: &str) {
     assert_eq!(core_value, native_value, "normalized {name} config differs");
 }
 
+#[test]
+fn v5_lockin_harmonics_default_and_follow_configured_set() {
+    let text = v4_base().replace("version = 4", "version = 5");
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config");
+    };
+    assert_eq!(config.lockin.harmonics, vec![1, 2, 3, 4, 5, 6]);
+
+    let text = text
+        .replace(
+            "stride_samples = 100\nfilter",
+            "stride_samples = 100\nharmonics = [1, 2, 10]\nfilter",
+        )
+        .replace("offsets = [0, 0, 0, 0, 0, 0]", "offsets = [0, 0, 0.5]");
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config with custom harmonics");
+    };
+    assert_eq!(config.lockin.harmonics, vec![1, 2, 10]);
+    assert_eq!(config.phase.m_omega_t0_offset, vec![0.0, 0.0, 0.5]);
+
+    let missing = text.replace("harmonics = [1, 2, 10]", "harmonics = [1, 3, 10]");
+    let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&missing) else {
+        panic!("standard Kerr method without the second harmonic must be rejected");
+    };
+    assert!(
+        diagnostics
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.path.as_deref() == Some("lockin.harmonics"))
+    );
+}
+
+#[test]
+fn v5_kerr_modulation_depth_accepts_radians_or_harmonics_estimate() {
+    let text = v4_base().replace("version = 4", "version = 5");
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config");
+    };
+    assert_eq!(
+        config.kerr.modulation_depth,
+        KerrModulationDepth::Fixed(0.92)
+    );
+
+    let fixed = text.replace(
+        "method = \"standard\"\nfactor = 1.0",
+        "method = \"standard\"\nfactor = 1.0\nmodulation_depth = 1.1",
+    );
+    let ConfigLoad::Ready { config, .. } = load_from_str(&fixed) else {
+        panic!("expected ready v5 config with an explicit modulation depth");
+    };
+    assert_eq!(
+        config.kerr.modulation_depth,
+        KerrModulationDepth::Fixed(1.1)
+    );
+    assert!(
+        render_normalized_config(&config)
+            .unwrap()
+            .contains("modulation_depth = 1.1")
+    );
+
+    let ConfigLoad::Diagnostics(diagnostics) =
+        load_from_str(&fixed.replace("modulation_depth = 1.1", "modulation_depth = 2.5"))
+    else {
+        panic!("modulation depth beyond the first J1 zero must be rejected");
+    };
+    assert!(
+        diagnostics
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.path.as_deref() == Some("kerr.modulation_depth"))
+    );
+
+    let estimated = fixed.replace("modulation_depth = 1.1", "modulation_depth = \"harmonics\"");
+    let ConfigLoad::Ready { config, .. } = load_from_str(&estimated) else {
+        panic!("expected ready v5 config with a harmonics-estimated modulation depth");
+    };
+    assert!(config.kerr.estimates_modulation_depth());
+    assert_eq!(config.kerr.required_harmonics(), vec![1, 2, 4, 6]);
+
+    let without_sixth = estimated
+        .replace(
+            "stride_samples = 100\nfilter",
+            "stride_samples = 100\nharmonics = [1, 2, 3, 4]\nfilter",
+        )
+        .replace("offsets = [0, 0, 0, 0, 0, 0]", "offsets = [0, 0, 0, 0]");
+    let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&without_sixth) else {
+        panic!("a harmonics-estimated depth without the sixth harmonic must be rejected");
+    };
+    assert!(diagnostics.diagnostics.iter().any(|diagnostic| {
+        diagnostic.path.as_deref() == Some("lockin.harmonics")
+            && diagnostic.message.contains("missing [6]")
+    }));
+}
+
+#[test]
+fn v5_kerr_field_bin_width_is_optional_and_positive() {
+    let text = v4_base().replace("version = 4", "version = 5");
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config");
+    };
+    assert_eq!(config.kerr.field_bin_width, None);
+    assert!(
+        !render_normalized_config(&config)
+            .unwrap()
+            .contains("field_bin_width")
+    );
+
+    let binned = text.replace(
+        "method = \"standard\"\nfactor = 1.0",
+        "method = \"standard\"\nfactor = 1.0\nfield_bin_width = 0.05",
+    );
+    let ConfigLoad::Ready { config, .. } = load_from_str(&binned) else {
+        panic!("expected ready v5 config with a field bin width");
+    };
+    assert_eq!(config.kerr.field_bin_width, Some(0.05));
+    assert!(
+        render_normalized_config(&config)
+            .unwrap()
+            .contains("field_bin_width = 0.05")
+    );
+
+    for width in ["0.0", "-0.1", "nan"] {
+        let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&binned.replace(
+            "field_bin_width = 0.05",
+            &format!("field_bin_width = {width}"),
+        )) else {
+            panic!("field_bin_width = {width} must be rejected");
+        };
+        assert!(
+            diagnostics
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.path.as_deref() == Some("kerr.field_bin_width"))
+        );
+    }
+}
+
+#[test]
+fn v5_pulse_baseline_defaults_to_constant_and_round_trips() {
+    let text = v4_base().replace("version = 4", "version = 5");
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config");
+    };
+    assert_eq!(config.pulse.baseline, PulseBaseline::Constant);
+    assert!(
+        !render_normalized_config(&config)
+            .unwrap()
+            .contains("baseline")
+    );
+
+    let background_after = "background_after = { start = 4.2e-3, end = 15e-3 }";
+    for (baseline, expected) in [
+        ("{ kind = \"linear\" }", PulseBaseline::Linear),
+        (
+            "{ kind = \"polynomial\", order = 3 }",
+            PulseBaseline::Polynomial { order: 3 },
+        ),
+        (
+            "{ kind = \"endpoint_constrained\" }",
+            PulseBaseline::EndpointConstrained { order: 1 },
+        ),
+    ] {
+        let text = text.replace(
+            background_after,
+            &format!("{background_after}\nbaseline = {baseline}"),
+        );
+        let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+            panic!("expected ready v5 config with baseline {baseline}");
+        };
+        assert_eq!(config.pulse.baseline, expected);
+        assert_core_native_normalized(&text, baseline);
+    }
+
+    for baseline in [
+        "{ kind = \"polynomial\", order = 0 }",
+        "{ kind = \"endpoint_constrained\", order = 4 }",
+    ] {
+        let text = text.replace(
+            background_after,
+            &format!("{background_after}\nbaseline = {baseline}"),
+        );
+        let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&text) else {
+            panic!("baseline {baseline} must be rejected");
+        };
+        assert!(
+            diagnostics
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.path.as_deref() == Some("pulse.baseline.order"))
+        );
+        let core = pmoke_config_core::validate_config_toml(&text);
+        assert!(!core.valid, "core must reject baseline {baseline}");
+    }
+}
+
+#[test]
+fn v5_coil_sensor_scale_round_trips_and_reserves_the_compensation_channel() {
+    let base = v4_base().replace("version = 4", "version = 5");
+    let coil = "scale = { area_turns = 2.5e-4, gain = 0.1, polarity = -1, compensation = { channel = 5, balance = 0.98 } }";
+    let text = base.replacen("scale = { factor = 1.0 }", coil, 1);
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config with a coil sensor");
+    };
+    let channel = config
+        .channels
+        .iter()
+        .find(|channel| channel.index == 4)
+        .unwrap();
+    assert_eq!(channel.factor, None);
+    let calibration = channel.coil.expect("coil calibration");
+    assert!((calibration.tesla_per_volt_second() + 4.0e4).abs() < 1e-6);
+    validate_sensor_metadata(&config).unwrap();
+
+    let mut with_factor = config.clone();
+    with_factor
+        .channels
+        .iter_mut()
+        .find(|channel| channel.index == 4)
+        .unwrap()
+        .factor = Some(1.0);
+    let error = validate_sensor_metadata(&with_factor).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("cannot set a coil calibration together with 'factor'"),
+        "{error}"
+    );
+    assert_eq!(config.sensor_compensation_ch(), vec![5]);
+    assert_eq!(
+        crate::utils::channels::build_channel_list(&config).unwrap(),
+        vec![1, 2, 3, 4, 5]
+    );
+    assert!(
+        render_normalized_config(&config)
+            .unwrap()
+            .contains("area_turns = 0.00025")
+    );
+    assert_core_native_normalized(&text, "coil");
+
+    let minimal = base.replacen(
+        "scale = { factor = 1.0 }",
+        "scale = { area_turns = 1.0 }",
+        1,
+    );
+    assert_core_native_normalized(&minimal, "minimal coil");
+
+    for (replacement, path) in [
+        (
+            coil.replace("channel = 5", "channel = 3"),
+            "reference.channel",
+        ),
+        (
+            coil.replace("area_turns = 2.5e-4", "area_turns = 0.0"),
+            "sensors[1].scale.area_turns",
+        ),
+        (
+            coil.replace("gain = 0.1", "gain = -0.1"),
+            "sensors[1].scale.gain",
+        ),
+    ] {
+        let text = base.replacen("scale = { factor = 1.0 }", &replacement, 1);
+        let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&text) else {
+            panic!("coil scale {replacement} must be rejected");
+        };
+        assert!(
+            diagnostics
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.path.as_deref() == Some(path)),
+            "{path}"
+        );
+        let core = pmoke_config_core::validate_config_toml(&text);
+        assert!(!core.valid, "core must reject coil scale {replacement}");
+    }
+}
+
+#[test]
+fn v5_phase_drift_defaults_to_constant_and_round_trips() {
+    let text = v4_base().replace("version = 4", "version = 5");
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config");
+    };
+    assert_eq!(config.phase.drift, PhaseDrift::Constant);
+    assert!(!render_normalized_config(&config).unwrap().contains("drift"));
+
+    let offsets = "offsets = [0, 0, 0, 0, 0, 0]";
+    let drifting = text.replace(
+        offsets,
+        &format!("{offsets}\ndrift = {{ kind = \"piecewise_linear\", window = 2e-3 }}"),
+    );
+    let ConfigLoad::Ready { config, .. } = load_from_str(&drifting) else {
+        panic!("expected ready v5 config with phase drift");
+    };
+    assert_eq!(
+        config.phase.drift,
+        PhaseDrift::PiecewiseLinear {
+            window: 2e-3,
+            step: None
+        }
+    );
+    assert_eq!(config.phase.drift.window_and_step(), Some((2e-3, 1e-3)));
+    assert_core_native_normalized(&drifting, "phase drift");
+
+    for (drift, path) in [
+        (
+            "{ kind = \"piecewise_linear\", window = 0.0 }",
+            "phase.drift.window",
+        ),
+        (
+            "{ kind = \"piecewise_linear\", window = 1e-3, step = -1e-3 }",
+            "phase.drift.step",
+        ),
+    ] {
+        let text = text.replace(offsets, &format!("{offsets}\ndrift = {drift}"));
+        let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&text) else {
+            panic!("phase drift {drift} must be rejected");
+        };
+        assert!(
+            diagnostics
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.path.as_deref() == Some(path))
+        );
+        let core = pmoke_config_core::validate_config_toml(&text);
+        assert!(!core.valid, "core must reject phase drift {drift}");
+    }
+}
+
+#[test]
+fn v5_kerr_background_run_round_trips_and_rejects_blank_paths() {
+    let text = v4_base().replace("version = 4", "version = 5").replace(
+        "method = \"standard\"\nfactor = 1.0",
+        "method = \"standard\"\nfactor = 1.0\nbackground_run = \"../zero_field\"",
+    );
+    let ConfigLoad::Ready { mut config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config with a background run");
+    };
+    assert_eq!(config.kerr.background_run.as_deref(), Some("../zero_field"));
+    config.source_path = PathBuf::from("/data/sample/config.toml");
+    config.set_artifact_root(PathBuf::from("/data/runs/shot_001"));
+    assert_eq!(
+        config.config_relative_path("../zero_field"),
+        Path::new("/data/sample/../zero_field")
+    );
+    assert!(
+        render_normalized_config(&config)
+            .unwrap()
+            .contains("background_run = \"../zero_field\"")
+    );
+
+    let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&text.replace(
+        "background_run = \"../zero_field\"",
+        "background_run = \" \"",
+    )) else {
+        panic!("a blank background run must be rejected");
+    };
+    assert!(
+        diagnostics
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.path.as_deref() == Some("kerr.background_run"))
+    );
+}
+
+#[test]
+fn v5_kerr_field_background_round_trips_and_rejects_bad_fits() {
+    let factor = "method = \"standard\"\nfactor = 1.0";
+    let text = v4_base().replace("version = 4", "version = 5").replace(
+        factor,
+        &format!(
+            "{factor}\nfield_background = {{ kind = \"odd_polynomial\", order = 3, ranges = [[3.0, 5.0], [-5.0, -3.0]] }}"
+        ),
+    );
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config with a field background");
+    };
+    assert_eq!(
+        config.kerr.field_background,
+        Some(KerrFieldBackground::OddPolynomial {
+            order: 3,
+            ranges: vec![[3.0, 5.0], [-5.0, -3.0]],
+        })
+    );
+    assert_core_native_normalized(&text, "kerr field background");
+
+    for (background, path) in [
+        (
+            "{ kind = \"odd_polynomial\", order = 2, ranges = [[3.0, 5.0]] }",
+            "kerr.field_background.order",
+        ),
+        (
+            "{ kind = \"linear\", ranges = [] }",
+            "kerr.field_background.ranges",
+        ),
+        (
+            "{ kind = \"linear\", ranges = [[5.0, 3.0]] }",
+            "kerr.field_background.ranges[0]",
+        ),
+    ] {
+        let text = v4_base().replace("version = 4", "version = 5").replace(
+            factor,
+            &format!("{factor}\nfield_background = {background}"),
+        );
+        let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&text) else {
+            panic!("field background {background} must be rejected");
+        };
+        assert!(
+            diagnostics
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.path.as_deref() == Some(path)),
+            "{path}"
+        );
+        let core = pmoke_config_core::validate_config_toml(&text);
+        assert!(
+            !core.valid,
+            "core must reject field background {background}"
+        );
+    }
+}
+
+#[test]
+fn v5_scope_trigger_timing_defaults_and_rejects_invalid_values() {
+    let text = v4_base().replace("version = 4", "version = 5");
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config");
+    };
+    let scope = &config.instruments.as_ref().unwrap().oscilloscope;
+    assert_eq!(scope.trigger_timeout_s, 10.0);
+    assert_eq!(scope.trigger_poll_interval_s, 0.1);
+
+    let timed = text.replace(
+        "connection = \"tcp://10.249.11.19:55255\"\n",
+        "connection = \"tcp://10.249.11.19:55255\"\ntrigger_timeout_s = 2.5\ntrigger_poll_interval_s = 0.05\n",
+    );
+    let ConfigLoad::Ready { config, .. } = load_from_str(&timed) else {
+        panic!("expected ready v5 config with trigger timing");
+    };
+    let scope = &config.instruments.as_ref().unwrap().oscilloscope;
+    assert_eq!(scope.trigger_timeout_s, 2.5);
+    assert_eq!(scope.trigger_poll_interval_s, 0.05);
+    assert!(
+        render_normalized_config(&config)
+            .unwrap()
+            .contains("trigger_timeout_s = 2.5")
+    );
+
+    for (invalid, path) in [
+        ("trigger_timeout_s = 0.0", "scope.trigger_timeout_s"),
+        (
+            "trigger_poll_interval_s = 5.0",
+            "scope.trigger_poll_interval_s",
+        ),
+    ] {
+        let key = invalid.split(" = ").next().unwrap();
+        let line = timed
+            .lines()
+            .find(|line| line.starts_with(key))
+            .unwrap()
+            .to_string();
+        let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&timed.replace(&line, invalid))
+        else {
+            panic!("{invalid} must be rejected");
+        };
+        assert!(
+            diagnostics
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.path.as_deref() == Some(path)),
+            "{invalid}"
+        );
+    }
+}
+
+#[test]
+fn infiniivision_scope_model_is_accepted_with_its_bandwidth_limit() {
+    let text = v4_base()
+        .replace("version = 4", "version = 5")
+        .replace("model = \"DHO5108\"", "model = \"InfiniiVision\"")
+        .replace(
+            "connection = \"tcp://10.249.11.19:55255\"\n",
+            "connection = \"tcp://10.249.11.19:5025\"\n\n[[scope.setup.channels]]\nchannel = 1\nbandwidth_limit = \"25mhz\"\n",
+        );
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config for an InfiniiVision scope");
+    };
+    let oscilloscope = &config.instruments.as_ref().unwrap().oscilloscope;
+    assert_eq!(oscilloscope.model, "InfiniiVision");
+    assert_eq!(
+        oscilloscope.setup.as_ref().unwrap().channels[0].bandwidth_limit,
+        Some(ScopeBandwidthLimit::Mhz25)
+    );
+
+    let ConfigLoad::Diagnostics(diagnostics) =
+        load_from_str(&text.replace("\"InfiniiVision\"", "\"TDS2024\""))
+    else {
+        panic!("an unregistered scope model must be rejected");
+    };
+    assert!(
+        diagnostics
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.path.as_deref() == Some("scope.model"))
+    );
+}
+
+#[test]
+fn sim_connections_cover_the_dho5108_and_generator_only() {
+    let text = v4_base()
+        .replace("version = 4", "version = 5")
+        .replace("tcp://10.249.11.19:55255", "sim://")
+        .replacen(
+            "[data]",
+            "[generator]\nmodel = \"WF1946B\"\nconnection = \"sim://\"\n\n[data]",
+            1,
+        );
+    assert_core_native_normalized(&text, "sim bench");
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config on a sim bench");
+    };
+    let instruments = config.instruments.as_ref().unwrap();
+    assert!(matches!(
+        &instruments.oscilloscope.connection,
+        Connection::Sim { bench } if bench == "default"
+    ));
+    assert!(matches!(
+        &instruments.function_generator.as_ref().unwrap().connection,
+        Connection::Sim { bench } if bench == "default"
+    ));
+    let rendered = render_normalized_config(&config).unwrap();
+    assert!(
+        rendered.contains("connection = \"sim://default\""),
+        "{rendered}"
+    );
+
+    let multimeter = "[[multimeters]]\nlabel = \"lamp_monitor\"\nmodel = \"Keithley2000\"\nconnection = \"sim://\"\nfunction = \"dc_volts\"\n\n[data]";
+    for (broken, path) in [
+        (
+            text.replace("model = \"DHO5108\"", "model = \"InfiniiVision\""),
+            "scope.connection",
+        ),
+        (
+            text.replacen("[data]", multimeter, 1),
+            "multimeters[0].connection",
+        ),
+    ] {
+        let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&broken) else {
+            panic!("expected {path} to reject sim://");
+        };
+        assert!(
+            diagnostics
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.path.as_deref() == Some(path)),
+            "{diagnostics:?}"
+        );
+    }
+}
+
+#[test]
+fn usbtmc_device_connection_selects_the_linux_character_device() {
+    let text = v4_base()
+        .replace("version = 4", "version = 5")
+        .replace("tcp://10.249.11.19:55255", "usbtmc:///dev/usbtmc0");
+    if usbtmc_device_supported() {
+        assert_core_native_normalized(&text, "usbtmc device");
+        let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+            panic!("expected ready config on a usbtmc device");
+        };
+        assert!(matches!(
+            &config.instruments.as_ref().unwrap().oscilloscope.connection,
+            Connection::UsbtmcDevice { path } if path == "/dev/usbtmc0"
+        ));
+    } else {
+        assert!(matches!(load_from_str(&text), ConfigLoad::Diagnostics(_)));
+    }
+
+    let generator = text.replacen(
+        "[data]",
+        "[generator]\nmodel = \"WF1946B\"\nconnection = \"usbtmc:///dev/usbtmc1\"\n\n[data]",
+        1,
+    );
+    let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&generator) else {
+        panic!("expected the WF1946B to reject a usbtmc device");
+    };
+    assert!(
+        diagnostics
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.path.as_deref() == Some("generator.connection")),
+        "{diagnostics:?}"
+    );
+}
+
+#[test]
+fn v5_scope_setup_round_trips_and_rejects_invalid_channels() {
+    let text = v4_base().replace("version = 4", "version = 5").replace(
+        "connection = \"tcp://10.249.11.19:55255\"\n",
+        "connection = \"tcp://10.249.11.19:55255\"\n\n[scope.setup]\ntimebase_scale = 0.002\nmemory_depth = 10000000\ntrigger_source = \"ext\"\ntrigger_slope = \"rising\"\n\n[[scope.setup.channels]]\nchannel = 2\nscale = 0.5\ncoupling = \"dc\"\nbandwidth_limit = \"20mhz\"\n",
+    );
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config with a scope setup");
+    };
+    let setup = config
+        .instruments
+        .as_ref()
+        .unwrap()
+        .oscilloscope
+        .setup
+        .as_ref()
+        .unwrap();
+    assert_eq!(setup.memory_depth, Some(10_000_000));
+    assert_eq!(setup.trigger_source, Some(ScopeTriggerSource::Ext));
+    assert_eq!(
+        setup.channels[0].bandwidth_limit,
+        Some(ScopeBandwidthLimit::Mhz20)
+    );
+    let rendered = render_normalized_config(&config).unwrap();
+    assert!(rendered.contains("[scope.setup]"), "{rendered}");
+    assert!(rendered.contains("[[scope.setup.channels]]"), "{rendered}");
+
+    let ConfigLoad::Diagnostics(diagnostics) =
+        load_from_str(&text.replace("channel = 2\nscale = 0.5", "channel = 9\nscale = 0.5"))
+    else {
+        panic!("an out-of-range setup channel must be rejected");
+    };
+    assert!(diagnostics.diagnostics.iter().any(|diagnostic| {
+        diagnostic.path.as_deref() == Some("scope.setup.channels[0].channel")
+    }));
+    assert!(matches!(
+        load_from_str(&text.replace("\"20mhz\"", "\"10mhz\"")),
+        ConfigLoad::Diagnostics(_)
+    ));
+}
+
+#[test]
+fn v5_generator_setup_round_trips_and_is_rejected_in_v4() {
+    let generator = "[generator]\nmodel = \"WF1946B\"\nconnection = \"gpib://0/2\"\n\n[generator.setup]\nwaveform = \"square\"\nfrequency_hz = 1000.0\namplitude_vpp = 2.5\nburst_cycles = 1\ntrigger_source = \"bus\"\noutput = true\n\n[data]";
+    let text = v4_base()
+        .replace("version = 4", "version = 5")
+        .replacen("[data]", generator, 1);
+    let ConfigLoad::Ready { config, warnings } = load_from_str(&text) else {
+        panic!("expected ready v5 config with a generator setup");
+    };
+    assert!(warnings.is_empty(), "{warnings:?}");
+    let setup = config
+        .instruments
+        .as_ref()
+        .unwrap()
+        .function_generator
+        .as_ref()
+        .unwrap()
+        .setup
+        .as_ref()
+        .unwrap();
+    assert_eq!(setup.waveform, Some(GeneratorWaveform::Square));
+    assert_eq!(setup.burst_cycles, Some(1));
+    assert_eq!(setup.output, Some(true));
+    let rendered = render_normalized_config(&config).unwrap();
+    assert!(rendered.contains("[generator.setup]"), "{rendered}");
+    assert!(rendered.contains("amplitude_vpp = 2.5"), "{rendered}");
+
+    let ConfigLoad::Diagnostics(diagnostics) =
+        load_from_str(&text.replace("burst_cycles = 1", "burst_cycles = 0"))
+    else {
+        panic!("a zero burst count must be rejected");
+    };
+    assert!(
+        diagnostics.diagnostics.iter().any(|diagnostic| {
+            diagnostic.path.as_deref() == Some("generator.setup.burst_cycles")
+        })
+    );
+    let ConfigLoad::Ready { warnings, .. } =
+        load_from_str(&text.replace("\"bus\"", "\"external\""))
+    else {
+        panic!("a non-bus trigger source is only a warning");
+    };
+    assert!(
+        warnings
+            .iter()
+            .any(|warning| warning.message.contains("generator.setup.trigger_source")),
+        "{warnings:?}"
+    );
+    assert!(matches!(
+        load_from_str(&text.replace("version = 5", "version = 4")),
+        ConfigLoad::Diagnostics(_)
+    ));
+}
+
+#[test]
+fn v5_multimeters_round_trip_and_reject_invalid_entries() {
+    let multimeters = "[[multimeters]]\nlabel = \"sample_temperature\"\nmodel = \"Keithley2010\"\nconnection = \"gpib://0/16\"\nfunction = \"temperature\"\nthermocouple = \"k\"\n\n[[multimeters]]\nlabel = \"lamp_monitor\"\nmodel = \"Keithley2000\"\nconnection = \"prologix-tcp://192.0.2.20?addr=17\"\nfunction = \"dc_volts\"\nrange = 10.0\n\n[data]";
+    let text = v4_base()
+        .replace("version = 4", "version = 5")
+        .replacen("[data]", multimeters, 1);
+    let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+        panic!("expected ready v5 config with multimeters");
+    };
+    let configured = &config.instruments.as_ref().unwrap().multimeters;
+    assert_eq!(configured.len(), 2);
+    assert_eq!(configured[0].function, MultimeterFunction::Temperature);
+    assert_eq!(configured[0].thermocouple, Some(Thermocouple::K));
+    assert_eq!(configured[1].range, Some(10.0));
+    let rendered = render_normalized_config(&config).unwrap();
+    assert!(rendered.contains("[[multimeters]]"), "{rendered}");
+    assert!(rendered.contains("label = \"lamp_monitor\""), "{rendered}");
+
+    for (broken, path) in [
+        (
+            text.replace("lamp_monitor", "sample_temperature"),
+            "multimeters[1].label",
+        ),
+        (
+            text.replace("function = \"temperature\"", "function = \"dc_volts\""),
+            "multimeters[0].thermocouple",
+        ),
+        (
+            text.replace("range = 10.0", "range = -1.0"),
+            "multimeters[1].range",
+        ),
+        (
+            text.replace("gpib://0/16", "tcp://192.0.2.30:5025"),
+            "multimeters[0].connection",
+        ),
+        (
+            text.replace("gpib://0/16", "vxi11://192.0.2.30/inst0"),
+            "multimeters[0].connection",
+        ),
+    ] {
+        let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&broken) else {
+            panic!("expected diagnostics for {path}");
+        };
+        assert!(
+            diagnostics
+                .diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.path.as_deref() == Some(path)),
+            "{path}: {diagnostics:?}"
+        );
+    }
+}
+
 #[test]
 fn v4_rejects_removed_channels_role_table() {
     let text = v4_base()
@@ -477,7 +1240,7 @@ fn v5_removed_filter_kind_is_reported_as_migration_diagnostic() {
 fn v5_removed_filter_field_is_reported_as_migration_diagnostic() {
     let text = v4_base().replace("version = 4", "version = 5").replace(
         "filter = { kind = \"boxcar_legacy\", half_window_cycles = 1.0 }",
-        "filter = { kind = \"boxcar_legacy\", half_window_cycles = 1.0, cutoff_hz = 10.0 }",
+        "filter = { kind = \"boxcar_legacy\", half_window_cycles = 1.0, iir_order = 4 }",
     );
     let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&text) else {
         panic!("expected removed-filter field migration diagnostics");
@@ -485,12 +1248,97 @@ fn v5_removed_filter_field_is_reported_as_migration_diagnostic() {
     assert!(
         diagnostics.diagnostics.iter().any(|diagnostic| {
             matches!(diagnostic.kind, DiagnosticKind::Migration)
+                && diagnostic.message.contains("iir_order")
+        }),
+        "diagnostics: {diagnostics:?}"
+    );
+}
+
+#[test]
+fn v5_cutoff_on_boxcar_is_a_schema_diagnostic() {
+    let text = v4_base().replace("version = 4", "version = 5").replace(
+        "filter = { kind = \"boxcar_legacy\", half_window_cycles = 1.0 }",
+        "filter = { kind = \"boxcar_legacy\", half_window_cycles = 1.0, cutoff_hz = 10.0 }",
+    );
+    let ConfigLoad::Diagnostics(diagnostics) = load_from_str(&text) else {
+        panic!("expected schema diagnostics");
+    };
+    assert!(
+        diagnostics.diagnostics.iter().any(|diagnostic| {
+            matches!(diagnostic.kind, DiagnosticKind::Deserialize)
                 && diagnostic.message.contains("cutoff_hz")
         }),
         "diagnostics: {diagnostics:?}"
     );
 }
 
+#[test]
+fn v5_lowpass_filter_kinds_load_and_round_trip() {
+    for (filter, kind, cutoff, order, zero_phase) in [
+        (
+            "filter = { kind = \"rc_cascade\", order = 2, cutoff_hz = 50.0 }",
+            LockinLpfKind::RcCascade,
+            Some(LockinCutoff::Hz(50.0)),
+            2,
+            true,
+        ),
+        (
+            "filter = { kind = \"windowed_sinc\", half_window_cycles = 4.0, cutoff_ref_ratio = 0.1 }",
+            LockinLpfKind::WindowedSinc,
+            Some(LockinCutoff::ReferenceRatio(0.1)),
+            pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+            true,
+        ),
+        (
+            "filter = { kind = \"gaussian\", enbw_hz = 20.0, zero_phase = false }",
+            LockinLpfKind::Gaussian,
+            Some(LockinCutoff::EnbwHz(20.0)),
+            pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+            false,
+        ),
+    ] {
+        let text = v4_base().replace("version = 4", "version = 5").replace(
+            "filter = { kind = \"boxcar_legacy\", half_window_cycles = 1.0 }",
+            filter,
+        );
+        let ConfigLoad::Ready { config, .. } = load_from_str(&text) else {
+            panic!("expected {filter} to load");
+        };
+        assert_eq!(config.lockin.lpf_kind, kind);
+        assert_eq!(config.lockin.lpf_cutoff, cutoff);
+        assert_eq!(config.lockin.lpf_order, order);
+        assert_eq!(config.lockin.lpf_zero_phase, zero_phase);
+
+        let rendered = render_normalized_config(&config).unwrap();
+        let ConfigLoad::Ready {
+            config: round_trip, ..
+        } = load_from_str(&rendered)
+        else {
+            panic!("expected rendered {filter} to load");
+        };
+        assert_eq!(round_trip.lockin.lpf_kind, kind);
+        assert_eq!(round_trip.lockin.lpf_cutoff, cutoff);
+        assert_eq!(round_trip.lockin.lpf_zero_phase, zero_phase);
+    }
+}
+
+#[test]
+fn v5_lowpass_filter_without_cutoff_warns_about_the_fallback() {
+    let text = v4_base().replace("version = 4", "version = 5").replace(
+        "filter = { kind = \"boxcar_legacy\", half_window_cycles = 1.0 }",
+        "filter = { kind = \"gaussian\" }",
+    );
+    let ConfigLoad::Ready { warnings, .. } = load_from_str(&text) else {
+        panic!("expected a gaussian filter without cutoff to load");
+    };
+    assert!(
+        warnings.iter().any(|warning| warning
+            .message
+            .contains("lockin.filter.kind = \"gaussian\" has no cutoff")),
+        "warnings: {warnings:?}"
+    );
+}
+
 #[test]
 fn v5_unknown_filter_kind_remains_a_schema_diagnostic() {
     let text = v4_base().replace("version = 4", "version = 5").replace(
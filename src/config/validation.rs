@@ -31,6 +31,39 @@ pub(super) fn validate_common(cfg: &mut Config) -> ValidationSummary {
             None,
         ));
     }
+    if let Some(instruments) = &cfg.instruments {
+        let scope = &instruments.oscilloscope;
+        let (timeout, interval) = (scope.trigger_timeout_s, scope.trigger_poll_interval_s);
+        if !(timeout.is_finite() && timeout > 0.0) {
+            errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some("scope.trigger_timeout_s".to_string()),
+                format!("scope.trigger_timeout_s must be finite and positive (got {timeout})"),
+                Some("allow for the arm delay plus the full capture window".to_string()),
+            ));
+        }
+        if !(interval.is_finite() && interval > 0.0 && interval <= timeout) {
+            errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some("scope.trigger_poll_interval_s".to_string()),
+                format!(
+                    "scope.trigger_poll_interval_s must be positive and no longer than scope.trigger_timeout_s (got {interval})"
+                ),
+                None,
+            ));
+        }
+        if let Some(setup) = &scope.setup {
+            validate_scope_setup(setup, &mut errors);
+        }
+        if let Some(setup) = instruments
+            .function_generator
+            .as_ref()
+            .and_then(|generator| generator.setup.as_ref())
+        {
+            validate_generator_setup(setup, &mut warnings, &mut errors);
+        }
+        validate_multimeters(&instruments.multimeters, &mut errors);
+    }
     if matches!(
         cfg.instruments
             .as_ref()
@@ -42,6 +75,17 @@ pub(super) fn validate_common(cfg: &mut Config) -> ValidationSummary {
             "instruments.oscilloscope.connection",
         ));
     }
+    if matches!(
+        cfg.instruments
+            .as_ref()
+            .map(|instruments| &instruments.oscilloscope.connection),
+        Some(Connection::UsbtmcDevice { .. })
+    ) && !usbtmc_device_supported()
+    {
+        errors.push(usbtmc_device_unsupported_diagnostic(
+            "instruments.oscilloscope.connection",
+        ));
+    }
     if cfg.lockin.workers == 0 {
         errors.push(ConfigDiagnostic::new(
             DiagnosticKind::Validation,
@@ -58,7 +102,10 @@ pub(super) fn validate_common(cfg: &mut Config) -> ValidationSummary {
             None,
         ));
     }
-    if !cfg.lockin.lpf_half_window_cycles.is_finite() || cfg.lockin.lpf_half_window_cycles <= 0.0 {
+    if cfg.lockin.lpf_kind.uses_half_window()
+        && (!cfg.lockin.lpf_half_window_cycles.is_finite()
+            || cfg.lockin.lpf_half_window_cycles <= 0.0)
+    {
         errors.push(ConfigDiagnostic::new(
             DiagnosticKind::Validation,
             Some("lockin.lpf_half_window_cycles".to_string()),
@@ -69,17 +116,7 @@ pub(super) fn validate_common(cfg: &mut Config) -> ValidationSummary {
             None,
         ));
     }
-    if !matches!(cfg.lockin.lpf_kind, LockinLpfKind::BoxcarLegacy) {
-        errors.push(ConfigDiagnostic::new(
-            DiagnosticKind::Validation,
-            Some("lockin.lpf_kind".to_string()),
-            "the active runtime supports only the boxcar_legacy LPF",
-            Some(
-                "set lockin.filter.kind = \"boxcar_legacy\" after reviewing the behavior change"
-                    .to_string(),
-            ),
-        ));
-    }
+    validate_lpf_design(cfg, &mut warnings, &mut errors);
     if let Some(label) = &cfg.lockin.lpf_debug_label
         && !is_safe_debug_label(label)
     {
@@ -90,12 +127,14 @@ pub(super) fn validate_common(cfg: &mut Config) -> ValidationSummary {
             None,
         ));
     }
-    if cfg.phase.m_omega_t0_offset.len() != 6 {
+    validate_harmonics(cfg, &mut errors);
+    if cfg.phase.m_omega_t0_offset.len() != cfg.lockin.harmonics.len() {
         errors.push(ConfigDiagnostic::new(
             DiagnosticKind::Validation,
             Some("phase.m_omega_t0_offset".to_string()),
             format!(
-                "phase.m_omega_t0_offset must have length 6 (got {})",
+                "phase.m_omega_t0_offset must have one entry per lock-in harmonic ({} expected, got {})",
+                cfg.lockin.harmonics.len(),
                 cfg.phase.m_omega_t0_offset.len()
             ),
             None,
@@ -111,6 +150,7 @@ pub(super) fn validate_common(cfg: &mut Config) -> ValidationSummary {
             ));
         }
     }
+    validate_phase_drift(cfg, &mut errors);
 
     let mut seen = BTreeSet::new();
     for ch in &cfg.channels {
@@ -166,6 +206,81 @@ pub(super) fn validate_common(cfg: &mut Config) -> ValidationSummary {
             None,
         ));
     }
+    if let KerrModulationDepth::Fixed(depth) = cfg.kerr.modulation_depth
+        && !(depth.is_finite()
+            && depth > 0.0
+            && depth < pmoke_config_core::MAX_KERR_MODULATION_DEPTH_RAD)
+    {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("kerr.modulation_depth".to_string()),
+            format!(
+                "kerr.modulation_depth must be in (0, {}) rad (got {depth})",
+                pmoke_config_core::MAX_KERR_MODULATION_DEPTH_RAD
+            ),
+            Some("set the EOM retardation amplitude or use \"harmonics\"".to_string()),
+        ));
+    }
+    if let Some(width) = cfg.kerr.field_bin_width
+        && !(width.is_finite() && width > 0.0)
+    {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("kerr.field_bin_width".to_string()),
+            format!("kerr.field_bin_width must be finite and positive (got {width})"),
+            Some("set the field grid spacing in the Kerr sensor's output unit".to_string()),
+        ));
+    }
+    if cfg
+        .kerr
+        .background_run
+        .as_deref()
+        .is_some_and(|run| run.trim().is_empty())
+    {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("kerr.background_run".to_string()),
+            "kerr.background_run must name a run directory".to_string(),
+            Some("remove the key or point it at an analyzed zero-field run".to_string()),
+        ));
+    }
+    if let Some(background) = &cfg.kerr.field_background {
+        let order = background.order();
+        if order % 2 == 0 || order > pmoke_config_core::MAX_KERR_FIELD_BACKGROUND_ORDER {
+            errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some("kerr.field_background.order".to_string()),
+                format!(
+                    "kerr.field_background.order must be odd and at most {} (got {order})",
+                    pmoke_config_core::MAX_KERR_FIELD_BACKGROUND_ORDER
+                ),
+                Some("use 1, 3, 5, or 7; even terms would absorb the magnetization".to_string()),
+            ));
+        }
+        if background.ranges().is_empty() {
+            errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some("kerr.field_background.ranges".to_string()),
+                "kerr.field_background.ranges must list at least one field range",
+                Some(
+                    "list the saturated high-field ranges, e.g. [[3.0, 5.0], [-5.0, -3.0]]"
+                        .to_string(),
+                ),
+            ));
+        }
+        for (index, [low, high]) in background.ranges().iter().enumerate() {
+            if !(low.is_finite() && high.is_finite() && low < high) {
+                errors.push(ConfigDiagnostic::new(
+                    DiagnosticKind::Validation,
+                    Some(format!("kerr.field_background.ranges[{index}]")),
+                    format!(
+                        "kerr.field_background.ranges[{index}] must be finite with low < high (got [{low}, {high}])"
+                    ),
+                    None,
+                ));
+            }
+        }
+    }
 
     let check_win = |label: &str, w: Window| -> Option<ConfigDiagnostic> {
         if !w.start.is_finite() || !w.end.is_finite() {
@@ -201,6 +316,7 @@ pub(super) fn validate_common(cfg: &mut Config) -> ValidationSummary {
     if let Some(diag) = check_win("reference.fft_window", cfg.reference.fft_window) {
         errors.push(diag);
     }
+    validate_pulse_baseline(cfg, &mut errors);
     if let Some(window) = cfg.lockin.snr_background_window
         && let Some(diag) = check_win("lockin.snr_background_window", window)
     {
@@ -230,6 +346,175 @@ pub(super) fn validate_common(cfg: &mut Config) -> ValidationSummary {
     ValidationSummary { warnings, errors }
 }
 
+fn validate_scope_setup(setup: &OscilloscopeSetup, errors: &mut Vec<ConfigDiagnostic>) {
+    let mut invalid = |path: String, message: String| {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some(path),
+            message,
+            None,
+        ));
+    };
+    let positive = [
+        ("scope.setup.timebase_scale", setup.timebase_scale),
+        (
+            "scope.setup.memory_depth",
+            setup.memory_depth.map(|depth| depth as f64),
+        ),
+    ];
+    for (path, value) in positive {
+        if let Some(value) = value.filter(|value| !(value.is_finite() && *value > 0.0)) {
+            invalid(
+                path.to_string(),
+                format!("{path} must be finite and positive (got {value})"),
+            );
+        }
+    }
+    for (path, value) in [
+        ("scope.setup.timebase_offset", setup.timebase_offset),
+        ("scope.setup.trigger_level", setup.trigger_level),
+    ] {
+        if let Some(value) = value.filter(|value| !value.is_finite()) {
+            invalid(
+                path.to_string(),
+                format!("{path} must be finite (got {value})"),
+            );
+        }
+    }
+    let mut seen = BTreeSet::new();
+    for (index, channel) in setup.channels.iter().enumerate() {
+        let prefix = format!("scope.setup.channels[{index}]");
+        if !(1..=8).contains(&channel.channel) {
+            invalid(
+                format!("{prefix}.channel"),
+                format!(
+                    "{prefix}.channel must be between 1 and 8 (got {})",
+                    channel.channel
+                ),
+            );
+        } else if !seen.insert(channel.channel) {
+            invalid(
+                format!("{prefix}.channel"),
+                format!(
+                    "channel {} appears more than once in scope.setup.channels",
+                    channel.channel
+                ),
+            );
+        }
+        if let Some(scale) = channel
+            .scale
+            .filter(|scale| !(scale.is_finite() && *scale > 0.0))
+        {
+            invalid(
+                format!("{prefix}.scale"),
+                format!("{prefix}.scale must be finite and positive (got {scale})"),
+            );
+        }
+        if let Some(offset) = channel.offset.filter(|offset| !offset.is_finite()) {
+            invalid(
+                format!("{prefix}.offset"),
+                format!("{prefix}.offset must be finite (got {offset})"),
+            );
+        }
+    }
+}
+
+fn validate_generator_setup(
+    setup: &FunctionGeneratorSetup,
+    warnings: &mut Vec<ConfigWarning>,
+    errors: &mut Vec<ConfigDiagnostic>,
+) {
+    for (path, value) in [
+        ("generator.setup.frequency_hz", setup.frequency_hz),
+        ("generator.setup.amplitude_vpp", setup.amplitude_vpp),
+    ] {
+        if let Some(value) = value.filter(|value| !(value.is_finite() && *value > 0.0)) {
+            errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some(path.to_string()),
+                format!("{path} must be finite and positive (got {value})"),
+                None,
+            ));
+        }
+    }
+    if let Some(offset) = setup.offset_v.filter(|offset| !offset.is_finite()) {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("generator.setup.offset_v".to_string()),
+            format!("generator.setup.offset_v must be finite (got {offset})"),
+            None,
+        ));
+    }
+    if setup.burst_cycles == Some(0) {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("generator.setup.burst_cycles".to_string()),
+            "generator.setup.burst_cycles must be at least 1",
+            None,
+        ));
+    }
+    if let Some(source) = setup
+        .trigger_source
+        .filter(|source| *source != GeneratorTriggerSource::Bus)
+    {
+        warnings.push(ConfigWarning::new(format!(
+            "generator.setup.trigger_source = \"{}\" ignores the bus trigger sent by autoshot; use \"bus\" unless the generator is fired externally",
+            source.as_str()
+        )));
+    }
+}
+
+fn validate_multimeters(multimeters: &[Multimeter], errors: &mut Vec<ConfigDiagnostic>) {
+    let mut invalid = |path: String, message: String| {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some(path),
+            message,
+            None,
+        ));
+    };
+    let mut labels = BTreeSet::new();
+    for (index, multimeter) in multimeters.iter().enumerate() {
+        let prefix = format!("multimeters[{index}]");
+        let label = multimeter.label.trim();
+        if label.is_empty() {
+            invalid(
+                format!("{prefix}.label"),
+                format!("{prefix}.label must not be empty"),
+            );
+        } else if !labels.insert(label) {
+            invalid(
+                format!("{prefix}.label"),
+                format!("multimeter label {label:?} appears more than once"),
+            );
+        }
+        if let Some(range) = multimeter.range {
+            if !multimeter.function.has_range() {
+                invalid(
+                    format!("{prefix}.range"),
+                    format!(
+                        "{prefix}.range is not supported for function = \"{}\"",
+                        multimeter.function.as_str()
+                    ),
+                );
+            } else if !(range.is_finite() && range > 0.0) {
+                invalid(
+                    format!("{prefix}.range"),
+                    format!("{prefix}.range must be finite and positive (got {range})"),
+                );
+            }
+        }
+        if multimeter.thermocouple.is_some()
+            && multimeter.function != MultimeterFunction::Temperature
+        {
+            invalid(
+                format!("{prefix}.thermocouple"),
+                format!("{prefix}.thermocouple requires function = \"temperature\""),
+            );
+        }
+    }
+}
+
 fn is_safe_debug_label(label: &str) -> bool {
     !label.is_empty()
         && label.len() <= 64
@@ -245,6 +530,7 @@ pub fn validate_for_target(cfg: &Config, target: ValidationTarget) -> Result<()>
         ValidationTarget::Single
         | ValidationTarget::Fetch
         | ValidationTarget::Screenshot
+        | ValidationTarget::Scope
         | ValidationTarget::Process
         | ValidationTarget::Auto => {
             validate_oscilloscope_required(cfg)?;
@@ -331,6 +617,7 @@ pub fn validate_for_target(cfg: &Config, target: ValidationTarget) -> Result<()>
         ValidationTarget::Automeasure
         | ValidationTarget::Fetch
         | ValidationTarget::Screenshot
+        | ValidationTarget::Scope
         | ValidationTarget::Single
         | ValidationTarget::Trigger
         | ValidationTarget::Autoshot => {}
@@ -347,11 +634,18 @@ fn validate_screenshot_target(cfg: &Config) -> Result<()> {
         .oscilloscope;
     match &oscilloscope.connection {
         Connection::Gpib { .. }
+        | Connection::Vxi11 { .. }
         | Connection::PrologixTcp { .. }
         | Connection::PrologixSerial { .. } => {
-            bail!("DHO5108 display capture requires TCP/IP or USB-TMC");
+            bail!(
+                "{} display capture requires TCP/IP or USB-TMC",
+                oscilloscope.model
+            );
         }
-        Connection::Tcpip { .. } | Connection::Usbtmc { .. } => {}
+        Connection::Tcpip { .. }
+        | Connection::Usbtmc { .. }
+        | Connection::UsbtmcDevice { .. }
+        | Connection::Sim { .. } => {}
     }
     Ok(())
 }
@@ -414,23 +708,36 @@ pub(super) fn validate_sensor_metadata(cfg: &Config) -> Result<()> {
             .find(|c| c.index == *ch)
             .ok_or_else(|| anyhow!("channel {} is not defined in [channels]", ch))?;
 
-        match (meta.factor, meta.scale_to_abs_max) {
-            (Some(_), Some(_)) => {
-                bail!("channel {ch} cannot set both 'factor' and 'scale_to_abs_max'");
-            }
-            (Some(factor), None) => {
+        match (meta.factor, meta.scale_to_abs_max, meta.coil) {
+            (Some(factor), None, None) => {
                 if !factor.is_finite() {
                     bail!("channel {ch} factor must be finite");
                 }
             }
-            (None, Some(scale_to_abs_max)) => {
+            (None, Some(scale_to_abs_max), None) => {
                 if !scale_to_abs_max.is_finite() || scale_to_abs_max == 0.0 {
                     bail!("channel {ch} scale_to_abs_max must be finite and non-zero");
                 }
             }
-            (None, None) => {
+            (None, None, Some(coil)) => {
+                let factor = coil.tesla_per_volt_second();
+                if !factor.is_finite() || factor == 0.0 {
+                    bail!(
+                        "channel {ch} coil calibration must resolve to a finite, non-zero factor"
+                    );
+                }
+            }
+            (None, None, None) => {
                 bail!("channel {ch} must set either 'factor' or 'scale_to_abs_max'");
             }
+            (_, _, Some(_)) => {
+                bail!(
+                    "channel {ch} cannot set a coil calibration together with 'factor' or 'scale_to_abs_max'"
+                );
+            }
+            (Some(_), Some(_), None) => {
+                bail!("channel {ch} cannot set both 'factor' and 'scale_to_abs_max'");
+            }
         }
         if meta.label.is_none() {
             bail!("channel {} has no 'label'", ch);
@@ -506,3 +813,142 @@ fn validate_file_exists(path: &Path, label: &str) -> Result<()> {
         bail!("{label} does not exist")
     }
 }
+
+fn validate_lpf_design(
+    cfg: &Config,
+    warnings: &mut Vec<ConfigWarning>,
+    errors: &mut Vec<ConfigDiagnostic>,
+) {
+    let kind = cfg.lockin.lpf_kind;
+    if kind == LockinLpfKind::BoxcarLegacy {
+        return;
+    }
+    if kind == LockinLpfKind::RcCascade
+        && !(1..=pmoke_config_core::MAX_LOCKIN_RC_ORDER).contains(&cfg.lockin.lpf_order)
+    {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("lockin.lpf_order".to_string()),
+            format!(
+                "lockin.lpf_order must be between 1 and {} (got {})",
+                pmoke_config_core::MAX_LOCKIN_RC_ORDER,
+                cfg.lockin.lpf_order
+            ),
+            None,
+        ));
+    }
+    match cfg.lockin.lpf_cutoff {
+        Some(cutoff) => {
+            let (path, value) = match cutoff {
+                LockinCutoff::Hz(value) => ("lockin.lpf_cutoff_hz", value),
+                LockinCutoff::ReferenceRatio(value) => ("lockin.lpf_cutoff_ref_ratio", value),
+                LockinCutoff::EnbwHz(value) => ("lockin.lpf_enbw_hz", value),
+            };
+            if !value.is_finite() || value <= 0.0 {
+                errors.push(ConfigDiagnostic::new(
+                    DiagnosticKind::Validation,
+                    Some(path.to_string()),
+                    format!("{path} must be positive (got {value})"),
+                    None,
+                ));
+            }
+        }
+        None => warnings.push(ConfigWarning::new(format!(
+            "lockin.lpf_kind = \"{}\" has no cutoff; the cutoff is chosen to match the ENBW of a one-cycle boxcar (half the reference frequency)",
+            kind.as_str()
+        ))),
+    }
+}
+
+fn validate_pulse_baseline(cfg: &Config, errors: &mut Vec<ConfigDiagnostic>) {
+    if let PulseBaseline::Polynomial { order } | PulseBaseline::EndpointConstrained { order } =
+        cfg.pulse.baseline
+        && !(1..=pmoke_config_core::MAX_PULSE_BASELINE_ORDER).contains(&order)
+    {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("pulse.baseline.order".to_string()),
+            format!(
+                "pulse.baseline.order must be between 1 and {} (got {order})",
+                pmoke_config_core::MAX_PULSE_BASELINE_ORDER
+            ),
+            None,
+        ));
+    }
+}
+
+fn validate_phase_drift(cfg: &Config, errors: &mut Vec<ConfigDiagnostic>) {
+    let PhaseDrift::PiecewiseLinear { window, step } = cfg.phase.drift else {
+        return;
+    };
+    for (path, value) in [
+        ("phase.drift.window", Some(window)),
+        ("phase.drift.step", step),
+    ] {
+        if let Some(value) = value
+            && (!value.is_finite() || value <= 0.0)
+        {
+            errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some(path.to_string()),
+                format!("{path} must be finite and positive (got {value})"),
+                None,
+            ));
+        }
+    }
+}
+
+fn validate_harmonics(cfg: &Config, errors: &mut Vec<ConfigDiagnostic>) {
+    let harmonics = &cfg.lockin.harmonics;
+    if harmonics.is_empty() {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("lockin.harmonics".to_string()),
+            "lockin.harmonics must list at least one harmonic",
+            None,
+        ));
+        return;
+    }
+    for (idx, &harmonic) in harmonics.iter().enumerate() {
+        if !(1..=pmoke_config_core::MAX_LOCKIN_HARMONIC).contains(&harmonic) {
+            errors.push(ConfigDiagnostic::new(
+                DiagnosticKind::Validation,
+                Some(format!("lockin.harmonics[{idx}]")),
+                format!(
+                    "lockin.harmonics[{idx}] must be in 1..={} (got {harmonic})",
+                    pmoke_config_core::MAX_LOCKIN_HARMONIC
+                ),
+                None,
+            ));
+        }
+    }
+    if harmonics.windows(2).any(|pair| pair[0] >= pair[1]) {
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("lockin.harmonics".to_string()),
+            "lockin.harmonics must be strictly increasing without duplicates",
+            None,
+        ));
+    }
+    let required = cfg.kerr.required_harmonics();
+    let missing = required
+        .iter()
+        .filter(|harmonic| !harmonics.contains(harmonic))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        let estimated = if cfg.kerr.estimates_modulation_depth() {
+            " with a harmonics-estimated modulation depth"
+        } else {
+            ""
+        };
+        errors.push(ConfigDiagnostic::new(
+            DiagnosticKind::Validation,
+            Some("lockin.harmonics".to_string()),
+            format!(
+                "the {:?} Kerr method{estimated} requires harmonics {required:?}; missing {missing:?}",
+                cfg.kerr.kerr_type
+            ),
+            Some(format!("add {missing:?} to lockin.harmonics")),
+        ));
+    }
+}
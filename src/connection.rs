@@ -1 +1,3 @@
-pub(crate) use pmoke_config_core::connection::{ConnectionDefaults, ConnectionUri};
+pub(crate) use pmoke_config_core::connection::{
+    ConnectionDefaults, ConnectionUri, DEFAULT_PROLOGIX_READ_TIMEOUT_MS,
+};
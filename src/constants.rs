@@ -3,40 +3,13 @@ pub const RAW_METADATA_FNAME: &str = "metadata.toml";
 pub const RAW_METADATA_VERSION: u32 = 2;
 pub const RAW_METADATA_LEGACY_VERSION: u32 = 1;
 pub const RAW_WAVEFORM_DIR: &str = "raw_waveform";
-pub const HARMONICS: [usize; 6] = [1, 2, 3, 4, 5, 6];
 pub const T_HEADER: &str = "time (s)";
-pub const LI_HEADER: [&str; 12] = [
-    "LIx_h1 (V)",
-    "LIy_h1 (V)",
-    "LIx_h2 (V)",
-    "LIy_h2 (V)",
-    "LIx_h3 (V)",
-    "LIy_h3 (V)",
-    "LIx_h4 (V)",
-    "LIy_h4 (V)",
-    "LIx_h5 (V)",
-    "LIy_h5 (V)",
-    "LIx_h6 (V)",
-    "LIy_h6 (V)",
-];
-
 pub const LI_RESULTS_NAME: &str = "lockin_results";
-
-pub const LI_ROTATED_HEADER: [&str; 12] = [
-    "LIin_h1 (V)",
-    "LIout_h1 (V)",
-    "LIin_h2 (V)",
-    "LIout_h2 (V)",
-    "LIin_h3 (V)",
-    "LIout_h3 (V)",
-    "LIin_h4 (V)",
-    "LIout_h4 (V)",
-    "LIin_h5 (V)",
-    "LIout_h5 (V)",
-    "LIin_h6 (V)",
-    "LIout_h6 (V)",
-];
 pub const LI_ROTATED_NAME: &str = "lockin_rotated";
 
 pub const KERR_NAME: &str = "kerr";
 pub const KERR_HEADER: &str = "Kerr angle (rad)";
+pub const KERR_STD_HEADER: &str = "Kerr angle std (rad)";
+pub const KERR_ERR_HEADER: &str = "kerr_err (rad)";
+pub const KERR_RAW_HEADER: &str = "Kerr angle raw (rad)";
+pub const FIELD_BIN_COUNT_HEADER: &str = "count";
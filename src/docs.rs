@@ -151,7 +151,7 @@ fn required_feature(path: &str) -> Option<&'static str> {
     let command = path.split_whitespace().nth(1)?;
     match command {
         "single" | "trigger" | "autoshot" | "fetch" | "screenshot" | "automeasure" | "process"
-        | "auto" => Some("hw-core"),
+        | "auto" | "scope" | "campaign" => Some("hw-core"),
         _ => None,
     }
 }
@@ -193,13 +193,15 @@ mod tests {
     #[cfg(feature = "hw-core")]
     fn hardware_commands_report_their_feature() {
         let reference = cli_reference();
-        let fetch = reference
-            .command
-            .subcommands
-            .iter()
-            .find(|command| command.path == "pmoke fetch")
-            .unwrap();
-        assert_eq!(fetch.required_feature, Some("hw-core"));
+        for path in ["pmoke fetch", "pmoke scope", "pmoke campaign"] {
+            let command = reference
+                .command
+                .subcommands
+                .iter()
+                .find(|command| command.path == path)
+                .unwrap();
+            assert_eq!(command.required_feature, Some("hw-core"), "{path}");
+        }
     }
 
     #[test]
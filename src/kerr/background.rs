@@ -0,0 +1,92 @@
+use crate::config::Config;
+use crate::constants::KERR_HEADER;
+use crate::utils::csv::read_csv_with_headers;
+use crate::utils::time_axis::same_time_axis;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::fs;
+
+const BACKGROUND_KERR_CSV: &str = "kerr/kerr.csv";
+
+/// Zero-field run whose Kerr angle was subtracted, recorded under `[kerr.background]`.
+#[derive(Debug, Clone, Serialize)]
+pub struct KerrBackgroundProvenance {
+    pub run_dir: String,
+    pub analysis_manifest_sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation: Option<u64>,
+    pub config_sha256: String,
+    pub file: &'static str,
+    pub sha256: String,
+}
+
+pub struct KerrBackground {
+    /// One series per phase signal channel, aligned with the Kerr time axis.
+    pub channels: Vec<Vec<f64>>,
+    pub provenance: KerrBackgroundProvenance,
+}
+
+/// Loads the published Kerr angle of `run` for the channels analyzed here.
+pub fn load_kerr_background(cfg: &Config, run: &str, t: &[f64]) -> Result<KerrBackground> {
+    let published = crate::lockin::provenance::load_published_stage_outputs(
+        &cfg.config_relative_path(run),
+        "kerr",
+        |file| file == BACKGROUND_KERR_CSV,
+    )
+    .context("failed to load kerr.background_run")?;
+    if fs::canonicalize(&cfg.paths().run_dir).is_ok_and(|own| own == published.run_dir) {
+        bail!("kerr.background_run must name another run, not the run being analyzed");
+    }
+    let path = published.paths.analysis_dir().join(BACKGROUND_KERR_CSV);
+    let (headers, columns) = read_csv_with_headers(&path)?;
+    let Some(time) = columns.first() else {
+        bail!("background Kerr table is empty: {}", path.display());
+    };
+    let dt = match t {
+        [first, second, ..] => second - first,
+        _ => bail!("Kerr background subtraction needs at least two time samples"),
+    };
+    if !same_time_axis(t, time, dt) {
+        bail!(
+            "time axis of {} does not match this run; the background shot needs the same timebase and lock-in settings",
+            path.display()
+        );
+    }
+    let channels = cfg
+        .phase_signal_ch()
+        .iter()
+        .map(|ch| {
+            let header = format!("Ch{ch} {KERR_HEADER}");
+            headers
+                .iter()
+                .position(|candidate| *candidate == header)
+                .map(|index| columns[index].clone())
+                .with_context(|| format!("{} has no \"{header}\" column", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let (_, sha256) = published
+        .files
+        .into_iter()
+        .next()
+        .expect("load_published_stage_outputs returns at least one file");
+    Ok(KerrBackground {
+        channels,
+        provenance: KerrBackgroundProvenance {
+            run_dir: published.run_dir.display().to_string(),
+            analysis_manifest_sha256: published.manifest_sha256,
+            generation: published.generation,
+            config_sha256: published.config_sha256,
+            file: BACKGROUND_KERR_CSV,
+            sha256,
+        },
+    })
+}
+
+pub(crate) fn subtract_background(kerr: &mut [f64], background: Option<&[f64]>) {
+    if let Some(background) = background {
+        for (value, offset) in kerr.iter_mut().zip(background) {
+            *value -= offset;
+        }
+    }
+}
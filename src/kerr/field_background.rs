@@ -0,0 +1,204 @@
+use crate::config::KerrFieldBackground;
+use crate::utils::numeric::solve_linear_system;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+/// Kerr angle of one channel with its propagated error.
+#[derive(Debug, Clone)]
+pub struct KerrAngle {
+    pub values_rad: Vec<f64>,
+    pub err_rad: Vec<f64>,
+    /// Angle before the field background was removed.
+    pub raw_rad: Option<Vec<f64>>,
+    pub field_background: Option<FieldBackgroundFit>,
+}
+
+impl KerrAngle {
+    pub fn new(values_rad: Vec<f64>, err_rad: Vec<f64>) -> Self {
+        Self {
+            values_rad,
+            err_rad,
+            raw_rad: None,
+            field_background: None,
+        }
+    }
+
+    /// Fits `background` against `field` and subtracts its odd part, keeping
+    /// the uncorrected angle in `raw_rad`.
+    pub fn remove_field_background(
+        mut self,
+        field: &[f64],
+        background: Option<&KerrFieldBackground>,
+    ) -> Result<Self> {
+        let Some(background) = background else {
+            return Ok(self);
+        };
+        let fit = fit_field_background(field, &self.values_rad, background)
+            .context("failed to fit kerr.field_background")?;
+        let corrected = self
+            .values_rad
+            .iter()
+            .zip(field)
+            .map(|(value, &field)| value - fit.value_at(field))
+            .collect();
+        self.raw_rad = Some(std::mem::replace(&mut self.values_rad, corrected));
+        self.field_background = Some(fit);
+        Ok(self)
+    }
+}
+
+/// Odd-in-field background fitted over the configured high-field ranges,
+/// recorded per channel in the analysis manifest.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldBackgroundFit {
+    pub kind: &'static str,
+    /// Coefficients of B, B³, … in rad per power of the field unit.
+    pub coefficients: Vec<f64>,
+    /// Offset of each range in rad; fitted alongside but not subtracted.
+    pub offsets: Vec<f64>,
+    pub samples: usize,
+}
+
+impl FieldBackgroundFit {
+    pub fn value_at(&self, field: f64) -> f64 {
+        let square = field * field;
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &coefficient| acc * square + coefficient)
+            * field
+    }
+}
+
+/// Fits `offset[range] + Σ c_k·B^(2k+1)` to the samples whose field lies in a
+/// configured range. The per-range offsets absorb the saturated magnetization
+/// so only the odd polynomial is attributed to the background.
+pub fn fit_field_background(
+    field: &[f64],
+    kerr: &[f64],
+    background: &KerrFieldBackground,
+) -> Result<FieldBackgroundFit> {
+    if field.len() != kerr.len() {
+        bail!(
+            "field and Kerr angle lengths differ ({} and {})",
+            field.len(),
+            kerr.len()
+        );
+    }
+    let ranges = background.ranges();
+    let selected = field
+        .iter()
+        .zip(kerr)
+        .filter_map(|(&field, &kerr)| {
+            ranges
+                .iter()
+                .position(|&[low, high]| field >= low && field <= high)
+                .map(|range| (range, field, kerr))
+        })
+        .collect::<Vec<_>>();
+    if let Some(empty) = (0..ranges.len()).find(|&range| selected.iter().all(|s| s.0 != range)) {
+        let [low, high] = ranges[empty];
+        bail!("kerr.field_background.ranges[{empty}] = [{low}, {high}] contains no field samples");
+    }
+    if selected.iter().any(|(_, _, kerr)| !kerr.is_finite()) {
+        bail!("Kerr angle in the field background ranges contains a non-finite value");
+    }
+
+    // Map the field onto [-1, 1] so the normal equations stay well conditioned.
+    let scale = selected
+        .iter()
+        .fold(0.0_f64, |acc, &(_, field, _)| acc.max(field.abs()));
+    if scale == 0.0 {
+        bail!("kerr.field_background.ranges only select zero field");
+    }
+    let odd_terms = background.order().div_ceil(2);
+    let terms = ranges.len() + odd_terms;
+    let mut matrix = vec![vec![0.0; terms + 1]; terms];
+    let mut basis = vec![0.0; terms];
+    for &(range, field, kerr) in &selected {
+        basis.fill(0.0);
+        basis[range] = 1.0;
+        let x = field / scale;
+        let mut power = x;
+        for value in &mut basis[ranges.len()..] {
+            *value = power;
+            power *= x * x;
+        }
+        for (row, &left) in matrix.iter_mut().zip(&basis) {
+            for (cell, &right) in row.iter_mut().zip(&basis) {
+                *cell += left * right;
+            }
+            row[terms] += left * kerr;
+        }
+    }
+    let norm = selected.len() as f64;
+    for value in matrix.iter_mut().flatten() {
+        *value /= norm;
+    }
+    let Some(solution) = solve_linear_system(matrix) else {
+        bail!(
+            "kerr.field_background.ranges do not determine an order-{} odd background; widen the ranges",
+            background.order()
+        );
+    };
+
+    let mut scale_power = scale;
+    let coefficients = solution[ranges.len()..]
+        .iter()
+        .map(|&coefficient| {
+            let value = coefficient / scale_power;
+            scale_power *= scale * scale;
+            value
+        })
+        .collect();
+    Ok(FieldBackgroundFit {
+        kind: background.kind(),
+        coefficients,
+        offsets: solution[..ranges.len()].to_vec(),
+        samples: selected.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fit_field_background;
+    use crate::config::KerrFieldBackground;
+
+    #[test]
+    fn odd_background_is_separated_from_saturated_magnetization() {
+        let field = (0..=400)
+            .map(|index| -5.0 + index as f64 * 0.025)
+            .collect::<Vec<_>>();
+        let magnetization = |field: f64| 0.002 * (field / 0.2).tanh();
+        let kerr = field
+            .iter()
+            .map(|&b| magnetization(b) + 4.0e-4 * b - 2.0e-6 * b.powi(3) + 1.0e-4)
+            .collect::<Vec<_>>();
+
+        let cubic = KerrFieldBackground::OddPolynomial {
+            order: 3,
+            ranges: vec![[3.0, 5.0], [-5.0, -3.0]],
+        };
+        let fit = fit_field_background(&field, &kerr, &cubic).unwrap();
+        assert_eq!(fit.kind, "odd_polynomial");
+        assert_eq!(fit.samples, 162);
+        assert!((fit.coefficients[0] - 4.0e-4).abs() < 1.0e-9, "{fit:?}");
+        assert!((fit.coefficients[1] + 2.0e-6).abs() < 1.0e-10, "{fit:?}");
+        assert!((fit.offsets[0] - 2.1e-3).abs() < 1.0e-8);
+        assert!((fit.offsets[1] + 1.9e-3).abs() < 1.0e-8);
+        assert!((fit.value_at(2.0) - (8.0e-4 - 1.6e-5)).abs() < 1.0e-9);
+
+        let linear = KerrFieldBackground::Linear {
+            ranges: vec![[3.0, 5.0]],
+        };
+        let fit = fit_field_background(&field, &kerr, &linear).unwrap();
+        assert_eq!(fit.coefficients.len(), 1);
+        assert_eq!(fit.offsets.len(), 1);
+
+        let missing = KerrFieldBackground::Linear {
+            ranges: vec![[3.0, 5.0], [6.0, 7.0]],
+        };
+        let error = fit_field_background(&field, &kerr, &missing).unwrap_err();
+        assert!(error.to_string().contains("ranges[1]"), "{error}");
+    }
+}
@@ -1,4 +1,7 @@
-use crate::config::Plot;
+use crate::config::{KerrFieldBackground, Plot};
+use crate::kerr::background::subtract_background;
+use crate::kerr::field_background::KerrAngle;
+use crate::kerr::{in_phase_column, in_phase_std};
 use crate::python;
 use anyhow::{Context, Result};
 use pyo3::prelude::*;
@@ -18,32 +21,49 @@ pub struct KerrHarmonicsAnalysisInput<'a> {
     pub t: &'a [f64],
     pub x: &'a [f64],
     pub ys: &'a [Vec<f64>],
+    pub harmonics: &'a [usize],
+    /// Per-point in-phase noise standard deviation of each harmonic, in
+    /// `harmonics` order.
+    pub noise: &'a [Vec<f64>],
     pub factor: f64,
+    /// Zero-field Kerr angle subtracted before plotting.
+    pub background: Option<&'a [f64]>,
+    /// Odd-in-field background fitted against `x` and removed before plotting.
+    pub field_background: Option<&'a KerrFieldBackground>,
     pub xlabel: &'a String,
     pub fig_name: String,
     pub output_path: &'a Path,
 }
 
 impl KerrHarmonicsAnalyser {
-    pub fn analyse(&self, input: KerrHarmonicsAnalysisInput<'_>) -> Result<Vec<f64>> {
+    /// Returns the Kerr angle and the representative modulation depth.
+    pub fn analyse(&self, input: KerrHarmonicsAnalysisInput<'_>) -> Result<(KerrAngle, f64)> {
         let output = crate::plot::prepare_plot_output(input.plot, input.output_path)?;
-        let harmonic = |index: usize, label: &str| {
-            input
-                .ys
-                .get(index)
-                .with_context(|| format!("missing rotated {label} harmonic input"))
-        };
-        let kerr = pmoke_analysis_core::calculate_harmonics_kerr(
-            harmonic(2, "second")?,
-            harmonic(4, "third")?,
-            harmonic(6, "fourth")?,
-            harmonic(10, "sixth")?,
+        let harmonic = |harmonic: usize| in_phase_column(input.ys, input.harmonics, harmonic);
+        let sigma = |harmonic: usize| in_phase_std(input.noise, input.harmonics, harmonic);
+        let mut kerr = pmoke_analysis_core::calculate_harmonics_kerr(
+            harmonic(2)?,
+            harmonic(3)?,
+            harmonic(4)?,
+            harmonic(6)?,
             input.factor,
         )
-        .context("failed to calculate the Kerr angle from harmonic components")?
-        .values_rad;
+        .context("failed to calculate the Kerr angle from harmonic components")?;
+        let kerr_err = pmoke_analysis_core::calculate_harmonics_kerr_error(
+            harmonic(2)?,
+            harmonic(3)?,
+            harmonic(4)?,
+            [sigma(2)?, sigma(3)?, sigma(4)?],
+            kerr.representative_modulation_depth,
+            input.factor,
+        )
+        .context("failed to propagate lock-in noise to the Kerr angle")?;
+        subtract_background(&mut kerr.values_rad, input.background);
+        let modulation_depth = kerr.representative_modulation_depth;
+        let kerr = KerrAngle::new(kerr.values_rad, kerr_err)
+            .remove_field_background(input.x, input.field_background)?;
         if output.is_none() && !(input.plot.enabled && input.plot.interactive) {
-            return Ok(kerr);
+            return Ok((kerr, modulation_depth));
         }
 
         Python::attach(|py| {
@@ -57,7 +77,7 @@ impl KerrHarmonicsAnalyser {
             .context("failed to load kerr_harmonics_analysis.py")?;
             let t_obj = python::f64_array1(py, input.t);
             let x_obj = python::f64_array1(py, input.x);
-            let kerr_obj = python::f64_array1(py, &kerr);
+            let kerr_obj = python::f64_array1(py, &kerr.values_rad);
             let output_string = output.map(|path| path.to_string_lossy().into_owned());
 
             let analyser = analysis_mod
@@ -90,7 +110,7 @@ impl KerrHarmonicsAnalyser {
                 "Kerr angle from harmonic components",
             )?;
 
-            Ok(kerr)
+            Ok((kerr, modulation_depth))
         })
     }
 }
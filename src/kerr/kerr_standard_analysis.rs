@@ -1,4 +1,7 @@
-use crate::config::Plot;
+use crate::config::{KerrFieldBackground, Plot};
+use crate::kerr::background::subtract_background;
+use crate::kerr::field_background::KerrAngle;
+use crate::kerr::{in_phase_column, in_phase_std};
 use crate::python;
 use anyhow::{Context, Result};
 use pyo3::prelude::*;
@@ -18,15 +21,52 @@ pub struct KerrStandardAnalysisInput<'a> {
     pub t: &'a [f64],
     pub x: &'a [f64],
     pub ys: &'a [Vec<f64>],
+    pub harmonics: &'a [usize],
+    /// Per-point in-phase noise standard deviation of each harmonic, in
+    /// `harmonics` order.
+    pub noise: &'a [Vec<f64>],
     pub factor: f64,
+    /// Zero-field Kerr angle subtracted before plotting.
+    pub background: Option<&'a [f64]>,
+    /// Odd-in-field background fitted against `x` and removed before plotting.
+    pub field_background: Option<&'a KerrFieldBackground>,
+    pub modulation_depth_rad: f64,
     pub xlabel: &'a String,
     pub fig_name: String,
     pub output_path: &'a Path,
 }
 
 impl KerrStandardAnalyser {
-    pub fn analyse(&self, input: KerrStandardAnalysisInput<'_>) -> Result<Vec<f64>> {
+    /// Returns the Kerr angle with its noise-propagated error.
+    pub fn analyse(&self, input: KerrStandardAnalysisInput<'_>) -> Result<KerrAngle> {
         let output = crate::plot::prepare_plot_output(input.plot, input.output_path)?;
+        let first = in_phase_column(input.ys, input.harmonics, 1)?;
+        let second = in_phase_column(input.ys, input.harmonics, 2)?;
+        let mut kerr = pmoke_analysis_core::calculate_standard_kerr(
+            first,
+            second,
+            input.modulation_depth_rad,
+            input.factor,
+        )
+        .context("failed to calculate the Kerr angle from the first and second harmonics")?;
+        let kerr_err = pmoke_analysis_core::calculate_standard_kerr_error(
+            first,
+            second,
+            [
+                in_phase_std(input.noise, input.harmonics, 1)?,
+                in_phase_std(input.noise, input.harmonics, 2)?,
+            ],
+            input.modulation_depth_rad,
+            input.factor,
+        )
+        .context("failed to propagate lock-in noise to the Kerr angle")?;
+        subtract_background(&mut kerr, input.background);
+        let kerr = KerrAngle::new(kerr, kerr_err)
+            .remove_field_background(input.x, input.field_background)?;
+        if output.is_none() && !(input.plot.enabled && input.plot.interactive) {
+            return Ok(kerr);
+        }
+
         Python::attach(|py| {
             let analysis_mod = python::cached_module(
                 py,
@@ -38,7 +78,7 @@ impl KerrStandardAnalyser {
             .context("failed to load kerr_standard_analysis.py")?;
             let t_obj = python::f64_array1(py, input.t);
             let x_obj = python::f64_array1(py, input.x);
-            let ys_obj = python::f64_array2(py, input.ys)?;
+            let kerr_obj = python::f64_array1(py, &kerr.values_rad);
             let output_string = output.map(|path| path.to_string_lossy().into_owned());
 
             let analyser = analysis_mod
@@ -46,14 +86,13 @@ impl KerrStandardAnalyser {
                 .call0()
                 .context("failed to create KerrStandardAnalyser instance")?;
 
-            let res = analyser
+            let plot_error: Option<String> = analyser
                 .call_method1(
-                    "analyse",
+                    "plot",
                     (
                         t_obj,
                         x_obj,
-                        ys_obj,
-                        input.factor,
+                        kerr_obj,
                         input.xlabel,
                         input.fig_name,
                         output_string.is_some(),
@@ -63,10 +102,8 @@ impl KerrStandardAnalyser {
                         input.plot.decimation.as_str(),
                     ),
                 )
-                .context("python KerrStandardAnalyser.analyse(...) failed")?;
-
-            let kerr = python::extract_f64_array1(&res.get_item("kerr")?)?;
-            let plot_error: Option<String> = res.get_item("plot_error")?.extract()?;
+                .context("python KerrStandardAnalyser.plot(...) failed")?
+                .extract()?;
             crate::plot::finish_embedded_plot(input.plot, output, plot_error, "Kerr standard")?;
 
             Ok(kerr)
@@ -1,25 +1,50 @@
+pub mod background;
+pub mod field_background;
 pub mod kerr_harmonics_analysis;
 pub mod kerr_standard_analysis;
 pub mod save;
 
 use crate::analysis_results::parse_analysis_result_files;
-use crate::config::{Channel, KerrType};
-use crate::constants::{KERR_NAME, LI_ROTATED_HEADER};
+use crate::config::{Channel, KerrModulationDepth, KerrType};
+use crate::constants::KERR_NAME;
+use crate::kerr::background::{KerrBackgroundProvenance, load_kerr_background};
+use crate::kerr::field_background::{FieldBackgroundFit, KerrAngle};
 use crate::kerr::kerr_harmonics_analysis::{KerrHarmonicsAnalyser, KerrHarmonicsAnalysisInput};
 use crate::kerr::kerr_standard_analysis::{KerrStandardAnalyser, KerrStandardAnalysisInput};
-use crate::kerr::save::{get_kerr_headers, write_kerr_results};
+use crate::kerr::save::{
+    get_kerr_headers, get_kerr_vs_field_headers, write_kerr_results, write_kerr_vs_field,
+};
+use crate::phase::{InPhaseNoise, load_in_phase_noise};
 use crate::ui;
 use crate::{config::Config, utils::csv::read_csv};
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use rayon::prelude::*;
+use serde::Serialize;
 use std::time::Instant;
 
-pub fn run(cfg: &Config) -> Result<()> {
+#[derive(Debug, Clone, Serialize)]
+pub struct KerrProvenance {
+    pub method: KerrType,
+    pub channels: Vec<KerrChannelProvenance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<KerrBackgroundProvenance>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KerrChannelProvenance {
+    pub channel: u8,
+    pub modulation_depth_rad: f64,
+    pub modulation_depth_source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field_background: Option<FieldBackgroundFit>,
+}
+
+pub fn run(cfg: &Config) -> Result<Option<KerrProvenance>> {
     let ch = cfg.phase_signal_ch();
 
     if ch.is_empty() {
         ui::skipped("Kerr analysis: no phase signal channels specified");
-        return Ok(());
+        return Ok(None);
     }
 
     let t0 = Instant::now();
@@ -47,19 +72,25 @@ pub fn run(cfg: &Config) -> Result<()> {
     let data = parse_analysis_result_files(
         &all_data,
         cfg.roles.sensor_ch.len(),
-        LI_ROTATED_HEADER.len(),
+        cfg.lockin.harmonics.len() * 2,
         "phase-rotated lock-in results",
     )?;
 
-    run_kerr_analysis(
+    let noise = ch
+        .iter()
+        .map(|channel| load_in_phase_noise(cfg, *channel, &data.time))
+        .collect::<Result<Vec<_>>>()?;
+
+    let provenance = run_kerr_analysis(
         cfg,
         &data.time,
         &data.sensor_rate,
         &data.sensor_integral,
         &data.results,
+        &noise,
     )?;
 
-    Ok(())
+    Ok(Some(provenance))
 }
 
 pub fn run_kerr_analysis(
@@ -68,7 +99,8 @@ pub fn run_kerr_analysis(
     sensor_rate_ch: &[Vec<f64>],
     sensor_integral_ch: &[Vec<f64>],
     li_rotated_results: &[Vec<Vec<f64>>],
-) -> Result<()> {
+    noise: &[InPhaseNoise],
+) -> Result<KerrProvenance> {
     let paths = cfg.paths();
     let kerr_sensor_ch_index = cfg.kerr.use_sensor_ch;
 
@@ -103,9 +135,21 @@ pub fn run_kerr_analysis(
         format!("sensor integral column for ch{kerr_sensor_ch_index} is missing")
     })?;
     let factor = cfg.kerr.factor;
-    let mut kerr_results: Vec<Vec<f64>> = Vec::new();
+    let background = cfg
+        .kerr
+        .background_run
+        .as_deref()
+        .map(|run| load_kerr_background(cfg, run, t))
+        .transpose()?;
+    let mut angles: Vec<KerrAngle> = Vec::with_capacity(ch.len());
+    let mut channels = Vec::with_capacity(ch.len());
     let pb = ui::progress("running Kerr analysis", ch.len() as u64);
-    for (ch_i, li_rotated_result) in ch.iter().zip(li_rotated_results.iter()) {
+    for (position, ((ch_i, li_rotated_result), noise)) in ch
+        .iter()
+        .zip(li_rotated_results.iter())
+        .zip(noise)
+        .enumerate()
+    {
         pb.set_message(format!("Kerr analysis ch{ch_i}"));
         let fig_name = format!("{}_ch{}", KERR_NAME, ch_i);
         let output_path = if ch.len() == 1 {
@@ -114,34 +158,69 @@ pub fn run_kerr_analysis(
             paths.kerr_channel_plot(*ch_i)
         };
 
-        let kerr_i = match kerr_type {
-            KerrType::Standard => KerrStandardAnalyser {}
-                .analyse(KerrStandardAnalysisInput {
-                    plot: &cfg.plot,
-                    t,
-                    x: sensor_integral,
-                    ys: li_rotated_result,
-                    factor,
-                    xlabel: &concat_label,
-                    fig_name,
-                    output_path: &output_path,
-                })
-                .context("failed to run Kerr analysis")?,
-            KerrType::Harmonics => KerrHarmonicsAnalyser {}
-                .analyse(KerrHarmonicsAnalysisInput {
-                    plot: &cfg.plot,
-                    t,
-                    x: sensor_integral,
-                    ys: li_rotated_result,
-                    factor,
-                    xlabel: &concat_label,
-                    fig_name,
-                    output_path: &output_path,
-                })
-                .context("failed to run Kerr harmonics analysis")?,
+        let (kerr_i, modulation_depth_rad, modulation_depth_source) = match kerr_type {
+            KerrType::Standard => {
+                let (modulation_depth_rad, source) = match cfg.kerr.modulation_depth {
+                    KerrModulationDepth::Fixed(depth) => (depth, "config"),
+                    KerrModulationDepth::Harmonics => (
+                        estimate_modulation_depth(li_rotated_result, &cfg.lockin.harmonics)
+                            .with_context(|| {
+                                format!("failed to estimate the modulation depth for ch{ch_i}")
+                            })?,
+                        "harmonics",
+                    ),
+                };
+                let kerr = KerrStandardAnalyser {}
+                    .analyse(KerrStandardAnalysisInput {
+                        plot: &cfg.plot,
+                        t,
+                        x: sensor_integral,
+                        ys: li_rotated_result,
+                        harmonics: &cfg.lockin.harmonics,
+                        noise: &noise.std,
+                        factor,
+                        background: background
+                            .as_ref()
+                            .map(|background| background.channels[position].as_slice()),
+                        field_background: cfg.kerr.field_background.as_ref(),
+                        modulation_depth_rad,
+                        xlabel: &concat_label,
+                        fig_name,
+                        output_path: &output_path,
+                    })
+                    .context("failed to run Kerr analysis")?;
+                (kerr, modulation_depth_rad, source)
+            }
+            KerrType::Harmonics => {
+                let (kerr, representative_modulation_depth) = KerrHarmonicsAnalyser {}
+                    .analyse(KerrHarmonicsAnalysisInput {
+                        plot: &cfg.plot,
+                        t,
+                        x: sensor_integral,
+                        ys: li_rotated_result,
+                        harmonics: &cfg.lockin.harmonics,
+                        noise: &noise.std,
+                        factor,
+                        background: background
+                            .as_ref()
+                            .map(|background| background.channels[position].as_slice()),
+                        field_background: cfg.kerr.field_background.as_ref(),
+                        xlabel: &concat_label,
+                        fig_name,
+                        output_path: &output_path,
+                    })
+                    .context("failed to run Kerr harmonics analysis")?;
+                (kerr, 0.5 * representative_modulation_depth, "harmonics")
+            }
         };
+        channels.push(KerrChannelProvenance {
+            channel: *ch_i,
+            modulation_depth_rad,
+            modulation_depth_source,
+            field_background: kerr_i.field_background.clone(),
+        });
 
-        kerr_results.push(kerr_i);
+        angles.push(kerr_i);
         pb.inc(1);
     }
     let path = paths.kerr_csv();
@@ -152,14 +231,72 @@ pub fn run_kerr_analysis(
         t,
         sensor_rate_ch,
         sensor_integral_ch,
-        &kerr_results,
+        &angles,
         cfg.lockin.save_npy,
     )?;
+    if let Some(bin_width) = cfg.kerr.field_bin_width {
+        let headers = get_kerr_vs_field_headers(cfg, &concat_label);
+        let enbw_hz = noise.iter().map(|noise| noise.enbw_hz).collect::<Vec<_>>();
+        let sample_interval_s = match t {
+            [first, second, ..] => second - first,
+            _ => f64::NAN,
+        };
+        write_kerr_vs_field(
+            cfg,
+            &headers,
+            sensor_integral,
+            &angles,
+            &enbw_hz,
+            sample_interval_s,
+            bin_width,
+        )?;
+    }
 
     ui::finish_saved(pb, format!("Kerr analysis results for channels {:?}", ch));
     ui::success("Kerr analysis completed");
 
-    Ok(())
+    Ok(KerrProvenance {
+        method: *kerr_type,
+        channels,
+        background: background.map(|background| background.provenance),
+    })
+}
+
+/// Returns the retardation amplitude φm, half the Bessel argument estimated from harmonics 2, 4 and 6.
+fn estimate_modulation_depth(ys: &[Vec<f64>], harmonics: &[usize]) -> Result<f64> {
+    let harmonic = |harmonic: usize| in_phase_column(ys, harmonics, harmonic);
+    let argument =
+        pmoke_analysis_core::estimate_modulation_depth(harmonic(2)?, harmonic(4)?, harmonic(6)?)?;
+    Ok(0.5 * argument)
+}
+
+pub(crate) fn in_phase_column<'a>(
+    ys: &'a [Vec<f64>],
+    harmonics: &[usize],
+    harmonic: usize,
+) -> Result<&'a [f64]> {
+    let Some(position) = harmonics.iter().position(|&value| value == harmonic) else {
+        bail!("harmonic {harmonic} is not in lockin.harmonics {harmonics:?}");
+    };
+    ys.get(position * 2)
+        .map(Vec::as_slice)
+        .with_context(|| format!("missing rotated in-phase column for harmonic {harmonic}"))
+}
+
+/// Per-point in-phase noise standard deviation of one harmonic; NaN when
+/// unavailable.
+pub(crate) fn in_phase_std<'a>(
+    noise: &'a [Vec<f64>],
+    harmonics: &[usize],
+    harmonic: usize,
+) -> Result<&'a [f64]> {
+    let Some(position) = harmonics.iter().position(|&value| value == harmonic) else {
+        bail!("harmonic {harmonic} is not in lockin.harmonics {harmonics:?}");
+    };
+    noise
+        .get(position)
+        .map(Vec::as_slice)
+        .with_context(|| format!("missing lock-in noise for harmonic {harmonic}"))
 }
 
 fn kerr_sensor_position(cfg: &Config) -> Result<usize> {
@@ -175,7 +312,7 @@ fn kerr_sensor_position(cfg: &Config) -> Result<usize> {
 
 #[cfg(test)]
 mod tests {
-    use super::kerr_sensor_position;
+    use super::{estimate_modulation_depth, in_phase_column, kerr_sensor_position};
     use crate::test_support::test_config;
 
     #[test]
@@ -188,4 +325,37 @@ mod tests {
         cfg.kerr.use_sensor_ch = 1;
         assert!(kerr_sensor_position(&cfg).is_err());
     }
+
+    #[test]
+    fn in_phase_column_follows_configured_harmonic_order() {
+        let ys = vec![
+            vec![1.0],
+            vec![-1.0],
+            vec![2.0],
+            vec![-2.0],
+            vec![8.0],
+            vec![-8.0],
+        ];
+
+        assert_eq!(in_phase_column(&ys, &[1, 2, 8], 8).unwrap(), [8.0]);
+        assert_eq!(in_phase_column(&ys, &[1, 2, 8], 2).unwrap(), [2.0]);
+        assert!(in_phase_column(&ys, &[1, 2, 8], 4).is_err());
+    }
+
+    #[test]
+    fn modulation_depth_estimate_is_half_the_bessel_argument() {
+        // J2, J4, and J6 at 2φm = 1.84 (φm = 0.92) with a zero Kerr angle.
+        let ys = vec![
+            vec![0.315_745_306_087_972_3; 4],
+            vec![0.0; 4],
+            vec![0.025_139_158_519_404_087; 4],
+            vec![0.0; 4],
+            vec![0.000_745_551_998_014_054_3; 4],
+            vec![0.0; 4],
+        ];
+
+        let depth = estimate_modulation_depth(&ys, &[2, 4, 6]).unwrap();
+        assert!((depth - 0.92).abs() < 1.0e-3, "got {depth}");
+        assert!(estimate_modulation_depth(&ys, &[2, 4, 5]).is_err());
+    }
 }
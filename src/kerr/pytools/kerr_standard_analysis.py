@@ -1,6 +1,5 @@
 import numpy as np
 from numpy.typing import NDArray
-from scipy.special import jn
 
 
 def _load_gsplot():
@@ -68,19 +67,10 @@ class KerrStandardAnalyser:
         pass
 
     @staticmethod
-    def calculate(a1: NDArray, a2: NDArray, phim=0.92) -> NDArray:
-        frac_top = jn(2, 2 * phim) * a1
-        frac_bottom = jn(1, 2 * phim) * a2
-        with np.errstate(divide="ignore", invalid="ignore"):
-            ratio = np.divide(frac_top, frac_bottom)
-        return (1 / 2) * np.arctan(ratio)
-
-    def analyse(
-        self,
+    def plot(
         t: NDArray,
         x: NDArray,
-        ys: NDArray,
-        factor: float,
+        kerr: NDArray,
         xlabel: str,
         fig_name: str,
         save: bool,
@@ -89,45 +79,30 @@ class KerrStandardAnalyser:
         max_points: int,
         decimation: str,
     ):
-
-        li1_in, li1_out = ys[0], ys[1]
-        li2_in, li2_out = ys[2], ys[3]
-        li3_in, li3_out = ys[4], ys[5]
-        li4_in, li4_out = ys[6], ys[7]
-        li5_in, li5_out = ys[8], ys[9]
-        li6_in, li6_out = ys[10], ys[11]
-
-        kerr = factor * self.calculate(li1_in, li2_in)
-
-        plot_error = None
-        if save or interactive:
-            try:
-                gs = _load_gsplot()
-
-                indices = decimation_indices(kerr, max_points, decimation)
-                t_plot = t[indices]
-                x_plot = x[indices]
-                kerr_plot = kerr[indices]
-
-                axs = gs.axes(
-                    True,
-                    size=(6, 6),
-                    mosaic="A",
-                    ion=interactive,
-                )
-
-                gs.scatter_colormap(axs[0], x_plot, kerr_plot * 1e3, t_plot)
-                axs[0].grid()
-
-                title = fig_name + " using Standard"
-                gs.title(title)
-
-                gs.label([[f"{xlabel}", "$\\theta_{\\rm K}$ (mrad)"]])
-                finish_plot(output_path, interactive)
-            except Exception as exc:
-                plot_error = str(exc)
-
-        return {
-            "kerr": kerr,
-            "plot_error": plot_error,
-        }
+        if not (save or interactive):
+            return None
+        try:
+            gs = _load_gsplot()
+
+            indices = decimation_indices(kerr, max_points, decimation)
+            t_plot = t[indices]
+            x_plot = x[indices]
+            kerr_plot = kerr[indices]
+
+            axs = gs.axes(
+                True,
+                size=(6, 6),
+                mosaic="A",
+                ion=interactive,
+            )
+
+            gs.scatter_colormap(axs[0], x_plot, kerr_plot * 1e3, t_plot)
+            axs[0].grid()
+            title = fig_name + " using Standard"
+            gs.title(title)
+
+            gs.label([[f"{xlabel}", "$\\theta_{\\rm K}$ (mrad)"]])
+            finish_plot(output_path, interactive)
+            return None
+        except Exception as exc:
+            return str(exc)
@@ -1,46 +1,187 @@
 use crate::analysis_results::{build_analysis_headers, write_analysis_results};
 use crate::config::Config;
-use crate::constants::KERR_HEADER;
-use anyhow::Result;
-use std::path::Path;
+use crate::constants::{
+    FIELD_BIN_COUNT_HEADER, KERR_ERR_HEADER, KERR_HEADER, KERR_RAW_HEADER, KERR_STD_HEADER,
+};
+use crate::kerr::field_background::KerrAngle;
+use crate::utils::csv::{write_csv, write_npy};
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
 
 pub fn get_kerr_headers(cfg: &Config) -> Result<Vec<String>> {
     let use_signal_ch = cfg.phase_signal_ch();
     let kerr_headers = use_signal_ch
         .iter()
         .map(|ch| format!("Ch{} {}", ch, KERR_HEADER))
+        .chain(
+            use_signal_ch
+                .iter()
+                .map(|ch| format!("Ch{ch} {KERR_ERR_HEADER}")),
+        )
+        .chain(
+            use_signal_ch
+                .iter()
+                .filter(|_| cfg.kerr.field_background.is_some())
+                .map(|ch| format!("Ch{ch} {KERR_RAW_HEADER}")),
+        )
         .collect::<Vec<_>>();
     build_analysis_headers(cfg, kerr_headers)
 }
 
+/// Writes the Kerr angles, their errors, and, with a field background, the
+/// uncorrected angles.
 pub fn write_kerr_results<P: AsRef<Path>>(
     fname: P,
     headers: &[String],
     t: &[f64],
     s_rate: &[Vec<f64>],
     s_integral: &[Vec<f64>],
-    kerr_results: &[Vec<f64>],
+    angles: &[KerrAngle],
     save_npy: bool,
 ) -> Result<()> {
-    write_analysis_results(
-        fname,
-        headers,
-        t,
-        s_rate,
-        s_integral,
-        kerr_results,
-        save_npy,
-    )
+    let columns = angles
+        .iter()
+        .map(|angle| angle.values_rad.clone())
+        .chain(angles.iter().map(|angle| angle.err_rad.clone()))
+        .chain(angles.iter().filter_map(|angle| angle.raw_rad.clone()))
+        .collect::<Vec<_>>();
+    write_analysis_results(fname, headers, t, s_rate, s_integral, &columns, save_npy)
+}
+
+/// Header row for a field-binned branch table: field, count, then mean, std,
+/// and propagated error per channel.
+pub fn get_kerr_vs_field_headers(cfg: &Config, field_header: &str) -> Vec<String> {
+    let mut headers = vec![field_header.to_string(), FIELD_BIN_COUNT_HEADER.to_string()];
+    for ch in cfg.phase_signal_ch() {
+        headers.push(format!("Ch{ch} {KERR_HEADER}"));
+        headers.push(format!("Ch{ch} {KERR_STD_HEADER}"));
+        headers.push(format!("Ch{ch} {KERR_ERR_HEADER}"));
+    }
+    if cfg.kerr.field_background.is_some() {
+        for ch in cfg.phase_signal_ch() {
+            headers.push(format!("Ch{ch} {KERR_RAW_HEADER}"));
+        }
+    }
+    headers
+}
+
+/// Splits the Kerr trace into field sweep branches and writes one binned table per branch.
+/// With a field background the corrected angle is binned, followed by the
+/// bin means of the uncorrected angle.
+///
+/// The lock-in output oversamples its low-pass filter, so neighbouring samples
+/// share noise. The error of a bin mean is `sqrt(mean err² / n)` with `n` the
+/// number of independent samples in the bin, `enbw_hz · count · Δt` limited to
+/// `[1, count]`; every sample counts when the bandwidth is unknown. It is NaN
+/// when any error in the branch is unavailable.
+pub fn write_kerr_vs_field(
+    cfg: &Config,
+    headers: &[String],
+    field: &[f64],
+    angles: &[KerrAngle],
+    enbw_hz: &[f64],
+    sample_interval_s: f64,
+    bin_width: f64,
+) -> Result<Vec<PathBuf>> {
+    let branches = pmoke_analysis_core::split_field_branches(field)
+        .context("failed to split the Kerr sensor integral into field branches")?;
+    let header_refs = headers.iter().map(String::as_str).collect::<Vec<_>>();
+    let paths = cfg.paths();
+    let mut written = Vec::with_capacity(branches.len());
+    for branch in branches {
+        let range = branch.samples.clone();
+        let variances = angles
+            .iter()
+            .map(|angle| {
+                angle.err_rad[range.clone()]
+                    .iter()
+                    .map(|error| error * error)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut values = angles
+            .iter()
+            .map(|angle| &angle.values_rad[range.clone()])
+            .collect::<Vec<_>>();
+        let variance_columns = variances
+            .iter()
+            .map(|variance| {
+                variance.iter().all(|value| value.is_finite()).then(|| {
+                    values.push(variance);
+                    values.len() - 1
+                })
+            })
+            .collect::<Vec<_>>();
+        let raw_columns = angles
+            .iter()
+            .filter_map(|angle| angle.raw_rad.as_ref())
+            .map(|raw| {
+                values.push(&raw[range.clone()]);
+                values.len() - 1
+            })
+            .collect::<Vec<_>>();
+        let bins = pmoke_analysis_core::bin_by_field(&field[range], &values, bin_width)
+            .with_context(|| format!("failed to bin the {} field branch", branch.name()))?;
+        let counts = bins
+            .counts
+            .iter()
+            .map(|&count| count as f64)
+            .collect::<Vec<_>>();
+        let errors = variance_columns
+            .iter()
+            .zip(enbw_hz)
+            .map(|(column, &enbw_hz)| match column {
+                Some(column) => bins.means[*column]
+                    .iter()
+                    .zip(&counts)
+                    .map(|(variance, &count)| {
+                        let independent = if enbw_hz > 0.0 && sample_interval_s > 0.0 {
+                            (enbw_hz * count * sample_interval_s).clamp(1.0, count)
+                        } else {
+                            count
+                        };
+                        (variance / independent).sqrt()
+                    })
+                    .collect(),
+                None => vec![f64::NAN; counts.len()],
+            })
+            .collect::<Vec<Vec<f64>>>();
+        let mut columns = vec![bins.centers.as_slice(), counts.as_slice()];
+        for ((mean, std), error) in bins.means.iter().zip(&bins.stds).zip(&errors) {
+            columns.push(mean);
+            columns.push(std);
+            columns.push(error);
+        }
+        columns.extend(
+            raw_columns
+                .iter()
+                .map(|&column| bins.means[column].as_slice()),
+        );
+
+        let path = paths.kerr_vs_field_csv(branch.name());
+        let npy_path = path.with_extension("npy");
+        if path.exists() || (cfg.lockin.save_npy && npy_path.exists()) {
+            bail!("analysis output already exists: {}", path.display());
+        }
+        write_csv(&path, &header_refs, &columns)?;
+        if cfg.lockin.save_npy {
+            write_npy(&npy_path, &columns)?;
+        }
+        written.push(path);
+    }
+    Ok(written)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::get_kerr_headers;
+    use super::{get_kerr_headers, get_kerr_vs_field_headers, write_kerr_vs_field};
+    use crate::config::KerrFieldBackground;
+    use crate::kerr::field_background::KerrAngle;
     use crate::test_support::test_config;
 
     #[test]
     fn kerr_headers_use_rate_integral_result_order() {
-        let cfg = test_config(vec![1, 2], vec![3, 4]);
+        let mut cfg = test_config(vec![1, 2], vec![3, 4]);
 
         let headers = get_kerr_headers(&cfg).unwrap();
 
@@ -54,7 +195,108 @@ mod tests {
                 "ch2 integral (T)".to_string(),
                 "Ch3 Kerr angle (rad)".to_string(),
                 "Ch4 Kerr angle (rad)".to_string(),
+                "Ch3 kerr_err (rad)".to_string(),
+                "Ch4 kerr_err (rad)".to_string(),
             ]
         );
+
+        cfg.kerr.field_background = Some(KerrFieldBackground::Linear {
+            ranges: vec![[1.0, 2.0]],
+        });
+        let headers = get_kerr_headers(&cfg).unwrap();
+        assert_eq!(
+            headers[headers.len() - 2..],
+            ["Ch3 Kerr angle raw (rad)", "Ch4 Kerr angle raw (rad)"]
+        );
+    }
+
+    #[test]
+    fn kerr_vs_field_writes_one_binned_table_per_branch() {
+        let dir = std::env::temp_dir().join(format!(
+            "pmoke_kerr_vs_field_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let mut cfg = test_config(vec![1], vec![3]);
+        cfg.set_artifact_root(dir.clone());
+        cfg.lockin.save_npy = true;
+        let headers = get_kerr_vs_field_headers(&cfg, "B (T)");
+        assert_eq!(
+            headers,
+            [
+                "B (T)",
+                "count",
+                "Ch3 Kerr angle (rad)",
+                "Ch3 Kerr angle std (rad)",
+                "Ch3 kerr_err (rad)"
+            ]
+        );
+
+        let field = [0.0, 0.4, 1.1, 2.0, 0.9, 0.2];
+        let kerr = vec![0.0, 0.2, 1.0, 3.0, 2.0, 1.0];
+        let angles = [KerrAngle::new(
+            kerr.clone(),
+            vec![0.3, 0.4, 0.1, 0.2, 0.1, 0.1],
+        )];
+        let unknown = [f64::NAN];
+        let written =
+            write_kerr_vs_field(&cfg, &headers, &field, &angles, &unknown, 1.0e-3, 1.0).unwrap();
+        let paths = cfg.paths();
+        assert_eq!(
+            written,
+            [
+                paths.kerr_vs_field_csv("up"),
+                paths.kerr_vs_field_csv("down")
+            ]
+        );
+        let (_, up) = crate::utils::csv::read_csv_with_headers(&written[0]).unwrap();
+        assert_eq!(up[0], [0.0, 1.0, 2.0]);
+        assert_eq!(up[1], [2.0, 1.0, 1.0]);
+        assert_eq!(up[2], [0.1, 1.0, 3.0]);
+        assert!((up[3][0] - 0.1).abs() < 1.0e-12);
+        assert_eq!(up[3][1..], [0.0, 0.0]);
+        assert!((up[4][0] - 0.25).abs() < 1.0e-12);
+        assert!((up[4][2] - 0.2).abs() < 1.0e-12);
+        assert!(written[1].with_extension("npy").exists());
+        assert!(
+            write_kerr_vs_field(&cfg, &headers, &field, &angles, &unknown, 1.0e-3, 1.0).is_err()
+        );
+
+        // Two samples 0.5 s apart under a 1 Hz filter carry one independent sample.
+        std::fs::remove_dir_all(&dir).unwrap();
+        let written =
+            write_kerr_vs_field(&cfg, &headers, &field, &angles, &[1.0], 0.5, 1.0).unwrap();
+        let (_, up) = crate::utils::csv::read_csv_with_headers(&written[0]).unwrap();
+        assert!((up[4][0] - 0.125_f64.sqrt()).abs() < 1.0e-12);
+        assert!((up[4][2] - 0.2).abs() < 1.0e-12);
+
+        let unavailable = [KerrAngle::new(kerr.clone(), vec![f64::NAN; field.len()])];
+        std::fs::remove_dir_all(&dir).unwrap();
+        let written =
+            write_kerr_vs_field(&cfg, &headers, &field, &unavailable, &unknown, 1.0e-3, 1.0)
+                .unwrap();
+        let (_, up) = crate::utils::csv::read_csv_with_headers(&written[0]).unwrap();
+        assert_eq!(up[2], [0.1, 1.0, 3.0]);
+        assert!(up[4].iter().all(|error| error.is_nan()));
+
+        cfg.kerr.field_background = Some(KerrFieldBackground::Linear {
+            ranges: vec![[1.5, 2.5]],
+        });
+        let headers = get_kerr_vs_field_headers(&cfg, "B (T)");
+        assert_eq!(headers[5], "Ch3 Kerr angle raw (rad)");
+        let mut corrected = KerrAngle::new(kerr.clone(), vec![0.1; field.len()]);
+        corrected.raw_rad = Some(kerr.iter().map(|value| value + 1.0).collect());
+        std::fs::remove_dir_all(&dir).unwrap();
+        let written =
+            write_kerr_vs_field(&cfg, &headers, &field, &[corrected], &unknown, 1.0e-3, 1.0)
+                .unwrap();
+        let (_, up) = crate::utils::csv::read_csv_with_headers(&written[0]).unwrap();
+        assert_eq!(up.len(), headers.len());
+        assert_eq!(up[2], [0.1, 1.0, 3.0]);
+        assert_eq!(up[5], [1.1, 2.0, 4.0]);
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
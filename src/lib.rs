@@ -64,6 +64,26 @@ fn run_with(args: Cli) -> Result<()> {
         return commands::instruments::run(command);
     }
 
+    if let Some(Command::Stack {
+        runs,
+        stage,
+        align,
+        output,
+    }) = args.command.as_ref()
+    {
+        return commands::stack::run(runs, *stage, *align, output, args.force);
+    }
+
+    #[cfg(feature = "hw-core")]
+    if let Some(Command::Campaign { campaign }) = args.command.as_ref() {
+        if args.run_dir.is_some() || args.force {
+            bail!(
+                "campaign run directories come from the campaign file; --run-dir and --force are not supported"
+            );
+        }
+        return commands::campaign::run(campaign, std::path::Path::new(&args.config));
+    }
+
     if let Some(Command::Bench { command }) = args.command.as_ref() {
         return commands::bench::run(command, args.run_dir.as_deref(), args.force);
     }
@@ -151,6 +171,8 @@ fn run_with(args: Cli) -> Result<()> {
                 | Command::Raw { .. }
                 | Command::Instruments { .. }
                 | Command::Bench { .. }
+                | Command::Stack { .. }
+                | Command::Campaign { .. }
                 | Command::Export { .. }
                 | Command::Doctor { .. },
             ) => unreachable!(),
@@ -174,6 +196,10 @@ fn run_with(args: Cli) -> Result<()> {
                 ValidationTarget::Screenshot,
                 commands::screenshot::screenshot,
             ),
+            Some(Command::Scope { command }) => {
+                config::validate_for_target(&cfg, ValidationTarget::Scope)?;
+                commands::scope::run(&cfg, command)
+            }
             Some(Command::Automeasure) => run_validated(
                 &cfg,
                 ValidationTarget::Automeasure,
@@ -218,6 +244,7 @@ fn run_with(args: Cli) -> Result<()> {
                 | Command::Raw { .. }
                 | Command::Instruments { .. }
                 | Command::Bench { .. }
+                | Command::Stack { .. }
                 | Command::Export { .. }
                 | Command::Doctor { .. },
             ) => unreachable!(),
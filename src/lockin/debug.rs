@@ -1,7 +1,5 @@
 use crate::config::{Config, LockinLpfKind, Window};
-use crate::lockin::lockin_core::{
-    HarmonicLockinResult, legacy_boxcar_enbw_hz, legacy_boxcar_response_abs,
-};
+use crate::lockin::lockin_core::{HarmonicLockinResult, lpf_enbw_hz, lpf_response_abs};
 use crate::lockin::lockin_params::LockinParams;
 use crate::ui;
 use anyhow::{Context, Result, bail};
@@ -57,7 +55,7 @@ fn prepare_debug_dir(
         .join(label)
         .join(format!(
             "{}_ch{}_h{}",
-            lpf_kind_name(cfg.lockin.lpf_kind),
+            cfg.lockin.lpf_kind.as_str(),
             signal_ch,
             harmonic
         ));
@@ -89,20 +87,28 @@ fn prepare_debug_dir(
 }
 
 fn auto_label(cfg: &Config, params: LockinParams) -> String {
+    let cutoff = params
+        .cutoff_hz
+        .map_or_else(|| "none".to_string(), |cutoff_hz| format!("{cutoff_hz:.6}"));
+    let shape = match cfg.lockin.lpf_kind {
+        LockinLpfKind::BoxcarLegacy | LockinLpfKind::WindowedSinc => {
+            format!("half_{:.6}", cfg.lockin.lpf_half_window_cycles)
+        }
+        LockinLpfKind::RcCascade => format!("order_{}", cfg.lockin.lpf_order),
+        LockinLpfKind::Gaussian => "gaussian".to_string(),
+    };
+    let phase = if params.filter.is_some() && !cfg.lockin.lpf_zero_phase {
+        "_causal"
+    } else {
+        ""
+    };
     format!(
-        "{}_{}_cutoff_none_half_{:.6}",
-        lpf_kind_name(cfg.lockin.lpf_kind),
+        "{}_{}_cutoff_{cutoff}_{shape}{phase}",
+        cfg.lockin.lpf_kind.as_str(),
         params.cutoff_source.as_str(),
-        cfg.lockin.lpf_half_window_cycles
     )
 }
 
-fn lpf_kind_name(kind: LockinLpfKind) -> &'static str {
-    match kind {
-        LockinLpfKind::BoxcarLegacy => "boxcar_legacy",
-    }
-}
-
 fn write_metadata(
     dir: &Path,
     cfg: &Config,
@@ -115,7 +121,7 @@ fn write_metadata(
         ("harmonic".to_string(), harmonic.to_string()),
         (
             "lpf_kind".to_string(),
-            lpf_kind_name(cfg.lockin.lpf_kind).to_string(),
+            cfg.lockin.lpf_kind.as_str().to_string(),
         ),
         ("f_ref".to_string(), params.f_ref.to_string()),
         ("dt".to_string(), params.dt.to_string()),
@@ -137,10 +143,27 @@ fn write_metadata(
             "fallback_used".to_string(),
             params.fallback_used.to_string(),
         ),
-        ("cutoff_hz".to_string(), "NaN".to_string()),
+        (
+            "cutoff_hz".to_string(),
+            params.cutoff_hz.unwrap_or(f64::NAN).to_string(),
+        ),
+        (
+            "lpf_order".to_string(),
+            params
+                .filter
+                .filter(|filter| filter.kind == pmoke_analysis_core::LowPassKind::RcCascade)
+                .map_or_else(|| "NaN".to_string(), |filter| filter.order.to_string()),
+        ),
+        (
+            "zero_phase".to_string(),
+            params
+                .filter
+                .is_none_or(|filter| filter.zero_phase)
+                .to_string(),
+        ),
         (
             "estimated_enbw_hz".to_string(),
-            legacy_boxcar_enbw_hz(params).to_string(),
+            lpf_enbw_hz(params).to_string(),
         ),
     ];
 
@@ -157,7 +180,7 @@ fn write_filter_response(dir: &Path, params: LockinParams) -> Result<()> {
     let max_freq = 0.5 * params.output_rate;
     for idx in 0..=RESPONSE_BINS {
         let freq = max_freq * idx as f64 / RESPONSE_BINS as f64;
-        let response = legacy_boxcar_response_abs(params, freq);
+        let response = lpf_response_abs(params, freq);
         let response_db = if response > 0.0 {
             20.0 * response.log10()
         } else {
@@ -185,10 +208,7 @@ fn write_baseband_psd(
         return Ok(());
     };
 
-    let window = cfg
-        .lockin
-        .snr_background_window
-        .unwrap_or(cfg.pulse.bg_window_before);
+    let window = cfg.lockin_noise_window();
     let samples = complex_window(t_raw, mixed_signal, window);
     if samples
         .iter()
@@ -262,10 +282,7 @@ fn write_snr_summary(
     t_output: &[f64],
     result: &HarmonicLockinResult,
 ) -> Result<()> {
-    let bg_window = cfg
-        .lockin
-        .snr_background_window
-        .unwrap_or(cfg.pulse.bg_window_before);
+    let bg_window = cfg.lockin_noise_window();
     let signal_window = cfg
         .lockin
         .snr_signal_window
@@ -3,6 +3,7 @@ use crate::lockin::lockin_params::LockinParams;
 use crate::utils::time_axis::TimeAxisRef;
 use anyhow::{Result, anyhow, bail};
 use num_complex::Complex64;
+use rayon::prelude::*;
 
 pub struct LockinProcessor<'a> {
     t: TimeAxisRef<'a>,
@@ -49,9 +50,6 @@ impl<'a> LockinProcessor<'a> {
         if !omega_tref.is_finite() {
             bail!("lock-in reference phase must be finite");
         }
-        if !matches!(lockin.lpf_kind, LockinLpfKind::BoxcarLegacy) {
-            bail!("the active runtime supports only the boxcar_legacy LPF");
-        }
         let finite_data = pmoke_analysis_core::FiniteSignal::new(data)?;
         let params = LockinParams::from_geometry(
             t.len(),
@@ -75,7 +73,10 @@ impl<'a> LockinProcessor<'a> {
         harmonic: usize,
         _include_debug_data: bool,
     ) -> HarmonicLockinResult {
-        self.compute_legacy_lockin_pair(harmonic)
+        match self.params.filter {
+            Some(filter) => self.compute_lowpass_lockin_pair(harmonic, filter),
+            None => self.compute_legacy_lockin_pair(harmonic),
+        }
     }
 
     pub fn output_times(&self) -> Vec<f64> {
@@ -98,22 +99,7 @@ impl<'a> LockinProcessor<'a> {
     }
 
     pub fn summary_lines(&self) -> Vec<String> {
-        vec![
-            format!("lpf_kind={:?}", self.params.lpf_kind),
-            format!("f_ref={:.6e} Hz", self.params.f_ref),
-            format!(
-                "half_window={:.6e} s, support={:.6e} s, tap_count={}",
-                self.params.t_half,
-                2.0 * self.params.t_half,
-                2 * self.params.n_half + 1
-            ),
-            format!(
-                "sample_rate={:.6e} Hz, output_rate={:.6e} Hz, stride_samples={}",
-                self.params.sample_rate, self.params.output_rate, self.params.stride
-            ),
-            "cutoff=none".to_string(),
-            "estimated_enbw=legacy_boxcar".to_string(),
-        ]
+        summary_lines(self.params)
     }
 
     fn compute_legacy_lockin_pair(&self, harmonic: usize) -> HarmonicLockinResult {
@@ -144,6 +130,151 @@ impl<'a> LockinProcessor<'a> {
             mixed_signal: None,
         }
     }
+
+    fn compute_lowpass_lockin_pair(
+        &self,
+        harmonic: usize,
+        filter: pmoke_analysis_core::LowPassSettings,
+    ) -> HarmonicLockinResult {
+        let output = pmoke_analysis_core::analyze_lowpass_pair_finite(
+            self.finite_data,
+            pmoke_analysis_core::LowPassLockinSettings {
+                start_time_s: self.t.value_at(0),
+                sample_interval_s: self.params.dt,
+                reference_frequency_hz: self.params.f_ref,
+                reference_phase_rad: self.omega_tref,
+                stride_samples: self.params.stride,
+                harmonic,
+                filter,
+            },
+        )
+        .expect("filter settings and waveform are validated by LockinProcessor::new");
+        debug_assert_eq!(
+            output.metadata.first_input_index,
+            self.params.i_start * self.params.stride
+        );
+        HarmonicLockinResult {
+            li_x: output.x,
+            li_y: output.y,
+            mixed_signal: None,
+        }
+    }
+}
+
+fn summary_lines(params: LockinParams) -> Vec<String> {
+    let cutoff = match params.cutoff_hz {
+        Some(cutoff_hz) => format!(
+            "cutoff={cutoff_hz:.6e} Hz ({})",
+            params.cutoff_source.as_str()
+        ),
+        None => "cutoff=none".to_string(),
+    };
+    let enbw = match params.filter {
+        Some(_) => format!("estimated_enbw={:.6e} Hz", lpf_enbw_hz(params)),
+        None => "estimated_enbw=legacy_boxcar".to_string(),
+    };
+    vec![
+        format!("lpf_kind={:?}", params.lpf_kind),
+        format!("f_ref={:.6e} Hz", params.f_ref),
+        format!(
+            "half_window={:.6e} s, support={:.6e} s, tap_count={}",
+            params.t_half,
+            2.0 * params.t_half,
+            2 * params.n_half + 1
+        ),
+        format!(
+            "sample_rate={:.6e} Hz, output_rate={:.6e} Hz, stride_samples={}",
+            params.sample_rate, params.output_rate, params.stride
+        ),
+        cutoff,
+        enbw,
+    ]
+}
+
+/// Boxcar lock-in over a signal that arrives in chunks; every harmonic keeps
+/// only its rolling window, so memory does not grow with record length.
+pub struct LockinStreamProcessor {
+    params: LockinParams,
+    streams: Vec<pmoke_analysis_core::BoxcarLegacyStream>,
+}
+
+impl LockinStreamProcessor {
+    pub fn new(t: TimeAxisRef<'_>, f_ref: f64, omega_tref: f64, lockin: &Lockin) -> Result<Self> {
+        if lockin.lpf_kind != LockinLpfKind::BoxcarLegacy {
+            bail!("streaming lock-in supports only the boxcar_legacy LPF");
+        }
+        if !t.value_at(0).is_finite() {
+            bail!("lock-in start time must be finite");
+        }
+        if !omega_tref.is_finite() {
+            bail!("lock-in reference phase must be finite");
+        }
+        let params = LockinParams::from_geometry(
+            t.len(),
+            t.dt()
+                .ok_or_else(|| anyhow!("lock-in time axis must contain at least two samples"))?,
+            f_ref,
+            lockin,
+        )?;
+        validate_output_index_range(params)?;
+        let streams = lockin
+            .harmonics
+            .iter()
+            .map(|&harmonic| {
+                pmoke_analysis_core::BoxcarLegacyStream::new(
+                    pmoke_analysis_core::BoxcarLegacySettings {
+                        start_time_s: t.value_at(0),
+                        sample_interval_s: params.dt,
+                        reference_frequency_hz: params.f_ref,
+                        reference_phase_rad: omega_tref,
+                        half_window_cycles: params.t_half * params.f_ref,
+                        stride_samples: params.stride,
+                        harmonic,
+                    },
+                    t.len(),
+                )
+            })
+            .collect::<pmoke_analysis_core::Result<Vec<_>>>()?;
+        Ok(Self { params, streams })
+    }
+
+    pub fn params(&self) -> LockinParams {
+        self.params
+    }
+
+    pub fn output_index_range(&self) -> (usize, usize) {
+        (self.params.i_start, self.params.i_end)
+    }
+
+    pub fn summary_lines(&self) -> Vec<String> {
+        summary_lines(self.params)
+    }
+
+    /// Feeds the next chunk to every harmonic; call inside the worker pool.
+    pub fn push(&mut self, chunk: &[f64]) -> Result<()> {
+        self.streams
+            .par_iter_mut()
+            .try_for_each(|stream| stream.push(chunk))?;
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<Vec<HarmonicLockinResult>> {
+        self.streams
+            .into_iter()
+            .map(|stream| {
+                let output = stream.finish()?;
+                debug_assert_eq!(
+                    output.metadata.first_input_index,
+                    self.params.i_start * self.params.stride
+                );
+                Ok(HarmonicLockinResult {
+                    li_x: output.x,
+                    li_y: output.y,
+                    mixed_signal: None,
+                })
+            })
+            .collect()
+    }
 }
 
 fn validate_output_index_range(params: LockinParams) -> Result<()> {
@@ -166,11 +297,28 @@ fn enbw_hz(weights: &[f64], sample_rate: f64) -> f64 {
     }
 }
 
-pub(crate) fn legacy_boxcar_enbw_hz(params: LockinParams) -> f64 {
-    enbw_hz(&legacy_boxcar_weights(params), params.sample_rate)
+pub(crate) fn lpf_enbw_hz(params: LockinParams) -> f64 {
+    match designed_filter(params) {
+        Some(filter) => filter.enbw_hz(),
+        None => enbw_hz(&legacy_boxcar_weights(params), params.sample_rate),
+    }
+}
+
+pub(crate) fn lpf_response_abs(params: LockinParams, freq_hz: f64) -> f64 {
+    match designed_filter(params) {
+        Some(filter) => filter.response_abs(freq_hz),
+        None => legacy_boxcar_response_abs(params, freq_hz),
+    }
+}
+
+fn designed_filter(params: LockinParams) -> Option<pmoke_analysis_core::LowPassFilter> {
+    params.filter.map(|settings| {
+        pmoke_analysis_core::LowPassFilter::new(settings, params.dt)
+            .expect("filter settings are validated by LockinParams")
+    })
 }
 
-pub(crate) fn legacy_boxcar_response_abs(params: LockinParams, freq_hz: f64) -> f64 {
+fn legacy_boxcar_response_abs(params: LockinParams, freq_hz: f64) -> f64 {
     let weights = legacy_boxcar_weights(params);
     let center = (weights.len() / 2) as isize;
     let omega = 2.0 * std::f64::consts::PI * freq_hz / params.sample_rate;
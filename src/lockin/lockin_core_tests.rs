@@ -1,12 +1,17 @@
 use super::*;
+use crate::config::{LockinCutoff, LockinLpfKind};
 use std::f64::consts::PI;
 
 fn test_lockin() -> Lockin {
     Lockin {
         workers: 1,
         stride_samples: 10,
+        harmonics: vec![1, 2, 3, 4, 5, 6],
         lpf_kind: LockinLpfKind::BoxcarLegacy,
         lpf_half_window_cycles: 1.0,
+        lpf_cutoff: None,
+        lpf_order: pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+        lpf_zero_phase: true,
         lpf_debug_output: false,
         lpf_debug_label: None,
         lpf_debug_overwrite: false,
@@ -77,13 +82,96 @@ fn boxcar_processor_rejects_trace_without_output_samples() {
     assert!(error.to_string().contains("output range is empty"));
 }
 
+#[test]
+fn stream_processor_matches_in_memory_boxcar_bit_for_bit() {
+    let (time, signal) = test_waveform();
+    let lockin = test_lockin();
+    let batch = LockinProcessor::new(&time, &signal, 1_000.0, 0.2, &lockin).unwrap();
+    let mut stream =
+        LockinStreamProcessor::new(TimeAxisRef::from(&time), 1_000.0, 0.2, &lockin).unwrap();
+    assert_eq!(stream.output_index_range(), batch.output_index_range());
+
+    for chunk in signal.chunks(777) {
+        stream.push(chunk).unwrap();
+    }
+    let streamed = stream.finish().unwrap();
+
+    for (&harmonic, result) in lockin.harmonics.iter().zip(streamed) {
+        let expected = batch.compute_harmonic_detailed(harmonic, false);
+        let bits = |values: &[f64]| values.iter().map(|v| v.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&result.li_x), bits(&expected.li_x));
+        assert_eq!(bits(&result.li_y), bits(&expected.li_y));
+    }
+}
+
+#[test]
+fn stream_processor_rejects_designed_filters_and_short_input() {
+    let (time, signal) = test_waveform();
+    let mut lockin = test_lockin();
+    lockin.lpf_kind = LockinLpfKind::RcCascade;
+    let error = LockinStreamProcessor::new(TimeAxisRef::from(&time), 1_000.0, 0.0, &lockin)
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("boxcar_legacy"));
+
+    let mut stream =
+        LockinStreamProcessor::new(TimeAxisRef::from(&time), 1_000.0, 0.0, &test_lockin()).unwrap();
+    stream.push(&signal[..signal.len() / 2]).unwrap();
+    assert!(stream.finish().is_err());
+}
+
 #[test]
 fn legacy_boxcar_response_is_unity_at_zero_frequency() {
     let (time, signal) = test_waveform();
     let processor = LockinProcessor::new(&time, &signal, 1_000.0, 0.0, &test_lockin()).unwrap();
     let params = processor.params();
 
-    assert!((legacy_boxcar_response_abs(params, 0.0) - 1.0).abs() < 1.0e-12);
-    assert!(legacy_boxcar_enbw_hz(params).is_finite());
-    assert!(legacy_boxcar_enbw_hz(params) > 0.0);
+    assert!((lpf_response_abs(params, 0.0) - 1.0).abs() < 1.0e-12);
+    assert!(lpf_enbw_hz(params).is_finite());
+    assert!(lpf_enbw_hz(params) > 0.0);
+}
+
+#[test]
+fn lowpass_processor_falls_back_to_one_cycle_boxcar_enbw() {
+    let (time, signal) = test_waveform();
+    for kind in [
+        LockinLpfKind::RcCascade,
+        LockinLpfKind::WindowedSinc,
+        LockinLpfKind::Gaussian,
+    ] {
+        let lockin = Lockin {
+            lpf_kind: kind,
+            lpf_half_window_cycles: 4.0,
+            ..test_lockin()
+        };
+        let processor = LockinProcessor::new(&time, &signal, 1_000.0, 0.0, &lockin).unwrap();
+        let params = processor.params();
+
+        assert!(params.fallback_used);
+        assert!(
+            (lpf_enbw_hz(params) - 500.0).abs() < 1.0e-3 * 500.0,
+            "{kind:?}"
+        );
+        assert!((lpf_response_abs(params, 0.0) - 1.0).abs() < 1.0e-9);
+        let result = processor.compute_harmonic_detailed(1, false);
+        let (i_start, i_end) = processor.output_index_range();
+        assert_eq!(result.li_x.len(), i_end - i_start + 1);
+    }
+}
+
+#[test]
+fn lowpass_processor_recovers_amplitude_with_explicit_cutoff() {
+    let (time, signal) = test_waveform();
+    let lockin = Lockin {
+        lpf_kind: LockinLpfKind::RcCascade,
+        lpf_cutoff: Some(LockinCutoff::ReferenceRatio(0.05)),
+        ..test_lockin()
+    };
+    let processor = LockinProcessor::new(&time, &signal, 1_000.0, 0.0, &lockin).unwrap();
+    assert_eq!(processor.params().cutoff_hz, Some(50.0));
+
+    let result = processor.compute_harmonic_detailed(1, false);
+    let middle = result.li_x.len() / 2;
+    let amplitude = result.li_x[middle].hypot(result.li_y[middle]);
+    assert!((amplitude - 0.35).abs() < 1.0e-3, "amplitude={amplitude}");
 }
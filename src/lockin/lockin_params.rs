@@ -1,5 +1,6 @@
-use crate::config::{Lockin, LockinLpfKind};
+use crate::config::{Lockin, LockinCutoff, LockinLpfKind};
 use anyhow::{Result, anyhow, bail};
+use pmoke_analysis_core::{LowPassFilter, LowPassKind, LowPassSettings};
 use std::f64::consts::PI;
 
 #[derive(Debug, Clone, Copy)]
@@ -16,6 +17,7 @@ pub struct LockinParams {
     pub cutoff_hz: Option<f64>,
     pub cutoff_source: CutoffSource,
     pub fallback_used: bool,
+    pub filter: Option<LowPassSettings>,
 
     pub omega: f64,
     pub t_half: f64,
@@ -58,13 +60,25 @@ impl LockinParams {
         let output_rate = sample_rate / stride as f64;
         let half_window_cycles = lockin.lpf_half_window_cycles;
         let omega = 2.0 * PI * f_ref;
-        let t_half = half_window_cycles / f_ref;
-        if !t_half.is_finite() || t_half < dt {
+        let mut t_half = half_window_cycles / f_ref;
+        if lockin.lpf_kind.uses_half_window() && (!t_half.is_finite() || t_half < dt) {
             bail!(
                 "lockin half-window ({t_half}) must be finite and >= dt ({dt}); increase lockin.lpf_half_window_cycles or sampling resolution"
             );
         }
-        let n_half = ((t_half / dt).floor() as usize).max(1);
+        let design = design_filter(dt, f_ref, t_half, lockin)?;
+        let n_half = match &design {
+            Some((filter, _)) => {
+                let n_half = filter.edge_samples().max(1);
+                t_half = n_half as f64 * dt;
+                n_half
+            }
+            None => ((t_half / dt).floor() as usize).max(1),
+        };
+        let (cutoff_hz, cutoff_source) = match &design {
+            Some((filter, source)) => (Some(filter.settings().cutoff_hz), *source),
+            None => (None, CutoffSource::None),
+        };
         let n_int = ((length - 1) / stride) + 1;
         let i_start = 2 + (n_half + 1) / stride;
         let i_end = n_int.saturating_sub(i_start);
@@ -76,9 +90,10 @@ impl LockinParams {
             length,
             f_ref,
             lpf_kind: lockin.lpf_kind,
-            cutoff_hz: None,
-            cutoff_source: CutoffSource::None,
-            fallback_used: false,
+            cutoff_hz,
+            cutoff_source,
+            fallback_used: cutoff_source == CutoffSource::Fallback,
+            filter: design.map(|(filter, _)| filter.settings()),
             omega,
             t_half,
             n_half,
@@ -110,6 +125,44 @@ impl LockinParams {
     }
 }
 
+/// Resolves the configured cutoff of a non-boxcar LPF against this trace's
+/// sampling. Without an explicit cutoff the filter is matched to the ENBW of a
+/// one-cycle boxcar, `f_ref / 2`.
+fn design_filter(
+    dt: f64,
+    f_ref: f64,
+    half_window_s: f64,
+    lockin: &Lockin,
+) -> Result<Option<(LowPassFilter, CutoffSource)>> {
+    let kind = match lockin.lpf_kind {
+        LockinLpfKind::BoxcarLegacy => return Ok(None),
+        LockinLpfKind::RcCascade => LowPassKind::RcCascade,
+        LockinLpfKind::WindowedSinc => LowPassKind::WindowedSinc,
+        LockinLpfKind::Gaussian => LowPassKind::Gaussian,
+    };
+    let mut settings = LowPassSettings {
+        kind,
+        cutoff_hz: f64::NAN,
+        order: lockin.lpf_order,
+        half_window_s,
+        zero_phase: lockin.lpf_zero_phase,
+    };
+    let (cutoff_hz, source) = match lockin.lpf_cutoff {
+        Some(LockinCutoff::Hz(cutoff_hz)) => (cutoff_hz, CutoffSource::ExplicitHz),
+        Some(LockinCutoff::ReferenceRatio(ratio)) => (ratio * f_ref, CutoffSource::ReferenceRatio),
+        Some(LockinCutoff::EnbwHz(enbw_hz)) => (
+            pmoke_analysis_core::lowpass_cutoff_for_enbw(settings, dt, enbw_hz)?,
+            CutoffSource::EnbwMatch,
+        ),
+        None => (
+            pmoke_analysis_core::lowpass_cutoff_for_enbw(settings, dt, 0.5 * f_ref)?,
+            CutoffSource::Fallback,
+        ),
+    };
+    settings.cutoff_hz = cutoff_hz;
+    Ok(Some((LowPassFilter::new(settings, dt)?, source)))
+}
+
 #[cfg(test)]
 #[path = "lockin_params_tests.rs"]
 mod tests;
@@ -2,6 +2,7 @@ pub mod debug;
 pub mod lockin_core;
 pub mod lockin_params;
 pub mod lockin_plot;
+pub mod noise;
 pub mod provenance;
 pub mod reference;
 pub mod resolve;
@@ -9,17 +10,21 @@ pub mod save;
 pub mod sensor;
 pub mod stride;
 
-use crate::config::Config;
-use crate::constants::{HARMONICS, LI_HEADER};
+use crate::config::{Config, LockinLpfKind};
+use crate::lockin::noise::{LOCKIN_NOISE_HEADERS, estimate_harmonic_noise, write_noise};
 use crate::lockin::provenance::LockinProvenance;
 use crate::lockin::reference::ref_analysis::RefFitParams;
 use crate::lockin::reference::run_fit_ref_core;
-use crate::lockin::save::{get_li_headers, write_li_results};
-use crate::lockin::sensor::{SensorOutput, run_sensor};
+use crate::lockin::save::{get_li_headers, lockin_result_headers, write_li_results};
+use crate::lockin::sensor::{SensorOutput, SensorProvenance, run_sensor};
 use crate::utils::time_axis::TimeAxisRef;
-use crate::utils::waveform::read_all_fetched_waveforms;
+use crate::utils::waveform::{
+    RawChannelStream, StreamedWaveformData, WaveformData, read_all_fetched_waveforms,
+    read_fetched_waveforms_streaming,
+};
 use crate::{plot, ui};
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
+use pmoke_analysis_core::LockinNoise;
 use rayon::prelude::*;
 
 pub struct LockinProcessOutput {
@@ -34,31 +39,34 @@ type LockinRunOutput = (
     Vec<Vec<f64>>,
     Vec<Vec<f64>>,
     Vec<Vec<Vec<f64>>>,
+    Vec<Vec<LockinNoise>>,
     RefFitParams,
     LockinProvenance,
+    SensorProvenance,
 );
 
 pub fn run(cfg: &Config) -> Result<()> {
     let pb = ui::spinner("reading fetched waveform data");
     let t0 = std::time::Instant::now();
-    let data = read_all_fetched_waveforms(cfg)?;
+    let fetched = read_lockin_input(cfg)?;
+    let data = fetched.as_input();
     let elapsed_read = t0.elapsed();
 
     ui::finish_read(
         pb,
         format!(
             "fetched data: {} channels, {} samples ({})",
-            data.channels.len(),
-            data.channels.first().map_or(0, Vec::len),
+            data.channel_count(),
+            data.loaded().t.len(),
             ui::fmt_duration(elapsed_read)
         ),
     );
 
-    if data.channels.is_empty() {
+    if data.channel_count() == 0 {
         bail!("Fetched data is empty, cannot extract channels.");
     }
 
-    let _ = run_li(cfg, &data.t, &data.channels)?;
+    let _ = run_li_input(cfg, data)?;
 
     Ok(())
 }
@@ -68,13 +76,18 @@ pub fn run_li<'a>(
     t: impl Into<TimeAxisRef<'a>>,
     data: &[Vec<f64>],
 ) -> Result<LockinRunOutput> {
-    let paths = cfg.paths();
     let t = t.into();
     let (sensor_ch, sensor_idx) = resolve::sensor_column_indices(cfg)?;
     let (_, ref_idx) = resolve::reference_column_index(cfg)?;
     let (signal_ch, signal_idx) = resolve::signal_column_indices(cfg)?;
+    let compensation_idx = resolve::sensor_compensation_column_indices(cfg)?;
 
-    let max_sensor_idx = sensor_idx.iter().max().cloned().unwrap_or(0);
+    let max_sensor_idx = sensor_idx
+        .iter()
+        .chain(compensation_idx.iter().flatten())
+        .max()
+        .cloned()
+        .unwrap_or(0);
     let max_signal_idx = signal_idx.iter().max().cloned().unwrap_or(0);
     let max_needed_idx = std::cmp::max(max_sensor_idx, std::cmp::max(ref_idx, max_signal_idx));
 
@@ -87,9 +100,154 @@ pub fn run_li<'a>(
     }
 
     let sensor_data: Vec<&[f64]> = sensor_idx.iter().map(|&idx| data[idx].as_slice()).collect();
+    let compensation_data: Vec<Option<&[f64]>> = compensation_idx
+        .iter()
+        .map(|idx| idx.map(|idx| data[idx].as_slice()))
+        .collect();
     let ref_data = data[ref_idx].as_slice();
     let signal_data: Vec<&[f64]> = signal_idx.iter().map(|&idx| data[idx].as_slice()).collect();
 
+    run_li_columns(
+        cfg,
+        t,
+        &sensor_ch,
+        &sensor_data,
+        &compensation_data,
+        ref_data,
+        &signal_ch,
+        LockinSignals::Memory(&signal_data),
+    )
+}
+
+/// Whether [`run_li_streamed`] can replace [`run_li`] for this config.
+pub fn streaming_supported(cfg: &Config) -> bool {
+    cfg.lockin.lpf_kind == LockinLpfKind::BoxcarLegacy && !cfg.lockin.lpf_debug_output
+}
+
+/// Fetched waveforms for lock-in analysis, with the signal channels left on
+/// disk when they can be streamed.
+pub enum FetchedLockinInput {
+    Memory(WaveformData),
+    Streamed(StreamedWaveformData),
+}
+
+#[derive(Clone, Copy)]
+pub enum LockinInput<'a> {
+    Memory(&'a WaveformData),
+    Streamed(&'a StreamedWaveformData),
+}
+
+impl FetchedLockinInput {
+    pub fn as_input(&self) -> LockinInput<'_> {
+        match self {
+            Self::Memory(data) => LockinInput::Memory(data),
+            Self::Streamed(data) => LockinInput::Streamed(data),
+        }
+    }
+}
+
+impl<'a> LockinInput<'a> {
+    /// Channels held in memory; streamed signal channels are not included.
+    pub fn loaded(self) -> &'a WaveformData {
+        match self {
+            Self::Memory(data) => data,
+            Self::Streamed(data) => &data.data,
+        }
+    }
+
+    pub fn channel_count(self) -> usize {
+        match self {
+            Self::Memory(data) => data.channels.len(),
+            Self::Streamed(data) => data.channels.len() + data.streams.len(),
+        }
+    }
+}
+
+pub fn read_lockin_input(cfg: &Config) -> Result<FetchedLockinInput> {
+    if streaming_supported(cfg)
+        && let Some(data) = read_fetched_waveforms_streaming(cfg, &cfg.roles.signal_ch)?
+    {
+        return Ok(FetchedLockinInput::Streamed(data));
+    }
+    Ok(FetchedLockinInput::Memory(read_all_fetched_waveforms(cfg)?))
+}
+
+pub fn run_li_input(cfg: &Config, input: LockinInput<'_>) -> Result<LockinRunOutput> {
+    match input {
+        LockinInput::Memory(data) => run_li(cfg, &data.t, &data.channels),
+        LockinInput::Streamed(data) => run_li_streamed(cfg, data),
+    }
+}
+
+/// Same as [`run_li`], but the signal channels are demodulated chunk by chunk
+/// while they are read, so they are never held in memory as whole channels.
+pub fn run_li_streamed(cfg: &Config, input: &StreamedWaveformData) -> Result<LockinRunOutput> {
+    let (sensor_ch, _) = resolve::sensor_column_indices(cfg)?;
+    let (ref_ch, _) = resolve::reference_column_index(cfg)?;
+    let (signal_ch, _) = resolve::signal_column_indices(cfg)?;
+    let loaded_column = |ch: u8| {
+        input
+            .channels
+            .iter()
+            .position(|&loaded| loaded == ch)
+            .map(|idx| input.data.channels[idx].as_slice())
+            .ok_or_else(|| anyhow!("channel {ch} was not loaded for lock-in analysis"))
+    };
+    let sensor_data = sensor_ch
+        .iter()
+        .map(|&ch| loaded_column(ch))
+        .collect::<Result<Vec<_>>>()?;
+    let compensation_data = sensor_ch
+        .iter()
+        .map(|&ch| {
+            cfg.sensor_compensation(ch)
+                .map(|compensation| loaded_column(compensation.channel))
+                .transpose()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let ref_data = loaded_column(ref_ch)?;
+    let streams = signal_ch
+        .iter()
+        .map(|&ch| {
+            input
+                .streams
+                .iter()
+                .find(|stream| stream.channel() == ch)
+                .ok_or_else(|| anyhow!("signal channel {ch} is not available as a raw stream"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    run_li_columns(
+        cfg,
+        &input.data.t,
+        &sensor_ch,
+        &sensor_data,
+        &compensation_data,
+        ref_data,
+        &signal_ch,
+        LockinSignals::Streamed(&streams),
+    )
+}
+
+enum LockinSignals<'a> {
+    Memory(&'a [&'a [f64]]),
+    Streamed(&'a [&'a RawChannelStream]),
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_li_columns<'a>(
+    cfg: &Config,
+    t: impl Into<TimeAxisRef<'a>>,
+    sensor_ch: &[u8],
+    sensor_data: &[&[f64]],
+    compensation_data: &[Option<&[f64]>],
+    ref_data: &[f64],
+    signal_ch: &[u8],
+    signals: LockinSignals<'_>,
+) -> Result<LockinRunOutput> {
+    let paths = cfg.paths();
+    let t = t.into();
+
     // Reference analysis
     let ref_fit_params = run_fit_ref_core(cfg, t, ref_data)?;
 
@@ -98,10 +256,25 @@ pub fn run_li<'a>(
         t: mut t_stride,
         rate: mut sensor_rate_stride,
         integral: mut sensor_integral_stride,
-    } = run_sensor(cfg, t, &sensor_data, &sensor_ch, ref_fit_params.f_ref)?;
+        provenance: sensor_provenance,
+    } = run_sensor(
+        cfg,
+        t,
+        sensor_data,
+        compensation_data,
+        sensor_ch,
+        ref_fit_params.f_ref,
+    )?;
 
     // Lock-in processing
-    let lockin_output = li_process(cfg, t, &signal_ch, &signal_data, ref_fit_params)?;
+    let lockin_output = match signals {
+        LockinSignals::Memory(signal_data) => {
+            li_process(cfg, t, signal_ch, signal_data, ref_fit_params)?
+        }
+        LockinSignals::Streamed(streams) => {
+            li_process_streamed(cfg, t, signal_ch, streams, ref_fit_params)?
+        }
+    };
     trim_lockin_context_to_result(
         &mut t_stride,
         &mut sensor_rate_stride,
@@ -114,6 +287,7 @@ pub fn run_li<'a>(
     // Save lock-in results
     let headers = get_li_headers(cfg)?;
     let t0 = std::time::Instant::now();
+    let mut noise = Vec::with_capacity(signal_ch.len());
     for (sig_ch, li_result) in signal_ch.iter().zip(lockin_output.result.iter()) {
         let li_result_path = paths.lockin_xy_csv(*sig_ch);
         write_li_results(
@@ -125,6 +299,20 @@ pub fn run_li<'a>(
             li_result,
             cfg.lockin.save_npy,
         )?;
+        let channel_noise = estimate_harmonic_noise(
+            cfg,
+            *sig_ch,
+            &t_stride,
+            li_result,
+            lockin_output.provenance.estimated_enbw_hz(),
+        )?;
+        write_noise(
+            &paths.lockin_noise_csv(*sig_ch),
+            &LOCKIN_NOISE_HEADERS,
+            &cfg.lockin.harmonics,
+            &channel_noise,
+        )?;
+        noise.push(channel_noise);
     }
     let elapsed_save = t0.elapsed();
     ui::saved(format!(
@@ -133,7 +321,7 @@ pub fn run_li<'a>(
         ui::fmt_duration(elapsed_save)
     ));
 
-    let headers = LI_HEADER;
+    let headers = lockin_result_headers(&cfg.lockin.harmonics);
     let labels: Vec<String> = headers
         .iter()
         .map(|s| s.trim().replace("(V)", ""))
@@ -151,7 +339,7 @@ pub fn run_li<'a>(
                     output,
                     &t_stride,
                     &lockin_output.result,
-                    &signal_ch,
+                    signal_ch,
                     &labels,
                 )
                 .context("failed to plot lock-in results")
@@ -163,8 +351,10 @@ pub fn run_li<'a>(
         sensor_rate_stride,
         sensor_integral_stride,
         lockin_output.result,
+        noise,
         ref_fit_params,
         lockin_output.provenance,
+        sensor_provenance,
     ))
 }
 
@@ -190,7 +380,7 @@ pub fn li_process<'a>(
     let omega_tref: f64 = ref_fit_params.omega_tref;
     let workers: usize = cfg.lockin.workers;
 
-    let harmonics = HARMONICS;
+    let harmonics = cfg.lockin.harmonics.as_slice();
 
     let pool = rayon::ThreadPoolBuilder::new()
         .num_threads(workers)
@@ -214,10 +404,11 @@ pub fn li_process<'a>(
         pb.set_message(format!("lock-in ch{sig_ch}"));
         let li_processor =
             lockin_core::LockinProcessor::new(t, signal, f_ref, omega_tref, &cfg.lockin)?;
+        check_harmonics_below_nyquist(li_processor.params(), harmonics)?;
         let processor_base_range = li_processor.base_index_range();
         let processor_output_range = li_processor.output_index_range();
         if provenance.is_none() {
-            provenance = Some(LockinProvenance::from_processor(&li_processor));
+            provenance = Some(LockinProvenance::from_processor(&li_processor, harmonics));
         }
         if let Some(expected) = base_index_range {
             if processor_base_range != expected {
@@ -242,19 +433,7 @@ pub fn li_process<'a>(
             output_index_range = Some(processor_output_range);
         }
         if !printed_lockin_summary {
-            ui::suspend_progress(&pb, || {
-                ui::settings_table(
-                    "Lock-in settings",
-                    li_processor
-                        .summary_lines()
-                        .into_iter()
-                        .map(|line| {
-                            let (setting, value) = line.split_once('=').unwrap_or((&line, ""));
-                            (setting.trim().to_string(), value.trim().to_string())
-                        })
-                        .collect(),
-                );
-            });
+            print_lockin_summary(&pb, li_processor.summary_lines());
             printed_lockin_summary = true;
         }
         let include_debug = cfg.lockin.lpf_debug_output;
@@ -263,7 +442,7 @@ pub fn li_process<'a>(
             let t_output = li_processor.output_times();
             let params = li_processor.params();
             let mut results = Vec::with_capacity(harmonics.len());
-            for &harmonic in &harmonics {
+            for &harmonic in harmonics {
                 pb.set_message(format!("lock-in ch{sig_ch} h{harmonic}"));
                 let result = li_processor.compute_harmonic_detailed(harmonic, include_debug);
                 if include_debug {
@@ -330,6 +509,136 @@ pub fn li_process<'a>(
     })
 }
 
+/// Streaming counterpart of [`li_process`]: each signal channel is read once
+/// in raw chunks and every harmonic is demodulated as the chunks arrive.
+pub fn li_process_streamed(
+    cfg: &Config,
+    t: TimeAxisRef<'_>,
+    signal_ch: &[u8],
+    streams: &[&RawChannelStream],
+    ref_fit_params: RefFitParams,
+) -> Result<LockinProcessOutput> {
+    if signal_ch.len() != streams.len() {
+        bail!(
+            "signal channel count ({}) and signal stream count ({}) differ",
+            signal_ch.len(),
+            streams.len()
+        );
+    }
+    if streams.is_empty() {
+        bail!("no signal channels were available for lock-in processing");
+    }
+    let workers = cfg.lockin.workers;
+    let harmonics = cfg.lockin.harmonics.as_slice();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers)
+        .build()
+        .context("Failed to build rayon thread pool")?;
+
+    let pb = ui::progress(
+        format!("streaming lock-in with {workers} workers"),
+        (streams.len() * t.len()) as u64,
+    );
+    let t0 = std::time::Instant::now();
+
+    let mut all_signals_results = Vec::with_capacity(streams.len());
+    let mut provenance = None;
+    let mut index_range = None;
+    for (&sig_ch, stream) in signal_ch.iter().zip(streams) {
+        pb.set_message(format!("lock-in ch{sig_ch}"));
+        if stream.sample_count() != t.len() {
+            bail!(
+                "signal ch{sig_ch} has {} samples, expected {}",
+                stream.sample_count(),
+                t.len()
+            );
+        }
+        let mut processor = lockin_core::LockinStreamProcessor::new(
+            t,
+            ref_fit_params.f_ref,
+            ref_fit_params.omega_tref,
+            &cfg.lockin,
+        )?;
+        check_harmonics_below_nyquist(processor.params(), harmonics)?;
+        if provenance.is_none() {
+            print_lockin_summary(&pb, processor.summary_lines());
+            let range = processor.output_index_range();
+            provenance = Some(LockinProvenance::from_params(
+                processor.params(),
+                range,
+                range,
+                harmonics,
+            ));
+            index_range = Some(range);
+        }
+
+        pool.install(|| {
+            stream.for_each_chunk(|chunk| {
+                processor.push(chunk)?;
+                pb.inc(chunk.len() as u64);
+                Ok(())
+            })
+        })
+        .with_context(|| format!("failed to stream lock-in input for ch{sig_ch}"))?;
+
+        let mut results_list = Vec::with_capacity(harmonics.len() * 2);
+        for result in processor.finish()? {
+            results_list.push(result.li_x);
+            results_list.push(result.li_y);
+        }
+        all_signals_results.push(results_list);
+    }
+
+    ui::finish_success(
+        pb,
+        format!(
+            "lock-in processing completed ({})",
+            ui::fmt_duration(t0.elapsed())
+        ),
+    );
+
+    let index_range = index_range.unwrap_or((0, 0));
+    Ok(LockinProcessOutput {
+        result: all_signals_results,
+        base_index_range: index_range,
+        output_index_range: index_range,
+        provenance: provenance
+            .context("no signal channels were available for lock-in processing")?,
+    })
+}
+
+fn check_harmonics_below_nyquist(
+    params: lockin_params::LockinParams,
+    harmonics: &[usize],
+) -> Result<()> {
+    let nyquist_hz = params.sample_rate / 2.0;
+    if let Some(&harmonic) = harmonics
+        .iter()
+        .find(|&&harmonic| harmonic as f64 * params.f_ref >= nyquist_hz)
+    {
+        bail!(
+            "lock-in harmonic {harmonic} ({:.6e} Hz) is at or above the Nyquist frequency ({nyquist_hz:.6e} Hz)",
+            harmonic as f64 * params.f_ref
+        );
+    }
+    Ok(())
+}
+
+fn print_lockin_summary(pb: &ui::UiProgress, lines: Vec<String>) {
+    ui::suspend_progress(pb, || {
+        ui::settings_table(
+            "Lock-in settings",
+            lines
+                .into_iter()
+                .map(|line| {
+                    let (setting, value) = line.split_once('=').unwrap_or((&line, ""));
+                    (setting.trim().to_string(), value.trim().to_string())
+                })
+                .collect(),
+        );
+    });
+}
+
 fn trim_lockin_context_to_result(
     t_stride: &mut Vec<f64>,
     sensor_rate_stride: &mut [Vec<f64>],
@@ -0,0 +1,164 @@
+use crate::config::Config;
+use crate::ui;
+use crate::utils::csv::{read_csv_with_headers, write_csv};
+use anyhow::{Context, Result, bail};
+use pmoke_analysis_core::{LockinNoise, estimate_lockin_noise};
+use std::path::Path;
+
+/// Fewest finite samples in the noise window for a usable estimate; matches
+/// the S/N diagnostics.
+const MIN_NOISE_SAMPLES: usize = 8;
+
+/// Column labels of a noise table: lock-in x/y or phase-rotated in/out.
+pub const LOCKIN_NOISE_HEADERS: [&str; 6] = [
+    "harmonic",
+    "x_std (V)",
+    "y_std (V)",
+    "xy_cov (V^2)",
+    "samples",
+    "enbw (Hz)",
+];
+pub const ROTATED_NOISE_HEADERS: [&str; 6] = [
+    "harmonic",
+    "in_std (V)",
+    "out_std (V)",
+    "in_out_cov (V^2)",
+    "samples",
+    "enbw (Hz)",
+];
+
+/// Estimates the noise of every harmonic's x/y pair in the pre-pulse window.
+pub fn estimate_harmonic_noise(
+    cfg: &Config,
+    signal_ch: u8,
+    t: &[f64],
+    li_result: &[Vec<f64>],
+    enbw_hz: f64,
+) -> Result<Vec<LockinNoise>> {
+    let window = cfg.lockin_noise_window();
+    let noise = li_result
+        .as_chunks::<2>()
+        .0
+        .iter()
+        .map(|[x, y]| {
+            estimate_lockin_noise(t, x, y, window.start, window.end, enbw_hz)
+                .with_context(|| format!("failed to estimate lock-in noise for ch{signal_ch}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let samples = noise.first().map_or(0, |noise| noise.samples);
+    if samples >= MIN_NOISE_SAMPLES {
+        return Ok(noise);
+    }
+    ui::warn(format!(
+        "ch{signal_ch}: noise window [{}, {}] s has only {samples} lock-in samples; Kerr error bars will be NaN",
+        window.start, window.end
+    ));
+    Ok(noise
+        .iter()
+        .map(|noise| LockinNoise {
+            samples: noise.samples,
+            ..LockinNoise::unavailable()
+        })
+        .collect())
+}
+
+/// Writes one row per harmonic: standard deviations, covariance, sample count,
+/// and filter bandwidth.
+pub fn write_noise(
+    path: &Path,
+    headers: &[&str; 6],
+    harmonics: &[usize],
+    noise: &[LockinNoise],
+) -> Result<()> {
+    let columns = [
+        harmonics.iter().map(|&harmonic| harmonic as f64).collect(),
+        noise.iter().map(LockinNoise::x_std).collect(),
+        noise.iter().map(LockinNoise::y_std).collect(),
+        noise.iter().map(|noise| noise.xy_cov).collect(),
+        noise.iter().map(|noise| noise.samples as f64).collect(),
+        noise.iter().map(|noise| noise.enbw_hz).collect::<Vec<_>>(),
+    ];
+    write_csv(path, headers, &columns)
+        .with_context(|| format!("failed to write lock-in noise: {}", path.display()))
+}
+
+/// Reads a noise table, or returns NaN noise with a warning when an older run
+/// has none.
+pub fn read_noise_or_unavailable(path: &Path, harmonics: &[usize]) -> Result<Vec<LockinNoise>> {
+    if !path.exists() {
+        ui::warn(format!(
+            "{} is missing; Kerr error bars will be NaN",
+            path.display()
+        ));
+        return Ok(vec![LockinNoise::unavailable(); harmonics.len()]);
+    }
+    let (_, columns) = read_csv_with_headers(path)?;
+    let [harmonic, x_std, y_std, xy_cov, samples, enbw_hz] = columns.as_slice() else {
+        bail!(
+            "{} must have {} columns",
+            path.display(),
+            LOCKIN_NOISE_HEADERS.len()
+        );
+    };
+    let listed = harmonic
+        .iter()
+        .map(|&value| value as usize)
+        .collect::<Vec<_>>();
+    if listed != harmonics {
+        bail!(
+            "{} lists harmonics {listed:?} but lockin.harmonics is {harmonics:?}",
+            path.display()
+        );
+    }
+    Ok((0..harmonics.len())
+        .map(|row| LockinNoise {
+            x_var: x_std[row] * x_std[row],
+            y_var: y_std[row] * y_std[row],
+            xy_cov: xy_cov[row],
+            samples: samples[row] as usize,
+            enbw_hz: enbw_hz[row],
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LOCKIN_NOISE_HEADERS, read_noise_or_unavailable, write_noise};
+    use pmoke_analysis_core::LockinNoise;
+
+    #[test]
+    fn noise_table_round_trips_and_checks_harmonics() {
+        let dir = std::env::temp_dir().join(format!(
+            "pmoke_lockin_noise_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let path = dir.join("ch3_noise.csv");
+        let noise = [
+            LockinNoise {
+                x_var: 4.0e-6,
+                y_var: 1.0e-6,
+                xy_cov: -5.0e-7,
+                samples: 40,
+                enbw_hz: 125.0,
+            },
+            LockinNoise::unavailable(),
+        ];
+        write_noise(&path, &LOCKIN_NOISE_HEADERS, &[2, 3], &noise).unwrap();
+
+        let read = read_noise_or_unavailable(&path, &[2, 3]).unwrap();
+        assert!((read[0].x_var - 4.0e-6).abs() < 1.0e-18);
+        assert_eq!(read[0].xy_cov, -5.0e-7);
+        assert_eq!(read[0].samples, 40);
+        assert_eq!(read[0].enbw_hz, 125.0);
+        assert!(read[1].y_var.is_nan());
+        assert!(read_noise_or_unavailable(&path, &[2, 4]).is_err());
+
+        let missing = read_noise_or_unavailable(&dir.join("ch9_noise.csv"), &[2, 3]).unwrap();
+        assert!(missing.iter().all(|noise| noise.x_var.is_nan()));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
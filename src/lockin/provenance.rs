@@ -1,8 +1,12 @@
 use crate::config::{ArtifactPaths, ArtifactResolver, Config, LockinLpfKind};
 
-use crate::lockin::lockin_core::{LockinProcessor, legacy_boxcar_enbw_hz};
+use crate::kerr::KerrProvenance;
+use crate::lockin::lockin_core::{LockinProcessor, lpf_enbw_hz};
+use crate::lockin::lockin_params::LockinParams;
 use crate::lockin::reference::ref_analysis::RefFitParams;
+use crate::lockin::sensor::SensorProvenance;
 use anyhow::{Context, Result, bail};
+use pmoke_analysis_core::ReferenceSineFit;
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::fs::{self, OpenOptions};
@@ -14,6 +18,7 @@ pub(crate) const ANALYSIS_MANIFEST_SCHEMA_VERSION: u32 = 3;
 #[derive(Debug, Clone, Serialize)]
 pub struct LockinProvenance {
     kind: LockinLpfKind,
+    harmonics: Vec<usize>,
     stride_samples: usize,
     input_sample_rate_hz: f64,
     output_sample_rate_hz: f64,
@@ -28,29 +33,51 @@ pub struct LockinProvenance {
     #[serde(skip_serializing_if = "Option::is_none")]
     cutoff_hz: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    cutoff_source: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     filter_settling_samples: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    zero_phase: Option<bool>,
 }
 
 impl LockinProvenance {
-    pub fn from_processor(processor: &LockinProcessor<'_>) -> Self {
-        let params = processor.params();
-        let (base_index_start, base_index_end) = processor.base_index_range();
-        let (output_index_start, output_index_end) = processor.output_index_range();
+    pub fn estimated_enbw_hz(&self) -> f64 {
+        self.estimated_enbw_hz
+    }
+
+    pub fn from_processor(processor: &LockinProcessor<'_>, harmonics: &[usize]) -> Self {
+        Self::from_params(
+            processor.params(),
+            processor.base_index_range(),
+            processor.output_index_range(),
+            harmonics,
+        )
+    }
+
+    pub fn from_params(
+        params: LockinParams,
+        (base_index_start, base_index_end): (usize, usize),
+        (output_index_start, output_index_end): (usize, usize),
+        harmonics: &[usize],
+    ) -> Self {
         Self {
             kind: params.lpf_kind,
+            harmonics: harmonics.to_vec(),
             stride_samples: params.stride,
             input_sample_rate_hz: params.sample_rate,
             output_sample_rate_hz: params.output_rate,
             reference_frequency_hz: params.f_ref,
             effective_window_seconds: 2.0 * params.t_half,
-            estimated_enbw_hz: legacy_boxcar_enbw_hz(params),
+            estimated_enbw_hz: lpf_enbw_hz(params),
             edge_policy: "trim",
             base_index_start,
             base_index_end,
             output_index_start,
             output_index_end,
-            cutoff_hz: None,
-            filter_settling_samples: None,
+            cutoff_hz: params.cutoff_hz,
+            cutoff_source: params.cutoff_hz.map(|_| params.cutoff_source.as_str()),
+            filter_settling_samples: params.filter.map(|_| params.n_half),
+            zero_phase: params.filter.map(|filter| filter.zero_phase),
         }
     }
 }
@@ -99,6 +126,8 @@ struct ReferenceProvenance {
     frequency_hz: f64,
     amplitude: f64,
     phase_rad: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fit: Option<ReferenceSineFit>,
 }
 
 #[derive(Serialize)]
@@ -135,6 +164,12 @@ struct AnalysisMetadata<'a> {
     published_through: &'static str,
     reference: ReferenceProvenance,
     lockin: &'a LockinProvenance,
+    sensor: &'a SensorProvenance,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kerr: Option<&'a KerrProvenance>,
+    /// Multimeter readings around the shot, copied from the acquisition manifest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    multimeters: Option<toml::Value>,
     column_sets: BTreeMap<String, ColumnSet>,
     artifacts: Vec<AnalysisArtifact>,
     outputs: Vec<OutputFileInfo>,
@@ -483,6 +518,9 @@ fn analysis_artifact_identity(path: &Path) -> Result<(String, Option<u8>)> {
     if stem == "kerr" {
         return Ok(("kerr".to_string(), None));
     }
+    if stem.starts_with("kerr_vs_field_") {
+        return Ok(("kerr_vs_field".to_string(), None));
+    }
     let channel = stem
         .strip_prefix("ch")
         .and_then(|value| value.split('_').next())
@@ -492,6 +530,12 @@ fn analysis_artifact_identity(path: &Path) -> Result<(String, Option<u8>)> {
         "lockin_xy"
     } else if stem.ends_with("_rotated") {
         "lockin_rotated"
+    } else if stem.ends_with("_phase_drift") {
+        "phase_drift"
+    } else if stem.ends_with("_rotated_noise") {
+        "lockin_rotated_noise"
+    } else if stem.ends_with("_noise") {
+        "lockin_noise"
     } else {
         return Err(anyhow::anyhow!(
             "unknown analysis artifact name: {}",
@@ -642,6 +686,102 @@ fn validate_upstream_artifact_checksums(
     Ok(())
 }
 
+/// Published results of one stage in another run, verified against that run's
+/// analysis manifest.
+#[derive(Debug, Clone)]
+pub(crate) struct PublishedStageOutputs {
+    pub run_dir: PathBuf,
+    pub paths: ArtifactPaths,
+    pub manifest_sha256: String,
+    pub generation: Option<u64>,
+    pub config_sha256: String,
+    /// Manifest-relative file names and their verified SHA-256, sorted by name.
+    pub files: Vec<(String, String)>,
+}
+
+pub(crate) fn load_published_stage_outputs(
+    run_dir: &Path,
+    stage: &str,
+    include: impl Fn(&str) -> bool,
+) -> Result<PublishedStageOutputs> {
+    let run_dir = fs::canonicalize(run_dir)
+        .with_context(|| format!("failed to resolve run directory: {}", run_dir.display()))?;
+    let paths = ArtifactPaths::new(&run_dir);
+    let manifest_path = paths.analysis_manifest();
+    let contents = fs::read_to_string(&manifest_path).with_context(|| {
+        format!(
+            "failed to read analysis manifest: {}",
+            manifest_path.display()
+        )
+    })?;
+    let manifest: toml::Value = toml::from_str(&contents).with_context(|| {
+        format!(
+            "failed to parse analysis manifest: {}",
+            manifest_path.display()
+        )
+    })?;
+    let config_sha256 = manifest
+        .get("stages")
+        .and_then(|stages| stages.get(stage))
+        .and_then(|stage| stage.get("config_sha256"))
+        .and_then(toml::Value::as_str)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} has no published {stage} results; run pmoke {stage} there first",
+                run_dir.display()
+            )
+        })?
+        .to_string();
+    let generation = manifest
+        .get("generation")
+        .and_then(toml::Value::as_integer)
+        .and_then(|generation| u64::try_from(generation).ok());
+
+    let mut files = Vec::new();
+    for output in manifest
+        .get("outputs")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let (Some(file), Some(sha256)) = (
+            output.get("file").and_then(toml::Value::as_str),
+            output.get("sha256").and_then(toml::Value::as_str),
+        ) else {
+            continue;
+        };
+        if !include(file) {
+            continue;
+        }
+        let path = paths.analysis_dir().join(file);
+        let actual = crate::utils::checksum::file_sha256(&path)
+            .with_context(|| format!("failed to verify {}", path.display()))?;
+        if actual != sha256 {
+            bail!(
+                "published {stage} output checksum mismatch for {}; rerun pmoke {stage} there",
+                path.display()
+            );
+        }
+        files.push((file.to_string(), actual));
+    }
+    if files.is_empty() {
+        bail!(
+            "{} lists no {stage} result tables in its analysis manifest",
+            run_dir.display()
+        );
+    }
+    files.sort();
+
+    Ok(PublishedStageOutputs {
+        manifest_sha256: crate::utils::checksum::sha256_hex(contents.as_bytes()),
+        run_dir,
+        paths,
+        generation,
+        config_sha256,
+        files,
+    })
+}
+
 fn next_generation(run_dir: &Path) -> Result<u64> {
     let manifest = ArtifactPaths::new(run_dir).analysis_manifest();
     let current = match fs::read_to_string(&manifest) {
@@ -659,12 +799,15 @@ fn next_generation(run_dir: &Path) -> Result<u64> {
         .ok_or_else(|| anyhow::anyhow!("analysis generation overflow"))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn write_analysis_metadata(
     cfg: &Config,
     output_paths: &ArtifactPaths,
     source_resolver: &ArtifactResolver,
     reference: &RefFitParams,
     lockin: &LockinProvenance,
+    sensor: &SensorProvenance,
+    kerr: Option<&KerrProvenance>,
     cfg_roles_reference_ch: u8,
 ) -> Result<()> {
     let path = output_paths.analysis_manifest();
@@ -684,6 +827,7 @@ pub fn write_analysis_metadata(
         .is_file()
         .then(|| crate::utils::checksum::file_sha256(&source_resolver.acquisition_manifest()))
         .transpose()?;
+    let multimeters = acquisition_multimeter_readings(&source_resolver.acquisition_manifest())?;
     let config_source_sha256 =
         crate::utils::checksum::file_sha256(&output_paths.analysis_source_config())?;
     let config_resolved_sha256 =
@@ -758,8 +902,12 @@ pub fn write_analysis_metadata(
             frequency_hz: reference.f_ref,
             amplitude: reference.a_ref,
             phase_rad: reference.omega_tref,
+            fit: reference.fit,
         },
         lockin,
+        sensor,
+        kerr,
+        multimeters,
         column_sets,
         artifacts,
         outputs,
@@ -769,6 +917,17 @@ pub fn write_analysis_metadata(
     write_atomic(&path, encoded.as_bytes())
 }
 
+fn acquisition_multimeter_readings(manifest: &Path) -> Result<Option<toml::Value>> {
+    if !manifest.is_file() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(manifest)
+        .with_context(|| format!("failed to read {}", manifest.display()))?;
+    let mut table: toml::Table = toml::from_str(&contents)
+        .with_context(|| format!("failed to parse {}", manifest.display()))?;
+    Ok(table.remove("multimeters"))
+}
+
 pub(crate) fn analysis_sources(
     run_dir: &Path,
     resolver: &ArtifactResolver,
@@ -947,7 +1106,11 @@ pub fn refresh_analysis_manifest_outputs(cfg: &Config, stage: &str) -> Result<()
     }
 
     match stage {
-        "li" | "phase" | "kerr" => {
+        "li" | "phase" => {
+            table.remove("exported_at");
+            table.remove("kerr");
+        }
+        "kerr" => {
             table.remove("exported_at");
         }
         "reference" | "sensor" => {}
@@ -1039,6 +1202,24 @@ pub fn refresh_analysis_manifest_outputs(cfg: &Config, stage: &str) -> Result<()
     write_atomic(&path, encoded.as_bytes())
 }
 
+pub fn write_kerr_provenance(cfg: &Config, kerr: &KerrProvenance) -> Result<()> {
+    let path = cfg.paths().analysis_manifest();
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut manifest: toml::Value =
+        toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))?;
+    manifest
+        .as_table_mut()
+        .ok_or_else(|| anyhow::anyhow!("analysis manifest root must be a table"))?
+        .insert(
+            "kerr".to_string(),
+            toml::Value::try_from(kerr).context("failed to encode Kerr provenance")?,
+        );
+    let encoded =
+        toml::to_string_pretty(&manifest).context("failed to encode updated analysis manifest")?;
+    write_atomic(&path, encoded.as_bytes())
+}
+
 fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
     let temporary = crate::commands::run_dir::unique_temporary_path(path)?;
     let result = (|| {
@@ -1091,9 +1272,10 @@ mod tests {
             .collect::<Vec<_>>();
         let processor = LockinProcessor::new(&time, &signal, f_ref, 0.0, &cfg.lockin).unwrap();
 
-        let provenance = LockinProvenance::from_processor(&processor);
+        let provenance = LockinProvenance::from_processor(&processor, &cfg.lockin.harmonics);
 
         assert_eq!(provenance.kind, LockinLpfKind::BoxcarLegacy);
+        assert_eq!(provenance.harmonics, vec![1, 2, 3, 4, 5, 6]);
         assert_eq!(provenance.stride_samples, 10);
         assert!((provenance.input_sample_rate_hz - 100_000.0).abs() < 1.0e-8);
         assert!((provenance.output_sample_rate_hz - 10_000.0).abs() < 1.0e-8);
@@ -1161,6 +1343,35 @@ mod tests {
         fs::remove_dir_all(directory).unwrap();
     }
 
+    #[test]
+    fn multimeter_readings_are_copied_from_the_acquisition_manifest() {
+        let nonce = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let directory = std::env::temp_dir().join(format!(
+            "pmoke-analysis-multimeters-{}-{nonce}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        let manifest = directory.join("manifest.toml");
+        assert_eq!(acquisition_multimeter_readings(&manifest).unwrap(), None);
+
+        fs::write(&manifest, b"schema_version = 1\n").unwrap();
+        assert_eq!(acquisition_multimeter_readings(&manifest).unwrap(), None);
+
+        fs::write(
+            &manifest,
+            b"schema_version = 1\n\n[[multimeters]]\nlabel = \"lamp_monitor\"\nbefore = 1.25\n",
+        )
+        .unwrap();
+        let readings = acquisition_multimeter_readings(&manifest).unwrap().unwrap();
+        assert_eq!(readings[0]["label"].as_str(), Some("lamp_monitor"));
+        assert_eq!(readings[0]["before"].as_float(), Some(1.25));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
     #[test]
     fn stage_fingerprints_cover_only_their_upstream_dependencies() {
         let mut cfg = crate::test_support::test_config(vec![1], vec![2]);
@@ -124,21 +124,45 @@ pub fn run_fit_ref_core_with_plot<'a>(
     let (fit_t, fit_ref_data) = stride_samples(cfg, t, ref_data);
     let results =
         fit_ref(&fit_t, &fit_ref_data, fft_results).context("failed to fit reference signal")?;
-    ui::summary_table(
-        "Reference fit",
-        &["Metric", "Value"],
+    let mut rows = vec![
         vec![
-            vec![
-                "frequency".to_string(),
-                format!("{:.8} MHz", results.f_ref * 1e-6),
-            ],
-            vec!["amplitude".to_string(), format!("{:.8} V", results.a_ref)],
-            vec![
-                "phase".to_string(),
-                format!("{:.8} rad", results.omega_tref),
-            ],
+            "frequency".to_string(),
+            format!("{:.8} MHz", results.f_ref * 1e-6),
         ],
-    );
+        vec!["amplitude".to_string(), format!("{:.8} V", results.a_ref)],
+        vec![
+            "phase".to_string(),
+            format!("{:.8} rad", results.omega_tref),
+        ],
+    ];
+    if let Some(fit) = results.fit {
+        rows[0][1].push_str(&format!(" ± {:.3e} Hz", fit.frequency_std_hz));
+        rows[1][1].push_str(&format!(" ± {:.3e} V", fit.amplitude_std));
+        rows[2][1].push_str(&format!(" ± {:.3e} rad", fit.phase_std_rad));
+        rows.push(vec![
+            "offset".to_string(),
+            format!("{:.6e} ± {:.3e} V", fit.offset, fit.offset_std),
+        ]);
+        rows.push(vec![
+            "residual rms".to_string(),
+            format!(
+                "{:.6e} V (max {:.6e} V)",
+                fit.residual_rms, fit.residual_max_abs
+            ),
+        ]);
+        rows.push(vec!["r²".to_string(), format!("{:.8}", fit.r_squared)]);
+        rows.push(vec![
+            "iterations".to_string(),
+            format!("{} ({} samples)", fit.iterations, fit.samples),
+        ]);
+        if !fit.converged {
+            ui::warn(format!(
+                "reference fit did not converge within {} iterations; results may be inaccurate",
+                fit.iterations
+            ));
+        }
+    }
+    ui::summary_table("Reference fit", &["Metric", "Value"], rows);
     if should_plot {
         plot_fit_results(cfg, &fit_t, &fit_ref_data, &results)
             .context("failed to plot reference signal")?;
@@ -1,104 +1,49 @@
-use crate::python;
 use anyhow::{Context, Result};
-use pyo3::prelude::*;
-use pyo3::types::PyModule;
-use std::sync::OnceLock;
+use pmoke_analysis_core::{ReferenceSineFit, estimate_reference_spectrum_peak, fit_reference_sine};
 
-#[allow(dead_code)]
-const REF_ANALYSIS_PY: &str = include_str!("pytools/ref_analysis.py");
-static REF_ANALYSIS_MODULE: OnceLock<Py<PyModule>> = OnceLock::new();
-
-#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 pub struct RefFitParams {
     pub f_ref: f64,
     pub a_ref: f64,
     pub omega_tref: f64,
+    pub fit: Option<ReferenceSineFit>,
 }
 
-#[allow(dead_code)]
 pub struct ReferenceFFT {}
 
 impl ReferenceFFT {
     pub fn fft(&self, dt: f64, y: &[f64]) -> Result<RefFitParams> {
-        Python::attach(|py| {
-            let fit_mod = python::cached_module(
-                py,
-                &REF_ANALYSIS_MODULE,
-                REF_ANALYSIS_PY,
-                "ref_analysis.py",
-                "ref_analysis",
-            )
-            .context("failed to load ref_analysis.py")?;
-            let y_obj = python::f64_array1(py, y);
-
-            let fitter = fit_mod
-                .getattr("ReferenceFFT")?
-                .call0()
-                .context("failed to create ReferenceFFT instance")?;
-
-            let res = fitter
-                .call_method1("fft", (dt, y_obj))
-                .context("python ReferenceFFT.fft(...) failed")?;
-
-            let f_ref: f64 = res.get_item("f_ref")?.extract()?;
-            let a_ref: f64 = res.get_item("A_ref")?.extract()?;
-            let omega_tref: f64 = res.get_item("omega_tref")?.extract()?;
-
-            Ok(RefFitParams {
-                f_ref,
-                a_ref,
-                omega_tref,
-            })
+        let peak = estimate_reference_spectrum_peak(dt, y)
+            .context("reference FFT peak estimation failed")?;
+
+        Ok(RefFitParams {
+            f_ref: peak.frequency_hz,
+            a_ref: peak.amplitude,
+            omega_tref: peak.phase_rad,
+            fit: None,
         })
     }
 }
 
-#[allow(dead_code)]
 pub struct ReferenceFitter {}
 
 impl ReferenceFitter {
     pub fn fit(&self, t: &[f64], y: &[f64], params: RefFitParams) -> Result<RefFitParams> {
-        Python::attach(|py| {
-            let fit_mod = python::cached_module(
-                py,
-                &REF_ANALYSIS_MODULE,
-                REF_ANALYSIS_PY,
-                "ref_analysis.py",
-                "ref_analysis",
-            )
-            .context("failed to load ref_analysis.py")?;
-            let t_obj = python::f64_array1(py, t);
-            let y_obj = python::f64_array1(py, y);
-
-            let fitter = fit_mod
-                .getattr("ReferenceFitter")?
-                .call0()
-                .context("failed to create ReferenceFitter instance")?;
-
-            let res = fitter
-                .call_method1(
-                    "fit",
-                    (t_obj, y_obj, params.f_ref, params.a_ref, params.omega_tref),
-                )
-                .context("python ReferenceFitter.fit(...) failed")?;
-
-            let f_ref: f64 = res.get_item("f_ref")?.extract()?;
-            let a_ref: f64 = res.get_item("A_ref")?.extract()?;
-            let omega_tref: f64 = res.get_item("omega_tref")?.extract()?;
-
-            Ok(RefFitParams {
-                f_ref,
-                a_ref,
-                omega_tref,
-            })
+        let fit = fit_reference_sine(t, y, params.f_ref)
+            .context("reference sine least-squares fit failed")?;
+
+        Ok(RefFitParams {
+            f_ref: fit.frequency_hz,
+            a_ref: fit.amplitude,
+            omega_tref: fit.phase_rad,
+            fit: Some(fit),
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{RefFitParams, ReferenceFFT};
+    use super::{RefFitParams, ReferenceFFT, ReferenceFitter};
     use std::f64::consts::PI;
 
     #[test]
@@ -176,4 +121,32 @@ mod tests {
 
         assert!(format!("{error:#}").contains("requires finite samples"));
     }
+
+    #[test]
+    fn reference_fitter_refines_fft_seed_on_strided_blocks() {
+        let sample_rate = 100.0e6;
+        let frequency = 1.234_567e6;
+        let dt = 1.0 / sample_rate;
+        let t: Vec<f64> = (0..40_000).map(|index| index as f64 * dt).collect();
+        let y: Vec<f64> = t
+            .iter()
+            .map(|&time| 0.02 + 0.8 * (2.0 * PI * frequency * time - 0.7).sin())
+            .collect();
+        let (fit_t, fit_y): (Vec<f64>, Vec<f64>) = (0..t.len())
+            .filter(|index| index % 10_000 < 1_000)
+            .map(|index| (t[index], y[index]))
+            .unzip();
+
+        let seed = ReferenceFFT {}.fft(dt, &y[..4_096]).unwrap();
+        let result = ReferenceFitter {}.fit(&fit_t, &fit_y, seed).unwrap();
+        let fit = result.fit.expect("fitter records fit statistics");
+
+        assert!(fit.converged);
+        assert_eq!(fit.samples, fit_t.len());
+        assert!((result.f_ref - frequency).abs() < 1.0e-3);
+        assert!((result.a_ref - 0.8).abs() < 1.0e-9);
+        assert!((result.omega_tref - 0.7).abs() < 1.0e-8);
+        assert!((fit.offset - 0.02).abs() < 1.0e-9);
+        assert!(fit.residual_rms < 1.0e-9);
+    }
 }
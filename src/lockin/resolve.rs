@@ -25,6 +25,31 @@ pub fn sensor_column_indices(cfg: &Config) -> Result<(Vec<u8>, Vec<usize>)> {
     Ok((sensor_ch.clone(), col_idx))
 }
 
+/// Column of each sensor's compensation coil, aligned with `roles.sensor_ch`.
+pub fn sensor_compensation_column_indices(cfg: &Config) -> Result<Vec<Option<usize>>> {
+    let channels = build_channel_list(cfg)?;
+    cfg.roles
+        .sensor_ch
+        .iter()
+        .map(|&ch| {
+            cfg.sensor_compensation(ch)
+                .map(|compensation| {
+                    channels
+                        .iter()
+                        .position(|c| *c == compensation.channel)
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "compensation channel {} not found in fetched channels {:?}",
+                                compensation.channel,
+                                channels
+                            )
+                        })
+                })
+                .transpose()
+        })
+        .collect()
+}
+
 pub fn reference_column_index(cfg: &Config) -> Result<(u8, usize)> {
     let ref_ch = cfg.roles.reference_ch;
     if ref_ch == 0 {
@@ -1,11 +1,22 @@
 use crate::analysis_results::{build_analysis_headers, write_analysis_results};
 use crate::config::Config;
-use crate::constants::LI_HEADER;
 use anyhow::Result;
 use std::path::Path;
 
+pub fn lockin_result_headers(harmonics: &[usize]) -> Vec<String> {
+    harmonics
+        .iter()
+        .flat_map(|harmonic| {
+            [
+                format!("LIx_h{harmonic} (V)"),
+                format!("LIy_h{harmonic} (V)"),
+            ]
+        })
+        .collect()
+}
+
 pub fn get_li_headers(cfg: &Config) -> Result<Vec<String>> {
-    build_analysis_headers(cfg, LI_HEADER)
+    build_analysis_headers(cfg, lockin_result_headers(&cfg.lockin.harmonics))
 }
 
 pub fn write_li_results<P: AsRef<Path>>(
@@ -22,8 +33,8 @@ pub fn write_li_results<P: AsRef<Path>>(
 
 #[cfg(test)]
 mod tests {
-    use super::get_li_headers;
-    use crate::{constants::LI_HEADER, test_support::test_config};
+    use super::{get_li_headers, lockin_result_headers};
+    use crate::test_support::test_config;
 
     #[test]
     fn lockin_headers_use_rate_integral_result_order() {
@@ -38,7 +49,29 @@ mod tests {
             "ch1 integral (T)".to_string(),
             "ch2 integral (T)".to_string(),
         ];
-        expected.extend(LI_HEADER.iter().map(|header| header.to_string()));
+        expected.extend(lockin_result_headers(&[1, 2, 3, 4, 5, 6]));
         assert_eq!(headers, expected);
+        assert_eq!(expected[5], "LIx_h1 (V)");
+        assert_eq!(expected[16], "LIy_h6 (V)");
+    }
+
+    #[test]
+    fn lockin_headers_follow_configured_harmonics() {
+        let mut cfg = test_config(vec![1], vec![3]);
+        cfg.lockin.harmonics = vec![1, 2, 8];
+
+        let headers = get_li_headers(&cfg).unwrap();
+
+        assert_eq!(
+            &headers[3..],
+            [
+                "LIx_h1 (V)",
+                "LIy_h1 (V)",
+                "LIx_h2 (V)",
+                "LIy_h2 (V)",
+                "LIx_h8 (V)",
+                "LIy_h8 (V)",
+            ]
+        );
     }
 }
@@ -2,7 +2,7 @@ pub mod pulse_calculator;
 pub mod sensor_integral_plot;
 pub mod sensor_raw_plot;
 
-use crate::config::{Channel, Config};
+use crate::config::{Channel, CoilCalibration, Config, PulseBaseline};
 use crate::constants::FETCHED_FNAME;
 use crate::lockin::reference::run_fit_ref_without_plot;
 use crate::lockin::stride::{li_stride_2d, li_stride_time};
@@ -10,6 +10,9 @@ use crate::utils::time_axis::TimeAxisRef;
 use crate::utils::waveform::read_waveform_channels;
 use crate::{plot, ui};
 use anyhow::{Context, Result, bail};
+use pulse_calculator::{PulseBaselineFit, PulseBaselineFitter};
+use serde::Serialize;
+use std::borrow::Cow;
 
 pub struct SensorMeta<'a> {
     pub scale: SensorScale,
@@ -27,6 +30,33 @@ pub struct SensorOutput {
     pub t: Vec<f64>,
     pub rate: Vec<Vec<f64>>,
     pub integral: Vec<Vec<f64>>,
+    pub provenance: SensorProvenance,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorProvenance {
+    pub baseline: PulseBaseline,
+    pub channels: Vec<SensorChannelProvenance>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorChannelProvenance {
+    pub channel: u8,
+    pub unit: String,
+    /// Resolved `unit` per integrated volt-second.
+    pub factor: f64,
+    /// Pickup-coil calibration that `factor` was resolved from; `unit` per
+    /// volt-second is then tesla per volt-second.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coil: Option<CoilCalibration>,
+    pub baseline_origin_s: f64,
+    /// Baseline coefficients in V, V/s, V/s², … about `baseline_origin_s`.
+    pub baseline_coefficients: Vec<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_of_pulse_time_s: Option<f64>,
+    /// Scaled integral at `end_of_pulse_time_s`; zero for a drift-free pulse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub residual_end_field: Option<f64>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -39,16 +69,32 @@ pub fn run(cfg: &Config) -> Result<()> {
     let ref_fit_params = run_fit_ref_without_plot(cfg)?;
 
     let sensor_ch = cfg.roles.sensor_ch.clone();
-    let pb = ui::spinner(format!("reading sensor channels {:?}", sensor_ch));
+    let compensation_ch = cfg.sensor_compensation_ch();
+    let read_ch = sensor_ch
+        .iter()
+        .chain(compensation_ch.iter())
+        .copied()
+        .collect::<Vec<_>>();
+    let pb = ui::spinner(format!("reading sensor channels {:?}", read_ch));
     let t0 = std::time::Instant::now();
     let waveform =
-        read_waveform_channels(cfg, &sensor_ch).context("failed to read sensor channels")?;
-    let s_col_refs: Vec<&[f64]> = waveform.channels.iter().map(|col| col.as_slice()).collect();
+        read_waveform_channels(cfg, &read_ch).context("failed to read sensor channels")?;
+    let (s_cols, compensation_cols) = waveform.channels.split_at(sensor_ch.len());
+    let s_col_refs: Vec<&[f64]> = s_cols.iter().map(|col| col.as_slice()).collect();
+    let mut compensation_cols = compensation_cols.iter();
+    let compensation_refs: Vec<Option<&[f64]>> = sensor_ch
+        .iter()
+        .map(|&ch| {
+            cfg.sensor_compensation(ch)
+                .and_then(|_| compensation_cols.next())
+                .map(|col| col.as_slice())
+        })
+        .collect();
     ui::finish_read(
         pb,
         format!(
             "sensor channels {:?} ({})",
-            sensor_ch,
+            read_ch,
             ui::fmt_duration(t0.elapsed())
         ),
     );
@@ -57,6 +103,7 @@ pub fn run(cfg: &Config) -> Result<()> {
         cfg,
         &waveform.t,
         &s_col_refs,
+        &compensation_refs,
         &sensor_ch,
         ref_fit_params.f_ref,
     )?;
@@ -67,6 +114,7 @@ pub fn run_sensor<'a>(
     cfg: &Config,
     t: impl Into<TimeAxisRef<'a>>,
     s_cols: &[&[f64]],
+    compensation_cols: &[Option<&[f64]>],
     sensor_ch: &[u8],
     f_ref: f64,
 ) -> Result<SensorOutput> {
@@ -79,20 +127,27 @@ pub fn run_sensor<'a>(
 
     let sensor_meta = extract_sensor_metadata(cfg)?;
     validate_sensor_channel_alignment(&cfg.roles.sensor_ch, s_cols, sensor_ch, &sensor_meta)?;
-
-    let pb = ui::spinner("averaging sensor backgrounds");
+    let compensated = compensate_sensor_columns(cfg, sensor_ch, s_cols, compensation_cols)?;
+    let compensated_refs: Vec<&[f64]> = compensated.iter().map(AsRef::as_ref).collect();
+    let s_cols: &[&[f64]] = &compensated_refs;
+
+    let end_of_pulse = end_of_pulse_index(cfg, t);
+    let pb = ui::spinner(format!(
+        "fitting {} sensor baselines",
+        cfg.pulse.baseline.kind()
+    ));
     let t0 = std::time::Instant::now();
-    let c_bg_arr = match calculate_background_averages(cfg, t, s_cols) {
-        Ok(c_bg_arr) => c_bg_arr,
+    let baselines = match calculate_background_baselines(cfg, t, s_cols, end_of_pulse) {
+        Ok(baselines) => baselines,
         Err(err) => {
-            ui::finish_cancelled(pb, "sensor background averaging stopped");
+            ui::finish_cancelled(pb, "sensor baseline fitting stopped");
             return Err(err);
         }
     };
     ui::finish_success(
         pb,
         format!(
-            "sensor backgrounds averaged ({})",
+            "sensor baselines fitted ({})",
             ui::fmt_duration(t0.elapsed())
         ),
     );
@@ -106,8 +161,12 @@ pub fn run_sensor<'a>(
         "sensor raw plot completed",
         |output| {
             let s_stride = li_stride_2d(cfg, t, s_cols, f_ref)?;
+            let bg_stride = baselines
+                .iter()
+                .map(|baseline| t_stride.iter().map(|&ti| baseline.value_at(ti)).collect())
+                .collect();
             sensor_raw_plot::SensorRawPlotter {}
-                .plot(&cfg.plot, output, &t_stride, s_stride, sensor_ch, &c_bg_arr)
+                .plot(&cfg.plot, output, &t_stride, s_stride, bg_stride, sensor_ch)
                 .context("failed to plot sensor data")
         },
     )?;
@@ -115,15 +174,12 @@ pub fn run_sensor<'a>(
     let pb = ui::progress("integrating sensor pulses", s_cols.len() as u64);
     let start = std::time::Instant::now();
 
-    let dt = t
-        .dt()
-        .ok_or_else(|| anyhow::anyhow!("time axis must contain at least two samples"))?;
     let sensor_series = s_cols
         .iter()
-        .zip(c_bg_arr.iter())
+        .zip(baselines.iter())
         .zip(sensor_meta.iter())
-        .map(|((s, &c_bg), meta)| {
-            let series = calculate_sensor_series(dt, s, c_bg, meta)?;
+        .map(|((s, baseline), meta)| {
+            let series = calculate_sensor_series(t, s, baseline, meta)?;
             pb.inc(1);
             Ok(series)
         })
@@ -151,6 +207,59 @@ pub fn run_sensor<'a>(
     if !scale_summary.is_empty() {
         ui::settings_table("Sensor auto scales", scale_summary);
     }
+    let coil_summary = sensor_ch
+        .iter()
+        .zip(sensor_meta.iter())
+        .filter_map(|(&ch, meta)| {
+            cfg.sensor_coil(ch)
+                .map(|coil| (format!("ch{ch} {}", meta.label), coil_summary(&coil)))
+        })
+        .collect::<Vec<_>>();
+    if !coil_summary.is_empty() {
+        ui::settings_table("Sensor coil calibrations", coil_summary);
+    }
+    let end_of_pulse_time = end_of_pulse.map(|index| t.value_at(index));
+    let provenance = SensorProvenance {
+        baseline: cfg.pulse.baseline,
+        channels: sensor_ch
+            .iter()
+            .zip(sensor_meta.iter())
+            .zip(baselines.iter().zip(sensor_series.iter()))
+            .map(
+                |((&channel, meta), (baseline, series))| SensorChannelProvenance {
+                    channel,
+                    unit: meta.unit.to_string(),
+                    factor: series.factor,
+                    coil: cfg.sensor_coil(channel),
+                    baseline_origin_s: baseline.origin,
+                    baseline_coefficients: baseline.coefficients.clone(),
+                    end_of_pulse_time_s: end_of_pulse_time,
+                    residual_end_field: end_of_pulse.map(|index| series.integral[index]),
+                },
+            )
+            .collect(),
+    };
+    ui::settings_table(
+        "Sensor baselines",
+        sensor_ch
+            .iter()
+            .zip(sensor_meta.iter())
+            .zip(provenance.channels.iter())
+            .map(|((&ch, meta), channel)| {
+                let residual = match (channel.residual_end_field, end_of_pulse_time) {
+                    (Some(residual), Some(time)) => {
+                        format!("residual={residual:.6e} {} at {time:.6e} s", meta.unit)
+                    }
+                    _ => "residual unavailable (background_after starts after the record)"
+                        .to_string(),
+                };
+                (
+                    format!("ch{ch} {}", meta.label),
+                    format!("{} baseline, {residual}", cfg.pulse.baseline.kind()),
+                )
+            })
+            .collect(),
+    );
     let s_rate = sensor_series
         .iter()
         .map(|series| series.rate.clone())
@@ -221,9 +330,77 @@ pub fn run_sensor<'a>(
         t: t_stride,
         rate: s_rate_stride,
         integral: s_integral_stride,
+        provenance,
     })
 }
 
+/// Subtracts `balance` times the compensation coil from each sensor column
+/// that has one configured.
+fn compensate_sensor_columns<'c>(
+    cfg: &Config,
+    sensor_ch: &[u8],
+    s_cols: &[&'c [f64]],
+    compensation_cols: &[Option<&[f64]>],
+) -> Result<Vec<Cow<'c, [f64]>>> {
+    if compensation_cols.len() != s_cols.len() {
+        bail!(
+            "sensor compensation count mismatch: data={}, compensation={}",
+            s_cols.len(),
+            compensation_cols.len()
+        );
+    }
+    sensor_ch
+        .iter()
+        .zip(s_cols.iter())
+        .zip(compensation_cols.iter())
+        .map(|((&ch, &column), compensation_col)| {
+            match (cfg.sensor_compensation(ch), compensation_col) {
+                (None, None) => Ok(Cow::Borrowed(column)),
+                (Some(compensation), Some(compensation_col)) => {
+                    if compensation_col.len() != column.len() {
+                        bail!(
+                            "sensor ch{ch} length ({}) and compensation ch{} length ({}) differ",
+                            column.len(),
+                            compensation.channel,
+                            compensation_col.len()
+                        );
+                    }
+                    Ok(Cow::Owned(
+                        column
+                            .iter()
+                            .zip(compensation_col.iter())
+                            .map(|(&value, &other)| value - compensation.balance * other)
+                            .collect(),
+                    ))
+                }
+                (Some(compensation), None) => bail!(
+                    "compensation channel {} for sensor ch{ch} was not read",
+                    compensation.channel
+                ),
+                (None, Some(_)) => {
+                    bail!("sensor ch{ch} has compensation data but no compensation coil")
+                }
+            }
+        })
+        .collect()
+}
+
+/// One-line description of a coil calibration and its resolved factor.
+pub fn coil_summary(coil: &CoilCalibration) -> String {
+    let compensation = coil.compensation.map_or_else(
+        || "none".to_string(),
+        |compensation| format!("ch{} x {}", compensation.channel, compensation.balance),
+    );
+    format!(
+        "factor={:.6e} T/(V*s), area_turns={:.6e} m^2, gain={}, polarity={}, compensation={}",
+        coil.tesla_per_volt_second(),
+        coil.area_turns,
+        coil.gain,
+        coil.polarity,
+        compensation
+    )
+}
+
 fn validate_sensor_channel_alignment(
     configured_sensor_ch: &[u8],
     s_cols: &[&[f64]],
@@ -313,6 +490,22 @@ pub fn extract_sensor_metadata<'a>(cfg: &'a Config) -> Result<Vec<SensorMeta<'a>
 }
 
 fn sensor_scale_from_channel(channel: &Channel) -> Result<SensorScale> {
+    if let Some(coil) = channel.coil {
+        if channel.factor.is_some() || channel.scale_to_abs_max.is_some() {
+            bail!(
+                "channel {} cannot set a coil calibration together with 'factor' or 'scale_to_abs_max'",
+                channel.index
+            );
+        }
+        let factor = coil.tesla_per_volt_second();
+        if !factor.is_finite() || factor == 0.0 {
+            bail!(
+                "channel {} coil calibration must resolve to a finite, non-zero factor",
+                channel.index
+            );
+        }
+        return Ok(SensorScale::Factor(factor));
+    }
     match (channel.factor, channel.scale_to_abs_max) {
         (Some(factor), None) => {
             if !factor.is_finite() {
@@ -355,11 +548,19 @@ fn validate_sensor_lengths<'a>(t: impl Into<TimeAxisRef<'a>>, s_cols: &[&[f64]])
     Ok(())
 }
 
-fn calculate_background_averages(
+/// First sample at or after the start of the after-pulse background window.
+fn end_of_pulse_index(cfg: &Config, t: TimeAxisRef<'_>) -> Option<usize> {
+    let start = cfg.pulse.bg_window_after.start;
+    let index = t.partition_point(|ti| ti < start);
+    (index < t.len()).then_some(index)
+}
+
+fn calculate_background_baselines(
     cfg: &Config,
     t: TimeAxisRef<'_>,
     s_cols: &[&[f64]],
-) -> Result<Vec<f64>> {
+    end_of_pulse: Option<usize>,
+) -> Result<Vec<PulseBaselineFit>> {
     let bg_window_before = &cfg.pulse.bg_window_before;
     let bg_window_after = &cfg.pulse.bg_window_after;
 
@@ -372,15 +573,43 @@ fn calculate_background_averages(
         bail!("No data points found in background windows. Cannot calculate background average.");
     }
 
+    let fitter = match cfg.pulse.baseline {
+        PulseBaseline::Constant => None,
+        baseline => Some(PulseBaselineFitter::new(
+            baseline.order(),
+            bg_window_before.start.min(bg_window_after.start),
+            bg_window_before.end.max(bg_window_after.end),
+        )?),
+    };
+    let dt = t
+        .dt()
+        .ok_or_else(|| anyhow::anyhow!("time axis must contain at least two samples"))?;
+
     s_cols
         .iter()
         .map(|col| {
-            let values = col
+            let samples = col
                 .iter()
                 .zip(t.iter())
-                .filter_map(|(&yi, ti)| is_in_bg(ti).then_some(yi));
+                .filter_map(|(&yi, ti)| is_in_bg(ti).then_some((ti, yi)));
 
-            pulse_calculator::PulseBgAverage {}.calculate(values)
+            let Some(fitter) = &fitter else {
+                let average = pulse_calculator::PulseBgAverage {}
+                    .calculate(samples.map(|(_, yi)| yi))?;
+                return Ok(PulseBaselineFit::constant(average));
+            };
+            let constraint = match cfg.pulse.baseline {
+                PulseBaseline::EndpointConstrained { .. } => {
+                    let end = end_of_pulse.ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "endpoint_constrained baseline needs pulse.background_after to start inside the record"
+                        )
+                    })?;
+                    Some(fitter.endpoint_constraint(dt, t.iter(), col, end)?)
+                }
+                _ => None,
+            };
+            fitter.fit(samples, constraint.as_ref())
         })
         .collect::<Result<Vec<_>>>()
 }
@@ -418,13 +647,17 @@ struct SensorSeries {
 }
 
 fn calculate_sensor_series(
-    dt: f64,
+    t: TimeAxisRef<'_>,
     data: &[f64],
-    background: f64,
+    baseline: &PulseBaselineFit,
     meta: &SensorMeta<'_>,
 ) -> Result<SensorSeries> {
-    let unscaled_integral =
-        pulse_calculator::PulseIntegralCalculator::new(dt).integrate(data, background, 1.0);
+    let dt = t
+        .dt()
+        .ok_or_else(|| anyhow::anyhow!("time axis must contain at least two samples"))?;
+    let background = || t.iter().map(|ti| baseline.value_at(ti));
+    let unscaled_integral = pulse_calculator::PulseIntegralCalculator::new(dt)
+        .integrate_with_baseline(data, background(), 1.0);
     let (factor, unscaled_max_abs) = match meta.scale {
         SensorScale::Factor(factor) => {
             if !factor.is_finite() {
@@ -445,7 +678,8 @@ fn calculate_sensor_series(
     }
     let rate = data
         .iter()
-        .map(|&value| (value - background) * factor)
+        .zip(background())
+        .map(|(&value, bg)| (value - bg) * factor)
         .collect::<Vec<_>>();
     let integral = unscaled_integral
         .iter()
@@ -480,9 +714,14 @@ fn max_abs_finite(values: &[f64]) -> Result<f64> {
 #[cfg(test)]
 mod tests {
     use super::{
-        SensorMeta, SensorScale, calculate_sensor_rates, calculate_sensor_series,
-        maximum_absolute_integral, validate_sensor_channel_alignment, validate_sensor_lengths,
+        PulseBaselineFit, SensorMeta, SensorScale, calculate_background_baselines,
+        calculate_sensor_rates, calculate_sensor_series, compensate_sensor_columns,
+        end_of_pulse_index, maximum_absolute_integral, validate_sensor_channel_alignment,
+        validate_sensor_lengths,
     };
+    use crate::config::{PulseBaseline, Window};
+    use crate::utils::time_axis::TimeAxisRef;
+    use std::f64::consts::PI;
 
     #[test]
     fn sensor_length_validation_checks_every_channel() {
@@ -495,6 +734,38 @@ mod tests {
         assert!(error.to_string().contains("sensor column 2"));
     }
 
+    #[test]
+    fn compensation_coil_is_subtracted_with_its_balance() {
+        let mut cfg = crate::test_support::test_config(vec![1, 2], vec![3]);
+        cfg.channels[1].factor = None;
+        cfg.channels[1].coil = Some(crate::config::CoilCalibration {
+            area_turns: 1.0,
+            gain: 1.0,
+            polarity: 1,
+            compensation: Some(crate::config::CoilCompensation {
+                channel: 5,
+                balance: 0.5,
+            }),
+        });
+        let plain = [1.0, 2.0];
+        let main = [3.0, 4.0];
+        let compensation = [2.0, -2.0];
+
+        let columns = compensate_sensor_columns(
+            &cfg,
+            &[1, 2],
+            &[&plain, &main],
+            &[None, Some(&compensation)],
+        )
+        .unwrap();
+        assert_eq!(columns[0].as_ref(), &plain);
+        assert_eq!(columns[1].as_ref(), &[2.0, 5.0]);
+
+        let error =
+            compensate_sensor_columns(&cfg, &[1, 2], &[&plain, &main], &[None, None]).unwrap_err();
+        assert!(error.to_string().contains("compensation channel 5"));
+    }
+
     #[test]
     fn sensor_channel_alignment_checks_order_and_count() {
         let data = [0.0, 1.0];
@@ -576,7 +847,13 @@ mod tests {
             unit: "T",
         };
 
-        let series = calculate_sensor_series(1.0, &data, 1.0, &metadata).unwrap();
+        let series = calculate_sensor_series(
+            TimeAxisRef::from(&[0.0, 1.0, 2.0]),
+            &data,
+            &PulseBaselineFit::constant(1.0),
+            &metadata,
+        )
+        .unwrap();
 
         assert_eq!(series.factor, -2.0);
         assert_eq!(series.unscaled_max_abs, Some(4.0));
@@ -592,8 +869,80 @@ mod tests {
             unit: "T",
         };
 
-        let error = calculate_sensor_series(1.0, &[1.0, 1.0], 1.0, &metadata).unwrap_err();
+        let error = calculate_sensor_series(
+            TimeAxisRef::from(&[0.0, 1.0]),
+            &[1.0, 1.0],
+            &PulseBaselineFit::constant(1.0),
+            &metadata,
+        )
+        .unwrap_err();
 
         assert!(error.to_string().contains("maximum is zero"));
     }
+
+    #[test]
+    fn drift_corrected_baselines_return_the_field_integral_to_zero() {
+        let mut cfg = crate::test_support::test_config(vec![1], vec![2]);
+        cfg.pulse.bg_window_before = Window {
+            start: 0.0,
+            end: 0.2,
+        };
+        cfg.pulse.bg_window_after = Window {
+            start: 0.6,
+            end: 1.0,
+        };
+        let time = (0..=1000)
+            .map(|index| index as f64 * 1.0e-3)
+            .collect::<Vec<_>>();
+        let data = time
+            .iter()
+            .map(|&t| {
+                let pulse = if (0.3..0.5).contains(&t) {
+                    (2.0 * PI * (t - 0.3) / 0.2).sin()
+                } else {
+                    0.0
+                };
+                pulse + 1.0e-3 + 2.0e-3 * t
+            })
+            .collect::<Vec<_>>();
+        let metadata = SensorMeta {
+            scale: SensorScale::Factor(1.0),
+            label: "field",
+            unit: "T",
+        };
+        let t = TimeAxisRef::from(&time);
+        let end = end_of_pulse_index(&cfg, t).unwrap();
+        assert_eq!(time[end], 0.6);
+
+        let mut residual = |baseline: PulseBaseline| {
+            cfg.pulse.baseline = baseline;
+            let fits = calculate_background_baselines(&cfg, t, &[&data], Some(end)).unwrap();
+            let series = calculate_sensor_series(t, &data, &fits[0], &metadata).unwrap();
+            (fits[0].clone(), series.integral[end])
+        };
+
+        let (_, constant) = residual(PulseBaseline::Constant);
+        assert!(constant.abs() > 1.0e-4, "{constant}");
+
+        let (linear_fit, linear) = residual(PulseBaseline::Linear);
+        assert!(linear.abs() < 1.0e-6, "{linear}");
+        assert!((linear_fit.coefficients[1] - 2.0e-3).abs() < 1.0e-9);
+
+        let (_, constrained) = residual(PulseBaseline::EndpointConstrained { order: 1 });
+        assert!(constrained.abs() < 1.0e-12, "{constrained}");
+    }
+
+    #[test]
+    fn end_of_pulse_is_missing_when_the_after_window_starts_past_the_record() {
+        let mut cfg = crate::test_support::test_config(vec![1], vec![2]);
+        cfg.pulse.bg_window_after = Window {
+            start: 5.0,
+            end: 6.0,
+        };
+
+        assert_eq!(
+            end_of_pulse_index(&cfg, TimeAxisRef::from(&[0.0, 1.0, 2.0])),
+            None
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use crate::utils::numeric::solve_linear_system;
 use anyhow::{Result, bail};
 
 pub struct PulseBgAverage {}
@@ -49,12 +50,24 @@ impl PulseIntegralCalculator {
     }
 
     pub fn integrate(&self, data: &[f64], c_bg: f64, coeff: f64) -> Vec<f64> {
+        self.integrate_with_baseline(data, std::iter::repeat(c_bg), coeff)
+    }
+
+    /// Integrates `data` after subtracting a per-sample baseline.
+    pub fn integrate_with_baseline<I>(&self, data: &[f64], baseline: I, coeff: f64) -> Vec<f64>
+    where
+        I: IntoIterator<Item = f64>,
+    {
         let n = data.len();
         if n == 0 {
             return Vec::new();
         }
+        let mut corrected = data.iter().zip(baseline).map(|(&value, bg)| value - bg);
         if n == 1 {
-            return vec![(data[0] - c_bg) * coeff];
+            return corrected
+                .next()
+                .map(|value| vec![value * coeff])
+                .unwrap_or_default();
         }
 
         let h = self.dt;
@@ -62,12 +75,14 @@ impl PulseIntegralCalculator {
         out.push(0.0);
 
         let mut acc = 0.0;
-        for i in 1..n {
-            let s0 = data[i - 1] - c_bg;
-            let s1 = data[i] - c_bg;
+        let Some(mut s0) = corrected.next() else {
+            return out;
+        };
+        for s1 in corrected {
             let incr = h * (s0 + s1) * 0.5;
             acc += incr;
             out.push(acc);
+            s0 = s1;
         }
 
         if coeff != 1.0 {
@@ -80,9 +95,186 @@ impl PulseIntegralCalculator {
     }
 }
 
+/// Background polynomial `c0 + c1·τ + … + cn·τⁿ` with `τ = t - origin`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PulseBaselineFit {
+    pub origin: f64,
+    /// Coefficients in V, V/s, V/s², … for increasing powers of `τ`.
+    pub coefficients: Vec<f64>,
+}
+
+impl PulseBaselineFit {
+    pub fn constant(value: f64) -> Self {
+        Self {
+            origin: 0.0,
+            coefficients: vec![value],
+        }
+    }
+
+    pub fn value_at(&self, t: f64) -> f64 {
+        let tau = t - self.origin;
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &coefficient| acc * tau + coefficient)
+    }
+}
+
+/// Trapezoidal integrals of each baseline basis function and of the raw data
+/// from the first sample up to the end-of-pulse sample.
+#[derive(Debug, Clone)]
+pub struct PulseEndpointConstraint {
+    moments: Vec<f64>,
+    target: f64,
+}
+
+/// Least-squares polynomial fit through background-window samples.
+///
+/// Time is mapped onto `x = (t - origin) / scale` with the background span in
+/// `[-1, 1]` so the normal equations stay well conditioned.
+#[derive(Debug, Clone)]
+pub struct PulseBaselineFitter {
+    order: usize,
+    origin: f64,
+    scale: f64,
+}
+
+impl PulseBaselineFitter {
+    pub fn new(order: usize, start: f64, end: f64) -> Result<Self> {
+        if !start.is_finite() || !end.is_finite() || start >= end {
+            bail!("baseline fit span must be finite and increasing");
+        }
+        Ok(Self {
+            order,
+            origin: 0.5 * (start + end),
+            scale: 0.5 * (end - start),
+        })
+    }
+
+    pub fn endpoint_constraint<T>(
+        &self,
+        dt: f64,
+        t: T,
+        data: &[f64],
+        end: usize,
+    ) -> Result<PulseEndpointConstraint>
+    where
+        T: IntoIterator<Item = f64>,
+    {
+        if end == 0 || end >= data.len() {
+            bail!("end-of-pulse sample must lie inside the record after its first sample");
+        }
+        let mut moments = vec![0.0; self.order + 1];
+        let mut target = 0.0;
+        for (index, (ti, &value)) in t.into_iter().zip(data).take(end + 1).enumerate() {
+            if !value.is_finite() {
+                bail!("sensor data before the end of the pulse contains a non-finite value");
+            }
+            let weight = if index == 0 || index == end { 0.5 } else { 1.0 };
+            let x = self.scaled(ti);
+            let mut power = 1.0;
+            for moment in &mut moments {
+                *moment += weight * power;
+                power *= x;
+            }
+            target += weight * value;
+        }
+        for moment in &mut moments {
+            *moment *= dt;
+        }
+        Ok(PulseEndpointConstraint {
+            moments,
+            target: target * dt,
+        })
+    }
+
+    pub fn fit<I>(
+        &self,
+        samples: I,
+        constraint: Option<&PulseEndpointConstraint>,
+    ) -> Result<PulseBaselineFit>
+    where
+        I: IntoIterator<Item = (f64, f64)>,
+    {
+        let terms = self.order + 1;
+        let mut powers = vec![0.0; 2 * self.order + 1];
+        let mut rhs = vec![0.0; terms];
+        let mut count = 0usize;
+        for (t, value) in samples {
+            if !value.is_finite() {
+                bail!("background window contains a non-finite value");
+            }
+            let x = self.scaled(t);
+            let mut power = 1.0;
+            for (index, sum) in powers.iter_mut().enumerate() {
+                if index < terms {
+                    rhs[index] += value * power;
+                }
+                *sum += power;
+                power *= x;
+            }
+            count += 1;
+        }
+        if count == 0 {
+            bail!("cannot fit a background baseline to empty windows");
+        }
+
+        let size = terms + usize::from(constraint.is_some());
+        let mut matrix = vec![vec![0.0; size + 1]; size];
+        let norm = count as f64;
+        for row in 0..terms {
+            for column in 0..terms {
+                matrix[row][column] = powers[row + column] / norm;
+            }
+            matrix[row][size] = rhs[row] / norm;
+        }
+        if let Some(constraint) = constraint {
+            let row_scale = constraint
+                .moments
+                .iter()
+                .fold(0.0_f64, |acc, moment| acc.max(moment.abs()));
+            if row_scale == 0.0 || !row_scale.is_finite() {
+                bail!("end-of-pulse constraint is degenerate");
+            }
+            for (index, moment) in constraint.moments.iter().enumerate() {
+                matrix[terms][index] = moment / row_scale;
+                matrix[index][terms] = moment / row_scale;
+            }
+            matrix[terms][size] = constraint.target / row_scale;
+        }
+
+        let solution = solve_linear_system(matrix).ok_or_else(|| {
+            anyhow::anyhow!(
+                "background windows do not determine an order-{} baseline",
+                self.order
+            )
+        })?;
+        let mut scale_power = 1.0;
+        let coefficients = solution[..terms]
+            .iter()
+            .map(|&coefficient| {
+                let value = coefficient / scale_power;
+                scale_power *= self.scale;
+                value
+            })
+            .collect::<Vec<_>>();
+        if coefficients.iter().any(|value| !value.is_finite()) {
+            bail!("background baseline coefficients are not finite");
+        }
+        Ok(PulseBaselineFit {
+            origin: self.origin,
+            coefficients,
+        })
+    }
+
+    fn scaled(&self, t: f64) -> f64 {
+        (t - self.origin) / self.scale
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{PulseBgAverage, PulseIntegralCalculator};
+    use super::{PulseBaselineFit, PulseBaselineFitter, PulseBgAverage, PulseIntegralCalculator};
 
     #[test]
     fn background_average_is_the_constant_least_squares_solution() {
@@ -109,4 +301,84 @@ mod tests {
 
         assert_eq!(integral, vec![0.0, -2.0, -4.0]);
     }
+
+    #[test]
+    fn pulse_integral_with_constant_baseline_matches_scalar_background() {
+        let data = [0.5, 1.5, -2.0, 4.0];
+        let calculator = PulseIntegralCalculator::new(0.25);
+
+        assert_eq!(
+            calculator.integrate_with_baseline(&data, [0.125; 4], 3.0),
+            calculator.integrate(&data, 0.125, 3.0)
+        );
+    }
+
+    #[test]
+    fn baseline_fit_recovers_polynomials_through_both_windows() {
+        let times = (0..=20)
+            .map(|index| -1.0 + index as f64 * 0.025)
+            .chain((0..=20).map(|index| 2.0 + index as f64 * 0.05))
+            .collect::<Vec<_>>();
+        let quadratic = |t: f64| 0.5 - 0.2 * t + 0.03 * t * t;
+
+        let fit = PulseBaselineFitter::new(2, -1.0, 3.0)
+            .unwrap()
+            .fit(times.iter().map(|&t| (t, quadratic(t))), None)
+            .unwrap();
+
+        assert_eq!(fit.origin, 1.0);
+        for t in [-1.0, 0.0, 1.0, 2.5, 3.0] {
+            assert!((fit.value_at(t) - quadratic(t)).abs() < 1.0e-12, "t={t}");
+        }
+        assert!((fit.coefficients[2] - 0.03).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn endpoint_constraint_zeroes_the_integral_at_the_end_of_pulse() {
+        let dt = 0.01;
+        let times = (0..=100).map(|index| index as f64 * dt).collect::<Vec<_>>();
+        let data = times
+            .iter()
+            .map(|&t| 0.1 + 0.05 * t + if (0.3..0.5).contains(&t) { 1.0 } else { 0.0 })
+            .collect::<Vec<_>>();
+        let end = 60;
+        let background = times
+            .iter()
+            .zip(&data)
+            .filter(|&(&t, _)| t <= 0.2 || t >= 0.6)
+            .map(|(&t, &value)| (t, value));
+        let fitter = PulseBaselineFitter::new(1, 0.0, 1.0).unwrap();
+        let constraint = fitter
+            .endpoint_constraint(dt, times.iter().copied(), &data, end)
+            .unwrap();
+
+        let fit = fitter.fit(background, Some(&constraint)).unwrap();
+        let integral = PulseIntegralCalculator::new(dt).integrate_with_baseline(
+            &data,
+            times.iter().map(|&t| fit.value_at(t)),
+            1.0,
+        );
+
+        assert!(integral[end].abs() < 1.0e-12, "{}", integral[end]);
+    }
+
+    #[test]
+    fn baseline_fit_rejects_underdetermined_and_invalid_inputs() {
+        let fitter = PulseBaselineFitter::new(2, 0.0, 1.0).unwrap();
+
+        assert!(fitter.fit([(0.0, 1.0), (1.0, 2.0)], None).is_err());
+        assert!(fitter.fit([], None).is_err());
+        assert!(
+            fitter
+                .fit([(0.0, 1.0), (0.5, f64::NAN), (1.0, 2.0)], None)
+                .is_err()
+        );
+        assert!(PulseBaselineFitter::new(1, 1.0, 1.0).is_err());
+        assert!(
+            fitter
+                .endpoint_constraint(0.1, [0.0, 0.1], &[1.0, 1.0], 0)
+                .is_err()
+        );
+        assert_eq!(PulseBaselineFit::constant(2.5).value_at(-7.0), 2.5);
+    }
 }
@@ -49,7 +49,7 @@ class SensorRawPlotter:
         t: NDArray,
         y: NDArray,
         index_arr: list[int],
-        c_bg_arr: NDArray,
+        bg: NDArray,
         save: bool,
         interactive: bool,
         output_path,
@@ -57,9 +57,9 @@ class SensorRawPlotter:
         ch_num = len(index_arr)
         mosaic = "".join([chr(65 + i) for i in range(ch_num)])
         axs = gs.axes(False, size=(6 * ch_num, 6), mosaic=mosaic, ion=interactive)
-        for i, (yi, c_bg) in enumerate(zip(y, c_bg_arr)):
+        for i, (yi, bgi) in enumerate(zip(y, bg)):
             gs.line(axs[i], t * 1e6, yi, marker="", linestyle="-")
-            axs[i].axhline(c_bg, color="red", ls="--", lw=1, label="Background")
+            axs[i].plot(t * 1e6, bgi, color="red", ls="--", lw=1, label="Background")
 
         gs.legend_axes()
         label = [["$t$ ($\\mu$s)", f"$V_{{\\rm Ch{i}}}$ (V)"] for i in index_arr]
@@ -20,8 +20,8 @@ impl SensorRawPlotter {
         output: Option<&Path>,
         t: &[f64],
         y: Vec<Vec<f64>>,
+        background: Vec<Vec<f64>>,
         index_arr: &[u8],
-        c_bg_arr: &[f64],
     ) -> Result<()> {
         Python::attach(|py| {
             let plot_mod = python::cached_module(
@@ -32,10 +32,13 @@ impl SensorRawPlotter {
                 "sensor_raw_plot",
             )
             .context("failed to load sensor_raw_plot.py")?;
-            let (t_plot, y_plot) = decimate_xy_2d(plot, t, &y)?;
+            let channels = y.len();
+            let stacked = y.into_iter().chain(background).collect::<Vec<_>>();
+            let (t_plot, mut y_plot) = decimate_xy_2d(plot, t, &stacked)?;
+            let bg_plot = y_plot.split_off(channels);
             let t_obj = python::f64_array1(py, &t_plot);
             let y_obj = python::f64_array2(py, &y_plot)?;
-            let c_bg_obj = python::f64_array1(py, c_bg_arr);
+            let bg_obj = python::f64_array2(py, &bg_plot)?;
             let output = output.map(|path| path.to_string_lossy().into_owned());
 
             let plotter = plot_mod
@@ -50,7 +53,7 @@ impl SensorRawPlotter {
                         t_obj,
                         y_obj,
                         index_arr,
-                        c_bg_obj,
+                        bg_obj,
                         output.is_some(),
                         plot.interactive,
                         output,
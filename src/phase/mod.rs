@@ -4,23 +4,82 @@ pub mod rotator;
 pub mod save;
 
 use crate::analysis_results::parse_analysis_result_files;
-use crate::constants::{LI_HEADER, LI_ROTATED_HEADER};
+use crate::lockin::noise::{ROTATED_NOISE_HEADERS, read_noise_or_unavailable, write_noise};
 use crate::phase::omega_t0_analysis::OT0Analyser;
 use crate::phase::phase_rotation_plot::PhaseRotationPlotter;
-use crate::phase::rotator::rotate_phase;
-use crate::phase::save::{get_li_rotated_headers, write_li_rotated_results};
+use crate::phase::rotator::{rotate_phase, rotate_phase_series};
+use crate::phase::save::{
+    get_li_rotated_headers, lockin_rotated_headers, write_li_rotated_results,
+};
+use crate::utils::csv::write_csv;
 use crate::{config::Config, utils::csv::read_csv};
 use crate::{plot, ui};
 use anyhow::{Context, Result, bail};
+use pmoke_analysis_core::{
+    LockinNoise, OmegaT0Estimate, PhaseDriftKnot, fit_phase_drift, interpolate_phase_drift,
+};
 use rayon::prelude::*;
 use std::f64::consts::PI;
 use std::time::Instant;
 
+/// Mean per-harmonic residual above which the even harmonics are reported as
+/// disagreeing on omega·t0.
+pub const OMEGA_T0_DISAGREEMENT_RAD: f64 = 0.05;
+
+/// Phase-rotated lock-in columns and in-phase noise, one entry per signal channel.
+type PhaseRunOutput = (Vec<Vec<Vec<f64>>>, Vec<InPhaseNoise>);
+
+/// In-phase noise of one signal channel as seen by the Kerr stage.
+#[derive(Debug, Clone)]
+pub struct InPhaseNoise {
+    /// Per-point standard deviation of each harmonic, in `lockin.harmonics` order.
+    pub std: Vec<Vec<f64>>,
+    /// Bandwidth of the lock-in filter; see [`LockinNoise::enbw_hz`].
+    pub enbw_hz: f64,
+}
+
+impl InPhaseNoise {
+    fn constant(rotated: &[LockinNoise], len: usize) -> Self {
+        Self {
+            std: rotated
+                .iter()
+                .map(|noise| vec![noise.x_std(); len])
+                .collect(),
+            enbw_hz: enbw_hz(rotated),
+        }
+    }
+
+    /// Rotates the lock-in noise of each harmonic by the phase of every point.
+    fn drifting(noise: &[LockinNoise], harmonics: &[usize], omega_t0: &[f64]) -> Self {
+        Self {
+            std: noise
+                .iter()
+                .zip(harmonics)
+                .map(|(noise, &harmonic)| {
+                    omega_t0
+                        .iter()
+                        .map(|&omega_t0| noise.rotated(harmonic_delta(harmonic, omega_t0)).x_std())
+                        .collect()
+                })
+                .collect(),
+            enbw_hz: enbw_hz(noise),
+        }
+    }
+}
+
+fn enbw_hz(noise: &[LockinNoise]) -> f64 {
+    noise.first().map_or(f64::NAN, |noise| noise.enbw_hz)
+}
+
 #[derive(Debug)]
 pub struct PhaseAnalysisOutput {
     pub rotated_result: Vec<Vec<f64>>,
-    pub omega_t0: f64,
-    pub deltas: [f64; 6],
+    pub omega_t0_fit: OmegaT0Estimate,
+    pub deltas: Vec<f64>,
+    /// Sliding-window omega·t0 fits; `None` for a constant phase.
+    pub drift: Option<Vec<PhaseDriftKnot>>,
+    /// omega·t0 interpolated to every point when drift is corrected.
+    pub drift_omega_t0: Option<Vec<f64>>,
 }
 
 pub fn run(cfg: &Config) -> Result<()> {
@@ -53,16 +112,27 @@ pub fn run(cfg: &Config) -> Result<()> {
     let data = parse_analysis_result_files(
         &all_data,
         cfg.roles.sensor_ch.len(),
-        LI_HEADER.len(),
+        cfg.lockin.harmonics.len() * 2,
         "lock-in results",
     )?;
 
+    let noise = ch
+        .iter()
+        .map(|channel| {
+            read_noise_or_unavailable(
+                &cfg.paths().lockin_noise_csv(*channel),
+                &cfg.lockin.harmonics,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
     let _ = run_phase_analysis(
         cfg,
         &data.time,
         &data.sensor_rate,
         &data.sensor_integral,
         &data.results,
+        &noise,
     )?;
     Ok(())
 }
@@ -73,8 +143,9 @@ pub fn run_phase_analysis(
     sensor_rate_ch: &[Vec<f64>],
     sensor_integral_ch: &[Vec<f64>],
     li_results: &[Vec<Vec<f64>>],
-) -> Result<Vec<Vec<Vec<f64>>>> {
-    let headers = LI_ROTATED_HEADER;
+    noise: &[Vec<LockinNoise>],
+) -> Result<PhaseRunOutput> {
+    let headers = lockin_rotated_headers(&cfg.lockin.harmonics);
     let labels: Vec<String> = headers
         .iter()
         .map(|s| s.trim().replace("(V)", ""))
@@ -87,29 +158,79 @@ pub fn run_phase_analysis(
         ch.len() as u64,
     );
     let mut rotated_results: Vec<Vec<Vec<f64>>> = Vec::new();
-    for (ch_i, li_result) in ch.iter().zip(li_results.iter()) {
+    let mut in_phase_noise: Vec<InPhaseNoise> = Vec::with_capacity(ch.len());
+    for ((ch_i, li_result), noise) in ch.iter().zip(li_results.iter()).zip(noise) {
         pb.set_message(format!("phase analysis ch{ch_i}"));
-        let phase_output = phase_analysis(cfg, li_result)?;
+        let phase_output = phase_analysis(cfg, t, li_result)?;
+        let fit = &phase_output.omega_t0_fit;
         ui::suspend_progress(&pb, || {
+            let mut rows = vec![
+                vec![
+                    "omega_t0".to_string(),
+                    format!("{:.8} ± {:.2e} rad", fit.omega_t0, fit.omega_t0_std),
+                ],
+                vec![
+                    "omega_t0 95% CI".to_string(),
+                    format!(
+                        "[{:.8}, {:.8}] rad",
+                        fit.confidence_interval[0], fit.confidence_interval[1]
+                    ),
+                ],
+                vec![
+                    format!("residual{:?}", cfg.lockin.harmonics),
+                    fit.harmonics
+                        .iter()
+                        .map(|harmonic| format!("{:.4}", harmonic.mean_residual_rad))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ],
+                vec![
+                    "residual rms".to_string(),
+                    format!("{:.4} rad", fit.residual_rms),
+                ],
+                vec![
+                    format!("delta{:?}", cfg.lockin.harmonics),
+                    phase_output
+                        .deltas
+                        .iter()
+                        .map(|delta| format!("{delta:.4}"))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ],
+            ];
+            if let Some(knots) = &phase_output.drift {
+                let (low, high) =
+                    knots
+                        .iter()
+                        .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), knot| {
+                            let drift = knot.omega_t0 - fit.omega_t0;
+                            (low.min(drift), high.max(drift))
+                        });
+                rows.push(vec![
+                    format!("{} drift", cfg.phase.drift.kind()),
+                    format!("{} windows, [{low:.4}, {high:.4}] rad", knots.len()),
+                ]);
+            }
             ui::summary_table(
                 format!("Phase rotation ch{ch_i}"),
                 &["Metric", "Value"],
-                vec![
-                    vec![
-                        "omega_t0".to_string(),
-                        format!("{:.8} rad", phase_output.omega_t0),
-                    ],
-                    vec![
-                        "delta[1..6]".to_string(),
-                        phase_output
-                            .deltas
-                            .iter()
-                            .map(|delta| format!("{delta:.4}"))
-                            .collect::<Vec<_>>()
-                            .join(", "),
-                    ],
-                ],
+                rows,
             );
+            let disagreement = fit.max_harmonic_residual_rad();
+            if disagreement > OMEGA_T0_DISAGREEMENT_RAD {
+                ui::warn(format!(
+                    "ch{ch_i}: even harmonics disagree on omega_t0 (max mean residual {disagreement:.4} rad > {OMEGA_T0_DISAGREEMENT_RAD} rad; per-harmonic omega_t0 {})",
+                    fit.harmonics
+                        .iter()
+                        .filter(|harmonic| harmonic.fitted)
+                        .map(|harmonic| format!(
+                            "n={}: {:.6}",
+                            harmonic.harmonic, harmonic.omega_t0
+                        ))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
         });
         let path = paths.lockin_rotated_csv(*ch_i);
         let headers = get_li_rotated_headers(cfg)?;
@@ -122,6 +243,26 @@ pub fn run_phase_analysis(
             &phase_output.rotated_result,
             cfg.lockin.save_npy,
         )?;
+        if let Some(knots) = &phase_output.drift {
+            write_phase_drift(&paths.phase_drift_csv(*ch_i), knots, fit.omega_t0)?;
+        }
+        // The table records the whole-trace rotation; with drift correction the
+        // Kerr stage rotates the noise point by point like the signal.
+        let channel_noise = noise
+            .iter()
+            .zip(&phase_output.deltas)
+            .map(|(noise, &delta)| noise.rotated(delta))
+            .collect::<Vec<_>>();
+        write_noise(
+            &paths.lockin_rotated_noise_csv(*ch_i),
+            &ROTATED_NOISE_HEADERS,
+            &cfg.lockin.harmonics,
+            &channel_noise,
+        )?;
+        in_phase_noise.push(match &phase_output.drift_omega_t0 {
+            Some(omega_t0) => InPhaseNoise::drifting(noise, &cfg.lockin.harmonics, omega_t0),
+            None => InPhaseNoise::constant(&channel_noise, t.len()),
+        });
         rotated_results.push(phase_output.rotated_result);
         pb.inc(1);
     }
@@ -139,14 +280,79 @@ pub fn run_phase_analysis(
                 .context("failed to plot phase-rotated results")
         },
     )?;
-    Ok(rotated_results)
+    Ok((rotated_results, in_phase_noise))
 }
 
-pub fn phase_analysis(cfg: &Config, li_result: &[Vec<f64>]) -> Result<PhaseAnalysisOutput> {
-    if li_result.len() != LI_HEADER.len() {
+/// Loads the per-point in-phase noise of `signal_ch` written by the lock-in and
+/// phase stages, rotated the way [`run_phase_analysis`] rotates the signal.
+pub fn load_in_phase_noise(cfg: &Config, signal_ch: u8, t: &[f64]) -> Result<InPhaseNoise> {
+    let paths = cfg.paths();
+    let harmonics = &cfg.lockin.harmonics;
+    if cfg.phase.drift.window_and_step().is_none() {
+        let rotated =
+            read_noise_or_unavailable(&paths.lockin_rotated_noise_csv(signal_ch), harmonics)?;
+        return Ok(InPhaseNoise::constant(&rotated, t.len()));
+    }
+    let noise = read_noise_or_unavailable(&paths.lockin_noise_csv(signal_ch), harmonics)?;
+    let knots = read_phase_drift(&paths.phase_drift_csv(signal_ch))?;
+    Ok(InPhaseNoise::drifting(
+        &noise,
+        harmonics,
+        &interpolate_phase_drift(&knots, t),
+    ))
+}
+
+/// Writes the fitted omega·t0 of each drift window and its offset from the
+/// whole-trace estimate.
+fn write_phase_drift(
+    path: &std::path::Path,
+    knots: &[PhaseDriftKnot],
+    omega_t0: f64,
+) -> Result<()> {
+    let columns = [
+        knots.iter().map(|knot| knot.time).collect::<Vec<_>>(),
+        knots.iter().map(|knot| knot.omega_t0).collect(),
+        knots.iter().map(|knot| knot.omega_t0 - omega_t0).collect(),
+        knots.iter().map(|knot| knot.samples as f64).collect(),
+    ];
+    write_csv(
+        path,
+        &["time (s)", "omega_t0 (rad)", "drift (rad)", "samples"],
+        &columns,
+    )
+    .with_context(|| format!("failed to write phase drift: {}", path.display()))
+}
+
+fn read_phase_drift(path: &std::path::Path) -> Result<Vec<PhaseDriftKnot>> {
+    let columns = read_csv(path)
+        .with_context(|| format!("failed to read phase drift: {}", path.display()))?;
+    let [time, omega_t0, _, samples] = columns.as_slice() else {
+        bail!("{} must have 4 columns", path.display());
+    };
+    Ok(time
+        .iter()
+        .zip(omega_t0)
+        .zip(samples)
+        .map(|((&time, &omega_t0), &samples)| PhaseDriftKnot {
+            time,
+            omega_t0,
+            samples: samples as usize,
+        })
+        .collect())
+}
+
+pub fn phase_analysis(
+    cfg: &Config,
+    t: &[f64],
+    li_result: &[Vec<f64>],
+) -> Result<PhaseAnalysisOutput> {
+    let harmonics = &cfg.lockin.harmonics;
+    let expected_columns = harmonics.len() * 2;
+    if li_result.len() != expected_columns {
         bail!(
-            "length_mismatch: phase analysis requires exactly {} lock-in columns (six x/y pairs), got {}",
-            LI_HEADER.len(),
+            "length_mismatch: phase analysis requires exactly {} lock-in columns (x/y pairs for harmonics {:?}), got {}",
+            expected_columns,
+            harmonics,
             li_result.len()
         );
     }
@@ -161,146 +367,153 @@ pub fn phase_analysis(cfg: &Config, li_result: &[Vec<f64>]) -> Result<PhaseAnaly
             sample_count
         );
     }
+    if t.len() != sample_count {
+        bail!(
+            "length_mismatch: phase analysis time axis has {} samples but lock-in columns have {}",
+            t.len(),
+            sample_count
+        );
+    }
     if li_result.iter().flatten().any(|value| !value.is_finite()) {
         bail!("non_finite_phase: phase analysis columns must contain only finite values");
     }
 
-    let pairs = li_result.as_chunks::<2>().0;
-
-    let [
-        [li1x, li1y],
-        [li2x, li2y],
-        [li3x, li3y],
-        [li4x, li4y],
-        [li5x, li5y],
-        [li6x, li6y],
-        ..,
-    ] = pairs
-    else {
-        unreachable!("lock-in column count was validated above");
-    };
-    let theta_1: Vec<f64> = li1y
-        .iter()
-        .zip(li1x.iter())
-        .map(|(y, x)| y.atan2(*x))
-        .collect();
-    let theta_2: Vec<f64> = li2y
-        .iter()
-        .zip(li2x.iter())
-        .map(|(y, x)| y.atan2(*x))
-        .collect();
-    let theta_3: Vec<f64> = li3y
-        .iter()
-        .zip(li3x.iter())
-        .map(|(y, x)| y.atan2(*x))
-        .collect();
-    let theta_4: Vec<f64> = li4y
-        .iter()
-        .zip(li4x.iter())
-        .map(|(y, x)| y.atan2(*x))
-        .collect();
-    let theta_5: Vec<f64> = li5y
-        .iter()
-        .zip(li5x.iter())
-        .map(|(y, x)| y.atan2(*x))
-        .collect();
-    let theta_6: Vec<f64> = li6y
-        .iter()
-        .zip(li6x.iter())
-        .map(|(y, x)| y.atan2(*x))
-        .collect();
-
     let offset_phases = &cfg.phase.m_omega_t0_offset;
+    if offset_phases.len() != harmonics.len() {
+        bail!(
+            "length_mismatch: phase.offsets has {} entries but lockin.harmonics has {}",
+            offset_phases.len(),
+            harmonics.len()
+        );
+    }
+    if !harmonics.iter().any(|harmonic| harmonic.is_multiple_of(2)) {
+        bail!(
+            "missing_even_harmonic: omega_t0 is fitted on even harmonics; add one to lockin.harmonics"
+        );
+    }
 
-    let m_omega_t0_1: Vec<f64> = theta_1
-        .iter()
-        .map(|&theta| theta - PI + offset_phases[0])
-        .collect();
-    let m_omega_t0_2: Vec<f64> = theta_2
-        .iter()
-        .map(|&theta| theta - PI / 2.0 + offset_phases[1])
-        .collect();
-    let m_omega_t0_3: Vec<f64> = theta_3
-        .iter()
-        .map(|&theta| theta - PI + offset_phases[2])
-        .collect();
-    let m_omega_t0_4: Vec<f64> = theta_4
-        .iter()
-        .map(|&theta| theta - PI / 2.0 + offset_phases[3])
-        .collect();
-    let m_omega_t0_5: Vec<f64> = theta_5
-        .iter()
-        .map(|&theta| theta - PI + offset_phases[4])
-        .collect();
-    let m_omega_t0_6: Vec<f64> = theta_6
+    let pairs = li_result.as_chunks::<2>().0;
+
+    let m_omega_t0: Vec<Vec<f64>> = pairs
         .iter()
-        .map(|&theta| theta - PI / 2.0 + offset_phases[5])
+        .zip(harmonics.iter().zip(offset_phases))
+        .map(|([x, y], (&harmonic, offset))| {
+            let reference = harmonic_reference_phase(harmonic);
+            y.iter()
+                .zip(x.iter())
+                .map(|(y, x)| y.atan2(*x) - reference + offset)
+                .collect()
+        })
         .collect();
+    let m_omega_t0_refs: Vec<&[f64]> = m_omega_t0.iter().map(Vec::as_slice).collect();
 
-    let omega_t0: f64 = OT0Analyser {}
+    let omega_t0_fit = OT0Analyser {}
         .analyse(
             &cfg.plot,
             &cfg.paths().phase_offset_plot(),
-            [
-                &m_omega_t0_1,
-                &m_omega_t0_2,
-                &m_omega_t0_3,
-                &m_omega_t0_4,
-                &m_omega_t0_5,
-                &m_omega_t0_6,
-            ],
+            harmonics,
+            &m_omega_t0_refs,
         )
         .context("failed to analyse omega_t0")?;
+    let omega_t0 = omega_t0_fit.omega_t0;
 
-    let delta_1 = PI - 1.0 * omega_t0;
-    let delta_2 = PI / 2.0 - 2.0 * omega_t0;
-    let delta_3 = PI - 3.0 * omega_t0;
-    let delta_4 = PI / 2.0 - 4.0 * omega_t0;
-    let delta_5 = PI - 5.0 * omega_t0;
-    let delta_6 = PI / 2.0 - 6.0 * omega_t0;
-    let deltas = [delta_1, delta_2, delta_3, delta_4, delta_5, delta_6];
-
-    let (li1_in, li1_out) = rotate_phase(li1x, li1y, delta_1)?;
-    let (li2_in, li2_out) = rotate_phase(li2x, li2y, delta_2)?;
-    let (li3_in, li3_out) = rotate_phase(li3x, li3y, delta_3)?;
-    let (li4_in, li4_out) = rotate_phase(li4x, li4y, delta_4)?;
-    let (li5_in, li5_out) = rotate_phase(li5x, li5y, delta_5)?;
-    let (li6_in, li6_out) = rotate_phase(li6x, li6y, delta_6)?;
-
-    let rotated_result: Vec<Vec<f64>> = vec![
-        li1_in, li1_out, li2_in, li2_out, li3_in, li3_out, li4_in, li4_out, li5_in, li5_out,
-        li6_in, li6_out,
-    ];
+    let deltas: Vec<f64> = harmonics
+        .iter()
+        .map(|&harmonic| harmonic_delta(harmonic, omega_t0))
+        .collect();
+
+    let drift = cfg
+        .phase
+        .drift
+        .window_and_step()
+        .map(|(window, step)| {
+            let pair_refs = pairs
+                .iter()
+                .map(|[x, y]| [x.as_slice(), y.as_slice()])
+                .collect::<Vec<_>>();
+            let angle_offsets = harmonics
+                .iter()
+                .zip(offset_phases)
+                .map(|(&harmonic, offset)| offset - harmonic_reference_phase(harmonic))
+                .collect::<Vec<_>>();
+            fit_phase_drift(
+                t,
+                harmonics,
+                &pair_refs,
+                &angle_offsets,
+                omega_t0,
+                window,
+                step,
+            )
+            .context("failed to fit phase drift")
+        })
+        .transpose()?;
+    let drift_omega_t0 = drift
+        .as_ref()
+        .map(|knots| interpolate_phase_drift(knots, t));
+
+    let mut rotated_result: Vec<Vec<f64>> = Vec::with_capacity(expected_columns);
+    for (([x, y], &delta), &harmonic) in pairs.iter().zip(&deltas).zip(harmonics) {
+        let (li_in, li_out) = match &drift_omega_t0 {
+            Some(omega_t0) => {
+                let deltas = omega_t0
+                    .iter()
+                    .map(|&omega_t0| harmonic_delta(harmonic, omega_t0))
+                    .collect::<Vec<_>>();
+                rotate_phase_series(x, y, &deltas)?
+            }
+            None => rotate_phase(x, y, delta)?,
+        };
+        rotated_result.push(li_in);
+        rotated_result.push(li_out);
+    }
 
     Ok(PhaseAnalysisOutput {
         rotated_result,
-        omega_t0,
+        omega_t0_fit,
         deltas,
+        drift,
+        drift_omega_t0,
     })
 }
 
+/// Rotation that brings harmonic `harmonic` onto the in-phase axis.
+fn harmonic_delta(harmonic: usize, omega_t0: f64) -> f64 {
+    harmonic_reference_phase(harmonic) - harmonic as f64 * omega_t0
+}
+
+fn harmonic_reference_phase(harmonic: usize) -> f64 {
+    if harmonic.is_multiple_of(2) {
+        PI / 2.0
+    } else {
+        PI
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::phase_analysis;
+    use super::{
+        InPhaseNoise, LockinNoise, harmonic_delta, harmonic_reference_phase, phase_analysis,
+    };
 
     #[test]
     fn phase_analysis_rejects_incomplete_harmonic_columns() {
         let cfg = crate::test_support::test_config(vec![1], vec![2]);
-        let error = phase_analysis(&cfg, &vec![vec![0.0]; 10]).unwrap_err();
+        let error = phase_analysis(&cfg, &[0.0], &vec![vec![0.0]; 10]).unwrap_err();
         assert!(error.to_string().starts_with("length_mismatch:"));
     }
 
     #[test]
     fn phase_analysis_rejects_extra_harmonic_columns() {
         let cfg = crate::test_support::test_config(vec![1], vec![2]);
-        let error = phase_analysis(&cfg, &vec![vec![0.0]; 14]).unwrap_err();
+        let error = phase_analysis(&cfg, &[0.0], &vec![vec![0.0]; 14]).unwrap_err();
         assert!(error.to_string().starts_with("length_mismatch:"));
     }
 
     #[test]
     fn phase_analysis_rejects_empty_columns() {
         let cfg = crate::test_support::test_config(vec![1], vec![2]);
-        let error = phase_analysis(&cfg, &vec![Vec::new(); 12]).unwrap_err();
+        let error = phase_analysis(&cfg, &[], &vec![Vec::new(); 12]).unwrap_err();
         assert!(error.to_string().starts_with("empty_phase_column:"));
     }
 
@@ -309,7 +522,7 @@ mod tests {
         let cfg = crate::test_support::test_config(vec![1], vec![2]);
         let mut columns = vec![vec![0.0; 2]; 12];
         columns[7].pop();
-        let error = phase_analysis(&cfg, &columns).unwrap_err();
+        let error = phase_analysis(&cfg, &[0.0, 1.0], &columns).unwrap_err();
         assert!(error.to_string().starts_with("length_mismatch:"));
     }
 
@@ -318,7 +531,115 @@ mod tests {
         let cfg = crate::test_support::test_config(vec![1], vec![2]);
         let mut columns = vec![vec![0.0; 2]; 12];
         columns[4][1] = f64::NAN;
-        let error = phase_analysis(&cfg, &columns).unwrap_err();
+        let error = phase_analysis(&cfg, &[0.0, 1.0], &columns).unwrap_err();
         assert!(error.to_string().starts_with("non_finite_phase:"));
     }
+
+    #[test]
+    fn phase_analysis_follows_configured_harmonics() {
+        let mut cfg = crate::test_support::test_config(vec![1], vec![2]);
+        cfg.lockin.harmonics = vec![1, 3];
+        let error = phase_analysis(&cfg, &[0.0], &vec![vec![0.0]; 12]).unwrap_err();
+        assert!(error.to_string().starts_with("length_mismatch:"));
+
+        let error = phase_analysis(&cfg, &[0.0], &vec![vec![0.0]; 4]).unwrap_err();
+        assert!(error.to_string().contains("phase.offsets has 0 entries"));
+
+        cfg.phase.m_omega_t0_offset = vec![0.0; 2];
+        let error = phase_analysis(&cfg, &[0.0], &vec![vec![0.0]; 4]).unwrap_err();
+        assert!(error.to_string().starts_with("missing_even_harmonic:"));
+    }
+
+    #[test]
+    fn phase_analysis_estimates_omega_t0_without_python() {
+        let mut cfg = crate::test_support::test_config(vec![1], vec![2]);
+        cfg.phase.m_omega_t0_offset = vec![0.0; 6];
+        let omega_t0 = 0.1;
+        let columns = cfg
+            .lockin
+            .harmonics
+            .iter()
+            .flat_map(|&harmonic| {
+                let angle = harmonic_reference_phase(harmonic) - harmonic as f64 * omega_t0;
+                [vec![angle.cos(); 4], vec![angle.sin(); 4]]
+            })
+            .collect::<Vec<_>>();
+
+        let output = phase_analysis(&cfg, &[0.0, 1.0, 2.0, 3.0], &columns).unwrap();
+
+        assert!((output.omega_t0_fit.omega_t0 - omega_t0).abs() < 1.0e-12);
+        assert!(output.omega_t0_fit.max_harmonic_residual_rad() < 1.0e-12);
+        for [x, y] in output.rotated_result.as_chunks::<2>().0 {
+            assert!(x.iter().all(|value| (value - 1.0).abs() < 1.0e-12));
+            assert!(y.iter().all(|value| value.abs() < 1.0e-12));
+        }
+    }
+
+    #[test]
+    fn piecewise_linear_drift_rotates_each_point_by_its_local_phase() {
+        let mut cfg = crate::test_support::test_config(vec![1], vec![2]);
+        cfg.phase.m_omega_t0_offset = vec![0.0; 6];
+        cfg.phase.drift = crate::config::PhaseDrift::PiecewiseLinear {
+            window: 0.02,
+            step: None,
+        };
+        let t = (0..400)
+            .map(|index| index as f64 * 1.0e-3)
+            .collect::<Vec<_>>();
+        let omega_t0 = |time: f64| 0.1 + 0.5 * time;
+        let columns = cfg
+            .lockin
+            .harmonics
+            .iter()
+            .flat_map(|&harmonic| {
+                let angle = |time: f64| {
+                    harmonic_reference_phase(harmonic) - harmonic as f64 * omega_t0(time)
+                };
+                [
+                    t.iter().map(|&time| angle(time).cos()).collect(),
+                    t.iter().map(|&time| angle(time).sin()).collect(),
+                ]
+            })
+            .collect::<Vec<Vec<f64>>>();
+
+        let output = phase_analysis(&cfg, &t, &columns).unwrap();
+
+        let knots = output.drift.expect("piecewise-linear drift knots");
+        assert_eq!(knots.len(), 39);
+        // The window edges hold the first and last knot, so skip them.
+        for [x, y] in output.rotated_result.as_chunks::<2>().0 {
+            assert!(x[10..390].iter().all(|value| (value - 1.0).abs() < 1.0e-6));
+            assert!(y[10..390].iter().all(|value| value.abs() < 1.0e-3));
+        }
+
+        // The noise follows each point's rotation, not the whole-trace one.
+        let noise = LockinNoise {
+            x_var: 4.0e-6,
+            y_var: 1.0e-6,
+            xy_cov: 0.0,
+            samples: 40,
+            enbw_hz: 50.0,
+        };
+        let omega_t0_fit = output.drift_omega_t0.expect("per-point omega_t0");
+        let in_phase =
+            InPhaseNoise::drifting(&[noise; 6], &cfg.lockin.harmonics, &omega_t0_fit).std;
+        for (sigma, &harmonic) in in_phase.iter().zip(&cfg.lockin.harmonics) {
+            for index in [10, 200, 389] {
+                let expected = noise
+                    .rotated(harmonic_delta(harmonic, omega_t0(t[index])))
+                    .x_std();
+                assert!((sigma[index] - expected).abs() < 1.0e-8, "n={harmonic}");
+            }
+        }
+        assert!((in_phase[5][10] - in_phase[5][389]).abs() > 1.0e-4);
+
+        cfg.phase.drift = crate::config::PhaseDrift::Constant;
+        let constant = phase_analysis(&cfg, &t, &columns).unwrap();
+        assert!(constant.drift.is_none());
+        assert!(
+            constant.rotated_result[11]
+                .iter()
+                .any(|value| value.abs() > 0.1)
+        );
+    }
 }
@@ -1,6 +1,7 @@
 use crate::config::Plot;
 use crate::python;
 use anyhow::{Context, Result};
+use pmoke_analysis_core::OmegaT0Estimate;
 use pyo3::prelude::*;
 use pyo3::types::PyModule;
 use std::path::Path;
@@ -14,8 +15,20 @@ static OT0_ANALYSIS_MODULE: OnceLock<Py<PyModule>> = OnceLock::new();
 pub struct OT0Analyser {}
 
 impl OT0Analyser {
-    pub fn analyse(&self, plot: &Plot, output_path: &Path, m_omega_t0: [&[f64]; 6]) -> Result<f64> {
+    pub fn analyse(
+        &self,
+        plot: &Plot,
+        output_path: &Path,
+        harmonics: &[usize],
+        m_omega_t0: &[&[f64]],
+    ) -> Result<OmegaT0Estimate> {
         let output = crate::plot::prepare_plot_output(plot, output_path)?;
+        let estimate = pmoke_analysis_core::estimate_omega_t0(harmonics, m_omega_t0)
+            .context("failed to fit omega_t0 on the even harmonics")?;
+        if output.is_none() && !(plot.enabled && plot.interactive) {
+            return Ok(estimate);
+        }
+
         Python::attach(|py| {
             let analysis_mod = python::cached_module(
                 py,
@@ -25,12 +38,10 @@ impl OT0Analyser {
                 "omega_t0_analysis",
             )
             .context("failed to load omega_t0_analysis.py")?;
-            let m_ot0_1_obj = python::f64_array1(py, m_omega_t0[0]);
-            let m_ot0_2_obj = python::f64_array1(py, m_omega_t0[1]);
-            let m_ot0_3_obj = python::f64_array1(py, m_omega_t0[2]);
-            let m_ot0_4_obj = python::f64_array1(py, m_omega_t0[3]);
-            let m_ot0_5_obj = python::f64_array1(py, m_omega_t0[4]);
-            let m_ot0_6_obj = python::f64_array1(py, m_omega_t0[5]);
+            let m_omega_t0_objs = m_omega_t0
+                .iter()
+                .map(|values| python::f64_array1(py, values))
+                .collect::<Vec<_>>();
             let output_string = output.map(|path| path.to_string_lossy().into_owned());
 
             let analyser = analysis_mod
@@ -38,30 +49,24 @@ impl OT0Analyser {
                 .call0()
                 .context("failed to create OT0Analyser instance")?;
 
-            let res = analyser
+            let plot_error: Option<String> = analyser
                 .call_method1(
-                    "analyse",
+                    "plot",
                     (
-                        m_ot0_1_obj,
-                        m_ot0_2_obj,
-                        m_ot0_3_obj,
-                        m_ot0_4_obj,
-                        m_ot0_5_obj,
-                        m_ot0_6_obj,
-                        output_string.is_some(),
+                        harmonics.to_vec(),
+                        m_omega_t0_objs,
+                        estimate.omega_t0,
                         plot.interactive && plot.enabled,
                         output_string,
                         plot.max_points,
                         plot.decimation.as_str(),
                     ),
                 )
-                .context("python OT0Analyser.analyse(...) failed")?;
-
-            let omega_t0: f64 = res.get_item("omega_t0")?.extract()?;
-            let plot_error: Option<String> = res.get_item("plot_error")?.extract()?;
+                .context("python OT0Analyser.plot(...) failed")?
+                .extract()?;
             crate::plot::finish_embedded_plot(plot, output, plot_error, "omega_t0 analysis")?;
 
-            Ok(omega_t0)
+            Ok(estimate)
         })
     }
 }
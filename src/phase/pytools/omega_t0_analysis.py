@@ -1,4 +1,3 @@
-import lmfit
 import numpy as np
 import warnings
 from numpy.typing import NDArray
@@ -83,78 +82,54 @@ class OT0Analyser:
     def __init__(self):
         pass
 
-    def analyse(
+    def plot(
         self,
-        m_ot0_1: NDArray,
-        m_ot0_2: NDArray,
-        m_ot0_3: NDArray,
-        m_ot0_4: NDArray,
-        m_ot0_5: NDArray,
-        m_ot0_6: NDArray,
-        save: bool,
+        harmonics,
+        m_omega_t0,
+        omega_t0: float,
         interactive: bool,
         output_path,
         max_points: int,
         decimation: str,
     ):
-        ones = np.ones(len(m_ot0_2))
-        harmonics_even = np.concatenate([ones * 2, ones * 4, ones * 6])
-        m_omega_t0_even = np.concatenate([m_ot0_2, m_ot0_4, m_ot0_6])
-
-        model = lmfit.models.LinearModel()
-        params = model.make_params(intercept=0, slope=0)
-        # do not vary the intercept
-        params["intercept"].vary = False
-        result = model.fit(m_omega_t0_even, params, x=harmonics_even)
-
-        # Create data for plotting
-        harmonics_even_plot = np.linspace(0, 7, 100)
-        m_omega_t0_even_plot = result.eval(x=harmonics_even_plot)
-
-        label = f"$-\\omega t_0$ = {result.params['slope'].value:.2e}$n$"
-
-        plot_error = None
-        if save or interactive:
-            try:
-                gs = _load_gsplot()
-
-                indices = decimation_indices(
-                    [m_ot0_1, m_ot0_2, m_ot0_3, m_ot0_4, m_ot0_5, m_ot0_6],
-                    max_points,
-                    decimation,
-                )
-                ones_plot = ones[indices]
-
-                axs = gs.axes(False, size=(6, 6), mosaic="A", ion=interactive)
-                cm = gs.get_cmap(cmap="viridis", N=6)
+        x_max = max(harmonics) + 1
+        harmonics_plot = np.linspace(0, x_max, 100)
+        m_omega_t0_plot = -omega_t0 * harmonics_plot
 
-                gs.scatter(axs[0], ones_plot * 1, m_ot0_1[indices], label="1", color=cm[0])
-                gs.scatter(axs[0], ones_plot * 2, m_ot0_2[indices], label="2", color=cm[1])
-                gs.scatter(axs[0], ones_plot * 3, m_ot0_3[indices], label="3", color=cm[2])
-                gs.scatter(axs[0], ones_plot * 4, m_ot0_4[indices], label="4", color=cm[3])
-                gs.scatter(axs[0], ones_plot * 5, m_ot0_5[indices], label="5", color=cm[4])
-                gs.scatter(axs[0], ones_plot * 6, m_ot0_6[indices], label="6", color=cm[5])
+        label = f"$-\\omega t_0$ = {-omega_t0:.2e}$n$"
 
-                gs.line(
-                    axs[0],
-                    harmonics_even_plot,
-                    m_omega_t0_even_plot,
-                    label=label,
-                    ms=0,
-                    ls="--",
-                    color="red",
-                )
+        try:
+            gs = _load_gsplot()
 
-                gs.legend(axs[0], loc="best", markerscale=5)
+            indices = decimation_indices(list(m_omega_t0), max_points, decimation)
+            ones_plot = np.ones(len(indices))
 
-                gs.label([["$n$", "$-\\omega t_0$ (rad)", [0, 7], ["", ""]]])
-                finish_plot(output_path, interactive)
-            except Exception as exc:
-                plot_error = str(exc)
+            axs = gs.axes(False, size=(6, 6), mosaic="A", ion=interactive)
+            cm = gs.get_cmap(cmap="viridis", N=max(len(harmonics), 2))
 
-        omega_t0 = -result.params["slope"].value
+            for index, (harmonic, values) in enumerate(zip(harmonics, m_omega_t0)):
+                gs.scatter(
+                    axs[0],
+                    ones_plot * harmonic,
+                    values[indices],
+                    label=str(harmonic),
+                    color=cm[index],
+                )
 
-        return {
-            "omega_t0": omega_t0,
-            "plot_error": plot_error,
-        }
+            gs.line(
+                axs[0],
+                harmonics_plot,
+                m_omega_t0_plot,
+                label=label,
+                ms=0,
+                ls="--",
+                color="red",
+            )
+
+            gs.legend(axs[0], loc="best", markerscale=5)
+
+            gs.label([["$n$", "$-\\omega t_0$ (rad)", [0, x_max], ["", ""]]])
+            finish_plot(output_path, interactive)
+        except Exception as exc:
+            return str(exc)
+        return None
@@ -1 +1 @@
-pub use pmoke_analysis_core::rotate_phase;
+pub use pmoke_analysis_core::{rotate_phase, rotate_phase_series};
@@ -1,11 +1,22 @@
 use crate::analysis_results::{build_analysis_headers, write_analysis_results};
 use crate::config::Config;
-use crate::constants::LI_ROTATED_HEADER;
 use anyhow::Result;
 use std::path::Path;
 
+pub fn lockin_rotated_headers(harmonics: &[usize]) -> Vec<String> {
+    harmonics
+        .iter()
+        .flat_map(|harmonic| {
+            [
+                format!("LIin_h{harmonic} (V)"),
+                format!("LIout_h{harmonic} (V)"),
+            ]
+        })
+        .collect()
+}
+
 pub fn get_li_rotated_headers(cfg: &Config) -> Result<Vec<String>> {
-    build_analysis_headers(cfg, LI_ROTATED_HEADER)
+    build_analysis_headers(cfg, lockin_rotated_headers(&cfg.lockin.harmonics))
 }
 
 pub fn write_li_rotated_results<P: AsRef<Path>>(
@@ -30,8 +41,8 @@ pub fn write_li_rotated_results<P: AsRef<Path>>(
 
 #[cfg(test)]
 mod tests {
-    use super::get_li_rotated_headers;
-    use crate::{constants::LI_ROTATED_HEADER, test_support::test_config};
+    use super::{get_li_rotated_headers, lockin_rotated_headers};
+    use crate::test_support::test_config;
 
     #[test]
     fn rotated_headers_use_rate_integral_result_order() {
@@ -46,7 +57,9 @@ mod tests {
             "ch1 integral (T)".to_string(),
             "ch2 integral (T)".to_string(),
         ];
-        expected.extend(LI_ROTATED_HEADER.iter().map(|header| header.to_string()));
+        expected.extend(lockin_rotated_headers(&[1, 2, 3, 4, 5, 6]));
         assert_eq!(headers, expected);
+        assert_eq!(expected[5], "LIin_h1 (V)");
+        assert_eq!(expected[16], "LIout_h6 (V)");
     }
 }
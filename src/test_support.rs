@@ -1,6 +1,6 @@
 use crate::config::{
-    Channel, Config, Fetch, Kerr, KerrType, Lockin, LockinLpfKind, Phase, Plot, Pulse, Reference,
-    Roles, Screenshot, Window,
+    Channel, Config, Fetch, Kerr, KerrModulationDepth, KerrType, Lockin, LockinLpfKind, Phase,
+    PhaseDrift, Plot, Pulse, PulseBaseline, Reference, Roles, Screenshot, Window,
 };
 
 pub fn test_config(sensor_ch: Vec<u8>, signal_ch: Vec<u8>) -> Config {
@@ -41,6 +41,7 @@ pub fn test_config(sensor_ch: Vec<u8>, signal_ch: Vec<u8>) -> Config {
                 index,
                 factor: Some(index as f64),
                 scale_to_abs_max: None,
+                coil: None,
                 label: Some(format!("ch{index}")),
                 unit_out: Some("T".to_string()),
             })
@@ -48,6 +49,7 @@ pub fn test_config(sensor_ch: Vec<u8>, signal_ch: Vec<u8>) -> Config {
         pulse: Pulse {
             bg_window_before: window,
             bg_window_after: window,
+            baseline: PulseBaseline::Constant,
         },
         reference: Reference {
             fft_window: window,
@@ -57,8 +59,12 @@ pub fn test_config(sensor_ch: Vec<u8>, signal_ch: Vec<u8>) -> Config {
         lockin: Lockin {
             workers: 1,
             stride_samples: 1,
+            harmonics: vec![1, 2, 3, 4, 5, 6],
             lpf_kind: LockinLpfKind::BoxcarLegacy,
             lpf_half_window_cycles: 1.0,
+            lpf_cutoff: None,
+            lpf_order: pmoke_config_core::DEFAULT_LOCKIN_RC_ORDER,
+            lpf_zero_phase: true,
             lpf_debug_output: false,
             lpf_debug_label: None,
             lpf_debug_overwrite: false,
@@ -68,11 +74,16 @@ pub fn test_config(sensor_ch: Vec<u8>, signal_ch: Vec<u8>) -> Config {
         },
         phase: Phase {
             m_omega_t0_offset: Vec::new(),
+            drift: PhaseDrift::Constant,
         },
         kerr: Kerr {
             use_sensor_ch: 1,
             kerr_type: KerrType::Standard,
             factor: 1.0,
+            modulation_depth: KerrModulationDepth::default(),
+            field_bin_width: None,
+            background_run: None,
+            field_background: None,
         },
     }
 }
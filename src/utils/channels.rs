@@ -5,6 +5,7 @@ pub fn build_channel_list(cfg: &Config) -> Result<Vec<u8>> {
     let mut channels: Vec<u8> = Vec::new();
 
     channels.extend(cfg.roles.sensor_ch.iter().copied());
+    channels.extend(cfg.sensor_compensation_ch());
     channels.extend(cfg.roles.signal_ch.iter().copied());
     channels.push(cfg.roles.reference_ch);
 
@@ -31,6 +32,24 @@ mod tests {
         assert_eq!(build_channel_list(&cfg).unwrap(), vec![1, 2, 3, 4]);
     }
 
+    #[test]
+    fn includes_sensor_compensation_coil_channels() {
+        let mut cfg = crate::test_support::test_config(vec![1], vec![3]);
+        cfg.roles.reference_ch = 2;
+        cfg.channels[0].factor = None;
+        cfg.channels[0].coil = Some(crate::config::CoilCalibration {
+            area_turns: 1.0e-3,
+            gain: 1.0,
+            polarity: 1,
+            compensation: Some(crate::config::CoilCompensation {
+                channel: 5,
+                balance: 0.98,
+            }),
+        });
+
+        assert_eq!(build_channel_list(&cfg).unwrap(), vec![1, 2, 3, 5]);
+    }
+
     #[test]
     fn rejects_a_channel_assigned_to_more_than_one_role() {
         let mut cfg = crate::test_support::test_config(vec![1], vec![3]);
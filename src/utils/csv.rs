@@ -8,11 +8,22 @@ const CSV_WRITE_BUFFER_BYTES: usize = 8 * 1024 * 1024;
 const CSV_READ_BUFFER_BYTES: usize = 8 * 1024 * 1024;
 
 pub fn read_csv<P: AsRef<Path>>(path: P) -> Result<Vec<Vec<f64>>> {
+    read_csv_with_headers(path).map(|(_, columns)| columns)
+}
+
+/// Like [`read_csv`], but also returns the header row.
+pub fn read_csv_with_headers<P: AsRef<Path>>(path: P) -> Result<(Vec<String>, Vec<Vec<f64>>)> {
     let mut rdr = ReaderBuilder::new()
         .has_headers(true)
         .buffer_capacity(CSV_READ_BUFFER_BYTES)
         .from_path(&path)
         .with_context(|| format!("failed to open csv: {}", path.as_ref().display()))?;
+    let headers = rdr
+        .headers()
+        .with_context(|| format!("failed to read csv header: {}", path.as_ref().display()))?
+        .iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
 
     let mut columns: Vec<Vec<f64>> = Vec::new();
 
@@ -29,7 +40,7 @@ pub fn read_csv<P: AsRef<Path>>(path: P) -> Result<Vec<Vec<f64>>> {
         }
     }
 
-    Ok(columns)
+    Ok((headers, columns))
 }
 
 pub fn read_selected_columns<P: AsRef<Path>>(path: P, cols: &[usize]) -> Result<Vec<Vec<f64>>> {
@@ -1,6 +1,7 @@
 pub mod channels;
 pub(crate) mod checksum;
 pub mod csv;
+pub(crate) mod numeric;
 pub mod raw_csv;
 pub mod raw_data;
 pub mod time_axis;
@@ -0,0 +1,43 @@
+/// Gaussian elimination with partial pivoting on an augmented matrix.
+pub(crate) fn solve_linear_system(mut matrix: Vec<Vec<f64>>) -> Option<Vec<f64>> {
+    const PIVOT_TOLERANCE: f64 = 1.0e-12;
+    let size = matrix.len();
+    for column in 0..size {
+        let pivot = (column..size).max_by(|&left, &right| {
+            matrix[left][column]
+                .abs()
+                .total_cmp(&matrix[right][column].abs())
+        })?;
+        if matrix[pivot][column].abs() <= PIVOT_TOLERANCE {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        let pivot_row = matrix[column].clone();
+        for row in &mut matrix[column + 1..] {
+            let factor = row[column] / pivot_row[column];
+            for (value, &pivot_value) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+    let mut solution = vec![0.0; size];
+    for row in (0..size).rev() {
+        let tail = (row + 1..size)
+            .map(|column| matrix[row][column] * solution[column])
+            .sum::<f64>();
+        solution[row] = (matrix[row][size] - tail) / matrix[row][row];
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::solve_linear_system;
+
+    #[test]
+    fn solves_with_pivoting_and_rejects_singular_systems() {
+        let solution = solve_linear_system(vec![vec![0.0, 2.0, 4.0], vec![3.0, 1.0, 5.0]]).unwrap();
+        assert_eq!(solution, [1.0, 2.0]);
+        assert!(solve_linear_system(vec![vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0]]).is_none());
+    }
+}
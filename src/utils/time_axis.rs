@@ -151,6 +151,21 @@ impl Iterator for TimeAxisIter<'_> {
 
 impl ExactSizeIterator for TimeAxisIter<'_> {}
 
+/// Largest time-axis difference, as a fraction of one sample interval, that
+/// still counts as the same axis after a CSV round trip.
+pub const TIME_AXIS_TOLERANCE: f64 = 1e-6;
+
+/// Whether `other` repeats `reference` sample for sample, within
+/// [`TIME_AXIS_TOLERANCE`] of the reference sample interval `dt`.
+pub fn same_time_axis(reference: &[f64], other: &[f64], dt: f64) -> bool {
+    let tolerance = dt.abs() * TIME_AXIS_TOLERANCE;
+    other.len() == reference.len()
+        && other
+            .iter()
+            .zip(reference)
+            .all(|(a, b)| (a - b).abs() <= tolerance)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
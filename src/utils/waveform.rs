@@ -175,6 +175,39 @@ pub struct WaveformData {
     pub channels: Vec<Vec<f64>>,
 }
 
+/// Fetched waveforms where some raw channels are decoded chunk by chunk on
+/// demand instead of being held in memory.
+#[derive(Debug)]
+pub struct StreamedWaveformData {
+    /// Channels read into memory, in the order of `channels`.
+    pub data: WaveformData,
+    pub channels: Vec<u8>,
+    pub streams: Vec<RawChannelStream>,
+}
+
+#[derive(Debug)]
+pub struct RawChannelStream {
+    channel: u8,
+    spec: RawChannelSpec,
+}
+
+impl RawChannelStream {
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.spec.expected_bytes / 2
+    }
+
+    /// Decodes the channel in `RAW_READ_CHUNK_BYTES` chunks. The checksum is
+    /// only known after the last chunk, so callers must discard what they
+    /// computed when this returns an error.
+    pub fn for_each_chunk(&self, on_chunk: impl FnMut(&[f64]) -> Result<()>) -> Result<()> {
+        stream_raw_channel_data(&self.spec, RAW_READ_CHUNK_BYTES, on_chunk)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RawVerification {
     pub metadata_version: u32,
@@ -267,6 +300,69 @@ pub fn read_raw_waveform_channels_from_dir(
     base_dir: &Path,
     channels: &[u8],
 ) -> Result<WaveformData> {
+    let (time_axis, specs) = raw_channel_specs(base_dir, channels)?;
+    let channels = specs
+        .iter()
+        .map(read_raw_channel_data)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(WaveformData {
+        t: WaveformTime::Uniform(time_axis),
+        channels,
+    })
+}
+
+/// Reads the fetched channels for lock-in analysis with `streamed` channels
+/// left on disk. Returns `None` when the analysis input resolves to CSV, which
+/// cannot be streamed.
+pub fn read_fetched_waveforms_streaming(
+    cfg: &Config,
+    streamed: &[u8],
+) -> Result<Option<StreamedWaveformData>> {
+    let channels = build_channel_list(cfg)?;
+    let use_raw = match cfg.fetch.analysis_input {
+        FetchAnalysisInput::Csv => false,
+        FetchAnalysisInput::Raw => true,
+        FetchAnalysisInput::Auto => match raw_status(cfg, &channels)? {
+            RawStatus::Complete => true,
+            RawStatus::Missing => false,
+            RawStatus::Invalid(message) => bail!("{message}"),
+        },
+    };
+    if !use_raw {
+        return Ok(None);
+    }
+
+    let resolver = cfg.resolver();
+    let manifest = resolver.acquisition_manifest();
+    let base_dir = manifest.parent().unwrap_or_else(|| Path::new("."));
+    let (time_axis, specs) = raw_channel_specs(base_dir, &channels)?;
+    let mut loaded_channels = Vec::new();
+    let mut loaded = Vec::new();
+    let mut streams = Vec::new();
+    for (channel, spec) in channels.into_iter().zip(specs) {
+        if streamed.contains(&channel) {
+            streams.push(RawChannelStream { channel, spec });
+        } else {
+            loaded.push(read_raw_channel_data(&spec)?);
+            loaded_channels.push(channel);
+        }
+    }
+
+    Ok(Some(StreamedWaveformData {
+        data: WaveformData {
+            t: WaveformTime::Uniform(time_axis),
+            channels: loaded,
+        },
+        channels: loaded_channels,
+        streams,
+    }))
+}
+
+fn raw_channel_specs(
+    base_dir: &Path,
+    channels: &[u8],
+) -> Result<(RawTimeAxis, Vec<RawChannelSpec>)> {
     let metadata = read_raw_metadata(base_dir)?;
     validate_raw_format(&metadata)?;
     warn_manifest_config_mismatch(base_dir, &metadata);
@@ -279,13 +375,8 @@ pub fn read_raw_waveform_channels_from_dir(
     for spec in &specs {
         validate_raw_channel_file_size(spec)?;
     }
-    let channels = specs
-        .iter()
-        .map(read_raw_channel_data)
-        .collect::<Result<Vec<_>>>()?;
-    let t = WaveformTime::Uniform(time_axis.ok_or_else(|| anyhow!("no raw channels requested"))?);
-
-    Ok(WaveformData { t, channels })
+    let time_axis = time_axis.ok_or_else(|| anyhow!("no raw channels requested"))?;
+    Ok((time_axis, specs))
 }
 
 pub fn verify_raw_waveform_dir(base_dir: &Path) -> Result<RawVerification> {
@@ -655,6 +746,16 @@ struct RawChannelSpec {
     y_reference: f64,
 }
 
+impl RawChannelSpec {
+    fn voltage_scale(&self) -> RawVoltageScale {
+        RawVoltageScale {
+            y_increment: self.y_increment,
+            y_origin: self.y_origin,
+            y_reference: self.y_reference,
+        }
+    }
+}
+
 fn raw_channel_spec(
     base_dir: &Path,
     metadata: &RawWaveformMetadata,
@@ -753,6 +854,52 @@ fn read_raw_channel_data_with_chunk_size(
     spec: &RawChannelSpec,
     chunk_bytes: usize,
 ) -> Result<Vec<f64>> {
+    let sample_count = spec.expected_bytes / 2;
+    let mut voltages = Vec::new();
+    voltages.try_reserve_exact(sample_count).with_context(|| {
+        format!(
+            "failed to allocate {sample_count} voltage samples for {}",
+            spec.key
+        )
+    })?;
+    voltages.resize(sample_count, 0.0);
+
+    let scale = spec.voltage_scale();
+    read_raw_channel_chunks(spec, chunk_bytes, |byte_offset, chunk| {
+        let sample_offset = byte_offset / 2;
+        decode_raw_word_chunk_into(
+            chunk,
+            &mut voltages[sample_offset..sample_offset + chunk.len() / 2],
+            scale,
+        );
+        Ok(())
+    })?;
+
+    Ok(voltages)
+}
+
+fn stream_raw_channel_data(
+    spec: &RawChannelSpec,
+    chunk_bytes: usize,
+    mut on_chunk: impl FnMut(&[f64]) -> Result<()>,
+) -> Result<()> {
+    let scale = spec.voltage_scale();
+    let mut decoded = vec![0.0; chunk_bytes.min(spec.expected_bytes) / 2];
+    read_raw_channel_chunks(spec, chunk_bytes, |_, chunk| {
+        let decoded = &mut decoded[..chunk.len() / 2];
+        decode_raw_word_chunk_into(chunk, decoded, scale);
+        on_chunk(decoded)
+    })
+}
+
+/// Reads `spec` in chunks of at most `chunk_bytes`, passing each chunk with
+/// its byte offset, then checks that the file did not grow and that its
+/// checksum matches.
+fn read_raw_channel_chunks(
+    spec: &RawChannelSpec,
+    chunk_bytes: usize,
+    mut on_chunk: impl FnMut(usize, &[u8]) -> Result<()>,
+) -> Result<()> {
     if chunk_bytes == 0 || !chunk_bytes.is_multiple_of(2) {
         bail!("raw read chunk size must be a positive even number: {chunk_bytes}");
     }
@@ -773,21 +920,6 @@ fn read_raw_channel_data_with_chunk_size(
         );
     }
 
-    let sample_count = spec.expected_bytes / 2;
-    let mut voltages = Vec::new();
-    voltages.try_reserve_exact(sample_count).with_context(|| {
-        format!(
-            "failed to allocate {sample_count} voltage samples for {}",
-            spec.key
-        )
-    })?;
-    voltages.resize(sample_count, 0.0);
-
-    let scale = RawVoltageScale {
-        y_increment: spec.y_increment,
-        y_origin: spec.y_origin,
-        y_reference: spec.y_reference,
-    };
     let mut buffer = vec![0_u8; chunk_bytes.min(spec.expected_bytes)];
     let mut hasher = spec.expected_sha256.as_ref().map(|_| Sha256::new());
     let mut byte_offset = 0;
@@ -803,12 +935,7 @@ fn read_raw_channel_data_with_chunk_size(
         if let Some(hasher) = &mut hasher {
             hasher.update(&*chunk);
         }
-        let sample_offset = byte_offset / 2;
-        decode_raw_word_chunk_into(
-            chunk,
-            &mut voltages[sample_offset..sample_offset + bytes_to_read / 2],
-            scale,
-        );
+        on_chunk(byte_offset, chunk)?;
         byte_offset += bytes_to_read;
     }
     let mut extra = [0_u8; 1];
@@ -836,7 +963,7 @@ fn read_raw_channel_data_with_chunk_size(
         }
     }
 
-    Ok(voltages)
+    Ok(())
 }
 
 fn validate_raw_channel_file_size(spec: &RawChannelSpec) -> Result<()> {
@@ -177,6 +177,41 @@ fn read_raw_channel_matches_conversion_across_chunks() {
     fs::remove_dir_all(dir).unwrap();
 }
 
+#[test]
+fn stream_raw_channel_yields_chunks_matching_full_read() {
+    let dir = unique_test_dir("raw_chunk_stream");
+    fs::create_dir(&dir).unwrap();
+    let path = dir.join("ch1.u16le");
+    let words = [0_u16, 1, 32_767, 32_768, 65_535];
+    let bytes = words
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect::<Vec<_>>();
+    fs::write(&path, &bytes).unwrap();
+    let spec = RawChannelSpec {
+        key: "ch1".to_owned(),
+        path,
+        expected_bytes: bytes.len(),
+        expected_sha256: None,
+        y_increment: 0.25,
+        y_origin: 2.0,
+        y_reference: 32_768.0,
+    };
+
+    let mut chunk_lens = Vec::new();
+    let mut streamed = Vec::new();
+    stream_raw_channel_data(&spec, 4, |chunk| {
+        chunk_lens.push(chunk.len());
+        streamed.extend_from_slice(chunk);
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(chunk_lens, vec![2, 2, 1]);
+    assert_eq!(streamed, read_raw_channel_data(&spec).unwrap());
+    fs::remove_dir_all(dir).unwrap();
+}
+
 #[test]
 fn raw_word_conversion_rejects_incomplete_word() {
     let error = convert_raw_word_to_voltages(&[1], 1.0, 0.0, 0.0).unwrap_err();
@@ -0,0 +1,159 @@
+#![cfg(feature = "hw-core")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static TEMP_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "pmoke_sim_end_to_end_{}_{}_{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos(),
+            TEMP_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+const SIM_CONFIG: &str = r#"
+version = 5
+
+[scope]
+model = "DHO5108"
+connection = "sim://ci"
+
+[generator]
+model = "WF1946B"
+connection = "sim://ci"
+
+[data]
+output = "raw"
+input = "raw"
+screenshot = false
+
+[[sensors]]
+channel = 1
+scale = { factor = 1.0 }
+label = "$B$"
+unit = "T"
+
+[pulse]
+background_before = { start = -5e-3, end = -0.1e-3 }
+background_after = { start = 15e-3, end = 40e-3 }
+
+[reference]
+channel = 2
+fft_window = { start = 0.0, end = 15e-3 }
+stride_samples = 10_000
+window_samples = 1_000
+
+[lockin]
+signal_channels = [3]
+workers = 2
+stride_samples = 100
+filter = { kind = "boxcar_legacy", half_window_cycles = 1.0 }
+
+[phase]
+offsets = [0, 0, 0, 0, 0, 0]
+
+[kerr]
+sensor = 1
+method = "standard"
+factor = 1.0
+
+[plot]
+mode = "off"
+"#;
+
+/// Angle the sim bench injects at the peak of its half-sine field pulse.
+const PEAK_KERR_ANGLE_RAD: f64 = 1.0e-3;
+const PULSE_WIDTH_S: f64 = 10.0e-3;
+/// Allowed mean deviation near the field peak: well below a sign flip, a
+/// factor of two, or a swapped harmonic, and above the lock-in noise.
+const PEAK_MEAN_TOLERANCE_RAD: f64 = 0.05 * PEAK_KERR_ANGLE_RAD;
+const POINT_TOLERANCE_RAD: f64 = 0.2 * PEAK_KERR_ANGLE_RAD;
+
+/// Normalized field of the sim bench pulse, `sin(πt/T)` on `[0, T]`.
+fn field(t: f64) -> f64 {
+    if (0.0..=PULSE_WIDTH_S).contains(&t) {
+        (std::f64::consts::PI * t / PULSE_WIDTH_S).sin()
+    } else {
+        0.0
+    }
+}
+
+#[test]
+fn auto_on_a_sim_bench_recovers_the_injected_kerr_angle() {
+    let dir = TempDir::new();
+    let config = dir.0.join("config.toml");
+    fs::write(&config, SIM_CONFIG).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pmoke"))
+        .current_dir(&dir.0)
+        .arg("--config")
+        .arg(&config)
+        .arg("auto")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "stdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let kerr = dir.0.join("analysis/kerr/kerr.csv");
+    assert!(kerr.is_file(), "auto must write {}", kerr.display());
+    let mut reader = csv::Reader::from_path(&kerr).unwrap();
+    let headers = reader.headers().unwrap().clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header == name)
+            .unwrap_or_else(|| panic!("missing {name} in {headers:?}"))
+    };
+    let time_column = column("time (s)");
+    let kerr_column = column("Ch3 Kerr angle (rad)");
+
+    let mut peak_residuals = Vec::new();
+    for record in reader.records() {
+        let record = record.unwrap();
+        let t = record[time_column].parse::<f64>().unwrap();
+        let angle = record[kerr_column].parse::<f64>().unwrap();
+        let expected = PEAK_KERR_ANGLE_RAD * field(t);
+        assert!(
+            (angle - expected).abs() < POINT_TOLERANCE_RAD,
+            "Kerr angle {angle} rad at t = {t} s, expected {expected} rad"
+        );
+        if field(t) > 0.9 {
+            peak_residuals.push(angle - expected);
+        }
+    }
+    assert!(
+        peak_residuals.len() > 10,
+        "{} has too few points near the field peak",
+        kerr.display()
+    );
+    let mean = peak_residuals.iter().sum::<f64>() / peak_residuals.len() as f64;
+    assert!(
+        mean.abs() < PEAK_MEAN_TOLERANCE_RAD,
+        "mean Kerr residual near the field peak is {mean} rad"
+    );
+}
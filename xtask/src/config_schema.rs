@@ -12,8 +12,13 @@ pub fn build(reference: &ConfigReference) -> Value {
         "required": ["version", "scope", "data", "pulse", "reference", "lockin", "phase", "kerr"],
         "properties": {
             "version": annotate(reference, "version", json!({"type": "integer", "const": 5})),
-            "scope": annotate(reference, "scope", instrument(reference, "scope", false)),
-            "generator": annotate(reference, "generator", instrument(reference, "generator", true)),
+            "scope": annotate(reference, "scope", scope(reference)),
+            "generator": annotate(reference, "generator", generator(reference)),
+            "multimeters": annotate(reference, "multimeters", json!({
+                "type": "array",
+                "items": multimeter(reference),
+                "default": []
+            })),
             "data": annotate(reference, "data", object(
                 &["output", "input"],
                 [
@@ -32,6 +37,7 @@ pub fn build(reference: &ConfigReference) -> Value {
                 [
                     ("background_before", window(reference, "pulse.background_before")),
                     ("background_after", window(reference, "pulse.background_after")),
+                    ("baseline", pulse_baseline(reference)),
                 ],
             )),
             "reference": annotate(reference, "reference", object(
@@ -46,12 +52,15 @@ pub fn build(reference: &ConfigReference) -> Value {
             "lockin": annotate(reference, "lockin", lockin(reference)),
             "phase": annotate(reference, "phase", object(
                 &["offsets"],
-                [("offsets", annotate(reference, "phase.offsets", json!({
-                    "type": "array",
-                    "minItems": 6,
-                    "maxItems": 6,
-                    "items": {"oneOf": [{"type": "number"}, {"type": "string"}]}
-                })))],
+                [
+                    ("offsets", annotate(reference, "phase.offsets", json!({
+                        "type": "array",
+                        "minItems": 1,
+                        "maxItems": 64,
+                        "items": {"oneOf": [{"type": "number"}, {"type": "string"}]}
+                    }))),
+                    ("drift", phase_drift(reference)),
+                ],
             )),
             "kerr": annotate(reference, "kerr", object(
                 &["sensor", "method", "factor"],
@@ -59,6 +68,22 @@ pub fn build(reference: &ConfigReference) -> Value {
                     ("sensor", channel(reference, "kerr.sensor")),
                     ("method", enum_string(reference, "kerr.method")),
                     ("factor", annotate(reference, "kerr.factor", json!({"type": "number"}))),
+                    ("modulation_depth", annotate(reference, "kerr.modulation_depth", json!({
+                        "oneOf": [
+                            {"type": "number", "exclusiveMinimum": 0, "exclusiveMaximum": 1.9158529851},
+                            {"const": "harmonics"}
+                        ],
+                        "default": 0.92
+                    }))),
+                    ("field_bin_width", annotate(reference, "kerr.field_bin_width", json!({
+                        "type": "number",
+                        "exclusiveMinimum": 0
+                    }))),
+                    ("background_run", annotate(reference, "kerr.background_run", json!({
+                        "type": "string",
+                        "minLength": 1
+                    }))),
+                    ("field_background", kerr_field_background(reference)),
                 ],
             )),
             "plot": annotate(reference, "plot", plot(reference)),
@@ -71,8 +96,11 @@ pub fn build(reference: &ConfigReference) -> Value {
             "semantic_constraints": [
                 "channel assignments must be unique across sensors, reference, and lock-in signals",
                 "kerr.sensor must reference a configured sensor channel",
+                "phase.offsets must have one entry per lockin.harmonics value",
+                "lockin.harmonics must include the harmonics required by kerr.method",
+                "kerr.modulation_depth = \"harmonics\" with kerr.method = \"standard\" also requires lockin.harmonics 2, 3, 4, and 6",
                 "pulse background windows must not overlap",
-                "lockin.filter must use the active boxcar_legacy fields only"
+                "lockin.filter may set at most one of cutoff_hz, cutoff_ref_ratio, and enbw_hz"
             ]
         }
     })
@@ -96,7 +124,7 @@ fn instrument(reference: &ConfigReference, prefix: &str, optional: bool) -> Valu
                     json!({
                         "type": "string",
                         "minLength": 1,
-                        "pattern": "^(tcp://|visa:|gpib://|prologix-tcp://|prologix-serial://)"
+                        "pattern": "^(tcp://|vxi11://|visa:|usbtmc://|gpib://|prologix-tcp://|prologix-serial://|sim://)"
                     }),
                 ),
             ),
@@ -110,6 +138,191 @@ fn instrument(reference: &ConfigReference, prefix: &str, optional: bool) -> Valu
     schema
 }
 
+fn scope(reference: &ConfigReference) -> Value {
+    let mut schema = instrument(reference, "scope", false);
+    schema["properties"]["trigger_timeout_s"] = annotate(
+        reference,
+        "scope.trigger_timeout_s",
+        json!({"type": "number", "exclusiveMinimum": 0, "default": 10.0}),
+    );
+    schema["properties"]["trigger_poll_interval_s"] = annotate(
+        reference,
+        "scope.trigger_poll_interval_s",
+        json!({"type": "number", "exclusiveMinimum": 0, "default": 0.1}),
+    );
+    schema["properties"]["setup"] = annotate(
+        reference,
+        "scope.setup",
+        object(
+            &[],
+            [
+                (
+                    "timebase_scale",
+                    annotate(
+                        reference,
+                        "scope.setup.timebase_scale",
+                        json!({"type": "number", "exclusiveMinimum": 0}),
+                    ),
+                ),
+                (
+                    "timebase_offset",
+                    annotate(
+                        reference,
+                        "scope.setup.timebase_offset",
+                        json!({"type": "number"}),
+                    ),
+                ),
+                (
+                    "memory_depth",
+                    positive_integer(reference, "scope.setup.memory_depth"),
+                ),
+                (
+                    "trigger_source",
+                    enum_string(reference, "scope.setup.trigger_source"),
+                ),
+                (
+                    "trigger_level",
+                    annotate(
+                        reference,
+                        "scope.setup.trigger_level",
+                        json!({"type": "number"}),
+                    ),
+                ),
+                (
+                    "trigger_slope",
+                    enum_string(reference, "scope.setup.trigger_slope"),
+                ),
+                (
+                    "channels",
+                    annotate(
+                        reference,
+                        "scope.setup.channels",
+                        json!({
+                            "type": "array",
+                            "items": scope_channel_setup(reference),
+                            "default": []
+                        }),
+                    ),
+                ),
+            ],
+        ),
+    );
+    schema
+}
+
+fn generator(reference: &ConfigReference) -> Value {
+    let mut schema = instrument(reference, "generator", true);
+    schema["properties"]["setup"] = annotate(
+        reference,
+        "generator.setup",
+        object(
+            &[],
+            [
+                (
+                    "waveform",
+                    enum_string(reference, "generator.setup.waveform"),
+                ),
+                (
+                    "frequency_hz",
+                    annotate(
+                        reference,
+                        "generator.setup.frequency_hz",
+                        json!({"type": "number", "exclusiveMinimum": 0}),
+                    ),
+                ),
+                (
+                    "amplitude_vpp",
+                    annotate(
+                        reference,
+                        "generator.setup.amplitude_vpp",
+                        json!({"type": "number", "exclusiveMinimum": 0}),
+                    ),
+                ),
+                (
+                    "offset_v",
+                    annotate(
+                        reference,
+                        "generator.setup.offset_v",
+                        json!({"type": "number"}),
+                    ),
+                ),
+                (
+                    "burst_cycles",
+                    positive_integer(reference, "generator.setup.burst_cycles"),
+                ),
+                (
+                    "trigger_source",
+                    enum_string(reference, "generator.setup.trigger_source"),
+                ),
+                (
+                    "output",
+                    annotate(
+                        reference,
+                        "generator.setup.output",
+                        json!({"type": "boolean"}),
+                    ),
+                ),
+            ],
+        ),
+    );
+    schema
+}
+
+fn multimeter(reference: &ConfigReference) -> Value {
+    let mut schema = instrument(reference, "multimeters[]", false);
+    schema["required"] = json!(["label", "model", "connection", "function"]);
+    schema["properties"]["label"] = annotate(
+        reference,
+        "multimeters[].label",
+        json!({"type": "string", "minLength": 1}),
+    );
+    schema["properties"]["model"] = enum_string(reference, "multimeters[].model");
+    schema["properties"]["function"] = enum_string(reference, "multimeters[].function");
+    schema["properties"]["range"] = annotate(
+        reference,
+        "multimeters[].range",
+        json!({"type": "number", "exclusiveMinimum": 0}),
+    );
+    schema["properties"]["thermocouple"] = enum_string(reference, "multimeters[].thermocouple");
+    schema
+}
+
+fn scope_channel_setup(reference: &ConfigReference) -> Value {
+    object(
+        &["channel"],
+        [
+            (
+                "channel",
+                channel(reference, "scope.setup.channels[].channel"),
+            ),
+            (
+                "scale",
+                annotate(
+                    reference,
+                    "scope.setup.channels[].scale",
+                    json!({"type": "number", "exclusiveMinimum": 0}),
+                ),
+            ),
+            (
+                "offset",
+                annotate(
+                    reference,
+                    "scope.setup.channels[].offset",
+                    json!({"type": "number"}),
+                ),
+            ),
+            (
+                "coupling",
+                enum_string(reference, "scope.setup.channels[].coupling"),
+            ),
+            (
+                "bandwidth_limit",
+                enum_string(reference, "scope.setup.channels[].bandwidth_limit"),
+            ),
+        ],
+    )
+}
+
 fn sensor(reference: &ConfigReference) -> Value {
     object(
         &["channel", "scale", "label", "unit"],
@@ -138,6 +351,30 @@ fn sensor(reference: &ConfigReference) -> Value {
                                     }))),
                                     ("polarity", enum_integer(reference, "sensors[].scale.polarity")),
                                 ],
+                            ),
+                            object(
+                                &["area_turns"],
+                                [
+                                    ("area_turns", annotate(reference, "sensors[].scale.area_turns", json!({
+                                        "type": "number",
+                                        "exclusiveMinimum": 0
+                                    }))),
+                                    ("gain", annotate(reference, "sensors[].scale.gain", json!({
+                                        "type": "number",
+                                        "exclusiveMinimum": 0,
+                                        "default": 1.0
+                                    }))),
+                                    ("polarity", enum_integer(reference, "sensors[].scale.polarity")),
+                                    ("compensation", annotate(reference, "sensors[].scale.compensation", object(
+                                        &["channel", "balance"],
+                                        [
+                                            ("channel", channel(reference, "sensors[].scale.compensation.channel")),
+                                            ("balance", annotate(reference, "sensors[].scale.compensation.balance", json!({
+                                                "type": "number"
+                                            }))),
+                                        ],
+                                    ))),
+                                ],
                             )
                         ]
                     }),
@@ -185,6 +422,24 @@ fn lockin(reference: &ConfigReference) -> Value {
                 "stride_samples",
                 positive_integer(reference, "lockin.stride_samples"),
             ),
+            (
+                "harmonics",
+                annotate(
+                    reference,
+                    "lockin.harmonics",
+                    json!({
+                        "type": "array",
+                        "minItems": 1,
+                        "uniqueItems": true,
+                        "items": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "maximum": 64
+                        },
+                        "default": [1, 2, 3, 4, 5, 6]
+                    }),
+                ),
+            ),
             ("filter", filter(reference)),
             (
                 "debug_output",
@@ -248,6 +503,26 @@ fn filter(reference: &ConfigReference) -> Value {
             json!({"type": "string", "const": value}),
         )
     };
+    let positive = |path: &str| {
+        annotate(
+            reference,
+            path,
+            json!({"type": "number", "exclusiveMinimum": 0}),
+        )
+    };
+    let cutoff_hz = positive("lockin.filter.cutoff_hz");
+    let cutoff_ref_ratio = positive("lockin.filter.cutoff_ref_ratio");
+    let enbw_hz = positive("lockin.filter.enbw_hz");
+    let zero_phase = annotate(
+        reference,
+        "lockin.filter.zero_phase",
+        json!({"type": "boolean", "default": true}),
+    );
+    let order = annotate(
+        reference,
+        "lockin.filter.order",
+        json!({"type": "integer", "minimum": 1, "maximum": 8, "default": 4}),
+    );
     annotate(
         reference,
         "lockin.filter",
@@ -255,7 +530,154 @@ fn filter(reference: &ConfigReference) -> Value {
             "oneOf": [
                 object(
                     &["kind", "half_window_cycles"],
-                    [("kind", kind("boxcar_legacy")), ("half_window_cycles", half_window)],
+                    [("kind", kind("boxcar_legacy")), ("half_window_cycles", half_window.clone())],
+                ),
+                object(
+                    &["kind"],
+                    [
+                        ("kind", kind("rc_cascade")),
+                        ("order", order),
+                        ("cutoff_hz", cutoff_hz.clone()),
+                        ("cutoff_ref_ratio", cutoff_ref_ratio.clone()),
+                        ("enbw_hz", enbw_hz.clone()),
+                        ("zero_phase", zero_phase.clone()),
+                    ],
+                ),
+                object(
+                    &["kind", "half_window_cycles"],
+                    [
+                        ("kind", kind("windowed_sinc")),
+                        ("half_window_cycles", half_window),
+                        ("cutoff_hz", cutoff_hz.clone()),
+                        ("cutoff_ref_ratio", cutoff_ref_ratio.clone()),
+                        ("enbw_hz", enbw_hz.clone()),
+                        ("zero_phase", zero_phase.clone()),
+                    ],
+                ),
+                object(
+                    &["kind"],
+                    [
+                        ("kind", kind("gaussian")),
+                        ("cutoff_hz", cutoff_hz),
+                        ("cutoff_ref_ratio", cutoff_ref_ratio),
+                        ("enbw_hz", enbw_hz),
+                        ("zero_phase", zero_phase),
+                    ],
+                )
+            ]
+        }),
+    )
+}
+
+fn pulse_baseline(reference: &ConfigReference) -> Value {
+    let kind = |value: &str| {
+        annotate(
+            reference,
+            "pulse.baseline.kind",
+            json!({"type": "string", "const": value}),
+        )
+    };
+    let order = annotate(
+        reference,
+        "pulse.baseline.order",
+        json!({"type": "integer", "minimum": 1, "maximum": 3}),
+    );
+    annotate(
+        reference,
+        "pulse.baseline",
+        json!({
+            "oneOf": [
+                object(&["kind"], [("kind", kind("constant"))]),
+                object(&["kind"], [("kind", kind("linear"))]),
+                object(
+                    &["kind", "order"],
+                    [("kind", kind("polynomial")), ("order", order.clone())],
+                ),
+                object(
+                    &["kind"],
+                    [
+                        ("kind", kind("endpoint_constrained")),
+                        ("order", with_default(order, 1)),
+                    ],
+                )
+            ]
+        }),
+    )
+}
+
+fn phase_drift(reference: &ConfigReference) -> Value {
+    let kind = |value: &str| {
+        annotate(
+            reference,
+            "phase.drift.kind",
+            json!({"type": "string", "const": value}),
+        )
+    };
+    annotate(
+        reference,
+        "phase.drift",
+        json!({
+            "oneOf": [
+                object(&["kind"], [("kind", kind("constant"))]),
+                object(
+                    &["kind", "window"],
+                    [
+                        ("kind", kind("piecewise_linear")),
+                        ("window", annotate(reference, "phase.drift.window", json!({
+                            "type": "number",
+                            "exclusiveMinimum": 0
+                        }))),
+                        ("step", annotate(reference, "phase.drift.step", json!({
+                            "type": "number",
+                            "exclusiveMinimum": 0
+                        }))),
+                    ],
+                )
+            ]
+        }),
+    )
+}
+
+fn kerr_field_background(reference: &ConfigReference) -> Value {
+    let kind = |value: &str| {
+        annotate(
+            reference,
+            "kerr.field_background.kind",
+            json!({"type": "string", "const": value}),
+        )
+    };
+    let ranges = || {
+        annotate(
+            reference,
+            "kerr.field_background.ranges",
+            json!({
+                "type": "array",
+                "minItems": 1,
+                "items": {
+                    "type": "array",
+                    "items": {"type": "number"},
+                    "minItems": 2,
+                    "maxItems": 2
+                }
+            }),
+        )
+    };
+    annotate(
+        reference,
+        "kerr.field_background",
+        json!({
+            "oneOf": [
+                object(&["kind", "ranges"], [("kind", kind("linear")), ("ranges", ranges())]),
+                object(
+                    &["kind", "order", "ranges"],
+                    [
+                        ("kind", kind("odd_polynomial")),
+                        ("order", annotate(reference, "kerr.field_background.order", json!({
+                            "type": "integer",
+                            "enum": [1, 3, 5, 7]
+                        }))),
+                        ("ranges", ranges()),
+                    ],
                 )
             ]
         }),
@@ -394,7 +816,7 @@ mod tests {
                 .as_array()
                 .unwrap()
                 .len(),
-            1
+            4
         );
         assert_eq!(
             schema["x-pmoke"]["fields"].as_array().unwrap().len(),
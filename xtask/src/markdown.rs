@@ -338,6 +338,15 @@ fn japanese(text: &str) -> Option<&'static str> {
         "Set single mode and send trigger signal" => "single mode設定とtrigger送信",
         "Fetch data from the oscilloscope and save to a file" => "オシロスコープデータの取得・保存",
         "Capture an oscilloscope screenshot directly to the PC" => "オシロスコープ画面のPC直接取得",
+        "Push or check the oscilloscope acquisition setup from the config" => {
+            "設定ファイルのオシロスコープ取得設定の送信・確認"
+        }
+        "Send scope.setup to the oscilloscope and read it back" => {
+            "scope.setupをオシロスコープへ送信し読み戻し"
+        }
+        "Read the oscilloscope setup back and report differences from scope.setup" => {
+            "オシロスコープ設定を読み戻しscope.setupとの差分を表示"
+        }
         "Perform auto measurement (set single mode, trigger, fetch)" => {
             "single mode・trigger・fetchの自動測定"
         }
@@ -349,10 +358,23 @@ fn japanese(text: &str) -> Option<&'static str> {
         "Run all analysis steps: reference, sensor, lock-in, phase, Kerr" => {
             "reference・sensor・lock-in・phase・Kerrの全解析"
         }
+        "Average repeated shots from several analyzed run directories" => {
+            "解析済み複数runの繰り返しshot平均"
+        }
+        "Analyzed run directories to average" => "平均する解析済みrun directory",
+        "Analysis results to average" => "平均する解析結果",
+        "How shots are aligned before averaging" => "平均前のshot位置合わせ方法",
+        "New run directory for the stacked results" => "stack結果を保存する新規run directory",
         "Automated analysis after manually triggering the pulse (fetch, lock-in, phase, Kerr)" => {
             "手動pulse trigger後のfetch・lock-in・phase・Kerr自動解析"
         }
         "Run the full automatic measurement and analysis" => "完全自動測定・解析",
+        "Run a series of measurement shots listed in a campaign file" => {
+            "campaign fileに列挙した一連の測定shotの実行"
+        }
+        "Campaign file listing the shots and their config overrides" => {
+            "shotと設定overrideを列挙したcampaign file"
+        }
         "Generate shell completion script" => "shell completion scriptの生成",
         "Create a starter config file" => "初期設定ファイルの生成",
         "Validate the config file without running an analysis command" => {
@@ -366,6 +388,9 @@ fn japanese(text: &str) -> Option<&'static str> {
         "List supported instrument models" => "対応装置モデル一覧",
         "Explain a supported instrument model" => "対応装置モデルの説明",
         "Send one SCPI text query to a connection URI" => "接続URIへの単一SCPI text query",
+        "Find instruments that answer *IDN? and print their connection URIs" => {
+            "*IDN?に応答する装置の検出と接続URI表示"
+        }
         "Benchmark one SCPI query and save a compact reproducibility report" => {
             "単一SCPI queryのbenchmarkと再現性report保存"
         }
@@ -415,6 +440,21 @@ fn japanese(text: &str) -> Option<&'static str> {
             "URIにtransport固有のtimeoutがない場合に使用するtimeout"
         }
         "SCPI query command, for example *IDN?" => "SCPI query command。例: *IDN?。",
+        "GPIB board to scan; repeat for multiple boards" => {
+            "scan対象GPIB board。複数指定時の反復option。"
+        }
+        "Skip GPIB board scans" => "GPIB board scanの省略",
+        "Skip the VISA resource list" => "VISA resource一覧の省略",
+        "Prologix controller to sweep with ++addr, for example prologix-tcp://host:1234" => {
+            "++addrで全addressを走査するPrologix controller。例: prologix-tcp://host:1234。"
+        }
+        "TCP/IP host or IPv4 subnet such as 192.0.2.0/24 to probe" => {
+            "probe対象TCP/IP hostまたはIPv4 subnet。例: 192.0.2.0/24。"
+        }
+        "Port probed on every --tcp host; repeat for multiple ports" => {
+            "各--tcp hostでprobeするport。複数指定時の反復option。"
+        }
+        "Connect and response timeout for each probe" => "probeごとの接続・応答timeout",
         "Connection URI accepted by `pmoke instruments query`" => {
             "`pmoke instruments query`で使用する接続URI"
         }